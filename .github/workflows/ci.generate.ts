@@ -479,6 +479,11 @@ const ci = {
             run:
               "deno run --unstable --allow-write --allow-read --allow-run ./tools/lint.js",
           },
+          {
+            name: "Check without default features",
+            if: "matrix.job == 'lint'",
+            run: "cargo check --locked -p deno --no-default-features",
+          },
           {
             name: "Build debug",
             if: [
@@ -504,6 +509,38 @@ const ci = {
             ].join("\n"),
             run: "cargo build --release --locked --all-targets",
           },
+          {
+            // The runtime without the TypeScript compiler that
+            // `deno compile --strip` embeds.
+            name: "Build stripped runtime (debug)",
+            if: [
+              "matrix.job == 'test' &&",
+              "(matrix.profile == 'debug' || matrix.profile == 'fastci')",
+            ].join("\n"),
+            shell: "bash",
+            run: [
+              "cargo build --locked -p deno --no-default-features --features napi,npm --target-dir target/denort",
+              'if [ "$RUNNER_OS" == "Windows" ]; then EXE=.exe; fi',
+              "cp target/denort/debug/deno$EXE target/debug/denort$EXE",
+            ].join("\n"),
+            env: { CARGO_PROFILE_DEV_DEBUG: 0 },
+          },
+          {
+            name: "Build stripped runtime (release)",
+            if: [
+              "matrix.job == 'test' &&",
+              "matrix.profile == 'release' && (matrix.use_sysroot ||",
+              "(github.repository == 'denoland/deno' &&",
+              "(github.ref == 'refs/heads/main' ||",
+              "startsWith(github.ref, 'refs/tags/'))))",
+            ].join("\n"),
+            shell: "bash",
+            run: [
+              "cargo build --release --locked -p deno --no-default-features --features napi,npm --target-dir target/denort",
+              'if [ "$RUNNER_OS" == "Windows" ]; then EXE=.exe; fi',
+              "cp target/denort/release/deno$EXE target/release/denort$EXE",
+            ].join("\n"),
+          },
           {
             name: "Upload PR artifact (linux)",
             if: [
@@ -530,6 +567,8 @@ const ci = {
             run: [
               "cd target/release",
               "zip -r deno-x86_64-unknown-linux-gnu.zip deno",
+              "mkdir -p denort-zip && cp denort denort-zip/deno",
+              "(cd denort-zip && zip -r ../denort-x86_64-unknown-linux-gnu.zip deno)",
               "./deno types > lib.deno.d.ts",
             ].join("\n"),
           },
@@ -545,6 +584,8 @@ const ci = {
             run: [
               "cd target/release",
               "zip -r deno-x86_64-apple-darwin.zip deno",
+              "mkdir -p denort-zip && cp denort denort-zip/deno",
+              "(cd denort-zip && zip -r ../denort-x86_64-apple-darwin.zip deno)",
             ]
               .join("\n"),
           },
@@ -557,8 +598,12 @@ const ci = {
               "github.repository == 'denoland/deno' &&",
               "(github.ref == 'refs/heads/main' || startsWith(github.ref, 'refs/tags/'))",
             ].join("\n"),
-            run:
+            run: [
               "Compress-Archive -CompressionLevel Optimal -Force -Path target/release/deno.exe -DestinationPath target/release/deno-x86_64-pc-windows-msvc.zip",
+              "New-Item -ItemType Directory -Force -Path target/release/denort-zip",
+              "Copy-Item target/release/denort.exe target/release/denort-zip/deno.exe",
+              "Compress-Archive -CompressionLevel Optimal -Force -Path target/release/denort-zip/deno.exe -DestinationPath target/release/denort-x86_64-pc-windows-msvc.zip",
+            ].join("\n"),
           },
           {
             name: "Upload canary to dl.deno.land (unix)",
@@ -824,6 +869,9 @@ const ci = {
                 "target/release/deno-x86_64-pc-windows-msvc.zip",
                 "target/release/deno-x86_64-unknown-linux-gnu.zip",
                 "target/release/deno-x86_64-apple-darwin.zip",
+                "target/release/denort-x86_64-pc-windows-msvc.zip",
+                "target/release/denort-x86_64-unknown-linux-gnu.zip",
+                "target/release/denort-x86_64-apple-darwin.zip",
                 "target/release/deno_src.tar.gz",
                 "target/release/lib.deno.d.ts",
               ].join("\n"),
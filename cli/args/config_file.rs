@@ -1,9 +1,14 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::validate_config_text;
+use crate::args::ConfigDiagnostic;
+use crate::args::ConfigDiagnosticKind;
 use crate::args::ConfigFlag;
 use crate::args::Flags;
 use crate::args::TaskFlags;
 use crate::util::fs::canonicalize_path;
+use crate::util::glob::is_glob_pattern;
+use crate::util::glob::FilePatterns;
 use crate::util::path::specifier_parent;
 use crate::util::path::specifier_to_file_path;
 
@@ -18,12 +23,15 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::ModuleSpecifier;
+use indexmap::IndexMap;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::ffi::OsString;
 use std::fmt;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 pub type MaybeImportsResult =
   Result<Vec<deno_graph::ReferrerImports>, AnyError>;
@@ -175,6 +183,35 @@ pub fn json_merge(a: &mut Value, b: &Value) {
   }
 }
 
+/// Gets the names of the tasks in the order they appear in the text.
+fn get_task_names(text: &str) -> Vec<String> {
+  use jsonc_parser::ast::ObjectProp;
+  use jsonc_parser::ast::Value;
+  let ast = match jsonc_parser::parse_to_ast(
+    text,
+    &Default::default(),
+    &Default::default(),
+  ) {
+    Ok(ast) => ast,
+    Err(_) => return Vec::new(),
+  };
+  let root = match ast.value {
+    Some(Value::Object(obj)) => obj,
+    _ => return Vec::new(),
+  };
+  match root.get("tasks") {
+    Some(ObjectProp {
+      value: Value::Object(tasks),
+      ..
+    }) => tasks
+      .properties
+      .iter()
+      .map(|prop| prop.name.as_str().to_string())
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
 fn parse_compiler_options(
   compiler_options: &HashMap<String, Value>,
   maybe_specifier: Option<ModuleSpecifier>,
@@ -272,11 +309,14 @@ impl Serialize for TsConfig {
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
-#[serde(default, deny_unknown_fields)]
+#[serde(default)]
 pub struct LintRulesConfig {
   pub tags: Option<Vec<String>>,
   pub include: Option<Vec<String>>,
   pub exclude: Option<Vec<String>>,
+  /// The options of the rules, keyed by the code of their rule.
+  #[serde(flatten)]
+  pub options: BTreeMap<String, Value>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -292,49 +332,152 @@ impl SerializedFilesConfig {
     config_file_specifier: &ModuleSpecifier,
   ) -> Result<FilesConfig, AnyError> {
     let config_dir = specifier_parent(config_file_specifier);
-    Ok(FilesConfig {
-      include: self
-        .include
-        .into_iter()
-        .map(|p| {
-          let url = config_dir.join(&p)?;
-          specifier_to_file_path(&url)
-        })
-        .collect::<Result<Vec<_>, _>>()?,
-      exclude: self
-        .exclude
-        .into_iter()
-        .map(|p| {
-          let url = config_dir.join(&p)?;
-          specifier_to_file_path(&url)
+    let resolve = |p: String| -> Result<PathBuf, AnyError> {
+      if is_glob_pattern(&p) {
+        // patterns can't be resolved as urls, because characters like `?`
+        // and the leading `!` have a meaning there
+        let config_dir_path = specifier_to_file_path(&config_dir)?;
+        Ok(match p.strip_prefix('!') {
+          Some(p) => {
+            let mut path = OsString::from("!");
+            path.push(config_dir_path.join(p));
+            PathBuf::from(path)
+          }
+          None => config_dir_path.join(p),
         })
-        .collect::<Result<Vec<_>, _>>()?,
-    })
+      } else {
+        let url = config_dir.join(&p)?;
+        specifier_to_file_path(&url)
+      }
+    };
+    let include = self
+      .include
+      .into_iter()
+      .map(resolve)
+      .collect::<Result<Vec<_>, _>>()?;
+    let exclude = self
+      .exclude
+      .into_iter()
+      .map(resolve)
+      .collect::<Result<Vec<_>, _>>()?;
+    // the paths are absolute, so they don't depend on the cwd
+    let cwd = std::env::current_dir().unwrap_or_default();
+    FilesConfig::new(include, exclude, &cwd)
   }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default)]
 pub struct FilesConfig {
   pub include: Vec<PathBuf>,
   pub exclude: Vec<PathBuf>,
+  /// The compiled includes and excludes, as the language server matches
+  /// every document against them.
+  patterns: Arc<FilePatterns>,
 }
 
+impl PartialEq for FilesConfig {
+  fn eq(&self, other: &Self) -> bool {
+    self.include == other.include && self.exclude == other.exclude
+  }
+}
+
+impl Eq for FilesConfig {}
+
 impl FilesConfig {
+  /// Creates the files config, compiling the includes and excludes with
+  /// relative paths resolved against `cwd`.
+  pub fn new(
+    include: Vec<PathBuf>,
+    exclude: Vec<PathBuf>,
+    cwd: &Path,
+  ) -> Result<Self, AnyError> {
+    let patterns = Arc::new(FilePatterns::new(&include, &exclude, cwd)?);
+    Ok(Self {
+      include,
+      exclude,
+      patterns,
+    })
+  }
+
   /// Gets if the provided specifier is allowed based on the includes
   /// and excludes in the configuration file.
   pub fn matches_specifier(&self, specifier: &ModuleSpecifier) -> bool {
-    let file_path = match specifier_to_file_path(specifier) {
-      Ok(file_path) => file_path,
-      Err(_) => return false,
-    };
-    // Skip files which is in the exclude list.
-    if self.exclude.iter().any(|i| file_path.starts_with(i)) {
-      return false;
+    match specifier_to_file_path(specifier) {
+      Ok(file_path) => self.patterns.matches_path(&file_path),
+      Err(_) => false,
+    }
+  }
+
+  /// Compiles the includes and excludes, resolving relative paths against
+  /// `cwd`.
+  pub fn to_patterns(&self, cwd: &Path) -> Result<FilePatterns, AnyError> {
+    FilePatterns::new(&self.include, &self.exclude, cwd)
+  }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SerializedLibOverride {
+  include: Vec<String>,
+  #[serde(default)]
+  exclude: Vec<String>,
+  lib: Vec<String>,
+}
+
+impl SerializedLibOverride {
+  pub fn into_resolved(
+    self,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<LibOverride, AnyError> {
+    if self.include.is_empty() {
+      bail!("A \"libOverrides\" entry must include at least one file.");
     }
+    let files = SerializedFilesConfig {
+      include: self.include,
+      exclude: self.exclude,
+    };
+    let files = files.into_resolved(config_file_specifier)?;
+    // the paths are absolute, so they don't depend on the cwd
+    let cwd = std::env::current_dir().unwrap_or_default();
+    Ok(LibOverride {
+      patterns: Arc::new(files.to_patterns(&cwd)?),
+      lib: self.lib,
+    })
+  }
+}
 
-    // Ignore files not in the include list if it's not empty.
-    self.include.is_empty()
-      || self.include.iter().any(|i| file_path.starts_with(i))
+/// The type libraries to type check the files matching the patterns with,
+/// instead of the `"lib"` of the compiler options.
+#[derive(Clone, Debug)]
+pub struct LibOverride {
+  /// The compiled patterns of the files, as overrides are looked up for
+  /// every module of a graph.
+  patterns: Arc<FilePatterns>,
+  pub lib: Vec<String>,
+}
+
+impl LibOverride {
+  /// Gets the index of the first of the overrides that applies to the
+  /// specifier, if any.
+  pub fn find_index(
+    overrides: &[LibOverride],
+    specifier: &ModuleSpecifier,
+  ) -> Option<usize> {
+    let file_path = specifier_to_file_path(specifier).ok()?;
+    overrides
+      .iter()
+      .position(|lib_override| lib_override.patterns.matches_path(&file_path))
+  }
+
+  /// Adds the unstable Deno APIs to the libraries when they include the Deno
+  /// namespace.
+  pub fn add_unstable_lib(&mut self) {
+    let has_deno_lib = self.lib.iter().any(|lib| {
+      matches!(lib.as_str(), "deno.ns" | "deno.window" | "deno.worker")
+    });
+    if has_deno_lib && !self.lib.iter().any(|lib| lib == "deno.unstable") {
+      self.lib.push("deno.unstable".to_string());
+    }
   }
 }
 
@@ -344,6 +487,8 @@ struct SerializedLintConfig {
   pub rules: LintRulesConfig,
   pub files: SerializedFilesConfig,
   pub report: Option<String>,
+  #[serde(rename = "reportUnusedOrBareIgnores")]
+  pub report_unused_or_bare_ignores: bool,
 }
 
 impl SerializedLintConfig {
@@ -355,6 +500,7 @@ impl SerializedLintConfig {
       rules: self.rules,
       files: self.files.into_resolved(config_file_specifier)?,
       report: self.report,
+      report_unused_or_bare_ignores: self.report_unused_or_bare_ignores,
     })
   }
 }
@@ -364,6 +510,9 @@ pub struct LintConfig {
   pub rules: LintRulesConfig,
   pub files: FilesConfig,
   pub report: Option<String>,
+  /// Whether to report the `deno-lint-ignore` directives which don't explain
+  /// why the diagnostics are ignored or which don't suppress any diagnostic.
+  pub report_unused_or_bare_ignores: bool,
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -374,6 +523,15 @@ pub enum ProseWrap {
   Preserve,
 }
 
+/// The options which only apply to YAML files, taking precedence over the
+/// general ones.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct YamlFmtOptionsConfig {
+  pub indent_width: Option<u8>,
+  pub single_quote: Option<bool>,
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 pub struct FmtOptionsConfig {
@@ -383,6 +541,63 @@ pub struct FmtOptionsConfig {
   pub single_quote: Option<bool>,
   pub prose_wrap: Option<ProseWrap>,
   pub semi_colons: Option<bool>,
+  /// Whether to format the fenced code blocks of markdown files and of JSDoc
+  /// comments.
+  pub code_blocks: Option<bool>,
+  pub yaml: Option<YamlFmtOptionsConfig>,
+  /// The line endings, which can only be set from an `.editorconfig` file.
+  #[serde(skip_deserializing)]
+  pub new_line_kind: Option<NewLineKind>,
+}
+
+impl FmtOptionsConfig {
+  /// Fills in the options which aren't set from the `.editorconfig`
+  /// properties of a file, so that options set in the config file or with
+  /// flags take precedence.
+  pub fn with_editorconfig(
+    &self,
+    properties: &HashMap<String, String>,
+  ) -> Self {
+    let get = |key: &str| properties.get(key).map(|value| value.as_str());
+    let mut options = self.clone();
+    if options.use_tabs.is_none() {
+      options.use_tabs = match get("indent_style") {
+        Some("tab") => Some(true),
+        Some("space") => Some(false),
+        _ => None,
+      };
+    }
+    if options.indent_width.is_none() {
+      let maybe_size = match get("indent_size") {
+        Some("tab") | None => get("tab_width"),
+        maybe_size => maybe_size,
+      };
+      options.indent_width = maybe_size
+        .and_then(|size| size.parse::<u8>().ok())
+        .filter(|size| *size > 0);
+    }
+    if options.line_width.is_none() {
+      // "off" and other values which aren't numbers keep the default
+      options.line_width = get("max_line_length")
+        .and_then(|length| length.parse::<u32>().ok())
+        .filter(|length| *length > 0);
+    }
+    if options.new_line_kind.is_none() {
+      options.new_line_kind = match get("end_of_line") {
+        Some("lf") => Some(NewLineKind::LineFeed),
+        Some("crlf") => Some(NewLineKind::CarriageReturnLineFeed),
+        _ => None,
+      };
+    }
+    options
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NewLineKind {
+  LineFeed,
+  CarriageReturnLineFeed,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -411,9 +626,10 @@ pub struct FmtConfig {
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
-#[serde(default, deny_unknown_fields)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 struct SerializedTestConfig {
   pub files: SerializedFilesConfig,
+  pub v8_flags: Vec<String>,
 }
 
 impl SerializedTestConfig {
@@ -423,6 +639,7 @@ impl SerializedTestConfig {
   ) -> Result<TestConfig, AnyError> {
     Ok(TestConfig {
       files: self.files.into_resolved(config_file_specifier)?,
+      v8_flags: self.v8_flags,
     })
   }
 }
@@ -430,12 +647,16 @@ impl SerializedTestConfig {
 #[derive(Clone, Debug, Default)]
 pub struct TestConfig {
   pub files: FilesConfig,
+  /// V8 flags which are set before running the tests, ahead of the
+  /// ones passed with `--v8-flags`.
+  pub v8_flags: Vec<String>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
-#[serde(default, deny_unknown_fields)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
 struct SerializedBenchConfig {
   pub files: SerializedFilesConfig,
+  pub v8_flags: Vec<String>,
 }
 
 impl SerializedBenchConfig {
@@ -445,6 +666,7 @@ impl SerializedBenchConfig {
   ) -> Result<BenchConfig, AnyError> {
     Ok(BenchConfig {
       files: self.files.into_resolved(config_file_specifier)?,
+      v8_flags: self.v8_flags,
     })
   }
 }
@@ -452,6 +674,168 @@ impl SerializedBenchConfig {
 #[derive(Clone, Debug, Default)]
 pub struct BenchConfig {
   pub files: FilesConfig,
+  /// V8 flags which are set before running the benchs, ahead of the
+  /// ones passed with `--v8-flags`.
+  pub v8_flags: Vec<String>,
+}
+
+/// A permission in the `"permissions"` configuration, which either grants
+/// everything with `true` or only the listed values.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum SerializedPermission {
+  All(bool),
+  List(Vec<String>),
+}
+
+impl SerializedPermission {
+  fn into_allowlist(self) -> Option<Vec<String>> {
+    match self {
+      SerializedPermission::All(true) => Some(vec![]),
+      SerializedPermission::All(false) => None,
+      // unlike an empty flag value, an empty list grants nothing
+      SerializedPermission::List(list) if list.is_empty() => None,
+      SerializedPermission::List(list) => Some(list),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SerializedPermissionsConfig {
+  pub env: Option<SerializedPermission>,
+  pub hrtime: bool,
+  pub net: Option<SerializedPermission>,
+  pub ffi: Option<SerializedPermission>,
+  pub read: Option<SerializedPermission>,
+  pub run: Option<SerializedPermission>,
+  pub scheme: Option<SerializedPermission>,
+  pub sys: Option<SerializedPermission>,
+  pub write: Option<SerializedPermission>,
+}
+
+impl SerializedPermissionsConfig {
+  pub fn into_resolved(
+    self,
+    config_file_specifier: &ModuleSpecifier,
+  ) -> Result<PermissionsConfig, AnyError> {
+    let config_dir = specifier_parent(config_file_specifier);
+    let resolve_paths = |permission: Option<SerializedPermission>| {
+      permission
+        .and_then(SerializedPermission::into_allowlist)
+        .map(|paths| {
+          paths
+            .into_iter()
+            .map(|path| specifier_to_file_path(&config_dir.join(&path)?))
+            .collect::<Result<Vec<_>, AnyError>>()
+        })
+        .transpose()
+    };
+    let allow_net =
+      match self.net.and_then(SerializedPermission::into_allowlist) {
+        Some(hosts) => Some(
+          super::flags_allow_net::parse(hosts)
+            .map_err(|err| anyhow!("Invalid \"net\" permission: {}", err))?,
+        ),
+        None => None,
+      };
+    Ok(PermissionsConfig {
+      allow_env: self.env.and_then(SerializedPermission::into_allowlist).map(
+        |vars| {
+          if cfg!(windows) {
+            vars.into_iter().map(|var| var.to_uppercase()).collect()
+          } else {
+            vars
+          }
+        },
+      ),
+      allow_hrtime: self.hrtime,
+      allow_net,
+      allow_ffi: resolve_paths(self.ffi)?,
+      allow_read: resolve_paths(self.read)?,
+      allow_run: self.run.and_then(SerializedPermission::into_allowlist),
+      allow_scheme: self.scheme.and_then(SerializedPermission::into_allowlist),
+      allow_sys: self.sys.and_then(SerializedPermission::into_allowlist),
+      allow_write: resolve_paths(self.write)?,
+    })
+  }
+}
+
+/// The permissions granted by the configuration file, like the `--allow-*`
+/// flags. An empty list grants everything and paths are absolute.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PermissionsConfig {
+  pub allow_env: Option<Vec<String>>,
+  pub allow_hrtime: bool,
+  pub allow_net: Option<Vec<String>>,
+  pub allow_ffi: Option<Vec<PathBuf>>,
+  pub allow_read: Option<Vec<PathBuf>>,
+  pub allow_run: Option<Vec<String>>,
+  pub allow_scheme: Option<Vec<String>>,
+  pub allow_sys: Option<Vec<String>>,
+  pub allow_write: Option<Vec<PathBuf>>,
+}
+
+impl PermissionsConfig {
+  /// Returns the flags which grant the same permissions.
+  pub fn to_permission_args(&self) -> Vec<String> {
+    Flags {
+      allow_env: self.allow_env.clone(),
+      allow_hrtime: self.allow_hrtime,
+      allow_net: self.allow_net.clone(),
+      allow_ffi: self.allow_ffi.clone(),
+      allow_read: self.allow_read.clone(),
+      allow_run: self.allow_run.clone(),
+      allow_scheme: self.allow_scheme.clone(),
+      allow_sys: self.allow_sys.clone(),
+      allow_write: self.allow_write.clone(),
+      ..Default::default()
+    }
+    .to_permission_args()
+  }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum SerializedTaskDefinition {
+  Command(String),
+  #[serde(rename_all = "camelCase")]
+  Object {
+    command: String,
+    description: Option<String>,
+    group: Option<String>,
+  },
+}
+
+impl From<SerializedTaskDefinition> for TaskDefinition {
+  fn from(task: SerializedTaskDefinition) -> Self {
+    match task {
+      SerializedTaskDefinition::Command(command) => TaskDefinition {
+        command,
+        description: None,
+        group: None,
+      },
+      SerializedTaskDefinition::Object {
+        command,
+        description,
+        group,
+      } => TaskDefinition {
+        command,
+        description,
+        group,
+      },
+    }
+  }
+}
+
+/// A task defined in the configuration file, either as a command string or as
+/// an object with a command and optional description and group.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskDefinition {
+  pub command: String,
+  pub description: Option<String>,
+  pub group: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -465,6 +849,7 @@ pub enum LockConfig {
 #[serde(rename_all = "camelCase")]
 pub struct ConfigFileJson {
   pub compiler_options: Option<Value>,
+  pub lib_overrides: Option<Value>,
   pub import_map: Option<String>,
   pub imports: Option<Value>,
   pub scopes: Option<Value>,
@@ -474,12 +859,21 @@ pub struct ConfigFileJson {
   pub test: Option<Value>,
   pub bench: Option<Value>,
   pub lock: Option<Value>,
+  pub permissions: Option<Value>,
+  pub user_agent: Option<String>,
+  pub exports: Option<Value>,
+  pub main: Option<String>,
 }
 
 #[derive(Clone, Debug)]
 pub struct ConfigFile {
   pub specifier: ModuleSpecifier,
   pub json: ConfigFileJson,
+  /// The task names in the order they are defined, which is lost when the
+  /// text is parsed to a `serde_json::Value`.
+  task_names: Vec<String>,
+  /// The problems found when validating the text against the known schema.
+  diagnostics: Vec<ConfigDiagnostic>,
 }
 
 impl ConfigFile {
@@ -632,14 +1026,41 @@ impl ConfigFile {
           ))
         }
       };
-    let json: ConfigFileJson = serde_json::from_value(jsonc)?;
+    let diagnostics = validate_config_text(text);
+    let json: ConfigFileJson = match serde_json::from_value(jsonc) {
+      Ok(json) => json,
+      Err(err) => {
+        let messages = diagnostics
+          .iter()
+          .filter(|d| d.kind == ConfigDiagnosticKind::InvalidType)
+          .map(|d| format!("\n  {d}"))
+          .collect::<String>();
+        if messages.is_empty() {
+          return Err(err.into());
+        }
+        bail!("Invalid config file {}:{}", specifier, messages);
+      }
+    };
+    let task_names = if json.tasks.is_some() {
+      get_task_names(text)
+    } else {
+      Vec::new()
+    };
 
     Ok(Self {
       specifier: specifier.to_owned(),
       json,
+      task_names,
+      diagnostics,
     })
   }
 
+  /// The problems found when validating the configuration file against the
+  /// known schema, in the order they appear in the file.
+  pub fn diagnostics(&self) -> &[ConfigDiagnostic] {
+    &self.diagnostics
+  }
+
   /// Returns true if the configuration indicates that JavaScript should be
   /// type checked, otherwise false.
   pub fn get_check_js(&self) -> bool {
@@ -670,6 +1091,10 @@ impl ConfigFile {
     self.json.import_map.clone()
   }
 
+  pub fn to_user_agent(&self) -> Option<String> {
+    self.json.user_agent.clone()
+  }
+
   pub fn to_import_map_value(&self) -> Value {
     let mut value = serde_json::Map::with_capacity(2);
     if let Some(imports) = &self.json.imports {
@@ -685,6 +1110,20 @@ impl ConfigFile {
     self.json.imports.is_some() || self.json.scopes.is_some()
   }
 
+  pub fn to_lib_overrides(&self) -> Result<Vec<LibOverride>, AnyError> {
+    if let Some(config) = self.json.lib_overrides.clone() {
+      let lib_overrides: Vec<SerializedLibOverride> =
+        serde_json::from_value(config)
+          .context("Failed to parse \"libOverrides\" configuration")?;
+      lib_overrides
+        .into_iter()
+        .map(|lib_override| lib_override.into_resolved(&self.specifier))
+        .collect()
+    } else {
+      Ok(Vec::new())
+    }
+  }
+
   pub fn to_fmt_config(&self) -> Result<Option<FmtConfig>, AnyError> {
     if let Some(config) = self.json.fmt.clone() {
       let fmt_config: SerializedFmtConfig = serde_json::from_value(config)
@@ -725,32 +1164,57 @@ impl ConfigFile {
     }
   }
 
+  pub fn to_permissions_config(
+    &self,
+  ) -> Result<Option<PermissionsConfig>, AnyError> {
+    if let Some(config) = self.json.permissions.clone() {
+      let permissions_config: SerializedPermissionsConfig =
+        serde_json::from_value(config)
+          .context("Failed to parse \"permissions\" configuration")?;
+      Ok(Some(permissions_config.into_resolved(&self.specifier)?))
+    } else {
+      Ok(None)
+    }
+  }
+
   /// Return any tasks that are defined in the configuration file as a sequence
   /// of JSON objects providing the name of the task and the arguments of the
   /// task in a detail field.
+  #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
   pub fn to_lsp_tasks(&self) -> Option<Value> {
-    let value = self.json.tasks.clone()?;
-    let tasks: BTreeMap<String, String> = serde_json::from_value(value).ok()?;
+    let tasks = self.to_tasks_config().ok()??;
     Some(
       tasks
         .into_iter()
-        .map(|(key, value)| {
+        .map(|(key, task)| {
           json!({
             "name": key,
-            "detail": value,
+            "detail": task.command,
           })
         })
         .collect(),
     )
   }
 
+  /// Return the tasks defined in the configuration file in the order they
+  /// are defined.
   pub fn to_tasks_config(
     &self,
-  ) -> Result<Option<BTreeMap<String, String>>, AnyError> {
+  ) -> Result<Option<IndexMap<String, TaskDefinition>>, AnyError> {
     if let Some(config) = self.json.tasks.clone() {
-      let tasks_config: BTreeMap<String, String> =
+      let mut tasks: BTreeMap<String, SerializedTaskDefinition> =
         serde_json::from_value(config)
           .context("Failed to parse \"tasks\" configuration")?;
+      let mut tasks_config = IndexMap::with_capacity(tasks.len());
+      for name in &self.task_names {
+        if let Some(task) = tasks.remove(name) {
+          tasks_config.insert(name.clone(), task.into());
+        }
+      }
+      // shouldn't happen, but don't drop any tasks
+      for (name, task) in tasks {
+        tasks_config.insert(name, task.into());
+      }
       Ok(Some(tasks_config))
     } else {
       Ok(None)
@@ -801,7 +1265,7 @@ impl ConfigFile {
 
   pub fn resolve_tasks_config(
     &self,
-  ) -> Result<BTreeMap<String, String>, AnyError> {
+  ) -> Result<IndexMap<String, TaskDefinition>, AnyError> {
     let maybe_tasks_config = self.to_tasks_config()?;
     if let Some(tasks_config) = maybe_tasks_config {
       for key in tasks_config.keys() {
@@ -822,6 +1286,55 @@ impl ConfigFile {
     }
   }
 
+  /// Resolves the entry point of the directory of the configuration file from
+  /// its `"exports"` field, which is either a path or an object with a `"."`
+  /// key, or its `"main"` field.
+  pub fn to_entrypoint(&self) -> Result<Option<ModuleSpecifier>, AnyError> {
+    let maybe_path = match &self.json.exports {
+      Some(Value::String(path)) => Some(path.as_str()),
+      Some(Value::Object(exports)) => match exports.get(".") {
+        Some(Value::String(path)) => Some(path.as_str()),
+        _ => bail!("Failed to parse \"exports\" configuration: expected a \".\" entry with a path"),
+      },
+      Some(_) => bail!("Failed to parse \"exports\" configuration: expected a path or an object"),
+      None => self.json.main.as_deref(),
+    };
+    match maybe_path {
+      Some(path) => Ok(Some(self.specifier.join(path).with_context(|| {
+        format!("Invalid entry point \"{path}\" in {}", self.specifier)
+      })?)),
+      None => Ok(None),
+    }
+  }
+
+  /// Resolves all the entry points of the directory of the configuration
+  /// file, which are the paths of its `"exports"` field, or its `"main"`
+  /// field. Unlike `to_entrypoint`, subpath exports are included.
+  pub fn to_entrypoints(&self) -> Result<Vec<ModuleSpecifier>, AnyError> {
+    let paths = match &self.json.exports {
+      Some(Value::String(path)) => vec![path.as_str()],
+      Some(Value::Object(exports)) => exports
+        .iter()
+        .map(|(key, value)| match value {
+          Value::String(path) => Ok(path.as_str()),
+          _ => bail!("Failed to parse \"exports\" configuration: expected a path for \"{key}\""),
+        })
+        .collect::<Result<Vec<_>, _>>()?,
+      Some(_) => bail!("Failed to parse \"exports\" configuration: expected a path or an object"),
+      None => self.json.main.as_deref().into_iter().collect(),
+    };
+    let mut entrypoints = Vec::with_capacity(paths.len());
+    for path in paths {
+      let entrypoint = self.specifier.join(path).with_context(|| {
+        format!("Invalid entry point \"{path}\" in {}", self.specifier)
+      })?;
+      if !entrypoints.contains(&entrypoint) {
+        entrypoints.push(entrypoint);
+      }
+    }
+    Ok(entrypoints)
+  }
+
   pub fn to_lock_config(&self) -> Result<Option<LockConfig>, AnyError> {
     if let Some(config) = self.json.lock.clone() {
       let lock_config: LockConfig = serde_json::from_value(config)
@@ -1048,8 +1561,12 @@ mod tests {
         },
         "rules": {
           "tags": ["recommended"],
-          "include": ["ban-untagged-todo"]
-        }
+          "include": ["ban-untagged-todo"],
+          "no-unused-vars": {
+            "argsIgnorePattern": "^_"
+          }
+        },
+        "reportUnusedOrBareIgnores": true
       },
       "fmt": {
         "files": {
@@ -1061,7 +1578,10 @@ mod tests {
           "lineWidth": 80,
           "indentWidth": 4,
           "singleQuote": true,
-          "proseWrap": "preserve"
+          "proseWrap": "preserve",
+          "yaml": {
+            "indentWidth": 2
+          }
         }
       },
       "tasks": {
@@ -1104,6 +1624,11 @@ mod tests {
       Some(vec!["recommended".to_string()])
     );
     assert!(lint_config.rules.exclude.is_none());
+    assert_eq!(
+      lint_config.rules.options.get("no-unused-vars"),
+      Some(&json!({ "argsIgnorePattern": "^_" }))
+    );
+    assert!(lint_config.report_unused_or_bare_ignores);
 
     let fmt_config = config_file
       .to_fmt_config()
@@ -1118,18 +1643,328 @@ mod tests {
     assert_eq!(fmt_config.options.line_width, Some(80));
     assert_eq!(fmt_config.options.indent_width, Some(4));
     assert_eq!(fmt_config.options.single_quote, Some(true));
+    let yaml_options = fmt_config.options.yaml.unwrap();
+    assert_eq!(yaml_options.indent_width, Some(2));
+    assert_eq!(yaml_options.single_quote, None);
 
     let tasks_config = config_file.to_tasks_config().unwrap().unwrap();
     assert_eq!(
-      tasks_config["build"],
+      tasks_config["build"].command,
       "deno run --allow-read --allow-write build.ts",
     );
     assert_eq!(
-      tasks_config["server"],
+      tasks_config["server"].command,
       "deno run --allow-net --allow-read server.ts"
     );
   }
 
+  #[test]
+  fn files_config_with_patterns() {
+    let config_text = r#"{
+      "fmt": {
+        "files": {
+          "include": ["src/", "src/**/*.{ts,md}", "!src/testdata"],
+          "exclude": ["**/*.d.ts"]
+        }
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let files = config_file.to_fmt_config().unwrap().unwrap().files;
+    assert_eq!(
+      files.include,
+      vec![
+        PathBuf::from("/deno/src/"),
+        PathBuf::from("/deno/src/**/*.{ts,md}"),
+        PathBuf::from("!/deno/src/testdata"),
+      ]
+    );
+    assert_eq!(files.exclude, vec![PathBuf::from("/deno/**/*.d.ts")]);
+
+    let matches = |specifier: &str| {
+      files.matches_specifier(&ModuleSpecifier::parse(specifier).unwrap())
+    };
+    assert!(matches("file:///deno/src/mod.ts"));
+    assert!(matches("file:///deno/src/nested/README.md"));
+    assert!(!matches("file:///deno/src/types.d.ts"));
+    assert!(!matches("file:///deno/src/testdata/mod.ts"));
+    assert!(!matches("file:///deno/mod.ts"));
+  }
+
+  #[test]
+  fn fmt_options_with_editorconfig() {
+    let properties = HashMap::from([
+      ("indent_style".to_string(), "tab".to_string()),
+      ("indent_size".to_string(), "tab".to_string()),
+      ("tab_width".to_string(), "4".to_string()),
+      ("max_line_length".to_string(), "off".to_string()),
+      ("end_of_line".to_string(), "crlf".to_string()),
+    ]);
+    let options = FmtOptionsConfig::default().with_editorconfig(&properties);
+    assert_eq!(options.use_tabs, Some(true));
+    assert_eq!(options.indent_width, Some(4));
+    assert_eq!(options.line_width, None);
+    assert_eq!(
+      options.new_line_kind,
+      Some(NewLineKind::CarriageReturnLineFeed)
+    );
+
+    // the configured options take precedence
+    let options = FmtOptionsConfig {
+      use_tabs: Some(false),
+      line_width: Some(100),
+      ..Default::default()
+    }
+    .with_editorconfig(&properties);
+    assert_eq!(options.use_tabs, Some(false));
+    assert_eq!(options.line_width, Some(100));
+    assert_eq!(options.indent_width, Some(4));
+  }
+
+  #[test]
+  fn tasks_object_form_and_order() {
+    let config_text = r#"{
+      "tasks": {
+        // comments are allowed
+        "test": "deno test",
+        "build": {
+          "command": "deno run build.ts",
+          "description": "Builds the project",
+          "group": "release"
+        },
+        "check": { "command": "deno check mod.ts" }
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.jsonc").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let tasks_config = config_file.resolve_tasks_config().unwrap();
+    assert_eq!(
+      tasks_config.keys().collect::<Vec<_>>(),
+      vec!["test", "build", "check"],
+    );
+    assert_eq!(
+      tasks_config["test"],
+      TaskDefinition {
+        command: "deno test".to_string(),
+        description: None,
+        group: None,
+      }
+    );
+    assert_eq!(
+      tasks_config["build"],
+      TaskDefinition {
+        command: "deno run build.ts".to_string(),
+        description: Some("Builds the project".to_string()),
+        group: Some("release".to_string()),
+      }
+    );
+    assert_eq!(
+      tasks_config["check"],
+      TaskDefinition {
+        command: "deno check mod.ts".to_string(),
+        description: None,
+        group: None,
+      }
+    );
+  }
+
+  #[test]
+  fn entrypoint_from_exports_or_main() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let cases = [
+      (
+        r#"{ "exports": "./src/mod.ts" }"#,
+        Some("file:///deno/src/mod.ts"),
+      ),
+      (
+        r#"{ "exports": { ".": "./lib.ts", "./util": "./util.ts" } }"#,
+        Some("file:///deno/lib.ts"),
+      ),
+      (
+        r#"{ "exports": "./mod.ts", "main": "./main.ts" }"#,
+        Some("file:///deno/mod.ts"),
+      ),
+      (r#"{ "main": "./main.ts" }"#, Some("file:///deno/main.ts")),
+      (r#"{}"#, None),
+    ];
+    for (config_text, expected) in cases {
+      let config_file =
+        ConfigFile::new(config_text, &config_specifier).unwrap();
+      assert_eq!(
+        config_file.to_entrypoint().unwrap(),
+        expected.map(|s| ModuleSpecifier::parse(s).unwrap()),
+        "{config_text}"
+      );
+    }
+
+    for config_text in [r#"{ "exports": 1 }"#, r#"{ "exports": {} }"#] {
+      let config_file =
+        ConfigFile::new(config_text, &config_specifier).unwrap();
+      assert!(config_file.to_entrypoint().is_err(), "{config_text}");
+    }
+  }
+
+  #[test]
+  fn entrypoints_from_exports_or_main() {
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let cases: [(&str, &[&str]); 5] = [
+      (
+        r#"{ "exports": "./src/mod.ts" }"#,
+        &["file:///deno/src/mod.ts"],
+      ),
+      (
+        r#"{ "exports": { ".": "./lib.ts", "./util": "./util.ts" } }"#,
+        &["file:///deno/lib.ts", "file:///deno/util.ts"],
+      ),
+      (
+        r#"{ "exports": { "./a": "./mod.ts", "./b": "./mod.ts" } }"#,
+        &["file:///deno/mod.ts"],
+      ),
+      (r#"{ "main": "./main.ts" }"#, &["file:///deno/main.ts"]),
+      (r#"{}"#, &[]),
+    ];
+    for (config_text, expected) in cases {
+      let config_file =
+        ConfigFile::new(config_text, &config_specifier).unwrap();
+      assert_eq!(
+        config_file.to_entrypoints().unwrap(),
+        expected
+          .iter()
+          .map(|s| ModuleSpecifier::parse(s).unwrap())
+          .collect::<Vec<_>>(),
+        "{config_text}"
+      );
+    }
+
+    let config_text = r#"{ "exports": { "./util": 1 } }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_entrypoints().is_err());
+  }
+
+  #[test]
+  fn test_parse_permissions_config() {
+    let config_text = r#"{
+      "permissions": {
+        "net": ["api.example.com", "localhost:8000"],
+        "read": ["./data", "/etc/hosts"],
+        "write": [],
+        "env": true,
+        "run": false,
+        "scheme": ["custom"],
+        "hrtime": true
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let permissions_config = config_file
+      .to_permissions_config()
+      .unwrap()
+      .expect("permissions should be defined");
+    assert_eq!(
+      permissions_config,
+      PermissionsConfig {
+        allow_env: Some(vec![]),
+        allow_hrtime: true,
+        allow_net: Some(vec![
+          "api.example.com".to_string(),
+          "localhost:8000".to_string()
+        ]),
+        allow_read: Some(vec![
+          PathBuf::from("/deno/data"),
+          PathBuf::from("/etc/hosts")
+        ]),
+        allow_scheme: Some(vec!["custom".to_string()]),
+        ..Default::default()
+      }
+    );
+    assert_eq!(
+      permissions_config.to_permission_args(),
+      vec![
+        "--allow-read=/deno/data,/etc/hosts",
+        "--allow-net=api.example.com,localhost:8000",
+        "--allow-env",
+        "--allow-scheme=custom",
+        "--allow-hrtime",
+      ]
+    );
+
+    let config_text = r#"{ "permissions": { "network": true } }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_permissions_config().is_err());
+  }
+
+  #[test]
+  fn test_parse_v8_flags_config() {
+    let config_text = r#"{
+      "test": {
+        "v8Flags": ["--expose-gc"]
+      },
+      "bench": {
+        "v8Flags": ["--allow-natives-syntax", "--max-old-space-size=4096"]
+      }
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let test_config = config_file.to_test_config().unwrap().unwrap();
+    assert_eq!(test_config.v8_flags, vec!["--expose-gc"]);
+    let bench_config = config_file.to_bench_config().unwrap().unwrap();
+    assert_eq!(
+      bench_config.v8_flags,
+      vec!["--allow-natives-syntax", "--max-old-space-size=4096"]
+    );
+
+    let config_text = r#"{ "bench": { "v8Flags": "--expose-gc" } }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_bench_config().is_err());
+  }
+
+  #[test]
+  fn test_parse_config_lib_overrides() {
+    let config_text = r#"{
+      "libOverrides": [{
+        "include": ["workers/"],
+        "exclude": ["workers/shared/"],
+        "lib": ["deno.worker", "dom"]
+      }, {
+        "include": ["workers/shared/"],
+        "lib": ["deno.window"]
+      }]
+    }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let mut lib_overrides = config_file.to_lib_overrides().unwrap();
+    assert_eq!(lib_overrides.len(), 2);
+    assert_eq!(lib_overrides[0].lib, vec!["deno.worker", "dom"]);
+
+    let find_index = |specifier: &str| {
+      LibOverride::find_index(
+        &lib_overrides,
+        &ModuleSpecifier::parse(specifier).unwrap(),
+      )
+    };
+    assert_eq!(find_index("file:///deno/workers/a.ts"), Some(0));
+    assert_eq!(find_index("file:///deno/workers/shared/b.ts"), Some(1));
+    assert_eq!(find_index("file:///deno/main.ts"), None);
+    assert_eq!(find_index("https://deno.land/x/mod.ts"), None);
+
+    lib_overrides[0].add_unstable_lib();
+    assert_eq!(
+      lib_overrides[0].lib,
+      vec!["deno.worker", "dom", "deno.unstable"]
+    );
+
+    let config_text = r#"{ "libOverrides": [{ "include": [], "lib": [] }] }"#;
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    assert!(config_file.to_lib_overrides().is_err());
+  }
+
   #[test]
   fn test_parse_config_with_empty_file() {
     let config_text = "";
@@ -1170,6 +2005,39 @@ mod tests {
     assert!(ConfigFile::new(config_text, &config_specifier).is_err());
   }
 
+  #[test]
+  fn test_parse_config_with_invalid_value_type() {
+    let config_text = r#"{ "importMap": 1 }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let err = ConfigFile::new(config_text, &config_specifier).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid config file file:///deno/deno.json:\n  Invalid value for \"importMap\". Expected a string, but found a number."
+    );
+  }
+
+  #[test]
+  fn test_parse_config_diagnostics() {
+    let config_text =
+      r#"{ "task": {}, "fmt": { "options": { "useTabs": 1 } } }"#;
+    let config_specifier =
+      ModuleSpecifier::parse("file:///deno/deno.json").unwrap();
+    let config_file = ConfigFile::new(config_text, &config_specifier).unwrap();
+    let kinds = config_file
+      .diagnostics()
+      .iter()
+      .map(|d| (d.kind, d.path.as_str()))
+      .collect::<Vec<_>>();
+    assert_eq!(
+      kinds,
+      vec![
+        (ConfigDiagnosticKind::UnknownKey, "task"),
+        (ConfigDiagnosticKind::InvalidType, "fmt.options.useTabs"),
+      ]
+    );
+  }
+
   #[test]
   fn test_tsconfig_as_bytes() {
     let mut tsconfig1 = TsConfig::new(json!({
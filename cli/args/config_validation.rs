@@ -0,0 +1,637 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Validation of a configuration file against the keys and value types Deno
+//! knows about, so that typos and wrong types are reported instead of being
+//! silently ignored.
+
+use deno_core::serde_json;
+use jsonc_parser::ast::Object;
+use jsonc_parser::ast::Value;
+use once_cell::sync::Lazy;
+use std::fmt;
+use std::ops::Range;
+
+/// The expected shape of a value in the configuration file, as described by
+/// the JSON schema of the configuration file.
+#[derive(Debug, Default)]
+pub struct Schema {
+  kind: SchemaKind,
+  /// The documentation of the value, in markdown.
+  #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
+  pub maybe_description: Option<String>,
+  /// The values of an enumeration, or the known values suggested for it.
+  #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
+  pub values: Vec<String>,
+  #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
+  is_enum: bool,
+}
+
+#[derive(Debug, Default)]
+enum SchemaKind {
+  /// Any value is accepted.
+  #[default]
+  Any,
+  String,
+  Boolean,
+  Number,
+  /// An array whose elements match the schema.
+  Array(Box<Schema>),
+  /// An object with the listed properties.
+  Object(Vec<(String, Schema)>),
+  /// An object with arbitrary keys whose values match the schema.
+  Map(Box<Schema>),
+  /// A value matching any of the schemas.
+  OneOf(Vec<Schema>),
+}
+
+static CONFIG_FILE: Lazy<Schema> = Lazy::new(|| {
+  let json: serde_json::Value =
+    serde_json::from_str(include_str!("../schemas/config-file.v1.json"))
+      .unwrap();
+  Schema::from_json(&json)
+});
+
+/// The schema of the configuration file, read from
+/// `cli/schemas/config-file.v1.json`.
+#[cfg_attr(not(feature = "lsp"), allow(dead_code))]
+pub fn config_file_schema() -> &'static Schema {
+  &CONFIG_FILE
+}
+
+impl Schema {
+  fn new(kind: SchemaKind) -> Self {
+    Self {
+      kind,
+      ..Default::default()
+    }
+  }
+
+  /// Converts the parts of a JSON schema Deno uses in the schema of the
+  /// configuration file. The other keywords, like `required`, aren't checked.
+  fn from_json(json: &serde_json::Value) -> Self {
+    let is_enum = json.get("enum").is_some();
+    let values = json
+      .get("enum")
+      .or_else(|| json.get("examples"))
+      .and_then(|values| values.as_array())
+      .map(|values| {
+        values
+          .iter()
+          .filter_map(|value| value.as_str())
+          .map(String::from)
+          .collect::<Vec<_>>()
+      })
+      .unwrap_or_default();
+    let kind = if let Some(schemas) =
+      json.get("oneOf").and_then(|schemas| schemas.as_array())
+    {
+      SchemaKind::OneOf(schemas.iter().map(Schema::from_json).collect())
+    } else {
+      match json.get("type") {
+        Some(serde_json::Value::String(type_name)) => {
+          SchemaKind::from_json(type_name, json)
+        }
+        Some(serde_json::Value::Array(type_names)) => SchemaKind::OneOf(
+          type_names
+            .iter()
+            .filter_map(|type_name| type_name.as_str())
+            .map(|type_name| {
+              Schema::new(SchemaKind::from_json(type_name, json))
+            })
+            .collect(),
+        ),
+        // the enumerations of the schema are all of strings
+        _ if is_enum => SchemaKind::String,
+        _ => SchemaKind::Any,
+      }
+    };
+    let maybe_description = json
+      .get("markdownDescription")
+      .or_else(|| json.get("description"))
+      .and_then(|description| description.as_str())
+      .map(String::from);
+    Self {
+      kind,
+      maybe_description,
+      values,
+      is_enum,
+    }
+  }
+
+  fn matches(&self, value: &Value) -> bool {
+    match (&self.kind, value) {
+      (SchemaKind::Any, _)
+      | (SchemaKind::String, Value::StringLit(_))
+      | (SchemaKind::Boolean, Value::BooleanLit(_))
+      | (SchemaKind::Number, Value::NumberLit(_))
+      | (SchemaKind::Array(_), Value::Array(_))
+      | (SchemaKind::Object(_), Value::Object(_))
+      | (SchemaKind::Map(_), Value::Object(_)) => true,
+      (SchemaKind::OneOf(schemas), value) => {
+        schemas.iter().any(|schema| schema.matches(value))
+      }
+      _ => false,
+    }
+  }
+
+  fn expected(&self) -> String {
+    match &self.kind {
+      SchemaKind::Any => "any value".to_string(),
+      SchemaKind::String => "a string".to_string(),
+      SchemaKind::Boolean => "a boolean".to_string(),
+      SchemaKind::Number => "a number".to_string(),
+      SchemaKind::Array(items) => format!("an array of {}", plural(items)),
+      SchemaKind::Object(_) | SchemaKind::Map(_) => "an object".to_string(),
+      SchemaKind::OneOf(schemas) => schemas
+        .iter()
+        .map(|schema| schema.expected())
+        .collect::<Vec<_>>()
+        .join(" or "),
+    }
+  }
+}
+
+/// The information the language server completes and documents the
+/// configuration file with.
+#[cfg_attr(not(feature = "lsp"), allow(dead_code))]
+impl Schema {
+  /// The schema of the value at a path of keys in the value of this schema.
+  pub fn get(&self, path: &[String]) -> Option<&Schema> {
+    let mut schema = self;
+    for key in path {
+      schema = schema.property(key)?;
+    }
+    Some(schema)
+  }
+
+  fn property(&self, key: &str) -> Option<&Schema> {
+    match &self.kind {
+      SchemaKind::Object(properties) => properties
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, schema)| schema),
+      SchemaKind::Map(values) => Some(values.as_ref()),
+      SchemaKind::OneOf(schemas) => {
+        schemas.iter().find_map(|schema| schema.property(key))
+      }
+      _ => None,
+    }
+  }
+
+  /// The properties Deno knows about when the value is an object.
+  pub fn properties(&self) -> &[(String, Schema)] {
+    match &self.kind {
+      SchemaKind::Object(properties) => properties,
+      SchemaKind::OneOf(schemas) => schemas
+        .iter()
+        .map(|schema| schema.properties())
+        .find(|properties| !properties.is_empty())
+        .unwrap_or_default(),
+      _ => &[],
+    }
+  }
+
+  /// The schema of the elements when the value is an array.
+  pub fn items(&self) -> Option<&Schema> {
+    match &self.kind {
+      SchemaKind::Array(items) => Some(items),
+      SchemaKind::OneOf(schemas) => schemas.iter().find_map(Schema::items),
+      _ => None,
+    }
+  }
+
+  /// Whether `true` and `false` are valid values.
+  pub fn accepts_boolean(&self) -> bool {
+    match &self.kind {
+      SchemaKind::Boolean => true,
+      SchemaKind::OneOf(schemas) => schemas.iter().any(Schema::accepts_boolean),
+      _ => false,
+    }
+  }
+
+  /// The type of the value in TypeScript syntax, like `string[]`.
+  pub fn type_name(&self) -> String {
+    match &self.kind {
+      SchemaKind::Any => "any".to_string(),
+      SchemaKind::String if self.is_enum => self
+        .values
+        .iter()
+        .map(|value| format!("\"{value}\""))
+        .collect::<Vec<_>>()
+        .join(" | "),
+      SchemaKind::String => "string".to_string(),
+      SchemaKind::Boolean => "boolean".to_string(),
+      SchemaKind::Number => "number".to_string(),
+      SchemaKind::Array(items) => format!("{}[]", items.type_name()),
+      SchemaKind::Object(_) | SchemaKind::Map(_) => "object".to_string(),
+      SchemaKind::OneOf(schemas) => schemas
+        .iter()
+        .map(|schema| schema.type_name())
+        .collect::<Vec<_>>()
+        .join(" | "),
+    }
+  }
+}
+
+impl SchemaKind {
+  fn from_json(type_name: &str, json: &serde_json::Value) -> Self {
+    match type_name {
+      "string" => SchemaKind::String,
+      "boolean" => SchemaKind::Boolean,
+      "number" | "integer" => SchemaKind::Number,
+      "array" => SchemaKind::Array(Box::new(
+        json.get("items").map(Schema::from_json).unwrap_or_default(),
+      )),
+      "object" => match json
+        .get("properties")
+        .and_then(|properties| properties.as_object())
+        .filter(|properties| !properties.is_empty())
+      {
+        Some(properties) => SchemaKind::Object(
+          properties
+            .iter()
+            .map(|(key, schema)| (key.clone(), Schema::from_json(schema)))
+            .collect(),
+        ),
+        None => {
+          let maybe_values = json
+            .get("additionalProperties")
+            .filter(|values| values.is_object())
+            .or_else(|| {
+              json
+                .get("patternProperties")
+                .and_then(|patterns| patterns.as_object())
+                .and_then(|patterns| patterns.values().next())
+            });
+          SchemaKind::Map(Box::new(
+            maybe_values.map(Schema::from_json).unwrap_or_default(),
+          ))
+        }
+      },
+      _ => SchemaKind::Any,
+    }
+  }
+}
+
+fn plural(schema: &Schema) -> String {
+  match &schema.kind {
+    SchemaKind::String => "strings".to_string(),
+    SchemaKind::Boolean => "booleans".to_string(),
+    SchemaKind::Number => "numbers".to_string(),
+    SchemaKind::Object(_) | SchemaKind::Map(_) => "objects".to_string(),
+    _ => schema.expected(),
+  }
+}
+
+fn found(value: &Value) -> &'static str {
+  match value {
+    Value::StringLit(_) => "a string",
+    Value::NumberLit(_) => "a number",
+    Value::BooleanLit(_) => "a boolean",
+    Value::Object(_) => "an object",
+    Value::Array(_) => "an array",
+    Value::NullKeyword(_) => "null",
+  }
+}
+
+fn value_range(value: &Value) -> Range<usize> {
+  match value {
+    Value::StringLit(lit) => lit.range.start..lit.range.end,
+    Value::NumberLit(lit) => lit.range.start..lit.range.end,
+    Value::BooleanLit(lit) => lit.range.start..lit.range.end,
+    Value::Object(obj) => obj.range.start..obj.range.end,
+    Value::Array(arr) => arr.range.start..arr.range.end,
+    Value::NullKeyword(keyword) => keyword.range.start..keyword.range.end,
+  }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigDiagnosticKind {
+  /// A top level key Deno doesn't know about. This is a warning, unless
+  /// `--strict-config` is used.
+  UnknownKey,
+  /// A value that doesn't have the expected type. This is always an error.
+  InvalidType,
+}
+
+/// A problem found when validating a configuration file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfigDiagnostic {
+  pub kind: ConfigDiagnosticKind,
+  /// The path of the key or value, like `fmt.options.lineWidth`.
+  pub path: String,
+  pub message: String,
+  /// The byte range of the key or value in the text of the file.
+  pub range: Range<usize>,
+}
+
+impl fmt::Display for ConfigDiagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+/// Validates the text of a configuration file. Text that isn't a JSONC object
+/// produces no diagnostics, as parsing the file reports that already.
+pub fn validate_config_text(text: &str) -> Vec<ConfigDiagnostic> {
+  let ast = match jsonc_parser::parse_to_ast(
+    text,
+    &Default::default(),
+    &Default::default(),
+  ) {
+    Ok(ast) => ast,
+    Err(_) => return Vec::new(),
+  };
+  let mut diagnostics = Vec::new();
+  if let Some(Value::Object(root)) = &ast.value {
+    if let SchemaKind::Object(properties) = &CONFIG_FILE.kind {
+      validate_properties(root, properties, "", &mut diagnostics);
+    }
+  }
+  diagnostics
+}
+
+fn validate_properties(
+  obj: &Object,
+  properties: &[(String, Schema)],
+  path: &str,
+  diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+  for prop in &obj.properties {
+    let name = prop.name.as_str();
+    let prop_path = join_path(path, name);
+    match properties.iter().find(|(key, _)| key == name) {
+      Some((_, schema)) => {
+        validate_value(&prop.value, schema, &prop_path, diagnostics)
+      }
+      // nested sections reject unknown keys when they're deserialized, so
+      // only the top level keys are reported here
+      None if path.is_empty() => {
+        let mut message = format!("Unknown key \"{name}\".");
+        if let Some(suggestion) = suggest_key(name, properties) {
+          message.push_str(&format!(" Did you mean \"{suggestion}\"?"));
+        }
+        diagnostics.push(ConfigDiagnostic {
+          kind: ConfigDiagnosticKind::UnknownKey,
+          path: prop_path,
+          message,
+          range: prop.range.start..prop.range.end,
+        });
+      }
+      None => {}
+    }
+  }
+}
+
+fn validate_value(
+  value: &Value,
+  schema: &Schema,
+  path: &str,
+  diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+  // a null value is the same as not setting the key
+  if matches!(value, Value::NullKeyword(_)) {
+    return;
+  }
+  if !schema.matches(value) {
+    diagnostics.push(ConfigDiagnostic {
+      kind: ConfigDiagnosticKind::InvalidType,
+      path: path.to_string(),
+      message: format!(
+        "Invalid value for \"{}\". Expected {}, but found {}.",
+        path,
+        schema.expected(),
+        found(value),
+      ),
+      range: value_range(value),
+    });
+    return;
+  }
+  match (&schema.kind, value) {
+    (SchemaKind::Array(items), Value::Array(arr)) => {
+      for (i, element) in arr.elements.iter().enumerate() {
+        validate_value(element, items, &format!("{path}[{i}]"), diagnostics);
+      }
+    }
+    (SchemaKind::Object(properties), Value::Object(obj)) => {
+      validate_properties(obj, properties, path, diagnostics);
+    }
+    (SchemaKind::Map(values), Value::Object(obj)) => {
+      for prop in &obj.properties {
+        let prop_path = join_path(path, prop.name.as_str());
+        validate_value(&prop.value, values, &prop_path, diagnostics);
+      }
+    }
+    (SchemaKind::OneOf(schemas), value) => {
+      // validate against the first schema of the matching type
+      if let Some(schema) = schemas.iter().find(|schema| schema.matches(value))
+      {
+        validate_value(value, schema, path, diagnostics);
+      }
+    }
+    _ => {}
+  }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+  if path.is_empty() {
+    key.to_string()
+  } else {
+    format!("{path}.{key}")
+  }
+}
+
+/// Suggests the known key closest to an unknown key, if any is close enough
+/// to likely be a typo.
+fn suggest_key<'a>(
+  name: &str,
+  properties: &'a [(String, Schema)],
+) -> Option<&'a str> {
+  let name = name.to_lowercase();
+  properties
+    .iter()
+    .map(|(key, _)| (key.as_str(), edit_distance(&name, &key.to_lowercase())))
+    .filter(|(key, distance)| *distance <= (key.len() / 3).max(1))
+    .min_by_key(|(_, distance)| *distance)
+    .map(|(key, _)| key)
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b = b.chars().collect::<Vec<_>>();
+  let mut row = (0..=b.len()).collect::<Vec<_>>();
+  for (i, a_char) in a.chars().enumerate() {
+    let mut previous = row[0];
+    row[0] = i + 1;
+    for (j, b_char) in b.iter().enumerate() {
+      let current = row[j + 1];
+      row[j + 1] = if a_char == *b_char {
+        previous
+      } else {
+        1 + previous.min(row[j]).min(current)
+      };
+      previous = current;
+    }
+  }
+  row[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  fn messages(text: &str) -> Vec<(ConfigDiagnosticKind, String, String)> {
+    validate_config_text(text)
+      .into_iter()
+      .map(|d| (d.kind, d.path, d.message))
+      .collect()
+  }
+
+  #[test]
+  fn valid_config() {
+    let text = r#"{
+      // comments are allowed
+      "compilerOptions": { "strict": true },
+      "importMap": null,
+      "imports": { "std/": "https://deno.land/std/" },
+      "lint": { "files": { "include": ["src/"] }, "rules": { "tags": [] } },
+      "fmt": { "options": { "lineWidth": 100, "proseWrap": "never" } },
+      "tasks": {
+        "start": "deno run main.ts",
+        "build": { "command": "deno compile main.ts", "group": "build" }
+      },
+      "lock": false,
+      "permissions": { "read": true, "net": ["deno.land"] },
+      "exports": { ".": "./mod.ts" }
+    }"#;
+    assert_eq!(messages(text), vec![]);
+  }
+
+  #[test]
+  fn unknown_keys() {
+    let text = r#"{
+      "task": { "start": "deno run main.ts" },
+      "importmap": "./import_map.json",
+      "something": true,
+      "fmt": { "unknown": true }
+    }"#;
+    assert_eq!(
+      messages(text),
+      vec![
+        (
+          ConfigDiagnosticKind::UnknownKey,
+          "task".to_string(),
+          "Unknown key \"task\". Did you mean \"tasks\"?".to_string(),
+        ),
+        (
+          ConfigDiagnosticKind::UnknownKey,
+          "importmap".to_string(),
+          "Unknown key \"importmap\". Did you mean \"importMap\"?".to_string(),
+        ),
+        (
+          ConfigDiagnosticKind::UnknownKey,
+          "something".to_string(),
+          "Unknown key \"something\".".to_string(),
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn invalid_types() {
+    let text = r#"{
+      "importMap": 5,
+      "fmt": { "options": { "lineWidth": "80" } },
+      "lint": { "files": { "include": ["src/", 1] } },
+      "tasks": { "start": ["deno", "run"] },
+      "lock": 1
+    }"#;
+    assert_eq!(
+      messages(text),
+      vec![
+        (
+          ConfigDiagnosticKind::InvalidType,
+          "importMap".to_string(),
+          "Invalid value for \"importMap\". Expected a string, but found a number.".to_string(),
+        ),
+        (
+          ConfigDiagnosticKind::InvalidType,
+          "fmt.options.lineWidth".to_string(),
+          "Invalid value for \"fmt.options.lineWidth\". Expected a number, but found a string.".to_string(),
+        ),
+        (
+          ConfigDiagnosticKind::InvalidType,
+          "lint.files.include[1]".to_string(),
+          "Invalid value for \"lint.files.include[1]\". Expected a string, but found a number.".to_string(),
+        ),
+        (
+          ConfigDiagnosticKind::InvalidType,
+          "tasks.start".to_string(),
+          "Invalid value for \"tasks.start\". Expected a string or an object, but found an array.".to_string(),
+        ),
+        (
+          ConfigDiagnosticKind::InvalidType,
+          "lock".to_string(),
+          "Invalid value for \"lock\". Expected a string or a boolean, but found a number.".to_string(),
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn diagnostic_range() {
+    let text = r#"{ "lock": 1 }"#;
+    let diagnostics = validate_config_text(text);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(&text[diagnostics[0].range.clone()], "1");
+  }
+
+  #[test]
+  fn invalid_text() {
+    assert_eq!(messages("{ invalid"), vec![]);
+    assert_eq!(messages("[]"), vec![]);
+  }
+
+  fn known_config_keys(path: &[&str]) -> Vec<&'static str> {
+    let path = path.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+    match config_file_schema().get(&path) {
+      Some(schema) => schema
+        .properties()
+        .iter()
+        .map(|(key, _)| key.as_str())
+        .collect(),
+      None => Vec::new(),
+    }
+  }
+
+  #[test]
+  fn known_keys() {
+    let keys = known_config_keys(&[]);
+    assert!(keys.contains(&"compilerOptions"));
+    assert!(keys.contains(&"tasks"));
+    assert_eq!(
+      known_config_keys(&["lint", "rules"]),
+      vec!["tags", "exclude", "include", "no-unused-vars"]
+    );
+    assert_eq!(
+      known_config_keys(&["tasks", "start"]),
+      vec!["command", "description", "group"]
+    );
+    assert!(known_config_keys(&["compilerOptions"]).contains(&"strict"));
+    assert_eq!(known_config_keys(&["imports"]), Vec::<&str>::new());
+    assert_eq!(known_config_keys(&["unknown"]), Vec::<&str>::new());
+  }
+
+  #[test]
+  fn suggests_keys() {
+    let properties = [
+      ("tasks".to_string(), Schema::default()),
+      ("importMap".to_string(), Schema::default()),
+    ];
+    assert_eq!(suggest_key("task", &properties), Some("tasks"));
+    assert_eq!(suggest_key("importmap", &properties), Some("importMap"));
+    assert_eq!(suggest_key("imprtMap", &properties), Some("importMap"));
+    assert_eq!(suggest_key("lint", &properties), None);
+  }
+}
@@ -1,23 +1,32 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use clap::Arg;
+use clap::ArgGroup;
 use clap::ArgMatches;
 use clap::ColorChoice;
 use clap::Command;
 use clap::ValueHint;
+use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
 use deno_core::url::Url;
+use deno_runtime::colors::ColorMode;
+use deno_runtime::deno_fetch::reqwest::header::HeaderValue;
+use deno_runtime::fmt_errors::StackTraceOptions;
 use deno_runtime::permissions::parse_sys_kind;
 use log::debug;
 use log::Level;
 use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde::Serialize;
 use std::env;
 use std::net::SocketAddr;
 use std::num::NonZeroU32;
 use std::num::NonZeroU8;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use super::flags_allow_net;
 
@@ -55,22 +64,82 @@ pub struct BenchFlags {
   pub files: FileFlags,
   pub filter: Option<String>,
   pub json: bool,
+  /// The interval in milliseconds at which to sample memory usage when
+  /// reporting the peak memory usage.
+  pub report_memory: Option<u64>,
+  /// Runs the benchmark matched by the filter in a loop, for the duration
+  /// when there's one or else until interrupted.
+  pub profile_loop: Option<Option<Duration>>,
+  pub fail_on_empty_files: bool,
+  /// The file to write the statistics of the benchmarks to.
+  pub save_baseline: Option<PathBuf>,
+  /// The file of statistics saved with `--save-baseline` to compare the
+  /// benchmarks against.
+  pub baseline: Option<PathBuf>,
+  /// The percentage by which a benchmark may be slower than in the baseline
+  /// before it's reported as a regression.
+  pub threshold: Option<u32>,
+  /// Fails the comparison when the benchmarks differ from the baseline's.
+  pub strict: bool,
+  /// Re-runs all the bench modules on a change in watch mode, instead of the
+  /// ones which use the changed files.
+  pub watch_run_all: bool,
+  /// The number of bench modules to run in parallel.
+  pub jobs: Option<NonZeroUsize>,
+  /// Only runs the warm-up of the bench modules in parallel, and measures
+  /// them one bench module at a time.
+  pub warmup_only_parallel: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BundleFlags {
   pub source_file: String,
   pub out_file: Option<PathBuf>,
+  /// Overrides the source map options of the configuration file.
+  pub sourcemap: Option<SourceMapMode>,
+}
+
+/// How the source map of a bundle is emitted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SourceMapMode {
+  /// Appended to the bundle as a data URL.
+  Inline,
+  /// Written next to the bundle, which refers to it by its file name.
+  External,
+  None,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CacheFlags {
   pub files: Vec<String>,
+  pub json: bool,
+  pub import_list: Option<String>,
+  pub lockfile_only: bool,
+  pub emit_dir: Option<PathBuf>,
+  pub emit_remote: bool,
+  pub export_dir: Option<PathBuf>,
+  pub import_dir: Option<PathBuf>,
+  pub overwrite: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CheckFlags {
   pub files: Vec<String>,
+  pub doc: bool,
+  pub emit_dir: Option<PathBuf>,
+  pub emit_remote: bool,
+  /// Whether to report the usages of APIs marked `@deprecated`.
+  pub deprecations: bool,
+  pub json: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CiFlags {
+  pub files: FileFlags,
+  pub no_fmt: bool,
+  pub no_lint: bool,
+  pub no_check: bool,
+  pub json: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -79,6 +148,11 @@ pub struct CompileFlags {
   pub output: Option<PathBuf>,
   pub args: Vec<String>,
   pub target: Option<String>,
+  pub no_metadata_flag: bool,
+  pub strip: bool,
+  /// Fail instead of warning about code that isn't supported in compiled
+  /// executables.
+  pub deny_warnings: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -111,8 +185,10 @@ impl Default for DocSourceFileFlag {
 pub struct DocFlags {
   pub private: bool,
   pub json: bool,
+  pub json_schema: bool,
   pub source_file: DocSourceFileFlag,
   pub filter: Option<String>,
+  pub show_references: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -122,10 +198,19 @@ pub struct EvalFlags {
   pub ext: String,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct FmtFlags {
   pub check: bool,
-  pub ext: String,
+  pub compact: bool,
+  pub diff: bool,
+  /// Whether to format every file again instead of skipping the files the
+  /// incremental cache knows are formatted.
+  pub no_cache: bool,
+  /// The content type of stdin, which is otherwise taken from
+  /// `stdin_filepath`, or else TypeScript.
+  pub ext: Option<String>,
+  /// The path stdin is formatted as if it were the contents of.
+  pub stdin_filepath: Option<PathBuf>,
   pub files: FileFlags,
   pub use_tabs: Option<bool>,
   pub line_width: Option<NonZeroU32>,
@@ -135,24 +220,81 @@ pub struct FmtFlags {
   pub no_semicolons: Option<bool>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// The content types `deno fmt -` can format stdin as.
+pub const FMT_STDIN_EXTENSIONS: [&str; 7] =
+  ["ts", "tsx", "js", "jsx", "md", "json", "jsonc"];
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct InitFlags {
   pub dir: Option<String>,
+  pub lib: bool,
+  pub name: Option<String>,
+  /// Skip the prompts, using the defaults for the options not given.
+  pub yes: bool,
+  pub no_tests: bool,
+  pub fmt: bool,
+  pub fmt_use_tabs: bool,
+  pub fmt_line_width: Option<NonZeroU32>,
+  pub fmt_single_quote: bool,
+  pub lint: bool,
+  pub jsx: Option<InitJsxFramework>,
+}
+
+impl InitFlags {
+  /// Whether any of the options otherwise asked by the prompts was given.
+  pub fn has_project_options(&self) -> bool {
+    self.lib
+      || self.name.is_some()
+      || self.no_tests
+      || self.fmt
+      || self.lint
+      || self.jsx.is_some()
+  }
+}
+
+/// The framework the JSX of an initialized project is compiled for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InitJsxFramework {
+  None,
+  React,
+  Preact,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InfoFlags {
   pub json: bool,
   pub file: Option<String>,
+  pub types: bool,
+  pub import_list: Option<String>,
+  pub journal_summary: bool,
+  /// Show the dependencies of the entry points of the configuration file.
+  pub config_entrypoints: bool,
+}
+
+/// The subcommand that an executable installed by `deno install` invokes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InstallSubcommand {
+  /// Runs the module with `deno run`.
+  Run,
+  /// Runs a task of the configuration file with `deno task`.
+  Task,
+}
+
+impl Default for InstallSubcommand {
+  fn default() -> Self {
+    Self::Run
+  }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InstallFlags {
+  /// The module to run, or the configuration file with `--subcommand=task`.
   pub module_url: String,
   pub args: Vec<String>,
   pub name: Option<String>,
   pub root: Option<PathBuf>,
   pub force: bool,
+  pub subcommand: InstallSubcommand,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -161,15 +303,22 @@ pub struct UninstallFlags {
   pub root: Option<PathBuf>,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct LintFlags {
   pub files: FileFlags,
   pub rules: bool,
   pub maybe_rules_tags: Option<Vec<String>>,
   pub maybe_rules_include: Option<Vec<String>>,
   pub maybe_rules_exclude: Option<Vec<String>>,
+  /// Only list the rules that apply to this file with `--rules`.
+  pub maybe_rules_file: Option<PathBuf>,
+  /// Only list the rules with one of these tags with `--rules`.
+  pub maybe_rules_filter_tags: Option<Vec<String>>,
   pub json: bool,
   pub compact: bool,
+  pub verbose: bool,
+  pub deps: bool,
+  pub strict_deps: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -190,10 +339,21 @@ impl RunFlags {
   }
 }
 
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StorageFlags {
+  List,
+  Show { origin: String },
+  Clear { origin: String },
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TaskFlags {
   pub cwd: Option<String>,
   pub task: String,
+  pub list: bool,
+  pub json: bool,
+  pub dry_run: bool,
+  pub show_secrets: bool,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -201,12 +361,75 @@ pub struct TestFlags {
   pub doc: bool,
   pub no_run: bool,
   pub fail_fast: Option<NonZeroUsize>,
+  /// Stop running the tests of a module after this many failures in it,
+  /// while the other modules keep running.
+  pub fail_fast_per_file: Option<NonZeroUsize>,
   pub files: FileFlags,
   pub allow_none: bool,
   pub filter: Option<String>,
   pub shuffle: Option<u64>,
   pub concurrent_jobs: Option<NonZeroUsize>,
   pub trace_ops: bool,
+  pub reporter: TestReporterConfig,
+  /// Where to write the JUnit XML report, in addition to the output of the
+  /// console reporter.
+  pub junit_path: Option<PathBuf>,
+  /// The interval in milliseconds at which to sample memory usage when
+  /// reporting the peak memory usage.
+  pub report_memory: Option<u64>,
+  pub fail_on_empty_files: bool,
+  pub update_golden: bool,
+  /// Fail the tests that run for longer than this, unless they set their own
+  /// timeout.
+  pub timeout: Option<Duration>,
+  /// Only run the tests with one of these tags.
+  pub tags: Vec<String>,
+  /// Don't run the tests with one of these tags.
+  pub skip_tags: Vec<String>,
+  /// How many more times a failing test is run before it's reported as
+  /// failed.
+  pub retries: usize,
+  /// Re-run every test on a change in watch mode, instead of only the tests
+  /// affected by it.
+  pub watch_all_on_change: bool,
+}
+
+/// The format of the output of `--print-import-order`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ImportOrderFormat {
+  Text,
+  Json,
+}
+
+/// The format of the report of `--report-unused-permissions`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UnusedPermissionsFormat {
+  Text,
+  Json,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TestReporterConfig {
+  /// Print the name and result of every test and step.
+  Pretty,
+  /// Print a single character per test.
+  Dot,
+  /// Print a single line per test module.
+  Compact,
+  /// Print a JUnit XML report once all tests have run.
+  Junit,
+  /// Print the results in the TAP version 14 format.
+  Tap,
+  /// Pass the events of the run to the callbacks of the object exported by
+  /// default from the module, given as a path or URL.
+  Module(String),
+}
+
+impl Default for TestReporterConfig {
+  fn default() -> Self {
+    Self::Pretty
+  }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -214,8 +437,11 @@ pub struct UpgradeFlags {
   pub dry_run: bool,
   pub force: bool,
   pub canary: bool,
+  pub rc: bool,
   pub version: Option<String>,
   pub output: Option<PathBuf>,
+  pub list: bool,
+  pub page: Option<NonZeroUsize>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -223,6 +449,9 @@ pub struct VendorFlags {
   pub specifiers: Vec<String>,
   pub output_path: Option<PathBuf>,
   pub force: bool,
+  pub check: bool,
+  pub json: bool,
+  pub import_map_only: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -231,6 +460,7 @@ pub enum DenoSubcommand {
   Bundle(BundleFlags),
   Cache(CacheFlags),
   Check(CheckFlags),
+  Ci(CiFlags),
   Compile(CompileFlags),
   Completions(CompletionsFlags),
   Coverage(CoverageFlags),
@@ -245,6 +475,7 @@ pub enum DenoSubcommand {
   Lint(LintFlags),
   Repl(ReplFlags),
   Run(RunFlags),
+  Storage(StorageFlags),
   Task(TaskFlags),
   Test(TestFlags),
   Types,
@@ -262,6 +493,39 @@ impl Default for DenoSubcommand {
   }
 }
 
+impl DenoSubcommand {
+  /// The name of the subcommand as typed on the command line.
+  pub fn name(&self) -> &'static str {
+    match self {
+      DenoSubcommand::Bench(_) => "bench",
+      DenoSubcommand::Bundle(_) => "bundle",
+      DenoSubcommand::Cache(_) => "cache",
+      DenoSubcommand::Check(_) => "check",
+      DenoSubcommand::Ci(_) => "ci",
+      DenoSubcommand::Compile(_) => "compile",
+      DenoSubcommand::Completions(_) => "completions",
+      DenoSubcommand::Coverage(_) => "coverage",
+      DenoSubcommand::Doc(_) => "doc",
+      DenoSubcommand::Eval(_) => "eval",
+      DenoSubcommand::Fmt(_) => "fmt",
+      DenoSubcommand::Init(_) => "init",
+      DenoSubcommand::Info(_) => "info",
+      DenoSubcommand::Install(_) => "install",
+      DenoSubcommand::Uninstall(_) => "uninstall",
+      DenoSubcommand::Lsp => "lsp",
+      DenoSubcommand::Lint(_) => "lint",
+      DenoSubcommand::Repl(_) => "repl",
+      DenoSubcommand::Run(_) => "run",
+      DenoSubcommand::Storage(_) => "storage",
+      DenoSubcommand::Task(_) => "task",
+      DenoSubcommand::Test(_) => "test",
+      DenoSubcommand::Types => "types",
+      DenoSubcommand::Upgrade(_) => "upgrade",
+      DenoSubcommand::Vendor(_) => "vendor",
+    }
+  }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TypeCheckMode {
   /// Type-check all modules.
@@ -316,6 +580,7 @@ pub struct Flags {
   pub allow_ffi: Option<Vec<PathBuf>>,
   pub allow_read: Option<Vec<PathBuf>>,
   pub allow_run: Option<Vec<String>>,
+  pub allow_scheme: Option<Vec<String>>,
   pub allow_sys: Option<Vec<String>>,
   pub allow_write: Option<Vec<PathBuf>>,
   pub ca_stores: Option<Vec<String>>,
@@ -325,11 +590,15 @@ pub struct Flags {
   /// the language server is configured with an explicit cache option.
   pub cache_path: Option<PathBuf>,
   pub cached_only: bool,
+  pub color: ColorMode,
   pub type_check_mode: TypeCheckMode,
   pub config_flag: ConfigFlag,
   pub node_modules_dir: bool,
   pub coverage_dir: Option<String>,
   pub enable_testing_features: bool,
+  pub exit_on_idle_timeout: Option<Duration>,
+  pub diagnose_event_loop: bool,
+  pub full_stack_traces: bool,
   pub ignore: Vec<PathBuf>,
   pub import_map_path: Option<String>,
   pub inspect_brk: Option<SocketAddr>,
@@ -343,10 +612,21 @@ pub struct Flags {
   pub no_lock: bool,
   pub no_npm: bool,
   pub no_prompt: bool,
+  pub no_config_permissions: bool,
+  pub preload: Vec<String>,
+  pub print_import_order: Option<ImportOrderFormat>,
   pub reload: bool,
+  pub report_unused_permissions: Option<UnusedPermissionsFormat>,
   pub seed: Option<u64>,
+  pub stack_trace_limit: Option<usize>,
+  pub storage_quota: Option<u64>,
+  pub strict_config: bool,
+  pub tls_client_cert: Option<String>,
+  pub tls_client_key: Option<String>,
+  pub tls_client_cert_hosts: Option<Vec<String>>,
   pub unstable: bool,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
+  pub user_agent: Option<String>,
   pub v8_flags: Vec<String>,
   pub version: bool,
   pub watch: Option<Vec<PathBuf>>,
@@ -362,6 +642,15 @@ fn join_paths(allowlist: &[PathBuf], d: &str) -> String {
 }
 
 impl Flags {
+  /// How the stack traces of errors are formatted, from
+  /// `--full-stack-traces` and `--stack-trace-limit`.
+  pub fn stack_trace_options(&self) -> StackTraceOptions {
+    StackTraceOptions {
+      full: self.full_stack_traces,
+      limit: self.stack_trace_limit,
+    }
+  }
+
   /// Return list of permission arguments that are equivalent
   /// to the ones used to create `self`.
   pub fn to_permission_args(&self) -> Vec<String> {
@@ -452,6 +741,17 @@ impl Flags {
       _ => {}
     }
 
+    match &self.allow_scheme {
+      Some(scheme_allowlist) if scheme_allowlist.is_empty() => {
+        args.push("--allow-scheme".to_string());
+      }
+      Some(scheme_allowlist) => {
+        let s = format!("--allow-scheme={}", scheme_allowlist.join(","));
+        args.push(s);
+      }
+      _ => {}
+    }
+
     match &self.allow_ffi {
       Some(ffi_allowlist) if ffi_allowlist.is_empty() => {
         args.push("--allow-ffi".to_string());
@@ -467,6 +767,10 @@ impl Flags {
       args.push("--allow-hrtime".to_string());
     }
 
+    if self.no_config_permissions {
+      args.push("--no-config-permissions".to_string());
+    }
+
     args
   }
 
@@ -478,6 +782,10 @@ impl Flags {
     use DenoSubcommand::*;
 
     match &self.subcommand {
+      Fmt(FmtFlags {
+        stdin_filepath: Some(path),
+        ..
+      }) => Some(vec![path.clone()]),
       Fmt(FmtFlags { files, .. }) => Some(files.include.clone()),
       Lint(LintFlags { files, .. }) => Some(files.include.clone()),
       Run(RunFlags { script }) => {
@@ -511,6 +819,7 @@ impl Flags {
       || self.allow_net.is_some()
       || self.allow_read.is_some()
       || self.allow_run.is_some()
+      || self.allow_scheme.is_some()
       || self.allow_sys.is_some()
       || self.allow_write.is_some()
   }
@@ -524,6 +833,7 @@ impl Flags {
         || arg.starts_with("--allow-net")
         || arg.starts_with("--allow-read")
         || arg.starts_with("--allow-run")
+        || arg.starts_with("--allow-scheme")
         || arg.starts_with("--allow-sys")
         || arg.starts_with("--allow-write")
     })
@@ -538,10 +848,23 @@ static ENV_VARIABLES_HELP: &str = r#"ENVIRONMENT VARIABLES:
     DENO_TLS_CA_STORE    Comma-separated list of order dependent certificate
                          stores. Possible values: "system", "mozilla".
                          Defaults to "mozilla".
+    DENO_TLS_CLIENT_CERT Load the TLS client certificate presented to
+                         servers requiring mutual TLS from PEM encoded file
+    DENO_TLS_CLIENT_KEY  Load the private key of the TLS client certificate
+                         from PEM encoded file
+    DENO_TLS_CLIENT_CERT_HOSTS
+                         Comma-separated list of the hosts to present the
+                         TLS client certificate to. Defaults to all hosts.
     DENO_CERT            Load certificate authority from PEM encoded file
     DENO_DIR             Set the cache directory
+    DENO_FETCH_CONCURRENCY
+                         Maximum number of concurrent remote module
+                         downloads per host. Defaults to 32.
     DENO_INSTALL_ROOT    Set deno install's output directory
                          (defaults to $HOME/.deno/bin)
+    DENO_JOURNAL         Append a line of JSON with the subcommand, flags,
+                         duration and exit code of every invocation to this
+                         file, summarized by 'deno info --journal-summary'
     DENO_NO_PROMPT       Set to disable permission prompts on access
                          (alternative to passing --no-prompt on invocation)
     DENO_NO_UPDATE_CHECK Set to disable checking if a newer Deno version is
@@ -551,6 +874,8 @@ static ENV_VARIABLES_HELP: &str = r#"ENVIRONMENT VARIABLES:
     DENO_JOBS            Number of parallel workers used for the --parallel
                          flag with the test subcommand. Defaults to number
                          of available CPUs.
+    FORCE_COLOR          Set to force color, or to 0 to disable it
+                         (overrides NO_COLOR)
     HTTP_PROXY           Proxy address for HTTP requests
                          (module downloads, fetch)
     HTTPS_PROXY          Proxy address for HTTPS requests
@@ -558,7 +883,9 @@ static ENV_VARIABLES_HELP: &str = r#"ENVIRONMENT VARIABLES:
     NPM_CONFIG_REGISTRY  URL to use for the npm registry.
     NO_COLOR             Set to disable color
     NO_PROXY             Comma-separated list of hosts which do not use a proxy
-                         (module downloads, fetch)"#;
+                         (module downloads, fetch)
+    UPDATE_GOLDEN        Set to 1 to rewrite golden files with the test
+                         subcommand (alternative to passing --update-golden)"#;
 
 static DENO_HELP: Lazy<String> = Lazy::new(|| {
   format!(
@@ -596,6 +923,12 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::Result<Flags> {
     flags.unstable = true;
   }
 
+  match matches.value_of("color") {
+    Some("always") => flags.color = ColorMode::Always,
+    Some("never") => flags.color = ColorMode::Never,
+    _ => {}
+  }
+
   if matches.is_present("quiet") {
     flags.log_level = Some(Level::Error);
   } else {
@@ -611,6 +944,7 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::Result<Flags> {
     Some(("bundle", m)) => bundle_parse(&mut flags, m),
     Some(("cache", m)) => cache_parse(&mut flags, m),
     Some(("check", m)) => check_parse(&mut flags, m),
+    Some(("ci", m)) => ci_parse(&mut flags, m),
     Some(("compile", m)) => compile_parse(&mut flags, m),
     Some(("completions", m)) => completions_parse(&mut flags, m, app),
     Some(("coverage", m)) => coverage_parse(&mut flags, m),
@@ -624,6 +958,7 @@ pub fn flags_from_vec(args: Vec<String>) -> clap::Result<Flags> {
     Some(("lsp", m)) => lsp_parse(&mut flags, m),
     Some(("repl", m)) => repl_parse(&mut flags, m),
     Some(("run", m)) => run_parse(&mut flags, m),
+    Some(("storage", m)) => storage_parse(&mut flags, m),
     Some(("task", m)) => task_parse(&mut flags, m, &args),
     Some(("test", m)) => test_parse(&mut flags, m),
     Some(("types", m)) => types_parse(&mut flags, m),
@@ -653,6 +988,7 @@ fn handle_repl_flags(flags: &mut Flags, repl_flags: ReplFlags) {
     flags.allow_sys = Some(vec![]);
     flags.allow_write = Some(vec![]);
     flags.allow_ffi = Some(vec![]);
+    flags.allow_scheme = Some(vec![]);
     flags.allow_hrtime = true;
   }
   flags.subcommand = DenoSubcommand::Repl(repl_flags);
@@ -688,10 +1024,25 @@ fn clap_root(version: &str) -> Command {
         .help("Suppress diagnostic output")
         .global(true),
     )
+    .arg(
+      Arg::new("color")
+        .long("color")
+        .help("Control when to use colored output")
+        .long_help(
+          "Control when to use colored output. When set to 'auto', color is \
+used unless NO_COLOR is set, and FORCE_COLOR overrides NO_COLOR.",
+        )
+        .takes_value(true)
+        .value_name("WHEN")
+        .possible_values(["auto", "always", "never"])
+        .require_equals(true)
+        .global(true),
+    )
     .subcommand(bench_subcommand())
     .subcommand(bundle_subcommand())
     .subcommand(cache_subcommand())
     .subcommand(check_subcommand())
+    .subcommand(ci_subcommand())
     .subcommand(compile_subcommand())
     .subcommand(completions_subcommand())
     .subcommand(coverage_subcommand())
@@ -706,6 +1057,7 @@ fn clap_root(version: &str) -> Command {
     .subcommand(lint_subcommand())
     .subcommand(repl_subcommand())
     .subcommand(run_subcommand())
+    .subcommand(storage_subcommand())
     .subcommand(task_subcommand())
     .subcommand(test_subcommand())
     .subcommand(types_subcommand())
@@ -739,6 +1091,73 @@ fn bench_subcommand<'a>() -> Command<'a> {
         .takes_value(true)
         .help("Run benchmarks with this string or pattern in the bench name"),
     )
+    .arg(report_memory_arg())
+    .arg(fail_on_empty_files_arg())
+    .arg(
+      Arg::new("profile-loop")
+        .long("profile-loop")
+        .takes_value(true)
+        .require_equals(true)
+        .min_values(0)
+        .value_name("DURATION")
+        .requires("filter")
+        .conflicts_with("json")
+        .validator(parse_duration)
+        .help("Run the benchmark matched by --filter in a loop")
+        .long_help(
+          "Run the benchmark matched by --filter in a loop without collecting \
+statistics, for the given duration or until interrupted. This is useful to \
+attach a profiler, for example with --inspect. The number of iterations per \
+second is printed every second, and the total number of iterations and the \
+mean time per iteration when the loop stops.",
+        ),
+    )
+    .arg(
+      Arg::new("save-baseline")
+        .long("save-baseline")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .conflicts_with("profile-loop")
+        .help("Save the statistics of the benchmarks to a file")
+        .long_help(
+          "Save the statistics of the benchmarks to a JSON file, to compare \
+later runs against with --baseline.",
+        ),
+    )
+    .arg(
+      Arg::new("baseline")
+        .long("baseline")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("FILE")
+        .value_hint(ValueHint::FilePath)
+        .conflicts_with("profile-loop")
+        .help("Compare the benchmarks against a saved baseline")
+        .long_help(
+          "Compare the benchmarks against the statistics saved with \
+--save-baseline, showing how much the mean time changed next to each \
+benchmark. Fails when a benchmark is slower by more than --threshold.",
+        ),
+    )
+    .arg(
+      Arg::new("threshold")
+        .long("threshold")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("PERCENT")
+        .requires("baseline")
+        .validator(parse_percentage)
+        .help(
+          "Set how much slower than the baseline a benchmark may be. \
+Defaults to 5%.",
+        ),
+    )
+    .arg(Arg::new("strict").long("strict").requires("baseline").help(
+      "Fail when benchmarks are missing from the baseline or the current \
+run",
+    ))
     .arg(
       Arg::new("files")
         .help("List of file names to run")
@@ -748,6 +1167,39 @@ fn bench_subcommand<'a>() -> Command<'a> {
     )
     .arg(watch_arg(false))
     .arg(no_clear_screen_arg())
+    .arg(
+      Arg::new("watch-run-all")
+        .requires("watch")
+        .long("watch-run-all")
+        .help("Re-run all benchmarks on a change in watch mode")
+        .long_help("Re-run all benchmarks on a change in watch mode. By default only the bench modules which use the changed files run again.")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("jobs")
+        .long("jobs")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("N")
+        .conflicts_with("profile-loop")
+        .validator(|val: &str| match val.parse::<NonZeroUsize>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("jobs should be a non zero unsigned integer".to_string()),
+        })
+        .help("Run N bench modules in parallel")
+        .long_help(
+          "Run N bench modules in parallel, each pinned to its own CPU core \
+where supported. The benchmarks of a bench module still run one at a time, \
+but running bench modules in parallel skews their timings.",
+        ),
+    )
+    .arg(
+      Arg::new("warmup-only-parallel")
+        .long("warmup-only-parallel")
+        .requires("jobs")
+        .help("Only warm up bench modules in parallel, and measure them one at a time")
+        .takes_value(false),
+    )
     .arg(script_arg().last(true))
     .about("Run benchmarks")
     .long_about(
@@ -761,7 +1213,16 @@ and report results to standard output:
 Directory arguments are expanded to all contained files matching the \
 glob {*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}:
 
-  deno bench src/",
+  deno bench src/
+
+Run a single benchmark in a loop to attach a profiler:
+
+  deno bench --inspect-wait --filter \"parse\" --profile-loop=30s
+
+Detect regressions by saving a baseline and comparing later runs against it:
+
+  deno bench --save-baseline=main.json
+  deno bench --baseline=main.json --threshold=5%",
     )
 }
 
@@ -779,6 +1240,20 @@ fn bundle_subcommand<'a>() -> Command<'a> {
         .required(false)
         .value_hint(ValueHint::FilePath),
     )
+    .arg(
+      Arg::new("sourcemap")
+        .long("sourcemap")
+        .takes_value(true)
+        .require_equals(true)
+        .possible_values(["inline", "external", "none"])
+        .requires_if("external", "out_file")
+        .help("Emit the source map inline, in a separate file or not at all")
+        .long_help(
+          "Emit the source map inline as a data URL, in a separate file next \
+to the output file or not at all. Defaults to the source map compiler options \
+of the configuration file, which don't emit a source map unless set.",
+        ),
+    )
     .arg(watch_arg(false))
     .arg(no_clear_screen_arg())
     .about("Bundle module and dependencies into single file")
@@ -798,10 +1273,58 @@ fn cache_subcommand<'a>() -> Command<'a> {
     .arg(
       Arg::new("file")
         .takes_value(true)
-        .required(true)
+        .required_unless_present_any(["import-list", "import"])
         .min_values(1)
         .value_hint(ValueHint::FilePath),
     )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .help("UNSTABLE: Outputs the cache summary in JSON format")
+        .takes_value(false),
+    )
+    .arg(import_list_arg())
+    .arg(
+      Arg::new("lockfile-only")
+        .long("lockfile-only")
+        .help("Resolve the npm packages and update the lock file without downloading them")
+        .conflicts_with("no-lock")
+        .takes_value(false),
+    )
+    .arg(emit_dir_arg().conflicts_with("lockfile-only"))
+    .arg(emit_remote_arg())
+    .arg(
+      Arg::new("export")
+        .long("export")
+        .value_name("DIR")
+        .help("Copy the cache entries of the modules to a portable bundle")
+        .takes_value(true)
+        .conflicts_with("lockfile-only")
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(
+      Arg::new("import")
+        .long("import")
+        .value_name("DIR")
+        .help("Merge the cache entries of a bundle into DENO_DIR")
+        .takes_value(true)
+        .conflicts_with_all(&[
+          "file",
+          "import-list",
+          "export",
+          "lockfile-only",
+          "emit-dir",
+          "json",
+        ])
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(
+      Arg::new("overwrite")
+        .long("overwrite")
+        .requires("import")
+        .help("Replace the entries that are already cached with other content")
+        .takes_value(false),
+    )
     .about("Cache the dependencies")
     .long_about(
       "Cache and compile remote dependencies recursively.
@@ -812,7 +1335,39 @@ them in the local cache, without running any code:
   deno cache https://deno.land/std/http/file_server.ts
 
 Future runs of this module will trigger no downloads or compilation unless \
---reload is specified.",
+--reload is specified.
+
+Many modules can be cached at once by listing them in a file, one per line. \
+The modules that fail to be cached are reported at the end without \
+preventing the others from being cached:
+
+  deno cache --import-list=entrypoints.txt
+
+To preview what a dependency change would do, --lockfile-only resolves the npm \
+packages and updates the lock file using only the registry information, \
+printing the packages that were added, removed or changed. The packages are \
+downloaded once they are used:
+
+  deno cache --lockfile-only main.ts npm:chalk@5
+
+To use the modules with tools that expect plain JavaScript, --emit-dir writes \
+the transpiled output of the local modules to a directory, mirroring their \
+layout, along with a manifest.json describing the emitted files:
+
+  deno cache --emit-dir=dist main.ts
+
+To populate the cache of a machine without network access, --export copies \
+the cache entries of the remote modules and npm packages to a directory, \
+along with a manifest.json of their checksums:
+
+  deno cache --export=bundle main.ts
+
+The directory is then merged into the cache of the other machine with \
+--import, after verifying the checksums. The entries that are already \
+cached with other content are skipped unless --overwrite is specified:
+
+  deno cache --import=bundle
+  deno run --cached-only main.ts",
     )
 }
 
@@ -839,18 +1394,124 @@ fn check_subcommand<'a>() -> Command<'a> {
         .min_values(1)
         .value_hint(ValueHint::FilePath),
     )
+    .arg(
+      Arg::new("doc")
+        .long("doc")
+        .help("Type-check code blocks in markdown files and JSDoc comments only")
+        .conflicts_with("watch")
+        .takes_value(false),
+    )
+    .arg(
+      emit_dir_arg()
+        .conflicts_with("doc")
+        .conflicts_with("watch"),
+    )
+    .arg(emit_remote_arg())
+    .arg(
+      Arg::new("deprecations")
+        .long("deprecations")
+        .help("Report the usages of APIs marked with @deprecated")
+        .long_help("Report the usages and imports of APIs whose declarations are marked with the @deprecated JSDoc tag, failing when there are any. A usage is ignored when the line before it is a `// deno-deprecated-ignore` comment.")
+        .conflicts_with("doc")
+        .conflicts_with("watch")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .help("Output the usages of deprecated APIs in JSON format")
+        .requires("deprecations")
+        .takes_value(false),
+    )
+    .arg(watch_arg(false))
+    .arg(no_clear_screen_arg())
     .about("Type-check the dependencies")
     .long_about(
       "Download and type-check without execution.
 
   deno check https://deno.land/std/http/file_server.ts
 
+Type-check the code blocks in markdown files and the examples in JSDoc
+comments without running them. Blocks tagged with `ignore` are skipped:
+
+  deno check --doc README.md mod.ts
+
+Write the transpiled JavaScript of the local modules to a directory once they \
+type-check. Relative imports of TypeScript files are rewritten to the emitted \
+JavaScript files, and remote modules are included under deps/ with \
+--emit-remote:
+
+  deno check --emit-dir=dist --emit-remote main.ts
+
+Report the usages of APIs marked with the @deprecated JSDoc tag, including the \
+ones of remote modules and npm packages:
+
+  deno check --deprecations main.ts
+
 Unless --reload is specified, this command will not re-download already cached dependencies.",
     )
 }
 
+fn ci_subcommand<'a>() -> Command<'a> {
+  compile_args_without_check_args(Command::new("ci"))
+    .about("Check formatting, lint and type-check the project")
+    .long_about(
+      "Check the formatting of the source files, lint them and type-check them \
+in one pass. The files are collected and parsed once, then shared by all of \
+the checks, and the problems are reported grouped by file.
+
+  deno ci
+  deno ci src/ --no-check
+
+The 'fmt' and 'lint' settings of the configuration file apply as they do for \
+'deno fmt --check' and 'deno lint'. Exits with a non-zero code when any of \
+the checks fails.",
+    )
+    .arg(
+      Arg::new("no-fmt")
+        .long("no-fmt")
+        .help("Skip checking the formatting"),
+    )
+    .arg(
+      Arg::new("no-lint")
+        .long("no-lint")
+        .help("Skip linting"),
+    )
+    .arg(
+      Arg::new("no-check")
+        .long("no-check")
+        .help("Skip type-checking"),
+    )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .help("Output the report of all the checks in JSON format")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("ignore")
+        .long("ignore")
+        .takes_value(true)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .help("Ignore particular source files")
+        .value_hint(ValueHint::AnyPath),
+    )
+    .arg(
+      Arg::new("files")
+        .takes_value(true)
+        .multiple_values(true)
+        .multiple_occurrences(true)
+        .required(false)
+        .value_hint(ValueHint::AnyPath),
+    )
+}
+
 fn compile_subcommand<'a>() -> Command<'a> {
   runtime_args(Command::new("compile"), true, false)
+    .arg(exit_on_idle_timeout_arg())
+    .arg(preload_arg())
+    .arg(report_unused_permissions_arg())
     .trailing_var_arg(true)
     .arg(script_arg().required(true))
     .arg(
@@ -873,6 +1534,21 @@ fn compile_subcommand<'a>() -> Command<'a> {
           "aarch64-apple-darwin",
         ]),
     )
+    .arg(
+      Arg::new("no-metadata-flag")
+        .long("no-metadata-flag")
+        .help("Pass '--deno-metadata' to the script instead of printing the executable's metadata"),
+    )
+    .arg(
+      Arg::new("strip")
+        .long("strip")
+        .help("Embed the program in a smaller runtime without the type checker, without source maps and with maximum compression"),
+    )
+    .arg(
+      Arg::new("deny-warnings")
+        .long("deny-warnings")
+        .help("Fail if the program uses APIs that aren't supported in compiled executables"),
+    )
     .about("UNSTABLE: Compile the script into a self contained executable")
     .long_about(
       "UNSTABLE: Compiles the given script into a self contained executable.
@@ -894,6 +1570,26 @@ Cross-compiling to different target architectures is supported using the \
 `--target` flag. On the first invocation with deno will download proper \
 binary and cache it in $DENO_DIR. The aarch64-apple-darwin target is not \
 supported in canary.
+
+The compiled executable prints the Deno version, target, main module, \
+embedded flags and included modules as JSON when run with '--deno-metadata'. \
+Use '--no-metadata-flag' to pass that argument to the script instead. The \
+compile timestamp is omitted when SOURCE_DATE_EPOCH is set.
+
+Use '--strip' to reduce the size of the executable. The program is embedded \
+in a runtime without the TypeScript compiler, the type definitions and the \
+language server, which compiled executables never use, and without the \
+WebGPU API. Compiling fails if a program using '--unstable' uses WebGPU. The \
+runtime is downloaded and cached in $DENO_DIR, unless DENORT_BIN is set to \
+its path. The program is compressed with the highest compression level and \
+source maps are omitted, so stack traces point at the transpiled JavaScript \
+and startup takes slightly longer. The size of the executable with and \
+without '--strip' is reported.
+
+The modules are checked for code that doesn't work in compiled executables, \
+like workers or dynamic imports of modules that can't be known ahead of time, \
+and a warning is printed for each occurrence. Use '--deny-warnings' to fail \
+instead.
 ",
     )
 }
@@ -1031,10 +1727,20 @@ Output documentation in JSON format:
 
     deno doc --json ./path/to/module.ts
 
+The JSON output has a top-level \"version\" field that is incremented when its
+shape changes. Print the JSON Schema of the output with:
+
+    deno doc --json-schema
+
 Target a specific symbol:
 
     deno doc ./path/to/module.ts MyClass.someField
 
+List where the types referenced by each exported symbol are declared, and
+whether they are local, from the standard library, remote or npm packages:
+
+    deno doc --show-references ./path/to/module.ts
+
 Show documentation for runtime built-ins:
 
     deno doc
@@ -1048,12 +1754,28 @@ Show documentation for runtime built-ins:
         .help("Output documentation in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("json-schema")
+        .long("json-schema")
+        .help("Output the JSON Schema of the documentation JSON format")
+        .takes_value(false)
+        .conflicts_with_all(&["json", "private", "source_file", "filter"]),
+    )
     .arg(
       Arg::new("private")
         .long("private")
         .help("Output private documentation")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("show-references")
+        .long("show-references")
+        .help(
+          "List the modules that the types of each exported symbol come from",
+        )
+        .takes_value(false)
+        .conflicts_with("json-schema"),
+    )
     // TODO(nayeemrmn): Make `--builtin` a proper option. Blocked by
     // https://github.com/clap-rs/clap/issues/1794. Currently `--builtin` is
     // just a possible value of `source_file` so leading hyphens must be
@@ -1129,7 +1851,7 @@ fn fmt_subcommand<'a>() -> Command<'a> {
   Command::new("fmt")
     .about("Format source files")
     .long_about(
-      "Auto-format JavaScript, TypeScript, Markdown, and JSON files.
+      "Auto-format JavaScript, TypeScript, Markdown, JSON, and YAML files.
 
   deno fmt
   deno fmt myfile1.ts myfile2.ts
@@ -1139,15 +1861,29 @@ Format stdin and write to stdout:
 
   cat file.ts | deno fmt -
 
+Format stdin as if it were the contents of a file, using the configuration
+and ignore rules which apply to it:
+
+  cat README.md | deno fmt --stdin-filepath=docs/README.md -
+
 Ignore formatting code by preceding it with an ignore comment:
 
   // deno-fmt-ignore
 
 Ignore formatting a file by adding an ignore comment at the top of the file:
 
-  // deno-fmt-ignore-file",
+  // deno-fmt-ignore-file
+
+The indent_style, indent_size, max_line_length and end_of_line properties of
+.editorconfig files are used for the options not set in the config file or
+with flags.
+
+The fenced JavaScript, TypeScript and JSON code blocks of Markdown files and
+JSDoc comments are formatted too, unless \"codeBlocks\" is set to false in the
+\"fmt.options\" of the config file.",
     )
     .arg(config_arg())
+    .arg(strict_config_arg())
     .arg(no_config_arg())
     .arg(
       Arg::new("check")
@@ -1155,13 +1891,45 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
         .help("Check if the source files are formatted")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("compact")
+        .long("compact")
+        .help("Output the not formatted locations in compact format")
+        .long_help("Output one `path:line:col: Not formatted` line per changed line instead of a diff, which is understood by the errorformat of most editors.")
+        .takes_value(false)
+        .requires("check"),
+    )
+    .arg(
+      Arg::new("diff")
+        .long("diff")
+        .help("Output a unified diff of the not formatted files")
+        .long_help("Output a unified diff between the contents and the formatted contents of each not formatted file, like `git diff`, showing at most 20 hunks per file.")
+        .takes_value(false)
+        .requires("check")
+        .conflicts_with("compact"),
+    )
+    .arg(
+      Arg::new("no-cache")
+        .long("no-cache")
+        .help("Format or check every file, ignoring the incremental cache")
+        .long_help("Format or check every file instead of skipping the files which were formatted with the same options by a previous run, and leave the incremental cache in the Deno cache directory untouched.")
+        .takes_value(false),
+    )
     .arg(
       Arg::new("ext")
         .long("ext")
         .help("Set standard input (stdin) content type")
+        .long_help("Set standard input (stdin) content type. Defaults to the extension of --stdin-filepath, or else ts.")
+        .takes_value(true)
+        .possible_values(FMT_STDIN_EXTENSIONS),
+    )
+    .arg(
+      Arg::new("stdin-filepath")
+        .long("stdin-filepath")
+        .help("Format standard input (stdin) as if it were the file at this path")
+        .long_help("Format standard input (stdin) as if it were the contents of the file at this path, which doesn't need to exist. Its extension sets the content type unless --ext is passed, the configuration file and .editorconfig files are looked up from its directory, and stdin is written out unchanged when the file is excluded from formatting.")
         .takes_value(true)
-        .default_value("ts")
-        .possible_values(["ts", "tsx", "js", "jsx", "md", "json", "jsonc"]),
+        .value_hint(ValueHint::FilePath),
     )
     .arg(
       Arg::new("ignore")
@@ -1234,7 +2002,8 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
         .alias("options-prose-wrap")
         .takes_value(true)
         .possible_values(["always", "never", "preserve"])
-        .help("Define how prose should be wrapped. Defaults to always."),
+        .help("Define how prose should be wrapped. Defaults to always.")
+        .long_help("Define how prose should be wrapped in markdown files. 'always' reflows paragraphs to the line width, 'never' joins the lines of each paragraph and 'preserve' keeps the existing line breaks. Defaults to always."),
     )
     .arg(
       Arg::new("no-semicolons")
@@ -1250,49 +2019,143 @@ Ignore formatting a file by adding an ignore comment at the top of the file:
 }
 
 fn init_subcommand<'a>() -> Command<'a> {
-  Command::new("init").about("Initialize a new project").arg(
-    Arg::new("dir")
-      .takes_value(true)
-      .required(false)
-      .value_hint(ValueHint::DirPath),
-  )
-}
-
-fn info_subcommand<'a>() -> Command<'a> {
-  Command::new("info")
-    .about("Show info about cache or info related to source file")
-    .long_about(
-      "Information about a module or the cache directories.
-
-Get information about a module:
-
-  deno info https://deno.land/std/http/file_server.ts
-
-The following information is shown:
-
-local: Local path of the file.
-type: JavaScript, TypeScript, or JSON.
-emit: Local path of compiled source code. (TypeScript only.)
+  Command::new("init")
+    .about("Initialize a new project")
+    .arg(
+      Arg::new("dir")
+        .takes_value(true)
+        .required(false)
+        .value_hint(ValueHint::DirPath),
+    )
+    .arg(Arg::new("lib").long("lib").help(
+      "Initialize a library with publishing metadata and a version bump task",
+    ))
+    .arg(
+      Arg::new("name")
+        .long("name")
+        .takes_value(true)
+        .help("The name of the project or the package name of the library")
+        .long_help("The name of the project or the package name of the library. Defaults to the directory name for a library, and to no name for a project initialized without prompts."),
+    )
+    .arg(
+      Arg::new("yes")
+        .short('y')
+        .long("yes")
+        .help("Skip the prompts, using the defaults for the options not given")
+        .long_help("Skip the prompts, using the defaults for the options not given. The prompts are only shown when stdin and stderr are terminals and none of the project options are given."),
+    )
+    .arg(
+      Arg::new("no-tests")
+        .long("no-tests")
+        .conflicts_with("lib")
+        .help("Don't create the test and benchmark files"),
+    )
+    .arg(
+      Arg::new("fmt")
+        .long("fmt")
+        .conflicts_with("lib")
+        .help("Add a fmt configuration to the configuration file"),
+    )
+    .arg(
+      Arg::new("fmt-use-tabs")
+        .long("fmt-use-tabs")
+        .conflicts_with("lib")
+        .help("Indent with tabs in the fmt configuration. Implies --fmt"),
+    )
+    .arg(
+      Arg::new("fmt-line-width")
+        .long("fmt-line-width")
+        .conflicts_with("lib")
+        .takes_value(true)
+        .value_name("WIDTH")
+        .help("The line width of the fmt configuration. Defaults to 80. Implies --fmt")
+        .validator(|val: &str| match val.parse::<NonZeroU32>() {
+          Ok(_) => Ok(()),
+          Err(_) => {
+            Err("fmt-line-width should be a non zero integer".to_string())
+          }
+        }),
+    )
+    .arg(
+      Arg::new("fmt-single-quote")
+        .long("fmt-single-quote")
+        .conflicts_with("lib")
+        .help("Use single quotes in the fmt configuration. Implies --fmt"),
+    )
+    .arg(
+      Arg::new("lint")
+        .long("lint")
+        .conflicts_with("lib")
+        .help("Add a lint configuration to the configuration file"),
+    )
+    .arg(
+      Arg::new("jsx")
+        .long("jsx")
+        .conflicts_with("lib")
+        .takes_value(true)
+        .value_name("FRAMEWORK")
+        .possible_values(["none", "react", "preact"])
+        .help("The framework to compile JSX for. Defaults to none"),
+    )
+}
+
+fn info_subcommand<'a>() -> Command<'a> {
+  Command::new("info")
+    .about("Show info about cache or info related to source file")
+    .long_about(
+      "Information about a module or the cache directories.
+
+Get information about a module:
+
+  deno info https://deno.land/std/http/file_server.ts
+
+Get information about many modules at once, listed one per line in a file:
+
+  deno info --import-list=entrypoints.txt
+
+With '--config-entrypoints', the dependencies of every entry point in the
+\"exports\" or \"main\" field of the configuration file are shown in one JSON
+document, with the modules reachable from each entry point listed in \"reachable\":
+
+  deno info --json --config-entrypoints
+
+The following information is shown:
+
+local: Local path of the file.
+type: JavaScript, TypeScript, or JSON.
+emit: Local path of compiled source code. (TypeScript only.)
 dependencies: Dependency tree of the source file.
 
 Without any additional arguments, 'deno info' shows:
 
 DENO_DIR: Directory containing Deno-managed files.
 Remote modules cache: Subdirectory containing downloaded remote modules.
-TypeScript compiler cache: Subdirectory containing TS compiler output.",
+TypeScript compiler cache: Subdirectory containing TS compiler output.
+
+Summarize the invocations recorded in the file set by DENO_JOURNAL:
+
+  DENO_JOURNAL=~/.deno_journal deno info --journal-summary",
     )
     .arg(Arg::new("file").takes_value(true).required(false).value_hint(ValueHint::FilePath))
-    .arg(reload_arg().requires("file"))
+    .arg(import_list_arg())
+    .group(ArgGroup::new("modules").args(&["file", "import-list"]).multiple(true))
+    .arg(reload_arg().requires("modules"))
+    .arg(preload_arg().requires("file"))
     .arg(ca_file_arg())
+    .arg(tls_client_cert_arg())
+    .arg(tls_client_key_arg())
+    .arg(tls_client_cert_hosts_arg())
+    .arg(user_agent_arg())
     .arg(
       location_arg()
-        .conflicts_with("file")
+        .conflicts_with("modules")
         .help("Show files used for origin bound APIs like the Web Storage API when running a script with '--location=<HREF>'")
     )
     // TODO(lucacasonato): remove for 2.0
     .arg(no_check_arg().hide(true))
     .arg(no_config_arg())
     .arg(config_arg())
+    .arg(strict_config_arg())
     .arg(import_map_arg())
     .arg(local_npm_arg())
     .arg(
@@ -1301,6 +2164,34 @@ TypeScript compiler cache: Subdirectory containing TS compiler output.",
         .help("UNSTABLE: Outputs the information in JSON format")
         .takes_value(false),
     )
+    .arg(
+      Arg::new("types")
+        .long("types")
+        .help("Show which declaration file provides the types for each module")
+        .long_help(
+          "Show which declaration file provides the types for each module and \
+what selected it: a '@deno-types' or '/// <reference types>' directive, an \
+'X-TypeScript-Types' header, a package.json 'types'/'typings' field or \
+an adjacent declaration file.",
+        )
+        .requires("file")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("journal-summary")
+        .long("journal-summary")
+        .help("Summarize the invocations recorded in the file set by DENO_JOURNAL")
+        .conflicts_with_all(&["modules", "location"])
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("config-entrypoints")
+        .long("config-entrypoints")
+        .help("Show the dependencies of the entry points of the configuration file")
+        .requires("json")
+        .conflicts_with_all(&["modules", "journal-summary"])
+        .takes_value(false),
+    )
 }
 
 fn install_subcommand<'a>() -> Command<'a> {
@@ -1328,6 +2219,13 @@ fn install_subcommand<'a>() -> Command<'a> {
         .short('f')
         .help("Forcefully overwrite existing installation")
         .takes_value(false))
+    .arg(
+      Arg::new("subcommand")
+        .long("subcommand")
+        .help("Subcommand invoked by the executable")
+        .takes_value(true)
+        .require_equals(true)
+        .possible_values(["run", "task"]))
     .about("Install script as an executable")
     .long_about(
       "Installs a script as an executable in the installation root's bin directory.
@@ -1356,7 +2254,17 @@ The installation root is determined, in order of precedence:
   - DENO_INSTALL_ROOT environment variable
   - $HOME/.deno
 
-These must be added to the path manually if required.")
+These must be added to the path manually if required.
+
+To install a task of a configuration file instead of a script, use
+--subcommand=task. The configuration file is followed by the name of the task,
+which is the executable name unless -n/--name is given:
+
+  deno install --subcommand=task -n mytool ./deno.json build
+
+The configuration file is copied to the installation, and the task runs in the
+directory of the original configuration file. Only the --quiet, --log-level and
+--unstable flags apply to tasks.")
 }
 
 fn uninstall_subcommand<'a>() -> Command<'a> {
@@ -1430,6 +2338,18 @@ List available rules:
 
   deno lint --rules
 
+Report unused import map entries, unused lockfile entries and remote packages
+imported at more than one version:
+
+  deno lint --deps
+  deno lint --deps --strict-deps
+
+List the rules that apply to a file with the configuration, or only the rules
+with a tag:
+
+  deno lint --rules --file=src/app.ts
+  deno lint --rules --tags=recommended
+
 Ignore diagnostics on the next line by preceding it with an ignore comment and
 rule name:
 
@@ -1444,6 +2364,24 @@ Ignore linting a file by adding an ignore comment at the top of the file:
 ",
     )
     .arg(Arg::new("rules").long("rules").help("List available rules"))
+    .arg(
+      Arg::new("file")
+        .long("file")
+        .require_equals(true)
+        .takes_value(true)
+        .requires("rules")
+        .help("List the rules that apply to a file with the configuration")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("tags")
+        .long("tags")
+        .require_equals(true)
+        .takes_value(true)
+        .use_value_delimiter(true)
+        .requires("rules")
+        .help("List the rules with one of the tags"),
+    )
     .arg(
       Arg::new("rules-tags")
         .long("rules-tags")
@@ -1473,6 +2411,7 @@ Ignore linting a file by adding an ignore comment at the top of the file:
     )
     .arg(no_config_arg())
     .arg(config_arg())
+    .arg(strict_config_arg())
     .arg(
       Arg::new("ignore")
         .long("ignore")
@@ -1495,6 +2434,29 @@ Ignore linting a file by adding an ignore comment at the top of the file:
         .takes_value(false)
         .conflicts_with("json"),
     )
+    .arg(
+      Arg::new("verbose")
+        .long("verbose")
+        .help("Include the documentation URL of the rule in each diagnostic")
+        .takes_value(false)
+        .conflicts_with("json"),
+    )
+    .arg(
+      Arg::new("deps")
+        .long("deps")
+        .help("UNSTABLE: Report unused and duplicate dependencies")
+        .takes_value(false)
+        .conflicts_with_all(&["rules", "compact", "watch"]),
+    )
+    .arg(
+      Arg::new("strict-deps")
+        .long("strict-deps")
+        .help("Exit with a non-zero code when dependency issues are found")
+        .takes_value(false)
+        .requires("deps"),
+    )
+    .arg(import_map_arg())
+    .arg(lock_arg())
     .arg(
       Arg::new("files")
         .takes_value(true)
@@ -1538,6 +2500,13 @@ fn run_subcommand<'a>() -> Command<'a> {
         .conflicts_with("inspect-brk"),
     )
     .arg(no_clear_screen_arg())
+    .arg(exit_on_idle_timeout_arg())
+    .arg(diagnose_event_loop_arg())
+    .arg(preload_arg())
+    .arg(print_import_order_arg().conflicts_with("watch"))
+    .arg(report_unused_permissions_arg().conflicts_with("watch"))
+    .arg(stack_trace_limit_arg())
+    .arg(full_stack_traces_arg())
     .trailing_var_arg(true)
     .arg(script_arg().required(true))
     .about("Run a JavaScript or TypeScript program")
@@ -1563,7 +2532,54 @@ Grant permission to read allow-listed files from disk:
 
 Specifying the filename '-' to read the file from stdin.
 
-  curl https://deno.land/std/examples/welcome.ts | deno run -",
+  curl https://deno.land/std/examples/welcome.ts | deno run -
+
+Specifying a directory runs the module set as \"exports\" or \"main\" in its
+configuration file, otherwise its mod.ts, main.ts or index.ts.
+
+  deno run ./tools/",
+    )
+}
+
+fn storage_subcommand<'a>() -> Command<'a> {
+  let origin_arg = Arg::new("origin").required(true).help(
+    "The origin or storage key of the data, for example 'https://example.com'",
+  );
+  Command::new("storage")
+    .about("Inspect and clear data persisted by the Web Storage API")
+    .long_about(
+      "Inspect and clear data persisted by the Web Storage API.
+
+Data stored with 'localStorage' is persisted per origin in the Deno cache
+directory. The origin is the value of '--location' when it is set, or the
+configuration file or main module otherwise.
+
+List the origins with persisted data:
+
+  deno storage list
+
+Print the persisted data of an origin as JSON:
+
+  deno storage show https://example.com
+
+Delete the persisted data of an origin:
+
+  deno storage clear https://example.com",
+    )
+    .subcommand_required(true)
+    .arg_required_else_help(true)
+    .subcommand(
+      Command::new("list").about("List the origins with persisted data"),
+    )
+    .subcommand(
+      Command::new("show")
+        .about("Print the persisted data of an origin as JSON")
+        .arg(origin_arg.clone()),
+    )
+    .subcommand(
+      Command::new("clear")
+        .about("Delete the persisted data of an origin")
+        .arg(origin_arg),
     )
 }
 
@@ -1571,6 +2587,7 @@ fn task_subcommand<'a>() -> Command<'a> {
   Command::new("task")
     .trailing_var_arg(true)
     .arg(config_arg())
+    .arg(strict_config_arg())
     .arg(
       Arg::new("cwd")
         .long("cwd")
@@ -1579,6 +2596,29 @@ fn task_subcommand<'a>() -> Command<'a> {
         .takes_value(true)
         .value_hint(ValueHint::DirPath)
     )
+    .arg(
+      Arg::new("list")
+        .long("list")
+        .help("List the tasks defined in the configuration file")
+    )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .requires("list")
+        .help("Output the list of tasks as JSON")
+    )
+    .arg(
+      Arg::new("dry-run")
+        .long("dry-run")
+        .conflicts_with("list")
+        .help("Print the expanded commands of the task without running them")
+    )
+    .arg(
+      Arg::new("show-secrets")
+        .long("show-secrets")
+        .requires("dry-run")
+        .help("Show the values of secret environment variables in the dry run")
+    )
     // Ideally the task name and trailing arguments should be two separate clap
     // arguments, but there is a bug in clap that's preventing us from doing
     // this (https://github.com/clap-rs/clap/issues/1538). Once that's fixed,
@@ -1592,13 +2632,43 @@ fn task_subcommand<'a>() -> Command<'a> {
     .long_about(
       "Run a task defined in the configuration file
 
-  deno task build",
+  deno task build
+
+Tasks can be defined as a command or as an object with a command, a \
+description and a group used when listing the tasks:
+
+  {
+    \"tasks\": {
+      \"dev\": \"deno run --watch main.ts\",
+      \"build\": {
+        \"command\": \"deno run -A build.ts\",
+        \"description\": \"Build the project\",
+        \"group\": \"release\"
+      }
+    }
+  }
+
+List the tasks as JSON:
+
+  deno task --list --json
+
+Print the commands a task would run, with its environment variables and globs
+expanded and the path of each executable, without running anything:
+
+  deno task --dry-run build
+
+The values of environment variables whose names contain TOKEN or SECRET are
+masked in the output unless --show-secrets is passed.",
     )
 }
 
 fn test_subcommand<'a>() -> Command<'a> {
   runtime_args(Command::new("test"), true, true)
     .trailing_var_arg(true)
+    .arg(preload_arg())
+    .arg(report_unused_permissions_arg().conflicts_with("watch"))
+    .arg(stack_trace_limit_arg())
+    .arg(full_stack_traces_arg())
     .arg(
       Arg::new("ignore")
         .long("ignore")
@@ -1641,6 +2711,44 @@ fn test_subcommand<'a>() -> Command<'a> {
           Err(_) => Err("fail-fast should be a non zero integer".to_string()),
         }),
     )
+    .arg(
+      Arg::new("fail-fast-per-file")
+        .long("fail-fast-per-file")
+        .help("Stop running the tests of a module after N failures in it")
+        .long_help("Stop running the tests of a module after N failures in it, while the tests of the other modules keep running. The tests already running finish, and the remaining tests of the module are counted as skipped in the summary.")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("N")
+        .validator(|val: &str| match val.parse::<NonZeroUsize>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err(
+            "fail-fast-per-file should be a non zero integer".to_string(),
+          ),
+        }),
+    )
+    .arg(
+      Arg::new("timeout")
+        .long("timeout")
+        .value_name("DURATION")
+        .help("Fail the tests that run for longer than the given duration")
+        .long_help("Fail the tests that run for longer than the given duration, for example '500ms', '30s' or '5m'. A number without a unit is a number of seconds. The \"timeout\" option of a test, in milliseconds, takes precedence. The worker running a test that times out is terminated, and the remaining tests of its module run in a new worker, which evaluates the module again.")
+        .takes_value(true)
+        .require_equals(true)
+        .validator(parse_duration),
+    )
+    .arg(
+      Arg::new("retries")
+        .long("retries")
+        .value_name("N")
+        .help("Run failing tests up to N more times")
+        .long_help("Run a failing test up to N more times, and only report it as failed if every attempt fails. The tests which pass on a retry are counted as flaky instead of passed. The resources left open by a failed attempt are closed before the next one.")
+        .takes_value(true)
+        .require_equals(true)
+        .validator(|val: &str| match val.parse::<usize>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("retries should be a number".to_string()),
+        }),
+    )
     .arg(
       Arg::new("allow-none")
         .long("allow-none")
@@ -1654,11 +2762,35 @@ fn test_subcommand<'a>() -> Command<'a> {
         .takes_value(true)
         .help("Run tests with this string or pattern in the test name"),
     )
+    .arg(
+      Arg::new("tags")
+        .long("tags")
+        .value_name("TAGS")
+        .require_equals(true)
+        .takes_value(true)
+        .use_value_delimiter(true)
+        .help("Only run the tests with one of these tags"),
+    )
+    .arg(
+      Arg::new("skip-tags")
+        .long("skip-tags")
+        .value_name("TAGS")
+        .require_equals(true)
+        .takes_value(true)
+        .use_value_delimiter(true)
+        .help("Don't run the tests with one of these tags"),
+    )
     .arg(
       Arg::new("shuffle")
         .long("shuffle")
         .value_name("NUMBER")
         .help("(UNSTABLE): Shuffle the order in which the tests are run")
+        .long_help(
+          "(UNSTABLE): Shuffle the order in which the test modules and the \
+tests of each module are run. The seed is printed before the tests run, and \
+the same order is used again when it's given as the NUMBER. In watch mode, the \
+seed is kept for every run.",
+        )
         .min_values(0)
         .max_values(1)
         .require_equals(true)
@@ -1700,6 +2832,33 @@ fn test_subcommand<'a>() -> Command<'a> {
           Err(_) => Err("jobs should be a non zero unsigned integer".to_string()),
         }),
     )
+    .arg(
+      Arg::new("reporter")
+        .long("reporter")
+        .help("Select reporter to use. Default to 'pretty'.")
+        .long_help("Select reporter to use. The 'dot' reporter prints a single character per test and the 'compact' reporter prints a single line per test module. Both print the details of failures after all tests have run and do not show the output of tests. The 'junit' reporter prints a JUnit XML report to stdout once all tests have run, or writes it to the path given by --junit-path. The 'tap' reporter prints the results in the TAP version 14 format, with the steps as subtests and the output of tests as comments. A path or URL of a module selects a custom reporter: the object the module exports by default gets the events of the run in its 'plan', 'start', 'result' and 'summary' callbacks. It runs in a separate runtime with the permissions given to the tests, and the run fails if it throws.")
+        .takes_value(true)
+        .value_name("REPORTER")
+        .validator(parse_test_reporter),
+    )
+    .arg(
+      Arg::new("junit-path")
+        .long("junit-path")
+        .help("Write a JUnit XML report to the given path")
+        .long_help("Write a JUnit XML report to the given path once all tests have run. The console shows the output of the reporter selected with --reporter, or of the 'pretty' reporter when it's 'junit'.")
+        .takes_value(true)
+        .value_name("PATH")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(report_memory_arg())
+    .arg(fail_on_empty_files_arg())
+    .arg(
+      Arg::new("update-golden")
+        .long("update-golden")
+        .help("Rewrite the golden files compared by TestContext.assertGoldenFile() instead of failing on a mismatch")
+        .long_help("Rewrite the golden files compared by TestContext.assertGoldenFile() with the actual contents instead of failing on a mismatch, creating any missing files and directories. Writing the files requires write permission. Setting the UPDATE_GOLDEN environment variable to 1 has the same effect.")
+        .takes_value(false),
+    )
     .arg(
       Arg::new("files")
         .help("List of file names to run")
@@ -1714,6 +2873,14 @@ fn test_subcommand<'a>() -> Command<'a> {
         .conflicts_with("coverage"),
     )
     .arg(no_clear_screen_arg())
+    .arg(
+      Arg::new("watch-all-on-change")
+        .requires("watch")
+        .long("watch-all-on-change")
+        .help("Re-run all tests on a change in watch mode")
+        .long_help("Re-run all tests on a change in watch mode. By default only the tests affected by the changed files run again, which are found from the modules each test uses.")
+        .takes_value(false),
+    )
     .arg(script_arg().last(true))
     .about("Run tests")
     .long_about(
@@ -1727,7 +2894,12 @@ report results to standard output:
 Directory arguments are expanded to all contained files matching the glob
 {*_,*.,}test.{js,mjs,ts,mts,jsx,tsx}:
 
-  deno test src/",
+  deno test src/
+
+Arguments after '--' are not test files, they are passed to the tests as
+'Deno.args':
+
+  deno test src/ -- --url=https://staging.example.com",
     )
 }
 
@@ -1757,7 +2929,17 @@ and is used to replace the current executable.
 If you want to not replace the current Deno executable but instead download an
 update to a different location, use the --output flag
 
-  deno upgrade --output $HOME/my_deno",
+  deno upgrade --output $HOME/my_deno
+
+Release candidates of upcoming versions can be installed with the --rc flag:
+
+  deno upgrade --rc
+
+To list the versions available in a release channel, newest first, without
+upgrading, use the --list flag:
+
+  deno upgrade --list
+  deno upgrade --list --channel=rc --page=2",
     )
     .arg(
       Arg::new("version")
@@ -1788,6 +2970,41 @@ update to a different location, use the --output flag
         .long("canary")
         .help("Upgrade to canary builds"),
     )
+    .arg(
+      Arg::new("rc")
+        .long("rc")
+        .help("Upgrade to the latest release candidate")
+        .conflicts_with("canary"),
+    )
+    .arg(
+      Arg::new("list")
+        .long("list")
+        .help("List the versions available in a release channel")
+        .conflicts_with_all(&["version", "output", "dry-run", "force"]),
+    )
+    .arg(
+      Arg::new("channel")
+        .long("channel")
+        .help("The release channel to list versions of")
+        .takes_value(true)
+        .require_equals(true)
+        .possible_values(["stable", "rc", "canary"])
+        .requires("list")
+        .conflicts_with_all(&["canary", "rc"]),
+    )
+    .arg(
+      Arg::new("page")
+        .long("page")
+        .help("The page of versions to list")
+        .takes_value(true)
+        .require_equals(true)
+        .value_name("N")
+        .requires("list")
+        .validator(|val: &str| match val.parse::<NonZeroUsize>() {
+          Ok(_) => Ok(()),
+          Err(_) => Err("page should be a non zero integer".to_string()),
+        }),
+    )
     .arg(ca_file_arg())
 }
 
@@ -1806,14 +3023,24 @@ maps remote specifiers to the downloaded files.
 
 Remote modules and multiple modules may also be specified:
 
-  deno vendor main.ts test.deps.ts https://deno.land/std/path/mod.ts",
+  deno vendor main.ts test.deps.ts https://deno.land/std/path/mod.ts
+
+A manifest of the vendored files' hashes is written to the output directory.
+Use --check to verify the vendored files have not been modified:
+
+  deno vendor --check
+
+Use --import-map-only to only write an import map that pins the remote
+modules to the URLs they resolve to, keeping the modules in the cache:
+
+  deno vendor --import-map-only=deps_map.json main.ts",
     )
     .arg(
       Arg::new("specifiers")
         .takes_value(true)
         .multiple_values(true)
         .multiple_occurrences(true)
-        .required(true),
+        .required_unless_present("check"),
     )
     .arg(
       Arg::new("output")
@@ -1831,12 +3058,49 @@ Remote modules and multiple modules may also be specified:
         )
         .takes_value(false),
     )
+    .arg(
+      Arg::new("check")
+        .long("check")
+        .help("Verify the output directory against its manifest of file hashes")
+        .conflicts_with_all(&["specifiers", "force"])
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("json")
+        .long("json")
+        .help("Output a summary of the vendored files in JSON format")
+        .conflicts_with("check")
+        .takes_value(false),
+    )
+    .arg(
+      Arg::new("import-map-only")
+        .long("import-map-only")
+        .help("Only write an import map pinning remote modules to their resolved URLs")
+        .long_help(
+          "Only write an import map that maps the remote and bare specifiers \
+of the module graph to the URLs they resolve to after redirects, without \
+copying any files. Defaults to import_map.json. Entries after the \
+\"deno-vendor:manual\" key are kept when the import map is updated.",
+        )
+        .takes_value(true)
+        .min_values(0)
+        .max_values(1)
+        .require_equals(true)
+        .value_name("PATH")
+        .value_hint(ValueHint::FilePath)
+        .conflicts_with_all(&["output", "force", "check", "json"]),
+    )
     .arg(no_config_arg())
     .arg(config_arg())
+    .arg(strict_config_arg())
     .arg(import_map_arg())
     .arg(lock_arg())
     .arg(reload_arg())
     .arg(ca_file_arg())
+    .arg(tls_client_cert_arg())
+    .arg(tls_client_key_arg())
+    .arg(tls_client_cert_hosts_arg())
+    .arg(user_agent_arg())
 }
 
 fn compile_args(app: Command) -> Command {
@@ -1847,6 +3111,7 @@ fn compile_args(app: Command) -> Command {
     .arg(local_npm_arg())
     .arg(no_config_arg())
     .arg(config_arg())
+    .arg(strict_config_arg())
     .arg(no_check_arg())
     .arg(check_arg())
     .arg(reload_arg())
@@ -1854,6 +3119,10 @@ fn compile_args(app: Command) -> Command {
     .arg(lock_write_arg())
     .arg(no_lock_arg())
     .arg(ca_file_arg())
+    .arg(tls_client_cert_arg())
+    .arg(tls_client_key_arg())
+    .arg(tls_client_cert_hosts_arg())
+    .arg(user_agent_arg())
 }
 
 fn compile_args_without_check_args(app: Command) -> Command {
@@ -1863,12 +3132,17 @@ fn compile_args_without_check_args(app: Command) -> Command {
     .arg(no_npm_arg())
     .arg(local_npm_arg())
     .arg(config_arg())
+    .arg(strict_config_arg())
     .arg(no_config_arg())
     .arg(reload_arg())
     .arg(lock_arg())
     .arg(lock_write_arg())
     .arg(no_lock_arg())
     .arg(ca_file_arg())
+    .arg(tls_client_cert_arg())
+    .arg(tls_client_key_arg())
+    .arg(tls_client_cert_hosts_arg())
+    .arg(user_agent_arg())
 }
 
 fn permission_args(app: Command) -> Command {
@@ -1955,6 +3229,15 @@ fn permission_args(app: Command) -> Command {
         .help("Allow loading dynamic libraries")
         .value_hint(ValueHint::AnyPath),
     )
+    .arg(
+      Arg::new("allow-scheme")
+        .long("allow-scheme")
+        .min_values(0)
+        .takes_value(true)
+        .use_value_delimiter(true)
+        .require_equals(true)
+        .help("Allow importing modules from custom schemes"),
+    )
     .arg(
       Arg::new("allow-hrtime")
         .long("allow-hrtime")
@@ -1974,6 +3257,11 @@ fn permission_args(app: Command) -> Command {
         .long("no-prompt")
         .help("Always throw if required permission wasn't passed"),
     )
+    .arg(
+      Arg::new("no-config-permissions")
+        .long("no-config-permissions")
+        .help("Ignore the permissions of the configuration file"),
+    )
 }
 
 fn runtime_args(
@@ -1997,6 +3285,7 @@ fn runtime_args(
     .arg(location_arg())
     .arg(v8_flags_arg())
     .arg(seed_arg())
+    .arg(storage_quota_arg())
     .arg(enable_testing_features_arg())
 }
 
@@ -2098,25 +3387,97 @@ fn ca_file_arg<'a>() -> Arg<'a> {
     .value_hint(ValueHint::FilePath)
 }
 
-fn cached_only_arg<'a>() -> Arg<'a> {
-  Arg::new("cached-only")
-    .long("cached-only")
-    .help("Require that remote dependencies are already cached")
+fn tls_client_cert_arg<'a>() -> Arg<'a> {
+  Arg::new("tls-client-cert")
+    .long("tls-client-cert")
+    .value_name("FILE")
+    .help("Load the TLS client certificate presented to servers requiring mutual TLS from PEM encoded file")
+    .takes_value(true)
+    .requires("tls-client-key")
+    .value_hint(ValueHint::FilePath)
 }
 
-fn location_arg<'a>() -> Arg<'a> {
-  Arg::new("location")
-    .long("location")
+fn tls_client_key_arg<'a>() -> Arg<'a> {
+  Arg::new("tls-client-key")
+    .long("tls-client-key")
+    .value_name("FILE")
+    .help("Load the private key of the TLS client certificate from PEM encoded file")
     .takes_value(true)
-    .value_name("HREF")
-    .validator(|href| {
-      let url = Url::parse(href);
-      if url.is_err() {
-        return Err("Failed to parse URL".to_string());
-      }
-      let mut url = url.unwrap();
-      if !["http", "https"].contains(&url.scheme()) {
-        return Err("Expected protocol \"http\" or \"https\"".to_string());
+    .requires("tls-client-cert")
+    .value_hint(ValueHint::FilePath)
+}
+
+fn tls_client_cert_hosts_arg<'a>() -> Arg<'a> {
+  Arg::new("tls-client-cert-hosts")
+    .long("tls-client-cert-hosts")
+    .takes_value(true)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .value_name("HOSTNAMES")
+    .help("Only present the TLS client certificate to these hosts")
+    .requires("tls-client-cert")
+    .validator(flags_allow_net::validator)
+}
+
+fn user_agent_arg<'a>() -> Arg<'a> {
+  Arg::new("user-agent")
+    .long("user-agent")
+    .value_name("USER_AGENT")
+    .help(
+      "Override the user agent used for fetching modules and by the runtime",
+    )
+    .long_help(
+      "Override the user agent used when downloading remote modules and npm \
+packages, and by fetch, WebSocket and navigator.userAgent. The Deno version \
+is appended in parentheses, for example \"my-tool/1.2 (Deno/x.y.z)\".",
+    )
+    .takes_value(true)
+    .validator(user_agent_arg_validate)
+}
+
+fn user_agent_arg_validate(user_agent: &str) -> Result<(), String> {
+  validate_user_agent(user_agent).map_err(|err| err.to_string())
+}
+
+/// Validates that a user agent override is non-empty and can be sent as an
+/// HTTP header value.
+pub fn validate_user_agent(user_agent: &str) -> Result<(), AnyError> {
+  if user_agent.trim().is_empty() {
+    bail!("The user agent must not be empty.");
+  }
+  if HeaderValue::from_str(user_agent).is_err() {
+    bail!(
+      "Invalid user agent {:?}. It contains characters that are not allowed in an HTTP header.",
+      user_agent
+    );
+  }
+  Ok(())
+}
+
+fn cached_only_arg<'a>() -> Arg<'a> {
+  Arg::new("cached-only")
+    .long("cached-only")
+    .help("Require that remote dependencies are already cached")
+    .long_help(
+      "Require that remote dependencies are already cached. This applies to \
+all module loading, including dynamic imports and workers, but not to the \
+network access of the program itself, like the fetch API.",
+    )
+}
+
+fn location_arg<'a>() -> Arg<'a> {
+  Arg::new("location")
+    .long("location")
+    .takes_value(true)
+    .value_name("HREF")
+    .validator(|href| {
+      let url = Url::parse(href);
+      if url.is_err() {
+        return Err("Failed to parse URL".to_string());
+      }
+      let mut url = url.unwrap();
+      if !["http", "https"].contains(&url.scheme()) {
+        return Err("Expected protocol \"http\" or \"https\"".to_string());
       }
       url.set_username("").unwrap();
       url.set_password(None).unwrap();
@@ -2156,6 +3517,177 @@ fn seed_arg<'a>() -> Arg<'a> {
     })
 }
 
+fn storage_quota_arg<'a>() -> Arg<'a> {
+  Arg::new("storage-quota")
+    .long("storage-quota")
+    .value_name("BYTES")
+    .help("Set the maximum size of the Web Storage API storage in bytes")
+    .takes_value(true)
+    .require_equals(true)
+    .validator(|val| match val.parse::<u64>() {
+      Ok(_) => Ok(()),
+      Err(_) => Err("Storage quota should be a number of bytes".to_string()),
+    })
+}
+
+fn exit_on_idle_timeout_arg<'a>() -> Arg<'a> {
+  Arg::new("exit-on-idle-timeout")
+    .long("exit-on-idle-timeout")
+    .value_name("DURATION")
+    .help("Exit when no pending op completes for the given duration")
+    .long_help(
+      "Exit when no pending op completes for the given duration, for example \
+'500ms', '30s' or '5m'. A number without a unit is a number of seconds. The \
+pending ops and open resources keeping the process alive are printed before \
+exiting with code 124.",
+    )
+    .takes_value(true)
+    .require_equals(true)
+    .validator(parse_duration)
+}
+
+fn diagnose_event_loop_arg<'a>() -> Arg<'a> {
+  Arg::new("diagnose-event-loop")
+    .long("diagnose-event-loop")
+    .help("Sample the event loop latency and print a histogram on exit")
+    .long_help(
+      "Sample how late the event loop picks up work, which is how late \
+timers fire, and how long synchronous JS runs without yielding to the event \
+loop. Histograms of both are printed when the program exits, and on Unix \
+when the process receives SIGUSR2. The samples are taken every 10ms.",
+    )
+}
+
+fn print_import_order_arg<'a>() -> Arg<'a> {
+  Arg::new("print-import-order")
+    .long("print-import-order")
+    .value_name("FORMAT")
+    .help(
+      "Print the order in which modules are evaluated after the program finishes",
+    )
+    .long_help(
+      "Print the order in which modules are evaluated after the program \
+finishes. A module is evaluated after the modules it imports statically. Each \
+module is printed with its index, the module that first imported it and whether \
+it was imported statically or dynamically. JSON modules are not included. The FORMAT is either 'text' \
+(default) or 'json'. The order is printed to stderr.",
+    )
+    .takes_value(true)
+    .min_values(0)
+    .require_equals(true)
+    .possible_values(["text", "json"])
+}
+
+fn report_unused_permissions_arg<'a>() -> Arg<'a> {
+  Arg::new("report-unused-permissions")
+    .long("report-unused-permissions")
+    .value_name("FORMAT")
+    .help("Report the granted permissions that were never used on exit")
+    .long_help(
+      "Report the permissions that were granted, for example with --allow-env, \
+but never checked once the program finishes. The FORMAT is either 'text' \
+(default) or 'json'. The report is printed to stderr, and isn't printed when \
+the program exits with Deno.exit().",
+    )
+    .takes_value(true)
+    .min_values(0)
+    .require_equals(true)
+    .possible_values(["text", "json"])
+}
+
+fn preload_arg<'a>() -> Arg<'a> {
+  Arg::new("preload")
+    .long("preload")
+    .takes_value(true)
+    .multiple_occurrences(true)
+    .require_equals(true)
+    .value_name("MODULE")
+    .help("Evaluate a module before the main module")
+    .long_help(
+      "Evaluate a module before the main module. The specifier is resolved \
+like an import from the current directory, so import maps apply. Can be \
+repeated; the modules are evaluated in the given order and each one, \
+including its top-level await, completes before the next one starts.",
+    )
+    .value_hint(ValueHint::FilePath)
+}
+
+fn stack_trace_limit_arg<'a>() -> Arg<'a> {
+  Arg::new("stack-trace-limit")
+    .long("stack-trace-limit")
+    .value_name("N")
+    .help("Show at most N frames in the stack traces of errors")
+    .long_help(
+      "Show at most N frames in the stack traces of errors. This also sets \
+the number of frames V8 captures, which is 10 by default, so that deeper \
+stacks can be shown.",
+    )
+    .takes_value(true)
+    .require_equals(true)
+    .validator(|val: &str| match val.parse::<usize>() {
+      Ok(_) => Ok(()),
+      Err(_) => Err("stack-trace-limit should be a number".to_string()),
+    })
+}
+
+fn full_stack_traces_arg<'a>() -> Arg<'a> {
+  Arg::new("full-stack-traces")
+    .long("full-stack-traces")
+    .help("Keep the frames of internal code in the stack traces of errors")
+    .takes_value(false)
+}
+
+/// Parses a duration such as `500ms`, `30s`, `5m` or `1h`. A number without
+/// a unit is a number of seconds.
+fn parse_duration(val: &str) -> Result<Duration, String> {
+  let (number, unit) = match val.find(|c: char| !c.is_ascii_digit()) {
+    Some(index) => val.split_at(index),
+    None => (val, "s"),
+  };
+  let number = number
+    .parse::<u64>()
+    .map_err(|_| format!("Invalid duration: {val}"))?;
+  match unit {
+    "ms" => Ok(Duration::from_millis(number)),
+    "s" => Ok(Duration::from_secs(number)),
+    "m" => Ok(Duration::from_secs(number * 60)),
+    "h" => Ok(Duration::from_secs(number * 60 * 60)),
+    _ => Err(format!(
+      "Invalid duration unit '{unit}', expected 'ms', 's', 'm' or 'h'"
+    )),
+  }
+}
+
+/// Parses a whole percentage, with or without the percent sign.
+fn parse_percentage(val: &str) -> Result<u32, String> {
+  val
+    .strip_suffix('%')
+    .unwrap_or(val)
+    .parse::<u32>()
+    .map_err(|_| format!("Invalid percentage: {val}"))
+}
+
+/// Parses the name of a built-in test reporter, or the path or URL of a
+/// module exporting a custom one.
+fn parse_test_reporter(val: &str) -> Result<TestReporterConfig, String> {
+  match val {
+    "pretty" => Ok(TestReporterConfig::Pretty),
+    "dot" => Ok(TestReporterConfig::Dot),
+    "compact" => Ok(TestReporterConfig::Compact),
+    "junit" => Ok(TestReporterConfig::Junit),
+    "tap" => Ok(TestReporterConfig::Tap),
+    _ if val.contains('/')
+      || val.contains('\\')
+      || Path::new(val).extension().is_some() =>
+    {
+      Ok(TestReporterConfig::Module(val.to_string()))
+    }
+    _ => Err(format!(
+      "Invalid reporter '{val}', expected 'pretty', 'dot', 'compact', 'junit', 'tap' or the path of a module"
+    )),
+  }
+}
+
 fn watch_arg<'a>(takes_files: bool) -> Arg<'a> {
   let arg = Arg::new("watch")
     .long("watch")
@@ -2223,6 +3755,36 @@ will be included.",
     )
 }
 
+fn report_memory_arg<'a>() -> Arg<'a> {
+  Arg::new("report-memory")
+    .long("report-memory")
+    .takes_value(true)
+    .require_equals(true)
+    .min_values(0)
+    .value_name("INTERVAL_MS")
+    .help("Report the peak memory usage in the summary")
+    .long_help(
+      "Report the peak resident set size of the process and the peak V8 heap \
+usage in the summary. The resident set size is sampled every 100 \
+milliseconds unless another interval is provided.",
+    )
+    .validator(|val: &str| match val.parse::<u64>() {
+      Ok(interval) if interval > 0 => Ok(()),
+      _ => Err("interval should be a positive number of milliseconds"),
+    })
+}
+
+fn fail_on_empty_files_arg<'a>() -> Arg<'a> {
+  Arg::new("fail-on-empty-files")
+    .long("fail-on-empty-files")
+    .help("Fail if a file doesn't register anything to run")
+    .long_help(
+      "Fail if a file doesn't register anything to run. A warning lists these \
+files either way, apart from the ones where the filter didn't match anything.",
+    )
+    .takes_value(false)
+}
+
 fn script_arg<'a>() -> Arg<'a> {
   Arg::new("script_arg")
     .multiple_values(true)
@@ -2239,6 +3801,33 @@ fn script_arg<'a>() -> Arg<'a> {
     .value_hint(ValueHint::FilePath)
 }
 
+fn import_list_arg<'a>() -> Arg<'a> {
+  Arg::new("import-list")
+    .long("import-list")
+    .value_name("FILE")
+    .help("Read the modules from a file with one specifier per line, or from stdin if '-'")
+    .takes_value(true)
+    .require_equals(true)
+    .value_hint(ValueHint::FilePath)
+}
+
+fn emit_dir_arg<'a>() -> Arg<'a> {
+  Arg::new("emit-dir")
+    .long("emit-dir")
+    .value_name("DIR")
+    .help("Write the transpiled JavaScript of the local modules to a directory")
+    .takes_value(true)
+    .require_equals(true)
+    .value_hint(ValueHint::DirPath)
+}
+
+fn emit_remote_arg<'a>() -> Arg<'a> {
+  Arg::new("emit-remote")
+    .long("emit-remote")
+    .requires("emit-dir")
+    .help("Include remote modules under deps/ in the emit directory")
+}
+
 fn lock_arg<'a>() -> Arg<'a> {
   Arg::new("lock")
     .long("lock")
@@ -2286,6 +3875,16 @@ fn config_arg<'a>() -> Arg<'a> {
     .value_hint(ValueHint::FilePath)
 }
 
+fn strict_config_arg<'a>() -> Arg<'a> {
+  Arg::new("strict-config")
+    .long("strict-config")
+    .help("Error on unknown keys in the configuration file")
+    .long_help(
+      "Error on unknown keys in the configuration file instead of warning \
+about them. Keys with a value of the wrong type are always an error.",
+    )
+}
+
 fn no_config_arg<'a>() -> Arg<'a> {
   Arg::new("no-config")
     .long("no-config")
@@ -2369,6 +3968,20 @@ fn bench_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     files: FileFlags { include, ignore },
     filter,
     json,
+    report_memory: report_memory_arg_parse(matches),
+    profile_loop: profile_loop_arg_parse(matches),
+    fail_on_empty_files: matches.is_present("fail-on-empty-files"),
+    save_baseline: matches.value_of("save-baseline").map(PathBuf::from),
+    baseline: matches.value_of("baseline").map(PathBuf::from),
+    threshold: matches
+      .value_of("threshold")
+      .map(|val| parse_percentage(val).unwrap()),
+    strict: matches.is_present("strict"),
+    watch_run_all: matches.is_present("watch-run-all"),
+    jobs: matches
+      .value_of("jobs")
+      .map(|val| val.parse::<NonZeroUsize>().unwrap()),
+    warmup_only_parallel: matches.is_present("warmup-only-parallel"),
   });
 }
 
@@ -2388,9 +4001,17 @@ fn bundle_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
   watch_arg_parse(flags, matches, false);
 
+  let sourcemap = match matches.value_of("sourcemap") {
+    Some("inline") => Some(SourceMapMode::Inline),
+    Some("external") => Some(SourceMapMode::External),
+    Some("none") => Some(SourceMapMode::None),
+    _ => None,
+  };
+
   flags.subcommand = DenoSubcommand::Bundle(BundleFlags {
     source_file,
     out_file,
+    sourcemap,
   });
 }
 
@@ -2398,10 +4019,31 @@ fn cache_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   compile_args_parse(flags, matches);
   let files = matches
     .values_of("file")
-    .unwrap()
-    .map(String::from)
-    .collect();
-  flags.subcommand = DenoSubcommand::Cache(CacheFlags { files });
+    .map(|values| values.map(String::from).collect())
+    .unwrap_or_default();
+  let json = matches.is_present("json");
+  json_output_quiet_parse(flags, json);
+  let import_list = matches.value_of("import-list").map(String::from);
+  let lockfile_only = matches.is_present("lockfile-only");
+  flags.subcommand = DenoSubcommand::Cache(CacheFlags {
+    files,
+    json,
+    import_list,
+    lockfile_only,
+    emit_dir: matches.value_of("emit-dir").map(PathBuf::from),
+    emit_remote: matches.is_present("emit-remote"),
+    export_dir: matches.value_of("export").map(PathBuf::from),
+    import_dir: matches.value_of("import").map(PathBuf::from),
+    overwrite: matches.is_present("overwrite"),
+  });
+}
+
+/// Silences logging and progress bars when the output is JSON, so that
+/// stdout only contains the final summary.
+fn json_output_quiet_parse(flags: &mut Flags, json: bool) {
+  if json && flags.log_level.is_none() {
+    flags.log_level = Some(Level::Error);
+  }
 }
 
 fn check_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -2415,7 +4057,35 @@ fn check_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if matches.is_present("all") || matches.is_present("remote") {
     flags.type_check_mode = TypeCheckMode::All;
   }
-  flags.subcommand = DenoSubcommand::Check(CheckFlags { files });
+  watch_arg_parse(flags, matches, false);
+  flags.subcommand = DenoSubcommand::Check(CheckFlags {
+    files,
+    doc: matches.is_present("doc"),
+    emit_dir: matches.value_of("emit-dir").map(PathBuf::from),
+    emit_remote: matches.is_present("emit-remote"),
+    deprecations: matches.is_present("deprecations"),
+    json: matches.is_present("json"),
+  });
+}
+
+fn ci_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  flags.type_check_mode = TypeCheckMode::Local;
+  compile_args_without_no_check_parse(flags, matches);
+  let include = match matches.values_of("files") {
+    Some(f) => f.map(PathBuf::from).collect(),
+    None => vec![],
+  };
+  let ignore = match matches.values_of("ignore") {
+    Some(f) => f.map(PathBuf::from).collect(),
+    None => vec![],
+  };
+  flags.subcommand = DenoSubcommand::Ci(CiFlags {
+    files: FileFlags { include, ignore },
+    no_fmt: matches.is_present("no-fmt"),
+    no_lint: matches.is_present("no-lint"),
+    no_check: matches.is_present("no-check"),
+    json: matches.is_present("json"),
+  });
 }
 
 fn compile_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -2432,12 +4102,21 @@ fn compile_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   let source_file = script[0].to_string();
   let output = matches.value_of("output").map(PathBuf::from);
   let target = matches.value_of("target").map(String::from);
+  let no_metadata_flag = matches.is_present("no-metadata-flag");
+  let strip = matches.is_present("strip");
+  let deny_warnings = matches.is_present("deny-warnings");
+  exit_on_idle_timeout_arg_parse(flags, matches);
+  preload_arg_parse(flags, matches);
+  report_unused_permissions_arg_parse(flags, matches);
 
   flags.subcommand = DenoSubcommand::Compile(CompileFlags {
     source_file,
     output,
     args,
     target,
+    no_metadata_flag,
+    strip,
+    deny_warnings,
   });
 }
 
@@ -2517,12 +4196,16 @@ fn doc_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     .unwrap_or_default();
   let private = matches.is_present("private");
   let json = matches.is_present("json");
+  let json_schema = matches.is_present("json-schema");
   let filter = matches.value_of("filter").map(String::from);
+  let show_references = matches.is_present("show-references");
   flags.subcommand = DenoSubcommand::Doc(DocFlags {
     source_file,
     json,
+    json_schema,
     filter,
     private,
+    show_references,
   });
 }
 
@@ -2535,6 +4218,7 @@ fn eval_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   flags.allow_sys = Some(vec![]);
   flags.allow_write = Some(vec![]);
   flags.allow_ffi = Some(vec![]);
+  flags.allow_scheme = Some(vec![]);
   flags.allow_hrtime = true;
   // TODO(@satyarohith): remove this flag in 2.0.
   let as_typescript = matches.is_present("ts");
@@ -2571,7 +4255,8 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     Some(f) => f.map(PathBuf::from).collect(),
     None => vec![],
   };
-  let ext = matches.value_of("ext").unwrap().to_string();
+  let ext = matches.value_of("ext").map(String::from);
+  let stdin_filepath = matches.value_of("stdin-filepath").map(PathBuf::from);
 
   let use_tabs = optional_bool_parse(matches, "use-tabs");
   let line_width = if matches.is_present("line-width") {
@@ -2596,7 +4281,11 @@ fn fmt_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
   flags.subcommand = DenoSubcommand::Fmt(FmtFlags {
     check: matches.is_present("check"),
+    compact: matches.is_present("compact"),
+    diff: matches.is_present("diff"),
+    no_cache: matches.is_present("no-cache"),
     ext,
+    stdin_filepath,
     files: FileFlags { include, ignore },
     use_tabs,
     line_width,
@@ -2616,8 +4305,32 @@ fn optional_bool_parse(matches: &ArgMatches, name: &str) -> Option<bool> {
 }
 
 fn init_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  let fmt_use_tabs = matches.is_present("fmt-use-tabs");
+  let fmt_line_width = matches
+    .value_of("fmt-line-width")
+    .map(|width| width.parse().unwrap());
+  let fmt_single_quote = matches.is_present("fmt-single-quote");
+  let jsx = match matches.value_of("jsx") {
+    Some("none") => Some(InitJsxFramework::None),
+    Some("react") => Some(InitJsxFramework::React),
+    Some("preact") => Some(InitJsxFramework::Preact),
+    _ => None,
+  };
   flags.subcommand = DenoSubcommand::Init(InitFlags {
     dir: matches.value_of("dir").map(|f| f.to_string()),
+    lib: matches.is_present("lib"),
+    name: matches.value_of("name").map(|f| f.to_string()),
+    yes: matches.is_present("yes"),
+    no_tests: matches.is_present("no-tests"),
+    fmt: matches.is_present("fmt")
+      || fmt_use_tabs
+      || fmt_line_width.is_some()
+      || fmt_single_quote,
+    fmt_use_tabs,
+    fmt_line_width,
+    fmt_single_quote,
+    lint: matches.is_present("lint"),
+    jsx,
   });
 }
 
@@ -2626,12 +4339,20 @@ fn info_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   config_args_parse(flags, matches);
   import_map_arg_parse(flags, matches);
   location_arg_parse(flags, matches);
+  preload_arg_parse(flags, matches);
   ca_file_arg_parse(flags, matches);
+  tls_client_cert_arg_parse(flags, matches);
+  user_agent_arg_parse(flags, matches);
   local_npm_args_parse(flags, matches);
   let json = matches.is_present("json");
+  let types = matches.is_present("types");
   flags.subcommand = DenoSubcommand::Info(InfoFlags {
     file: matches.value_of("file").map(|f| f.to_string()),
     json,
+    types,
+    import_list: matches.value_of("import-list").map(String::from),
+    journal_summary: matches.is_present("journal-summary"),
+    config_entrypoints: matches.is_present("config-entrypoints"),
   });
 }
 
@@ -2650,6 +4371,10 @@ fn install_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
   let module_url = cmd[0].to_string();
   let args = cmd[1..].to_vec();
+  let subcommand = match matches.value_of("subcommand") {
+    Some("task") => InstallSubcommand::Task,
+    _ => InstallSubcommand::Run,
+  };
 
   flags.subcommand = DenoSubcommand::Install(InstallFlags {
     name,
@@ -2657,6 +4382,7 @@ fn install_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     args,
     root,
     force,
+    subcommand,
   });
 }
 
@@ -2673,6 +4399,8 @@ fn lsp_parse(flags: &mut Flags, _matches: &clap::ArgMatches) {
 
 fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   config_args_parse(flags, matches);
+  import_map_arg_parse(flags, matches);
+  lock_arg_parse(flags, matches);
   watch_arg_parse(flags, matches, false);
   let files = match matches.values_of("files") {
     Some(f) => f.map(PathBuf::from).collect(),
@@ -2694,9 +4422,16 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   let maybe_rules_exclude = matches
     .values_of("rules-exclude")
     .map(|f| f.map(String::from).collect());
+  let maybe_rules_file = matches.value_of("file").map(PathBuf::from);
+  let maybe_rules_filter_tags = matches
+    .values_of("tags")
+    .map(|f| f.map(String::from).collect());
 
   let json = matches.is_present("json");
   let compact = matches.is_present("compact");
+  let verbose = matches.is_present("verbose");
+  let deps = matches.is_present("deps");
+  let strict_deps = matches.is_present("strict-deps");
   flags.subcommand = DenoSubcommand::Lint(LintFlags {
     files: FileFlags {
       include: files,
@@ -2706,9 +4441,13 @@ fn lint_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     maybe_rules_tags,
     maybe_rules_include,
     maybe_rules_exclude,
-
+    maybe_rules_file,
+    maybe_rules_filter_tags,
     json,
     compact,
+    verbose,
+    deps,
+    strict_deps,
   });
 }
 
@@ -2746,9 +4485,25 @@ fn run_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   }
 
   watch_arg_parse(flags, matches, true);
+  exit_on_idle_timeout_arg_parse(flags, matches);
+  flags.diagnose_event_loop = matches.is_present("diagnose-event-loop");
+  preload_arg_parse(flags, matches);
+  print_import_order_arg_parse(flags, matches);
+  report_unused_permissions_arg_parse(flags, matches);
+  stack_trace_args_parse(flags, matches);
   flags.subcommand = DenoSubcommand::Run(RunFlags { script });
 }
 
+fn storage_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  let origin = |m: &clap::ArgMatches| m.value_of("origin").unwrap().to_string();
+  let storage_flags = match matches.subcommand() {
+    Some(("show", m)) => StorageFlags::Show { origin: origin(m) },
+    Some(("clear", m)) => StorageFlags::Clear { origin: origin(m) },
+    _ => StorageFlags::List,
+  };
+  flags.subcommand = DenoSubcommand::Storage(storage_flags);
+}
+
 fn task_parse(
   flags: &mut Flags,
   matches: &clap::ArgMatches,
@@ -2759,10 +4514,15 @@ fn task_parse(
   } else {
     ConfigFlag::Discover
   };
+  flags.strict_config = matches.is_present("strict-config");
 
   let mut task_flags = TaskFlags {
     cwd: None,
     task: String::new(),
+    list: matches.is_present("list"),
+    json: matches.is_present("json"),
+    dry_run: matches.is_present("dry-run"),
+    show_secrets: matches.is_present("show-secrets"),
   };
 
   if let Some(cwd) = matches.value_of("cwd") {
@@ -2788,10 +4548,42 @@ fn task_parse(
           flags.config_flag = ConfigFlag::Disabled;
           index += 1;
         }
+        "--strict-config" => {
+          flags.strict_config = true;
+          index += 1;
+        }
         "-q" | "--quiet" => {
           flags.log_level = Some(Level::Error);
           index += 1;
         }
+        "--color=auto" => {
+          flags.color = ColorMode::Auto;
+          index += 1;
+        }
+        "--color=always" => {
+          flags.color = ColorMode::Always;
+          index += 1;
+        }
+        "--color=never" => {
+          flags.color = ColorMode::Never;
+          index += 1;
+        }
+        "--list" => {
+          task_flags.list = true;
+          index += 1;
+        }
+        "--json" => {
+          task_flags.json = true;
+          index += 1;
+        }
+        "--dry-run" => {
+          task_flags.dry_run = true;
+          index += 1;
+        }
+        "--show-secrets" => {
+          task_flags.show_secrets = true;
+          index += 1;
+        }
         _ => break,
       }
     }
@@ -2817,6 +4609,9 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   // NOTE: `deno test` always uses `--no-prompt`, tests shouldn't ever do
   // interactive prompts, unless done by user code
   flags.no_prompt = true;
+  preload_arg_parse(flags, matches);
+  report_unused_permissions_arg_parse(flags, matches);
+  stack_trace_args_parse(flags, matches);
 
   let ignore = match matches.values_of("ignore") {
     Some(f) => f.map(PathBuf::from).collect(),
@@ -2897,18 +4692,47 @@ fn test_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     Vec::new()
   };
 
+  let reporter = matches
+    .value_of("reporter")
+    .map(|val| parse_test_reporter(val).unwrap())
+    .unwrap_or(TestReporterConfig::Pretty);
+
   flags.coverage_dir = matches.value_of("coverage").map(String::from);
   watch_arg_parse(flags, matches, false);
   flags.subcommand = DenoSubcommand::Test(TestFlags {
     no_run,
     doc,
     fail_fast,
+    fail_fast_per_file: matches
+      .value_of("fail-fast-per-file")
+      .map(|value| value.parse().unwrap()),
     files: FileFlags { include, ignore },
     filter,
     shuffle,
     allow_none,
     concurrent_jobs,
     trace_ops,
+    reporter,
+    junit_path: matches.value_of("junit-path").map(PathBuf::from),
+    report_memory: report_memory_arg_parse(matches),
+    fail_on_empty_files: matches.is_present("fail-on-empty-files"),
+    update_golden: matches.is_present("update-golden"),
+    timeout: matches
+      .value_of("timeout")
+      .map(|val| parse_duration(val).unwrap()),
+    tags: matches
+      .values_of("tags")
+      .map(|values| values.map(String::from).collect())
+      .unwrap_or_default(),
+    skip_tags: matches
+      .values_of("skip-tags")
+      .map(|values| values.map(String::from).collect())
+      .unwrap_or_default(),
+    retries: matches
+      .value_of("retries")
+      .map(|val| val.parse().unwrap())
+      .unwrap_or(0),
+    watch_all_on_change: matches.is_present("watch-all-on-change"),
   });
 }
 
@@ -2921,7 +4745,9 @@ fn upgrade_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
 
   let dry_run = matches.is_present("dry-run");
   let force = matches.is_present("force");
-  let canary = matches.is_present("canary");
+  let channel = matches.value_of("channel");
+  let canary = matches.is_present("canary") || channel == Some("canary");
+  let rc = matches.is_present("rc") || channel == Some("rc");
   let version = matches.value_of("version").map(|s| s.to_string());
   let output = if matches.is_present("output") {
     let install_root = matches.value_of("output").unwrap();
@@ -2929,22 +4755,31 @@ fn upgrade_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   } else {
     None
   };
+  let list = matches.is_present("list");
+  let page = matches.value_of("page").map(|value| value.parse().unwrap());
   flags.subcommand = DenoSubcommand::Upgrade(UpgradeFlags {
     dry_run,
     force,
     canary,
+    rc,
     version,
     output,
+    list,
+    page,
   });
 }
 
 fn vendor_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   ca_file_arg_parse(flags, matches);
+  tls_client_cert_arg_parse(flags, matches);
+  user_agent_arg_parse(flags, matches);
   config_args_parse(flags, matches);
   import_map_arg_parse(flags, matches);
   lock_arg_parse(flags, matches);
   reload_arg_parse(flags, matches);
 
+  let json = matches.is_present("json");
+  json_output_quiet_parse(flags, json);
   flags.subcommand = DenoSubcommand::Vendor(VendorFlags {
     specifiers: matches
       .values_of("specifiers")
@@ -2952,6 +4787,14 @@ fn vendor_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
       .unwrap_or_default(),
     output_path: matches.value_of("output").map(PathBuf::from),
     force: matches.is_present("force"),
+    check: matches.is_present("check"),
+    json,
+    import_map_only: matches.is_present("import-map-only").then(|| {
+      matches
+        .value_of("import-map-only")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("import_map.json"))
+    }),
   });
 }
 
@@ -2966,6 +4809,8 @@ fn compile_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   reload_arg_parse(flags, matches);
   lock_args_parse(flags, matches);
   ca_file_arg_parse(flags, matches);
+  tls_client_cert_arg_parse(flags, matches);
+  user_agent_arg_parse(flags, matches);
 }
 
 fn compile_args_without_no_check_parse(
@@ -2980,6 +4825,8 @@ fn compile_args_without_no_check_parse(
   reload_arg_parse(flags, matches);
   lock_args_parse(flags, matches);
   ca_file_arg_parse(flags, matches);
+  tls_client_cert_arg_parse(flags, matches);
+  user_agent_arg_parse(flags, matches);
 }
 
 fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -3027,6 +4874,13 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     debug!("sys info allowlist: {:#?}", &flags.allow_sys);
   }
 
+  if let Some(scheme_wl) = matches.values_of("allow-scheme") {
+    let scheme_allowlist: Vec<String> =
+      scheme_wl.map(ToString::to_string).collect();
+    flags.allow_scheme = Some(scheme_allowlist);
+    debug!("scheme allowlist: {:#?}", &flags.allow_scheme);
+  }
+
   if let Some(ffi_wl) = matches.values_of("allow-ffi") {
     let ffi_allowlist: Vec<PathBuf> = ffi_wl.map(PathBuf::from).collect();
     flags.allow_ffi = Some(ffi_allowlist);
@@ -3045,11 +4899,15 @@ fn permission_args_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     flags.allow_write = Some(vec![]);
     flags.allow_sys = Some(vec![]);
     flags.allow_ffi = Some(vec![]);
+    flags.allow_scheme = Some(vec![]);
     flags.allow_hrtime = true;
   }
   if matches.is_present("no-prompt") {
     flags.no_prompt = true;
   }
+  if matches.is_present("no-config-permissions") {
+    flags.no_config_permissions = true;
+  }
 }
 fn unsafely_ignore_certificate_errors_parse(
   flags: &mut Flags,
@@ -3078,6 +4936,7 @@ fn runtime_args_parse(
   location_arg_parse(flags, matches);
   v8_flags_arg_parse(flags, matches);
   seed_arg_parse(flags, matches);
+  storage_quota_arg_parse(flags, matches);
   enable_testing_features_arg_parse(flags, matches);
 }
 
@@ -3137,6 +4996,22 @@ fn ca_file_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
     .map(CaData::File);
 }
 
+fn tls_client_cert_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  flags.tls_client_cert =
+    matches.value_of("tls-client-cert").map(ToOwned::to_owned);
+  flags.tls_client_key =
+    matches.value_of("tls-client-key").map(ToOwned::to_owned);
+  if let Some(hosts) = matches.values_of("tls-client-cert-hosts") {
+    flags.tls_client_cert_hosts = Some(
+      flags_allow_net::parse(hosts.map(ToString::to_string).collect()).unwrap(),
+    );
+  }
+}
+
+fn user_agent_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
+  flags.user_agent = matches.value_of("user-agent").map(ToOwned::to_owned);
+}
+
 fn enable_testing_features_arg_parse(
   flags: &mut Flags,
   matches: &clap::ArgMatches,
@@ -3174,6 +5049,56 @@ fn seed_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
   }
 }
 
+fn storage_quota_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  flags.storage_quota = matches
+    .value_of("storage-quota")
+    .map(|val| val.parse::<u64>().unwrap());
+}
+
+fn exit_on_idle_timeout_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  flags.exit_on_idle_timeout = matches
+    .value_of("exit-on-idle-timeout")
+    .map(|val| parse_duration(val).unwrap());
+}
+
+fn preload_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if let Some(preload) = matches.values_of("preload") {
+    flags.preload = preload.map(String::from).collect();
+  }
+}
+
+fn print_import_order_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if matches.is_present("print-import-order") {
+    flags.print_import_order = match matches.value_of("print-import-order") {
+      Some("json") => Some(ImportOrderFormat::Json),
+      _ => Some(ImportOrderFormat::Text),
+    };
+  }
+}
+
+fn stack_trace_args_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if let Some(limit) = matches.value_of("stack-trace-limit") {
+    let limit = limit.parse::<usize>().unwrap();
+    flags.stack_trace_limit = Some(limit);
+
+    flags.v8_flags.push(format!("--stack-trace-limit={limit}"));
+  }
+  flags.full_stack_traces = matches.is_present("full-stack-traces");
+}
+
+fn report_unused_permissions_arg_parse(
+  flags: &mut Flags,
+  matches: &ArgMatches,
+) {
+  if matches.is_present("report-unused-permissions") {
+    flags.report_unused_permissions =
+      match matches.value_of("report-unused-permissions") {
+        Some("json") => Some(UnusedPermissionsFormat::Json),
+        _ => Some(UnusedPermissionsFormat::Text),
+      };
+  }
+}
+
 fn no_check_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if let Some(cache_type) = matches.value_of("no-check") {
     match cache_type {
@@ -3188,6 +5113,29 @@ fn no_check_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   }
 }
 
+fn report_memory_arg_parse(matches: &clap::ArgMatches) -> Option<u64> {
+  if let Some(interval) = matches.value_of("report-memory") {
+    Some(interval.parse().unwrap())
+  } else if matches.is_present("report-memory") {
+    Some(100)
+  } else {
+    None
+  }
+}
+
+fn profile_loop_arg_parse(
+  matches: &clap::ArgMatches,
+) -> Option<Option<Duration>> {
+  if !matches.is_present("profile-loop") {
+    return None;
+  }
+  Some(
+    matches
+      .value_of("profile-loop")
+      .map(|val| parse_duration(val).unwrap()),
+  )
+}
+
 fn check_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
   if let Some(cache_type) = matches.value_of("check") {
     match cache_type {
@@ -3231,6 +5179,7 @@ fn config_args_parse(flags: &mut Flags, matches: &ArgMatches) {
   } else {
     ConfigFlag::Discover
   };
+  flags.strict_config = matches.is_present("strict-config");
 }
 
 fn no_remote_arg_parse(flags: &mut Flags, matches: &clap::ArgMatches) {
@@ -3349,8 +5298,11 @@ mod tests {
           force: true,
           dry_run: true,
           canary: false,
+          rc: false,
           version: None,
           output: None,
+          list: false,
+          page: None,
         }),
         ..Flags::default()
       }
@@ -3358,35 +5310,112 @@ mod tests {
   }
 
   #[test]
-  fn version() {
-    let r = flags_from_vec(svec!["deno", "--version"]);
-    assert_eq!(r.unwrap_err().kind(), clap::ErrorKind::DisplayVersion);
-    let r = flags_from_vec(svec!["deno", "-V"]);
-    assert_eq!(r.unwrap_err().kind(), clap::ErrorKind::DisplayVersion);
-  }
-
-  #[test]
-  fn run_reload() {
-    let r = flags_from_vec(svec!["deno", "run", "-r", "script.ts"]);
-    let flags = r.unwrap();
+  fn upgrade_rc() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--rc"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          rc: true,
+          version: None,
+          output: None,
+          list: false,
+          page: None,
         }),
-        reload: true,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--rc", "--canary"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn run_watch() {
-    let r = flags_from_vec(svec!["deno", "run", "--watch", "script.ts"]);
-    let flags = r.unwrap();
+  fn upgrade_list() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--list"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          rc: false,
+          version: None,
+          output: None,
+          list: true,
+          page: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "upgrade",
+      "--list",
+      "--channel=rc",
+      "--page=2"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          rc: true,
+          version: None,
+          output: None,
+          list: true,
+          page: Some(NonZeroUsize::new(2).unwrap()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--channel=rc"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "upgrade", "--list", "--page=0"]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "upgrade", "--list", "--version", "1.0.0"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn version() {
+    let r = flags_from_vec(svec!["deno", "--version"]);
+    assert_eq!(r.unwrap_err().kind(), clap::ErrorKind::DisplayVersion);
+    let r = flags_from_vec(svec!["deno", "-V"]);
+    assert_eq!(r.unwrap_err().kind(), clap::ErrorKind::DisplayVersion);
+  }
+
+  #[test]
+  fn run_reload() {
+    let r = flags_from_vec(svec!["deno", "run", "-r", "script.ts"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        reload: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_watch() {
+    let r = flags_from_vec(svec!["deno", "run", "--watch", "script.ts"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
@@ -3545,6 +5574,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_scheme: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -3581,6 +5611,28 @@ mod tests {
     );
   }
 
+  #[test]
+  fn no_config_permissions() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--no-config-permissions",
+      "gist.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "gist.ts".to_string(),
+        }),
+        no_config_permissions: true,
+        ..Flags::default()
+      }
+    );
+    assert_eq!(flags.to_permission_args(), svec!["--no-config-permissions"]);
+  }
+
   #[test]
   fn double_hyphen() {
     // notice that flags passed after double dash will not
@@ -3616,7 +5668,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: false,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![
               PathBuf::from("script_1.ts"),
@@ -3630,6 +5683,9 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
         }),
         ..Flags::default()
       }
@@ -3641,7 +5697,66 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: true,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--check", "--compact"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: true,
+          compact: true,
+          diff: false,
+          no_cache: false,
+          ext: None,
+          stdin_filepath: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--compact"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--check", "--diff"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: true,
+          compact: false,
+          diff: true,
+          no_cache: false,
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -3657,13 +5772,20 @@ mod tests {
       }
     );
 
+    let r = flags_from_vec(svec!["deno", "fmt", "--diff"]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "fmt", "--check", "--diff", "--compact"]);
+    assert!(r.is_err());
+
     let r = flags_from_vec(svec!["deno", "fmt"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: false,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -3674,6 +5796,9 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
         }),
         ..Flags::default()
       }
@@ -3685,7 +5810,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: false,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -3696,6 +5822,9 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
         }),
         watch: Some(vec![]),
         ..Flags::default()
@@ -3709,7 +5838,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: false,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -3720,6 +5850,9 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
         }),
         watch: Some(vec![]),
         no_clear_screen: true,
@@ -3740,7 +5873,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: true,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![PathBuf::from("foo.ts")],
             ignore: vec![PathBuf::from("bar.js")],
@@ -3751,6 +5885,9 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
         }),
         watch: Some(vec![]),
         ..Flags::default()
@@ -3763,7 +5900,41 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: false,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
+        }),
+        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--config",
+      "deno.jsonc",
+      "--strict-config"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -3774,8 +5945,12 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        strict_config: true,
         ..Flags::default()
       }
     );
@@ -3793,7 +5968,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: false,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![PathBuf::from("foo.ts")],
             ignore: vec![],
@@ -3804,6 +5980,9 @@ mod tests {
           single_quote: None,
           prose_wrap: None,
           no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         watch: Some(vec![]),
@@ -3829,7 +6008,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: false,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -3840,6 +6020,9 @@ mod tests {
           single_quote: Some(true),
           prose_wrap: Some("never".to_string()),
           no_semicolons: Some(true),
+          compact: false,
+          diff: false,
+          no_cache: false,
         }),
         ..Flags::default()
       }
@@ -3858,7 +6041,8 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Fmt(FmtFlags {
           check: false,
-          ext: "ts".to_string(),
+          ext: None,
+          stdin_filepath: None,
           files: FileFlags {
             include: vec![],
             ignore: vec![],
@@ -3869,6 +6053,76 @@ mod tests {
           single_quote: Some(false),
           prose_wrap: None,
           no_semicolons: Some(false),
+          compact: false,
+          diff: false,
+          no_cache: false,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn fmt_stdin() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--ext=md",
+      "--stdin-filepath=docs/README.txt",
+      "-"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          ext: Some("md".to_string()),
+          stdin_filepath: Some(PathBuf::from("docs/README.txt")),
+          files: FileFlags {
+            include: vec![PathBuf::from("-")],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "fmt", "--ext=py", "-"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn fmt_no_cache() {
+    let r = flags_from_vec(svec!["deno", "fmt", "--check", "--no-cache"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: true,
+          ext: None,
+          stdin_filepath: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          compact: false,
+          diff: false,
+          no_cache: true,
         }),
         ..Flags::default()
       }
@@ -3893,8 +6147,13 @@ mod tests {
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: false,
           compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         ..Flags::default()
       }
@@ -3922,8 +6181,13 @@ mod tests {
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: false,
           compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         watch: Some(vec![]),
         ..Flags::default()
@@ -3953,8 +6217,13 @@ mod tests {
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: false,
           compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         watch: Some(vec![]),
         no_clear_screen: true,
@@ -3979,8 +6248,13 @@ mod tests {
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: false,
           compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         ..Flags::default()
       }
@@ -3999,13 +6273,53 @@ mod tests {
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: false,
           compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--rules",
+      "--file=src/app.ts",
+      "--tags=recommended,jsx",
+      "--json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          rules: true,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          maybe_rules_file: Some(PathBuf::from("src/app.ts")),
+          maybe_rules_filter_tags: Some(svec!["recommended", "jsx"]),
+          json: true,
+          compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         ..Flags::default()
       }
     );
 
+    let r = flags_from_vec(svec!["deno", "lint", "--file=src/app.ts"]);
+    assert!(r.is_err());
+
     let r = flags_from_vec(svec![
       "deno",
       "lint",
@@ -4025,8 +6339,13 @@ mod tests {
           maybe_rules_tags: Some(svec![""]),
           maybe_rules_include: Some(svec!["ban-untagged-todo", "no-undef"]),
           maybe_rules_exclude: Some(svec!["no-const-assign"]),
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: false,
           compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         ..Flags::default()
       }
@@ -4045,8 +6364,13 @@ mod tests {
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: true,
           compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         ..Flags::default()
       }
@@ -4072,8 +6396,13 @@ mod tests {
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: true,
           compact: false,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
         ..Flags::default()
@@ -4100,14 +6429,86 @@ mod tests {
           maybe_rules_tags: None,
           maybe_rules_include: None,
           maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
           json: false,
           compact: true,
+          verbose: false,
+          deps: false,
+          strict_deps: false,
         }),
         config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
-  }
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--deps",
+      "--strict-deps",
+      "--json",
+      "--import-map",
+      "import_map.json",
+      "script_1.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![PathBuf::from("script_1.ts")],
+            ignore: vec![],
+          },
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
+          json: true,
+          compact: false,
+          verbose: false,
+          deps: true,
+          strict_deps: true,
+        }),
+        import_map_path: Some("import_map.json".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--strict-deps"]);
+    assert!(r.is_err());
+
+    let r =
+      flags_from_vec(svec!["deno", "lint", "--compact", "--verbose", "a.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![PathBuf::from("a.ts")],
+            ignore: vec![],
+          },
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          maybe_rules_file: None,
+          maybe_rules_filter_tags: None,
+          json: false,
+          compact: true,
+          verbose: true,
+          deps: false,
+          strict_deps: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "lint", "--json", "--verbose"]);
+    assert!(r.is_err());
+  }
 
   #[test]
   fn types() {
@@ -4129,12 +6530,89 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          json: false,
+          import_list: None,
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "cache", "--json", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          json: true,
+          import_list: None,
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
         }),
+        log_level: Some(Level::Error),
         ..Flags::default()
       }
     );
   }
 
+  #[test]
+  fn cache_import_list() {
+    let r = flags_from_vec(svec!["deno", "cache", "--import-list=-"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          json: false,
+          import_list: Some("-".to_string()),
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--import-list=entrypoints.txt",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          json: false,
+          import_list: Some("entrypoints.txt".to_string()),
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "cache"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn check() {
     let r = flags_from_vec(svec!["deno", "check", "script.ts"]);
@@ -4143,6 +6621,11 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Check(CheckFlags {
           files: svec!["script.ts"],
+          doc: false,
+          emit_dir: None,
+          emit_remote: false,
+          deprecations: false,
+          json: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -4156,6 +6639,11 @@ mod tests {
         Flags {
           subcommand: DenoSubcommand::Check(CheckFlags {
             files: svec!["script.ts"],
+            doc: false,
+            emit_dir: None,
+            emit_remote: false,
+            deprecations: false,
+            json: false,
           }),
           type_check_mode: TypeCheckMode::All,
           ..Flags::default()
@@ -4173,6 +6661,197 @@ mod tests {
     }
   }
 
+  #[test]
+  fn check_watch() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "check",
+      "--watch",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+          doc: false,
+          emit_dir: None,
+          emit_remote: false,
+          deprecations: false,
+          json: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        watch: Some(vec![]),
+        no_clear_screen: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn check_doc() {
+    let r =
+      flags_from_vec(svec!["deno", "check", "--doc", "README.md", "mod.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["README.md", "mod.ts"],
+          doc: true,
+          emit_dir: None,
+          emit_remote: false,
+          deprecations: false,
+          json: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "check", "--doc", "--watch", "mod.ts"]);
+    assert_eq!(r.unwrap_err().kind(), clap::ErrorKind::ArgumentConflict);
+  }
+
+  #[test]
+  fn check_deprecations() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "check",
+      "--deprecations",
+      "--json",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["main.ts"],
+          doc: false,
+          emit_dir: None,
+          emit_remote: false,
+          deprecations: true,
+          json: true,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "check", "--json", "main.ts"]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::ErrorKind::MissingRequiredArgument
+    );
+  }
+
+  #[test]
+  fn ci() {
+    let r = flags_from_vec(svec!["deno", "ci"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Ci(CiFlags::default()),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "ci",
+      "--no-fmt",
+      "--no-lint",
+      "--no-check",
+      "--json",
+      "--ignore=src/gen",
+      "--config",
+      "deno.json",
+      "src/",
+      "mod.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Ci(CiFlags {
+          files: FileFlags {
+            include: vec![PathBuf::from("src/"), PathBuf::from("mod.ts")],
+            ignore: vec![PathBuf::from("src/gen")],
+          },
+          no_fmt: true,
+          no_lint: true,
+          no_check: true,
+          json: true,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        config_flag: ConfigFlag::Path("deno.json".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn check_emit_dir() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "check",
+      "--emit-dir=dist",
+      "--emit-remote",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["main.ts"],
+          doc: false,
+          emit_dir: Some(PathBuf::from("dist")),
+          emit_remote: true,
+          deprecations: false,
+          json: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "check", "--emit-remote", "main.ts"]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::ErrorKind::MissingRequiredArgument
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "check",
+      "--emit-dir=dist",
+      "--watch",
+      "main.ts"
+    ]);
+    assert_eq!(r.unwrap_err().kind(), clap::ErrorKind::ArgumentConflict);
+
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--emit-dir=dist", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["main.ts"],
+          json: false,
+          import_list: None,
+          lockfile_only: false,
+          emit_dir: Some(PathBuf::from("dist")),
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn info() {
     let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
@@ -4182,6 +6861,10 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
           file: Some("script.ts".to_string()),
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
         }),
         ..Flags::default()
       }
@@ -4194,6 +6877,10 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
           file: Some("script.ts".to_string()),
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
         }),
         reload: true,
         ..Flags::default()
@@ -4207,6 +6894,10 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: true,
           file: Some("script.ts".to_string()),
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
         }),
         ..Flags::default()
       }
@@ -4218,7 +6909,11 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
-          file: None
+          file: None,
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
         }),
         ..Flags::default()
       }
@@ -4230,7 +6925,11 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: true,
-          file: None
+          file: None,
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
         }),
         ..Flags::default()
       }
@@ -4242,12 +6941,128 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
-          file: None
+          file: None,
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
         }),
         config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
         ..Flags::default()
       }
     );
+
+    let r =
+      flags_from_vec(svec!["deno", "info", "--types", "--json", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: Some("script.ts".to_string()),
+          types: true,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--types"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn info_import_list() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--reload",
+      "--json",
+      "--import-list=entrypoints.txt"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: None,
+          types: false,
+          import_list: Some("entrypoints.txt".to_string()),
+          journal_summary: false,
+          config_entrypoints: false,
+        }),
+        reload: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--reload"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn info_journal_summary() {
+    let r =
+      flags_from_vec(svec!["deno", "info", "--journal-summary", "--json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: None,
+          types: false,
+          import_list: None,
+          journal_summary: true,
+          config_entrypoints: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "info", "--journal-summary", "script.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn info_config_entrypoints() {
+    let r =
+      flags_from_vec(svec!["deno", "info", "--json", "--config-entrypoints"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: None,
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--config-entrypoints"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--json",
+      "--config-entrypoints",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn subcommand_names() {
+    let r = flags_from_vec(svec!["deno", "fmt", "--check"]);
+    assert_eq!(r.unwrap().subcommand.name(), "fmt");
+    let r = flags_from_vec(svec!["deno"]);
+    assert_eq!(r.unwrap().subcommand.name(), "repl");
   }
 
   #[test]
@@ -4284,6 +7099,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_scheme: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4308,6 +7124,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_scheme: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4333,6 +7150,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_scheme: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4371,6 +7189,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_scheme: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4402,6 +7221,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_scheme: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4427,6 +7247,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_scheme: Some(vec![]),
         allow_hrtime: true,
         ..Flags::default()
       }
@@ -4466,6 +7287,7 @@ mod tests {
         allow_sys: Some(vec![]),
         allow_write: Some(vec![]),
         allow_ffi: Some(vec![]),
+        allow_scheme: Some(vec![]),
         allow_hrtime: true,
         unsafely_ignore_certificate_errors: Some(vec![]),
         ..Flags::default()
@@ -4706,6 +7528,26 @@ mod tests {
     assert!(r.is_err());
   }
 
+  #[test]
+  fn allow_scheme_allowlist() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-scheme=test-fixture",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        allow_scheme: Some(svec!["test-fixture"]),
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn reload_validator() {
     let r = flags_from_vec(svec![
@@ -4787,6 +7629,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          sourcemap: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -4794,6 +7637,43 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bundle_with_sourcemap() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--sourcemap=external",
+      "source.ts",
+      "bundle.js"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          source_file: "source.ts".to_string(),
+          out_file: Some(PathBuf::from("bundle.js")),
+          sourcemap: Some(SourceMapMode::External),
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        allow_write: Some(vec![]),
+        ..Flags::default()
+      }
+    );
+
+    // the external source map is written next to the output file
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--sourcemap=external",
+      "source.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r =
+      flags_from_vec(svec!["deno", "bundle", "--sourcemap=foo", "source.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn bundle_with_config() {
     let r = flags_from_vec(svec![
@@ -4811,6 +7691,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: Some(PathBuf::from("bundle.js")),
+          sourcemap: None,
         }),
         allow_write: Some(vec![]),
         no_remote: true,
@@ -4830,6 +7711,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: Some(PathBuf::from("bundle.js")),
+          sourcemap: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         allow_write: Some(vec![]),
@@ -4853,6 +7735,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          sourcemap: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         lock_write: true,
@@ -4872,6 +7755,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          sourcemap: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -4889,6 +7773,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "script.ts".to_string(),
           out_file: None,
+          sourcemap: None,
         }),
         type_check_mode: TypeCheckMode::None,
         ..Flags::default()
@@ -4905,6 +7790,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          sourcemap: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         watch: Some(vec![]),
@@ -4928,6 +7814,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          sourcemap: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         watch: Some(vec![]),
@@ -4971,6 +7858,10 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           file: Some("script.ts".to_string()),
           json: false,
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -4991,6 +7882,14 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts"],
+          json: false,
+          import_list: None,
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -5013,7 +7912,9 @@ mod tests {
           source_file: DocSourceFileFlag::Path("script.ts".to_owned()),
           private: false,
           json: false,
+          json_schema: false,
           filter: None,
+          show_references: false,
         }),
         import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
@@ -5028,38 +7929,177 @@ mod tests {
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Cache(CacheFlags {
-          files: svec!["script.ts", "script_two.ts"],
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts", "script_two.ts"],
+          json: false,
+          import_list: None,
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_seed() {
+    let r = flags_from_vec(svec!["deno", "run", "--seed", "250", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        seed: Some(250_u64),
+        v8_flags: svec!["--random-seed=250"],
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_seed_with_v8_flags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--seed",
+      "250",
+      "--v8-flags=--expose-gc",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        seed: Some(250_u64),
+        v8_flags: svec!["--expose-gc", "--random-seed=250"],
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_storage_quota() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--storage-quota=4096", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        storage_quota: Some(4096),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--storage-quota=foo", "script.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_diagnose_event_loop() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--diagnose-event-loop",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
         }),
+        diagnose_event_loop: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn run_seed() {
-    let r = flags_from_vec(svec!["deno", "run", "--seed", "250", "script.ts"]);
+  fn run_exit_on_idle_timeout() {
+    let cases = [
+      ("--exit-on-idle-timeout=500ms", Duration::from_millis(500)),
+      ("--exit-on-idle-timeout=30s", Duration::from_secs(30)),
+      ("--exit-on-idle-timeout=5m", Duration::from_secs(300)),
+      ("--exit-on-idle-timeout=2h", Duration::from_secs(7200)),
+      ("--exit-on-idle-timeout=10", Duration::from_secs(10)),
+    ];
+    for (arg, duration) in cases {
+      let r = flags_from_vec(svec!["deno", "run", arg, "script.ts"]);
+      assert_eq!(
+        r.unwrap(),
+        Flags {
+          subcommand: DenoSubcommand::Run(RunFlags {
+            script: "script.ts".to_string(),
+          }),
+          exit_on_idle_timeout: Some(duration),
+          ..Flags::default()
+        }
+      );
+    }
+
+    for arg in [
+      "--exit-on-idle-timeout=",
+      "--exit-on-idle-timeout=5d",
+      "--exit-on-idle-timeout=ms",
+    ] {
+      let r = flags_from_vec(svec!["deno", "run", arg, "script.ts"]);
+      assert!(r.is_err(), "{arg}");
+    }
+  }
+
+  #[test]
+  fn run_preload() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--preload=./apm.ts",
+      "--preload=instrument",
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
         }),
-        seed: Some(250_u64),
-        v8_flags: svec!["--random-seed=250"],
+        preload: svec!["./apm.ts", "instrument"],
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "test", "--preload=./setup.ts"]);
+    assert_eq!(r.unwrap().preload, svec!["./setup.ts"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--preload=./apm.ts",
+      "script.ts"
+    ]);
+    assert_eq!(r.unwrap().preload, svec!["./apm.ts"]);
+    let r =
+      flags_from_vec(svec!["deno", "info", "--preload=./apm.ts", "script.ts"]);
+    assert_eq!(r.unwrap().preload, svec!["./apm.ts"]);
+    let r = flags_from_vec(svec!["deno", "info", "--preload=./apm.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn run_seed_with_v8_flags() {
+  fn run_stack_traces() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--seed",
-      "250",
-      "--v8-flags=--expose-gc",
+      "--stack-trace-limit=50",
+      "--full-stack-traces",
       "script.ts"
     ]);
     assert_eq!(
@@ -5068,11 +8108,102 @@ mod tests {
         subcommand: DenoSubcommand::Run(RunFlags {
           script: "script.ts".to_string(),
         }),
-        seed: Some(250_u64),
-        v8_flags: svec!["--expose-gc", "--random-seed=250"],
+        stack_trace_limit: Some(50),
+        full_stack_traces: true,
+        v8_flags: svec!["--stack-trace-limit=50"],
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "test", "--stack-trace-limit=5"]);
+    assert_eq!(r.unwrap().stack_trace_limit, Some(5));
+    let r = flags_from_vec(svec!["deno", "test", "--stack-trace-limit=all"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_print_import_order() {
+    let cases = [
+      ("--print-import-order", ImportOrderFormat::Text),
+      ("--print-import-order=text", ImportOrderFormat::Text),
+      ("--print-import-order=json", ImportOrderFormat::Json),
+    ];
+    for (arg, format) in cases {
+      let r = flags_from_vec(svec!["deno", "run", arg, "script.ts"]);
+      assert_eq!(
+        r.unwrap(),
+        Flags {
+          subcommand: DenoSubcommand::Run(RunFlags {
+            script: "script.ts".to_string(),
+          }),
+          print_import_order: Some(format),
+          ..Flags::default()
+        }
+      );
+    }
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--print-import-order=yaml",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--print-import-order",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn report_unused_permissions() {
+    let cases = [
+      ("--report-unused-permissions", UnusedPermissionsFormat::Text),
+      (
+        "--report-unused-permissions=text",
+        UnusedPermissionsFormat::Text,
+      ),
+      (
+        "--report-unused-permissions=json",
+        UnusedPermissionsFormat::Json,
+      ),
+    ];
+    for (arg, format) in cases {
+      let r = flags_from_vec(svec!["deno", "run", arg, "script.ts"]);
+      assert_eq!(
+        r.unwrap(),
+        Flags {
+          subcommand: DenoSubcommand::Run(RunFlags {
+            script: "script.ts".to_string(),
+          }),
+          report_unused_permissions: Some(format),
+          ..Flags::default()
+        }
+      );
+      for subcommand in ["test", "compile"] {
+        let r = flags_from_vec(svec!["deno", subcommand, arg, "script.ts"]);
+        assert_eq!(r.unwrap().report_unused_permissions, Some(format));
+      }
+    }
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--report-unused-permissions=yaml",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--watch",
+      "--report-unused-permissions"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -5091,10 +8222,47 @@ mod tests {
           args: vec![],
           root: None,
           force: false,
+          subcommand: InstallSubcommand::Run,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn install_with_task_subcommand() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "install",
+      "--subcommand=task",
+      "-n",
+      "mytool",
+      "./deno.json",
+      "build",
+      "--verbose"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Install(InstallFlags {
+          name: Some("mytool".to_string()),
+          module_url: "./deno.json".to_string(),
+          args: svec!["build", "--verbose"],
+          root: None,
+          force: false,
+          subcommand: InstallSubcommand::Task,
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "install",
+      "--subcommand=test",
+      "./deno.json"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -5110,6 +8278,7 @@ mod tests {
           args: svec!["foo", "bar"],
           root: Some(PathBuf::from("/foo")),
           force: true,
+          subcommand: InstallSubcommand::Run,
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -5183,6 +8352,36 @@ mod tests {
     );
   }
 
+  #[test]
+  fn color() {
+    let r = flags_from_vec(svec!["deno", "--color=never", "fmt"]);
+    assert_eq!(r.unwrap().color, ColorMode::Never);
+
+    let r = flags_from_vec(svec!["deno", "test", "--color=always"]);
+    assert_eq!(r.unwrap().color, ColorMode::Always);
+
+    let r = flags_from_vec(svec!["deno", "lint"]);
+    assert_eq!(r.unwrap().color, ColorMode::Auto);
+
+    let r = flags_from_vec(svec!["deno", "task", "--color=never", "build"]);
+    let flags = r.unwrap();
+    assert_eq!(flags.color, ColorMode::Never);
+    assert_eq!(
+      flags.subcommand,
+      DenoSubcommand::Task(TaskFlags {
+        cwd: None,
+        task: "build".to_string(),
+        list: false,
+        json: false,
+        dry_run: false,
+        show_secrets: false,
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "run", "--color=sometimes", "a.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn completions() {
     let r = flags_from_vec(svec!["deno", "completions", "zsh"]).unwrap();
@@ -5650,82 +8849,310 @@ mod tests {
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Test(TestFlags {
-          no_run: true,
+          no_run: true,
+          doc: false,
+          fail_fast: None,
+          filter: Some("- foo".to_string()),
+          allow_none: true,
+          files: FileFlags {
+            include: vec![PathBuf::from("dir1/"), PathBuf::from("dir2/")],
+            ignore: vec![],
+          },
+          shuffle: None,
+          concurrent_jobs: None,
+          trace_ops: true,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
+        }),
+        unstable: true,
+        no_prompt: true,
+        coverage_dir: Some("cov".to_string()),
+        location: Some(Url::parse("https://foo/").unwrap()),
+        type_check_mode: TypeCheckMode::Local,
+        allow_net: Some(vec![]),
+        argv: svec!["arg1", "arg2"],
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_with_cafile() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--cert",
+      "example.crt",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        ca_data: Some(CaData::File("example.crt".to_owned())),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn run_with_tls_client_cert() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--tls-client-cert",
+      "client.crt",
+      "--tls-client-key",
+      "client.key",
+      "--tls-client-cert-hosts=registry.internal,localhost:5552",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        tls_client_cert: Some("client.crt".to_string()),
+        tls_client_key: Some("client.key".to_string()),
+        tls_client_cert_hosts: Some(svec![
+          "registry.internal",
+          "localhost:5552"
+        ]),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--tls-client-cert",
+      "client.crt",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_with_enable_testing_features() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--enable-testing-features-do-not-use",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        enable_testing_features: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn test_with_concurrent_jobs() {
+    let r = flags_from_vec(svec!["deno", "test", "--jobs=4"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          concurrent_jobs: Some(NonZeroUsize::new(4).unwrap()),
+          trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_prompt: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--jobs=0"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_reporter() {
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=dot"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
           doc: false,
           fail_fast: None,
-          filter: Some("- foo".to_string()),
-          allow_none: true,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
           files: FileFlags {
-            include: vec![PathBuf::from("dir1/"), PathBuf::from("dir2/")],
+            include: vec![],
             ignore: vec![],
           },
-          shuffle: None,
           concurrent_jobs: None,
-          trace_ops: true,
+          trace_ops: false,
+          reporter: TestReporterConfig::Dot,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
         }),
-        unstable: true,
         no_prompt: true,
-        coverage_dir: Some("cov".to_string()),
-        location: Some(Url::parse("https://foo/").unwrap()),
         type_check_mode: TypeCheckMode::Local,
-        allow_net: Some(vec![]),
-        argv: svec!["arg1", "arg2"],
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn run_with_cafile() {
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=compact"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Compact,
+        ..TestFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=tap"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Tap,
+        ..TestFlags::default()
+      })
+    );
+
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--cert",
-      "example.crt",
-      "script.ts"
+      "test",
+      "--allow-net",
+      "--reporter=./my_reporter.ts"
     ]);
+    let flags = r.unwrap();
     assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-        }),
-        ca_data: Some(CaData::File("example.crt".to_owned())),
-        ..Flags::default()
-      }
+      flags.subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Module("./my_reporter.ts".to_string()),
+        ..TestFlags::default()
+      })
     );
+    assert_eq!(flags.allow_net, Some(vec![]));
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=xml"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn run_with_enable_testing_features() {
+  fn test_junit_reporter() {
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=junit"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Junit,
+        ..TestFlags::default()
+      })
+    );
+
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--enable-testing-features-do-not-use",
-      "script.ts"
+      "test",
+      "--reporter=dot",
+      "--junit-path=report.xml"
     ]);
     assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-        }),
-        enable_testing_features: true,
-        ..Flags::default()
-      }
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        reporter: TestReporterConfig::Dot,
+        junit_path: Some(PathBuf::from("report.xml")),
+        ..TestFlags::default()
+      })
     );
   }
 
   #[test]
-  fn test_with_concurrent_jobs() {
-    let r = flags_from_vec(svec!["deno", "test", "--jobs=4"]);
+  fn test_update_golden() {
+    let r = flags_from_vec(svec!["deno", "test", "--update-golden"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        update_golden: true,
+        ..TestFlags::default()
+      })
+    );
+  }
+
+  #[test]
+  fn test_report_memory() {
+    let r = flags_from_vec(svec!["deno", "test", "--report-memory"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        report_memory: Some(100),
+        ..TestFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "bench", "--report-memory=20"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        report_memory: Some(20),
+        ..BenchFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--report-memory=0"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_with_fail_fast() {
+    let r = flags_from_vec(svec!["deno", "test", "--fail-fast=3"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: false,
           doc: false,
-          fail_fast: None,
+          fail_fast: Some(NonZeroUsize::new(3).unwrap()),
           filter: None,
           allow_none: false,
           shuffle: None,
@@ -5733,8 +9160,19 @@ mod tests {
             include: vec![],
             ignore: vec![],
           },
-          concurrent_jobs: Some(NonZeroUsize::new(4).unwrap()),
+          concurrent_jobs: None,
           trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
@@ -5742,20 +9180,87 @@ mod tests {
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "test", "--jobs=0"]);
+    let r = flags_from_vec(svec!["deno", "test", "--fail-fast=0"]);
     assert!(r.is_err());
   }
 
   #[test]
-  fn test_with_fail_fast() {
-    let r = flags_from_vec(svec!["deno", "test", "--fail-fast=3"]);
+  fn test_with_timeout() {
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=90s"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        timeout: Some(Duration::from_secs(90)),
+        ..TestFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=1x"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_with_tags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--tags=slow,net",
+      "--skip-tags=flaky"
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        tags: svec!["slow", "net"],
+        skip_tags: svec!["flaky"],
+        ..TestFlags::default()
+      })
+    );
+  }
+
+  #[test]
+  fn test_with_retries() {
+    let r = flags_from_vec(svec!["deno", "test", "--retries=2"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        retries: 2,
+        ..TestFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--retries=-1"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "test", "--retries"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_with_fail_fast_per_file() {
+    let r = flags_from_vec(svec!["deno", "test", "--fail-fast-per-file=2"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Test(TestFlags {
+        fail_fast_per_file: Some(NonZeroUsize::new(2).unwrap()),
+        ..TestFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--fail-fast-per-file=0"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "test", "--fail-fast-per-file"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_with_fail_on_empty_files() {
+    let r = flags_from_vec(svec!["deno", "test", "--fail-on-empty-files"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Test(TestFlags {
           no_run: false,
           doc: false,
-          fail_fast: Some(NonZeroUsize::new(3).unwrap()),
+          fail_fast: None,
           filter: None,
           allow_none: false,
           shuffle: None,
@@ -5765,15 +9270,23 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: true,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
         }),
         type_check_mode: TypeCheckMode::Local,
         no_prompt: true,
         ..Flags::default()
       }
     );
-
-    let r = flags_from_vec(svec!["deno", "test", "--fail-fast=0"]);
-    assert!(r.is_err());
   }
 
   #[test]
@@ -5799,6 +9312,17 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5827,6 +9351,17 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
         }),
         no_prompt: true,
         watch: None,
@@ -5855,6 +9390,17 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5882,6 +9428,17 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -5911,6 +9468,17 @@ mod tests {
           },
           concurrent_jobs: None,
           trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
         }),
         watch: Some(vec![]),
         type_check_mode: TypeCheckMode::Local,
@@ -5921,6 +9489,71 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_watch_all_on_change() {
+    let r =
+      flags_from_vec(svec!["deno", "test", "--watch", "--watch-all-on-change"]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Test(test_flags) => {
+        assert!(test_flags.watch_all_on_change)
+      }
+      _ => unreachable!(),
+    }
+    assert_eq!(flags.watch, Some(vec![]));
+
+    let r = flags_from_vec(svec!["deno", "test", "--watch-all-on-change"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_watch_with_args() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--watch",
+      "a_test.ts",
+      "--",
+      "staging",
+      "b_test.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          allow_none: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![PathBuf::from("a_test.ts")],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_ops: false,
+          reporter: TestReporterConfig::Pretty,
+          report_memory: None,
+          fail_on_empty_files: false,
+          update_golden: false,
+          junit_path: None,
+          fail_fast_per_file: None,
+          timeout: None,
+          tags: vec![],
+          skip_tags: vec![],
+          retries: 0,
+          watch_all_on_change: false,
+        }),
+        no_prompt: true,
+        type_check_mode: TypeCheckMode::Local,
+        watch: Some(vec![]),
+        argv: svec!["staging", "b_test.ts"],
+        ..Flags::default()
+      }
+    );
+  }
+
   #[test]
   fn bundle_with_cafile() {
     let r = flags_from_vec(svec![
@@ -5936,6 +9569,7 @@ mod tests {
         subcommand: DenoSubcommand::Bundle(BundleFlags {
           source_file: "source.ts".to_string(),
           out_file: None,
+          sourcemap: None,
         }),
         type_check_mode: TypeCheckMode::Local,
         ca_data: Some(CaData::File("example.crt".to_owned())),
@@ -5954,8 +9588,11 @@ mod tests {
           force: false,
           dry_run: false,
           canary: false,
+          rc: false,
           version: None,
           output: None,
+          list: false,
+          page: None,
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
@@ -5978,6 +9615,14 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Cache(CacheFlags {
           files: svec!["script.ts", "script_two.ts"],
+          json: false,
+          import_list: None,
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
@@ -5985,6 +9630,93 @@ mod tests {
     );
   }
 
+  #[test]
+  fn cache_lockfile_only() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--lockfile-only",
+      "--lock-write",
+      "npm:chalk@5"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["npm:chalk@5"],
+          json: false,
+          import_list: None,
+          lockfile_only: true,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: None,
+          overwrite: false,
+        }),
+        lock_write: true,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--lockfile-only",
+      "--no-lock",
+      "npm:chalk@5"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn cache_export_import() {
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--export=bundle", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["main.ts"],
+          json: false,
+          import_list: None,
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: Some(PathBuf::from("bundle")),
+          import_dir: None,
+          overwrite: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--import=bundle", "--overwrite"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: vec![],
+          json: false,
+          import_list: None,
+          lockfile_only: false,
+          emit_dir: None,
+          emit_remote: false,
+          export_dir: None,
+          import_dir: Some(PathBuf::from("bundle")),
+          overwrite: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--import=bundle", "main.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "cache", "--overwrite", "main.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn info_with_cafile() {
     let r = flags_from_vec(svec![
@@ -6000,6 +9732,10 @@ mod tests {
         subcommand: DenoSubcommand::Info(InfoFlags {
           json: false,
           file: Some("https://example.com".to_string()),
+          types: false,
+          import_list: None,
+          journal_summary: false,
+          config_entrypoints: false,
         }),
         ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
@@ -6007,6 +9743,40 @@ mod tests {
     );
   }
 
+  #[test]
+  fn run_with_user_agent() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--user-agent",
+      "my-tool/1.2",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+        }),
+        user_agent: Some("my-tool/1.2".to_string()),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--user-agent",
+      "my-tool\n/1.2",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--user-agent", "", "script.ts"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn doc() {
     let r = flags_from_vec(svec!["deno", "doc", "--json", "path/to/module.ts"]);
@@ -6016,8 +9786,10 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: true,
+          json_schema: false,
           source_file: DocSourceFileFlag::Path("path/to/module.ts".to_string()),
           filter: None,
+          show_references: false,
         }),
         ..Flags::default()
       }
@@ -6035,8 +9807,10 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: false,
+          json_schema: false,
           source_file: DocSourceFileFlag::Path("path/to/module.ts".to_string()),
           filter: Some("SomeClass.someField".to_string()),
+          show_references: false,
         }),
         ..Flags::default()
       }
@@ -6049,8 +9823,10 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: false,
+          json_schema: false,
           source_file: Default::default(),
           filter: None,
+          show_references: false,
         }),
         ..Flags::default()
       }
@@ -6063,8 +9839,10 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: false,
           json: false,
+          json_schema: false,
           source_file: DocSourceFileFlag::Builtin,
           filter: Some("Deno.Listener".to_string()),
+          show_references: false,
         }),
         ..Flags::default()
       }
@@ -6078,12 +9856,38 @@ mod tests {
         subcommand: DenoSubcommand::Doc(DocFlags {
           private: true,
           json: false,
+          json_schema: false,
           source_file: DocSourceFileFlag::Path("path/to/module.js".to_string()),
           filter: None,
+          show_references: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--json-schema"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          json_schema: true,
+          source_file: Default::default(),
+          filter: None,
+          show_references: false,
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--json-schema",
+      "path/to/module.ts"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -6148,6 +9952,140 @@ mod tests {
           output: None,
           args: vec![],
           target: None,
+          no_metadata_flag: false,
+          strip: false,
+          deny_warnings: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_with_exit_on_idle_timeout() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--exit-on-idle-timeout=30s",
+      "https://deno.land/std/examples/colors.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://deno.land/std/examples/colors.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          no_metadata_flag: false,
+          strip: false,
+          deny_warnings: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        exit_on_idle_timeout: Some(Duration::from_secs(30)),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_with_no_metadata_flag() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--no-metadata-flag",
+      "https://deno.land/std/examples/colors.ts",
+      "--deno-metadata"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://deno.land/std/examples/colors.ts".to_string(),
+          output: None,
+          args: svec!["--deno-metadata"],
+          target: None,
+          no_metadata_flag: true,
+          strip: false,
+          deny_warnings: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_with_strip() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--strip",
+      "https://deno.land/std/examples/colors.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://deno.land/std/examples/colors.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          no_metadata_flag: false,
+          strip: true,
+          deny_warnings: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--strip",
+      "--target",
+      "x86_64-unknown-linux-gnu",
+      "https://deno.land/std/examples/colors.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://deno.land/std/examples/colors.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: Some("x86_64-unknown-linux-gnu".to_string()),
+          no_metadata_flag: false,
+          strip: true,
+          deny_warnings: false,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn compile_with_deny_warnings() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--deny-warnings",
+      "https://deno.land/std/examples/colors.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://deno.land/std/examples/colors.ts".to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          no_metadata_flag: false,
+          strip: false,
+          deny_warnings: true,
         }),
         type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
@@ -6167,6 +10105,9 @@ mod tests {
           output: Some(PathBuf::from("colors")),
           args: svec!["foo", "bar"],
           target: None,
+          no_metadata_flag: false,
+          strip: false,
+          deny_warnings: false,
         }),
         import_map_path: Some("import_map.json".to_string()),
         no_remote: true,
@@ -6275,6 +10216,14 @@ mod tests {
       flags.config_path_args(),
       Some(vec![PathBuf::from("dir/a.js"), PathBuf::from("dir/b.js")])
     );
+
+    let flags =
+      flags_from_vec(svec!["deno", "fmt", "--stdin-filepath=dir/a.md", "-"])
+        .unwrap();
+    assert_eq!(
+      flags.config_path_args(),
+      Some(vec![PathBuf::from("dir/a.md")])
+    );
   }
 
   #[test]
@@ -6288,54 +10237,202 @@ mod tests {
   }
 
   #[test]
-  fn vendor_minimal() {
-    let r = flags_from_vec(svec!["deno", "vendor", "mod.ts",]);
+  fn vendor_minimal() {
+    let r = flags_from_vec(svec!["deno", "vendor", "mod.ts",]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: svec!["mod.ts"],
+          force: false,
+          output_path: None,
+          check: false,
+          json: false,
+          import_map_only: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn vendor_all() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "vendor",
+      "--config",
+      "deno.json",
+      "--import-map",
+      "import_map.json",
+      "--lock",
+      "lock.json",
+      "--force",
+      "--output",
+      "out_dir",
+      "--reload",
+      "mod.ts",
+      "deps.test.ts",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: svec!["mod.ts", "deps.test.ts"],
+          force: true,
+          output_path: Some(PathBuf::from("out_dir")),
+          check: false,
+          json: false,
+          import_map_only: None,
+        }),
+        config_flag: ConfigFlag::Path("deno.json".to_string()),
+        import_map_path: Some("import_map.json".to_string()),
+        lock: Some(PathBuf::from("lock.json")),
+        reload: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn vendor_check() {
+    let r =
+      flags_from_vec(svec!["deno", "vendor", "--check", "--output", "out_dir"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: vec![],
+          force: false,
+          output_path: Some(PathBuf::from("out_dir")),
+          check: true,
+          json: false,
+          import_map_only: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "vendor", "--check", "mod.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn vendor_json() {
+    let r = flags_from_vec(svec!["deno", "vendor", "--json", "mod.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: svec!["mod.ts"],
+          force: false,
+          output_path: None,
+          check: false,
+          json: true,
+          import_map_only: None,
+        }),
+        log_level: Some(Level::Error),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "vendor",
+      "--log-level=debug",
+      "--json",
+      "mod.ts"
+    ]);
+    assert_eq!(r.unwrap().log_level, Some(Level::Debug));
+
+    let r = flags_from_vec(svec!["deno", "vendor", "--check", "--json"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn vendor_import_map_only() {
+    let r =
+      flags_from_vec(svec!["deno", "vendor", "--import-map-only", "mod.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Vendor(VendorFlags {
+          specifiers: svec!["mod.ts"],
+          force: false,
+          output_path: None,
+          check: false,
+          json: false,
+          import_map_only: Some(PathBuf::from("import_map.json")),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "vendor",
+      "--import-map-only=deps.json",
+      "mod.ts"
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Vendor(VendorFlags {
+        specifiers: svec!["mod.ts"],
+        force: false,
+        output_path: None,
+        check: false,
+        json: false,
+        import_map_only: Some(PathBuf::from("deps.json")),
+      })
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "vendor",
+      "--import-map-only",
+      "--output",
+      "out_dir",
+      "mod.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn storage() {
+    let r = flags_from_vec(svec!["deno", "storage", "list"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Vendor(VendorFlags {
-          specifiers: svec!["mod.ts"],
-          force: false,
-          output_path: None,
+        subcommand: DenoSubcommand::Storage(StorageFlags::List),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "storage", "show", "https://example.com"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Storage(StorageFlags::Show {
+          origin: "https://example.com".to_string(),
         }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn vendor_all() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "vendor",
-      "--config",
-      "deno.json",
-      "--import-map",
-      "import_map.json",
-      "--lock",
-      "lock.json",
-      "--force",
-      "--output",
-      "out_dir",
-      "--reload",
-      "mod.ts",
-      "deps.test.ts",
-    ]);
+    let r =
+      flags_from_vec(svec!["deno", "storage", "clear", "https://example.com"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Vendor(VendorFlags {
-          specifiers: svec!["mod.ts", "deps.test.ts"],
-          force: true,
-          output_path: Some(PathBuf::from("out_dir")),
+        subcommand: DenoSubcommand::Storage(StorageFlags::Clear {
+          origin: "https://example.com".to_string(),
         }),
-        config_flag: ConfigFlag::Path("deno.json".to_owned()),
-        import_map_path: Some("import_map.json".to_string()),
-        lock: Some(PathBuf::from("lock.json")),
-        reload: true,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "storage", "clear"]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -6347,6 +10444,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         argv: svec!["hello", "world"],
         ..Flags::default()
@@ -6360,6 +10461,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         ..Flags::default()
       }
@@ -6372,6 +10477,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: Some("foo".to_string()),
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         ..Flags::default()
       }
@@ -6396,9 +10505,13 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         argv: svec!["--", "hello", "world"],
-        config_flag: ConfigFlag::Path("deno.json".to_owned()),
+        config_flag: ConfigFlag::Path("deno.json".to_string()),
         ..Flags::default()
       }
     );
@@ -6412,6 +10525,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: Some("foo".to_string()),
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         argv: svec!["--", "hello", "world"],
         ..Flags::default()
@@ -6429,6 +10546,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         argv: svec!["--"],
         ..Flags::default()
@@ -6445,6 +10566,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         argv: svec!["-1", "--test"],
         ..Flags::default()
@@ -6461,6 +10586,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         argv: svec!["--test"],
         ..Flags::default()
@@ -6479,6 +10608,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         unstable: true,
         log_level: Some(log::Level::Error),
@@ -6496,6 +10629,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         ..Flags::default()
       }
@@ -6511,6 +10648,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         ..Flags::default()
@@ -6527,6 +10668,10 @@ mod tests {
         subcommand: DenoSubcommand::Task(TaskFlags {
           cwd: None,
           task: "".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
         }),
         config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         ..Flags::default()
@@ -6534,6 +10679,96 @@ mod tests {
     );
   }
 
+  #[test]
+  fn task_subcommand_strict_config() {
+    let r = flags_from_vec(svec!["deno", "task", "--strict-config", "build"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
+        }),
+        strict_config: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn task_subcommand_list() {
+    let r = flags_from_vec(svec!["deno", "task", "--list"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: "".to_string(),
+          list: true,
+          json: false,
+          dry_run: false,
+          show_secrets: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "task", "--list", "--json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: "".to_string(),
+          list: true,
+          json: true,
+          dry_run: false,
+          show_secrets: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "task", "--json"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn task_subcommand_dry_run() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "task",
+      "--dry-run",
+      "--show-secrets",
+      "build",
+      "--watch"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: "build".to_string(),
+          list: false,
+          json: false,
+          dry_run: true,
+          show_secrets: true,
+        }),
+        argv: svec!["--watch"],
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "task", "--show-secrets", "build"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "task", "--dry-run", "--list"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn task_subcommand_noconfig_invalid() {
     let r = flags_from_vec(svec!["deno", "task", "--no-config"]);
@@ -6568,6 +10803,16 @@ mod tests {
             include: vec![PathBuf::from("dir1/"), PathBuf::from("dir2/")],
             ignore: vec![],
           },
+          report_memory: None,
+          profile_loop: None,
+          fail_on_empty_files: false,
+          save_baseline: None,
+          baseline: None,
+          threshold: None,
+          strict: false,
+          watch_run_all: false,
+          jobs: None,
+          warmup_only_parallel: false,
         }),
         unstable: true,
         type_check_mode: TypeCheckMode::Local,
@@ -6580,6 +10825,81 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bench_baseline() {
+    let r = flags_from_vec(svec!["deno", "bench", "--save-baseline=main.json"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        save_baseline: Some(PathBuf::from("main.json")),
+        ..BenchFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--baseline=main.json",
+      "--threshold=10%",
+      "--strict"
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        baseline: Some(PathBuf::from("main.json")),
+        threshold: Some(10),
+        strict: true,
+        ..BenchFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--baseline=main.json",
+      "--threshold=2"
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        baseline: Some(PathBuf::from("main.json")),
+        threshold: Some(2),
+        ..BenchFlags::default()
+      })
+    );
+
+    // the threshold and --strict apply to a baseline
+    let r = flags_from_vec(svec!["deno", "bench", "--threshold=5%"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "bench", "--strict"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--baseline=main.json",
+      "--threshold=five"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn bench_watch_run_all() {
+    let r =
+      flags_from_vec(svec!["deno", "bench", "--watch", "--watch-run-all"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags.subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        watch_run_all: true,
+        ..BenchFlags::default()
+      })
+    );
+    assert_eq!(flags.watch, Some(vec![]));
+
+    let r = flags_from_vec(svec!["deno", "bench", "--watch-run-all"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn bench_watch() {
     let r = flags_from_vec(svec!["deno", "bench", "--watch"]);
@@ -6593,6 +10913,16 @@ mod tests {
             include: vec![],
             ignore: vec![],
           },
+          report_memory: None,
+          profile_loop: None,
+          fail_on_empty_files: false,
+          save_baseline: None,
+          baseline: None,
+          threshold: None,
+          strict: false,
+          watch_run_all: false,
+          jobs: None,
+          warmup_only_parallel: false,
         }),
         no_prompt: true,
         type_check_mode: TypeCheckMode::Local,
@@ -6602,6 +10932,85 @@ mod tests {
     );
   }
 
+  #[test]
+  fn bench_profile_loop() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--filter",
+      "parse",
+      "--profile-loop"
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        filter: Some("parse".to_string()),
+        profile_loop: Some(None),
+        ..BenchFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--filter",
+      "parse",
+      "--profile-loop=30s"
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        filter: Some("parse".to_string()),
+        profile_loop: Some(Some(Duration::from_secs(30))),
+        ..BenchFlags::default()
+      })
+    );
+
+    // a filter is required
+    let r = flags_from_vec(svec!["deno", "bench", "--profile-loop"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--filter",
+      "parse",
+      "--profile-loop=soon"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn bench_jobs() {
+    let r = flags_from_vec(svec!["deno", "bench", "--jobs=4"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        jobs: Some(NonZeroUsize::new(4).unwrap()),
+        ..BenchFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "bench",
+      "--jobs=2",
+      "--warmup-only-parallel"
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        jobs: Some(NonZeroUsize::new(2).unwrap()),
+        warmup_only_parallel: true,
+        ..BenchFlags::default()
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "bench", "--jobs=0"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "bench", "--warmup-only-parallel"]);
+    assert!(r.is_err());
+  }
+
   #[test]
   fn run_with_check() {
     let r = flags_from_vec(svec!["deno", "run", "--check", "script.ts",]);
@@ -6681,7 +11090,19 @@ mod tests {
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags { dir: None }),
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          lib: false,
+          name: None,
+          yes: false,
+          no_tests: false,
+          fmt: false,
+          fmt_use_tabs: false,
+          fmt_line_width: None,
+          fmt_single_quote: false,
+          lint: false,
+          jsx: None,
+        }),
         ..Flags::default()
       }
     );
@@ -6692,6 +11113,16 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Init(InitFlags {
           dir: Some(String::from("foo")),
+          lib: false,
+          name: None,
+          yes: false,
+          no_tests: false,
+          fmt: false,
+          fmt_use_tabs: false,
+          fmt_line_width: None,
+          fmt_single_quote: false,
+          lint: false,
+          jsx: None,
         }),
         ..Flags::default()
       }
@@ -6701,10 +11132,94 @@ mod tests {
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags { dir: None }),
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          lib: false,
+          name: None,
+          yes: false,
+          no_tests: false,
+          fmt: false,
+          fmt_use_tabs: false,
+          fmt_line_width: None,
+          fmt_single_quote: false,
+          lint: false,
+          jsx: None,
+        }),
         log_level: Some(Level::Error),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "init",
+      "--lib",
+      "--name",
+      "@scope/foo",
+      "foo"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: Some(String::from("foo")),
+          lib: true,
+          name: Some(String::from("@scope/foo")),
+          yes: false,
+          no_tests: false,
+          fmt: false,
+          fmt_use_tabs: false,
+          fmt_line_width: None,
+          fmt_single_quote: false,
+          lint: false,
+          jsx: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "init", "--name", "foo"]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Init(InitFlags {
+        name: Some(String::from("foo")),
+        ..InitFlags::default()
+      })
+    );
+  }
+
+  #[test]
+  fn init_project_options() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "init",
+      "--yes",
+      "--no-tests",
+      "--fmt-line-width=100",
+      "--fmt-single-quote",
+      "--lint",
+      "--jsx=preact",
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Init(InitFlags {
+        dir: None,
+        lib: false,
+        name: None,
+        yes: true,
+        no_tests: true,
+        fmt: true,
+        fmt_use_tabs: false,
+        fmt_line_width: Some(NonZeroU32::new(100).unwrap()),
+        fmt_single_quote: true,
+        lint: true,
+        jsx: Some(InitJsxFramework::Preact),
+      })
+    );
+
+    let r = flags_from_vec(svec!["deno", "init", "--lib", "--fmt"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "init", "--jsx=vue"]);
+    assert!(r.is_err());
   }
 }
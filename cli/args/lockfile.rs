@@ -1,5 +1,8 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 use deno_core::error::AnyError;
+use deno_core::ModuleSpecifier;
+use deno_runtime::colors;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use crate::args::config_file::LockConfig;
@@ -9,6 +12,8 @@ use crate::Flags;
 
 use super::DenoSubcommand;
 
+pub use deno_lockfile::IntegrityFailure;
+pub use deno_lockfile::IntegrityFailureKind;
 pub use deno_lockfile::Lockfile;
 pub use deno_lockfile::LockfileError;
 use deno_lockfile::NpmPackageDependencyLockfileInfo;
@@ -61,6 +66,141 @@ pub fn discover(
   Ok(Some(lockfile))
 }
 
+/// The length at which checksums are truncated in non-verbose output.
+const TRUNCATED_CHECKSUM_LEN: usize = 16;
+
+fn truncate_checksum(checksum: &str, verbose: bool) -> String {
+  if verbose || checksum.chars().count() <= TRUNCATED_CHECKSUM_LEN {
+    checksum.to_string()
+  } else {
+    let prefix = checksum
+      .chars()
+      .take(TRUNCATED_CHECKSUM_LEN - 3)
+      .collect::<String>();
+    format!("{prefix}...")
+  }
+}
+
+fn describe_failure_kind(kind: IntegrityFailureKind) -> &'static str {
+  match kind {
+    IntegrityFailureKind::ContentChanged => "Content changed",
+    IntegrityFailureKind::NpmIntegrityChanged => {
+      "npm package integrity changed"
+    }
+  }
+}
+
+/// Formats the integrity check failures of a lockfile as a table for each
+/// kind of failure, followed by the command that regenerates the lockfile
+/// for the given roots. Checksums are truncated unless `verbose` is set.
+pub fn format_lockfile_error(
+  error: &LockfileError,
+  roots: &[ModuleSpecifier],
+  verbose: bool,
+) -> String {
+  let mut failures_by_kind: BTreeMap<_, Vec<&IntegrityFailure>> =
+    BTreeMap::new();
+  for failure in &error.failures {
+    failures_by_kind
+      .entry(failure.kind)
+      .or_default()
+      .push(failure);
+  }
+
+  let specifier_width = error
+    .failures
+    .iter()
+    .map(|f| f.specifier.chars().count())
+    .chain(["Specifier".len()])
+    .max()
+    .unwrap();
+  let expected_width = error
+    .failures
+    .iter()
+    .map(|f| truncate_checksum(&f.expected, verbose).chars().count())
+    .chain(["Expected".len()])
+    .max()
+    .unwrap();
+
+  let mut output = format!(
+    "Integrity check failed for {} {} in the lockfile at \"{}\".\n",
+    error.failures.len(),
+    if error.failures.len() == 1 {
+      "entry"
+    } else {
+      "entries"
+    },
+    error.filename.display(),
+  );
+  for (kind, failures) in failures_by_kind {
+    output.push_str(&format!(
+      "\n{} ({}):\n  {}\n",
+      colors::bold(describe_failure_kind(kind)),
+      failures.len(),
+      colors::gray(format!(
+        "{:specifier_width$}  {:expected_width$}  Actual",
+        "Specifier", "Expected"
+      )),
+    ));
+    for failure in failures {
+      let expected = truncate_checksum(&failure.expected, verbose);
+      output.push_str(&format!(
+        "  {:specifier_width$}  {}  {}\n",
+        failure.specifier,
+        colors::green(format!("{expected:expected_width$}")),
+        colors::red(truncate_checksum(&failure.actual, verbose)),
+      ));
+    }
+  }
+
+  let is_truncated = error.failures.iter().any(|f| {
+    truncate_checksum(&f.expected, verbose) != f.expected
+      || truncate_checksum(&f.actual, verbose) != f.actual
+  });
+  if is_truncated {
+    output.push_str(&format!(
+      "\n{}\n",
+      colors::gray(
+        "Checksums are truncated, run with \"--log-level=debug\" to show them in full."
+      )
+    ));
+  }
+
+  let roots = if roots.is_empty() {
+    "<entrypoint>".to_string()
+  } else {
+    roots.iter().map(display_root).collect::<Vec<_>>().join(" ")
+  };
+  output.push_str(&format!(
+    concat!(
+      "\nIf the sources were changed on purpose, regenerate the lockfile with:\n",
+      "  {}",
+    ),
+    colors::cyan(format!(
+      "deno cache --reload --lock={} --lock-write {}",
+      error.filename.display(),
+      roots,
+    )),
+  ));
+  output
+}
+
+/// Displays local roots as paths relative to the current directory, which is
+/// how they are usually passed on the command line.
+fn display_root(root: &ModuleSpecifier) -> String {
+  if root.scheme() == "file" {
+    if let Ok(path) = root.to_file_path() {
+      let cwd = std::env::current_dir().unwrap_or_default();
+      return path
+        .strip_prefix(&cwd)
+        .unwrap_or(&path)
+        .display()
+        .to_string();
+    }
+  }
+  root.to_string()
+}
+
 // NOTE(bartlomieju): we don't want a reverse mapping to be possible.
 #[allow(clippy::from_over_into)]
 impl Into<NpmPackageLockfileInfo> for NpmResolutionPackage {
@@ -82,3 +222,77 @@ impl Into<NpmPackageLockfileInfo> for NpmResolutionPackage {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use test_util::strip_ansi_codes;
+
+  fn failure(
+    kind: IntegrityFailureKind,
+    specifier: &str,
+    expected: &str,
+    actual: &str,
+  ) -> IntegrityFailure {
+    IntegrityFailure {
+      kind,
+      specifier: specifier.to_string(),
+      expected: expected.to_string(),
+      actual: actual.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_format_lockfile_error() {
+    let error = LockfileError {
+      filename: PathBuf::from("deno.lock"),
+      failures: vec![
+        failure(
+          IntegrityFailureKind::NpmIntegrityChanged,
+          "npm:chalk@5.0.0",
+          "sha512-foobar",
+          "sha512-0123456789abcdef",
+        ),
+        failure(
+          IntegrityFailureKind::ContentChanged,
+          "https://deno.land/x/mod.ts",
+          "0123456789abcdef0123",
+          "fedcba9876543210fedc",
+        ),
+        failure(
+          IntegrityFailureKind::ContentChanged,
+          "https://deno.land/x/a.ts",
+          "bad",
+          "bad2",
+        ),
+      ],
+    };
+    let roots = [ModuleSpecifier::parse("https://deno.land/x/mod.ts").unwrap()];
+    assert_eq!(
+      strip_ansi_codes(&format_lockfile_error(&error, &roots, false)),
+      r#"Integrity check failed for 3 entries in the lockfile at "deno.lock".
+
+Content changed (2):
+  Specifier                   Expected          Actual
+  https://deno.land/x/mod.ts  0123456789abc...  fedcba9876543...
+  https://deno.land/x/a.ts    bad               bad2
+
+npm package integrity changed (1):
+  Specifier                   Expected          Actual
+  npm:chalk@5.0.0             sha512-foobar     sha512-012345...
+
+Checksums are truncated, run with "--log-level=debug" to show them in full.
+
+If the sources were changed on purpose, regenerate the lockfile with:
+  deno cache --reload --lock=deno.lock --lock-write https://deno.land/x/mod.ts"#
+    );
+
+    let output =
+      strip_ansi_codes(&format_lockfile_error(&error, &[], true)).to_string();
+    assert!(output.contains(
+      "  https://deno.land/x/mod.ts  0123456789abcdef0123  fedcba9876543210fedc\n"
+    ));
+    assert!(!output.contains("Checksums are truncated"));
+    assert!(output.ends_with("--lock-write <entrypoint>"));
+  }
+}
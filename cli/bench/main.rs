@@ -14,6 +14,7 @@ use std::process::Stdio;
 use std::time::SystemTime;
 
 mod http;
+#[cfg(feature = "lsp")]
 mod lsp;
 
 fn read_json(filename: &str) -> Result<Value> {
@@ -471,6 +472,7 @@ async fn main() -> Result<()> {
     new_data.cargo_deps = cargo_deps;
   }
 
+  #[cfg(feature = "lsp")]
   if benchmarks.contains(&"lsp") {
     let lsp_exec_times = lsp::benchmarks(&deno_exe)?;
     new_data.lsp_exec_time = lsp_exec_times;
@@ -325,13 +325,15 @@ fn create_cli_snapshot(snapshot_path: PathBuf) {
     deno_fetch::init::<PermissionsContainer>(Default::default()),
     deno_cache::init::<SqliteBackedCache>(None),
     deno_websocket::init::<PermissionsContainer>("".to_owned(), None, None),
-    deno_webstorage::init(None),
+    deno_webstorage::init(None, Default::default()),
     deno_crypto::init(None),
+    #[cfg(feature = "webgpu")]
     deno_webgpu::init(false),
     deno_broadcast_channel::init(
       deno_broadcast_channel::InMemoryBroadcastChannel::default(),
       false, // No --unstable.
     ),
+    #[cfg(feature = "npm")]
     deno_node::init::<PermissionsContainer>(None), // No --unstable.
     deno_ffi::init::<PermissionsContainer>(false),
     deno_net::init::<PermissionsContainer>(
@@ -394,19 +396,7 @@ fn git_commit_hash() -> String {
   }
 }
 
-fn main() {
-  // Skip building from docs.rs.
-  if env::var_os("DOCS_RS").is_some() {
-    return;
-  }
-
-  // Host snapshots won't work when cross compiling.
-  let target = env::var("TARGET").unwrap();
-  let host = env::var("HOST").unwrap();
-  if target != host {
-    panic!("Cross compiling with snapshot is not supported.");
-  }
-
+fn export_napi_symbols() {
   let symbols_path = std::path::Path::new("napi").join(
     format!("generated_symbol_exports_list_{}.def", env::consts::OS).as_str(),
   )
@@ -443,6 +433,25 @@ fn main() {
       );
     }
   }
+}
+
+fn main() {
+  // Skip building from docs.rs.
+  if env::var_os("DOCS_RS").is_some() {
+    return;
+  }
+
+  // Host snapshots won't work when cross compiling.
+  let target = env::var("TARGET").unwrap();
+  let host = env::var("HOST").unwrap();
+  if target != host {
+    panic!("Cross compiling with snapshot is not supported.");
+  }
+
+  // The Node-API symbols are only exported when the `napi` feature is on.
+  if env::var_os("CARGO_FEATURE_NAPI").is_some() {
+    export_napi_symbols();
+  }
 
   // To debug snapshot issues uncomment:
   // op_fetch_asset::trace_serializer();
@@ -464,8 +473,11 @@ fn main() {
   let c = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
   let o = PathBuf::from(env::var_os("OUT_DIR").unwrap());
 
-  let compiler_snapshot_path = o.join("COMPILER_SNAPSHOT.bin");
-  ts::create_compiler_snapshot(compiler_snapshot_path, &c);
+  // The TypeScript compiler is only embedded with the `tsc` feature.
+  if env::var_os("CARGO_FEATURE_TSC").is_some() {
+    let compiler_snapshot_path = o.join("COMPILER_SNAPSHOT.bin");
+    ts::create_compiler_snapshot(compiler_snapshot_path, &c);
+  }
 
   let cli_snapshot_path = o.join("CLI_SNAPSHOT.bin");
   create_cli_snapshot(cli_snapshot_path);
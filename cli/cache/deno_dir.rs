@@ -103,6 +103,11 @@ impl DenoDir {
     self.root.join("latest.txt")
   }
 
+  /// Folder used for keeping interrupted downloads of `deno upgrade`.
+  pub fn upgrade_downloads_folder_path(&self) -> PathBuf {
+    self.root.join("upgrade")
+  }
+
   /// Folder used for the npm cache.
   pub fn npm_folder_path(&self) -> PathBuf {
     self.root.join("npm")
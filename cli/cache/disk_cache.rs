@@ -110,6 +110,8 @@ impl DiskCache {
 
         out = out.join(remaining_components);
       }
+      // the custom schemes of embedders
+      _ if url.host_str().is_some() => out = url_to_filename(url)?,
       _ => return None,
     };
 
@@ -204,6 +206,10 @@ mod tests {
         "https/deno.land/d8300752800fe3f0beda9505dc1c3b5388beb1ee45afd1f1e2c9fc0866df15cf",
       ),
       ("wasm://wasm/d1c677ea", "wasm/wasm/d1c677ea"),
+      (
+        "unknown://localhost/test.ts",
+        "unknown/localhost/99405eb3050dc0cdedbc91ddee3a2195ccf8d7b690a3120d6b46b8cfbd7dcfc2",
+      ),
     ];
 
     if cfg!(target_os = "windows") {
@@ -294,7 +300,7 @@ mod tests {
 
     let cache = DiskCache::new(&cache_location);
 
-    let mut test_cases = vec!["unknown://localhost/test.ts"];
+    let mut test_cases = vec!["unknown:test.ts"];
 
     if cfg!(target_os = "windows") {
       test_cases.push("file://");
@@ -31,17 +31,17 @@ fn base_url_to_filename(url: &Url) -> Option<PathBuf> {
   let scheme = url.scheme();
   out.push(scheme);
 
-  match scheme {
-    "http" | "https" => {
-      let host = url.host_str().unwrap();
+  match (scheme, url.host_str()) {
+    ("data" | "blob", _) => (),
+    // the custom schemes of embedders are cached per host like http(s)
+    (_, Some(host)) => {
       let host_port = match url.port() {
         Some(port) => format!("{host}_PORT{port}"),
         None => host.to_string(),
       };
       out.push(host_port);
     }
-    "data" | "blob" => (),
-    scheme => {
+    (scheme, None) => {
       error!("Don't know how to create cache name for scheme: {}", scheme);
       return None;
     }
@@ -272,7 +272,11 @@ mod tests {
       (
         "data:text/plain,Hello%2C%20Deno!",
         "data/967374e3561d6741234131e342bf5c6848b70b13758adfe23ee1a813a8131818",
-      )
+      ),
+      (
+        "corp://artifacts/pkg/mod.ts",
+        "corp/artifacts/2bd114175ab7753a15db46ebf9d4c099d35846af16d55bd7057c3891983c16d4",
+      ),
     ];
 
     for (url, expected) in test_cases.iter() {
@@ -3,6 +3,7 @@
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
@@ -41,6 +42,11 @@ impl IncrementalCache {
     })
   }
 
+  /// A cache which never skips a file and isn't written.
+  pub fn disabled() -> Self {
+    IncrementalCache(None)
+  }
+
   pub fn is_file_same(&self, file_path: &Path, file_text: &str) -> bool {
     if let Some(inner) = &self.0 {
       inner.is_file_same(file_path, file_text)
@@ -105,14 +111,16 @@ impl IncrementalCacheInner {
 
     // sqlite isn't `Sync`, so we do all the updating on a dedicated task
     let handle = tokio::task::spawn(async move {
+      let mut updates = Vec::new();
       while let Some(message) = receiver.recv().await {
         match message {
-          ReceiverMessage::Update(path, hash) => {
-            let _ = cache.set_source_hash(&path, hash);
-          }
+          ReceiverMessage::Update(path, hash) => updates.push((path, hash)),
           ReceiverMessage::Exit => break,
         }
       }
+      if let Err(err) = cache.set_source_hashes(&updates) {
+        log::debug!("Updating the incremental cache failed.\n{:#}", err);
+      }
     });
 
     IncrementalCacheInner {
@@ -166,6 +174,9 @@ impl SqlIncrementalCache {
   pub fn new(db_file_path: &Path, state_hash: u64) -> Result<Self, AnyError> {
     log::debug!("Loading incremental cache.");
     let conn = Connection::open(db_file_path)?;
+    // wait for other processes using the cache, like parallel invocations of
+    // `deno fmt`, instead of failing
+    conn.busy_timeout(Duration::from_secs(5))?;
     Self::from_connection(conn, state_hash, crate::version::deno())
   }
 
@@ -236,6 +247,23 @@ impl SqlIncrementalCache {
     ])?;
     Ok(())
   }
+
+  /// Sets the hashes of the files in a single transaction, so that the cache
+  /// either has all of them or none of them.
+  pub fn set_source_hashes(
+    &self,
+    hashes: &[(PathBuf, u64)],
+  ) -> Result<(), AnyError> {
+    if hashes.is_empty() {
+      return Ok(());
+    }
+    let transaction = self.conn.unchecked_transaction()?;
+    for (path, source_hash) in hashes {
+      self.set_source_hash(path, *source_hash)?;
+    }
+    transaction.commit()?;
+    Ok(())
+  }
 }
 
 fn create_tables(
@@ -328,6 +356,13 @@ mod test {
     cache.set_source_hash(&path2, 5).unwrap();
     assert_eq!(cache.get_source_hash(&path), Some(4));
     assert_eq!(cache.get_source_hash(&path2), Some(5));
+
+    // and setting several hashes at once
+    cache
+      .set_source_hashes(&[(path.clone(), 6), (path2.clone(), 7)])
+      .unwrap();
+    assert_eq!(cache.get_source_hash(&path), Some(6));
+    assert_eq!(cache.get_source_hash(&path2), Some(7));
   }
 
   #[tokio::test]
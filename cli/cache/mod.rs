@@ -6,12 +6,14 @@ use crate::npm;
 
 use deno_core::futures;
 use deno_core::futures::FutureExt;
+use deno_core::parking_lot::Mutex;
 use deno_core::ModuleSpecifier;
 use deno_graph::source::CacheInfo;
 use deno_graph::source::LoadFuture;
 use deno_graph::source::LoadResponse;
 use deno_graph::source::Loader;
 use deno_runtime::permissions::PermissionsContainer;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 mod check;
@@ -46,6 +48,7 @@ pub struct FetchCacher {
   file_fetcher: Arc<FileFetcher>,
   root_permissions: PermissionsContainer,
   cache_info_enabled: bool,
+  maybe_types_headers: Option<Arc<Mutex<HashMap<ModuleSpecifier, String>>>>,
 }
 
 impl FetchCacher {
@@ -61,6 +64,7 @@ impl FetchCacher {
       file_fetcher,
       root_permissions,
       cache_info_enabled: false,
+      maybe_types_headers: None,
     }
   }
 
@@ -69,6 +73,22 @@ impl FetchCacher {
   pub fn enable_loading_cache_info(&mut self) {
     self.cache_info_enabled = true;
   }
+
+  /// Records the `X-TypeScript-Types` header of the loaded modules, since the
+  /// graph doesn't tell a types dependency from a header apart from one from
+  /// a directive. It should only be enabled for deno info.
+  pub fn enable_recording_types_headers(&mut self) {
+    self.maybe_types_headers = Some(Default::default());
+  }
+
+  /// The `X-TypeScript-Types` headers recorded for the loaded modules.
+  pub fn types_headers(&self) -> HashMap<ModuleSpecifier, String> {
+    self
+      .maybe_types_headers
+      .as_ref()
+      .map(|types_headers| types_headers.lock().clone())
+      .unwrap_or_default()
+  }
 }
 
 impl Loader for FetchCacher {
@@ -139,6 +159,7 @@ impl Loader for FetchCacher {
       self.root_permissions.clone()
     };
     let file_fetcher = self.file_fetcher.clone();
+    let maybe_types_headers = self.maybe_types_headers.clone();
 
     async move {
       file_fetcher
@@ -156,6 +177,17 @@ impl Loader for FetchCacher {
             Err(err)
           },
           |file| {
+            if let Some(types_headers) = &maybe_types_headers {
+              if let Some(types_header) = file
+                .maybe_headers
+                .as_ref()
+                .and_then(|headers| headers.get("x-typescript-types"))
+              {
+                types_headers
+                  .lock()
+                  .insert(file.specifier.clone(), types_header.clone());
+              }
+            }
             Ok(Some(LoadResponse::Module {
               specifier: file.specifier,
               maybe_headers: file.maybe_headers,
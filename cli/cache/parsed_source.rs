@@ -67,12 +67,25 @@ impl ParsedSourceCache {
     }
   }
 
-  pub fn reset_for_file_watcher(&self) -> Self {
-    Self {
-      db_cache_path: self.db_cache_path.clone(),
-      cli_version: self.cli_version.clone(),
-      sources: Default::default(),
+  /// Prepares the cache for a file watcher restart. Parsed sources of the
+  /// changed paths are freed and the rest are kept so they don't need to be
+  /// parsed again. When the changed paths are unknown, everything is freed.
+  pub fn reset_for_file_watcher(
+    &self,
+    changed_paths: Option<&[PathBuf]>,
+  ) -> Self {
+    match changed_paths {
+      Some(changed_paths) => {
+        let mut sources = self.sources.0.lock();
+        for path in changed_paths {
+          if let Ok(specifier) = ModuleSpecifier::from_file_path(path) {
+            sources.remove(&specifier);
+          }
+        }
+      }
+      None => self.sources.0.lock().clear(),
     }
+    self.clone()
   }
 
   pub fn get_parsed_source_from_module(
@@ -40,8 +40,16 @@ pub fn emit_parsed_source(
       media_type,
     )?;
     let transpiled_source = parsed_source.transpile(emit_options)?;
-    debug_assert!(transpiled_source.source_map.is_none());
-    emit_cache.set_emit_code(specifier, source_hash, &transpiled_source.text);
-    Ok(transpiled_source.text)
+    let mut code = transpiled_source.text;
+    if let Some(source_map) = transpiled_source.source_map {
+      // errors are only mapped with inline source maps, so inline the source
+      // map that the compiler options asked to emit separately
+      code.push_str(&format!(
+        "\n//# sourceMappingURL=data:application/json;base64,{}",
+        base64::encode(source_map)
+      ));
+    }
+    emit_cache.set_emit_code(specifier, source_hash, &code);
+    Ok(code)
   }
 }
@@ -10,6 +10,8 @@ use crate::http_util::resolve_redirect_from_response;
 use crate::http_util::CacheSemantics;
 use crate::http_util::HeadersMap;
 use crate::http_util::HttpClient;
+use crate::scheme_handlers::SchemeHandler;
+use crate::scheme_handlers::SchemeHandlers;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::UpdateGuard;
 use crate::util::text_encoding;
@@ -35,14 +37,20 @@ use deno_runtime::permissions::PermissionsContainer;
 use log::debug;
 use std::borrow::Borrow;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::future::Future;
 use std::io::Read;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::SystemTime;
+use tokio::sync::OwnedSemaphorePermit;
+use tokio::sync::Semaphore;
 
 pub const SUPPORTED_SCHEMES: [&str; 5] =
   ["data", "blob", "file", "http", "https"];
@@ -170,6 +178,99 @@ pub fn map_content_type(
   }
 }
 
+/// Counts of the remote resources that were downloaded or reused from the
+/// cache. Shared between clones of the structure that records them.
+#[derive(Debug, Default)]
+pub struct FetchStats {
+  downloaded: AtomicUsize,
+  cached: AtomicUsize,
+  bytes: AtomicU64,
+  downloads: Mutex<HashSet<String>>,
+}
+
+impl FetchStats {
+  pub fn record_download(&self, name: &str, bytes: usize) {
+    self.downloaded.fetch_add(1, Ordering::Relaxed);
+    self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    self.downloads.lock().insert(name.to_string());
+  }
+
+  pub fn record_cached(&self) {
+    self.cached.fetch_add(1, Ordering::Relaxed);
+  }
+
+  /// The number of resources that were downloaded.
+  pub fn downloaded(&self) -> usize {
+    self.downloaded.load(Ordering::Relaxed)
+  }
+
+  /// The number of resources that were reused from the cache.
+  pub fn cached(&self) -> usize {
+    self.cached.load(Ordering::Relaxed)
+  }
+
+  /// The total number of bytes downloaded.
+  pub fn bytes(&self) -> u64 {
+    self.bytes.load(Ordering::Relaxed)
+  }
+
+  /// If the resource with the provided name was downloaded.
+  pub fn was_downloaded(&self, name: &str) -> bool {
+    self.downloads.lock().contains(name)
+  }
+}
+
+/// The default number of concurrent downloads allowed to a single host.
+const DEFAULT_FETCH_CONCURRENCY: usize = 32;
+
+/// Gets the per-host download concurrency from the value of the
+/// `DENO_FETCH_CONCURRENCY` environment variable, falling back to the
+/// default when it is not set or invalid.
+fn resolve_fetch_concurrency(maybe_value: Option<&str>) -> usize {
+  match maybe_value.map(|value| value.trim().parse::<usize>()) {
+    Some(Ok(value)) if value > 0 => value,
+    Some(_) => {
+      log::warn!(
+        "{} Ignoring invalid DENO_FETCH_CONCURRENCY value. Expected a positive integer.",
+        colors::yellow("Warning"),
+      );
+      DEFAULT_FETCH_CONCURRENCY
+    }
+    None => DEFAULT_FETCH_CONCURRENCY,
+  }
+}
+
+/// Limits the number of downloads in flight to a single host. Shared between
+/// clones of the structure so that concurrent graph loads share the limit.
+#[derive(Debug, Clone)]
+struct DownloadLimiter {
+  max_per_host: usize,
+  semaphores: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+}
+
+impl DownloadLimiter {
+  pub fn new(max_per_host: usize) -> Self {
+    Self {
+      max_per_host,
+      semaphores: Default::default(),
+    }
+  }
+
+  pub async fn acquire(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> OwnedSemaphorePermit {
+    let semaphore = self
+      .semaphores
+      .lock()
+      .entry(specifier.origin().ascii_serialization())
+      .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_host)))
+      .clone();
+    // the semaphore is never closed
+    semaphore.acquire_owned().await.unwrap()
+  }
+}
+
 /// A structure for resolving, fetching and caching source files.
 #[derive(Debug, Clone)]
 pub struct FileFetcher {
@@ -182,6 +283,9 @@ pub struct FileFetcher {
   blob_store: BlobStore,
   download_log_level: log::Level,
   progress_bar: Option<ProgressBar>,
+  stats: Arc<FetchStats>,
+  download_limiter: DownloadLimiter,
+  scheme_handlers: SchemeHandlers,
 }
 
 impl FileFetcher {
@@ -203,14 +307,35 @@ impl FileFetcher {
       blob_store,
       download_log_level: log::Level::Info,
       progress_bar,
+      stats: Default::default(),
+      download_limiter: DownloadLimiter::new(resolve_fetch_concurrency(
+        env::var("DENO_FETCH_CONCURRENCY").ok().as_deref(),
+      )),
+      scheme_handlers: Default::default(),
     }
   }
 
+  /// Statistics about the remote files fetched by this file fetcher.
+  pub fn stats(&self) -> &FetchStats {
+    &self.stats
+  }
+
+  /// The statistics, shared with any clone.
+  pub fn shared_stats(&self) -> Arc<FetchStats> {
+    self.stats.clone()
+  }
+
   /// Sets the log level to use when outputting the download message.
+  #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
   pub fn set_download_log_level(&mut self, level: log::Level) {
     self.download_log_level = level;
   }
 
+  /// Sets the handlers used to fetch the modules of custom schemes.
+  pub fn set_scheme_handlers(&mut self, scheme_handlers: SchemeHandlers) {
+    self.scheme_handlers = scheme_handlers;
+  }
+
   /// Creates a `File` structure for a remote file.
   fn build_remote_file(
     &self,
@@ -394,6 +519,45 @@ impl FileFetcher {
     })
   }
 
+  /// Fetch a module of a custom scheme with the handler registered for it,
+  /// caching it like a remote module.
+  async fn fetch_from_scheme_handler(
+    &self,
+    specifier: &ModuleSpecifier,
+    handler: &SchemeHandler,
+  ) -> Result<File, AnyError> {
+    debug!(
+      "FileFetcher::fetch_from_scheme_handler() - specifier: {}",
+      specifier
+    );
+    if self.should_use_cache(specifier) {
+      if let Some(file) = self.fetch_cached(specifier, 0)? {
+        self.stats.record_cached();
+        return Ok(file);
+      }
+    }
+
+    if self.cache_setting == CacheSetting::Only {
+      return Err(custom_error(
+        "NotCached",
+        format!(
+          "Specifier not found in cache: \"{specifier}\", --cached-only is specified."
+        ),
+      ));
+    }
+
+    let response = handler(specifier.clone()).await?;
+    self.http_cache.set(
+      specifier,
+      response.headers.clone(),
+      &response.bytes,
+    )?;
+    self
+      .stats
+      .record_download(specifier.as_str(), response.bytes.len());
+    self.build_remote_file(specifier, response.bytes, &response.headers)
+  }
+
   /// Asynchronously fetch remote source file specified by the URL following
   /// redirects.
   ///
@@ -419,6 +583,7 @@ impl FileFetcher {
     if self.should_use_cache(specifier) {
       match self.fetch_cached(specifier, redirect_limit) {
         Ok(Some(file)) => {
+          self.stats.record_cached();
           return futures::future::ok(file).boxed();
         }
         Ok(None) => {}
@@ -460,7 +625,8 @@ impl FileFetcher {
     let file_fetcher = self.clone();
     // A single pass of fetch either yields code or yields a redirect.
     async move {
-      let result = match fetch_once(
+      let permit = file_fetcher.download_limiter.acquire(&specifier).await;
+      let fetch_result = fetch_once(
         &client,
         FetchOnceArgs {
           url: specifier.clone(),
@@ -470,10 +636,13 @@ impl FileFetcher {
           maybe_progress_guard: maybe_progress_guard.as_ref(),
         },
       )
-      .await?
-      {
+      .await?;
+      // release the permit before following any redirect
+      drop(permit);
+      let result = match fetch_result {
         FetchOnceResult::NotModified => {
           let file = file_fetcher.fetch_cached(&specifier, 10)?.unwrap();
+          file_fetcher.stats.record_cached();
           Ok(file)
         }
         FetchOnceResult::Redirect(redirect_url, headers) => {
@@ -491,6 +660,9 @@ impl FileFetcher {
           file_fetcher
             .http_cache
             .set(&specifier, headers.clone(), &bytes)?;
+          file_fetcher
+            .stats
+            .record_download(specifier.as_str(), bytes.len());
           let file =
             file_fetcher.build_remote_file(&specifier, bytes, &headers)?;
           Ok(file)
@@ -553,10 +725,21 @@ impl FileFetcher {
     permissions: PermissionsContainer,
     maybe_accept: Option<&str>,
   ) -> Result<File, AnyError> {
-    let scheme = get_validated_scheme(specifier)?;
+    let maybe_handler = self.scheme_handlers.get(specifier.scheme());
+    let scheme = if maybe_handler.is_some() {
+      specifier.scheme().to_string()
+    } else {
+      get_validated_scheme(specifier)?
+    };
     permissions.check_specifier(specifier)?;
     if let Some(file) = self.cache.get(specifier) {
       Ok(file)
+    } else if let Some(handler) = maybe_handler {
+      let result = self.fetch_from_scheme_handler(specifier, &handler).await;
+      if let Ok(file) = &result {
+        self.cache.insert(specifier.clone(), file.clone());
+      }
+      result
     } else if scheme == "file" {
       // we do not in memory cache files, as this would prevent files on the
       // disk changing effecting things like workers and dynamic imports.
@@ -2232,4 +2415,39 @@ mod tests {
     // Check that the error message contains the original URL
     assert!(err.to_string().contains(url_str));
   }
+
+  #[test]
+  fn test_resolve_fetch_concurrency() {
+    assert_eq!(resolve_fetch_concurrency(None), DEFAULT_FETCH_CONCURRENCY);
+    assert_eq!(resolve_fetch_concurrency(Some("8")), 8);
+    assert_eq!(resolve_fetch_concurrency(Some(" 64 ")), 64);
+    assert_eq!(
+      resolve_fetch_concurrency(Some("0")),
+      DEFAULT_FETCH_CONCURRENCY
+    );
+    assert_eq!(
+      resolve_fetch_concurrency(Some("abc")),
+      DEFAULT_FETCH_CONCURRENCY
+    );
+  }
+
+  #[tokio::test]
+  async fn test_download_limiter() {
+    let limiter = DownloadLimiter::new(2);
+    let a = resolve_url("https://deno.land/a.ts").unwrap();
+    let b = resolve_url("https://deno.land/b.ts").unwrap();
+    let other = resolve_url("https://example.com/c.ts").unwrap();
+    let _permit_a = limiter.acquire(&a).await;
+    let permit_b = limiter.acquire(&b).await;
+    // another host has its own limit
+    let _permit_other = limiter.acquire(&other).await;
+    // the limit for deno.land is reached until a permit is released
+    let cloned_limiter = limiter.clone();
+    let cloned_a = a.clone();
+    let mut pending =
+      Box::pin(async move { cloned_limiter.acquire(&cloned_a).await });
+    assert!(futures::poll!(pending.as_mut()).is_pending());
+    drop(permit_b);
+    pending.await;
+  }
 }
@@ -1,7 +1,9 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::format_lockfile_error;
 use crate::args::CliOptions;
 use crate::args::Lockfile;
+use crate::args::LockfileError;
 use crate::args::TsConfigType;
 use crate::args::TypeCheckMode;
 use crate::cache;
@@ -77,29 +79,39 @@ pub fn graph_valid(
   })
 }
 
-/// Checks the lockfile against the graph and and exits on errors.
+/// Checks the lockfile against the graph and exits on errors, after
+/// reporting all the modules that failed the check.
 pub fn graph_lock_or_exit(graph: &ModuleGraph, lockfile: &mut Lockfile) {
+  let mut failures = Vec::new();
   for module in graph.modules() {
     if let Some(source) = &module.maybe_source {
-      if !lockfile.check_or_insert_remote(module.specifier.as_str(), source) {
-        let err = format!(
-          concat!(
-            "The source code is invalid, as it does not match the expected hash in the lock file.\n",
-            "  Specifier: {}\n",
-            "  Lock file: {}",
-          ),
-          module.specifier,
-          lockfile.filename.display(),
-        );
-        log::error!("{} {}", colors::red("error:"), err);
-        std::process::exit(10);
+      if let Err(failure) = lockfile
+        .check_or_insert_remote_integrity(module.specifier.as_str(), source)
+      {
+        failures.push(failure);
       }
     }
   }
+  if !failures.is_empty() {
+    let error = LockfileError {
+      filename: lockfile.filename.clone(),
+      failures,
+    };
+    log::error!(
+      "{} {}",
+      colors::red("error:"),
+      format_lockfile_error(
+        &error,
+        &graph.roots,
+        log::log_enabled!(log::Level::Debug)
+      )
+    );
+    std::process::exit(10);
+  }
 }
 
 pub async fn create_graph_and_maybe_check(
-  root: ModuleSpecifier,
+  roots: Vec<ModuleSpecifier>,
   ps: &ProcState,
 ) -> Result<Arc<deno_graph::ModuleGraph>, AnyError> {
   let mut cache = cache::FetchCacher::new(
@@ -119,7 +131,7 @@ pub async fn create_graph_and_maybe_check(
   let mut graph = ModuleGraph::default();
   graph
     .build(
-      vec![root],
+      roots,
       &mut cache,
       deno_graph::BuildOptions {
         is_dynamic: false,
@@ -170,6 +182,8 @@ pub async fn create_graph_and_maybe_check(
         log_checks: true,
         reload: ps.options.reload_flag(),
         has_node_builtin_specifier: npm_graph_info.has_node_builtin_specifier,
+        lib_overrides: ps.options.resolve_lib_overrides()?,
+        report_deprecations: ps.options.report_deprecations(),
       },
     )?;
     log::debug!("{}", check_result.stats);
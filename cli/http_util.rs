@@ -5,18 +5,30 @@ use crate::version::get_user_agent;
 use cache_control::Cachability;
 use cache_control::CacheControl;
 use chrono::DateTime;
-use deno_core::anyhow::bail;
+use deno_core::anyhow::anyhow;
 use deno_core::error::custom_error;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures::StreamExt;
 use deno_core::url::Url;
-use deno_runtime::deno_fetch::create_http_client;
+use deno_runtime::deno_fetch::create_http_client_builder;
 use deno_runtime::deno_fetch::reqwest;
+use deno_runtime::deno_fetch::reqwest::header::HeaderMap;
+use deno_runtime::deno_fetch::reqwest::header::HeaderValue;
+use deno_runtime::deno_fetch::reqwest::header::CONTENT_LENGTH;
+use deno_runtime::deno_fetch::reqwest::header::CONTENT_RANGE;
+use deno_runtime::deno_fetch::reqwest::header::ETAG;
+use deno_runtime::deno_fetch::reqwest::header::IF_RANGE;
+use deno_runtime::deno_fetch::reqwest::header::LAST_MODIFIED;
 use deno_runtime::deno_fetch::reqwest::header::LOCATION;
+use deno_runtime::deno_fetch::reqwest::header::RANGE;
 use deno_runtime::deno_fetch::reqwest::Response;
+use deno_runtime::deno_fetch::reqwest::StatusCode;
 use deno_runtime::deno_tls::rustls::RootCertStore;
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
 use std::time::Duration;
 use std::time::SystemTime;
 
@@ -216,34 +228,102 @@ impl CacheSemantics {
   }
 }
 
+/// A client certificate presented to servers that require mutual TLS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsClientCert {
+  /// The PEM encoded certificate chain.
+  pub cert_chain: String,
+  /// The PEM encoded private key, which can't be protected by a passphrase.
+  pub private_key: String,
+  /// The hosts to present the certificate to, or `None` to present it to
+  /// every host.
+  pub maybe_hosts: Option<Vec<String>>,
+}
+
 #[derive(Debug, Clone)]
-pub struct HttpClient(reqwest::Client);
+pub struct HttpClient {
+  client: reqwest::Client,
+  /// The client presenting a client certificate along with the hosts it's
+  /// presented to, when it's only presented to some hosts.
+  maybe_scoped_client: Option<(Vec<String>, reqwest::Client)>,
+}
 
 impl HttpClient {
   pub fn new(
     root_cert_store: Option<RootCertStore>,
     unsafely_ignore_certificate_errors: Option<Vec<String>>,
   ) -> Result<Self, AnyError> {
-    Ok(HttpClient::from_client(create_http_client(
+    Self::new_with_user_agent(
       get_user_agent(),
       root_cert_store,
-      vec![],
-      None,
       unsafely_ignore_certificate_errors,
       None,
-    )?))
+    )
+  }
+
+  pub fn new_with_user_agent(
+    user_agent: String,
+    root_cert_store: Option<RootCertStore>,
+    unsafely_ignore_certificate_errors: Option<Vec<String>>,
+    maybe_client_cert: Option<TlsClientCert>,
+  ) -> Result<Self, AnyError> {
+    let create_client = |client_cert_chain_and_key| {
+      let builder = create_http_client_builder(
+        user_agent.clone(),
+        root_cert_store.clone(),
+        vec![],
+        None,
+        unsafely_ignore_certificate_errors.clone(),
+        client_cert_chain_and_key,
+      )?;
+      // the downloads of a module graph share an HTTP/2 connection, so its
+      // flow control windows grow with the throughput
+      let client = builder.http2_adaptive_window(true).build()?;
+      Ok::<_, AnyError>(client)
+    };
+    match maybe_client_cert {
+      None => Ok(HttpClient::from_client(create_client(None)?)),
+      Some(TlsClientCert {
+        cert_chain,
+        private_key,
+        maybe_hosts: None,
+      }) => Ok(HttpClient::from_client(create_client(Some((
+        cert_chain,
+        private_key,
+      )))?)),
+      Some(TlsClientCert {
+        cert_chain,
+        private_key,
+        maybe_hosts: Some(hosts),
+      }) => {
+        let scoped_client = create_client(Some((cert_chain, private_key)))?;
+        Ok(Self {
+          client: create_client(None)?,
+          maybe_scoped_client: Some((hosts, scoped_client)),
+        })
+      }
+    }
   }
 
   pub fn from_client(client: reqwest::Client) -> Self {
-    Self(client)
+    Self {
+      client,
+      maybe_scoped_client: None,
+    }
+  }
+
+  /// Gets the client for requests to the url, which only presents the client
+  /// certificate when it's scoped to the url's host.
+  fn client_for(&self, url: &Url) -> &reqwest::Client {
+    match &self.maybe_scoped_client {
+      Some((hosts, scoped_client)) if is_host_in(url, hosts) => scoped_client,
+      _ => &self.client,
+    }
   }
 
   /// Do a GET request without following redirects.
-  pub fn get_no_redirect<U: reqwest::IntoUrl>(
-    &self,
-    url: U,
-  ) -> reqwest::RequestBuilder {
-    self.0.get(url)
+  pub fn get_no_redirect(&self, url: Url) -> reqwest::RequestBuilder {
+    self.client_for(&url).get(url)
   }
 
   pub async fn download_text<U: reqwest::IntoUrl>(
@@ -283,16 +363,7 @@ impl HttpClient {
     if response.status() == 404 {
       return Ok(None);
     } else if !response.status().is_success() {
-      let status = response.status();
-      let maybe_response_text = response.text().await.ok();
-      bail!(
-        "Bad response: {:?}{}",
-        status,
-        match maybe_response_text {
-          Some(text) => format!("\n\n{text}"),
-          None => String::new(),
-        }
-      );
+      return Err(bad_response_error(response).await);
     }
 
     get_response_body_with_progress(response, progress_guard)
@@ -300,17 +371,154 @@ impl HttpClient {
       .map(Some)
   }
 
+  /// Downloads like `download_with_progress`, but if the connection drops
+  /// the bytes received so far are kept in `partial_path` along with the
+  /// response's validator. The next download of the same file then only
+  /// requests the remaining bytes with a `Range` request guarded by
+  /// `If-Range`, and starts over when the server doesn't honor it.
+  pub async fn download_with_progress_resumable<U: reqwest::IntoUrl>(
+    &self,
+    url: U,
+    partial_path: &Path,
+    progress_guard: &UpdateGuard,
+  ) -> Result<Option<Vec<u8>>, AnyError> {
+    let url = url.into_url()?;
+    let mut maybe_partial = PartialDownload::read(partial_path);
+    loop {
+      let mut headers = HeaderMap::new();
+      if let Some(partial) = &maybe_partial {
+        headers.insert(
+          RANGE,
+          HeaderValue::from_str(&format!("bytes={}-", partial.bytes.len()))?,
+        );
+        headers.insert(IF_RANGE, HeaderValue::from_str(&partial.validator)?);
+      }
+      let response = self
+        .get_redirected_response_with_headers(url.clone(), headers)
+        .await?;
+      let status = response.status();
+      if status == StatusCode::NOT_FOUND {
+        PartialDownload::remove(partial_path);
+        return Ok(None);
+      }
+
+      let data = match maybe_partial.take() {
+        Some(partial)
+          if status == StatusCode::PARTIAL_CONTENT
+            && content_range_start(&response)
+              == Some(partial.bytes.len() as u64) =>
+        {
+          log::debug!(
+            "Resuming download of {} at {} bytes.",
+            url,
+            partial.bytes.len()
+          );
+          partial.bytes
+        }
+        Some(_)
+          if status == StatusCode::PARTIAL_CONTENT
+            || status == StatusCode::RANGE_NOT_SATISFIABLE =>
+        {
+          // the range couldn't be served, so download everything again
+          PartialDownload::remove(partial_path);
+          continue;
+        }
+        _ => Vec::new(),
+      };
+      if (status != StatusCode::OK && status != StatusCode::PARTIAL_CONTENT)
+        || (status == StatusCode::PARTIAL_CONTENT && data.is_empty())
+      {
+        return Err(bad_response_error(response).await);
+      }
+
+      let maybe_validator = response_validator(&response);
+      return match read_response_body(response, data, Some(progress_guard))
+        .await
+      {
+        Ok(data) => {
+          PartialDownload::remove(partial_path);
+          Ok(Some(data))
+        }
+        Err((data, err)) => {
+          match maybe_validator {
+            Some(validator) if !data.is_empty() => {
+              let partial = PartialDownload {
+                bytes: data,
+                validator,
+              };
+              if let Err(err) = partial.write(partial_path) {
+                log::debug!("Failed saving partial download. {:#}", err);
+              }
+            }
+            _ => PartialDownload::remove(partial_path),
+          }
+          Err(err)
+        }
+      };
+    }
+  }
+
+  /// Gets the size of a file from the `Content-Length` header of a `HEAD`
+  /// request, without downloading it. Returns `None` when the server doesn't
+  /// send the header.
+  pub async fn content_length<U: reqwest::IntoUrl>(
+    &self,
+    url: U,
+  ) -> Result<Option<u64>, AnyError> {
+    let mut url = url.into_url()?;
+    // the first request and up to 5 redirects
+    for _ in 0..6 {
+      let response = self.client_for(&url).head(url.clone()).send().await?;
+      let status = response.status();
+      if status.is_redirection() {
+        url = resolve_redirect_from_response(&url, &response)?;
+        continue;
+      }
+      if !status.is_success() {
+        return Err(bad_response_error(response).await);
+      }
+      // `Response::content_length` is the size of the (empty) body of a
+      // `HEAD` response, so the header is read instead
+      return Ok(
+        response
+          .headers()
+          .get(CONTENT_LENGTH)
+          .and_then(|value| value.to_str().ok())
+          .and_then(|value| value.parse().ok()),
+      );
+    }
+    Err(custom_error("Http", "Too many redirects."))
+  }
+
   pub async fn get_redirected_response<U: reqwest::IntoUrl>(
     &self,
     url: U,
+  ) -> Result<Response, AnyError> {
+    self
+      .get_redirected_response_with_headers(url, HeaderMap::new())
+      .await
+  }
+
+  async fn get_redirected_response_with_headers<U: reqwest::IntoUrl>(
+    &self,
+    url: U,
+    headers: HeaderMap,
   ) -> Result<Response, AnyError> {
     let mut url = url.into_url()?;
-    let mut response = self.get_no_redirect(url.clone()).send().await?;
+    let mut response = self
+      .get_no_redirect(url.clone())
+      .headers(headers.clone())
+      .send()
+      .await?;
     let status = response.status();
     if status.is_redirection() {
       for _ in 0..5 {
         let new_url = resolve_redirect_from_response(&url, &response)?;
-        let new_response = self.get_no_redirect(new_url.clone()).send().await?;
+        let new_response = self
+          .get_no_redirect(new_url.clone())
+          .headers(headers.clone())
+          .send()
+          .await?;
         let status = new_response.status();
         if status.is_redirection() {
           response = new_response;
@@ -326,6 +534,127 @@ impl HttpClient {
   }
 }
 
+/// Checks if the host of the url is one of the hosts, which may include a
+/// port.
+fn is_host_in(url: &Url, hosts: &[String]) -> bool {
+  let host = match url.host_str() {
+    Some(host) => host,
+    None => return false,
+  };
+  let maybe_host_and_port = url
+    .port_or_known_default()
+    .map(|port| format!("{host}:{port}"));
+  hosts.iter().any(|allowed| {
+    allowed == host || Some(allowed) == maybe_host_and_port.as_ref()
+  })
+}
+
+async fn bad_response_error(response: Response) -> AnyError {
+  let status = response.status();
+  let maybe_response_text = response.text().await.ok();
+  anyhow!(
+    "Bad response: {:?}{}",
+    status,
+    match maybe_response_text {
+      Some(text) => format!("\n\n{text}"),
+      None => String::new(),
+    }
+  )
+}
+
+/// The bytes of an interrupted download and the validator (a strong ETag or
+/// the last modified date) the server sent them with.
+struct PartialDownload {
+  bytes: Vec<u8>,
+  validator: String,
+}
+
+impl PartialDownload {
+  fn read(partial_path: &Path) -> Option<Self> {
+    let validator =
+      fs::read_to_string(partial_validator_path(partial_path)).ok()?;
+    let bytes = fs::read(partial_path).ok()?;
+    if bytes.is_empty() || validator.is_empty() {
+      None
+    } else {
+      Some(Self { bytes, validator })
+    }
+  }
+
+  fn write(&self, partial_path: &Path) -> Result<(), AnyError> {
+    if let Some(parent) = partial_path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+    fs::write(partial_path, &self.bytes)?;
+    fs::write(partial_validator_path(partial_path), &self.validator)?;
+    Ok(())
+  }
+
+  fn remove(partial_path: &Path) {
+    let _ = fs::remove_file(partial_path);
+    let _ = fs::remove_file(partial_validator_path(partial_path));
+  }
+}
+
+fn partial_validator_path(partial_path: &Path) -> PathBuf {
+  let mut path = partial_path.as_os_str().to_owned();
+  path.push(".etag");
+  PathBuf::from(path)
+}
+
+/// Gets the validator to use for `If-Range`, which only accepts strong ETags.
+fn response_validator(response: &Response) -> Option<String> {
+  let headers = response.headers();
+  headers
+    .get(ETAG)
+    .and_then(|etag| etag.to_str().ok())
+    .filter(|etag| !etag.starts_with("W/"))
+    .or_else(|| {
+      headers
+        .get(LAST_MODIFIED)
+        .and_then(|date| date.to_str().ok())
+    })
+    .map(ToString::to_string)
+}
+
+/// Gets the first byte position of a `Content-Range: bytes 100-199/200` header.
+fn content_range_start(response: &Response) -> Option<u64> {
+  let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+  let range = value.strip_prefix("bytes ")?;
+  let (start, _) = range.split_once('-')?;
+  start.trim().parse().ok()
+}
+
+/// Reads the response body after the bytes in `data`. On error, the bytes
+/// read so far are returned with it.
+async fn read_response_body(
+  response: reqwest::Response,
+  mut data: Vec<u8>,
+  progress_guard: Option<&UpdateGuard>,
+) -> Result<Vec<u8>, (Vec<u8>, AnyError)> {
+  let offset = data.len() as u64;
+  if let Some(progress_guard) = progress_guard {
+    if let Some(content_length) = response.content_length() {
+      progress_guard.set_total_size(offset + content_length);
+      data.reserve(content_length as usize);
+    }
+    progress_guard.set_position(offset);
+  }
+  let mut stream = response.bytes_stream();
+  while let Some(item) = stream.next().await {
+    match item {
+      Ok(bytes) => {
+        data.extend(bytes.into_iter());
+        if let Some(progress_guard) = progress_guard {
+          progress_guard.set_position(data.len() as u64);
+        }
+      }
+      Err(err) => return Err((data, err.into())),
+    }
+  }
+  Ok(data)
+}
+
 pub async fn get_response_body_with_progress(
   response: reqwest::Response,
   progress_guard: Option<&UpdateGuard>,
@@ -352,6 +681,8 @@ pub async fn get_response_body_with_progress(
 #[cfg(test)]
 mod test {
   use super::*;
+  use crate::util::progress_bar::ProgressBar;
+  use crate::util::progress_bar::ProgressBarStyle;
 
   #[tokio::test]
   async fn test_http_client_download_redirect() {
@@ -374,6 +705,75 @@ mod test {
     assert_eq!(err.to_string(), "Too many redirects.");
   }
 
+  #[test]
+  fn test_is_host_in() {
+    let hosts = vec![
+      "registry.internal".to_string(),
+      "localhost:5552".to_string(),
+    ];
+    let url = |url: &str| Url::parse(url).unwrap();
+    assert!(is_host_in(&url("https://registry.internal/mod.ts"), &hosts));
+    assert!(is_host_in(&url("https://localhost:5552/mod.ts"), &hosts));
+    assert!(!is_host_in(&url("https://localhost:5545/mod.ts"), &hosts));
+    assert!(!is_host_in(&url("https://deno.land/x/mod.ts"), &hosts));
+  }
+
+  #[tokio::test]
+  async fn test_http_client_download_resumable() {
+    let _http_server_guard = test_util::http_server();
+    let client = HttpClient::new(None, None).unwrap();
+    let temp_dir = test_util::TempDir::new();
+    let partial_path = temp_dir.path().join("range_resume.txt.partial");
+    let progress_bar = ProgressBar::new(ProgressBarStyle::TextOnly);
+    let url = "http://localhost:4545/range_resume.txt";
+
+    // a matching validator resumes from the partial download
+    temp_dir.write(&partial_path, "0123456789");
+    temp_dir.write(partial_validator_path(&partial_path), "\"range-resume\"");
+    let bytes = client
+      .download_with_progress_resumable(
+        url,
+        &partial_path,
+        &progress_bar.update(""),
+      )
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(bytes, b"0123456789abcdefghij");
+    assert!(!partial_path.exists());
+    assert!(!partial_validator_path(&partial_path).exists());
+
+    // a changed validator downloads the whole file again
+    temp_dir.write(&partial_path, "XXXXX");
+    temp_dir.write(partial_validator_path(&partial_path), "\"changed\"");
+    let bytes = client
+      .download_with_progress_resumable(
+        url,
+        &partial_path,
+        &progress_bar.update(""),
+      )
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(bytes, b"0123456789abcdefghij");
+    assert!(!partial_path.exists());
+
+    // a partial download that is too long is discarded
+    temp_dir.write(&partial_path, "0123456789abcdefghij0123");
+    temp_dir.write(partial_validator_path(&partial_path), "\"range-resume\"");
+    let bytes = client
+      .download_with_progress_resumable(
+        url,
+        &partial_path,
+        &progress_bar.update(""),
+      )
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(bytes, b"0123456789abcdefghij");
+    assert!(!partial_path.exists());
+  }
+
   #[test]
   fn test_resolve_url_from_location_full_1() {
     let url = "http://deno.land".parse::<Url>().unwrap();
@@ -0,0 +1,376 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The run journal: when `DENO_JOURNAL` is set to a path, a line of JSON is
+//! appended to that file for every invocation of the CLI, so that developers
+//! can see which commands take their time with `deno info --journal-summary`.
+//! The journal never leaves the machine, and failing to write it never fails
+//! the command.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use deno_core::anyhow::Context;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::args::Flags;
+use crate::colors;
+use crate::file_fetcher::FetchStats;
+use crate::util::display::human_elapsed;
+use crate::util::display::write_json_to_stdout;
+use crate::version;
+
+pub const JOURNAL_ENV_VAR: &str = "DENO_JOURNAL";
+
+/// The size above which the journal is moved to `<path>.1`, replacing the
+/// previous one, before an entry is appended.
+const MAX_JOURNAL_SIZE: u64 = 10 * 1024 * 1024;
+
+/// The fetch statistics of the file fetchers and npm caches created during
+/// the invocation.
+static FETCH_STATS: Lazy<Mutex<Vec<Arc<FetchStats>>>> =
+  Lazy::new(Default::default);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalEntry {
+  /// The seconds since the Unix epoch when the command started.
+  pub started_at: u64,
+  pub subcommand: String,
+  /// The flags passed to the command, without their values or the arguments
+  /// of the script.
+  pub flags: Vec<String>,
+  pub duration_ms: u64,
+  pub exit_code: i32,
+  /// The number of remote modules and npm packages reused from the cache.
+  pub cached: usize,
+  /// The number of remote modules and npm packages downloaded.
+  pub downloaded: usize,
+  pub deno_version: String,
+}
+
+/// Counts the fetches recorded by the statistics in the journal entry of the
+/// invocation.
+pub fn track_fetch_stats(stats: Arc<FetchStats>) {
+  let mut all_stats = FETCH_STATS.lock();
+  if !all_stats.iter().any(|s| Arc::ptr_eq(s, &stats)) {
+    all_stats.push(stats);
+  }
+}
+
+fn journal_path() -> Option<PathBuf> {
+  std::env::var_os(JOURNAL_ENV_VAR)
+    .filter(|path| !path.is_empty())
+    .map(PathBuf::from)
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+  let mut rotated = path.as_os_str().to_owned();
+  rotated.push(".1");
+  PathBuf::from(rotated)
+}
+
+/// The invocation being recorded. Commands which exit the process on their
+/// own, like a script calling `Deno.exit()`, aren't recorded.
+pub struct Journal {
+  path: PathBuf,
+  started: Instant,
+  started_at: u64,
+  subcommand: &'static str,
+  flags: Vec<String>,
+}
+
+impl Journal {
+  /// Starts recording the invocation when `DENO_JOURNAL` is set.
+  pub fn start(args: &[String], flags: &Flags) -> Option<Self> {
+    Some(Self {
+      path: journal_path()?,
+      started: Instant::now(),
+      started_at: SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0),
+      subcommand: flags.subcommand.name(),
+      flags: sanitize_args(args, &flags.argv),
+    })
+  }
+
+  /// Appends the entry of the invocation to the journal, only logging when
+  /// that fails.
+  pub fn finish(self, exit_code: i32) {
+    let (cached, downloaded) =
+      FETCH_STATS
+        .lock()
+        .iter()
+        .fold((0, 0), |(cached, downloaded), stats| {
+          (cached + stats.cached(), downloaded + stats.downloaded())
+        });
+    let entry = JournalEntry {
+      started_at: self.started_at,
+      subcommand: self.subcommand.to_string(),
+      flags: self.flags,
+      duration_ms: self.started.elapsed().as_millis() as u64,
+      exit_code,
+      cached,
+      downloaded,
+      deno_version: version::deno(),
+    };
+    if let Err(err) = append_entry(&self.path, &entry) {
+      log::debug!(
+        "Failed writing the journal {}: {:#}",
+        self.path.display(),
+        err
+      );
+    }
+  }
+}
+
+/// Gets the names of the flags in the arguments of the invocation, leaving
+/// out their values, the positional arguments and the arguments of the
+/// script, which may hold anything.
+fn sanitize_args(args: &[String], script_args: &[String]) -> Vec<String> {
+  let end = args.len().saturating_sub(script_args.len()).max(1);
+  args
+    .get(1..end)
+    .unwrap_or_default()
+    .iter()
+    .take_while(|arg| *arg != "--")
+    .filter(|arg| arg.starts_with('-'))
+    .map(|arg| match arg.split_once('=') {
+      Some((name, _)) => name.to_string(),
+      None => arg.to_string(),
+    })
+    .collect()
+}
+
+fn append_entry(path: &Path, entry: &JournalEntry) -> Result<(), AnyError> {
+  let is_full = fs::metadata(path)
+    .map(|metadata| metadata.len() >= MAX_JOURNAL_SIZE)
+    .unwrap_or(false);
+  if is_full {
+    fs::rename(path, rotated_path(path))?;
+  }
+  // the entry is written with a single call so that the lines of concurrent
+  // invocations don't interleave
+  let mut line = serde_json::to_string(entry)?;
+  line.push('\n');
+  fs::OpenOptions::new()
+    .create(true)
+    .append(true)
+    .open(path)?
+    .write_all(line.as_bytes())?;
+  Ok(())
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SubcommandSummary {
+  invocations: usize,
+  failures: usize,
+  total_ms: u64,
+  max_ms: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JournalSummary {
+  invocations: usize,
+  total_ms: u64,
+  cached: usize,
+  downloaded: usize,
+  /// The lines which aren't valid entries, like ones written by another
+  /// version of Deno.
+  invalid_lines: usize,
+  subcommands: BTreeMap<String, SubcommandSummary>,
+}
+
+fn summarize(text: &str) -> JournalSummary {
+  let mut summary = JournalSummary::default();
+  for line in text.lines().filter(|line| !line.trim().is_empty()) {
+    let entry = match serde_json::from_str::<JournalEntry>(line) {
+      Ok(entry) => entry,
+      Err(_) => {
+        summary.invalid_lines += 1;
+        continue;
+      }
+    };
+    summary.invocations += 1;
+    summary.total_ms += entry.duration_ms;
+    summary.cached += entry.cached;
+    summary.downloaded += entry.downloaded;
+    let subcommand = summary.subcommands.entry(entry.subcommand).or_default();
+    subcommand.invocations += 1;
+    if entry.exit_code != 0 {
+      subcommand.failures += 1;
+    }
+    subcommand.total_ms += entry.duration_ms;
+    subcommand.max_ms = subcommand.max_ms.max(entry.duration_ms);
+  }
+  summary
+}
+
+/// Prints the aggregate statistics of the journal, including the entries
+/// rotated out of it.
+pub fn print_summary(json: bool) -> Result<(), AnyError> {
+  let path = journal_path().ok_or_else(|| {
+    generic_error(format!(
+      "The journal can't be summarized because {JOURNAL_ENV_VAR} isn't set"
+    ))
+  })?;
+  let mut text = String::new();
+  for file_path in [rotated_path(&path), path.clone()] {
+    match fs::read_to_string(&file_path) {
+      Ok(file_text) => text.push_str(&file_text),
+      Err(err) if err.kind() == ErrorKind::NotFound => {}
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Failed reading the journal {}", file_path.display())
+        })
+      }
+    }
+  }
+  let summary = summarize(&text);
+
+  if json {
+    return write_json_to_stdout(&summary);
+  }
+  println!("{} {}", colors::bold("Journal:"), path.display());
+  println!(
+    "{} {} in {}",
+    colors::bold("Invocations:"),
+    summary.invocations,
+    human_elapsed(summary.total_ms as u128)
+  );
+  println!(
+    "{} {} cached, {} downloaded",
+    colors::bold("Modules and packages:"),
+    summary.cached,
+    summary.downloaded
+  );
+  if summary.invalid_lines > 0 {
+    println!(
+      "{} {}",
+      colors::bold("Invalid lines:"),
+      summary.invalid_lines
+    );
+  }
+  if summary.subcommands.is_empty() {
+    return Ok(());
+  }
+
+  let mut subcommands = summary.subcommands.iter().collect::<Vec<_>>();
+  subcommands.sort_by(|(_, a), (_, b)| b.total_ms.cmp(&a.total_ms));
+  println!();
+  println!(
+    "{}",
+    colors::bold(format!(
+      "{:<12} {:>11} {:>8} {:>10} {:>10} {:>10}",
+      "subcommand", "invocations", "failures", "total", "average", "max"
+    ))
+  );
+  for (name, subcommand) in subcommands {
+    let average_ms = subcommand.total_ms / subcommand.invocations as u64;
+    println!(
+      "{:<12} {:>11} {:>8} {:>10} {:>10} {:>10}",
+      name,
+      subcommand.invocations,
+      subcommand.failures,
+      human_elapsed(subcommand.total_ms as u128),
+      human_elapsed(average_ms as u128),
+      human_elapsed(subcommand.max_ms as u128)
+    );
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  #[test]
+  fn sanitizes_args() {
+    assert_eq!(
+      sanitize_args(
+        &args(&[
+          "deno",
+          "run",
+          "--allow-read=/home/user",
+          "-A",
+          "--config",
+          "deno.json",
+          "main.ts",
+          "--secret=1",
+          "token",
+        ]),
+        &args(&["--secret=1", "token"]),
+      ),
+      args(&["--allow-read", "-A", "--config"])
+    );
+    assert_eq!(
+      sanitize_args(
+        &args(&["deno", "test", "--parallel", "--", "--filter=a"]),
+        &args(&["--filter=a"]),
+      ),
+      args(&["--parallel"])
+    );
+    assert!(sanitize_args(&args(&["deno"]), &[]).is_empty());
+    assert!(sanitize_args(&[], &[]).is_empty());
+  }
+
+  #[test]
+  fn summarizes_entries() {
+    let entry = |subcommand: &str, duration_ms: u64, exit_code: i32| {
+      serde_json::to_string(&JournalEntry {
+        started_at: 0,
+        subcommand: subcommand.to_string(),
+        flags: Vec::new(),
+        duration_ms,
+        exit_code,
+        cached: 2,
+        downloaded: 1,
+        deno_version: "1.30.3".to_string(),
+      })
+      .unwrap()
+    };
+    let text = [
+      entry("test", 300, 1),
+      entry("test", 100, 0),
+      "{\"truncated".to_string(),
+      entry("fmt", 50, 0),
+    ]
+    .join("\n");
+    let summary = summarize(&text);
+    assert_eq!(summary.invocations, 3);
+    assert_eq!(summary.total_ms, 450);
+    assert_eq!(summary.cached, 6);
+    assert_eq!(summary.downloaded, 3);
+    assert_eq!(summary.invalid_lines, 1);
+    assert_eq!(
+      summary.subcommands["test"],
+      SubcommandSummary {
+        invocations: 2,
+        failures: 1,
+        total_ms: 400,
+        max_ms: 300,
+      }
+    );
+    assert_eq!(summary.subcommands["fmt"].invocations, 1);
+  }
+}
@@ -3,13 +3,16 @@
 const core = globalThis.Deno.core;
 const ops = core.ops;
 const internals = globalThis.__bootstrap.internals;
-import { setExitHandler } from "internal:runtime/js/30_os.js";
+import { exit, setExitHandler } from "internal:runtime/js/30_os.js";
 import { Console } from "internal:deno_console/02_console.js";
 import { serializePermissions } from "internal:runtime/js/10_permissions.js";
 import { assert } from "internal:deno_web/00_infra.js";
+import { clearTimersSince, nextTimerId } from "internal:deno_web/02_timers.js";
 const primordials = globalThis.__bootstrap.primordials;
 const {
   ArrayFrom,
+  ArrayIsArray,
+  ArrayPrototypeEvery,
   ArrayPrototypeFilter,
   ArrayPrototypeJoin,
   ArrayPrototypeMap,
@@ -25,6 +28,7 @@ const {
   MapPrototypeHas,
   MapPrototypeSet,
   MathCeil,
+  NumberParseInt,
   ObjectKeys,
   ObjectPrototypeHasOwnProperty,
   ObjectPrototypeIsPrototypeOf,
@@ -33,6 +37,7 @@ const {
   Set,
   SymbolToStringTag,
   TypeError,
+  Uint8ArrayPrototype,
 } = primordials;
 
 const opSanitizerDelayResolveQueue = [];
@@ -549,12 +554,16 @@ function withPermissions(fn, permissions) {
  *   origin: string,
  *   location: TestLocation,
  *   filteredOut: boolean,
+ *   excludedByTag: boolean,
  *   ignore: boolean,
  *   only: boolean.
+ *   expectedFailure: boolean,
  *   sanitizeOps: boolean,
  *   sanitizeResources: boolean,
  *   sanitizeExit: boolean,
  *   permissions: PermissionOptions,
+ *   timeout: number | null,
+ *   tags: string[],
  * }} TestDescription
  *
  * @typedef {{
@@ -564,6 +573,7 @@ function withPermissions(fn, permissions) {
  *   origin: string,
  *   location: TestLocation,
  *   ignore: boolean,
+ *   expectedFailure: boolean,
  *   level: number,
  *   parent: TestDescription | TestStepDescription,
  *   rootId: number,
@@ -583,7 +593,7 @@ function withPermissions(fn, permissions) {
  *   context: TestContext,
  *   children: TestStepDescription[],
  *   finalized: boolean,
- *   status: "pending" | "ok" | ""failed" | ignored",
+ *   status: "pending" | "ok" | ""failed" | ignored" | "expectedFailure" | "unexpectedlyPassed",
  *   error: unknown,
  *   elapsed: number | null,
  *   reportedWait: boolean,
@@ -626,10 +636,13 @@ function test(
   const defaults = {
     ignore: false,
     only: false,
+    expectedFailure: false,
     sanitizeOps: true,
     sanitizeResources: true,
     sanitizeExit: true,
     permissions: null,
+    timeout: null,
+    tags: [],
   };
 
   if (typeof nameOrFnOrOptions === "string") {
@@ -702,6 +715,21 @@ function test(
     testDesc = { ...defaults, ...nameOrFnOrOptions, fn, name };
   }
 
+  if (
+    testDesc.timeout != null &&
+    (typeof testDesc.timeout !== "number" || !(testDesc.timeout > 0))
+  ) {
+    throw new TypeError(
+      "The test timeout must be a positive number of milliseconds",
+    );
+  }
+  if (
+    !ArrayIsArray(testDesc.tags) ||
+    !ArrayPrototypeEvery(testDesc.tags, (tag) => typeof tag === "string")
+  ) {
+    throw new TypeError("The test tags must be an array of strings");
+  }
+
   // Delete this prop in case the user passed it. It's used to detect steps.
   delete testDesc.parent;
   testDesc.fn = wrapTestFnWithSanitizers(testDesc.fn, testDesc);
@@ -719,9 +747,10 @@ function test(
     columnNumber: jsError.frames[1].columnNumber,
   };
 
-  const { id, filteredOut } = ops.op_register_test(testDesc);
+  const { id, filteredOut, excludedByTag } = ops.op_register_test(testDesc);
   testDesc.id = id;
   testDesc.filteredOut = filteredOut;
+  testDesc.excludedByTag = excludedByTag;
 
   ArrayPrototypePush(testDescs, testDesc);
   MapPrototypeSet(testStates, testDesc.id, {
@@ -748,6 +777,7 @@ function bench(
     only: false,
     sanitizeExit: true,
     permissions: null,
+    timeout: null,
   };
 
   if (typeof nameOrFnOrOptions === "string") {
@@ -836,20 +866,20 @@ async function runTest(desc) {
     return "ignored";
   }
 
+  let failed = false;
+  let error;
   try {
     await desc.fn(desc);
     const failCount = failedChildStepsCount(desc);
-    return failCount === 0 ? "ok" : {
-      "failed": core.destructureError(
-        new Error(
-          `${failCount} test step${failCount === 1 ? "" : "s"} failed.`,
-        ),
-      ),
-    };
-  } catch (error) {
-    return {
-      "failed": core.destructureError(error),
-    };
+    if (failCount > 0) {
+      failed = true;
+      error = new Error(
+        `${failCount} test step${failCount === 1 ? "" : "s"} failed.`,
+      );
+    }
+  } catch (e) {
+    failed = true;
+    error = e;
   } finally {
     const state = MapPrototypeGet(testStates, desc.id);
     state.finalized = true;
@@ -858,6 +888,65 @@ async function runTest(desc) {
       stepReportResult(childDesc);
     }
   }
+
+  if (!failed) {
+    return desc.expectedFailure ? "unexpectedlyPassed" : "ok";
+  }
+  const jsError = core.destructureError(error);
+  return desc.expectedFailure
+    ? { "expectedFailure": jsError }
+    : { "failed": jsError };
+}
+
+/** Whether the test failed, and so may be retried. */
+function isFailedResult(result) {
+  return typeof result === "object" &&
+    ObjectPrototypeHasOwnProperty(result, "failed");
+}
+
+/** Starts over the steps and the context of a test which is retried. */
+function resetTestState(desc) {
+  MapPrototypeSet(testStates, desc.id, {
+    context: createTestContext(desc),
+    children: [],
+    finalized: false,
+  });
+}
+
+// The number of event loop turns to wait for the pending ops of a failed
+// attempt of a test to complete, as some can't be cancelled.
+const LEAKED_OPS_MAX_DELAYS = 10;
+
+/** What a test attempt can leak, recorded before it runs. */
+function captureLeakState() {
+  const metrics = core.metrics();
+  return {
+    resources: core.resources(),
+    timerId: nextTimerId(),
+    pendingOps: metrics.opsDispatchedAsync - metrics.opsCompletedAsync,
+  };
+}
+
+/** Closes the resources and clears the timers left by a failed attempt of a
+ * test, and waits for its pending ops to complete, so that they don't affect
+ * the next attempt or get reported as leaked by it. */
+async function resetLeakedState(stateBefore) {
+  clearTimersSince(stateBefore.timerId);
+  for (const rid of new SafeArrayIterator(ObjectKeys(core.resources()))) {
+    if (!ObjectPrototypeHasOwnProperty(stateBefore.resources, rid)) {
+      core.tryClose(NumberParseInt(rid, 10));
+    }
+  }
+  // at least two turns, like for the op sanitizer
+  await opSanitizerDelay();
+  for (let i = 1; i < LEAKED_OPS_MAX_DELAYS; i++) {
+    await opSanitizerDelay();
+    const metrics = core.metrics();
+    const pendingOps = metrics.opsDispatchedAsync - metrics.opsCompletedAsync;
+    if (pendingOps <= stateBefore.pendingOps) {
+      break;
+    }
+  }
 }
 
 function compareMeasurements(a, b) {
@@ -880,17 +969,10 @@ function benchStats(n, highPrecision, avg, min, max, all) {
   };
 }
 
-async function benchMeasure(timeBudget, desc) {
+/** Warms up the bench, returning the mean time of its warm-up iterations. */
+async function benchWarmup(desc) {
   const fn = desc.fn;
-  let n = 0;
-  let avg = 0;
   let wavg = 0;
-  const all = [];
-  let min = Infinity;
-  let max = -Infinity;
-  const lowPrecisionThresholdInNs = 1e4;
-
-  // warmup step
   let c = 0;
   let iterations = 20;
   let budget = 10 * 1e6;
@@ -919,7 +1001,20 @@ async function benchMeasure(timeBudget, desc) {
     }
   }
 
-  wavg /= c;
+  return wavg / c;
+}
+
+async function benchMeasure(timeBudget, desc) {
+  const fn = desc.fn;
+  let n = 0;
+  let avg = 0;
+  const all = [];
+  let min = Infinity;
+  let max = -Infinity;
+  const lowPrecisionThresholdInNs = 1e4;
+
+  // warmup step, unless the bench was warmed up ahead of its measurement
+  const wavg = desc.warmupAvg ?? await benchWarmup(desc);
 
   // measure step
   if (wavg > lowPrecisionThresholdInNs) {
@@ -1021,6 +1116,93 @@ async function runBench(desc) {
   }
 }
 
+/**
+ * Warms up the bench ahead of its measurement, so that bench modules can be
+ * warmed up in parallel and then measured one at a time. A bench which fails
+ * is warmed up again when it's measured, which reports the error.
+ */
+async function runBenchWarmup(desc) {
+  let token = null;
+
+  try {
+    if (desc.permissions) {
+      token = pledgePermissions(desc.permissions);
+    }
+
+    if (desc.sanitizeExit) {
+      setExitHandler((exitCode) => {
+        assert(
+          false,
+          `Bench attempted to exit with exit code: ${exitCode}`,
+        );
+      });
+    }
+
+    desc.warmupAvg = await benchWarmup(desc);
+  } catch {
+    // reported when the bench is measured
+  } finally {
+    if (desc.sanitizeExit) setExitHandler(null);
+    if (token !== null) restorePermissions(token);
+  }
+}
+
+/**
+ * Runs the bench in a loop without collecting statistics, so a profiler can be
+ * attached. The loop runs for `durationMs` or until the process is interrupted
+ * when it's `null`. The iterations so far are reported every second.
+ */
+async function runBenchLoop(desc, durationMs) {
+  let token = null;
+
+  try {
+    if (desc.permissions) {
+      token = pledgePermissions(desc.permissions);
+    }
+
+    if (desc.sanitizeExit) {
+      setExitHandler((exitCode) => {
+        assert(
+          false,
+          `Bench attempted to exit with exit code: ${exitCode}`,
+        );
+      });
+    }
+
+    const fn = desc.fn;
+    const progressIntervalInNs = 1e9;
+    const start = benchNow();
+    const end = durationMs === null ? Infinity : start + durationMs * 1e6;
+    let lastProgress = start;
+    let now = start;
+    let iterations = 0;
+
+    while (now < end) {
+      if (desc.async) {
+        await fn();
+      } else {
+        fn();
+      }
+      iterations++;
+      now = benchNow();
+
+      if (now - lastProgress >= progressIntervalInNs) {
+        lastProgress = now;
+        ops.op_dispatch_bench_event({
+          progress: [desc.id, { iterations, elapsed: now - start }],
+        });
+      }
+    }
+
+    return { looped: { iterations, elapsed: now - start } };
+  } catch (error) {
+    return { failed: core.destructureError(error) };
+  } finally {
+    if (desc.sanitizeExit) setExitHandler(null);
+    if (token !== null) restorePermissions(token);
+  }
+}
+
 let origin = null;
 
 function getTestOrigin() {
@@ -1051,24 +1233,36 @@ function enableBench() {
 
 async function runTests({
   shuffle = null,
+  startIndex = 0,
+  timeout = null,
+  retries = 0,
 } = {}) {
   core.setMacrotaskCallback(handleOpSanitizerDelayMacrotask);
 
   const origin = getTestOrigin();
   const only = ArrayPrototypeFilter(testDescs, (test) => test.only);
+  const candidates = only.length > 0 ? only : testDescs;
   const filtered = ArrayPrototypeFilter(
-    only.length > 0 ? only : testDescs,
+    candidates,
     (desc) => !desc.filteredOut,
   );
 
-  ops.op_dispatch_test_event({
-    plan: {
-      origin,
-      total: filtered.length,
-      filteredOut: testDescs.length - filtered.length,
-      usedOnly: only.length > 0,
-    },
-  });
+  // the tests before `startIndex` already ran in a worker that was terminated
+  // when a test timed out
+  if (startIndex === 0) {
+    ops.op_dispatch_test_event({
+      plan: {
+        origin,
+        total: filtered.length,
+        filteredOut: testDescs.length - filtered.length,
+        excludedByTag: ArrayPrototypeFilter(
+          candidates,
+          (desc) => desc.excludedByTag,
+        ).length,
+        usedOnly: only.length > 0,
+      },
+    });
+  }
 
   if (shuffle !== null) {
     // http://en.wikipedia.org/wiki/Linear_congruential_generator
@@ -1089,21 +1283,53 @@ async function runTests({
     }
   }
 
-  for (const desc of filtered) {
+  for (let i = startIndex; i < filtered.length; i++) {
     if (ops.op_tests_should_stop()) {
       break;
     }
+    if (ops.op_tests_file_should_stop()) {
+      ops.op_dispatch_test_event({
+        failFastCutoff: [origin, filtered.length - i],
+      });
+      break;
+    }
+    const desc = filtered[i];
     ops.op_dispatch_test_event({ wait: desc.id });
-    const earlier = DateNow();
-    const result = await runTest(desc);
-    const elapsed = DateNow() - earlier;
+    const testTimeout = desc.ignore ? null : desc.timeout ?? timeout;
+    let result;
+    let elapsed = 0;
+    // only the result of the last attempt is reported, so that a test that
+    // passes on a retry doesn't count towards `--fail-fast`
+    for (let attempt = 0;; attempt++) {
+      if (attempt > 0) {
+        resetTestState(desc);
+        ops.op_dispatch_test_event({ retry: [desc.id, attempt] });
+      }
+      const stateBefore = attempt < retries ? captureLeakState() : null;
+      if (testTimeout !== null) {
+        ops.op_test_timeout_start(desc.id, i, MathCeil(testTimeout));
+      }
+      const earlier = DateNow();
+      result = await runTest(desc);
+      elapsed += DateNow() - earlier;
+      if (testTimeout !== null) {
+        ops.op_test_timeout_end();
+      }
+      if (attempt >= retries || !isFailedResult(result)) {
+        break;
+      }
+      await resetLeakedState(stateBefore);
+    }
     ops.op_dispatch_test_event({
       result: [desc.id, result, elapsed],
     });
   }
 }
 
-async function runBenchmarks() {
+async function runBenchmarks({
+  profileLoop = null,
+  warmupOnlyParallel = false,
+} = {}) {
   core.setMacrotaskCallback(handleOpSanitizerDelayMacrotask);
 
   const origin = getBenchOrigin();
@@ -1134,20 +1360,48 @@ async function runBenchmarks() {
     (a, b) => groups.indexOf(a.group) - groups.indexOf(b.group),
   );
 
+  if (profileLoop !== null && filtered.length > 1) {
+    globalThis.console = originalConsole;
+    throw new Error(
+      `--profile-loop requires the filter to match a single bench, but it matched ${filtered.length}: ${
+        ArrayPrototypeJoin(
+          ArrayPrototypeMap(filtered, (desc) => `"${desc.name}"`),
+          ", ",
+        )
+      }`,
+    );
+  }
+
+  const filteredOut = ArrayPrototypeFilter(
+    benchDescs,
+    (desc) => desc.filteredOut,
+  );
   ops.op_dispatch_bench_event({
     plan: {
       origin,
       total: filtered.length,
+      registered: benchDescs.length,
+      filteredOut: filteredOut.length,
       usedOnly: only.length > 0,
       names: ArrayPrototypeMap(filtered, (desc) => desc.name),
     },
   });
 
+  if (warmupOnlyParallel && profileLoop === null) {
+    for (const desc of filtered) {
+      await runBenchWarmup(desc);
+    }
+    await core.opAsync("op_bench_lock_measurements");
+  }
+
   for (const desc of filtered) {
     desc.baseline = !!desc.baseline;
     ops.op_dispatch_bench_event({ wait: desc.id });
+    const result = profileLoop !== null
+      ? await runBenchLoop(desc, profileLoop.durationMs)
+      : await runBench(desc);
     ops.op_dispatch_bench_event({
-      result: [desc.id, await runBench(desc)],
+      result: [desc.id, result],
     });
   }
 
@@ -1201,7 +1455,10 @@ function stepReportResult(desc) {
     stepReportResult(childDesc);
   }
   let result;
-  if (state.status == "pending" || state.status == "failed") {
+  if (
+    state.status == "pending" || state.status == "failed" ||
+    state.status == "expectedFailure"
+  ) {
     result = {
       [state.status]: state.error && core.destructureError(state.error),
     };
@@ -1215,12 +1472,32 @@ function stepReportResult(desc) {
 }
 
 function failedChildStepsCount(desc) {
+  // the expected failures of the steps only fail their parent when the
+  // parent is expected to fail as well
+  const countsExpectedFailures = expectsFailure(desc);
   return ArrayPrototypeFilter(
     MapPrototypeGet(testStates, desc.id).children,
-    (d) => MapPrototypeGet(testStates, d.id).status === "failed",
+    (d) => {
+      const status = MapPrototypeGet(testStates, d.id).status;
+      return status === "failed" || status === "unexpectedlyPassed" ||
+        (status === "expectedFailure" && countsExpectedFailures);
+    },
   ).length;
 }
 
+/** Whether the test or step is expected to fail, because it or one of its
+ * ancestors uses the `expectedFailure` option. */
+function expectsFailure(desc) {
+  let currentDesc = desc;
+  while (currentDesc != null) {
+    if (currentDesc.expectedFailure) {
+      return true;
+    }
+    currentDesc = currentDesc.parent;
+  }
+  return false;
+}
+
 /** If a test validation error already occurred then don't bother checking
  * the sanitizers as that will create extra noise.
  */
@@ -1234,6 +1511,13 @@ function shouldSkipSanitizers(desc) {
 }
 
 /** @param desc {TestDescription | TestStepDescription} */
+class AssertionError extends Error {
+  constructor(message) {
+    super(message);
+    this.name = "AssertionError";
+  }
+}
+
 function createTestContext(desc) {
   let parent;
   let level;
@@ -1264,6 +1548,25 @@ function createTestContext(desc) {
      * File Uri of the test code.
      */
     origin: desc.origin,
+    /**
+     * @param actual {string | Uint8Array}
+     * @param goldenPath {string}
+     */
+    assertGoldenFile(actual, goldenPath) {
+      const binary = typeof actual !== "string";
+      if (
+        binary && !ObjectPrototypeIsPrototypeOf(Uint8ArrayPrototype, actual)
+      ) {
+        throw new TypeError("Expected a string or Uint8Array.");
+      }
+      const mismatch = ops.op_assert_golden_file(
+        { origin: desc.origin, path: goldenPath, binary },
+        binary ? actual : core.encode(actual),
+      );
+      if (mismatch !== null) {
+        throw new AssertionError(mismatch);
+      }
+    },
     /**
      * @param nameOrFnOrOptions {string | TestStepDefinition | ((t: TestContext) => void | Promise<void>)}
      * @param maybeFn {((t: TestContext) => void | Promise<void>) | undefined}
@@ -1306,6 +1609,7 @@ function createTestContext(desc) {
         );
       }
       stepDesc.ignore ??= false;
+      stepDesc.expectedFailure ??= false;
       stepDesc.sanitizeOps ??= desc.sanitizeOps;
       stepDesc.sanitizeResources ??= desc.sanitizeResources;
       stepDesc.sanitizeExit ??= desc.sanitizeExit;
@@ -1364,6 +1668,12 @@ function createTestContext(desc) {
           state.status = "failed";
         }
 
+        if (state.status === "failed" && expectsFailure(stepDesc)) {
+          state.status = "expectedFailure";
+        } else if (state.status === "ok" && stepDesc.expectedFailure) {
+          state.status = "unexpectedlyPassed";
+        }
+
         state.elapsed = DateNow() - start;
 
         if (MapPrototypeGet(testStates, stepDesc.parent.id).finalized) {
@@ -1392,6 +1702,27 @@ function createTestContext(desc) {
   };
 }
 
+// Wrap test function so that a test case which is allowed to exit still fails
+// the test run, as the remaining tests and the summary are skipped.
+function reportExit(fn) {
+  return async function exitReporter(...params) {
+    setExitHandler((exitCode) => {
+      setExitHandler(null);
+      core.print(
+        `error: Test case exited the test run with exit code ${exitCode}. The remaining tests were not run.\n`,
+        true,
+      );
+      exit(exitCode === 0 ? 1 : exitCode);
+    });
+
+    try {
+      await fn(...new SafeArrayIterator(params));
+    } finally {
+      setExitHandler(null);
+    }
+  };
+}
+
 /**
  * @template T {Function}
  * @param testFn {T}
@@ -1413,6 +1744,8 @@ function wrapTestFnWithSanitizers(testFn, opts) {
   }
   if (opts.sanitizeExit) {
     testFn = assertExit(testFn, true);
+  } else {
+    testFn = reportExit(testFn);
   }
   return testFn;
 }
@@ -3,9 +3,11 @@
 use super::diagnostics::DenoDiagnostic;
 use super::documents::Documents;
 use super::language_server;
+use super::text::LineIndex;
 use super::tsc;
 
-use crate::tools::lint::create_linter;
+use crate::args::ConfigFile;
+use crate::tools::lint::CliLinter;
 
 use deno_ast::SourceRange;
 use deno_ast::SourceRangedForSpanned;
@@ -16,12 +18,11 @@ use deno_core::error::AnyError;
 use deno_core::serde::Deserialize;
 use deno_core::serde_json::json;
 use deno_core::ModuleSpecifier;
-use deno_lint::rules::LintRule;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::sync::Arc;
+use text_size::TextSize;
 use tower_lsp::lsp_types as lsp;
 use tower_lsp::lsp_types::Position;
 use tower_lsp::lsp_types::Range;
@@ -56,8 +57,16 @@ static PREFERRED_FIXES: Lazy<HashMap<&'static str, (u32, bool)>> =
 static IMPORT_SPECIFIER_RE: Lazy<Regex> =
   Lazy::new(|| Regex::new(r#"\sfrom\s+["']([^"']*)["']"#).unwrap());
 
+/// Matches a line consisting only of a `deno-lint-ignore` comment with rule
+/// names.
+static LINT_IGNORE_LINE_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r#"^\s*//\s*deno-lint-ignore(\s+[\w-]+)+\s*$"#).unwrap()
+});
+
 const SUPPORTED_EXTENSIONS: &[&str] = &[".ts", ".tsx", ".js", ".jsx", ".mjs"];
 
+const UNSTABLE_LIB: &str = r#"["deno.ns", "deno.window", "deno.unstable"]"#;
+
 /// Category of self-generated diagnostic messages (those not coming from)
 /// TypeScript.
 #[derive(Debug, PartialEq, Eq)]
@@ -121,10 +130,9 @@ fn as_lsp_range(range: &deno_lint::diagnostic::Range) -> Range {
 
 pub fn get_lint_references(
   parsed_source: &deno_ast::ParsedSource,
-  lint_rules: Vec<Arc<dyn LintRule>>,
+  linter: &CliLinter,
 ) -> Result<Vec<Reference>, AnyError> {
-  let linter = create_linter(parsed_source.media_type(), lint_rules);
-  let lint_diagnostics = linter.lint_with_ast(parsed_source);
+  let lint_diagnostics = linter.lint_parsed_source(parsed_source);
 
   Ok(
     lint_diagnostics
@@ -388,15 +396,34 @@ impl CodeActionCollection {
       })
       .unwrap();
 
-    let line_content = maybe_text_info.map(|ti| {
+    let line_content = maybe_text_info.as_ref().map(|ti| {
       ti.line_text(diagnostic.range.start.line as usize)
         .to_string()
     });
+    let maybe_previous_line =
+      diagnostic.range.start.line.checked_sub(1).and_then(|line| {
+        maybe_text_info
+          .as_ref()
+          .map(|ti| (line, ti.line_text(line as usize).to_string()))
+      });
 
-    let mut changes = HashMap::new();
-    changes.insert(
-      specifier.clone(),
-      vec![lsp::TextEdit {
+    // If the previous line already ignores other rules, append the rule to
+    // that comment instead of adding another one.
+    let edit = match maybe_previous_line {
+      Some((line, line_text)) if LINT_IGNORE_LINE_RE.is_match(&line_text) => {
+        let position = lsp::Position {
+          line,
+          character: line_text.trim_end().encode_utf16().count() as u32,
+        };
+        lsp::TextEdit {
+          new_text: format!(" {code}"),
+          range: lsp::Range {
+            start: position,
+            end: position,
+          },
+        }
+      }
+      _ => lsp::TextEdit {
         new_text: prepend_whitespace(
           format!("// deno-lint-ignore {code}\n"),
           line_content,
@@ -411,8 +438,11 @@ impl CodeActionCollection {
             character: 0,
           },
         },
-      }],
-    );
+      },
+    };
+
+    let mut changes = HashMap::new();
+    changes.insert(specifier.clone(), vec![edit]);
     let ignore_error_action = lsp::CodeAction {
       title: format!("Disable {code} for this line"),
       kind: Some(lsp::CodeActionKind::QUICKFIX),
@@ -534,6 +564,89 @@ impl CodeActionCollection {
     Ok(())
   }
 
+  /// Add code actions which make the unstable Deno APIs available to the type
+  /// checker, either for the whole project by adding the unstable lib to the
+  /// configuration file, or for the module by referencing the unstable lib.
+  pub fn add_deno_unstable_actions(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    diagnostic: &lsp::Diagnostic,
+    maybe_text_info: Option<SourceTextInfo>,
+    maybe_config_file: Option<&ConfigFile>,
+  ) {
+    let maybe_config_edit = maybe_config_file.and_then(|config_file| {
+      let path = config_file.specifier.to_file_path().ok()?;
+      let text = std::fs::read_to_string(path).ok()?;
+      let (offset, new_text) = get_unstable_lib_insertion(&text)?;
+      let line_index = LineIndex::new(&text);
+      let position = line_index.position_tsc(TextSize::from(
+        text[..offset].encode_utf16().count() as u32,
+      ));
+      Some((
+        config_file.specifier.clone(),
+        lsp::TextEdit {
+          new_text,
+          range: lsp::Range {
+            start: position,
+            end: position,
+          },
+        },
+      ))
+    });
+    if let Some((config_specifier, edit)) = maybe_config_edit {
+      let mut changes = HashMap::new();
+      changes.insert(config_specifier, vec![edit]);
+      let code_action = lsp::CodeAction {
+        title: "Add the unstable Deno types to the configuration file"
+          .to_string(),
+        kind: Some(lsp::CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        command: None,
+        is_preferred: None,
+        disabled: None,
+        data: None,
+        edit: Some(lsp::WorkspaceEdit {
+          changes: Some(changes),
+          change_annotations: None,
+          document_changes: None,
+        }),
+      };
+      self.actions.push(CodeActionKind::Deno(code_action));
+    }
+
+    // Insert the directive after a shebang, if present.
+    let line = match maybe_text_info {
+      Some(text_info) if text_info.text_str().starts_with("#!") => 1,
+      _ => 0,
+    };
+    let mut changes = HashMap::new();
+    changes.insert(
+      specifier.clone(),
+      vec![lsp::TextEdit {
+        new_text: "/// <reference lib=\"deno.unstable\" />\n".to_string(),
+        range: lsp::Range {
+          start: lsp::Position { line, character: 0 },
+          end: lsp::Position { line, character: 0 },
+        },
+      }],
+    );
+    let code_action = lsp::CodeAction {
+      title: "Add a reference to the unstable Deno types".to_string(),
+      kind: Some(lsp::CodeActionKind::QUICKFIX),
+      diagnostics: Some(vec![diagnostic.clone()]),
+      command: None,
+      is_preferred: None,
+      disabled: None,
+      data: None,
+      edit: Some(lsp::WorkspaceEdit {
+        changes: Some(changes),
+        change_annotations: None,
+        document_changes: None,
+      }),
+    };
+    self.actions.push(CodeActionKind::Deno(code_action));
+  }
+
   /// Add a TypeScript code fix action to the code actions collection.
   pub fn add_ts_fix_action(
     &mut self,
@@ -704,6 +817,75 @@ impl CodeActionCollection {
   }
 }
 
+/// Determines if a diagnostic from tsc reports a missing unstable Deno API.
+pub fn is_unstable_deno_diagnostic(diagnostic: &lsp::Diagnostic) -> bool {
+  matches!(
+    diagnostic.code,
+    Some(lsp::NumberOrString::Number(2339 | 2551))
+  ) && crate::tsc::get_unstable_deno_property(&diagnostic.message).is_some()
+}
+
+/// Gets the offset and text to insert into the text of a configuration file
+/// so that `"compilerOptions"."lib"` includes the unstable Deno types, or
+/// `None` when they are already included or the file can't be updated.
+fn get_unstable_lib_insertion(text: &str) -> Option<(usize, String)> {
+  use jsonc_parser::ast::ObjectProp;
+  use jsonc_parser::ast::Value;
+  let ast =
+    jsonc_parser::parse_to_ast(text, &Default::default(), &Default::default())
+      .ok()?;
+  let root = match ast.value {
+    Some(Value::Object(obj)) => obj,
+    _ => return None,
+  };
+  match root.get("compilerOptions") {
+    Some(ObjectProp {
+      value: Value::Object(compiler_options),
+      ..
+    }) => match compiler_options.get("lib") {
+      Some(ObjectProp {
+        value: Value::Array(lib),
+        ..
+      }) => {
+        let has_unstable = lib.elements.iter().any(|element| {
+          matches!(element, Value::StringLit(lit) if lit.value == "deno.unstable")
+        });
+        if has_unstable {
+          return None;
+        }
+        let separator = if lib.elements.is_empty() { "" } else { ", " };
+        Some((lib.range.end - 1, format!("{separator}\"deno.unstable\"")))
+      }
+      None => {
+        let suffix = if compiler_options.properties.is_empty() {
+          "\n  "
+        } else {
+          ","
+        };
+        Some((
+          compiler_options.range.start + 1,
+          format!("\n    \"lib\": {UNSTABLE_LIB}{suffix}"),
+        ))
+      }
+      Some(_) => None,
+    },
+    None => {
+      let suffix = if root.properties.is_empty() {
+        "\n"
+      } else {
+        ","
+      };
+      Some((
+        root.range.start + 1,
+        format!(
+          "\n  \"compilerOptions\": {{\n    \"lib\": {UNSTABLE_LIB}\n  }}{suffix}"
+        ),
+      ))
+    }
+    Some(_) => None,
+  }
+}
+
 /// Prepend the whitespace characters found at the start of line_content to content.
 fn prepend_whitespace(content: String, line_content: Option<String>) -> String {
   if let Some(line) = line_content {
@@ -825,4 +1007,68 @@ mod tests {
       }
     );
   }
+
+  #[test]
+  fn test_get_unstable_lib_insertion() {
+    fn apply(text: &str) -> Option<String> {
+      let (offset, insert_text) = get_unstable_lib_insertion(text)?;
+      Some(format!(
+        "{}{}{}",
+        &text[..offset],
+        insert_text,
+        &text[offset..]
+      ))
+    }
+
+    assert_eq!(
+      apply("{}").unwrap(),
+      r#"{
+  "compilerOptions": {
+    "lib": ["deno.ns", "deno.window", "deno.unstable"]
+  }
+}"#
+    );
+    assert_eq!(
+      apply("{\n  \"tasks\": {}\n}").unwrap(),
+      r#"{
+  "compilerOptions": {
+    "lib": ["deno.ns", "deno.window", "deno.unstable"]
+  },
+  "tasks": {}
+}"#
+    );
+    assert_eq!(
+      apply("{\n  \"compilerOptions\": {\n    \"strict\": true\n  }\n}")
+        .unwrap(),
+      r#"{
+  "compilerOptions": {
+    "lib": ["deno.ns", "deno.window", "deno.unstable"],
+    "strict": true
+  }
+}"#
+    );
+    assert_eq!(
+      apply(r#"{ "compilerOptions": { "lib": ["deno.window"] } }"#).unwrap(),
+      r#"{ "compilerOptions": { "lib": ["deno.window", "deno.unstable"] } }"#
+    );
+    assert_eq!(
+      apply(r#"{ "compilerOptions": { "lib": ["deno.unstable"] } }"#),
+      None
+    );
+  }
+
+  #[test]
+  fn test_lint_ignore_line_re() {
+    assert!(
+      LINT_IGNORE_LINE_RE.is_match("  // deno-lint-ignore no-explicit-any")
+    );
+    assert!(
+      LINT_IGNORE_LINE_RE.is_match("// deno-lint-ignore no-var prefer-const")
+    );
+    assert!(!LINT_IGNORE_LINE_RE.is_match("// deno-lint-ignore"));
+    assert!(!LINT_IGNORE_LINE_RE.is_match("// deno-lint-ignore-file no-var"));
+    assert!(
+      !LINT_IGNORE_LINE_RE.is_match("const a = 1; // deno-lint-ignore no-var")
+    );
+  }
 }
@@ -133,7 +133,19 @@ pub fn server_capabilities(
         supported: Some(true),
         change_notifications: Some(OneOf::Left(true)),
       }),
-      file_operations: None,
+      file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+        will_rename: Some(FileOperationRegistrationOptions {
+          filters: vec![FileOperationFilter {
+            scheme: Some("file".to_string()),
+            pattern: FileOperationPattern {
+              glob: "**/*".to_string(),
+              matches: None,
+              options: None,
+            },
+          }],
+        }),
+        ..Default::default()
+      }),
     }),
     linked_editing_range_provider: None,
     moniker_provider: None,
@@ -117,6 +117,21 @@ impl Client {
   ) -> Result<(), AnyError> {
     self.0.register_capability(registrations).await
   }
+
+  pub async fn create_work_done_progress(
+    &self,
+    token: lsp::NumberOrString,
+  ) -> Result<(), AnyError> {
+    self.0.create_work_done_progress(token).await
+  }
+
+  pub async fn send_work_done_progress(
+    &self,
+    token: lsp::NumberOrString,
+    progress: lsp::WorkDoneProgress,
+  ) {
+    self.0.send_work_done_progress(token, progress).await
+  }
 }
 
 type AsyncReturn<T> = Pin<Box<dyn Future<Output = T> + 'static + Send>>;
@@ -147,6 +162,15 @@ trait ClientTrait: Send + Sync {
     &self,
     registrations: Vec<lsp::Registration>,
   ) -> AsyncReturn<Result<(), AnyError>>;
+  fn create_work_done_progress(
+    &self,
+    token: lsp::NumberOrString,
+  ) -> AsyncReturn<Result<(), AnyError>>;
+  fn send_work_done_progress(
+    &self,
+    token: lsp::NumberOrString,
+    progress: lsp::WorkDoneProgress,
+  ) -> AsyncReturn<()>;
 }
 
 #[derive(Clone)]
@@ -276,6 +300,37 @@ impl ClientTrait for TowerClient {
         .map_err(|err| anyhow!("{}", err))
     })
   }
+
+  fn create_work_done_progress(
+    &self,
+    token: lsp::NumberOrString,
+  ) -> AsyncReturn<Result<(), AnyError>> {
+    let client = self.0.clone();
+    Box::pin(async move {
+      client
+        .send_request::<lsp::request::WorkDoneProgressCreate>(
+          lsp::WorkDoneProgressCreateParams { token },
+        )
+        .await
+        .map_err(|err| anyhow!("{}", err))
+    })
+  }
+
+  fn send_work_done_progress(
+    &self,
+    token: lsp::NumberOrString,
+    progress: lsp::WorkDoneProgress,
+  ) -> AsyncReturn<()> {
+    let client = self.0.clone();
+    Box::pin(async move {
+      client
+        .send_notification::<lsp::notification::Progress>(lsp::ProgressParams {
+          token,
+          value: lsp::ProgressParamsValue::WorkDone(progress),
+        })
+        .await
+    })
+  }
 }
 
 #[derive(Clone)]
@@ -338,4 +393,19 @@ impl ClientTrait for ReplClient {
   ) -> AsyncReturn<Result<(), AnyError>> {
     Box::pin(future::ready(Ok(())))
   }
+
+  fn create_work_done_progress(
+    &self,
+    _token: lsp::NumberOrString,
+  ) -> AsyncReturn<Result<(), AnyError>> {
+    Box::pin(future::ready(Ok(())))
+  }
+
+  fn send_work_done_progress(
+    &self,
+    _token: lsp::NumberOrString,
+    _progress: lsp::WorkDoneProgress,
+  ) -> AsyncReturn<()> {
+    Box::pin(future::ready(()))
+  }
 }
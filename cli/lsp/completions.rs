@@ -7,6 +7,10 @@ use super::lsp_custom;
 use super::registries::ModuleRegistry;
 use super::tsc;
 
+use crate::node;
+use crate::npm::NpmPackageReference;
+use crate::npm::NpmPackageResolver;
+use crate::resolver::find_npm_import_map_entry;
 use crate::util::path::is_supported_ext;
 use crate::util::path::relative_specifier;
 use crate::util::path::specifier_to_file_path;
@@ -20,6 +24,7 @@ use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::url::Position;
 use deno_core::ModuleSpecifier;
+use deno_runtime::permissions::PermissionsContainer;
 use import_map::ImportMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -135,6 +140,7 @@ fn to_narrow_lsp_range(
 /// Given a specifier, a position, and a snapshot, optionally return a
 /// completion response, which will be valid import completions for the specific
 /// context.
+#[allow(clippy::too_many_arguments)]
 pub async fn get_import_completions(
   specifier: &ModuleSpecifier,
   position: &lsp::Position,
@@ -143,6 +149,7 @@ pub async fn get_import_completions(
   module_registries: &ModuleRegistry,
   documents: &Documents,
   maybe_import_map: Option<Arc<ImportMap>>,
+  npm_resolver: &NpmPackageResolver,
 ) -> Option<lsp::CompletionResponse> {
   let document = documents.get(specifier)?;
   let (text, _, range) = document.get_maybe_dependency(position)?;
@@ -153,6 +160,7 @@ pub async fn get_import_completions(
     &range,
     maybe_import_map.clone(),
     documents,
+    npm_resolver,
   ) {
     // completions for import map specifiers
     Some(lsp::CompletionResponse::List(completion_list))
@@ -264,10 +272,13 @@ fn get_import_map_completions(
   range: &lsp::Range,
   maybe_import_map: Option<Arc<ImportMap>>,
   documents: &Documents,
+  npm_resolver: &NpmPackageResolver,
 ) -> Option<lsp::CompletionList> {
   if !text.is_empty() {
     if let Some(import_map) = maybe_import_map {
-      let mut items = Vec::new();
+      let mut items =
+        get_npm_import_map_completions(text, range, &import_map, npm_resolver)
+          .unwrap_or_default();
       for key in import_map.imports().keys() {
         // for some reason, the import_map stores keys that begin with `/` as
         // `file:///` in its index, so we have to reverse that here
@@ -347,6 +358,80 @@ fn get_import_map_completions(
   None
 }
 
+/// Given an existing specifier that the import map maps to an npm package,
+/// return the subpaths exported by the package as completions.
+fn get_npm_import_map_completions(
+  text: &str,
+  range: &lsp::Range,
+  import_map: &ImportMap,
+  npm_resolver: &NpmPackageResolver,
+) -> Option<Vec<lsp::CompletionItem>> {
+  let (key, value) = find_npm_import_map_entry(import_map, text)?;
+  let reference =
+    NpmPackageReference::from_str(value.as_str().trim_end_matches('/')).ok()?;
+  let export_sub_paths = node::node_resolve_package_export_sub_paths(
+    &reference.req,
+    npm_resolver,
+    &mut PermissionsContainer::allow_all(),
+  )
+  .ok()?;
+  Some(get_npm_export_completions(
+    text,
+    range,
+    key,
+    reference.sub_path.as_deref(),
+    &export_sub_paths,
+  ))
+}
+
+/// Maps the subpaths exported by an npm package to the specifiers importing
+/// them through the import map entry with the provided key. When the entry
+/// maps to a subpath of the package, only the exports below it apply.
+fn get_npm_export_completions(
+  text: &str,
+  range: &lsp::Range,
+  key: &str,
+  maybe_sub_path: Option<&str>,
+  export_sub_paths: &[String],
+) -> Vec<lsp::CompletionItem> {
+  let prefix = if key.ends_with('/') {
+    key.to_string()
+  } else {
+    format!("{key}/")
+  };
+  export_sub_paths
+    .iter()
+    .filter_map(|export_sub_path| {
+      let export_sub_path = match maybe_sub_path {
+        Some(sub_path) => {
+          export_sub_path.strip_prefix(sub_path)?.strip_prefix('/')?
+        }
+        None => export_sub_path,
+      };
+      let new_text = format!("{prefix}{export_sub_path}");
+      if !new_text.starts_with(text) {
+        return None;
+      }
+      let text_edit = Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+        range: *range,
+        new_text: new_text.clone(),
+      }));
+      Some(lsp::CompletionItem {
+        label: new_text.clone(),
+        kind: Some(lsp::CompletionItemKind::MODULE),
+        detail: Some("(npm)".to_string()),
+        sort_text: Some("1".to_string()),
+        filter_text: Some(new_text),
+        text_edit,
+        commit_characters: Some(
+          IMPORT_COMMIT_CHARS.iter().map(|&c| c.into()).collect(),
+        ),
+        ..Default::default()
+      })
+    })
+    .collect()
+}
+
 /// Return local completions that are relative to the base specifier.
 fn get_local_completions(
   base: &ModuleSpecifier,
@@ -721,4 +806,57 @@ mod tests {
       text_info.text_str().chars().count() as u32
     );
   }
+
+  #[test]
+  fn test_get_npm_export_completions() {
+    let range = lsp::Range {
+      start: lsp::Position {
+        line: 0,
+        character: 20,
+      },
+      end: lsp::Position {
+        line: 0,
+        character: 30,
+      },
+    };
+    let export_sub_paths = vec![
+      "client".to_string(),
+      "client/foo".to_string(),
+      "server".to_string(),
+    ];
+    let labels = |items: Vec<lsp::CompletionItem>| {
+      items.into_iter().map(|item| item.label).collect::<Vec<_>>()
+    };
+    assert_eq!(
+      labels(get_npm_export_completions(
+        "@std/pkg/",
+        &range,
+        "@std/pkg",
+        None,
+        &export_sub_paths
+      )),
+      vec!["@std/pkg/client", "@std/pkg/client/foo", "@std/pkg/server"]
+    );
+    assert_eq!(
+      labels(get_npm_export_completions(
+        "pkg/cl",
+        &range,
+        "pkg/",
+        None,
+        &export_sub_paths
+      )),
+      vec!["pkg/client", "pkg/client/foo"]
+    );
+    // an entry mapping to a subpath of the package
+    assert_eq!(
+      labels(get_npm_export_completions(
+        "client/",
+        &range,
+        "client/",
+        Some("client"),
+        &export_sub_paths
+      )),
+      vec!["client/foo"]
+    );
+  }
 }
@@ -27,6 +27,9 @@ pub struct ClientCapabilities {
   /// built around VSCode's testing API. It indicates that the server should
   /// send notifications about tests discovered in modules.
   pub testing_api: bool,
+  /// The client supports progress reported with server initiated
+  /// `window/workDoneProgress` tokens.
+  pub work_done_progress: bool,
   pub workspace_configuration: bool,
   pub workspace_did_change_watched_files: bool,
 }
@@ -519,6 +522,11 @@ impl Config {
           == Some(true);
     }
 
+    if let Some(window) = &capabilities.window {
+      self.client_capabilities.work_done_progress =
+        window.work_done_progress.unwrap_or(false);
+    }
+
     if let Some(workspace) = &capabilities.workspace {
       self.client_capabilities.workspace_configuration =
         workspace.configuration.unwrap_or(false);
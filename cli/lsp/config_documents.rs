@@ -0,0 +1,843 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Completions, hover and diagnostics for the configuration file and the
+//! import map, which are JSON documents that tsc doesn't know about.
+
+use std::ops::Range;
+
+use deno_core::url::Url;
+use deno_lint::rules;
+use jsonc_parser::ast::Value;
+use jsonc_parser::tokens::Token;
+use jsonc_parser::Scanner;
+use text_size::TextSize;
+use tower_lsp::lsp_types as lsp;
+
+use super::text::LineIndex;
+use crate::args::config_file_schema;
+use crate::tools::lint::get_rule_docs_url;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ConfigDocumentKind {
+  /// A `deno.json` or `deno.jsonc` file.
+  ConfigFile,
+  /// The import map in use, when it isn't embedded in the configuration file.
+  ImportMap,
+}
+
+const IMPORT_MAP_KEY_DOCS: &[(&str, &str)] = &[
+  (
+    "imports",
+    "Mappings of specifiers and prefixes of specifiers to other specifiers.",
+  ),
+  (
+    "scopes",
+    "Mappings which only apply to the modules under a prefix.",
+  ),
+];
+
+fn find_doc(docs: &[(&str, &'static str)], key: &str) -> Option<&'static str> {
+  docs
+    .iter()
+    .find(|(name, _)| *name == key)
+    .map(|(_, doc)| *doc)
+}
+
+/// The part of a JSON document at a position.
+#[derive(Debug, Eq, PartialEq)]
+struct JsonLocation {
+  /// The keys leading to the object or array at the position.
+  path: Vec<String>,
+  kind: JsonLocationKind,
+  maybe_string: Option<JsonString>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum JsonLocationKind {
+  /// A key of the object.
+  Key,
+  /// The value of a key of the object.
+  Value(String),
+  /// An element of the array.
+  Element,
+}
+
+/// The string literal at a position.
+#[derive(Debug, Eq, PartialEq)]
+struct JsonString {
+  /// The byte range of the contents, without the quotes.
+  range: Range<usize>,
+  value: String,
+}
+
+struct Container {
+  is_array: bool,
+  /// The key of the container in its parent object.
+  maybe_key: Option<String>,
+  /// The last key of an object, until the next comma.
+  maybe_current_key: Option<String>,
+  is_after_colon: bool,
+}
+
+impl Container {
+  fn location_kind(&self) -> JsonLocationKind {
+    if self.is_array {
+      JsonLocationKind::Element
+    } else if self.is_after_colon {
+      JsonLocationKind::Value(
+        self.maybe_current_key.clone().unwrap_or_default(),
+      )
+    } else {
+      JsonLocationKind::Key
+    }
+  }
+}
+
+/// Finds the part of a JSON document at a byte offset. The text is tokenized
+/// up to the end of the line of the offset instead of parsed, as it's usually
+/// incomplete while being edited.
+fn locate(text: &str, offset: usize) -> Option<JsonLocation> {
+  let line_end = text[offset..].find('\n').map_or(text.len(), |n| offset + n);
+  let mut scanner = Scanner::new(&text[..line_end]);
+  let mut stack: Vec<Container> = Vec::new();
+  let path = |stack: &[Container]| -> Vec<String> {
+    stack.iter().filter_map(|c| c.maybe_key.clone()).collect()
+  };
+  loop {
+    let token = match scanner.scan() {
+      Ok(Some(token)) => token,
+      Ok(None) => break,
+      // a string which isn't closed yet, as it's being typed
+      Err(err)
+        if err.range.start < offset
+          && text.as_bytes()[err.range.start] == b'"' =>
+      {
+        let start = err.range.start + 1;
+        let container = stack.last()?;
+        return Some(JsonLocation {
+          path: path(&stack),
+          kind: container.location_kind(),
+          maybe_string: Some(JsonString {
+            range: start..line_end,
+            value: text[start..line_end].to_string(),
+          }),
+        });
+      }
+      Err(_) => return None,
+    };
+    let start = scanner.token_start();
+    let end = scanner.token_end();
+    if start >= offset {
+      break;
+    }
+    match token {
+      Token::String(_) => {
+        // the range and the value exclude the quotes
+        let range = start + 1..end - 1;
+        if offset < end {
+          let container = stack.last()?;
+          return Some(JsonLocation {
+            path: path(&stack),
+            kind: container.location_kind(),
+            maybe_string: Some(JsonString {
+              value: text[range.clone()].to_string(),
+              range,
+            }),
+          });
+        }
+        if let Some(container) = stack.last_mut() {
+          if !container.is_array && !container.is_after_colon {
+            container.maybe_current_key = Some(text[range].to_string());
+          }
+        }
+      }
+      Token::CommentLine(_) if offset <= end => return None,
+      Token::CommentBlock(_) if offset < end => return None,
+      Token::OpenBrace | Token::OpenBracket => {
+        let maybe_key = stack
+          .last()
+          .filter(|container| !container.is_array)
+          .and_then(|container| container.maybe_current_key.clone());
+        stack.push(Container {
+          is_array: token == Token::OpenBracket,
+          maybe_key,
+          maybe_current_key: None,
+          is_after_colon: false,
+        });
+      }
+      Token::CloseBrace | Token::CloseBracket => {
+        stack.pop();
+      }
+      Token::Colon => {
+        if let Some(container) = stack.last_mut() {
+          container.is_after_colon = true;
+        }
+      }
+      Token::Comma => {
+        if let Some(container) = stack.last_mut() {
+          container.is_after_colon = false;
+          container.maybe_current_key = None;
+        }
+      }
+      _ => {}
+    }
+  }
+  let container = stack.last()?;
+  Some(JsonLocation {
+    path: path(&stack),
+    kind: container.location_kind(),
+    maybe_string: None,
+  })
+}
+
+struct Candidate {
+  label: String,
+  kind: lsp::CompletionItemKind,
+  maybe_documentation: Option<String>,
+  /// Whether the value is a string, rather than a literal like `true`.
+  is_string: bool,
+}
+
+impl Candidate {
+  fn key(label: &str, maybe_documentation: Option<&str>) -> Self {
+    Self {
+      label: label.to_string(),
+      kind: lsp::CompletionItemKind::PROPERTY,
+      maybe_documentation: maybe_documentation.map(String::from),
+      is_string: true,
+    }
+  }
+
+  fn value(label: &str, maybe_documentation: Option<String>) -> Self {
+    Self {
+      label: label.to_string(),
+      kind: lsp::CompletionItemKind::VALUE,
+      maybe_documentation,
+      is_string: true,
+    }
+  }
+
+  fn literal(label: &str) -> Self {
+    Self {
+      label: label.to_string(),
+      kind: lsp::CompletionItemKind::KEYWORD,
+      maybe_documentation: None,
+      is_string: false,
+    }
+  }
+}
+
+fn is_lint_rules_list(path: &[String]) -> bool {
+  matches!(
+    path,
+    [lint, rules, list]
+      if lint == "lint" && rules == "rules" && (list == "include" || list == "exclude")
+  )
+}
+
+fn lint_rule_documentation(code: &str) -> Option<String> {
+  let rule = rules::get_all_rules()
+    .into_iter()
+    .find(|rule| rule.code() == code)?;
+  Some(format!(
+    "{}\n\n[Documentation]({})",
+    rule.docs().trim(),
+    get_rule_docs_url(rule.code())
+  ))
+}
+
+fn config_file_candidates(location: &JsonLocation) -> Vec<Candidate> {
+  let path = location.path.as_slice();
+  let schema = config_file_schema();
+  match &location.kind {
+    JsonLocationKind::Key => match schema.get(path) {
+      Some(schema) => schema
+        .properties()
+        .iter()
+        .map(|(key, schema)| {
+          Candidate::key(key, schema.maybe_description.as_deref())
+        })
+        .collect(),
+      None => Vec::new(),
+    },
+    JsonLocationKind::Value(key) => {
+      let mut value_path = path.to_vec();
+      value_path.push(key.clone());
+      match schema.get(&value_path) {
+        Some(schema) if schema.accepts_boolean() => {
+          vec![Candidate::literal("true"), Candidate::literal("false")]
+        }
+        Some(schema) => schema
+          .values
+          .iter()
+          .map(|value| Candidate::value(value, None))
+          .collect(),
+        None => Vec::new(),
+      }
+    }
+    JsonLocationKind::Element if is_lint_rules_list(path) => {
+      rules::get_all_rules()
+        .into_iter()
+        .map(|rule| {
+          Candidate::value(rule.code(), lint_rule_documentation(rule.code()))
+        })
+        .collect()
+    }
+    JsonLocationKind::Element if path == ["lint", "rules", "tags"] => {
+      let mut tags = rules::get_all_rules()
+        .into_iter()
+        .flat_map(|rule| rule.tags().to_vec())
+        .collect::<Vec<_>>();
+      tags.sort_unstable();
+      tags.dedup();
+      tags
+        .into_iter()
+        .map(|tag| Candidate::value(tag, None))
+        .collect()
+    }
+    JsonLocationKind::Element => {
+      match schema.get(path).and_then(|schema| schema.items()) {
+        Some(items) => items
+          .values
+          .iter()
+          .map(|value| Candidate::value(value, None))
+          .collect(),
+        None => Vec::new(),
+      }
+    }
+  }
+}
+
+fn import_map_candidates(location: &JsonLocation) -> Vec<Candidate> {
+  match &location.kind {
+    JsonLocationKind::Key if location.path.is_empty() => IMPORT_MAP_KEY_DOCS
+      .iter()
+      .map(|(key, doc)| Candidate::key(key, Some(*doc)))
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
+fn to_lsp_range(range: Range<usize>, line_index: &LineIndex) -> lsp::Range {
+  lsp::Range {
+    start: line_index.position_utf16(TextSize::from(range.start as u32)),
+    end: line_index.position_utf16(TextSize::from(range.end as u32)),
+  }
+}
+
+/// Gets the completions at a position of a configuration file or an import
+/// map, which are the keys and values Deno knows about.
+pub fn get_completions(
+  kind: ConfigDocumentKind,
+  text: &str,
+  position: lsp::Position,
+  line_index: &LineIndex,
+) -> Option<lsp::CompletionResponse> {
+  let offset = u32::from(line_index.offset(position).ok()?) as usize;
+  let location = locate(text, offset)?;
+  let candidates = match kind {
+    ConfigDocumentKind::ConfigFile => config_file_candidates(&location),
+    ConfigDocumentKind::ImportMap => import_map_candidates(&location),
+  };
+  let items = candidates
+    .into_iter()
+    .filter_map(|candidate| {
+      let (text_edit, insert_text) = match &location.maybe_string {
+        // literals can't be completed in a string
+        Some(_) if !candidate.is_string => return None,
+        Some(string) => (
+          Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit {
+            range: to_lsp_range(string.range.clone(), line_index),
+            new_text: candidate.label.clone(),
+          })),
+          None,
+        ),
+        None if candidate.is_string => {
+          (None, Some(format!("\"{}\"", candidate.label)))
+        }
+        None => (None, None),
+      };
+      Some(lsp::CompletionItem {
+        label: candidate.label,
+        kind: Some(candidate.kind),
+        documentation: candidate.maybe_documentation.map(|value| {
+          lsp::Documentation::MarkupContent(lsp::MarkupContent {
+            kind: lsp::MarkupKind::Markdown,
+            value,
+          })
+        }),
+        text_edit,
+        insert_text,
+        ..Default::default()
+      })
+    })
+    .collect::<Vec<_>>();
+  if items.is_empty() {
+    return None;
+  }
+  Some(lsp::CompletionResponse::List(lsp::CompletionList {
+    is_incomplete: false,
+    items,
+  }))
+}
+
+/// Gets the documentation of the key or the lint rule at a position of a
+/// configuration file or an import map.
+pub fn get_hover(
+  kind: ConfigDocumentKind,
+  text: &str,
+  position: lsp::Position,
+  line_index: &LineIndex,
+) -> Option<lsp::Hover> {
+  let offset = u32::from(line_index.offset(position).ok()?) as usize;
+  let location = locate(text, offset)?;
+  let string = location.maybe_string.as_ref()?;
+  let path = location.path.as_slice();
+  let value = match (kind, &location.kind) {
+    (ConfigDocumentKind::ConfigFile, JsonLocationKind::Key) => {
+      let mut key_path = path.to_vec();
+      key_path.push(string.value.clone());
+      let schema = config_file_schema().get(&key_path)?;
+      let description = schema.maybe_description.as_ref()?;
+      if path == ["compilerOptions"] {
+        format!(
+          "```ts\n{}: {}\n```\n\n{}",
+          string.value,
+          schema.type_name(),
+          description
+        )
+      } else {
+        description.clone()
+      }
+    }
+    (ConfigDocumentKind::ConfigFile, JsonLocationKind::Element)
+      if is_lint_rules_list(path) =>
+    {
+      lint_rule_documentation(&string.value)?
+    }
+    (ConfigDocumentKind::ImportMap, JsonLocationKind::Key)
+      if path.is_empty() =>
+    {
+      find_doc(IMPORT_MAP_KEY_DOCS, &string.value)?.to_string()
+    }
+    _ => return None,
+  };
+  Some(lsp::Hover {
+    contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+      kind: lsp::MarkupKind::Markdown,
+      value,
+    }),
+    range: Some(to_lsp_range(string.range.clone(), line_index)),
+  })
+}
+
+/// Whether an address of an import map is a URL or a path, as bare
+/// specifiers can only be keys.
+fn is_valid_address(address: &str) -> bool {
+  address.starts_with('/')
+    || address.starts_with("./")
+    || address.starts_with("../")
+    || Url::parse(address).is_ok()
+}
+
+fn value_range(value: &Value) -> Range<usize> {
+  match value {
+    Value::StringLit(lit) => lit.range.start..lit.range.end,
+    Value::NumberLit(lit) => lit.range.start..lit.range.end,
+    Value::BooleanLit(lit) => lit.range.start..lit.range.end,
+    Value::Object(obj) => obj.range.start..obj.range.end,
+    Value::Array(arr) => arr.range.start..arr.range.end,
+    Value::NullKeyword(keyword) => keyword.range.start..keyword.range.end,
+  }
+}
+
+/// Validates the addresses of the `"imports"` of an import map or of one of
+/// its scopes. The types of the values of a configuration file are already
+/// validated along with the rest of the file.
+fn validate_specifier_map(
+  kind: ConfigDocumentKind,
+  value: &Value,
+  path: &str,
+  errors: &mut Vec<(Range<usize>, String)>,
+) {
+  let obj = match value {
+    Value::Object(obj) => obj,
+    Value::NullKeyword(_) => return,
+    value => {
+      if kind == ConfigDocumentKind::ImportMap {
+        errors.push((
+          value_range(value),
+          format!("Invalid value for \"{path}\". Expected an object."),
+        ));
+      }
+      return;
+    }
+  };
+  for prop in &obj.properties {
+    let key = prop.name.as_str();
+    match &prop.value {
+      Value::StringLit(lit) => {
+        let address = lit.value.as_ref();
+        if !is_valid_address(address) {
+          errors.push((
+            value_range(&prop.value),
+            format!(
+              "Invalid address \"{address}\" for \"{key}\". Expected a URL, or a path starting with \"/\", \"./\" or \"../\"."
+            ),
+          ));
+        } else if key.ends_with('/') && !address.ends_with('/') {
+          errors.push((
+            value_range(&prop.value),
+            format!(
+              "Invalid address \"{address}\" for \"{key}\". The address of a key ending with \"/\" must also end with \"/\"."
+            ),
+          ));
+        }
+      }
+      Value::NullKeyword(_) => {}
+      value => {
+        if kind == ConfigDocumentKind::ImportMap {
+          errors.push((
+            value_range(value),
+            format!("Invalid value for \"{key}\". Expected a string."),
+          ));
+        }
+      }
+    }
+  }
+}
+
+/// Validates the entries of an import map, or of the `"imports"` and
+/// `"scopes"` of a configuration file, returning the byte ranges and the
+/// messages of the errors.
+fn validate_import_map_text(
+  kind: ConfigDocumentKind,
+  text: &str,
+) -> Vec<(Range<usize>, String)> {
+  let ast = match jsonc_parser::parse_to_ast(
+    text,
+    &Default::default(),
+    &Default::default(),
+  ) {
+    Ok(ast) => ast,
+    Err(_) => return Vec::new(),
+  };
+  let root = match &ast.value {
+    Some(Value::Object(root)) => root,
+    _ => return Vec::new(),
+  };
+  let mut errors = Vec::new();
+  for prop in &root.properties {
+    match prop.name.as_str() {
+      "imports" => {
+        validate_specifier_map(kind, &prop.value, "imports", &mut errors)
+      }
+      "scopes" => match &prop.value {
+        Value::Object(scopes) => {
+          for scope in &scopes.properties {
+            let prefix = scope.name.as_str();
+            if !is_valid_address(prefix) {
+              errors.push((
+                scope.range.start..scope.range.end,
+                format!(
+                  "Invalid scope \"{prefix}\". Expected a URL, or a path starting with \"/\", \"./\" or \"../\"."
+                ),
+              ));
+            }
+            validate_specifier_map(
+              kind,
+              &scope.value,
+              &format!("scopes.{prefix}"),
+              &mut errors,
+            );
+          }
+        }
+        Value::NullKeyword(_) => {}
+        value => {
+          if kind == ConfigDocumentKind::ImportMap {
+            errors.push((
+              value_range(value),
+              "Invalid value for \"scopes\". Expected an object.".to_string(),
+            ));
+          }
+        }
+      },
+      _ => {}
+    }
+  }
+  errors
+}
+
+/// Gets the diagnostics of the entries of an import map, or of the import
+/// map embedded in a configuration file.
+pub fn get_import_map_diagnostics(
+  kind: ConfigDocumentKind,
+  text: &str,
+  line_index: &LineIndex,
+) -> Vec<lsp::Diagnostic> {
+  validate_import_map_text(kind, text)
+    .into_iter()
+    .map(|(range, message)| lsp::Diagnostic {
+      range: to_lsp_range(range, line_index),
+      severity: Some(lsp::DiagnosticSeverity::ERROR),
+      source: Some("deno".to_string()),
+      message,
+      ..Default::default()
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  /// Locates the position marked with `|` in the text.
+  fn locate_marker(text: &str) -> Option<JsonLocation> {
+    let offset = text.find('|').unwrap();
+    locate(&text.replace('|', ""), offset)
+  }
+
+  fn path(keys: &[&str]) -> Vec<String> {
+    keys.iter().map(|key| key.to_string()).collect()
+  }
+
+  #[test]
+  fn test_locate() {
+    assert_eq!(
+      locate_marker(r#"{ "compilerOptions": { "str|" } }"#),
+      Some(JsonLocation {
+        path: path(&["compilerOptions"]),
+        kind: JsonLocationKind::Key,
+        maybe_string: Some(JsonString {
+          range: 24..27,
+          value: "str".to_string(),
+        }),
+      })
+    );
+    assert_eq!(
+      locate_marker(
+        "{\n  // \"a\": [\n  \"compilerOptions\": { \"jsx\": | }\n}"
+      ),
+      Some(JsonLocation {
+        path: path(&["compilerOptions"]),
+        kind: JsonLocationKind::Value("jsx".to_string()),
+        maybe_string: None,
+      })
+    );
+    assert_eq!(
+      locate_marker(
+        r#"{ "lint": { "files": {}, "rules": { "include": ["ban-untagged-todo", "|"#
+      ),
+      Some(JsonLocation {
+        path: path(&["lint", "rules", "include"]),
+        kind: JsonLocationKind::Element,
+        maybe_string: Some(JsonString {
+          range: 70..70,
+          value: String::new(),
+        }),
+      })
+    );
+    assert_eq!(
+      locate_marker(r#"{ "a": "b\"c", | }"#),
+      Some(JsonLocation {
+        path: vec![],
+        kind: JsonLocationKind::Key,
+        maybe_string: None,
+      })
+    );
+    assert_eq!(locate_marker(r#"{ /* | */ }"#), None);
+    assert_eq!(locate_marker(r#"| {}"#), None);
+    assert_eq!(locate_marker(r#"{} |"#), None);
+  }
+
+  fn labels(
+    kind: ConfigDocumentKind,
+    text: &str,
+  ) -> Vec<(String, Option<String>)> {
+    let offset = text.find('|').unwrap();
+    let text = text.replace('|', "");
+    let line_index = LineIndex::new(&text);
+    let position = line_index.position_utf16(TextSize::from(offset as u32));
+    match get_completions(kind, &text, position, &line_index) {
+      Some(lsp::CompletionResponse::List(list)) => list
+        .items
+        .into_iter()
+        .map(|item| (item.label, item.insert_text))
+        .collect(),
+      _ => Vec::new(),
+    }
+  }
+
+  #[test]
+  fn test_get_completions() {
+    let items = labels(ConfigDocumentKind::ConfigFile, r#"{ "|" }"#);
+    assert!(items.contains(&("compilerOptions".to_string(), None)));
+    assert!(items.contains(&("tasks".to_string(), None)));
+
+    let items = labels(
+      ConfigDocumentKind::ConfigFile,
+      r#"{ "compilerOptions": { | } }"#,
+    );
+    assert!(
+      items.contains(&("strict".to_string(), Some("\"strict\"".to_string())))
+    );
+
+    assert_eq!(
+      labels(
+        ConfigDocumentKind::ConfigFile,
+        r#"{ "compilerOptions": { "strict": | } }"#,
+      ),
+      vec![("true".to_string(), None), ("false".to_string(), None)]
+    );
+    assert_eq!(
+      labels(
+        ConfigDocumentKind::ConfigFile,
+        r#"{ "compilerOptions": { "jsx": "|" } }"#,
+      )
+      .len(),
+      5
+    );
+    assert_eq!(
+      labels(
+        ConfigDocumentKind::ConfigFile,
+        r#"{ "fmt": { "options": { "proseWrap": "|" } } }"#,
+      ),
+      vec![
+        ("always".to_string(), None),
+        ("never".to_string(), None),
+        ("preserve".to_string(), None),
+      ]
+    );
+    let items = labels(
+      ConfigDocumentKind::ConfigFile,
+      r#"{ "compilerOptions": { "lib": ["deno.ns", "|"] } }"#,
+    );
+    assert!(items.contains(&("deno.window".to_string(), None)));
+
+    let items = labels(
+      ConfigDocumentKind::ConfigFile,
+      r#"{ "lint": { "rules": { "exclude": ["|"] } } }"#,
+    );
+    assert!(items.contains(&("no-explicit-any".to_string(), None)));
+    assert_eq!(
+      labels(
+        ConfigDocumentKind::ConfigFile,
+        r#"{ "lint": { "rules": { "tags": ["|"] } } }"#,
+      ),
+      vec![("recommended".to_string(), None)]
+    );
+
+    assert_eq!(
+      labels(ConfigDocumentKind::ImportMap, r#"{ "|" }"#),
+      vec![("imports".to_string(), None), ("scopes".to_string(), None)]
+    );
+    assert_eq!(
+      labels(ConfigDocumentKind::ImportMap, r#"{ "imports": { "|" } }"#),
+      vec![]
+    );
+  }
+
+  #[test]
+  fn test_get_hover() {
+    let text = r#"{ "compilerOptions": { "jsx": "react" } }"#;
+    let line_index = LineIndex::new(text);
+    let hover = get_hover(
+      ConfigDocumentKind::ConfigFile,
+      text,
+      lsp::Position {
+        line: 0,
+        character: 25,
+      },
+      &line_index,
+    )
+    .unwrap();
+    assert_eq!(
+      hover.contents,
+      lsp::HoverContents::Markup(lsp::MarkupContent {
+        kind: lsp::MarkupKind::Markdown,
+        value: "```ts\njsx: \"preserve\" | \"react\" | \"react-jsx\" | \"react-jsxdev\" | \"react-native\"\n```\n\nSpecify what JSX code is generated.".to_string(),
+      })
+    );
+    assert_eq!(
+      hover.range,
+      Some(lsp::Range {
+        start: lsp::Position {
+          line: 0,
+          character: 24,
+        },
+        end: lsp::Position {
+          line: 0,
+          character: 27,
+        },
+      })
+    );
+
+    let text = r#"{ "fmt": { "options": { "lineWidth": 80 } } }"#;
+    let line_index = LineIndex::new(text);
+    let hover = get_hover(
+      ConfigDocumentKind::ConfigFile,
+      text,
+      lsp::Position {
+        line: 0,
+        character: 27,
+      },
+      &line_index,
+    )
+    .unwrap();
+    assert_eq!(
+      hover.contents,
+      lsp::HoverContents::Markup(lsp::MarkupContent {
+        kind: lsp::MarkupKind::Markdown,
+        value: "The width of a line the printer will try to stay under. Note that the printer may exceed this width in certain cases.".to_string(),
+      })
+    );
+  }
+
+  #[test]
+  fn test_validate_import_map_text() {
+    let text = r#"{
+  "imports": {
+    "std/": "https://deno.land/std/",
+    "a": "./a.ts",
+    "b": "b.ts",
+    "c/": "./c.ts",
+    "d": 1
+  },
+  "scopes": {
+    "https://example.com/": { "e": "/e.ts" },
+    "example": {}
+  }
+}"#;
+    let messages = |kind| {
+      validate_import_map_text(kind, text)
+        .into_iter()
+        .map(|(range, message)| (text[range].to_string(), message))
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      messages(ConfigDocumentKind::ImportMap),
+      vec![
+        (
+          "\"b.ts\"".to_string(),
+          "Invalid address \"b.ts\" for \"b\". Expected a URL, or a path starting with \"/\", \"./\" or \"../\".".to_string(),
+        ),
+        (
+          "\"./c.ts\"".to_string(),
+          "Invalid address \"./c.ts\" for \"c/\". The address of a key ending with \"/\" must also end with \"/\".".to_string(),
+        ),
+        (
+          "1".to_string(),
+          "Invalid value for \"d\". Expected a string.".to_string(),
+        ),
+        (
+          "\"example\": {}".to_string(),
+          "Invalid scope \"example\". Expected a URL, or a path starting with \"/\", \"./\" or \"../\".".to_string(),
+        ),
+      ]
+    );
+    // the types of the values of a configuration file are validated with the
+    // rest of the file
+    assert_eq!(messages(ConfigDocumentKind::ConfigFile).len(), 3);
+  }
+}
@@ -17,7 +17,7 @@ use crate::graph_util;
 use crate::graph_util::enhanced_resolution_error_message;
 use crate::node;
 use crate::npm::NpmPackageReference;
-use crate::tools::lint::get_configured_rules;
+use crate::tools::lint::CliLinter;
 
 use deno_ast::MediaType;
 use deno_core::anyhow::anyhow;
@@ -30,7 +30,6 @@ use deno_core::ModuleSpecifier;
 use deno_graph::Resolution;
 use deno_graph::ResolutionError;
 use deno_graph::SpecifierError;
-use deno_lint::rules::LintRule;
 use deno_runtime::tokio_util::create_basic_runtime;
 use log::error;
 use std::collections::HashMap;
@@ -455,9 +454,15 @@ async fn generate_lint_diagnostics(
 ) -> DiagnosticVec {
   let documents = snapshot.documents.documents(true, true);
   let workspace_settings = config.settings.workspace.clone();
-  let lint_rules = get_configured_rules(lint_options.rules.clone());
   let mut diagnostics_vec = Vec::new();
   if workspace_settings.lint {
+    let linter = match CliLinter::new(lint_options) {
+      Ok(linter) => linter,
+      Err(err) => {
+        error!("Error resolving the lint rules: {:#}", err);
+        return diagnostics_vec;
+      }
+    };
     for document in documents {
       // exit early if cancelled
       if token.is_cancelled() {
@@ -478,7 +483,7 @@ async fn generate_lint_diagnostics(
         generate_document_lint_diagnostics(
           config,
           lint_options,
-          lint_rules.clone(),
+          &linter,
           &document,
         ),
       ));
@@ -490,7 +495,7 @@ async fn generate_lint_diagnostics(
 fn generate_document_lint_diagnostics(
   config: &ConfigSnapshot,
   lint_options: &LintOptions,
-  lint_rules: Vec<Arc<dyn LintRule>>,
+  linter: &CliLinter,
   document: &Document,
 ) -> Vec<lsp::Diagnostic> {
   if !config.specifier_enabled(document.specifier()) {
@@ -502,7 +507,7 @@ fn generate_document_lint_diagnostics(
   match document.maybe_parsed_source() {
     Some(Ok(parsed_source)) => {
       if let Ok(references) =
-        analysis::get_lint_references(&parsed_source, lint_rules)
+        analysis::get_lint_references(&parsed_source, linter)
       {
         references
           .into_iter()
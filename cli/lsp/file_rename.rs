@@ -0,0 +1,369 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Computes the edits for `workspace/willRenameFiles`, which update the
+//! relative specifiers referring to modules that are renamed or moved.
+
+use super::documents::Document;
+use super::text::LineIndex;
+
+use crate::util::path::relative_specifier;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_core::ModuleSpecifier;
+use deno_graph::DefaultModuleAnalyzer;
+use std::collections::HashMap;
+use tower_lsp::lsp_types as lsp;
+
+/// The rename of a file or of a directory and everything it contains.
+#[derive(Debug, Clone)]
+pub struct SpecifierRename {
+  old: String,
+  new: String,
+}
+
+impl SpecifierRename {
+  pub fn from_lsp(rename: &lsp::FileRename) -> Option<Self> {
+    let old = ModuleSpecifier::parse(&rename.old_uri).ok()?;
+    let new = ModuleSpecifier::parse(&rename.new_uri).ok()?;
+    if old.scheme() != "file" || new.scheme() != "file" {
+      return None;
+    }
+    Some(Self {
+      old: old.as_str().trim_end_matches('/').to_string(),
+      new: new.as_str().trim_end_matches('/').to_string(),
+    })
+  }
+
+  /// Returns where the specifier is moved to by this rename, if it is the
+  /// renamed file or is contained in the renamed directory.
+  fn apply(&self, specifier: &ModuleSpecifier) -> Option<ModuleSpecifier> {
+    let rest = specifier.as_str().strip_prefix(&self.old)?;
+    if !rest.is_empty() && !rest.starts_with('/') {
+      return None;
+    }
+    ModuleSpecifier::parse(&format!("{}{}", self.new, rest)).ok()
+  }
+}
+
+fn apply_renames(
+  renames: &[SpecifierRename],
+  specifier: &ModuleSpecifier,
+) -> Option<ModuleSpecifier> {
+  renames.iter().find_map(|rename| rename.apply(specifier))
+}
+
+fn is_relative(specifier: &str) -> bool {
+  specifier.starts_with("./") || specifier.starts_with("../")
+}
+
+/// Gets the edits that update the relative specifiers of imports, re-exports
+/// and dynamic imports in the documents, along with the entries of a local
+/// import map, so that they keep referring to the same modules once the
+/// renames are applied.
+pub fn get_edits_for_file_renames(
+  renames: &[SpecifierRename],
+  documents: &[Document],
+  maybe_import_map: Option<(&ModuleSpecifier, &str)>,
+) -> HashMap<lsp::Url, Vec<lsp::TextEdit>> {
+  let mut changes = HashMap::new();
+  for document in documents {
+    let edits = get_document_edits(renames, document);
+    if !edits.is_empty() {
+      changes.insert(document.specifier().clone(), edits);
+    }
+  }
+  if let Some((import_map_specifier, text)) = maybe_import_map {
+    let edits = get_import_map_edits(renames, import_map_specifier, text);
+    if !edits.is_empty() {
+      changes.insert(import_map_specifier.clone(), edits);
+    }
+  }
+  changes
+}
+
+fn get_document_edits(
+  renames: &[SpecifierRename],
+  document: &Document,
+) -> Vec<lsp::TextEdit> {
+  let referrer = document.specifier();
+  if referrer.scheme() != "file" {
+    return Vec::new();
+  }
+  let parsed_source = match document.maybe_parsed_source() {
+    Some(Ok(parsed_source)) => parsed_source,
+    _ => return Vec::new(),
+  };
+  let new_referrer =
+    apply_renames(renames, referrer).unwrap_or_else(|| referrer.clone());
+  let content = document.content();
+  let line_index = document.line_index();
+  let module_info = DefaultModuleAnalyzer::module_info(&parsed_source);
+  let mut edits = Vec::new();
+  for dependency in module_info.dependencies {
+    if !is_relative(&dependency.specifier) {
+      continue;
+    }
+    let target = match referrer.join(&dependency.specifier) {
+      Ok(target) => target,
+      Err(_) => continue,
+    };
+    let maybe_new_target = apply_renames(renames, &target);
+    if maybe_new_target.is_none() && new_referrer == *referrer {
+      continue;
+    }
+    let new_target = maybe_new_target.unwrap_or(target);
+    let new_specifier = match relative_specifier(&new_referrer, &new_target) {
+      Some(new_specifier) => new_specifier,
+      None => continue,
+    };
+    if new_specifier == dependency.specifier {
+      continue;
+    }
+    let range = lsp::Range {
+      start: lsp::Position {
+        line: dependency.specifier_range.start.line as u32,
+        character: dependency.specifier_range.start.character as u32,
+      },
+      end: lsp::Position {
+        line: dependency.specifier_range.end.line as u32,
+        character: dependency.specifier_range.end.character as u32,
+      },
+    };
+    // keep the quotes the specifier was written with
+    let new_text = match get_text(&content, &line_index, range)
+      .and_then(|text| text.chars().next())
+    {
+      Some(quote @ ('"' | '\'' | '`')) => {
+        format!("{quote}{new_specifier}{quote}")
+      }
+      _ => new_specifier,
+    };
+    edits.push(lsp::TextEdit { range, new_text });
+  }
+  edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+  edits
+}
+
+fn get_text<'a>(
+  content: &'a str,
+  line_index: &LineIndex,
+  range: lsp::Range,
+) -> Option<&'a str> {
+  let start = u32::from(line_index.offset(range.start).ok()?) as usize;
+  let end = u32::from(line_index.offset(range.end).ok()?) as usize;
+  content.get(start..end)
+}
+
+/// Gets the edits for the addresses of an import map which refer to renamed
+/// modules or directories.
+fn get_import_map_edits(
+  renames: &[SpecifierRename],
+  import_map_specifier: &ModuleSpecifier,
+  text: &str,
+) -> Vec<lsp::TextEdit> {
+  let value: Value = match serde_json::from_str(text) {
+    Ok(value) => value,
+    Err(_) => return Vec::new(),
+  };
+  let mut addresses = Vec::new();
+  if let Some(Value::Object(imports)) = value.get("imports") {
+    addresses.extend(imports.values());
+  }
+  if let Some(Value::Object(scopes)) = value.get("scopes") {
+    for scope in scopes.values() {
+      if let Value::Object(imports) = scope {
+        addresses.extend(imports.values());
+      }
+    }
+  }
+
+  let mut new_addresses = HashMap::new();
+  for address in addresses.into_iter().filter_map(|a| a.as_str()) {
+    if new_addresses.contains_key(address) {
+      continue;
+    }
+    let specifier = match import_map_specifier.join(address) {
+      Ok(specifier) => specifier,
+      Err(_) => continue,
+    };
+    let new_specifier = match apply_renames(renames, &specifier) {
+      Some(new_specifier) => new_specifier,
+      None => continue,
+    };
+    let new_address = if is_relative(address) {
+      match relative_specifier(import_map_specifier, &new_specifier) {
+        Some(new_address) => new_address,
+        None => continue,
+      }
+    } else if address.starts_with("file:") {
+      new_specifier.to_string()
+    } else {
+      continue;
+    };
+    new_addresses.insert(address, new_address);
+  }
+
+  let line_index = LineIndex::new(text);
+  let mut edits = Vec::new();
+  for (address, new_address) in new_addresses {
+    let literal = serde_json::to_string(address).unwrap();
+    for (start, _) in text.match_indices(&literal) {
+      // only replace the addresses, not the specifier keys
+      if !text[..start].trim_end().ends_with(':') {
+        continue;
+      }
+      let end = start + literal.len();
+      edits.push(lsp::TextEdit {
+        range: lsp::Range {
+          start: line_index.position_utf16((start as u32).into()),
+          end: line_index.position_utf16((end as u32).into()),
+        },
+        new_text: serde_json::to_string(&new_address).unwrap(),
+      });
+    }
+  }
+  edits.sort_by_key(|edit| (edit.range.start.line, edit.range.start.character));
+  edits
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lsp::documents::Documents;
+  use crate::lsp::documents::LanguageId;
+  use test_util::TempDir;
+
+  fn rename(old: &str, new: &str) -> SpecifierRename {
+    SpecifierRename::from_lsp(&lsp::FileRename {
+      old_uri: old.to_string(),
+      new_uri: new.to_string(),
+    })
+    .unwrap()
+  }
+
+  fn open_documents(sources: &[(&str, &str)]) -> Vec<Document> {
+    let temp_dir = TempDir::new();
+    let mut documents = Documents::new(&temp_dir.path().join("deps"));
+    sources
+      .iter()
+      .map(|(specifier, source)| {
+        documents.open(
+          ModuleSpecifier::parse(specifier).unwrap(),
+          1,
+          LanguageId::TypeScript,
+          (*source).into(),
+        )
+      })
+      .collect()
+  }
+
+  fn new_texts(
+    changes: &HashMap<lsp::Url, Vec<lsp::TextEdit>>,
+    specifier: &str,
+  ) -> Vec<String> {
+    changes
+      .get(&ModuleSpecifier::parse(specifier).unwrap())
+      .map(|edits| edits.iter().map(|e| e.new_text.clone()).collect())
+      .unwrap_or_default()
+  }
+
+  #[test]
+  fn test_file_rename_edits() {
+    let documents = open_documents(&[
+      (
+        "file:///a/main.ts",
+        r#"import { a } from "./utils.ts";
+export * from './utils.ts';
+const b = await import("./lib/b.ts");
+import "https://deno.land/x/mod.ts";
+"#,
+      ),
+      ("file:///a/utils.ts", "export const a = 1;\n"),
+      ("file:///a/lib/b.ts", "import { a } from \"../utils.ts\";\n"),
+    ]);
+    let changes = get_edits_for_file_renames(
+      &[rename("file:///a/utils.ts", "file:///a/helpers.ts")],
+      &documents,
+      None,
+    );
+    assert_eq!(
+      new_texts(&changes, "file:///a/main.ts"),
+      vec!["\"./helpers.ts\"", "'./helpers.ts'"]
+    );
+    assert_eq!(
+      new_texts(&changes, "file:///a/lib/b.ts"),
+      vec!["\"../helpers.ts\""]
+    );
+    assert_eq!(changes.len(), 2);
+  }
+
+  #[test]
+  fn test_directory_rename_edits() {
+    let documents = open_documents(&[
+      (
+        "file:///a/main.ts",
+        "import \"./lib/b.ts\";\nimport \"./lib/c.ts\";\n",
+      ),
+      (
+        "file:///a/lib/b.ts",
+        "import \"./c.ts\";\nimport \"../utils.ts\";\n",
+      ),
+      ("file:///a/lib/c.ts", ""),
+      ("file:///a/utils.ts", ""),
+    ]);
+    let changes = get_edits_for_file_renames(
+      &[rename("file:///a/lib/", "file:///a/src/nested/")],
+      &documents,
+      None,
+    );
+    assert_eq!(
+      new_texts(&changes, "file:///a/main.ts"),
+      vec!["\"./src/nested/b.ts\"", "\"./src/nested/c.ts\""]
+    );
+    // the import of a module that moves along is unchanged
+    assert_eq!(
+      new_texts(&changes, "file:///a/lib/b.ts"),
+      vec!["\"../../utils.ts\""]
+    );
+  }
+
+  #[test]
+  fn test_import_map_edits() {
+    let import_map = r#"{
+  "imports": {
+    "./utils.ts": "./utils.ts",
+    "utils": "./utils.ts",
+    "lib/": "./lib/",
+    "other": "./other.ts"
+  },
+  "scopes": {
+    "./lib/": {
+      "utils": "file:///a/utils.ts"
+    }
+  }
+}"#;
+    let import_map_specifier =
+      ModuleSpecifier::parse("file:///a/import_map.json").unwrap();
+    let changes = get_edits_for_file_renames(
+      &[
+        rename("file:///a/utils.ts", "file:///a/helpers.ts"),
+        rename("file:///a/lib", "file:///a/src"),
+      ],
+      &[],
+      Some((&import_map_specifier, import_map)),
+    );
+    let edits = changes.get(&import_map_specifier).unwrap();
+    assert_eq!(
+      edits
+        .iter()
+        .map(|e| (e.range.start.line, e.new_text.as_str()))
+        .collect::<Vec<_>>(),
+      vec![
+        (2, "\"./helpers.ts\""),
+        (3, "\"./helpers.ts\""),
+        (4, "\"./src/\""),
+        (9, "\"file:///a/helpers.ts\""),
+      ]
+    );
+  }
+}
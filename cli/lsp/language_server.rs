@@ -8,6 +8,7 @@ use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_json::Value;
 use deno_core::ModuleSpecifier;
+use deno_graph::Resolution;
 use deno_runtime::deno_web::BlobStore;
 use import_map::ImportMap;
 use log::error;
@@ -18,12 +19,15 @@ use std::env;
 use std::fmt::Write as _;
 use std::path::PathBuf;
 use std::sync::Arc;
+use text_size::TextSize;
+use tokio_util::sync::CancellationToken;
 use tower_lsp::jsonrpc::Error as LspError;
 use tower_lsp::jsonrpc::Result as LspResult;
 use tower_lsp::lsp_types::request::*;
 use tower_lsp::lsp_types::*;
 
 use super::analysis::fix_ts_import_changes;
+use super::analysis::is_unstable_deno_diagnostic;
 use super::analysis::ts_changes_to_edit;
 use super::analysis::CodeActionCollection;
 use super::analysis::CodeActionData;
@@ -34,6 +38,8 @@ use super::code_lens;
 use super::completions;
 use super::config::Config;
 use super::config::SETTINGS_SECTION;
+use super::config_documents;
+use super::config_documents::ConfigDocumentKind;
 use super::diagnostics;
 use super::diagnostics::DiagnosticsServer;
 use super::documents::to_hover_text;
@@ -42,11 +48,14 @@ use super::documents::AssetOrDocument;
 use super::documents::Document;
 use super::documents::Documents;
 use super::documents::LanguageId;
+use super::file_rename;
 use super::logging::lsp_log;
 use super::lsp_custom;
+use super::npm::NpmTypesEntrypoints;
 use super::parent_process_checker;
 use super::performance::Performance;
 use super::performance::PerformanceMark;
+use super::progress::ProgressReporter;
 use super::refactor;
 use super::registries::ModuleRegistry;
 use super::testing;
@@ -58,12 +67,16 @@ use super::tsc::TsServer;
 use super::urls;
 use crate::args::get_root_cert_store;
 use crate::args::resolve_import_map_from_specifier;
+use crate::args::resolve_tls_client_cert;
+use crate::args::validate_config_text;
 use crate::args::CaData;
 use crate::args::CacheSetting;
 use crate::args::CliOptions;
+use crate::args::ConfigDiagnosticKind;
 use crate::args::ConfigFile;
 use crate::args::Flags;
 use crate::args::FmtOptions;
+use crate::args::LibOverride;
 use crate::args::LintOptions;
 use crate::args::TsConfig;
 use crate::cache::DenoDir;
@@ -71,17 +84,21 @@ use crate::cache::HttpCache;
 use crate::file_fetcher::FileFetcher;
 use crate::graph_util::graph_valid;
 use crate::http_util::HttpClient;
+use crate::node;
 use crate::npm::NpmCache;
+use crate::npm::NpmPackageReference;
 use crate::npm::NpmPackageResolver;
 use crate::npm::RealNpmRegistryApi;
 use crate::proc_state::ProcState;
 use crate::tools::fmt::format_file;
 use crate::tools::fmt::format_parsed_source;
+use crate::util::editorconfig::EditorConfigResolver;
 use crate::util::fs::remove_dir_all_if_exists;
 use crate::util::path::ensure_directory_specifier;
 use crate::util::path::specifier_to_file_path;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
+use crate::version::get_user_agent;
 
 #[derive(Debug, Clone)]
 pub struct LanguageServer(Arc<tokio::sync::RwLock<Inner>>);
@@ -95,6 +112,10 @@ pub struct StateSnapshot {
   pub maybe_import_map: Option<Arc<ImportMap>>,
   pub root_uri: Option<Url>,
   pub maybe_npm_resolver: Option<NpmPackageResolver>,
+  pub npm_types_entrypoints: Arc<NpmTypesEntrypoints>,
+  /// The `"libOverrides"` of the configuration file, each of which is
+  /// type checked by its own TypeScript language service.
+  pub lib_overrides: Vec<LibOverride>,
 }
 
 #[derive(Debug)]
@@ -125,8 +146,10 @@ pub struct Inner {
   /// An optional configuration file which has been specified in the client
   /// options.
   maybe_config_file: Option<ConfigFile>,
+  /// The libraries to type check the documents matching the patterns with.
+  lib_overrides: Vec<LibOverride>,
   /// An optional import map which is used to resolve modules.
-  maybe_import_map: Option<Arc<ImportMap>>,
+  pub maybe_import_map: Option<Arc<ImportMap>>,
   /// The URL for the import map which is used to determine relative imports.
   maybe_import_map_uri: Option<Url>,
   /// Configuration for formatter which has been taken from specified config file.
@@ -136,7 +159,9 @@ pub struct Inner {
   /// A lazily create "server" for handling test run requests.
   maybe_testing_server: Option<testing::TestServer>,
   /// Resolver for npm packages.
-  npm_resolver: NpmPackageResolver,
+  pub npm_resolver: NpmPackageResolver,
+  /// The cached type declaration entrypoints of the npm packages.
+  pub npm_types_entrypoints: Arc<NpmTypesEntrypoints>,
   /// A collection of measurements which instrument that performance of the LSP.
   performance: Arc<Performance>,
   /// A memoized version of fixable diagnostic codes retrieved from TypeScript.
@@ -199,10 +224,25 @@ impl LanguageServer {
           let cli_options = result.cli_options;
           let roots = result.roots;
           let open_docs = result.open_docs;
+          let progress = ProgressReporter::begin(
+            &result.client,
+            result.supports_progress,
+            "Caching dependencies",
+          )
+          .await;
+          // the caching is spawned, so it needs to be cancelled explicitly
+          // when this request is cancelled and its future is dropped
+          let token = CancellationToken::new();
+          let drop_guard = token.clone().drop_guard();
           let handle = tokio::task::spawn_local(async move {
-            create_graph_for_caching(cli_options, roots, open_docs).await
+            let caching =
+              create_graph_for_caching(cli_options, roots, open_docs);
+            tokio::select! {
+              result = caching => Some(result),
+              _ = token.cancelled() => None,
+            }
           });
-          if let Err(err) = handle.await.unwrap() {
+          if let Some(Err(err)) = handle.await.unwrap() {
             self
               .0
               .read()
@@ -211,11 +251,15 @@ impl LanguageServer {
               .show_message(MessageType::WARNING, err)
               .await;
           }
+          drop_guard.disarm();
+          progress.report("Resolving npm packages").await;
           // do npm resolution in a write—we should have everything
           // cached by this point anyway
           self.0.write().await.refresh_npm_specifiers().await;
+          progress.report("Analyzing modules").await;
           // now refresh the data in a read
           self.0.read().await.post_cache(result.mark).await;
+          progress.end().await;
         }
         Ok(Some(json!(true)))
       }
@@ -224,6 +268,17 @@ impl LanguageServer {
     }
   }
 
+  pub async fn document_status_request(
+    &self,
+    params: Option<Value>,
+  ) -> LspResult<Option<Value>> {
+    match params.map(serde_json::from_value) {
+      Some(Ok(params)) => self.0.read().await.document_status(params),
+      Some(Err(err)) => Err(LspError::invalid_params(err.to_string())),
+      None => Err(LspError::invalid_params("Missing parameters")),
+    }
+  }
+
   pub async fn performance_request(&self) -> LspResult<Option<Value>> {
     Ok(Some(self.0.read().await.get_performance()))
   }
@@ -361,6 +416,7 @@ impl Inner {
       http_client,
       maybe_cache_path: None,
       maybe_config_file: None,
+      lib_overrides: Vec::new(),
       maybe_import_map: None,
       maybe_import_map_uri: None,
       fmt_options: Default::default(),
@@ -369,6 +425,7 @@ impl Inner {
       module_registries,
       module_registries_location,
       npm_resolver,
+      npm_types_entrypoints: Default::default(),
       performance,
       ts_fixable_diagnostics: Default::default(),
       ts_server,
@@ -537,7 +594,9 @@ impl Inner {
       documents: self.documents.clone(),
       maybe_import_map: self.maybe_import_map.clone(),
       maybe_npm_resolver: Some(self.npm_resolver.snapshotted()),
+      npm_types_entrypoints: self.npm_types_entrypoints.clone(),
       root_uri: self.config.root_uri.clone(),
+      lib_overrides: self.lib_overrides.clone(),
     })
   }
 
@@ -597,9 +656,11 @@ impl Inner {
       workspace_settings.tls_certificate.map(CaData::File),
     )?);
     let module_registries_location = dir.registries_folder_path();
-    self.http_client = HttpClient::new(
+    self.http_client = HttpClient::new_with_user_agent(
+      get_user_agent(),
       root_cert_store,
       workspace_settings.unsafely_ignore_certificate_errors,
+      resolve_tls_client_cert(None, None, None)?,
     )?;
     self.module_registries = ModuleRegistry::new(
       &module_registries_location,
@@ -801,6 +862,132 @@ impl Inner {
     Ok(())
   }
 
+  /// Whether the document is the configuration file or the import map in
+  /// use, which get completions, hover and diagnostics of their own.
+  fn config_document_kind(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<ConfigDocumentKind> {
+    if let Some(config_file) = &self.maybe_config_file {
+      if config_file.specifier == *specifier {
+        return Some(ConfigDocumentKind::ConfigFile);
+      }
+    }
+    match &self.maybe_import_map_uri {
+      Some(import_map_uri) if import_map_uri == specifier => {
+        Some(ConfigDocumentKind::ImportMap)
+      }
+      _ => None,
+    }
+  }
+
+  /// Gets the text of the configuration file or the import map, preferring
+  /// the text of the document when it is open in the editor.
+  fn config_document_text(
+    &self,
+    specifier: &ModuleSpecifier,
+  ) -> Option<Arc<str>> {
+    match self.documents.get(specifier) {
+      Some(document) if document.is_open() => Some(document.content()),
+      _ => specifier_to_file_path(specifier)
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| text.into()),
+    }
+  }
+
+  async fn send_config_document_diagnostics(&self, kind: ConfigDocumentKind) {
+    match kind {
+      ConfigDocumentKind::ConfigFile => {
+        self.send_config_file_diagnostics().await
+      }
+      ConfigDocumentKind::ImportMap => self.send_import_map_diagnostics().await,
+    }
+  }
+
+  /// Publish the problems found when validating the configuration file and
+  /// the import map it may embed.
+  async fn send_config_file_diagnostics(&self) {
+    let specifier = match &self.maybe_config_file {
+      Some(config_file) => config_file.specifier.clone(),
+      None => return,
+    };
+    let text = match self.config_document_text(&specifier) {
+      Some(text) => text,
+      None => return,
+    };
+    let line_index = text::LineIndex::new(&text);
+    let mut diagnostics: Vec<Diagnostic> = validate_config_text(&text)
+      .into_iter()
+      .map(|diagnostic| Diagnostic {
+        range: Range {
+          start: line_index
+            .position_utf16(TextSize::from(diagnostic.range.start as u32)),
+          end: line_index
+            .position_utf16(TextSize::from(diagnostic.range.end as u32)),
+        },
+        severity: Some(match diagnostic.kind {
+          ConfigDiagnosticKind::UnknownKey => DiagnosticSeverity::WARNING,
+          ConfigDiagnosticKind::InvalidType => DiagnosticSeverity::ERROR,
+        }),
+        source: Some("deno".to_string()),
+        message: diagnostic.message,
+        ..Default::default()
+      })
+      .collect();
+    diagnostics.extend(config_documents::get_import_map_diagnostics(
+      ConfigDocumentKind::ConfigFile,
+      &text,
+      &line_index,
+    ));
+    self
+      .publish_config_document_diagnostics(&specifier, diagnostics)
+      .await;
+  }
+
+  /// Publish the problems found in the entries of the import map, when it's
+  /// a file of its own.
+  async fn send_import_map_diagnostics(&self) {
+    let specifier = match &self.maybe_import_map_uri {
+      Some(import_map_uri)
+        if self.config_document_kind(import_map_uri)
+          == Some(ConfigDocumentKind::ImportMap) =>
+      {
+        import_map_uri.clone()
+      }
+      _ => return,
+    };
+    let text = match self.config_document_text(&specifier) {
+      Some(text) => text,
+      None => return,
+    };
+    let line_index = text::LineIndex::new(&text);
+    let diagnostics = config_documents::get_import_map_diagnostics(
+      ConfigDocumentKind::ImportMap,
+      &text,
+      &line_index,
+    );
+    self
+      .publish_config_document_diagnostics(&specifier, diagnostics)
+      .await;
+  }
+
+  async fn publish_config_document_diagnostics(
+    &self,
+    specifier: &ModuleSpecifier,
+    diagnostics: Vec<Diagnostic>,
+  ) {
+    match self.url_map.normalize_specifier(specifier) {
+      Ok(uri) => {
+        self
+          .client
+          .publish_diagnostics(uri, diagnostics, None)
+          .await
+      }
+      Err(err) => error!("{}", err),
+    }
+  }
+
   async fn update_tsconfig(&mut self) -> Result<(), AnyError> {
     let mark = self.performance.mark("update_tsconfig", None::<()>);
     let mut tsconfig = TsConfig::new(json!({
@@ -831,9 +1018,32 @@ impl Inner {
     if let Err(err) = self.merge_user_tsconfig(&mut tsconfig) {
       self.client.show_message(MessageType::WARNING, err).await;
     }
+    self.lib_overrides = match &self.maybe_config_file {
+      Some(config_file) => match config_file.to_lib_overrides() {
+        Ok(lib_overrides) => lib_overrides,
+        Err(err) => {
+          self.client.show_message(MessageType::WARNING, err).await;
+          Vec::new()
+        }
+      },
+      None => Vec::new(),
+    };
+    if workspace_settings.unstable {
+      for lib_override in &mut self.lib_overrides {
+        lib_override.add_unstable_lib();
+      }
+    }
+    let libs = self
+      .lib_overrides
+      .iter()
+      .map(|lib_override| lib_override.lib.clone())
+      .collect();
     let _ok: bool = self
       .ts_server
-      .request(self.snapshot(), tsc::RequestMethod::Configure(tsconfig))
+      .request(
+        self.snapshot(),
+        tsc::RequestMethod::Configure(tsconfig, libs),
+      )
       .await?;
     self.performance.measure(mark);
     Ok(())
@@ -910,6 +1120,7 @@ impl Inner {
     if let Err(err) = self.update_config_file() {
       self.client.show_message(MessageType::WARNING, err).await;
     }
+    self.send_config_file_diagnostics().await;
     if let Err(err) = self.update_tsconfig().await {
       self.client.show_message(MessageType::WARNING, err).await;
     }
@@ -1023,6 +1234,9 @@ impl Inner {
       params.text_document.language_id.parse().unwrap(),
       params.text_document.text.into(),
     );
+    if let Some(kind) = self.config_document_kind(specifier) {
+      self.send_config_document_diagnostics(kind).await;
+    }
 
     self.performance.measure(mark);
     document
@@ -1048,6 +1262,9 @@ impl Inner {
       }
       Err(err) => error!("{}", err),
     }
+    if let Some(kind) = self.config_document_kind(&specifier) {
+      self.send_config_document_diagnostics(kind).await;
+    }
     self.performance.measure(mark);
   }
 
@@ -1071,6 +1288,9 @@ impl Inner {
     if let Err(err) = self.documents.close(&specifier) {
       error!("{}", err);
     }
+    if let Some(kind) = self.config_document_kind(&specifier) {
+      self.send_config_document_diagnostics(kind).await;
+    }
     if self.is_diagnosable(&specifier) {
       self.refresh_npm_specifiers().await;
       let mut specifiers = self.documents.dependents(&specifier);
@@ -1114,6 +1334,7 @@ impl Inner {
     if let Err(err) = self.update_config_file() {
       self.client.show_message(MessageType::WARNING, err).await;
     }
+    self.send_config_file_diagnostics().await;
     if let Err(err) = self.update_import_map().await {
       self.client.show_message(MessageType::WARNING, err).await;
     }
@@ -1150,6 +1371,7 @@ impl Inner {
         if let Err(err) = self.update_config_file() {
           self.client.show_message(MessageType::WARNING, err).await;
         }
+        self.send_config_file_diagnostics().await;
         if let Err(err) = self.update_tsconfig().await {
           self.client.show_message(MessageType::WARNING, err).await;
         }
@@ -1163,6 +1385,7 @@ impl Inner {
         if let Err(err) = self.update_import_map().await {
           self.client.show_message(MessageType::WARNING, err).await;
         }
+        self.send_import_map_diagnostics().await;
         touched = true;
       }
     }
@@ -1266,9 +1489,11 @@ impl Inner {
       return Ok(None);
     }
 
+    let properties = EditorConfigResolver::default().properties(&file_path);
+    let fmt_options = self.fmt_options.options.with_editorconfig(&properties);
     let format_result = match document.maybe_parsed_source() {
       Some(Ok(parsed_source)) => {
-        format_parsed_source(&parsed_source, &self.fmt_options.options)
+        format_parsed_source(&parsed_source, &fmt_options)
       }
       Some(Err(err)) => Err(anyhow!("{}", err)),
       None => {
@@ -1281,7 +1506,7 @@ impl Inner {
           .map(|ext| file_path.with_extension(ext))
           .unwrap_or(file_path);
         // it's not a js/ts file, so attempt to format its contents
-        format_file(&file_path, &document.content(), &self.fmt_options.options)
+        format_file(&file_path, &document.content(), &fmt_options)
       }
     };
 
@@ -1316,6 +1541,18 @@ impl Inner {
     let specifier = self
       .url_map
       .normalize_url(&params.text_document_position_params.text_document.uri);
+    if let Some(kind) = self.config_document_kind(&specifier) {
+      let document = match self.documents.get(&specifier) {
+        Some(document) => document,
+        None => return Ok(None),
+      };
+      return Ok(config_documents::get_hover(
+        kind,
+        &document.content(),
+        params.text_document_position_params.position,
+        &document.line_index(),
+      ));
+    }
     if !self.is_diagnosable(&specifier)
       || !self.config.specifier_enabled(&specifier)
     {
@@ -1419,15 +1656,18 @@ impl Inner {
       .iter()
       .filter(|d| match &d.source {
         Some(source) => match source.as_str() {
-          "deno-ts" => match &d.code {
-            Some(NumberOrString::String(code)) => {
-              self.ts_fixable_diagnostics.contains(code)
-            }
-            Some(NumberOrString::Number(code)) => {
-              self.ts_fixable_diagnostics.contains(&code.to_string())
-            }
-            _ => false,
-          },
+          "deno-ts" => {
+            is_unstable_deno_diagnostic(d)
+              || match &d.code {
+                Some(NumberOrString::String(code)) => {
+                  self.ts_fixable_diagnostics.contains(code)
+                }
+                Some(NumberOrString::Number(code)) => {
+                  self.ts_fixable_diagnostics.contains(&code.to_string())
+                }
+                _ => false,
+              }
+          }
           "deno-lint" => matches!(&d.code, Some(_)),
           "deno" => diagnostics::DenoDiagnostic::is_fixable(d),
           _ => false,
@@ -1443,6 +1683,14 @@ impl Inner {
       for diagnostic in &fixable_diagnostics {
         match diagnostic.source.as_deref() {
           Some("deno-ts") => {
+            if is_unstable_deno_diagnostic(diagnostic) {
+              code_actions.add_deno_unstable_actions(
+                &specifier,
+                diagnostic,
+                asset_or_doc.document().map(|d| d.text_info()),
+                self.maybe_config_file.as_ref(),
+              );
+            }
             let code = match diagnostic.code.as_ref().unwrap() {
               NumberOrString::String(code) => code.to_string(),
               NumberOrString::Number(code) => code.to_string(),
@@ -1909,6 +2157,18 @@ impl Inner {
     let specifier = self
       .url_map
       .normalize_url(&params.text_document_position.text_document.uri);
+    if let Some(kind) = self.config_document_kind(&specifier) {
+      let document = match self.documents.get(&specifier) {
+        Some(document) => document,
+        None => return Ok(None),
+      };
+      return Ok(config_documents::get_completions(
+        kind,
+        &document.content(),
+        params.text_document_position.position,
+        &document.line_index(),
+      ));
+    }
     if !self.is_diagnosable(&specifier)
       || !self.config.specifier_enabled(&specifier)
     {
@@ -1929,6 +2189,7 @@ impl Inner {
       &self.module_registries,
       &self.documents,
       self.maybe_import_map.clone(),
+      &self.npm_resolver,
     )
     .await
     {
@@ -2364,6 +2625,55 @@ impl Inner {
     }
   }
 
+  async fn will_rename_files(
+    &self,
+    params: RenameFilesParams,
+  ) -> LspResult<Option<WorkspaceEdit>> {
+    let mark = self.performance.mark("will_rename_files", Some(&params));
+    let renames = params
+      .files
+      .iter()
+      .filter_map(file_rename::SpecifierRename::from_lsp)
+      .collect::<Vec<_>>();
+    if renames.is_empty() {
+      self.performance.measure(mark);
+      return Ok(None);
+    }
+
+    let documents = self
+      .documents
+      .documents(false, true)
+      .into_iter()
+      .filter(|doc| self.config.specifier_enabled(doc.specifier()))
+      .collect::<Vec<_>>();
+    // only a local import map can be edited along with the modules
+    let maybe_import_map = self
+      .maybe_import_map_uri
+      .as_ref()
+      .filter(|uri| uri.scheme() == "file")
+      .and_then(|uri| {
+        let path = specifier_to_file_path(uri).ok()?;
+        let text = std::fs::read_to_string(path).ok()?;
+        Some((uri, text))
+      });
+    let changes = file_rename::get_edits_for_file_renames(
+      &renames,
+      &documents,
+      maybe_import_map
+        .as_ref()
+        .map(|(uri, text)| (*uri, text.as_str())),
+    );
+    self.performance.measure(mark);
+    if changes.is_empty() {
+      Ok(None)
+    } else {
+      Ok(Some(WorkspaceEdit {
+        changes: Some(changes),
+        ..Default::default()
+      }))
+    }
+  }
+
   async fn selection_range(
     &self,
     params: SelectionRangeParams,
@@ -2939,6 +3249,13 @@ impl tower_lsp::LanguageServer for LanguageServer {
     self.0.read().await.rename(params).await
   }
 
+  async fn will_rename_files(
+    &self,
+    params: RenameFilesParams,
+  ) -> LspResult<Option<WorkspaceEdit>> {
+    self.0.read().await.will_rename_files(params).await
+  }
+
   async fn selection_range(
     &self,
     params: SelectionRangeParams,
@@ -2980,6 +3297,8 @@ struct PrepareCacheResult {
   roots: Vec<ModuleSpecifier>,
   open_docs: Vec<Document>,
   mark: PerformanceMark,
+  client: Client,
+  supports_progress: bool,
 }
 
 // These are implementations of custom commands supported by the LSP
@@ -3027,6 +3346,8 @@ impl Inner {
       open_docs,
       roots,
       mark,
+      client: self.client.clone(),
+      supports_progress: self.config.client_capabilities.work_done_progress,
     }))
   }
 
@@ -3051,6 +3372,178 @@ impl Inner {
       .unwrap();
   }
 
+  fn document_status(
+    &self,
+    params: lsp_custom::DocumentStatusParams,
+  ) -> LspResult<Option<Value>> {
+    let specifier = self.url_map.normalize_url(&params.text_document.uri);
+    Ok(Some(json!(urls::to_status_url(&specifier))))
+  }
+
+  /// Describe what the language server knows about a module, based on the
+  /// documents and configuration already in memory.
+  fn get_document_status(&self, specifier: &ModuleSpecifier) -> String {
+    let mut contents = String::new();
+    writeln!(contents, "# Deno Document Status\n\n`{specifier}`\n").unwrap();
+
+    contents.push_str("## Configuration\n\n");
+    writeln!(
+      contents,
+      "  - Deno enabled: {}",
+      if self.config.specifier_enabled(specifier) {
+        "yes"
+      } else {
+        "no"
+      }
+    )
+    .unwrap();
+    match &self.maybe_config_file {
+      Some(config_file) => writeln!(
+        contents,
+        "  - Configuration file: [{0}]({0})",
+        config_file.specifier
+      ),
+      None => writeln!(contents, "  - Configuration file: none"),
+    }
+    .unwrap();
+    match &self.maybe_import_map_uri {
+      Some(import_map_uri) => {
+        writeln!(
+          contents,
+          "  - Import map: [{import_map_uri}]({import_map_uri})"
+        )
+      }
+      None => writeln!(contents, "  - Import map: none"),
+    }
+    .unwrap();
+
+    let maybe_document = self.documents.get(specifier);
+    match &maybe_document {
+      Some(document) => {
+        match document.maybe_lsp_version() {
+          Some(version) => writeln!(
+            contents,
+            "  - Document: open in the editor (version {version})"
+          ),
+          None => writeln!(contents, "  - Document: loaded from disk or cache"),
+        }
+        .unwrap();
+        writeln!(contents, "  - Media type: {}", document.media_type())
+          .unwrap();
+        if let Some(Err(err)) = document.maybe_parsed_source() {
+          writeln!(contents, "  - Parse error: {err}").unwrap();
+        }
+      }
+      None => contents.push_str("  - Document: not loaded\n"),
+    }
+
+    contents.push_str("\n## Dependencies\n\n");
+    let dependencies = maybe_document
+      .as_ref()
+      .map(|document| document.dependencies())
+      .filter(|dependencies| !dependencies.is_empty());
+    let dependencies = match dependencies {
+      Some(dependencies) => dependencies,
+      None => {
+        contents.push_str("The document has no dependencies.\n");
+        return contents;
+      }
+    };
+    contents.push_str("|Specifier|Kind|Resolved|Status|\n|---|---|---|---|\n");
+    for (key, dependency) in dependencies {
+      let resolutions = [
+        ("code", &dependency.maybe_code),
+        ("types", &dependency.maybe_type),
+      ];
+      for (kind, resolution) in resolutions {
+        let (resolved, status) = match resolution {
+          Resolution::None => continue,
+          Resolution::Ok(resolved) => (
+            format!("`{}`", resolved.specifier),
+            self.get_dependency_status(&resolved.specifier),
+          ),
+          Resolution::Err(err) => (String::new(), format!("error: {err}")),
+        };
+        writeln!(
+          contents,
+          "|`{}`|{}{}|{}|{}|",
+          key,
+          kind,
+          if dependency.is_dynamic {
+            " (dynamic)"
+          } else {
+            ""
+          },
+          resolved,
+          status.replace('|', "\\|")
+        )
+        .unwrap();
+      }
+    }
+    contents
+  }
+
+  /// Describe whether a resolved dependency is available, linking to where it
+  /// is stored locally when it is.
+  fn get_dependency_status(&self, specifier: &ModuleSpecifier) -> String {
+    if let Some(document) = self.documents.get(specifier) {
+      let mut status = if specifier.scheme() == "file" {
+        "local".to_string()
+      } else {
+        "cached".to_string()
+      };
+      if document.specifier() != specifier {
+        write!(status, ", redirected to `{}`", document.specifier()).unwrap();
+      }
+      let maybe_location = if document.specifier().scheme() == "file" {
+        Some(document.specifier().clone())
+      } else {
+        self
+          .deps_http_cache
+          .get_cache_filename(document.specifier())
+          .and_then(|path| ModuleSpecifier::from_file_path(path).ok())
+      };
+      if let Some(location) = maybe_location {
+        write!(status, " ([location]({location}))").unwrap();
+      }
+      status
+    } else if let Ok(pkg_ref) = NpmPackageReference::from_specifier(specifier) {
+      let snapshot = self.npm_resolver.snapshot();
+      match snapshot.resolve_package_from_deno_module(&pkg_ref.req) {
+        Ok(package) => {
+          let maybe_folder = self
+            .npm_resolver
+            .resolve_package_folder_from_deno_module(&pkg_ref.req)
+            .ok()
+            .filter(|folder| folder.exists())
+            .and_then(|folder| {
+              ModuleSpecifier::from_directory_path(folder).ok()
+            });
+          match maybe_folder {
+            Some(folder) => format!(
+              "npm package `{}` ([location]({folder}))",
+              package.id.display()
+            ),
+            None => {
+              format!("npm package `{}`, not cached", package.id.display())
+            }
+          }
+        }
+        Err(_) => "uncached npm package".to_string(),
+      }
+    } else if let Some(module_name) = specifier.as_str().strip_prefix("node:") {
+      if node::resolve_builtin_node_module(module_name).is_ok() {
+        "built-in Node module".to_string()
+      } else {
+        "unknown Node module".to_string()
+      }
+    } else if specifier.scheme() == "file" {
+      "missing local module".to_string()
+    } else {
+      "uncached".to_string()
+    }
+  }
+
   fn get_performance(&self) -> Value {
     let averages = self.performance.averages();
     json!({ "averages": averages })
@@ -3182,6 +3675,34 @@ impl Inner {
           .join("\n    - ")
       )
       .unwrap();
+      contents.push_str("\n## Permissions\n\n");
+      match self
+        .maybe_config_file
+        .as_ref()
+        .map(|config_file| config_file.to_permissions_config())
+      {
+        Some(Ok(Some(permissions_config))) => {
+          let args = permissions_config.to_permission_args();
+          if args.is_empty() {
+            contents.push_str(
+              "The configuration file doesn't grant any permissions.\n",
+            );
+          } else {
+            writeln!(
+              contents,
+              "The configuration file grants `{}`. Flags override these permissions.",
+              args.join(" ")
+            )
+            .unwrap();
+          }
+        }
+        Some(Err(err)) => {
+          writeln!(contents, "The permissions are invalid: {err:#}").unwrap();
+        }
+        _ => {
+          contents.push_str("No permissions are configured.\n");
+        }
+      }
       contents
         .push_str("\n## Performance\n\n|Name|Duration|Count|\n|---|---|---|\n");
       let mut averages = self.performance.averages();
@@ -3195,6 +3716,8 @@ impl Inner {
         .unwrap();
       }
       Some(contents)
+    } else if let Some(status_specifier) = urls::from_status_url(&specifier) {
+      Some(self.get_document_status(&status_specifier))
     } else {
       let asset_or_doc = self.get_maybe_asset_or_document(&specifier);
       if let Some(asset_or_doc) = asset_or_doc {
@@ -5,6 +5,7 @@ use deno_core::serde::Serialize;
 use tower_lsp::lsp_types as lsp;
 
 pub const CACHE_REQUEST: &str = "deno/cache";
+pub const DOCUMENT_STATUS_REQUEST: &str = "deno/documentStatus";
 pub const PERFORMANCE_REQUEST: &str = "deno/performance";
 pub const TASK_REQUEST: &str = "deno/task";
 pub const RELOAD_IMPORT_REGISTRIES_REQUEST: &str =
@@ -25,6 +26,14 @@ pub struct CacheParams {
   pub uris: Vec<lsp::TextDocumentIdentifier>,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentStatusParams {
+  /// The document to describe. The response is the URL of a virtual document
+  /// which can then be requested via `deno/virtualTextDocument`.
+  pub text_document: lsp::TextDocumentIdentifier,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RegistryStateNotificationParams {
   pub origin: String,
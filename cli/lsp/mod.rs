@@ -15,14 +15,18 @@ mod client;
 mod code_lens;
 mod completions;
 mod config;
+mod config_documents;
 mod diagnostics;
 mod documents;
+mod file_rename;
 pub mod language_server;
 mod logging;
 mod lsp_custom;
+mod npm;
 mod parent_process_checker;
 mod path_to_regex;
 mod performance;
+mod progress;
 mod refactor;
 mod registries;
 mod repl;
@@ -40,6 +44,10 @@ pub async fn start() -> Result<(), AnyError> {
     language_server::LanguageServer::new(client::Client::from_tower(client))
   })
   .custom_method(lsp_custom::CACHE_REQUEST, LanguageServer::cache_request)
+  .custom_method(
+    lsp_custom::DOCUMENT_STATUS_REQUEST,
+    LanguageServer::document_status_request,
+  )
   .custom_method(
     lsp_custom::PERFORMANCE_REQUEST,
     LanguageServer::performance_request,
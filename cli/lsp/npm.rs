@@ -0,0 +1,172 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use super::documents::Document;
+use crate::node::node_resolve_npm_reference;
+use crate::node::NodeResolution;
+use crate::npm::NpmPackageId;
+use crate::npm::NpmPackageReference;
+use crate::npm::NpmPackageReq;
+use crate::npm::NpmPackageResolver;
+
+use deno_ast::MediaType;
+use deno_core::parking_lot::Mutex;
+use deno_core::ModuleSpecifier;
+use deno_runtime::deno_node::NodeResolutionMode;
+use deno_runtime::permissions::PermissionsContainer;
+use import_map::ImportMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// The type declaration entrypoints of the npm packages imported by the
+/// documents, which are provided to tsc as root files so the symbols they
+/// export are offered as auto-imports. Only the entrypoints are provided, so tsc doesn't have to scan
+/// every file of every package.
+///
+/// Entrypoints are cached per package version, as they are looked up for every
+/// update of the script names and every auto-import of a module in a package.
+#[derive(Debug, Default)]
+pub struct NpmTypesEntrypoints(
+  Mutex<HashMap<NpmPackageId, Option<ModuleSpecifier>>>,
+);
+
+impl NpmTypesEntrypoints {
+  /// Gets the entrypoints of the packages imported by the documents, which
+  /// provide type declarations.
+  pub fn for_documents(
+    &self,
+    documents: &[Document],
+    npm_resolver: &NpmPackageResolver,
+  ) -> Vec<ModuleSpecifier> {
+    let snapshot = npm_resolver.snapshot();
+    let package_reqs = snapshot.package_reqs();
+    let ids = package_reqs.values().collect::<HashSet<_>>();
+    self.0.lock().retain(|id, _| ids.contains(id));
+
+    let mut reqs = documents
+      .iter()
+      .flat_map(|document| document.dependencies().values())
+      .filter_map(|dependency| dependency.get_code())
+      .filter_map(|specifier| {
+        NpmPackageReference::from_specifier(specifier).ok()
+      })
+      .map(|reference| reference.req)
+      .collect::<Vec<_>>();
+    reqs.sort_by_key(|req| req.to_string());
+    reqs.dedup();
+
+    let mut entrypoints = Vec::new();
+    for req in reqs {
+      let id = match package_reqs.get(&req) {
+        Some(id) => id,
+        None => continue,
+      };
+      if let Some(entrypoint) = self.get(&req, id, npm_resolver) {
+        if !entrypoints.contains(&entrypoint) {
+          entrypoints.push(entrypoint);
+        }
+      }
+    }
+    entrypoints
+  }
+
+  /// Gets the entrypoint of a package, resolving it the first time.
+  fn get(
+    &self,
+    req: &NpmPackageReq,
+    id: &NpmPackageId,
+    npm_resolver: &NpmPackageResolver,
+  ) -> Option<ModuleSpecifier> {
+    if let Some(maybe_entrypoint) = self.0.lock().get(id) {
+      return maybe_entrypoint.clone();
+    }
+    // packages which aren't cached yet are retried on the next lookup
+    if !is_package_cached(req, npm_resolver) {
+      return None;
+    }
+    let maybe_entrypoint = resolve_types_entrypoint(req, npm_resolver);
+    self.0.lock().insert(id.clone(), maybe_entrypoint.clone());
+    maybe_entrypoint
+  }
+}
+
+fn is_package_cached(
+  req: &NpmPackageReq,
+  npm_resolver: &NpmPackageResolver,
+) -> bool {
+  npm_resolver
+    .resolve_package_folder_from_deno_module(req)
+    .map(|folder| folder.exists())
+    .unwrap_or(false)
+}
+
+fn resolve_types_entrypoint(
+  req: &NpmPackageReq,
+  npm_resolver: &NpmPackageResolver,
+) -> Option<ModuleSpecifier> {
+  let reference = NpmPackageReference {
+    req: req.clone(),
+    sub_path: None,
+  };
+  let resolution = node_resolve_npm_reference(
+    &reference,
+    NodeResolutionMode::Types,
+    npm_resolver,
+    &mut PermissionsContainer::allow_all(),
+  )
+  .ok()
+  .flatten()?;
+  let (specifier, media_type) =
+    NodeResolution::into_specifier_and_media_type(Some(resolution));
+  match media_type {
+    MediaType::Dts | MediaType::Dmts | MediaType::Dcts => Some(specifier),
+    _ => None,
+  }
+}
+
+/// Return the import specifier a referrer should use for a module inside one
+/// of the top level npm packages, instead of a path into the npm cache. This
+/// is the `npm:` specifier of the package, or the specifier mapped to it by
+/// the import map.
+pub fn npm_import_specifier(
+  specifier: &ModuleSpecifier,
+  referrer: &ModuleSpecifier,
+  npm_resolver: &NpmPackageResolver,
+  types_entrypoints: &NpmTypesEntrypoints,
+  maybe_import_map: Option<&ImportMap>,
+) -> Option<String> {
+  if !npm_resolver.in_npm_package(specifier) {
+    return None;
+  }
+  let path = specifier.to_file_path().ok()?;
+  let snapshot = npm_resolver.snapshot();
+  let mut package_reqs = snapshot.package_reqs().iter().collect::<Vec<_>>();
+  package_reqs.sort_by_key(|(req, _)| req.to_string());
+  let npm_specifier = package_reqs.into_iter().find_map(|(req, id)| {
+    let folder = npm_resolver
+      .resolve_package_folder_from_deno_module(req)
+      .ok()?;
+    let sub_path = path.strip_prefix(&folder).ok()?;
+    if types_entrypoints.get(req, id, npm_resolver).as_ref() == Some(specifier)
+    {
+      return Some(format!("npm:{req}"));
+    }
+    let sub_path = sub_path
+      .components()
+      .map(|c| c.as_os_str().to_string_lossy())
+      .collect::<Vec<_>>()
+      .join("/");
+    let sub_path = match sub_path.strip_suffix(".d.ts") {
+      Some(stem) => format!("{stem}.js"),
+      None => sub_path,
+    };
+    Some(format!("npm:{req}/{sub_path}"))
+  })?;
+  if let Some(import_map) = maybe_import_map {
+    if let Ok(npm_specifier) = ModuleSpecifier::parse(&npm_specifier) {
+      if let Some(mapped) = import_map.lookup(&npm_specifier, referrer) {
+        return Some(mapped);
+      }
+    }
+  }
+  Some(npm_specifier)
+}
@@ -0,0 +1,95 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use tower_lsp::lsp_types as lsp;
+
+use super::client::Client;
+use super::logging::lsp_log;
+
+static NEXT_TOKEN_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Reports the progress of a long running operation to the client with a
+/// server initiated `window/workDoneProgress` token. Nothing is reported when
+/// the client doesn't support it or refuses to create the token. The progress
+/// is ended when the reporter is dropped, so a cancelled operation doesn't
+/// leave it behind.
+pub struct ProgressReporter {
+  client: Client,
+  maybe_token: Option<lsp::NumberOrString>,
+}
+
+impl ProgressReporter {
+  pub async fn begin(client: &Client, is_supported: bool, title: &str) -> Self {
+    let mut maybe_token = None;
+    if is_supported {
+      let id = NEXT_TOKEN_ID.fetch_add(1, Ordering::Relaxed);
+      let token = lsp::NumberOrString::String(format!("deno/progress/{id}"));
+      match client.create_work_done_progress(token.clone()).await {
+        Ok(()) => {
+          client
+            .send_work_done_progress(
+              token.clone(),
+              lsp::WorkDoneProgress::Begin(lsp::WorkDoneProgressBegin {
+                title: title.to_string(),
+                cancellable: Some(false),
+                message: None,
+                percentage: None,
+              }),
+            )
+            .await;
+          maybe_token = Some(token);
+        }
+        Err(err) => {
+          lsp_log!("Unable to create a work done progress token: {}", err);
+        }
+      }
+    }
+    Self {
+      client: client.clone(),
+      maybe_token,
+    }
+  }
+
+  pub async fn report(&self, message: &str) {
+    if let Some(token) = &self.maybe_token {
+      self
+        .client
+        .send_work_done_progress(
+          token.clone(),
+          lsp::WorkDoneProgress::Report(lsp::WorkDoneProgressReport {
+            cancellable: None,
+            message: Some(message.to_string()),
+            percentage: None,
+          }),
+        )
+        .await;
+    }
+  }
+
+  pub async fn end(mut self) {
+    if let Some(token) = self.maybe_token.take() {
+      self
+        .client
+        .send_work_done_progress(token, end_progress())
+        .await;
+    }
+  }
+}
+
+impl Drop for ProgressReporter {
+  fn drop(&mut self) {
+    // the operation was cancelled before it ended
+    if let Some(token) = self.maybe_token.take() {
+      let client = self.client.clone();
+      tokio::spawn(async move {
+        client.send_work_done_progress(token, end_progress()).await;
+      });
+    }
+  }
+}
+
+fn end_progress() -> lsp::WorkDoneProgress {
+  lsp::WorkDoneProgress::End(lsp::WorkDoneProgressEnd { message: None })
+}
@@ -27,6 +27,7 @@ use deno_core::futures::StreamExt;
 use deno_core::parking_lot::Mutex;
 use deno_core::parking_lot::RwLock;
 use deno_core::ModuleSpecifier;
+use deno_runtime::fmt_errors::StackTraceOptions;
 use deno_runtime::ops::io::Stdio;
 use deno_runtime::ops::io::StdioPipe;
 use deno_runtime::permissions::Permissions;
@@ -165,7 +166,15 @@ async fn test_specifier(
       &ps,
       specifier.clone(),
       PermissionsContainer::new(permissions),
-      vec![ops::testing::init(sender, fail_fast_tracker, filter)],
+      vec![ops::testing::init(
+        sender,
+        fail_fast_tracker,
+        filter,
+        None,
+        false,
+        None,
+        Default::default(),
+      )],
       Stdio {
         stdin: StdioPipe::Inherit,
         stdout,
@@ -256,6 +265,7 @@ impl TestRun {
     lsp_log!("Executing test run with arguments: {}", args.join(" "));
     let flags = flags_from_vec(args.into_iter().map(String::from).collect())?;
     let ps = proc_state::ProcState::build(flags).await?;
+    let stack_trace_options = ps.options.stack_trace_options();
     // Various test files should not share the same permissions in terms of
     // `PermissionsContainer` - otherwise granting/revoking permissions in one
     // file would have impact on other files, which is undesirable.
@@ -287,7 +297,7 @@ impl TestRun {
 
     let (sender, mut receiver) = mpsc::unbounded_channel::<test::TestEvent>();
     let sender = TestEventSender::new(sender);
-    let fail_fast_tracker = FailFastTracker::new(fail_fast);
+    let fail_fast_tracker = FailFastTracker::new(fail_fast, None);
 
     let mut queue = self.queue.iter().collect::<Vec<&ModuleSpecifier>>();
     queue.sort();
@@ -315,6 +325,8 @@ impl TestRun {
         exclude: lsp_filter
           .map(|f| f.exclude.values().map(|t| t.name.clone()).collect())
           .unwrap_or_default(),
+        tags: vec![],
+        skip_tags: vec![],
       };
       let token = self.token.clone();
       let tests = tests_.clone();
@@ -366,6 +378,7 @@ impl TestRun {
       client.clone(),
       maybe_root_uri,
       self.tests.clone(),
+      stack_trace_options,
     ));
 
     let handler = {
@@ -383,6 +396,7 @@ impl TestRun {
             test::TestEvent::Plan(plan) => {
               summary.total += plan.total;
               summary.filtered_out += plan.filtered_out;
+              summary.excluded_by_tag += plan.excluded_by_tag;
 
               if plan.used_only {
                 used_only = true;
@@ -405,6 +419,12 @@ impl TestRun {
                   summary.failed += 1;
                   summary.failures.push((description.clone(), error.clone()));
                 }
+                test::TestResult::ExpectedFailure(_) => {
+                  summary.expected_failures += 1;
+                }
+                test::TestResult::UnexpectedlyPassed => {
+                  summary.unexpected_passes.push(description.clone());
+                }
                 test::TestResult::Cancelled => {
                   summary.failed += 1;
                 }
@@ -412,6 +432,8 @@ impl TestRun {
 
               reporter.report_result(&description, &result, elapsed);
             }
+            // the tests run from the editor aren't retried
+            test::TestEvent::Retry(_, _) => {}
             test::TestEvent::UncaughtError(origin, error) => {
               reporter.report_uncaught_error(&origin, &error);
               summary.failed += 1;
@@ -438,6 +460,12 @@ impl TestRun {
                 test::TestStepResult::Pending(_) => {
                   summary.pending_steps += 1;
                 }
+                test::TestStepResult::ExpectedFailure(_) => {
+                  summary.expected_failure_steps += 1;
+                }
+                test::TestStepResult::UnexpectedlyPassed => {
+                  summary.unexpectedly_passed_steps += 1;
+                }
               }
               reporter.report_step_result(
                 test_steps.get(&id).unwrap(),
@@ -445,6 +473,9 @@ impl TestRun {
                 duration,
               );
             }
+            test::TestEvent::FailFastCutoff(_, skipped) => {
+              summary.fail_fast_skipped += skipped;
+            }
           }
         }
 
@@ -457,7 +488,7 @@ impl TestRun {
           ));
         }
 
-        if summary.failed > 0 {
+        if summary.failed > 0 || !summary.unexpected_passes.is_empty() {
           return Err(anyhow!("Test failed"));
         }
 
@@ -604,6 +635,7 @@ struct LspTestReporter {
   id: u32,
   stack: HashMap<String, Vec<TestOrTestStepDescription>>,
   tests: Arc<Mutex<HashMap<ModuleSpecifier, TestDefinitions>>>,
+  stack_trace_options: StackTraceOptions,
 }
 
 impl LspTestReporter {
@@ -612,6 +644,7 @@ impl LspTestReporter {
     client: Client,
     maybe_root_uri: Option<&ModuleSpecifier>,
     tests: Arc<Mutex<HashMap<ModuleSpecifier, TestDefinitions>>>,
+    stack_trace_options: StackTraceOptions,
   ) -> Self {
     Self {
       client,
@@ -620,6 +653,7 @@ impl LspTestReporter {
       id: run.id,
       stack: HashMap::new(),
       tests,
+      stack_trace_options,
     }
   }
 
@@ -701,25 +735,32 @@ impl LspTestReporter {
     assert_eq!(stack.pop(), Some(desc.into()));
     self.current_origin = None;
     match result {
-      test::TestResult::Ok => {
-        self.progress(lsp_custom::TestRunProgressMessage::Passed {
+      test::TestResult::Ok | test::TestResult::ExpectedFailure(_) => self
+        .progress(lsp_custom::TestRunProgressMessage::Passed {
           test: desc.into(),
           duration: Some(elapsed as u32),
-        })
-      }
+        }),
       test::TestResult::Ignored => {
         self.progress(lsp_custom::TestRunProgressMessage::Skipped {
           test: desc.into(),
         })
       }
       test::TestResult::Failed(js_error) => {
-        let err_string = test::format_test_error(js_error);
+        let err_string =
+          test::format_test_error(js_error, &self.stack_trace_options);
         self.progress(lsp_custom::TestRunProgressMessage::Failed {
           test: desc.into(),
           messages: as_test_messages(err_string, false),
           duration: Some(elapsed as u32),
         })
       }
+      test::TestResult::UnexpectedlyPassed => {
+        self.progress(lsp_custom::TestRunProgressMessage::Failed {
+          test: desc.into(),
+          messages: as_test_messages(test::UNEXPECTED_PASS_MESSAGE, false),
+          duration: Some(elapsed as u32),
+        })
+      }
       test::TestResult::Cancelled => {
         self.progress(lsp_custom::TestRunProgressMessage::Failed {
           test: desc.into(),
@@ -738,7 +779,7 @@ impl LspTestReporter {
     let err_string = format!(
       "Uncaught error from {}: {}\nThis error was not caught from a test and caused the test runner to fail on the referenced module.\nIt most likely originated from a dangling promise, event/timeout handler or top-level code.",
       origin,
-      test::format_test_error(js_error)
+      test::format_test_error(js_error, &self.stack_trace_options)
     );
     let messages = as_test_messages(err_string, false);
     for t in stack.iter().rev() {
@@ -815,7 +856,7 @@ impl LspTestReporter {
     let stack = self.stack.entry(desc.origin.clone()).or_default();
     assert_eq!(stack.pop(), Some(desc.into()));
     match result {
-      test::TestStepResult::Ok => {
+      test::TestStepResult::Ok | test::TestStepResult::ExpectedFailure(_) => {
         self.progress(lsp_custom::TestRunProgressMessage::Passed {
           test: desc.into(),
           duration: Some(elapsed as u32),
@@ -828,7 +869,8 @@ impl LspTestReporter {
       }
       test::TestStepResult::Failed(js_error) => {
         let messages = if let Some(js_error) = js_error {
-          let err_string = test::format_test_error(js_error);
+          let err_string =
+            test::format_test_error(js_error, &self.stack_trace_options);
           as_test_messages(err_string, false)
         } else {
           vec![]
@@ -839,6 +881,13 @@ impl LspTestReporter {
           duration: Some(elapsed as u32),
         })
       }
+      test::TestStepResult::UnexpectedlyPassed => {
+        self.progress(lsp_custom::TestRunProgressMessage::Failed {
+          test: desc.into(),
+          messages: as_test_messages(test::UNEXPECTED_PASS_MESSAGE, false),
+          duration: Some(elapsed as u32),
+        })
+      }
       test::TestStepResult::Pending(_) => {
         self.progress(lsp_custom::TestRunProgressMessage::Enqueued {
           test: desc.into(),
@@ -5,6 +5,8 @@ use super::config;
 use super::documents::AssetOrDocument;
 use super::language_server;
 use super::language_server::StateSnapshot;
+use super::npm::npm_import_specifier;
+use super::npm::NpmTypesEntrypoints;
 use super::performance::Performance;
 use super::refactor::RefactorCodeActionData;
 use super::refactor::ALL_KNOWN_REFACTOR_ACTION_KINDS;
@@ -17,7 +19,9 @@ use super::text::LineIndex;
 use super::urls::LspUrlMap;
 use super::urls::INVALID_SPECIFIER;
 
+use crate::args::LibOverride;
 use crate::args::TsConfig;
+use crate::npm::NpmPackageResolver;
 use crate::tsc;
 use crate::tsc::ResolveArgs;
 use crate::util::path::relative_specifier;
@@ -43,6 +47,7 @@ use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_core::RuntimeOptions;
 use deno_runtime::tokio_util::create_basic_runtime;
+use import_map::ImportMap;
 use log::warn;
 use once_cell::sync::Lazy;
 use regex::Captures;
@@ -113,7 +118,13 @@ impl TsServer {
               .expect("could not start tsc");
             started = true;
           }
-          let value = request(&mut ts_runtime, state_snapshot, req, token);
+          // requests which were cancelled while they were queued are skipped
+          // so they don't hold up the requests behind them
+          let value = if token.is_cancelled() {
+            Err(request_cancelled_error())
+          } else {
+            request(&mut ts_runtime, state_snapshot, req, token)
+          };
           if tx.send(value).is_err() {
             warn!("Unable to send result to client.");
           }
@@ -124,6 +135,9 @@ impl TsServer {
     Self(tx)
   }
 
+  /// Send a request to tsc. When the returned future is dropped before it
+  /// completes, for example because the client sent a `$/cancelRequest` for
+  /// the LSP request waiting on it, the tsc request is cancelled as well.
   pub async fn request<R>(
     &self,
     snapshot: Arc<StateSnapshot>,
@@ -132,9 +146,11 @@ impl TsServer {
   where
     R: de::DeserializeOwned,
   {
-    self
-      .request_with_cancellation(snapshot, req, Default::default())
-      .await
+    let token = CancellationToken::new();
+    let drop_guard = token.clone().drop_guard();
+    let result = self.request_with_cancellation(snapshot, req, token).await;
+    drop_guard.disarm();
+    result
   }
 
   pub async fn request_with_cancellation<R>(
@@ -1899,6 +1915,9 @@ fn parse_code_actions(
             update_import_statement(
               tc.as_text_edit(asset_or_doc.line_index()),
               data,
+              Some(&language_server.npm_resolver),
+              &language_server.npm_types_entrypoints,
+              language_server.maybe_import_map.as_deref(),
             )
           }));
         } else {
@@ -2090,10 +2109,14 @@ struct CompletionEntryDataImport {
 }
 
 /// Modify an import statement text replacement to have the correct import
-/// specifier to work with Deno module resolution.
+/// specifier to work with Deno module resolution. Modules inside npm packages
+/// are imported with their `npm:` specifier instead of a path into the cache.
 fn update_import_statement(
   mut text_edit: lsp::TextEdit,
   item_data: &CompletionItemData,
+  maybe_npm_resolver: Option<&NpmPackageResolver>,
+  npm_types_entrypoints: &NpmTypesEntrypoints,
+  maybe_import_map: Option<&ImportMap>,
 ) -> lsp::TextEdit {
   if let Some(data) = &item_data.data {
     if let Ok(import_data) =
@@ -2101,9 +2124,18 @@ fn update_import_statement(
     {
       if let Ok(import_specifier) = normalize_specifier(&import_data.file_name)
       {
-        if let Some(new_module_specifier) =
+        let maybe_npm_specifier = maybe_npm_resolver.and_then(|npm_resolver| {
+          npm_import_specifier(
+            &import_specifier,
+            &item_data.specifier,
+            npm_resolver,
+            npm_types_entrypoints,
+            maybe_import_map,
+          )
+        });
+        if let Some(new_module_specifier) = maybe_npm_specifier.or_else(|| {
           relative_specifier(&item_data.specifier, &import_specifier)
-        {
+        }) {
           text_edit.new_text = text_edit
             .new_text
             .replace(&import_data.module_specifier, &new_module_specifier);
@@ -2763,11 +2795,26 @@ fn op_respond(state: &mut OpState, args: Response) -> bool {
   true
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ScriptNamesArgs {
+  /// The index of the library override of the language service, which only
+  /// gets the documents the override applies to.
+  lib_scope: Option<usize>,
+}
+
 #[op]
-fn op_script_names(state: &mut OpState) -> Vec<String> {
+fn op_script_names(state: &mut OpState, args: ScriptNamesArgs) -> Vec<String> {
   let state = state.borrow_mut::<State>();
   let documents = &state.state_snapshot.documents;
-  let open_docs = documents.documents(true, true);
+  let lib_overrides = &state.state_snapshot.lib_overrides;
+  let open_docs = documents
+    .documents(true, true)
+    .into_iter()
+    .filter(|d| {
+      LibOverride::find_index(lib_overrides, d.specifier()) == args.lib_scope
+    })
+    .collect::<Vec<_>>();
 
   let mut result = Vec::with_capacity(open_docs.len() + 1);
 
@@ -2776,10 +2823,33 @@ fn op_script_names(state: &mut OpState) -> Vec<String> {
     result.push("asset:///node_types.d.ts".to_string());
   }
 
-  result.extend(open_docs.into_iter().map(|d| d.specifier().to_string()));
+  result.extend(open_docs.iter().map(|d| d.specifier().to_string()));
+
+  // the type declarations of the npm packages imported by the documents are
+  // provided as well, so that the symbols they export are offered as
+  // auto-imports
+  if let Some(npm_resolver) = &state.state_snapshot.maybe_npm_resolver {
+    result.extend(
+      state
+        .state_snapshot
+        .npm_types_entrypoints
+        .for_documents(&open_docs, npm_resolver)
+        .into_iter()
+        .map(|s| s.to_string()),
+    );
+  }
   result
 }
 
+/// Gets the index of the library override that applies to a specifier, which
+/// selects the language service of the specifier.
+#[op]
+fn op_lib_scope(state: &mut OpState, args: SpecifierArgs) -> Option<usize> {
+  let state = state.borrow_mut::<State>();
+  let specifier = state.normalize_specifier(&args.specifier).ok()?;
+  LibOverride::find_index(&state.state_snapshot.lib_overrides, &specifier)
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ScriptVersionArgs {
@@ -2804,7 +2874,8 @@ fn op_script_version(
 fn js_runtime(performance: Arc<Performance>) -> JsRuntime {
   JsRuntime::new(RuntimeOptions {
     extensions: vec![init_extension(performance)],
-    startup_snapshot: Some(tsc::compiler_snapshot()),
+    // the lsp feature requires the tsc feature, so the snapshot is included
+    startup_snapshot: Some(tsc::compiler_snapshot().unwrap()),
     ..Default::default()
   })
 }
@@ -2815,6 +2886,7 @@ fn init_extension(performance: Arc<Performance>) -> Extension {
       op_exists::decl(),
       op_is_cancelled::decl(),
       op_is_node_file::decl(),
+      op_lib_scope::decl(),
       op_load::decl(),
       op_resolve::decl(),
       op_respond::decl(),
@@ -3115,8 +3187,9 @@ impl From<&CompletionItemData> for GetCompletionDetailsArgs {
 /// Methods that are supported by the Language Service in the compiler isolate.
 #[derive(Debug)]
 pub enum RequestMethod {
-  /// Configure the compilation settings for the server.
-  Configure(TsConfig),
+  /// Configure the compilation settings for the server, along with the
+  /// libraries of the `"libOverrides"` of the configuration file.
+  Configure(TsConfig, Vec<Vec<String>>),
   /// Get rename locations at a given position.
   FindRenameLocations {
     specifier: ModuleSpecifier,
@@ -3189,10 +3262,11 @@ pub enum RequestMethod {
 impl RequestMethod {
   fn to_value(&self, state: &State, id: usize) -> Value {
     match self {
-      RequestMethod::Configure(config) => json!({
+      RequestMethod::Configure(config, lib_overrides) => json!({
         "id": id,
         "method": "configure",
         "compilerOptions": config,
+        "libOverrides": lib_overrides,
       }),
       RequestMethod::FindRenameLocations {
         specifier,
@@ -3414,6 +3488,10 @@ impl RequestMethod {
   }
 }
 
+fn request_cancelled_error() -> AnyError {
+  custom_error("RequestCancelled", "The request was cancelled.")
+}
+
 /// Send a request into a runtime and return the JSON value of the response.
 pub fn request(
   runtime: &mut JsRuntime,
@@ -3426,14 +3504,22 @@ pub fn request(
     let mut op_state = op_state.borrow_mut();
     let state = op_state.borrow_mut::<State>();
     state.state_snapshot = state_snapshot;
-    state.token = token;
+    state.token = token.clone();
     state.last_id += 1;
     let id = state.last_id;
     (state.performance.clone(), method.to_value(state, id))
   };
   let mark = performance.mark("request", Some(request_params.clone()));
   let request_src = format!("globalThis.serverRequest({request_params});");
-  runtime.execute_script(&located_script_name!(), &request_src)?;
+  if let Err(err) =
+    runtime.execute_script(&located_script_name!(), &request_src)
+  {
+    // tsc throws when it notices the cancellation
+    if token.is_cancelled() {
+      return Err(request_cancelled_error());
+    }
+    return Err(err);
+  }
 
   let op_state = runtime.op_state();
   let mut op_state = op_state.borrow_mut();
@@ -3503,7 +3589,7 @@ mod tests {
       request(
         &mut runtime,
         state_snapshot.clone(),
-        RequestMethod::Configure(ts_config),
+        RequestMethod::Configure(ts_config, Vec::new()),
         Default::default(),
       )
       .expect("failed request"),
@@ -3527,6 +3613,32 @@ mod tests {
     assert_eq!(actual, r"test [`a link`](http://deno.land/x/mod.ts) test");
   }
 
+  #[tokio::test]
+  async fn test_cancelled_request_is_skipped() {
+    let ts_server = TsServer::new(Default::default());
+    let snapshot = Arc::new(StateSnapshot::default());
+    let token = CancellationToken::new();
+    token.cancel();
+    let err = ts_server
+      .request_with_cancellation::<bool>(
+        snapshot.clone(),
+        RequestMethod::Restart,
+        token,
+      )
+      .await
+      .unwrap_err();
+    assert_eq!(
+      deno_core::error::get_custom_error_class(&err),
+      Some("RequestCancelled")
+    );
+    // the server keeps handling the requests after it
+    let restarted: bool = ts_server
+      .request(snapshot, RequestMethod::Restart)
+      .await
+      .unwrap();
+    assert!(restarted);
+  }
+
   #[test]
   fn test_project_configure() {
     let temp_dir = TempDir::new();
@@ -3564,7 +3676,7 @@ mod tests {
     let result = request(
       &mut runtime,
       state_snapshot,
-      RequestMethod::Configure(ts_config),
+      RequestMethod::Configure(ts_config, Vec::new()),
       Default::default(),
     );
     assert!(result.is_ok());
@@ -4312,6 +4424,9 @@ mod tests {
           new_text: orig_text.to_string(),
         },
         &item_data,
+        None,
+        &NpmTypesEntrypoints::default(),
+        None,
       );
       assert_eq!(
         actual,
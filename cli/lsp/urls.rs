@@ -50,6 +50,30 @@ const COMPONENT: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
   .add(b'+')
   .add(b',');
 
+/// The prefix of the virtual documents which describe the status of a single
+/// module, where the rest of the path is the encoded module specifier.
+const STATUS_PREFIX: &str = "deno:/status/";
+
+/// Return the URL of the virtual status document of a module.
+pub fn to_status_url(specifier: &ModuleSpecifier) -> Url {
+  Url::parse(&format!(
+    "{}{}",
+    STATUS_PREFIX,
+    percent_encoding::utf8_percent_encode(specifier.as_str(), COMPONENT)
+  ))
+  .unwrap()
+}
+
+/// Return the module specifier described by a virtual status document, if the
+/// URL is one.
+pub fn from_status_url(url: &Url) -> Option<ModuleSpecifier> {
+  let encoded = url.as_str().strip_prefix(STATUS_PREFIX)?;
+  let specifier = percent_encoding::percent_decode_str(encoded)
+    .decode_utf8()
+    .ok()?;
+  ModuleSpecifier::parse(&specifier).ok()
+}
+
 fn hash_data_specifier(specifier: &ModuleSpecifier) -> String {
   let mut file_name_str = specifier.path().to_string();
   if let Some(query) = specifier.query() {
@@ -170,6 +194,22 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_status_url() {
+    let fixture = resolve_url("https://deno.land/x/pkg@1.0.0/mod.ts").unwrap();
+    let actual_url = to_status_url(&fixture);
+    let expected_url = Url::parse(
+      "deno:/status/https%3A%2F%2Fdeno.land%2Fx%2Fpkg%401.0.0%2Fmod.ts",
+    )
+    .unwrap();
+    assert_eq!(actual_url, expected_url);
+    assert_eq!(from_status_url(&actual_url), Some(fixture));
+    assert_eq!(
+      from_status_url(&Url::parse("deno:/status.md").unwrap()),
+      None
+    );
+  }
+
   #[test]
   fn test_lsp_url_map() {
     let map = LspUrlMap::default();
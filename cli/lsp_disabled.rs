@@ -0,0 +1,38 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Stand-in for the `lsp` module when the binary is built without the `lsp`
+//! feature. `deno lsp` reports that it's unavailable and the REPL works
+//! without completions.
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+
+pub async fn start() -> Result<(), AnyError> {
+  bail!(
+    "This build of Deno does not include the language server. Rebuild with the \"lsp\" feature enabled to use `deno lsp`."
+  )
+}
+
+#[derive(Debug)]
+pub struct ReplCompletionItem {
+  pub new_text: String,
+  pub range: std::ops::Range<usize>,
+}
+
+pub struct ReplLanguageServer;
+
+impl ReplLanguageServer {
+  pub async fn new_initialized() -> Result<ReplLanguageServer, AnyError> {
+    Ok(ReplLanguageServer)
+  }
+
+  pub async fn commit_text(&mut self, _line_text: &str) {}
+
+  pub async fn completions(
+    &mut self,
+    _line_text: &str,
+    _position: usize,
+  ) -> Vec<ReplCompletionItem> {
+    Vec::new()
+  }
+}
@@ -9,15 +9,22 @@ mod errors;
 mod file_fetcher;
 mod graph_util;
 mod http_util;
+mod journal;
 mod js;
+#[cfg(feature = "lsp")]
+mod lsp;
+#[cfg(not(feature = "lsp"))]
+#[path = "lsp_disabled.rs"]
 mod lsp;
 mod module_loader;
+#[cfg(feature = "napi")]
 mod napi;
 mod node;
 mod npm;
 mod ops;
 mod proc_state;
 mod resolver;
+mod scheme_handlers;
 mod semver;
 mod standalone;
 mod tools;
@@ -33,6 +40,7 @@ use crate::proc_state::ProcState;
 use crate::resolver::CliResolver;
 use crate::util::display;
 use crate::util::v8::get_v8_flags_from_env;
+use crate::util::v8::init_config_v8_flags;
 use crate::util::v8::init_v8_flags;
 
 use args::CliOptions;
@@ -41,6 +49,7 @@ use deno_core::error::AnyError;
 use deno_core::error::JsError;
 use deno_runtime::colors;
 use deno_runtime::fmt_errors::format_js_error;
+use deno_runtime::fmt_errors::StackTraceOptions;
 use deno_runtime::tokio_util::run_local;
 use std::env;
 use std::path::PathBuf;
@@ -50,6 +59,12 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
     DenoSubcommand::Bench(bench_flags) => {
       let cli_options = CliOptions::from_flags(flags)?;
       let bench_options = cli_options.resolve_bench_options(bench_flags)?;
+      init_config_v8_flags(
+        &bench_options.v8_flags,
+        &cli_options.resolve_v8_flags(&bench_options.v8_flags),
+        get_v8_flags_from_env(),
+        cli_options.strict_config(),
+      )?;
       if cli_options.watch_paths().is_some() {
         tools::bench::run_benchmarks_with_watch(cli_options, bench_options)
           .await?;
@@ -70,16 +85,20 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       tools::run::eval_command(flags, eval_flags).await
     }
     DenoSubcommand::Cache(cache_flags) => {
-      let ps = ProcState::build(flags).await?;
-      ps.load_and_type_check_files(&cache_flags.files).await?;
-      ps.cache_module_emits()?;
+      tools::cache::cache(flags, cache_flags).await?;
       Ok(0)
     }
     DenoSubcommand::Check(check_flags) => {
-      let ps = ProcState::build(flags).await?;
-      ps.load_and_type_check_files(&check_flags.files).await?;
+      if check_flags.doc {
+        tools::check::check_doc(flags, check_flags).await?;
+      } else if flags.watch.is_some() {
+        tools::check::check_with_watch(flags, check_flags).await?;
+      } else {
+        tools::check::check_files(flags, check_flags).await?;
+      }
       Ok(0)
     }
+    DenoSubcommand::Ci(ci_flags) => tools::ci::ci(flags, ci_flags).await,
     DenoSubcommand::Compile(compile_flags) => {
       tools::standalone::compile(flags, compile_flags).await?;
       Ok(0)
@@ -115,8 +134,18 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       Ok(0)
     }
     DenoSubcommand::Lint(lint_flags) => {
-      if lint_flags.rules {
-        tools::lint::print_rules_list(lint_flags.json);
+      if lint_flags.rules && lint_flags.maybe_rules_file.is_some() {
+        let cli_options = CliOptions::from_flags(flags)?;
+        tools::lint::print_file_rules(&cli_options, lint_flags)?;
+      } else if lint_flags.rules {
+        tools::lint::print_rules_list(
+          lint_flags.json,
+          lint_flags.maybe_rules_filter_tags.as_deref(),
+        );
+      } else if lint_flags.deps {
+        return tools::deps::analyze(flags, lint_flags).await;
+      } else if flags.watch.is_some() {
+        tools::lint::lint_with_watch(flags, lint_flags).await?;
       } else {
         let cli_options = CliOptions::from_flags(flags)?;
         let lint_options = cli_options.resolve_lint_options(lint_flags)?;
@@ -134,6 +163,10 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
         tools::run::run_script(flags, run_flags).await
       }
     }
+    DenoSubcommand::Storage(storage_flags) => {
+      tools::storage::storage(flags, storage_flags).await?;
+      Ok(0)
+    }
     DenoSubcommand::Task(task_flags) => {
       tools::task::execute_script(flags, task_flags).await
     }
@@ -149,7 +182,14 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
         );
       }
       let cli_options = CliOptions::from_flags(flags)?;
+      cli_options.log_permissions_config();
       let test_options = cli_options.resolve_test_options(test_flags)?;
+      init_config_v8_flags(
+        &test_options.v8_flags,
+        &cli_options.resolve_v8_flags(&test_options.v8_flags),
+        get_v8_flags_from_env(),
+        cli_options.strict_config(),
+      )?;
 
       if cli_options.watch_paths().is_some() {
         tools::test::run_tests_with_watch(cli_options, test_options).await?;
@@ -164,7 +204,7 @@ async fn run_subcommand(flags: Flags) -> Result<i32, AnyError> {
       Ok(0)
     }
     DenoSubcommand::Types => {
-      let types = tsc::get_types_declaration_file_text(flags.unstable);
+      let types = tsc::get_types_declaration_file_text(flags.unstable)?;
       display::write_to_stdout_ignore_sigpipe(types.as_bytes())?;
       Ok(0)
     }
@@ -203,18 +243,33 @@ fn setup_panic_hook() {
   }));
 }
 
-fn unwrap_or_exit<T>(result: Result<T, AnyError>) -> T {
+/// Gets the exit code of the process when a subcommand fails with the error.
+fn error_exit_code(error: &AnyError) -> i32 {
+  if error.downcast_ref::<args::LockfileError>().is_some() {
+    10
+  } else {
+    1
+  }
+}
+
+fn unwrap_or_exit<T>(
+  result: Result<T, AnyError>,
+  stack_trace_options: &StackTraceOptions,
+) -> T {
   match result {
     Ok(value) => value,
     Err(error) => {
       let mut error_string = format!("{error:?}");
-      let mut error_code = 1;
+      let error_code = error_exit_code(&error);
 
       if let Some(e) = error.downcast_ref::<JsError>() {
-        error_string = format_js_error(e);
+        error_string = format_js_error(e, stack_trace_options);
       } else if let Some(e) = error.downcast_ref::<args::LockfileError>() {
-        error_string = e.to_string();
-        error_code = 10;
+        error_string = args::format_lockfile_error(
+          e,
+          &[],
+          log::log_enabled!(log::Level::Debug),
+        );
       }
 
       eprintln!(
@@ -242,16 +297,13 @@ pub fn main() {
   let args: Vec<String> = env::args().collect();
 
   let future = async move {
-    let standalone_res =
-      match standalone::extract_standalone(args.clone()).await {
-        Ok(Some((metadata, eszip))) => standalone::run(eszip, metadata).await,
-        Ok(None) => Ok(()),
-        Err(err) => Err(err),
-      };
-    // TODO(bartlomieju): doesn't handle exit code set by the runtime properly
-    unwrap_or_exit(standalone_res);
+    if let Some((metadata, eszip)) =
+      standalone::extract_standalone(args.clone()).await?
+    {
+      return standalone::run(eszip, metadata).await;
+    }
 
-    let flags = match flags_from_vec(args) {
+    let flags = match flags_from_vec(args.clone()) {
       Ok(flags) => flags,
       Err(err @ clap::Error { .. })
         if err.kind() == clap::ErrorKind::DisplayHelp
@@ -260,17 +312,28 @@ pub fn main() {
         err.print().unwrap();
         std::process::exit(0);
       }
-      Err(err) => unwrap_or_exit(Err(AnyError::from(err))),
+      Err(err) => unwrap_or_exit(Err(AnyError::from(err)), &Default::default()),
     };
 
+    colors::set_color_mode(flags.color);
     init_v8_flags(&flags.v8_flags, get_v8_flags_from_env());
-
     util::logger::init(flags.log_level);
 
-    run_subcommand(flags).await
+    let stack_trace_options = flags.stack_trace_options();
+    let maybe_journal = journal::Journal::start(&args, &flags);
+    let result = run_subcommand(flags).await;
+    if let Some(journal) = maybe_journal {
+      journal.finish(match &result {
+        Ok(exit_code) => *exit_code,
+        Err(error) => error_exit_code(error),
+      });
+    }
+    Ok(unwrap_or_exit(result, &stack_trace_options))
   };
 
-  let exit_code = unwrap_or_exit(run_local(future));
+  // the errors of the subcommand are handled above, with the stack trace
+  // options of its flags
+  let exit_code = unwrap_or_exit(run_local(future), &Default::default());
 
   std::process::exit(exit_code);
 }
@@ -1,19 +1,27 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::CacheSetting;
+use crate::args::ImportOrderFormat;
 use crate::args::TsTypeLib;
 use crate::emit::emit_parsed_source;
+use crate::errors::get_error_class_name;
 use crate::node;
 use crate::proc_state::ProcState;
+use crate::util::path::specifier_to_file_path;
 use crate::util::text_encoding::code_without_source_map;
+use crate::util::text_encoding::external_source_map_url;
 use crate::util::text_encoding::source_map_from_code;
 
 use deno_ast::MediaType;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::Context;
+use deno_core::error::custom_error;
 use deno_core::error::AnyError;
 use deno_core::futures::future::FutureExt;
 use deno_core::futures::Future;
+use deno_core::parking_lot::Mutex;
 use deno_core::resolve_url;
+use deno_core::serde_json;
 use deno_core::ModuleLoader;
 use deno_core::ModuleSource;
 use deno_core::ModuleSpecifier;
@@ -21,8 +29,11 @@ use deno_core::ModuleType;
 use deno_core::OpState;
 use deno_core::ResolutionKind;
 use deno_core::SourceMapGetter;
+use deno_runtime::colors;
 use deno_runtime::permissions::PermissionsContainer;
+use serde::Serialize;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::str;
@@ -33,6 +44,121 @@ struct ModuleCodeSource {
   pub media_type: MediaType,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ImportOrderEntry {
+  index: usize,
+  specifier: ModuleSpecifier,
+  referrer: Option<ModuleSpecifier>,
+  is_dynamic: bool,
+}
+
+/// The scheme of the generated modules that record when the module importing
+/// them is evaluated.
+const IMPORT_ORDER_SCHEME: &str = "deno-import-order";
+
+#[derive(Default)]
+struct ImportOrderState {
+  /// The module that first imported each loaded module and whether that
+  /// import was dynamic.
+  imports: HashMap<ModuleSpecifier, (Option<ModuleSpecifier>, bool)>,
+  entries: Vec<ImportOrderEntry>,
+}
+
+/// Records the order in which the modules are evaluated for
+/// `--print-import-order`, along with the module that imported them.
+///
+/// V8 doesn't report when a module is evaluated, so while this is enabled the
+/// loader appends a static import of a generated module to every JavaScript
+/// module. The imports of a module are evaluated in order before its body, so
+/// that last import reports the module right before its body runs.
+#[derive(Default)]
+pub struct ImportOrder(Mutex<ImportOrderState>);
+
+impl ImportOrder {
+  fn record_import(
+    &self,
+    specifier: &ModuleSpecifier,
+    maybe_referrer: Option<&ModuleSpecifier>,
+    is_dynamic: bool,
+  ) {
+    self
+      .0
+      .lock()
+      .imports
+      .entry(specifier.clone())
+      .or_insert_with(|| (maybe_referrer.cloned(), is_dynamic));
+  }
+
+  /// Records that the module is about to be evaluated.
+  pub fn record_evaluation(&self, specifier: ModuleSpecifier) {
+    let mut state = self.0.lock();
+    let (referrer, is_dynamic) =
+      state.imports.get(&specifier).cloned().unwrap_or_default();
+    let index = state.entries.len() + 1;
+    state.entries.push(ImportOrderEntry {
+      index,
+      specifier,
+      referrer,
+      is_dynamic,
+    });
+  }
+
+  fn recorder_specifier(specifier: &ModuleSpecifier) -> String {
+    format!("{IMPORT_ORDER_SCHEME}:{specifier}")
+  }
+
+  fn recorder_source(
+    specifier: &ModuleSpecifier,
+  ) -> Result<ModuleSource, AnyError> {
+    // the query and fragment belong to the importing module too
+    let importer = specifier.as_str()[IMPORT_ORDER_SCHEME.len() + 1..]
+      .parse::<ModuleSpecifier>()
+      .with_context(|| format!("Invalid import order module: {specifier}"))?;
+    let code = format!(
+      "Deno[Deno.internal].core.ops.op_import_order_evaluate({});",
+      serde_json::to_string(importer.as_str())?
+    );
+    Ok(ModuleSource {
+      code: code.into_bytes().into_boxed_slice(),
+      module_url_specified: specifier.to_string(),
+      module_url_found: specifier.to_string(),
+      module_type: ModuleType::JavaScript,
+    })
+  }
+
+  pub fn print(&self, format: ImportOrderFormat) {
+    let entries = &self.0.lock().entries;
+    match format {
+      ImportOrderFormat::Json => {
+        eprintln!("{}", serde_json::to_string_pretty(entries).unwrap());
+      }
+      ImportOrderFormat::Text => {
+        eprintln!("{}", colors::bold("Import order:"));
+        for entry in entries.iter() {
+          match &entry.referrer {
+            Some(referrer) => eprintln!(
+              "{:>4} {} {}",
+              entry.index,
+              entry.specifier,
+              colors::gray(format!(
+                "({} import from {})",
+                if entry.is_dynamic {
+                  "dynamic"
+                } else {
+                  "static"
+                },
+                referrer
+              ))
+            ),
+            None => eprintln!("{:>4} {}", entry.index, entry.specifier),
+          }
+        }
+      }
+    }
+  }
+}
+
 pub struct CliModuleLoader {
   pub lib: TsTypeLib,
   /// The initial set of permissions used to resolve the static imports in the
@@ -144,7 +270,7 @@ impl CliModuleLoader {
     is_dynamic: bool,
   ) -> Result<ModuleSource, AnyError> {
     let code_source = if self.ps.npm_resolver.in_npm_package(specifier) {
-      let file_path = specifier.to_file_path().unwrap();
+      let file_path = specifier_to_file_path(specifier)?;
       let code = std::fs::read_to_string(&file_path).with_context(|| {
         let mut msg = "Unable to load ".to_string();
         msg.push_str(&file_path.to_string_lossy());
@@ -196,6 +322,13 @@ impl CliModuleLoader {
       // because we don't need it
       code_without_source_map(code_source.code)
     };
+    let code = match (&self.ps.maybe_import_order, code_source.media_type) {
+      (Some(_), MediaType::Json) | (None, _) => code,
+      (Some(_), _) => format!(
+        "{code}\nimport \"{}\";",
+        ImportOrder::recorder_specifier(specifier)
+      ),
+    };
     Ok(ModuleSource {
       code: code.into_bytes().into_boxed_slice(),
       module_url_specified: specifier.to_string(),
@@ -215,6 +348,11 @@ impl ModuleLoader for CliModuleLoader {
     referrer: &str,
     kind: ResolutionKind,
   ) -> Result<ModuleSpecifier, AnyError> {
+    if self.ps.maybe_import_order.is_some()
+      && specifier.starts_with(&format!("{IMPORT_ORDER_SCHEME}:"))
+    {
+      return Ok(ModuleSpecifier::parse(specifier)?);
+    }
     let mut permissions = if matches!(kind, ResolutionKind::DynamicImport) {
       self.dynamic_permissions.clone()
     } else {
@@ -229,6 +367,18 @@ impl ModuleLoader for CliModuleLoader {
     maybe_referrer: Option<ModuleSpecifier>,
     is_dynamic: bool,
   ) -> Pin<Box<deno_core::ModuleSourceFuture>> {
+    if let Some(import_order) = &self.ps.maybe_import_order {
+      if specifier.scheme() == IMPORT_ORDER_SCHEME {
+        return Box::pin(deno_core::futures::future::ready(
+          ImportOrder::recorder_source(specifier),
+        ));
+      }
+      import_order.record_import(
+        specifier,
+        maybe_referrer.as_ref(),
+        is_dynamic,
+      );
+    }
     // NOTE: this block is async only because of `deno_core` interface
     // requirements; module was already loaded when constructing module graph
     // during call to `prepare_load` so we can load it synchronously.
@@ -243,7 +393,7 @@ impl ModuleLoader for CliModuleLoader {
     &self,
     _op_state: Rc<RefCell<OpState>>,
     specifier: &ModuleSpecifier,
-    _maybe_referrer: Option<String>,
+    maybe_referrer: Option<String>,
     is_dynamic: bool,
   ) -> Pin<Box<dyn Future<Output = Result<(), AnyError>>>> {
     if self.ps.npm_resolver.in_npm_package(specifier) {
@@ -261,16 +411,37 @@ impl ModuleLoader for CliModuleLoader {
       self.root_permissions.clone()
     };
     let lib = self.lib;
+    let maybe_dynamic_referrer = maybe_referrer.filter(|_| is_dynamic);
 
     async move {
-      ps.prepare_module_load(
-        vec![specifier],
-        is_dynamic,
-        lib,
-        root_permissions,
-        dynamic_permissions,
-      )
-      .await
+      let result = ps
+        .prepare_module_load(
+          vec![specifier.clone()],
+          is_dynamic,
+          lib,
+          root_permissions,
+          dynamic_permissions,
+        )
+        .await;
+      match maybe_dynamic_referrer {
+        // the graph only knows the dynamically imported module as a root, so
+        // name the importing module when it would need the network
+        Some(referrer) if ps.options.cache_setting() == CacheSetting::Only => {
+          result.map_err(|err| {
+            if get_error_class_name(&err) == "NotCached" {
+              custom_error(
+                "NotCached",
+                format!(
+                  "{err:#}\n    at dynamic import of \"{specifier}\" in {referrer}"
+                ),
+              )
+            } else {
+              err
+            }
+          })
+        }
+        _ => result,
+      }
     }
     .boxed_local()
   }
@@ -286,7 +457,15 @@ impl SourceMapGetter for CliModuleLoader {
       _ => return None,
     }
     let source = self.load_prepared_module(&specifier, None).ok()?;
-    source_map_from_code(&source.code)
+    source_map_from_code(&source.code).or_else(|| {
+      // a local module, like a bundle, may refer to a source map next to it
+      if specifier.scheme() != "file" {
+        return None;
+      }
+      let url = external_source_map_url(&source.code)?;
+      let map_path = specifier.join(url).ok()?.to_file_path().ok()?;
+      std::fs::read(map_path).ok()
+    })
   }
 
   fn get_source_line(
@@ -121,6 +121,12 @@ pub static MODULE_ALL_URL: Lazy<Url> =
   Lazy::new(|| NODE_COMPAT_URL.join("node/module_all.ts").unwrap());
 
 pub fn resolve_builtin_node_module(specifier: &str) -> Result<Url, AnyError> {
+  if !cfg!(feature = "npm") {
+    return Err(generic_error(format!(
+      "Built-in \"node:\" module {specifier} is unavailable, this build of Deno does not include npm support."
+    )));
+  }
+
   if let Some(module) = find_builtin_node_module(specifier) {
     match module.specifier {
       // We will load the source code from the `std/node` polyfill.
@@ -310,6 +316,31 @@ pub fn node_resolve_npm_reference(
   Ok(Some(resolve_response))
 }
 
+/// Gets the subpaths exported by an npm package, without their leading `./`.
+/// The main export and subpath patterns are excluded.
+#[cfg_attr(not(feature = "lsp"), allow(dead_code))]
+pub fn node_resolve_package_export_sub_paths(
+  pkg_req: &NpmPackageReq,
+  npm_resolver: &NpmPackageResolver,
+  permissions: &mut dyn NodePermissions,
+) -> Result<Vec<String>, AnyError> {
+  let package_folder =
+    npm_resolver.resolve_package_folder_from_deno_module(pkg_req)?;
+  let package_json_path = package_folder.join("package.json");
+  let package_json =
+    PackageJson::load(npm_resolver, permissions, package_json_path)?;
+  let sub_paths = match &package_json.exports {
+    Some(exports) => exports
+      .keys()
+      .filter_map(|key| key.strip_prefix("./"))
+      .filter(|key| !key.contains('*'))
+      .map(ToOwned::to_owned)
+      .collect(),
+    None => Vec::new(),
+  };
+  Ok(sub_paths)
+}
+
 pub fn node_resolve_binary_export(
   pkg_req: &NpmPackageReq,
   bin_name: Option<&str>,
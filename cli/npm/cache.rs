@@ -16,6 +16,7 @@ use deno_core::url::Url;
 
 use crate::args::CacheSetting;
 use crate::cache::DenoDir;
+use crate::file_fetcher::FetchStats;
 use crate::http_util::HttpClient;
 use crate::semver::Version;
 use crate::util::fs::canonicalize_path;
@@ -324,6 +325,7 @@ pub struct NpmCache {
   progress_bar: ProgressBar,
   /// ensures a package is only downloaded once per run
   previously_reloaded_packages: Arc<Mutex<HashSet<String>>>,
+  stats: Arc<FetchStats>,
 }
 
 impl NpmCache {
@@ -339,9 +341,20 @@ impl NpmCache {
       http_client,
       progress_bar,
       previously_reloaded_packages: Default::default(),
+      stats: Default::default(),
     }
   }
 
+  /// Statistics about the package tarballs downloaded or reused.
+  pub fn stats(&self) -> &FetchStats {
+    &self.stats
+  }
+
+  /// The statistics, shared with any clone.
+  pub fn shared_stats(&self) -> Arc<FetchStats> {
+    self.stats.clone()
+  }
+
   pub fn as_readonly(&self) -> ReadonlyNpmCache {
     self.readonly.clone()
   }
@@ -397,6 +410,7 @@ impl NpmCache {
       // the first time, or another process is currently extracting the zip file
       && !package_folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME).exists()
     {
+      self.stats.record_cached();
       return Ok(());
     } else if self.cache_setting == CacheSetting::Only {
       return Err(custom_error(
@@ -410,12 +424,20 @@ impl NpmCache {
     }
 
     let guard = self.progress_bar.update(&dist.tarball);
+    let partial_path = package_folder.with_file_name(format!(
+      "{}.tgz.partial",
+      package_folder.file_name().unwrap().to_string_lossy()
+    ));
     let maybe_bytes = self
       .http_client
-      .download_with_progress(&dist.tarball, &guard)
+      .download_with_progress_resumable(&dist.tarball, &partial_path, &guard)
       .await?;
     match maybe_bytes {
       Some(bytes) => {
+        self.stats.record_download(
+          &format!("{}@{}", package.0, package.1),
+          bytes.len(),
+        );
         verify_and_extract_tarball(package, &bytes, dist, &package_folder)
       }
       None => {
@@ -7,8 +7,10 @@ mod resolvers;
 mod tarball;
 
 pub use cache::NpmCache;
+pub use registry::NpmDependencyEntryKind;
 #[cfg(test)]
 pub use registry::NpmPackageVersionDistInfo;
+pub use registry::NpmPackageVersionInfo;
 pub use registry::NpmRegistryApi;
 pub use registry::RealNpmRegistryApi;
 pub use resolution::resolve_graph_npm_info;
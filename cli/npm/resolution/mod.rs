@@ -11,6 +11,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::args::Lockfile;
+use crate::args::LockfileError;
 use crate::semver::Version;
 
 use self::graph::GraphDependencyResolver;
@@ -393,10 +394,24 @@ impl NpmResolution {
         package_id.as_serialized(),
       );
     }
+    let mut failures = Vec::new();
     for package in snapshot.all_packages() {
-      lockfile.check_or_insert_npm_package(package.into())?;
+      if let Err(failure) = lockfile.check_or_insert_npm_package(package.into())
+      {
+        failures.push(failure);
+      }
+    }
+    if failures.is_empty() {
+      Ok(())
+    } else {
+      Err(
+        LockfileError {
+          filename: lockfile.filename.clone(),
+          failures,
+        }
+        .into(),
+      )
     }
-    Ok(())
   }
 }
 
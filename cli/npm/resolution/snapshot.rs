@@ -104,6 +104,12 @@ impl NpmResolutionSnapshot {
     }
   }
 
+  /// The package requirements of the Deno modules along with the packages
+  /// they resolved to.
+  pub fn package_reqs(&self) -> &HashMap<NpmPackageReq, NpmPackageId> {
+    &self.package_reqs
+  }
+
   pub fn top_level_packages(&self) -> Vec<NpmPackageId> {
     self
       .package_reqs
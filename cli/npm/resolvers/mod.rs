@@ -222,6 +222,11 @@ impl NpmPackageResolver {
     Ok(path)
   }
 
+  /// The npm registry API the packages are resolved with.
+  pub fn api(&self) -> &RealNpmRegistryApi {
+    &self.api
+  }
+
   /// Attempts to get the package size in bytes.
   pub fn package_size(
     &self,
@@ -251,7 +256,24 @@ impl NpmPackageResolver {
       return Ok(());
     }
 
-    if self.no_npm {
+    self.resolve_package_reqs(packages).await?;
+    self.inner.cache_packages().await
+  }
+
+  /// Adds package requirements to the resolver and updates the lockfile with
+  /// the resolved packages, using only the information of the registry.
+  ///
+  /// This will not cache any package files, which are downloaded once the
+  /// packages are used.
+  pub async fn resolve_package_reqs(
+    &self,
+    packages: Vec<NpmPackageReq>,
+  ) -> Result<(), AnyError> {
+    if packages.is_empty() {
+      return Ok(());
+    }
+
+    if self.no_npm || !cfg!(feature = "npm") {
       let fmt_reqs = packages
         .iter()
         .collect::<HashSet<_>>() // prevent duplicates
@@ -259,16 +281,20 @@ impl NpmPackageResolver {
         .map(|p| format!("\"{p}\""))
         .collect::<Vec<_>>()
         .join(", ");
+      let reason = if self.no_npm {
+        "--no-npm is specified"
+      } else {
+        "this build of Deno does not include npm support"
+      };
       return Err(custom_error(
         "NoNpm",
         format!(
-          "Following npm specifiers were requested: {fmt_reqs}; but --no-npm is specified."
+          "Following npm specifiers were requested: {fmt_reqs}; but {reason}."
         ),
       ));
     }
 
     self.inner.add_package_reqs(packages).await?;
-    self.inner.cache_packages().await?;
 
     // If there's a lock file, update it with all discovered npm packages
     if let Some(lockfile_mutex) = &self.maybe_lockfile {
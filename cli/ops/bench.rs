@@ -1,12 +1,16 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time;
 
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::op;
+use deno_core::v8;
 use deno_core::Extension;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
@@ -16,15 +20,25 @@ use deno_runtime::permissions::PermissionsContainer;
 use serde::Deserialize;
 use serde::Serialize;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
+use crate::ops::testing::record_heap_statistics;
 use crate::tools::bench::BenchDescription;
 use crate::tools::bench::BenchEvent;
 use crate::tools::test::TestFilter;
+use crate::util::memory::MemorySampler;
+
+/// The lock which bench modules warmed up in parallel hold while they're
+/// measured, so that only one bench module is measured at a time.
+#[derive(Clone, Default)]
+pub struct MeasureLock(Arc<Mutex<()>>);
 
 pub fn init(
   sender: UnboundedSender<BenchEvent>,
   filter: TestFilter,
+  maybe_memory_sampler: Option<MemorySampler>,
+  maybe_measure_lock: Option<MeasureLock>,
 ) -> Extension {
   Extension::builder("deno_bench")
     .ops(vec![
@@ -34,10 +48,17 @@ pub fn init(
       op_register_bench::decl(),
       op_dispatch_bench_event::decl(),
       op_bench_now::decl(),
+      op_bench_lock_measurements::decl(),
     ])
     .state(move |state| {
       state.put(sender.clone());
       state.put(filter.clone());
+      if let Some(memory_sampler) = &maybe_memory_sampler {
+        state.put(memory_sampler.clone());
+      }
+      if let Some(measure_lock) = &maybe_measure_lock {
+        state.put(measure_lock.clone());
+      }
       Ok(())
     })
     .build()
@@ -133,8 +154,15 @@ fn op_register_bench(
   Ok(BenchRegisterResult { id, filtered_out })
 }
 
-#[op]
-fn op_dispatch_bench_event(state: &mut OpState, event: BenchEvent) {
+#[op(v8)]
+fn op_dispatch_bench_event(
+  scope: &mut v8::HandleScope,
+  state: &mut OpState,
+  event: BenchEvent,
+) {
+  if let Some(memory_sampler) = state.try_borrow::<MemorySampler>() {
+    record_heap_statistics(scope, memory_sampler);
+  }
   let sender = state.borrow::<UnboundedSender<BenchEvent>>().clone();
   sender.send(event).ok();
 }
@@ -145,3 +173,14 @@ fn op_bench_now(state: &mut OpState) -> Result<u64, AnyError> {
   let ns_u64 = u64::try_from(ns)?;
   Ok(ns_u64)
 }
+
+/// Waits until no other bench module is measured, then holds the measure lock
+/// until the worker of the bench module is dropped.
+#[op]
+async fn op_bench_lock_measurements(state: Rc<RefCell<OpState>>) {
+  let maybe_measure_lock = state.borrow().try_borrow::<MeasureLock>().cloned();
+  if let Some(measure_lock) = maybe_measure_lock {
+    let guard = measure_lock.0.lock_owned().await;
+    state.borrow_mut().put(guard);
+  }
+}
@@ -0,0 +1,327 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! `--diagnose-event-loop`: samples how late the event loop picks up work and
+//! how long JS runs without yielding to it, to tell whether timers drift
+//! because of synchronous code or because the loop is starved.
+
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Waker;
+use std::time::Duration;
+use std::time::Instant;
+
+use deno_core::op;
+use deno_core::parking_lot::Mutex;
+use deno_core::v8;
+use deno_core::Extension;
+use deno_core::OpState;
+use deno_runtime::colors;
+use deno_runtime::tokio_util::create_basic_runtime;
+use deno_runtime::worker::ExitCode;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+/// The first bucket holds the samples under 1ms, the following ones double
+/// in size from 1ms and the last one holds everything above.
+const BUCKET_COUNT: usize = 13;
+const BAR_WIDTH: u64 = 40;
+
+#[derive(Default)]
+struct Histogram {
+  buckets: [u64; BUCKET_COUNT],
+  count: u64,
+  max: Duration,
+}
+
+impl Histogram {
+  fn record(&mut self, value: Duration) {
+    let millis = value.as_millis() as u64;
+    let index = (u64::BITS - millis.leading_zeros()) as usize;
+    self.buckets[index.min(BUCKET_COUNT - 1)] += 1;
+    self.count += 1;
+    self.max = self.max.max(value);
+  }
+
+  fn bucket_label(index: usize) -> String {
+    match index {
+      0 => "< 1ms".to_string(),
+      _ if index == BUCKET_COUNT - 1 => format!(">= {}ms", 1 << (index - 1)),
+      _ => format!("{}-{}ms", 1 << (index - 1), 1 << index),
+    }
+  }
+
+  fn print(&self, title: &str) {
+    eprintln!(
+      "  {} {}",
+      colors::bold(title),
+      colors::gray(format!("({} samples, max {:?})", self.count, self.max))
+    );
+    let first = self.buckets.iter().position(|count| *count > 0);
+    let last = self.buckets.iter().rposition(|count| *count > 0);
+    let (first, last) = match first.zip(last) {
+      Some(range) => range,
+      None => return,
+    };
+    let max_count = self.buckets.iter().max().copied().unwrap_or(0);
+    for index in first..=last {
+      let count = self.buckets[index];
+      let width = if count == 0 {
+        0
+      } else {
+        (count * BAR_WIDTH / max_count).max(1)
+      };
+      eprintln!(
+        "    {:>12} {:>8} {}",
+        Self::bucket_label(index),
+        count,
+        colors::cyan("█".repeat(width as usize))
+      );
+    }
+  }
+}
+
+struct DiagnosticsState {
+  started: Instant,
+  /// When the sampler woke the event loop, until it's polled.
+  maybe_probe_sent: Option<Instant>,
+  maybe_waker: Option<Waker>,
+  /// When the current turn of the event loop started.
+  turn_started: Instant,
+  /// The longest the current turn was seen running JS by an interrupt.
+  maybe_turn_blocked: Option<Duration>,
+  is_interrupt_pending: bool,
+  is_stopped: bool,
+  tick_latency: Histogram,
+  sync_segments: Histogram,
+}
+
+/// The samples of the main worker, shared by the event loop middleware, the
+/// isolate interrupts and the sampler thread.
+#[derive(Clone)]
+struct EventLoopDiagnostics(Arc<Mutex<DiagnosticsState>>);
+
+impl EventLoopDiagnostics {
+  fn new() -> Self {
+    let now = Instant::now();
+    Self(Arc::new(Mutex::new(DiagnosticsState {
+      started: now,
+      maybe_probe_sent: None,
+      maybe_waker: None,
+      turn_started: now,
+      maybe_turn_blocked: None,
+      is_interrupt_pending: false,
+      is_stopped: false,
+      tick_latency: Histogram::default(),
+      sync_segments: Histogram::default(),
+    })))
+  }
+
+  fn print_report(&self) {
+    let state = self.0.lock();
+    eprintln!(
+      "{} after {:?}",
+      colors::green_bold("Event loop diagnostics"),
+      state.started.elapsed()
+    );
+    state.tick_latency.print("Tick latency");
+    state.sync_segments.print("Synchronous JS segments");
+  }
+}
+
+pub fn init() -> Extension {
+  let diagnostics = EventLoopDiagnostics::new();
+  Extension::builder("deno_event_loop_diagnostics")
+    .state(move |state| {
+      state.put(diagnostics.clone());
+      Ok(())
+    })
+    .middleware(|op| match op.name {
+      "op_exit" => op_exit_with_event_loop_report::decl(),
+      _ => op,
+    })
+    .event_loop_middleware(poll_event_loop_diagnostics)
+    .build()
+}
+
+/// Stops the sampler thread when the worker is dropped along with its state.
+struct StopSamplingOnDrop(EventLoopDiagnostics);
+
+impl Drop for StopSamplingOnDrop {
+  fn drop(&mut self) {
+    self.0 .0.lock().is_stopped = true;
+  }
+}
+
+/// Starts sampling the worker on a thread of its own, which also prints the
+/// report when the process receives SIGUSR2, even while JS is blocking the
+/// event loop. The isolate handle is only available once the runtime has
+/// been created.
+pub fn start_sampling(state: &mut OpState, isolate_handle: v8::IsolateHandle) {
+  let diagnostics = match state.try_borrow::<EventLoopDiagnostics>() {
+    Some(diagnostics) => diagnostics.clone(),
+    None => return,
+  };
+  state.put(StopSamplingOnDrop(diagnostics.clone()));
+  std::thread::spawn(move || {
+    create_basic_runtime().block_on(sample(diagnostics, isolate_handle))
+  });
+}
+
+/// Prints the report collected so far, if `--diagnose-event-loop` is set.
+pub fn print_report(state: &OpState) {
+  if let Some(diagnostics) = state.try_borrow::<EventLoopDiagnostics>() {
+    diagnostics.print_report();
+  }
+}
+
+async fn sample(
+  diagnostics: EventLoopDiagnostics,
+  isolate_handle: v8::IsolateHandle,
+) {
+  let mut maybe_report_signal = report_signal();
+  let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+  loop {
+    tokio::select! {
+      _ = interval.tick() => {
+        if !sample_once(&diagnostics, &isolate_handle) {
+          return;
+        }
+      }
+      _ = report_requested(&mut maybe_report_signal) => {
+        diagnostics.print_report();
+      }
+    }
+  }
+}
+
+/// Wakes the event loop to measure how long it takes to be polled, which is
+/// how late a timer due now would fire, and interrupts the isolate to catch
+/// JS running for long without yielding. Returns false once the worker is
+/// dropped.
+fn sample_once(
+  diagnostics: &EventLoopDiagnostics,
+  isolate_handle: &v8::IsolateHandle,
+) -> bool {
+  let mut state = diagnostics.0.lock();
+  if state.is_stopped {
+    return false;
+  }
+  if state.maybe_probe_sent.is_none() {
+    if let Some(waker) = &state.maybe_waker {
+      waker.wake_by_ref();
+      state.maybe_probe_sent = Some(Instant::now());
+    }
+  }
+  if !state.is_interrupt_pending {
+    // the interrupt owns a reference until it runs, which it never does when
+    // the isolate is disposed before running JS again
+    let data = Arc::into_raw(diagnostics.0.clone()) as *mut c_void;
+    if !isolate_handle.request_interrupt(on_interrupt, data) {
+      // SAFETY: the interrupt wasn't requested, so the reference is unused
+      drop(unsafe { Arc::from_raw(data as *const Mutex<DiagnosticsState>) });
+      return false;
+    }
+    state.is_interrupt_pending = true;
+  }
+  true
+}
+
+#[cfg(unix)]
+type ReportSignal = tokio::signal::unix::Signal;
+#[cfg(not(unix))]
+type ReportSignal = ();
+
+#[cfg(unix)]
+fn report_signal() -> Option<ReportSignal> {
+  use tokio::signal::unix::signal;
+  use tokio::signal::unix::SignalKind;
+  signal(SignalKind::user_defined2()).ok()
+}
+
+#[cfg(not(unix))]
+fn report_signal() -> Option<ReportSignal> {
+  None
+}
+
+/// Resolves when the process receives SIGUSR2.
+async fn report_requested(maybe_signal: &mut Option<ReportSignal>) {
+  #[cfg(unix)]
+  if let Some(signal) = maybe_signal {
+    if signal.recv().await.is_some() {
+      return;
+    }
+    *maybe_signal = None;
+  }
+  #[cfg(not(unix))]
+  let _ = maybe_signal;
+  std::future::pending().await
+}
+
+/// Runs on the thread of the isolate while it executes JS, or as soon as it
+/// does again.
+extern "C" fn on_interrupt(_isolate: &mut v8::Isolate, data: *mut c_void) {
+  let data = data as *const Mutex<DiagnosticsState>;
+  // SAFETY: the reference was leaked by `sample()` for this interrupt
+  let diagnostics = unsafe { Arc::from_raw(data) };
+  let mut state = diagnostics.lock();
+  state.is_interrupt_pending = false;
+  let blocked = state.turn_started.elapsed();
+  if state.maybe_turn_blocked.map_or(true, |max| blocked > max) {
+    state.maybe_turn_blocked = Some(blocked);
+  }
+}
+
+/// Middlewares run at the start of every turn of the event loop, so the
+/// previous turn ended and its longest sample is the length of its
+/// synchronous segment, up to the sampling interval.
+fn poll_event_loop_diagnostics(
+  state: Rc<RefCell<OpState>>,
+  cx: &mut Context,
+) -> bool {
+  let diagnostics = state.borrow().borrow::<EventLoopDiagnostics>().clone();
+  let mut state = diagnostics.0.lock();
+  let now = Instant::now();
+  if let Some(probe_sent) = state.maybe_probe_sent.take() {
+    state.tick_latency.record(now - probe_sent);
+  }
+  if let Some(blocked) = state.maybe_turn_blocked.take() {
+    state.sync_segments.record(blocked);
+  }
+  state.turn_started = now;
+  if !state
+    .maybe_waker
+    .as_ref()
+    .map_or(false, |waker| waker.will_wake(cx.waker()))
+  {
+    state.maybe_waker = Some(cx.waker().clone());
+  }
+  false
+}
+
+#[op]
+fn op_exit_with_event_loop_report(state: &mut OpState) {
+  print_report(state);
+  std::process::exit(state.borrow::<ExitCode>().get())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn histogram_buckets() {
+    let mut histogram = Histogram::default();
+    for millis in [0, 1, 2, 3, 4, 1023, 1024, 2048, 60_000] {
+      histogram.record(Duration::from_millis(millis));
+    }
+    assert_eq!(histogram.buckets, [1, 1, 2, 1, 0, 0, 0, 0, 0, 0, 1, 1, 2]);
+    assert_eq!(histogram.count, 9);
+    assert_eq!(histogram.max, Duration::from_secs(60));
+    assert_eq!(Histogram::bucket_label(0), "< 1ms");
+    assert_eq!(Histogram::bucket_label(1), "1-2ms");
+    assert_eq!(Histogram::bucket_label(11), "1024-2048ms");
+    assert_eq!(Histogram::bucket_label(12), ">= 2048ms");
+  }
+}
@@ -0,0 +1,110 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::Context;
+use std::time::Duration;
+
+use deno_core::futures::FutureExt;
+use deno_core::Extension;
+use deno_core::OpState;
+use deno_runtime::colors;
+use tokio::time::Instant;
+use tokio::time::Sleep;
+
+/// The exit code used when the process is terminated by
+/// `--exit-on-idle-timeout`. It matches the one used by `timeout(1)`.
+pub const IDLE_TIMEOUT_EXIT_CODE: i32 = 124;
+
+struct IdleWatchdog {
+  timeout: Duration,
+  ops_completed: u64,
+  deadline: Option<Pin<Box<Sleep>>>,
+  op_names: Vec<&'static str>,
+}
+
+pub fn init(timeout: Duration) -> Extension {
+  Extension::builder("deno_idle_timeout")
+    .state(move |state| {
+      state.put(IdleWatchdog {
+        timeout,
+        ops_completed: 0,
+        deadline: None,
+        op_names: Vec::new(),
+      });
+      Ok(())
+    })
+    .event_loop_middleware(poll_idle_watchdog)
+    .build()
+}
+
+/// Provides the op names used to describe pending ops. They are only
+/// available once the runtime has been created.
+pub fn set_op_names(state: &mut OpState, op_names: Vec<&'static str>) {
+  if let Some(watchdog) = state.try_borrow_mut::<IdleWatchdog>() {
+    watchdog.op_names = op_names;
+  }
+}
+
+/// Restarts the deadline whenever an op completed since the previous poll.
+/// Timers and all I/O complete ops, so the deadline is only reached when the
+/// event loop is kept alive without making any progress. Middlewares run
+/// between turns of the event loop, so this never fires while JS is running.
+fn poll_idle_watchdog(state: Rc<RefCell<OpState>>, cx: &mut Context) -> bool {
+  let mut state = state.borrow_mut();
+  let ops_completed = state.tracker.aggregate().ops_completed;
+  let watchdog = state.borrow_mut::<IdleWatchdog>();
+  if watchdog.deadline.is_none() || watchdog.ops_completed != ops_completed {
+    watchdog.ops_completed = ops_completed;
+    let deadline = Instant::now() + watchdog.timeout;
+    if let Some(sleep) = &mut watchdog.deadline {
+      sleep.as_mut().reset(deadline);
+    } else {
+      watchdog.deadline = Some(Box::pin(tokio::time::sleep_until(deadline)));
+    }
+  }
+  let sleep = watchdog.deadline.as_mut().unwrap();
+  if sleep.poll_unpin(cx).is_pending() {
+    return false;
+  }
+
+  report_pending(&state);
+  std::process::exit(IDLE_TIMEOUT_EXIT_CODE);
+}
+
+fn report_pending(state: &OpState) {
+  let watchdog = state.borrow::<IdleWatchdog>();
+  let mut pending_ops = BTreeMap::new();
+  for (id, metrics) in state.tracker.per_op().iter().enumerate() {
+    let pending = metrics.ops_dispatched_async - metrics.ops_completed_async;
+    if pending > 0 {
+      let name = watchdog.op_names.get(id).copied().unwrap_or("<unknown>");
+      *pending_ops.entry(name.to_string()).or_insert(0) += pending;
+    }
+  }
+  let mut resources = BTreeMap::new();
+  for (_, name) in state.resource_table.names() {
+    *resources.entry(name.into_owned()).or_insert(0) += 1;
+  }
+
+  eprintln!(
+    "{}: No pending op completed within {:?}, exiting.",
+    colors::red_bold("error"),
+    watchdog.timeout,
+  );
+  print_counts("Pending ops", &pending_ops);
+  print_counts("Open resources", &resources);
+}
+
+fn print_counts(title: &str, counts: &BTreeMap<String, u64>) {
+  if counts.is_empty() {
+    eprintln!("{title}: none");
+    return;
+  }
+  eprintln!("{title}:");
+  for (name, count) in counts {
+    eprintln!("  {name} {}", colors::gray(format!("({count})")));
+  }
+}
@@ -3,10 +3,13 @@
 use crate::proc_state::ProcState;
 use deno_core::error::AnyError;
 use deno_core::op;
+use deno_core::resolve_url;
 use deno_core::Extension;
 use deno_core::OpState;
 
 pub mod bench;
+pub mod event_loop_diagnostics;
+pub mod idle_timeout;
 pub mod testing;
 
 pub fn cli_exts(ps: ProcState) -> Vec<Extension> {
@@ -15,7 +18,10 @@ pub fn cli_exts(ps: ProcState) -> Vec<Extension> {
 
 fn init_proc_state(ps: ProcState) -> Extension {
   Extension::builder("deno_cli")
-    .ops(vec![op_npm_process_state::decl()])
+    .ops(vec![
+      op_npm_process_state::decl(),
+      op_import_order_evaluate::decl(),
+    ])
     .state(move |state| {
       state.put(ps.clone());
       Ok(())
@@ -28,3 +34,15 @@ fn op_npm_process_state(state: &mut OpState) -> Result<String, AnyError> {
   let proc_state = state.borrow_mut::<ProcState>();
   Ok(proc_state.npm_resolver.get_npm_process_state())
 }
+
+#[op]
+fn op_import_order_evaluate(
+  state: &mut OpState,
+  specifier: String,
+) -> Result<(), AnyError> {
+  let proc_state = state.borrow::<ProcState>();
+  if let Some(import_order) = &proc_state.maybe_import_order {
+    import_order.record_evaluation(resolve_url(&specifier)?);
+  }
+  Ok(())
+}
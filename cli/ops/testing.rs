@@ -6,15 +6,25 @@ use crate::tools::test::TestEvent;
 use crate::tools::test::TestEventSender;
 use crate::tools::test::TestFilter;
 use crate::tools::test::TestLocation;
+use crate::tools::test::TestRegistrations;
 use crate::tools::test::TestResult;
 use crate::tools::test::TestStepDescription;
+use crate::tools::test::TestTimeoutWatchdog;
+use crate::util::diff::hex_diff;
+use crate::util::diff::unified_diff;
+use crate::util::memory::MemorySampler;
+use crate::util::path::specifier_to_file_path;
 
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::op;
+use deno_core::resolve_url;
+use deno_core::v8;
 use deno_core::Extension;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
+use deno_core::ZeroCopyBuf;
 use deno_runtime::permissions::create_child_permissions;
 use deno_runtime::permissions::ChildPermissionsArg;
 use deno_runtime::permissions::PermissionsContainer;
@@ -23,12 +33,17 @@ use serde::Deserializer;
 use serde::Serialize;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
 use uuid::Uuid;
 
 pub fn init(
   sender: TestEventSender,
   fail_fast_tracker: FailFastTracker,
   filter: TestFilter,
+  maybe_memory_sampler: Option<MemorySampler>,
+  update_golden: bool,
+  maybe_timeout_watchdog: Option<TestTimeoutWatchdog>,
+  registrations: TestRegistrations,
 ) -> Extension {
   Extension::builder("deno_test")
     .ops(vec![
@@ -39,11 +54,26 @@ pub fn init(
       op_register_test_step::decl(),
       op_dispatch_test_event::decl(),
       op_tests_should_stop::decl(),
+      op_tests_file_should_stop::decl(),
+      op_assert_golden_file::decl(),
+      op_test_timeout_start::decl(),
+      op_test_timeout_end::decl(),
     ])
     .state(move |state| {
       state.put(sender.clone());
       state.put(fail_fast_tracker.clone());
       state.put(filter.clone());
+      state.put(UpdateGolden(update_golden));
+      if let Some(memory_sampler) = &maybe_memory_sampler {
+        state.put(memory_sampler.clone());
+      }
+      if let Some(timeout_watchdog) = &maybe_timeout_watchdog {
+        state.put(timeout_watchdog.clone());
+      }
+      state.put(RegisteredTests {
+        registrations: registrations.clone(),
+        count: 0,
+      });
       Ok(())
     })
     .build()
@@ -106,6 +136,7 @@ struct TestInfo {
   name: String,
   origin: String,
   location: TestLocation,
+  tags: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -113,27 +144,51 @@ struct TestInfo {
 struct TestRegisterResult {
   id: usize,
   filtered_out: bool,
+  excluded_by_tag: bool,
 }
 
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
+/// The tests registered by the module evaluated in this worker.
+struct RegisteredTests {
+  registrations: TestRegistrations,
+  count: usize,
+}
+
 #[op]
 fn op_register_test(
   state: &mut OpState,
   info: TestInfo,
 ) -> Result<TestRegisterResult, AnyError> {
-  let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
-  let filter = state.borrow::<TestFilter>().clone();
-  let filtered_out = !filter.includes(&info.name);
+  let registered = state.borrow_mut::<RegisteredTests>();
+  let index = registered.count;
+  registered.count += 1;
+  let (id, is_new) = registered
+    .registrations
+    .get_or_register(index, || NEXT_ID.fetch_add(1, Ordering::SeqCst));
+  let filter = state.borrow::<TestFilter>();
+  let excluded_by_tag = !filter.includes_tags(&info.tags);
+  let filtered_out = !filter.includes(&info.name) || excluded_by_tag;
+  let result = TestRegisterResult {
+    id,
+    filtered_out,
+    excluded_by_tag,
+  };
+  // the module is evaluated again after a test timed out, and the reporters
+  // already know about its tests
+  if !is_new {
+    return Ok(result);
+  }
   let description = TestDescription {
     id,
     name: info.name,
     origin: info.origin,
     location: info.location,
+    tags: info.tags,
   };
   let mut sender = state.borrow::<TestEventSender>().clone();
   sender.send(TestEvent::Register(description)).ok();
-  Ok(TestRegisterResult { id, filtered_out })
+  Ok(result)
 }
 
 fn deserialize_parent<'de, D>(deserializer: D) -> Result<usize, D::Error>
@@ -182,17 +237,28 @@ fn op_register_test_step(
   Ok(TestRegisterResult {
     id,
     filtered_out: false,
+    excluded_by_tag: false,
   })
 }
 
-#[op]
+#[op(v8)]
 fn op_dispatch_test_event(
+  scope: &mut v8::HandleScope,
   state: &mut OpState,
   event: TestEvent,
 ) -> Result<(), AnyError> {
+  if let Some(memory_sampler) = state.try_borrow::<MemorySampler>() {
+    record_heap_statistics(scope, memory_sampler);
+  }
   if matches!(
     event,
-    TestEvent::Result(_, TestResult::Cancelled | TestResult::Failed(_), _)
+    TestEvent::Result(
+      _,
+      TestResult::Cancelled
+        | TestResult::Failed(_)
+        | TestResult::UnexpectedlyPassed,
+      _
+    )
   ) {
     state.borrow::<FailFastTracker>().add_failure();
   }
@@ -201,7 +267,121 @@ fn op_dispatch_test_event(
   Ok(())
 }
 
+#[op]
+fn op_test_timeout_start(
+  state: &mut OpState,
+  id: usize,
+  index: usize,
+  timeout: u64,
+) {
+  if let Some(timeout_watchdog) = state.try_borrow::<TestTimeoutWatchdog>() {
+    timeout_watchdog.test_started(id, index, Duration::from_millis(timeout));
+  }
+}
+
+#[op]
+fn op_test_timeout_end(state: &mut OpState) {
+  if let Some(timeout_watchdog) = state.try_borrow::<TestTimeoutWatchdog>() {
+    timeout_watchdog.test_finished();
+  }
+}
+
+/// Records the heap statistics of the isolate dispatching an event.
+pub fn record_heap_statistics(
+  scope: &mut v8::HandleScope,
+  memory_sampler: &MemorySampler,
+) {
+  let mut stats = v8::HeapStatistics::default();
+  scope.get_heap_statistics(&mut stats);
+  memory_sampler.record_heap(
+    stats.used_heap_size() as u64,
+    stats.total_heap_size() as u64,
+  );
+}
+
 #[op]
 fn op_tests_should_stop(state: &mut OpState) -> bool {
   state.borrow::<FailFastTracker>().should_stop()
 }
+
+#[op]
+fn op_tests_file_should_stop(state: &mut OpState) -> bool {
+  state.borrow::<FailFastTracker>().should_stop_file()
+}
+
+/// Whether golden files are rewritten with the actual contents instead of
+/// being compared with them.
+#[derive(Clone, Copy)]
+struct UpdateGolden(bool);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoldenFileArgs {
+  origin: String,
+  path: String,
+  binary: bool,
+}
+
+/// Compares the actual contents with a golden file, which is resolved
+/// relative to the test module. Returns a description of the differences
+/// when they don't match.
+#[op]
+fn op_assert_golden_file(
+  state: &mut OpState,
+  args: GoldenFileArgs,
+  actual: ZeroCopyBuf,
+) -> Result<Option<String>, AnyError> {
+  let specifier = resolve_url(&args.origin)?.join(&args.path)?;
+  let path = specifier_to_file_path(&specifier).with_context(|| {
+    format!("Golden files must be local files, but found {specifier}")
+  })?;
+  let update = state.borrow::<UpdateGolden>().0;
+  let permissions = state.borrow_mut::<PermissionsContainer>();
+  let api_name = "TestContext.assertGoldenFile()";
+
+  if update {
+    permissions.check_write(&path, api_name)?;
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent).with_context(|| {
+        format!("Failed creating directory {}", parent.display())
+      })?;
+    }
+    std::fs::write(&path, &*actual)
+      .with_context(|| format!("Failed writing {}", path.display()))?;
+    return Ok(None);
+  }
+
+  permissions.check_read(&path, api_name)?;
+  let expected = match std::fs::read(&path) {
+    Ok(expected) => expected,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+      return Ok(Some(format!(
+        "Golden file not found: {}\nRun with --update-golden to create it.",
+        path.display()
+      )));
+    }
+    Err(err) => {
+      return Err(err)
+        .with_context(|| format!("Failed reading {}", path.display()))
+    }
+  };
+  if expected == *actual {
+    return Ok(None);
+  }
+
+  let diff = if args.binary {
+    hex_diff(&expected, &actual)
+  } else {
+    unified_diff(
+      &args.path,
+      "actual",
+      &String::from_utf8_lossy(&expected),
+      &String::from_utf8_lossy(&actual),
+    )
+  };
+  Ok(Some(format!(
+    "Actual contents do not match the golden file {}\nRun with --update-golden to update it.\n\n{}",
+    path.display(),
+    diff.trim_end_matches('\n'),
+  )))
+}
@@ -20,6 +20,8 @@ use crate::file_fetcher::FileFetcher;
 use crate::graph_util::graph_lock_or_exit;
 use crate::graph_util::graph_valid_with_cli_options;
 use crate::http_util::HttpClient;
+use crate::journal;
+use crate::module_loader::ImportOrder;
 use crate::node;
 use crate::node::NodeResolution;
 use crate::npm::resolve_graph_npm_info;
@@ -29,6 +31,9 @@ use crate::npm::NpmPackageReq;
 use crate::npm::NpmPackageResolver;
 use crate::npm::RealNpmRegistryApi;
 use crate::resolver::CliResolver;
+use crate::scheme_handlers::test_fixture_handler;
+use crate::scheme_handlers::SchemeHandlers;
+use crate::scheme_handlers::TEST_FIXTURE_SCHEME;
 use crate::tools::check;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
@@ -46,7 +51,9 @@ use deno_core::CompiledWasmModuleStore;
 use deno_core::ModuleSpecifier;
 use deno_core::SharedArrayBufferStore;
 use deno_graph::source::Loader;
+use deno_graph::source::Reporter;
 use deno_graph::source::Resolver;
+use deno_graph::ModuleAnalyzer;
 use deno_graph::ModuleGraph;
 use deno_graph::ModuleKind;
 use deno_graph::Resolution;
@@ -76,6 +83,8 @@ pub struct Inner {
   pub dir: DenoDir,
   pub file_fetcher: Arc<FileFetcher>,
   pub http_client: HttpClient,
+  /// The user agent used for remote requests and by the runtime.
+  pub user_agent: String,
   pub options: Arc<CliOptions>,
   pub emit_cache: EmitCache,
   pub emit_options: deno_ast::EmitOptions,
@@ -98,6 +107,7 @@ pub struct Inner {
   pub cjs_resolutions: Mutex<HashSet<ModuleSpecifier>>,
   progress_bar: ProgressBar,
   node_std_graph_prepared: AtomicBool,
+  pub maybe_import_order: Option<ImportOrder>,
 }
 
 impl Deref for ProcState {
@@ -132,8 +142,9 @@ impl ProcState {
   }
 
   /// Reset all runtime state to its default. This should be used on file
-  /// watcher restarts.
-  pub fn reset_for_file_watcher(&mut self) {
+  /// watcher restarts. Caches that survive a restart are kept, with only the
+  /// entries for `changed_paths` invalidated (or all of them when `None`).
+  pub fn reset_for_file_watcher(&mut self, changed_paths: Option<&[PathBuf]>) {
     self.0 = Arc::new(Inner {
       dir: self.dir.clone(),
       options: self.options.clone(),
@@ -142,6 +153,7 @@ impl ProcState {
       emit_options: self.emit_options.clone(),
       file_fetcher: self.file_fetcher.clone(),
       http_client: self.http_client.clone(),
+      user_agent: self.user_agent.clone(),
       graph_data: Default::default(),
       lockfile: self.lockfile.clone(),
       maybe_import_map: self.maybe_import_map.clone(),
@@ -151,7 +163,9 @@ impl ProcState {
       broadcast_channel: Default::default(),
       shared_array_buffer_store: Default::default(),
       compiled_wasm_module_store: Default::default(),
-      parsed_source_cache: self.parsed_source_cache.reset_for_file_watcher(),
+      parsed_source_cache: self
+        .parsed_source_cache
+        .reset_for_file_watcher(changed_paths),
       maybe_resolver: self.maybe_resolver.clone(),
       maybe_file_watcher_reporter: self.maybe_file_watcher_reporter.clone(),
       node_analysis_cache: self.node_analysis_cache.clone(),
@@ -160,6 +174,10 @@ impl ProcState {
       cjs_resolutions: Default::default(),
       progress_bar: self.progress_bar.clone(),
       node_std_graph_prepared: AtomicBool::new(false),
+      maybe_import_order: self
+        .options
+        .print_import_order()
+        .map(|_| Default::default()),
     });
     self.init_watcher();
   }
@@ -197,11 +215,14 @@ impl ProcState {
     let root_cert_store = cli_options.resolve_root_cert_store()?;
     let cache_usage = cli_options.cache_setting();
     let progress_bar = ProgressBar::new(ProgressBarStyle::TextOnly);
-    let http_client = HttpClient::new(
+    let user_agent = cli_options.resolve_user_agent()?;
+    let http_client = HttpClient::new_with_user_agent(
+      user_agent.clone(),
       Some(root_cert_store.clone()),
       cli_options.unsafely_ignore_certificate_errors().clone(),
+      cli_options.resolve_tls_client_cert()?,
     )?;
-    let file_fetcher = FileFetcher::new(
+    let mut file_fetcher = FileFetcher::new(
       http_cache,
       cache_usage,
       !cli_options.no_remote(),
@@ -209,6 +230,12 @@ impl ProcState {
       blob_store.clone(),
       Some(progress_bar.clone()),
     );
+    let mut scheme_handlers = SchemeHandlers::default();
+    if cli_options.enable_testing_features() {
+      scheme_handlers.register(TEST_FIXTURE_SCHEME, test_fixture_handler)?;
+    }
+    file_fetcher.set_scheme_handlers(scheme_handlers);
+    journal::track_fetch_stats(file_fetcher.shared_stats());
 
     let lockfile = cli_options.maybe_lock_file();
 
@@ -246,6 +273,7 @@ impl ProcState {
       http_client.clone(),
       progress_bar.clone(),
     );
+    journal::track_fetch_stats(npm_cache.shared_stats());
     let api = RealNpmRegistryApi::new(
       registry_url,
       npm_cache.clone(),
@@ -266,6 +294,8 @@ impl ProcState {
       NodeAnalysisCache::new(Some(dir.node_analysis_db_file_path()));
 
     let emit_options: deno_ast::EmitOptions = ts_config_result.ts_config.into();
+    let maybe_import_order =
+      cli_options.print_import_order().map(|_| Default::default());
     Ok(ProcState(Arc::new(Inner {
       dir,
       options: cli_options,
@@ -276,6 +306,7 @@ impl ProcState {
       emit_options,
       file_fetcher: Arc::new(file_fetcher),
       http_client,
+      user_agent,
       graph_data: Default::default(),
       lockfile,
       maybe_import_map,
@@ -294,6 +325,7 @@ impl ProcState {
       cjs_resolutions: Default::default(),
       progress_bar,
       node_std_graph_prepared: AtomicBool::new(false),
+      maybe_import_order,
     })))
   }
 
@@ -411,6 +443,8 @@ impl ProcState {
         reload: self.options.reload_flag()
           && !roots.iter().all(|r| reload_exclusions.contains(r)),
         has_node_builtin_specifier,
+        lib_overrides: self.options.resolve_lib_overrides()?,
+        report_deprecations: self.options.report_deprecations(),
       };
       let check_cache =
         TypeCheckCache::new(&self.dir.type_checking_cache_db_file_path());
@@ -612,6 +646,40 @@ impl ProcState {
     }
   }
 
+  /// Resolves the specifier of a module passed with `--preload` like an
+  /// import from the current directory, so import maps apply. Specifiers the
+  /// resolver rejects are resolved as a path or URL, like the main module.
+  pub fn resolve_preload_module(
+    &self,
+    specifier: &str,
+  ) -> Result<ModuleSpecifier, AnyError> {
+    let referrer =
+      ModuleSpecifier::from_directory_path(std::env::current_dir()?).unwrap();
+    let maybe_resolved = self
+      .maybe_resolver
+      .as_ref()
+      .and_then(|resolver| resolver.resolve(specifier, &referrer).ok());
+    match maybe_resolved {
+      Some(resolved) => Ok(resolved),
+      None => {
+        deno_core::resolve_url_or_path(specifier).map_err(|err| err.into())
+      }
+    }
+  }
+
+  /// Resolves the modules passed with `--preload`, in the order they're
+  /// evaluated.
+  pub fn resolve_preload_modules(
+    &self,
+  ) -> Result<Vec<ModuleSpecifier>, AnyError> {
+    self
+      .options
+      .preload_modules()
+      .iter()
+      .map(|specifier| self.resolve_preload_module(specifier))
+      .collect()
+  }
+
   pub fn cache_module_emits(&self) -> Result<(), AnyError> {
     let graph = self.graph();
     for module in graph.modules() {
@@ -663,6 +731,24 @@ impl ProcState {
     &self,
     roots: Vec<ModuleSpecifier>,
     loader: &mut dyn Loader,
+  ) -> Result<deno_graph::ModuleGraph, AnyError> {
+    let analyzer = self.parsed_source_cache.as_analyzer();
+    self
+      .create_graph_with_options(roots, loader, &*analyzer, None, true)
+      .await
+  }
+
+  /// Creates a graph using the provided module analyzer, optionally
+  /// reporting the progress of loading modules to a reporter. The npm
+  /// packages of the graph are always resolved, but only downloaded when
+  /// `cache_npm_packages` is set.
+  pub async fn create_graph_with_options(
+    &self,
+    roots: Vec<ModuleSpecifier>,
+    loader: &mut dyn Loader,
+    analyzer: &dyn ModuleAnalyzer,
+    maybe_reporter: Option<&dyn Reporter>,
+    cache_npm_packages: bool,
   ) -> Result<deno_graph::ModuleGraph, AnyError> {
     let maybe_imports = self.options.to_maybe_imports()?;
 
@@ -672,7 +758,6 @@ impl ProcState {
     );
     let maybe_graph_resolver =
       maybe_cli_resolver.as_ref().map(|r| r.as_graph_resolver());
-    let analyzer = self.parsed_source_cache.as_analyzer();
 
     let mut graph = ModuleGraph::default();
     graph
@@ -683,21 +768,27 @@ impl ProcState {
           is_dynamic: false,
           imports: maybe_imports,
           resolver: maybe_graph_resolver,
-          module_analyzer: Some(&*analyzer),
-          reporter: None,
+          module_analyzer: Some(analyzer),
+          reporter: maybe_reporter,
         },
       )
       .await;
 
     // add the found npm package requirements to the npm resolver and cache them
     let graph_npm_info = resolve_graph_npm_info(&graph);
-    if !graph_npm_info.package_reqs.is_empty() {
+    if !cache_npm_packages {
+      self
+        .npm_resolver
+        .resolve_package_reqs(graph_npm_info.package_reqs)
+        .await?;
+    } else if !graph_npm_info.package_reqs.is_empty() {
       self
         .npm_resolver
         .add_package_reqs(graph_npm_info.package_reqs)
         .await?;
     }
-    if graph_npm_info.has_node_builtin_specifier
+    if cache_npm_packages
+      && graph_npm_info.has_node_builtin_specifier
       && self.options.type_check_mode() != TypeCheckMode::None
     {
       self
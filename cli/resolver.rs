@@ -2,6 +2,7 @@
 
 use deno_core::error::AnyError;
 use deno_core::resolve_import;
+use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
 use deno_graph::source::Resolver;
 use deno_graph::source::DEFAULT_JSX_IMPORT_SOURCE_MODULE;
@@ -65,11 +66,119 @@ impl Resolver for CliResolver {
     referrer: &ModuleSpecifier,
   ) -> Result<ModuleSpecifier, AnyError> {
     if let Some(import_map) = &self.maybe_import_map {
-      import_map
-        .resolve(specifier, referrer)
-        .map_err(|err| err.into())
+      import_map.resolve(specifier, referrer).or_else(|err| {
+        match resolve_npm_import_map_subpath(import_map, specifier) {
+          Some(resolved) => Ok(resolved),
+          None => Err(err.into()),
+        }
+      })
     } else {
       resolve_import(specifier, referrer.as_str()).map_err(|err| err.into())
     }
   }
 }
+
+/// Finds the import map entry mapping a bare specifier to an npm package,
+/// either as a prefix entry like `"lodash/": "npm:lodash@4/"` or an entry for
+/// the package name like `"lodash": "npm:lodash@4"`, which is then also used
+/// for its subpaths. The longest matching entry wins. Returns the key of the
+/// entry and the mapped `npm:` specifier.
+pub fn find_npm_import_map_entry<'a>(
+  import_map: &'a ImportMap,
+  specifier: &str,
+) -> Option<(&'a str, &'a Url)> {
+  let mut maybe_entry: Option<(&str, &Url)> = None;
+  for entry in import_map.imports().entries() {
+    let (key, value) = match entry.value {
+      Some(value) if value.scheme() == "npm" => (entry.key, value),
+      _ => continue,
+    };
+    let is_match = if key.ends_with('/') {
+      specifier.starts_with(key)
+    } else {
+      specifier
+        .strip_prefix(key)
+        .map(|rest| rest.starts_with('/'))
+        .unwrap_or(false)
+    };
+    let is_longer = maybe_entry
+      .map(|(entry_key, _)| key.len() > entry_key.len())
+      .unwrap_or(true);
+    if is_match && is_longer {
+      maybe_entry = Some((key, value));
+    }
+  }
+  maybe_entry
+}
+
+/// Resolves a bare specifier with a subpath that's mapped to an npm package
+/// by the import map. The import map can't do this itself because `npm:`
+/// specifiers can't be used as a base to resolve the subpath against.
+fn resolve_npm_import_map_subpath(
+  import_map: &ImportMap,
+  specifier: &str,
+) -> Option<ModuleSpecifier> {
+  let (key, value) = find_npm_import_map_entry(import_map, specifier)?;
+  let sub_path = specifier[key.len()..].trim_start_matches('/');
+  let package = value.as_str().trim_end_matches('/');
+  if sub_path.is_empty() {
+    ModuleSpecifier::parse(package).ok()
+  } else {
+    ModuleSpecifier::parse(&format!("{package}/{sub_path}")).ok()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn resolve(import_map_json: &str, specifier: &str) -> Option<String> {
+    let base = ModuleSpecifier::parse("file:///project/").unwrap();
+    let import_map = import_map::parse_from_json(&base, import_map_json)
+      .unwrap()
+      .import_map;
+    let resolver = CliResolver::with_import_map(Arc::new(import_map));
+    let referrer = base.join("main.ts").unwrap();
+    resolver
+      .resolve(specifier, &referrer)
+      .ok()
+      .map(|specifier| specifier.to_string())
+  }
+
+  #[test]
+  fn resolves_npm_subpaths_through_import_map() {
+    let import_map = r#"{
+      "imports": {
+        "lodash": "npm:lodash@4",
+        "preact/": "npm:preact@10/",
+        "@std/path": "npm:@scope/path@1",
+        "@std/fs/": "npm:@scope/fs@1/",
+        "lodash/fp": "npm:lodash-fp@2",
+        "lodash/debounce/": "npm:debounce@1/"
+      }
+    }"#;
+    let cases = [
+      ("lodash", Some("npm:lodash@4")),
+      ("lodash/get", Some("npm:lodash@4/get")),
+      ("lodash/get/nested.js", Some("npm:lodash@4/get/nested.js")),
+      ("lodashx/get", None),
+      ("preact/hooks", Some("npm:preact@10/hooks")),
+      ("@std/path", Some("npm:@scope/path@1")),
+      ("@std/path/posix", Some("npm:@scope/path@1/posix")),
+      ("@std/fs/copy", Some("npm:@scope/fs@1/copy")),
+      // subpaths pinned to a different package take precedence
+      ("lodash/fp", Some("npm:lodash-fp@2")),
+      ("lodash/fp/map", Some("npm:lodash-fp@2/map")),
+      ("lodash/debounce/index.js", Some("npm:debounce@1/index.js")),
+      ("./local.ts", Some("file:///project/local.ts")),
+      ("unmapped", None),
+    ];
+    for (specifier, expected) in cases {
+      assert_eq!(
+        resolve(import_map, specifier).as_deref(),
+        expected,
+        "{specifier}"
+      );
+    }
+  }
+}
@@ -0,0 +1,135 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Custom module schemes, which embedders register to load modules from
+//! somewhere other than the file system or the network, like an artifact
+//! store.
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::futures::FutureExt;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::file_fetcher::SUPPORTED_SCHEMES;
+
+/// The scheme handled by `test_fixture_handler`, which is registered when
+/// `--enable-testing-features-do-not-use` is specified.
+pub const TEST_FIXTURE_SCHEME: &str = "test-fixture";
+
+/// A module loaded by a scheme handler.
+#[derive(Debug, Clone, Default)]
+pub struct SchemeResponse {
+  pub bytes: Vec<u8>,
+  /// The headers of the module, like the ones of a remote module. The media
+  /// type is based on the extension of the specifier unless there is a
+  /// `content-type` header.
+  pub headers: HashMap<String, String>,
+}
+
+pub type SchemeHandlerFuture =
+  Pin<Box<dyn Future<Output = Result<SchemeResponse, AnyError>> + Send>>;
+
+pub type SchemeHandler =
+  Arc<dyn Fn(ModuleSpecifier) -> SchemeHandlerFuture + Send + Sync>;
+
+/// The handlers of custom module schemes, by scheme.
+///
+/// Their modules are part of module graphs like remote modules: relative
+/// imports resolve against them, they are cached in `DENO_DIR` under a
+/// directory named after the scheme and importing them dynamically requires
+/// the scheme permission, which `--allow-scheme` grants.
+#[derive(Clone, Default)]
+pub struct SchemeHandlers(HashMap<String, SchemeHandler>);
+
+impl SchemeHandlers {
+  /// Registers the handler that loads the modules of a scheme. The schemes
+  /// that are built into the CLI can't be handled.
+  pub fn register(
+    &mut self,
+    scheme: &str,
+    handler: impl Fn(ModuleSpecifier) -> SchemeHandlerFuture + Send + Sync + 'static,
+  ) -> Result<(), AnyError> {
+    if SUPPORTED_SCHEMES.contains(&scheme) || matches!(scheme, "npm" | "node") {
+      bail!("The \"{scheme}\" scheme is built in and can't be handled.");
+    }
+    self.0.insert(scheme.to_string(), Arc::new(handler));
+    Ok(())
+  }
+
+  pub fn get(&self, scheme: &str) -> Option<SchemeHandler> {
+    self.0.get(scheme).cloned()
+  }
+}
+
+impl fmt::Debug for SchemeHandlers {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_set().entries(self.0.keys()).finish()
+  }
+}
+
+/// Handles the modules of the `test-fixture:` scheme for the integration
+/// tests. A module default exports its own specifier, after importing the
+/// comma separated specifiers of its `imports` query parameter, for example
+/// `test-fixture://fixtures/a.ts?imports=./b.ts`.
+pub fn test_fixture_handler(specifier: ModuleSpecifier) -> SchemeHandlerFuture {
+  let mut code = String::new();
+  for (key, value) in specifier.query_pairs() {
+    if key == "imports" {
+      for import in value.split(',') {
+        writeln!(code, "import {};", serde_json::to_string(import).unwrap())
+          .unwrap();
+      }
+    }
+  }
+  writeln!(
+    code,
+    "export default {};",
+    serde_json::to_string(specifier.as_str()).unwrap()
+  )
+  .unwrap();
+  let response = SchemeResponse {
+    bytes: code.into_bytes(),
+    headers: HashMap::new(),
+  };
+  async move { Ok(response) }.boxed()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn register_built_in_scheme() {
+    let mut handlers = SchemeHandlers::default();
+    assert!(handlers.register("https", test_fixture_handler).is_err());
+    assert!(handlers.register("npm", test_fixture_handler).is_err());
+    assert!(handlers.get("https").is_none());
+    handlers
+      .register(TEST_FIXTURE_SCHEME, test_fixture_handler)
+      .unwrap();
+    assert!(handlers.get(TEST_FIXTURE_SCHEME).is_some());
+  }
+
+  #[tokio::test]
+  async fn test_fixture_module() {
+    let specifier = ModuleSpecifier::parse(
+      "test-fixture://fixtures/a.ts?imports=./b.ts,./c.ts",
+    )
+    .unwrap();
+    let response = test_fixture_handler(specifier).await.unwrap();
+    assert_eq!(
+      String::from_utf8(response.bytes).unwrap(),
+      concat!(
+        "import \"./b.ts\";\n",
+        "import \"./c.ts\";\n",
+        "export default \"test-fixture://fixtures/a.ts?imports=./b.ts,./c.ts\";\n",
+      )
+    );
+  }
+}
@@ -2,10 +2,12 @@
 
 use crate::args::CaData;
 use crate::args::Flags;
+use crate::args::UnusedPermissionsFormat;
 use crate::colors;
 use crate::file_fetcher::get_source_from_data_url;
 use crate::ops;
 use crate::proc_state::ProcState;
+use crate::util::display;
 use crate::util::v8::construct_v8_flags;
 use crate::version;
 use crate::CliResolver;
@@ -13,6 +15,8 @@ use deno_core::anyhow::Context;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
 use deno_core::futures::io::AllowStdIo;
+use deno_core::futures::io::BufReader;
+use deno_core::futures::AsyncRead;
 use deno_core::futures::AsyncReadExt;
 use deno_core::futures::AsyncSeekExt;
 use deno_core::futures::FutureExt;
@@ -20,6 +24,7 @@ use deno_core::located_script_name;
 use deno_core::serde::Deserialize;
 use deno_core::serde::Serialize;
 use deno_core::serde_json;
+use deno_core::serde_json::json;
 use deno_core::url::Url;
 use deno_core::v8_set_flags;
 use deno_core::ModuleLoader;
@@ -42,6 +47,7 @@ use std::io::SeekFrom;
 use std::pin::Pin;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Deserialize, Serialize)]
 pub struct Metadata {
@@ -55,12 +61,31 @@ pub struct Metadata {
   pub ca_stores: Option<Vec<String>>,
   pub ca_data: Option<Vec<u8>>,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
+  pub user_agent: Option<String>,
   pub maybe_import_map: Option<(Url, String)>,
   pub entrypoint: ModuleSpecifier,
+  /// Modules evaluated before the entrypoint, in order.
+  pub preload_modules: Vec<ModuleSpecifier>,
+  /// The version of Deno the binary was compiled with.
+  pub deno_version: String,
+  pub target: String,
+  /// RFC 3339 timestamp of when the binary was compiled. This is omitted
+  /// when `SOURCE_DATE_EPOCH` is set for reproducible builds.
+  pub compiled_at: Option<String>,
+  /// Whether the `--deno-metadata` argument is handled by the binary rather
+  /// than being passed to the user script.
+  pub metadata_flag: bool,
+  pub exit_on_idle_timeout: Option<Duration>,
+  pub report_unused_permissions: Option<UnusedPermissionsFormat>,
+  /// Whether the eszip was compressed with zstd by `deno compile --strip`.
+  pub compressed: bool,
 }
 
 pub const MAGIC_TRAILER: &[u8; 8] = b"d3n0l4nd";
 
+/// Argument that makes a compiled binary print its metadata and exit.
+pub const METADATA_FLAG: &str = "--deno-metadata";
+
 /// This function will try to run this binary as a standalone binary
 /// produced by `deno compile`. It determines if this is a standalone
 /// binary by checking for the magic trailer string `D3N0` at EOF-12.
@@ -77,8 +102,7 @@ pub async fn extract_standalone(
 
   let file = std::fs::File::open(current_exe_path)?;
 
-  let mut bufreader =
-    deno_core::futures::io::BufReader::new(AllowStdIo::new(file));
+  let mut bufreader = BufReader::new(AllowStdIo::new(file));
 
   let trailer_pos = bufreader.seek(SeekFrom::End(-24)).await?;
   let mut trailer = [0; 24];
@@ -94,19 +118,11 @@ pub async fn extract_standalone(
   let metadata_pos = u64_from_bytes(metadata_pos)?;
   let metadata_len = trailer_pos - metadata_pos;
 
-  bufreader.seek(SeekFrom::Start(eszip_archive_pos)).await?;
-
-  let (eszip, loader) = eszip::EszipV2::parse(bufreader)
-    .await
-    .context("Failed to parse eszip header")?;
-
-  let mut bufreader = loader.await.context("Failed to parse eszip archive")?;
-
   bufreader.seek(SeekFrom::Start(metadata_pos)).await?;
 
   let mut metadata = String::new();
 
-  bufreader
+  (&mut bufreader)
     .take(metadata_len)
     .read_to_string(&mut metadata)
     .await
@@ -115,9 +131,38 @@ pub async fn extract_standalone(
   let mut metadata: Metadata = serde_json::from_str(&metadata).unwrap();
   metadata.argv.append(&mut args[1..].to_vec());
 
+  bufreader.seek(SeekFrom::Start(eszip_archive_pos)).await?;
+
+  let eszip = if metadata.compressed {
+    let mut compressed = Vec::new();
+    bufreader
+      .take(metadata_pos - eszip_archive_pos)
+      .read_to_end(&mut compressed)
+      .await
+      .context("Failed to read eszip archive")?;
+    let archive = zstd::stream::decode_all(compressed.as_slice())
+      .context("Failed to decompress eszip archive")?;
+    parse_eszip(BufReader::new(AllowStdIo::new(std::io::Cursor::new(
+      archive,
+    ))))
+    .await?
+  } else {
+    parse_eszip(bufreader).await?
+  };
+
   Ok(Some((metadata, eszip)))
 }
 
+async fn parse_eszip<R: AsyncRead + Unpin>(
+  reader: BufReader<R>,
+) -> Result<eszip::EszipV2, AnyError> {
+  let (eszip, loader) = eszip::EszipV2::parse(reader)
+    .await
+    .context("Failed to parse eszip header")?;
+  loader.await.context("Failed to parse eszip archive")?;
+  Ok(eszip)
+}
+
 fn u64_from_bytes(arr: &[u8]) -> Result<u64, AnyError> {
   let fixed_arr: &[u8; 8] = arr
     .try_into()
@@ -215,6 +260,9 @@ fn metadata_to_flags(metadata: &Metadata) -> Flags {
     log_level: metadata.log_level,
     ca_stores: metadata.ca_stores.clone(),
     ca_data: metadata.ca_data.clone().map(CaData::Bytes),
+    user_agent: metadata.user_agent.clone(),
+    exit_on_idle_timeout: metadata.exit_on_idle_timeout,
+    report_unused_permissions: metadata.report_unused_permissions,
     ..Default::default()
   }
 }
@@ -222,7 +270,17 @@ fn metadata_to_flags(metadata: &Metadata) -> Flags {
 pub async fn run(
   eszip: eszip::EszipV2,
   metadata: Metadata,
-) -> Result<(), AnyError> {
+) -> Result<i32, AnyError> {
+  let args = std::env::args().collect::<Vec<_>>();
+  if metadata.metadata_flag
+    && args.get(1).map(|a| a.as_str()) == Some(METADATA_FLAG)
+  {
+    // the arguments passed to the binary were appended to the embedded ones
+    let embedded_args_len = metadata.argv.len() - (args.len() - 1);
+    print_metadata(&eszip, &metadata, &metadata.argv[..embedded_args_len])?;
+    return Ok(0);
+  }
+
   let flags = metadata_to_flags(&metadata);
   let main_module = &metadata.entrypoint;
   let ps = ProcState::build(flags).await?;
@@ -251,6 +309,10 @@ pub async fn run(
   v8_set_flags(construct_v8_flags(&metadata.v8_flags, vec![]));
 
   let root_cert_store = ps.root_cert_store.clone();
+  let mut extensions = ops::cli_exts(ps.clone());
+  if let Some(timeout) = metadata.exit_on_idle_timeout {
+    extensions.push(ops::idle_timeout::init(timeout));
+  }
 
   let options = WorkerOptions {
     bootstrap: BootstrapOptions {
@@ -267,10 +329,10 @@ pub async fn run(
       runtime_version: version::deno(),
       ts_version: version::TYPESCRIPT.to_string(),
       unstable: metadata.unstable,
-      user_agent: version::get_user_agent(),
+      user_agent: ps.user_agent.clone(),
       inspect: ps.options.is_inspecting(),
     },
-    extensions: ops::cli_exts(ps),
+    extensions,
     extensions_with_js: vec![],
     startup_snapshot: Some(crate::js::deno_isolate_init()),
     unsafely_ignore_certificate_errors: metadata
@@ -290,6 +352,7 @@ pub async fn run(
     get_error_class_fn: Some(&get_error_class_name),
     cache_storage_dir: None,
     origin_storage_dir: None,
+    webstorage_options: Default::default(),
     blob_store,
     broadcast_channel,
     shared_array_buffer_store: None,
@@ -298,9 +361,18 @@ pub async fn run(
   };
   let mut worker = MainWorker::bootstrap_from_options(
     main_module.clone(),
-    permissions,
+    permissions.clone(),
     options,
   );
+  let op_names = worker.js_runtime.op_names();
+  ops::idle_timeout::set_op_names(
+    &mut worker.js_runtime.op_state().borrow_mut(),
+    op_names,
+  );
+  for specifier in &metadata.preload_modules {
+    let id = worker.preload_side_module(specifier).await?;
+    worker.evaluate_module(id).await?;
+  }
   worker.execute_main_module(main_module).await?;
   worker.dispatch_load_event(&located_script_name!())?;
 
@@ -312,7 +384,33 @@ pub async fn run(
   }
 
   worker.dispatch_unload_event(&located_script_name!())?;
-  std::process::exit(0);
+  if let Some(format) = metadata.report_unused_permissions {
+    display::print_unused_permissions(&permissions.0.lock(), format);
+  }
+  Ok(worker.exit_code())
+}
+
+fn print_metadata(
+  eszip: &eszip::EszipV2,
+  metadata: &Metadata,
+  embedded_args: &[String],
+) -> Result<(), AnyError> {
+  let mut assets = eszip.specifiers();
+  assets.sort();
+  display::write_json_to_stdout(&json!({
+    "denoVersion": metadata.deno_version,
+    "target": metadata.target,
+    "mainModule": metadata.entrypoint,
+    "preloadModules": metadata.preload_modules,
+    "args": embedded_args,
+    "unstable": metadata.unstable,
+    "seed": metadata.seed,
+    "location": metadata.location,
+    "v8Flags": metadata.v8_flags,
+    "permissions": metadata.permissions,
+    "compiledAt": metadata.compiled_at,
+    "assets": assets,
+  }))
 }
 
 fn get_error_class_name(e: &AnyError) -> &'static str {
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use deno_core::url::Url;
 use test_util as util;
 
@@ -21,6 +22,24 @@ itest!(pass {
   output: "bench/pass.out",
 });
 
+itest!(report_memory {
+  args: "bench --report-memory bench/pass.ts",
+  exit_code: 0,
+  output: "bench/report_memory.out",
+});
+
+itest!(profile_loop {
+  args: "bench --filter=bench1 --profile-loop=1500ms bench/pass.ts",
+  exit_code: 0,
+  output: "bench/profile_loop.out",
+});
+
+itest!(profile_loop_multiple {
+  args: "bench --filter=bench --profile-loop bench/pass.ts",
+  exit_code: 1,
+  output: "bench/profile_loop_multiple.out",
+});
+
 itest!(ignore {
   args: "bench bench/ignore.ts",
   exit_code: 0,
@@ -117,6 +136,24 @@ itest!(group_baseline {
   output: "bench/group_baseline.out",
 });
 
+itest!(group_across_files {
+  args: "bench bench/group_across_files/a_bench.ts bench/group_across_files/b_bench.ts",
+  exit_code: 0,
+  output: "bench/group_across_files.out",
+});
+
+itest!(parallel_jobs {
+  args: "bench --jobs=2 bench/pass.ts bench/group_baseline.ts",
+  exit_code: 0,
+  output: "bench/parallel_jobs.out",
+});
+
+itest!(parallel_warmup_only {
+  args: "bench --jobs=2 --warmup-only-parallel bench/pass.ts bench/group_baseline.ts",
+  exit_code: 0,
+  output: "bench/parallel_warmup_only.out",
+});
+
 itest!(unresolved_promise {
   args: "bench bench/unresolved_promise.ts",
   exit_code: 1,
@@ -178,6 +215,49 @@ itest!(bench_with_malformed_config {
   output: "bench/collect_with_malformed_config.out",
 });
 
+itest!(baseline_regressed {
+  args: "bench --baseline=bench/baseline_fast.json bench/baseline.ts",
+  exit_code: 1,
+  output: "bench/baseline_regressed.out",
+});
+
+itest!(baseline_improved {
+  args: "bench --baseline=bench/baseline_slow.json --threshold=10% bench/baseline.ts",
+  exit_code: 0,
+  output_str: Some("[WILDCARD]noop [WILDCARD]%\n[WILDCARD]"),
+});
+
+itest!(baseline_strict {
+  args: "bench --baseline=bench/baseline_slow.json --strict bench/baseline.ts",
+  exit_code: 1,
+  output: "bench/baseline_strict.out",
+});
+
+#[test]
+fn save_baseline() {
+  let temp_dir = util::TempDir::new();
+  let baseline_path = temp_dir.path().join("baseline.json");
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bench")
+    .arg(format!("--save-baseline={}", baseline_path.display()))
+    .arg("bench/baseline.ts")
+    .stdout(std::process::Stdio::null())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let baseline: serde_json::Value =
+    serde_json::from_str(&std::fs::read_to_string(&baseline_path).unwrap())
+      .unwrap();
+  let benches = baseline["benches"].as_array().unwrap();
+  assert_eq!(benches.len(), 2);
+  assert_eq!(benches[0]["origin"], "bench/baseline.ts");
+  assert_eq!(benches[0]["name"], "noop");
+  assert!(benches[0]["mean"].as_f64().unwrap() > 0.0);
+}
+
 #[test]
 fn recursive_permissions_pledge() {
   let output = util::deno_cmd()
@@ -470,3 +470,79 @@ itest!(bundle_shebang_file {
   args: "bundle subdir/shebang_file.js",
   output: "bundle/shebang_file.bundle.out",
 });
+
+fn bundle_error_with_sourcemap(sourcemap: &str) -> (TempDir, String) {
+  let error = util::testdata_path().join("bundle/sourcemap/error.ts");
+  let t = TempDir::new();
+  let bundle = t.path().join("error.bundle.js");
+  let status = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("bundle")
+    .arg(format!("--sourcemap={sourcemap}"))
+    .arg(error)
+    .arg(&bundle)
+    .spawn()
+    .unwrap()
+    .wait()
+    .unwrap();
+  assert!(status.success());
+  let code = std::fs::read_to_string(&bundle).unwrap();
+  (t, code)
+}
+
+#[test]
+fn bundle_sourcemap_external() {
+  let (t, code) = bundle_error_with_sourcemap("external");
+  assert_ends_with!(
+    code.as_str(),
+    "//# sourceMappingURL=error.bundle.js.map\n"
+  );
+  assert!(t.path().join("error.bundle.js.map").is_file());
+
+  // errors are mapped with the source map next to the bundle
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg(t.path().join("error.bundle.js"))
+    .output()
+    .unwrap();
+  assert!(!output.status.success());
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  assert_contains!(stderr, "error: Uncaught Error: boom");
+  assert_contains!(stderr, "bundle/sourcemap/error.ts:3:");
+
+  // and the location of the bundle is reported without it
+  std::fs::remove_file(t.path().join("error.bundle.js.map")).unwrap();
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg(t.path().join("error.bundle.js"))
+    .output()
+    .unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  assert_contains!(stderr, "error: Uncaught Error: boom");
+  assert_contains!(stderr, "error.bundle.js:");
+}
+
+#[test]
+fn bundle_sourcemap_inline() {
+  let (t, code) = bundle_error_with_sourcemap("inline");
+  assert_contains!(code, "//# sourceMappingURL=data:application/json;base64,");
+  assert!(!t.path().join("error.bundle.js.map").exists());
+
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg(t.path().join("error.bundle.js"))
+    .output()
+    .unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  assert_contains!(stderr, "bundle/sourcemap/error.ts:3:");
+}
+
+#[test]
+fn bundle_sourcemap_none() {
+  let (t, code) = bundle_error_with_sourcemap("none");
+  assert!(!code.contains("sourceMappingURL"));
+  assert!(!t.path().join("error.bundle.js.map").exists());
+}
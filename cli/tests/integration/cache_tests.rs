@@ -43,9 +43,21 @@ itest!(redirect_cache {
   output: "cache/redirect_cache.out",
 });
 
+itest!(import_list {
+  args: "cache --import-list=cache/import_list/entrypoints.txt",
+  output: "cache/import_list.out",
+  exit_code: 1,
+});
+
+itest!(import_list_stdin {
+  args: "cache --import-list=-",
+  input: Some("cache/import_list/a.ts\ncache/import_list/b.ts\n"),
+  output_str: Some("[WILDCARD]Cached 3 modules[WILDCARD]"),
+});
+
 itest!(ignore_require {
   args: "cache --reload --no-check cache/ignore_require.js",
-  output_str: Some(""),
+  output_str: Some("Cached [WILDCARD]"),
   exit_code: 0,
 });
 
@@ -94,4 +106,210 @@ itest!(check_local_by_default2 {
 itest!(json_import {
   // should not error
   args: "cache --quiet cache/json_import/main.ts",
+  output_str: Some("Cached [WILDCARD]"),
+});
+
+itest!(cache_json {
+  args: "cache --quiet --json run/002_hello.ts",
+  output: "cache/cache_json.out",
 });
+
+itest!(cache_json_remote {
+  args: "cache --json --reload http://localhost:4545/run/002_hello.ts",
+  output: "cache/cache_json_remote.out",
+  http_server: true,
+});
+
+itest!(cache_json_error {
+  args: "cache --json cache/json_error/missing.ts",
+  output: "cache/cache_json_error.out",
+  exit_code: 1,
+});
+
+#[tokio::test]
+async fn cache_reuses_h2_connection() {
+  use deno_runtime::deno_fetch::reqwest;
+  use std::time::Instant;
+  use test_util as util;
+
+  async fn get_count(url: &str) -> usize {
+    reqwest::get(url)
+      .await
+      .unwrap()
+      .text()
+      .await
+      .unwrap()
+      .parse()
+      .unwrap()
+  }
+
+  // only the connections used for the files of this test are counted, as
+  // other tests may use the server at the same time
+  async fn h2_connection_count() -> usize {
+    get_count(
+      "http://localhost:4545/h2_connection_count?/cache/fetch_concurrency/",
+    )
+    .await
+  }
+
+  const FETCH_CONCURRENCY: u32 = 4;
+  const MODULES: u32 = 25;
+
+  let _g = util::http_server();
+  let count_before = h2_connection_count().await;
+  // reset the count of a previous run
+  get_count("http://localhost:4545/fetch_concurrency_max_in_flight").await;
+  let start = Instant::now();
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .env("NO_COLOR", "1")
+    .env("DENO_FETCH_CONCURRENCY", FETCH_CONCURRENCY.to_string())
+    .arg("cache")
+    .arg("--reload")
+    .arg("--no-check")
+    .arg("--cert")
+    .arg("tls/RootCA.pem")
+    .arg("https://localhost:5547/cache/fetch_concurrency/mod.ts")
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  let elapsed = start.elapsed();
+  assert!(output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("Cached 25 modules (25 downloaded, 0 from cache)"));
+
+  // the 24 dependencies are discovered at once, so the limit is reached but
+  // not exceeded
+  let max_in_flight =
+    get_count("http://localhost:4545/fetch_concurrency_max_in_flight").await;
+  assert_eq!(
+    max_in_flight, FETCH_CONCURRENCY as usize,
+    "expected {FETCH_CONCURRENCY} concurrent downloads"
+  );
+
+  // the server delays each module, so downloading them one after the other
+  // would take at least the delay for each of them, and with the limit at
+  // least the delay for each batch
+  let sequential = util::FETCH_CONCURRENCY_DELAY * MODULES;
+  let batches = 1 + (MODULES - 1 + FETCH_CONCURRENCY - 1) / FETCH_CONCURRENCY;
+  let limited = util::FETCH_CONCURRENCY_DELAY * batches;
+  assert!(
+    elapsed < sequential,
+    "expected the modules to be downloaded concurrently, but it took {elapsed:?}"
+  );
+  assert!(
+    elapsed >= limited,
+    "expected at most {FETCH_CONCURRENCY} concurrent downloads, but it took {elapsed:?}"
+  );
+
+  let connections = h2_connection_count().await - count_before;
+  assert_eq!(connections, 1, "expected the connection to be reused");
+}
+
+#[test]
+fn cache_export_import_bundle() {
+  use std::process::Stdio;
+  use test_util as util;
+
+  let _g = util::http_server();
+  let temp_dir = util::TempDir::new();
+  let bundle_dir = temp_dir.path().join("bundle");
+  let specifier = "http://localhost:4545/run/002_hello.ts";
+  let output = util::deno_cmd()
+    .env("NO_COLOR", "1")
+    .arg("cache")
+    .arg("--export")
+    .arg(&bundle_dir)
+    .arg(specifier)
+    .stderr(Stdio::piped())
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("Exported 1 cache entry to "));
+
+  let deno_dir = util::new_deno_dir();
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .env("NO_COLOR", "1")
+    .arg("cache")
+    .arg("--import")
+    .arg(&bundle_dir)
+    .stderr(Stdio::piped())
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("Imported 1 cache entry from "));
+  assert!(stderr.contains("(0 already cached)"));
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .arg("run")
+    .arg("--cached-only")
+    .arg(specifier)
+    .stdout(Stdio::piped())
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, b"Hello World\n");
+
+  // importing again leaves the cache untouched
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .env("NO_COLOR", "1")
+    .arg("cache")
+    .arg("--import")
+    .arg(&bundle_dir)
+    .stderr(Stdio::piped())
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains("Imported 0 cache entries from "));
+  assert!(stderr.contains("(1 already cached)"));
+}
+
+#[test]
+fn cache_import_bundle_invalid_npm_package_name() {
+  use std::process::Stdio;
+  use test_util as util;
+
+  // the folder of the package would be `victim/1.0.0` in the temp dir
+  let temp_dir = util::TempDir::new();
+  temp_dir.create_dir_all("deno_dir");
+  temp_dir.create_dir_all("victim/1.0.0");
+  temp_dir.write("victim/1.0.0/file.txt", "");
+  temp_dir.create_dir_all("bundle/npm/registry.npmjs.org/x/1.0.0");
+  temp_dir.write("bundle/npm/registry.npmjs.org/x/1.0.0/package.json", "{}");
+  temp_dir.write(
+    "bundle/manifest.json",
+    r#"{
+  "version": 1,
+  "entries": [{
+    "kind": "npmPackage",
+    "name": "../../../victim@1.0.0",
+    "files": {
+      "npm/registry.npmjs.org/x/1.0.0/package.json": "abc"
+    }
+  }]
+}"#,
+  );
+
+  let output = util::deno_cmd()
+    .env("NO_COLOR", "1")
+    .env("DENO_DIR", temp_dir.path().join("deno_dir"))
+    .current_dir(temp_dir.path())
+    .arg("cache")
+    .arg("--import")
+    .arg("bundle")
+    .arg("--overwrite")
+    .stderr(Stdio::piped())
+    .output()
+    .unwrap();
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains(
+    "Invalid npm package in the manifest of the cache bundle: ../../../victim@1.0.0"
+  ));
+  assert!(temp_dir.path().join("victim/1.0.0/file.txt").exists());
+}
@@ -72,6 +72,31 @@ itest!(localhost_unsafe_ssl {
     exit_code: 1,
   });
 
+itest_flaky!(client_cert_url_imports {
+    args: "run --quiet --reload --cert tls/RootCA.pem --tls-client-cert tls/localhost.crt --tls-client-key tls/localhost.key cert/client_cert_url_imports.ts",
+    output: "cert/cafile_url_imports.ts.out",
+    http_server: true,
+  });
+
+itest_flaky!(client_cert_url_imports_scoped_host {
+    args: "run --quiet --reload --cert tls/RootCA.pem --tls-client-cert tls/localhost.crt --tls-client-key tls/localhost.key --tls-client-cert-hosts=localhost:5552 cert/client_cert_url_imports.ts",
+    output: "cert/cafile_url_imports.ts.out",
+    http_server: true,
+  });
+
+itest_flaky!(client_cert_url_imports_other_host {
+    args: "run --quiet --reload --cert tls/RootCA.pem --tls-client-cert tls/localhost.crt --tls-client-key tls/localhost.key --tls-client-cert-hosts=deno.land cert/client_cert_url_imports.ts",
+    output: "cert/client_cert_other_host.out",
+    http_server: true,
+    exit_code: 1,
+  });
+
+itest!(client_cert_encrypted_key {
+    args: "run --quiet --reload --tls-client-cert tls/localhost.crt --tls-client-key tls/localhost_encrypted.key cert/client_cert_url_imports.ts",
+    output: "cert/client_cert_encrypted_key.out",
+    exit_code: 1,
+  });
+
 #[flaky_test::flaky_test]
 fn cafile_env_fetch() {
   use deno_core::url::Url;
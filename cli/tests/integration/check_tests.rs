@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use std::process::Command;
 use std::process::Stdio;
 use test_util as util;
@@ -23,6 +24,30 @@ itest!(check_random_extension {
   http_server: true,
 });
 
+itest!(check_doc_jsdoc {
+  args: "check --quiet --doc check/doc/mod.ts",
+  output: "check/doc/mod.out",
+  exit_code: 1,
+});
+
+itest!(check_doc_markdown {
+  args: "check --quiet --doc check/doc/README.md",
+  output: "check/doc/readme.out",
+  exit_code: 1,
+});
+
+itest!(check_deprecations {
+  args: "check --quiet --deprecations check/deprecations/main.ts",
+  output: "check/deprecations/main.out",
+  exit_code: 1,
+});
+
+itest!(check_deprecations_json {
+  args: "check --quiet --deprecations --json check/deprecations/main.ts",
+  output: "check/deprecations/main_json.out",
+  exit_code: 1,
+});
+
 itest!(check_all {
   args: "check --quiet --all check/check_all.ts",
   output: "check/check_all.out",
@@ -36,6 +61,19 @@ itest!(check_all_local {
   http_server: true,
 });
 
+// the worker is type checked with the worker libraries and the main module
+// with the window ones, while both import the same module
+itest!(check_lib_overrides {
+  args: "check --quiet --config check/lib_overrides/deno.json check/lib_overrides/main.ts check/lib_overrides/workers/worker.ts",
+  output_str: Some(""),
+});
+
+itest!(check_lib_overrides_error {
+  args: "check --quiet --config check/lib_overrides/deno.json check/lib_overrides/main.ts check/lib_overrides/workers/uses_window.ts",
+  output: "check/lib_overrides/uses_window.out",
+  exit_code: 1,
+});
+
 itest!(module_detection_force {
   args: "check --quiet check/module_detection_force/main.ts",
   output_str: Some(""),
@@ -229,3 +267,47 @@ fn ts_no_recheck_on_redirect() {
 
   assert!(std::str::from_utf8(&output.stderr).unwrap().is_empty());
 }
+
+#[test]
+fn check_emit_dir() {
+  let temp_dir = TempDir::new();
+  temp_dir.write(
+    "src/main.ts",
+    "import { add } from \"./math/add.ts\";\nconsole.log(add(1, 2));\n",
+  );
+  temp_dir.write(
+    "src/math/add.ts",
+    "export function add(a: number, b: number): number {\n  return a + b;\n}\n",
+  );
+  let output = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("check")
+    .arg("--emit-dir=dist")
+    .arg("src/main.ts")
+    .stderr(Stdio::piped())
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+
+  let main_js = temp_dir.read_to_string("dist/main.js");
+  assert!(main_js.contains("from \"./math/add.js\""));
+  let add_js = temp_dir.read_to_string("dist/math/add.js");
+  assert!(add_js.contains("export function add(a, b)"));
+  let manifest: serde_json::Value =
+    serde_json::from_str(&temp_dir.read_to_string("dist/manifest.json"))
+      .unwrap();
+  assert_eq!(manifest["modules"]["main.ts"]["output"], "main.js");
+  assert_eq!(manifest["modules"]["math/add.ts"]["output"], "math/add.js");
+
+  // the output is the same when emitting again
+  let output = util::deno_cmd()
+    .current_dir(temp_dir.path())
+    .arg("check")
+    .arg("--emit-dir=dist")
+    .arg("src/main.ts")
+    .stderr(Stdio::piped())
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(temp_dir.read_to_string("dist/main.js"), main_js);
+}
@@ -0,0 +1,23 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+itest!(ci_clean {
+  args: "ci ci/clean/",
+  output: "ci/clean.out",
+});
+
+itest!(ci_project {
+  args: "ci ci/project/",
+  output: "ci/project.out",
+  exit_code: 1,
+});
+
+itest!(ci_project_json {
+  args: "ci --no-check --json ci/project/",
+  output: "ci/project_json.out",
+  exit_code: 1,
+});
+
+itest!(ci_no_fmt_no_lint {
+  args: "ci --no-fmt --no-lint --no-check ci/project/",
+  output_str: Some("Checked 2 files\n"),
+});
@@ -1,5 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
+use deno_core::serde_json::json;
 use std::fs::File;
 use std::process::Command;
 use test_util as util;
@@ -39,6 +41,138 @@ fn compile() {
   }
 }
 
+#[test]
+fn standalone_strip() {
+  let denort = util::denort_exe_path();
+  assert!(
+    denort.exists(),
+    "{} is missing, build it with `cargo build -p deno --no-default-features --features napi,npm --target-dir target/denort` and copy it there",
+    denort.display()
+  );
+  let dir = TempDir::new();
+  let compile = |name: &str, strip: bool| {
+    let exe = if cfg!(windows) {
+      dir.path().join(format!("{name}.exe"))
+    } else {
+      dir.path().join(name)
+    };
+    let mut cmd = util::deno_cmd_with_deno_dir(&dir);
+    cmd
+      .current_dir(util::root_path())
+      .env("DENORT_BIN", &denort)
+      .arg("compile");
+    if strip {
+      cmd.arg("--strip");
+    }
+    let output = cmd
+      .arg("--output")
+      .arg(&exe)
+      .arg("./test_util/std/examples/welcome.ts")
+      .stderr(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.contains("Strip executable from "), strip);
+    let output = Command::new(&exe)
+      .stdout(std::process::Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, "Welcome to Deno!\n".as_bytes());
+    std::fs::metadata(&exe).unwrap().len()
+  };
+  let size = compile("welcome", false);
+  let stripped_size = compile("welcome_stripped", true);
+  // leaving out the TypeScript compiler snapshot and the type definitions
+  // saves well over 5MB
+  const SIZE_BUDGET: u64 = 5 * 1024 * 1024;
+  assert!(
+    stripped_size + SIZE_BUDGET <= size,
+    "stripped executable is {stripped_size} bytes, unstripped is {size} bytes"
+  );
+  // apart from the runtime, only the program and its metadata are embedded
+  const TOLERANCE: u64 = 64 * 1024;
+  let denort_size = std::fs::metadata(&denort).unwrap().len();
+  assert!(
+    stripped_size <= denort_size + TOLERANCE,
+    "stripped executable is {} bytes larger than the runtime",
+    stripped_size - denort_size
+  );
+}
+
+#[test]
+fn standalone_strip_webgpu() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("webgpu.exe")
+  } else {
+    dir.path().join("webgpu")
+  };
+  let output = util::deno_cmd_with_deno_dir(&dir)
+    .current_dir(util::testdata_path())
+    .env("DENORT_BIN", util::denort_exe_path())
+    .arg("compile")
+    .arg("--unstable")
+    .arg("--strip")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./compile/strip_webgpu.ts")
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains(
+    "The program uses capabilities which aren't included with --strip"
+  ));
+  assert!(stderr.contains("The WebGPU API isn't included"));
+  assert!(!exe.exists());
+}
+
+#[test]
+fn standalone_preload() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("preload.exe")
+  } else {
+    dir.path().join("preload")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--unstable")
+    .arg("--import-map=./run/preload/import_map.json")
+    .arg("--preload=./run/preload/first.ts")
+    .arg("--preload=instrument")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./run/preload/main.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let output = Command::new(&exe)
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(
+    output.stdout,
+    b"first preload\nsecond preload\nmain [ \"first\", \"second\" ]\n"
+  );
+}
+
 #[test]
 fn standalone_args() {
   let dir = TempDir::new();
@@ -75,6 +209,109 @@ fn standalone_args() {
   assert_eq!(output.stdout, b"a\nb\nfoo\n--bar\n--unstable\n");
 }
 
+#[test]
+fn standalone_exit_code() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("set_exit_code.exe")
+  } else {
+    dir.path().join("set_exit_code")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--unstable")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./run/set_exit_code_2.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let output = Command::new(exe)
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert_eq!(output.status.code(), Some(42));
+}
+
+#[test]
+fn standalone_metadata_flag() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("args.exe")
+  } else {
+    dir.path().join("args")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .env("SOURCE_DATE_EPOCH", "0")
+    .arg("compile")
+    .arg("--unstable")
+    .arg("--allow-read")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./compile/args.ts")
+    .arg("a")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let output = Command::new(&exe)
+    .arg("--deno-metadata")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let metadata: serde_json::Value =
+    serde_json::from_slice(&output.stdout).unwrap();
+  assert!(metadata["denoVersion"]
+    .as_str()
+    .unwrap()
+    .starts_with(env!("CARGO_PKG_VERSION")));
+  assert_eq!(metadata["args"], json!(["a"]));
+  assert_eq!(metadata["unstable"], true);
+  assert!(metadata["compiledAt"].is_null());
+  assert!(metadata["mainModule"]
+    .as_str()
+    .unwrap()
+    .ends_with("/compile/args.ts"));
+  assert_eq!(metadata["assets"], json!([metadata["mainModule"]]));
+
+  // opting out passes the argument through to the script
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--unstable")
+    .arg("--no-metadata-flag")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./compile/args.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let output = Command::new(exe)
+    .arg("--deno-metadata")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, b"--deno-metadata\n");
+}
+
 #[test]
 fn standalone_error() {
   let dir = TempDir::new();
@@ -564,3 +801,50 @@ fn check_local_by_default2() {
     r#"error: TS2322 [ERROR]: Type '12' is not assignable to type '"b"'."#
   ));
 }
+
+#[test]
+fn compile_warns_about_unsupported_apis() {
+  let dir = TempDir::new();
+  let exe = if cfg!(windows) {
+    dir.path().join("unsupported_apis.exe")
+  } else {
+    dir.path().join("unsupported_apis")
+  };
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--output")
+    .arg(&exe)
+    .arg("./compile/unsupported_apis.ts")
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains(
+    "(dynamic-import) The module imported can't be known at compile time, so it isn't embedded in the executable."
+  ));
+  assert!(stderr.contains("compile/unsupported_apis.ts:2:7"));
+  assert!(exe.exists());
+
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("compile")
+    .arg("--deny-warnings")
+    .arg("--output")
+    .arg(dir.path().join("denied"))
+    .arg("./compile/unsupported_apis.ts")
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(!output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(stderr.contains(
+    "Found 1 use of code that doesn't work in compiled executables."
+  ));
+  assert!(!dir.path().join("denied").exists());
+}
@@ -1,5 +1,8 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::url::Url;
 use std::fs;
 use test_util as util;
 use test_util::TempDir;
@@ -85,6 +88,48 @@ fn error_if_invalid_cache() {
   assert!(error.contains("Before generating coverage report, run `deno test --coverage` to ensure consistent state."));
 }
 
+#[test]
+fn unusual_paths() {
+  let deno_dir = TempDir::new();
+  let tempdir = TempDir::new();
+  let tempdir = tempdir.path().join("cov");
+
+  let status = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--quiet")
+    .arg(format!("--coverage={}", tempdir.to_str().unwrap()))
+    .arg("unusual_paths/")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .status()
+    .unwrap();
+  assert!(status.success());
+
+  // the pattern is matched against the decoded file name
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--include=dir #1/mod 100%")
+    .arg(format!("{}/", tempdir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+  assert!(output.stderr.is_empty());
+  assert!(output.status.success());
+  let actual =
+    util::strip_ansi_codes(std::str::from_utf8(&output.stdout).unwrap())
+      .to_string();
+  assert!(
+    util::wildcard_match(
+      "cover [WILDCARD]/dir%20%231/mod%20100%25.ts ... 100.000% (3/3)\n",
+      &actual
+    ),
+    "{actual}"
+  );
+}
+
 fn run_coverage_text(test_name: &str, extension: &str) {
   let deno_dir = TempDir::new();
   let tempdir = TempDir::new();
@@ -245,6 +290,75 @@ fn multifile_coverage() {
   assert!(output.status.success());
 }
 
+#[test]
+fn test_run_metadata() {
+  let deno_dir = TempDir::new();
+  let tempdir = TempDir::new();
+  let tempdir = tempdir.path().join("cov");
+
+  let status = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--quiet")
+    .arg(format!("--coverage={}", tempdir.to_str().unwrap()))
+    .arg("coverage/multifile/")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::inherit())
+    .status()
+    .unwrap();
+  assert!(status.success());
+
+  let metadata: serde_json::Value = serde_json::from_str(
+    &fs::read_to_string(tempdir.join("test_run_metadata.json")).unwrap(),
+  )
+  .unwrap();
+  assert_eq!(metadata["version"], 1);
+  let modules = metadata["modules"].as_array().unwrap();
+  assert_eq!(modules.len(), 2);
+  let mod_url = Url::from_file_path(
+    util::testdata_path().join("coverage/multifile/mod.js"),
+  )
+  .unwrap();
+  for (module, (file_name, test_name)) in modules
+    .iter()
+    .zip([("a_test.js", "bugrepo a"), ("b_test.js", "bugrepo b")])
+  {
+    let url = Url::from_file_path(
+      util::testdata_path()
+        .join("coverage/multifile")
+        .join(file_name),
+    )
+    .unwrap();
+    assert_eq!(module["specifier"], url.as_str());
+    assert_eq!(module["passed"], true);
+    assert!(module["duration"].is_u64());
+    let tests = module["tests"].as_array().unwrap();
+    assert_eq!(tests.len(), 1);
+    assert_eq!(tests[0]["name"], test_name);
+    assert_eq!(tests[0]["result"], "ok");
+    assert!(tests[0]["duration"].is_u64());
+    assert_eq!(
+      module["coveredScripts"],
+      json!([url.as_str(), mod_url.as_str()])
+    );
+  }
+
+  // the metadata isn't mistaken for a coverage profile
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(util::testdata_path())
+    .arg("coverage")
+    .arg("--quiet")
+    .arg("--lcov")
+    .arg(format!("{}/", tempdir.to_str().unwrap()))
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  assert!(output.status.success(), "{stderr}");
+  assert!(stderr.is_empty(), "{stderr}");
+}
+
 fn no_snaps_included(test_name: &str, extension: &str) {
   let deno_dir = TempDir::new();
   let tempdir = TempDir::new();
@@ -373,3 +487,83 @@ fn no_transpiled_lines() {
 
   assert!(output.status.success());
 }
+
+#[test]
+fn node_v8_coverage() {
+  let deno_dir = TempDir::new();
+  let tempdir = TempDir::new();
+  let source = concat!(
+    "export function add(a, b) {\n  return a + b;\n}\n\n",
+    "export function sub(a, b) {\n  return a - b;\n}\n",
+  );
+  let source_path = tempdir.path().join("math.js");
+  fs::write(&source_path, source).unwrap();
+  let add_start = source.find("function add").unwrap();
+  let add_end = source.find("}\n").unwrap() + 1;
+  let sub_start = source.find("function sub").unwrap();
+  let sub_end = source.len() - 1;
+  let url = Url::from_file_path(&source_path).unwrap();
+  let coverage = json!({
+    "result": [
+      {
+        "scriptId": "1",
+        "url": "node:internal/bootstrap/node",
+        "functions": [],
+      },
+      {
+        "scriptId": "2",
+        "url": url.to_string(),
+        "functions": [
+          {
+            "functionName": "",
+            "ranges": [
+              { "startOffset": 0, "endOffset": source.len(), "count": 1 }
+            ],
+            "isBlockCoverage": false,
+          },
+          {
+            "functionName": "add",
+            "ranges": [
+              { "startOffset": add_start, "endOffset": add_end, "count": 1 }
+            ],
+            "isBlockCoverage": false,
+          },
+          {
+            "functionName": "sub",
+            "ranges": [
+              { "startOffset": sub_start, "endOffset": sub_end, "count": 0 }
+            ],
+            "isBlockCoverage": false,
+          },
+        ],
+      },
+    ],
+    "timestamp": 1.0,
+  });
+  let coverage_dir = tempdir.path().join("cov");
+  fs::create_dir(&coverage_dir).unwrap();
+  fs::write(
+    coverage_dir.join("coverage-1-1-0.json"),
+    coverage.to_string(),
+  )
+  .unwrap();
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(tempdir.path())
+    .arg("coverage")
+    .arg("--lcov")
+    .arg(&coverage_dir)
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .output()
+    .unwrap();
+
+  let stdout = std::str::from_utf8(&output.stdout).unwrap();
+  let stderr = std::str::from_utf8(&output.stderr).unwrap();
+  assert!(output.status.success(), "{stderr}");
+  assert!(stderr.is_empty(), "{stderr}");
+  assert!(stdout.contains("math.js\n"), "{stdout}");
+  assert!(stdout.contains("FNDA:1,add\n"), "{stdout}");
+  assert!(stdout.contains("FNDA:0,sub\n"), "{stdout}");
+  assert!(!stdout.contains("node:internal"), "{stdout}");
+}
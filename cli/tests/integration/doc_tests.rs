@@ -1,5 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
+use deno_core::serde_json::json;
 use test_util as util;
 use test_util::TempDir;
 use util::assert_contains;
@@ -37,6 +39,11 @@ itest!(deno_doc_import_map {
   output: "doc/use_import_map.out",
 });
 
+itest!(deno_doc_json_ranges {
+  args: "doc --json doc/ranges.ts",
+  output: "doc/ranges.out",
+});
+
 itest!(deno_doc_types_hint {
   args: "doc doc/types_hint.ts",
   output: "doc/types_hint.out",
@@ -70,3 +77,234 @@ itest!(deno_doc_invalid_url {
   output: "doc/invalid_url.out",
   exit_code: 1,
 });
+
+#[test]
+fn deno_doc_json_schema() {
+  let output = util::deno_cmd()
+    .arg("doc")
+    .arg("--json-schema")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let schema =
+    std::fs::read_to_string(util::root_path().join("cli/schemas/doc.v1.json"))
+      .unwrap();
+  assert_eq!(std::str::from_utf8(&output.stdout).unwrap(), schema);
+}
+
+#[test]
+fn deno_doc_json_matches_schema() {
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("doc")
+    .arg("--json")
+    .arg("--private")
+    .arg("doc/schema.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  let schema: serde_json::Value = serde_json::from_str(
+    &std::fs::read_to_string(util::root_path().join("cli/schemas/doc.v1.json"))
+      .unwrap(),
+  )
+  .unwrap();
+  let mut errors = Vec::new();
+  validate_schema(&schema, &schema, &json, "", &mut errors);
+  assert!(errors.is_empty(), "{}", errors.join("\n"));
+
+  // the module has a symbol of each kind
+  let kinds = json["nodes"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|node| node["kind"].as_str().unwrap())
+    .collect::<Vec<_>>();
+  for kind in [
+    "function",
+    "variable",
+    "enum",
+    "class",
+    "typeAlias",
+    "namespace",
+    "interface",
+  ] {
+    assert!(kinds.contains(&kind), "no {kind} in {kinds:?}");
+  }
+}
+
+/// Validates a value against the subset of JSON Schema draft-07 used by the
+/// `deno doc` schema, panicking on any other keyword so the schema can't use
+/// one that isn't checked.
+fn validate_schema(
+  root: &serde_json::Value,
+  schema: &serde_json::Value,
+  value: &serde_json::Value,
+  path: &str,
+  errors: &mut Vec<String>,
+) {
+  let schema = schema.as_object().unwrap();
+  for (keyword, expected) in schema {
+    match keyword.as_str() {
+      "$id" | "$schema" | "title" | "description" | "definitions" => {}
+      "$ref" => {
+        let name = expected
+          .as_str()
+          .unwrap()
+          .strip_prefix("#/definitions/")
+          .unwrap();
+        validate_schema(root, &root["definitions"][name], value, path, errors);
+      }
+      "type" => {
+        let matches = match expected.as_str().unwrap() {
+          "object" => value.is_object(),
+          "array" => value.is_array(),
+          "string" => value.is_string(),
+          "integer" => value.is_u64() || value.is_i64(),
+          "number" => value.is_number(),
+          "boolean" => value.is_boolean(),
+          "null" => value.is_null(),
+          other => panic!("Unknown type in schema: {other}"),
+        };
+        if !matches {
+          errors.push(format!("{path}: expected {expected}, found {value}"));
+        }
+      }
+      "const" => {
+        if value != expected {
+          errors.push(format!("{path}: expected {expected}, found {value}"));
+        }
+      }
+      "enum" => {
+        if !expected.as_array().unwrap().contains(value) {
+          errors.push(format!("{path}: {value} isn't one of {expected}"));
+        }
+      }
+      "required" => {
+        if let Some(object) = value.as_object() {
+          for name in expected.as_array().unwrap() {
+            let name = name.as_str().unwrap();
+            if !object.contains_key(name) {
+              errors.push(format!("{path}: missing property \"{name}\""));
+            }
+          }
+        }
+      }
+      "properties" => {
+        if let Some(object) = value.as_object() {
+          for (name, property) in expected.as_object().unwrap() {
+            if let Some(value) = object.get(name) {
+              let path = format!("{path}/{name}");
+              validate_schema(root, property, value, &path, errors);
+            }
+          }
+        }
+      }
+      "items" => {
+        if let Some(array) = value.as_array() {
+          for (index, item) in array.iter().enumerate() {
+            let path = format!("{path}/{index}");
+            validate_schema(root, expected, item, &path, errors);
+          }
+        }
+      }
+      other => panic!("Unsupported keyword in schema: {other}"),
+    }
+  }
+}
+
+#[test]
+fn deno_doc_show_references() {
+  let _g = util::http_server();
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("doc")
+    .arg("--show-references")
+    .arg("--json")
+    .arg("doc/references/mod.ts")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+  let symbol = |name: &str| {
+    json["symbols"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .find(|symbol| symbol["name"] == name)
+      .unwrap()
+      .clone()
+  };
+
+  // the type parameter and the global types are left out
+  let create = symbol("create");
+  let references = create["references"].as_array().unwrap();
+  assert_eq!(references.len(), 2);
+  assert_eq!(references[0]["name"], "A");
+  assert_eq!(
+    references[0]["specifier"],
+    "http://localhost:4545/subdir/export_types.ts"
+  );
+  assert_eq!(references[0]["origin"], "remote");
+  assert_eq!(references[1]["name"], "Options");
+  assert!(references[1]["specifier"]
+    .as_str()
+    .unwrap()
+    .ends_with("/doc/references/types.ts"));
+  assert_eq!(references[1]["origin"], "local");
+  assert_eq!(references[1]["exported"], true);
+  assert_eq!(create["exposesRemoteTypes"], true);
+  assert_eq!(create["exposesNonExportedTypes"], false);
+
+  let internal = symbol("internal");
+  assert_eq!(internal["references"][0]["name"], "Internal");
+  assert_eq!(internal["references"][0]["exported"], false);
+  assert_eq!(internal["exposesRemoteTypes"], false);
+  assert_eq!(internal["exposesNonExportedTypes"], true);
+
+  let add = symbol("add");
+  assert_eq!(add["references"], json!([]));
+
+  // the re-exports are followed to the modules declaring the types
+  let configure = symbol("configure");
+  let references = configure["references"].as_array().unwrap();
+  assert_eq!(references.len(), 2);
+  assert_eq!(references[0]["name"], "Level");
+  assert!(references[0]["specifier"]
+    .as_str()
+    .unwrap()
+    .ends_with("/doc/references/types.ts"));
+  assert_eq!(references[1]["name"], "Settings");
+  assert!(references[1]["specifier"]
+    .as_str()
+    .unwrap()
+    .ends_with("/doc/references/config.ts"));
+
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("doc")
+    .arg("--show-references")
+    .arg("doc/references/mod.ts")
+    .arg("internal")
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert_contains!(stdout, "internal ");
+  assert_contains!(stdout, " exposes non-exported types\n");
+  assert_contains!(stdout, "/doc/references/mod.ts (local, not exported)\n");
+  assert!(!stdout.contains("create"));
+}
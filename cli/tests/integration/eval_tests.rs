@@ -66,6 +66,27 @@ itest!(v8_flags_eval {
   output: "run/v8_flags.js.out",
 });
 
+itest!(eval_color_always {
+  args: "eval --color=always console.log(String(Deno.noColor))",
+  output_str: Some("false\n"),
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+itest!(eval_color_never {
+  args: "eval --color=never console.log(String(Deno.noColor))",
+  output_str: Some("true\n"),
+  envs: vec![("FORCE_COLOR".to_string(), "1".to_string())],
+});
+
+itest!(eval_force_color {
+  args: "eval console.log(String(Deno.noColor))",
+  output_str: Some("false\n"),
+  envs: vec![
+    ("FORCE_COLOR".to_string(), "1".to_string()),
+    ("NO_COLOR".to_string(), "1".to_string()),
+  ],
+});
+
 itest!(check_local_by_default {
   args: "eval --quiet import('http://localhost:4545/subdir/type_error.ts').then(console.log);",
   output: "eval/check_local_by_default.out",
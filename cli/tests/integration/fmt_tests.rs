@@ -164,6 +164,107 @@ fn fmt_auto_ignore_git_and_node_modules() {
   );
 }
 
+#[test]
+fn fmt_editorconfig() {
+  let temp_dir = TempDir::new();
+  temp_dir.create_dir_all("spaces");
+  temp_dir.create_dir_all("crlf");
+  temp_dir.write(
+    ".editorconfig",
+    "root = true\n\n[*]\nindent_style = tab\nmax_line_length = 40\n",
+  );
+  temp_dir.write(
+    "spaces/.editorconfig",
+    "[*.ts]\nindent_style = space\nindent_size = 4\n",
+  );
+  temp_dir.write("crlf/.editorconfig", "[*]\nend_of_line = crlf\n");
+  let text = "if (a) {\nconsole.log(\"a somewhat longer line here\");\n}\n";
+  for path in ["mod.ts", "spaces/mod.ts", "crlf/mod.ts"] {
+    temp_dir.write(path, text);
+  }
+  temp_dir.write("spaces/data.json", "{\n\"a\": 1\n}\n");
+
+  let run_fmt = |args: &[&str]| {
+    let status = util::deno_cmd()
+      .current_dir(temp_dir.path())
+      .arg("fmt")
+      .args(args)
+      .spawn()
+      .unwrap()
+      .wait()
+      .unwrap();
+    assert!(status.success());
+  };
+  run_fmt(&[]);
+  assert_eq!(
+    temp_dir.read_to_string("mod.ts"),
+    "if (a) {\n\tconsole.log(\n\t\t\"a somewhat longer line here\",\n\t);\n}\n"
+  );
+  assert_eq!(
+    temp_dir.read_to_string("spaces/mod.ts"),
+    "if (a) {\n    console.log(\n        \"a somewhat longer line here\",\n    );\n}\n"
+  );
+  assert_eq!(
+    temp_dir.read_to_string("spaces/data.json"),
+    "{\n\t\"a\": 1\n}\n"
+  );
+  assert_eq!(
+    temp_dir.read_to_string("crlf/mod.ts"),
+    "if (a) {\r\n\tconsole.log(\r\n\t\t\"a somewhat longer line here\",\r\n\t);\r\n}\r\n"
+  );
+
+  // the options of the config file take precedence
+  temp_dir.write(
+    "deno.json",
+    r#"{ "fmt": { "options": { "useTabs": false, "lineWidth": 80 } } }"#,
+  );
+  run_fmt(&["--config", "deno.json", "mod.ts"]);
+  assert_eq!(
+    temp_dir.read_to_string("mod.ts"),
+    "if (a) {\n  console.log(\"a somewhat longer line here\");\n}\n"
+  );
+}
+
+#[test]
+fn fmt_markdown_tables_and_reference_links() {
+  let temp_dir = TempDir::new();
+  let testdata_markdown_dir = util::testdata_path().join("fmt/markdown");
+  let names = ["tables", "reference_links"];
+  for name in names {
+    std::fs::copy(
+      testdata_markdown_dir.join(format!("{name}.md")),
+      temp_dir.path().join(format!("{name}.md")),
+    )
+    .unwrap();
+  }
+
+  let run_fmt = |args: &[&str]| {
+    let status = util::deno_cmd()
+      .current_dir(temp_dir.path())
+      .arg("fmt")
+      .arg("--prose-wrap=always")
+      .arg("--line-width=40")
+      .args(args)
+      .args(["tables.md", "reference_links.md"])
+      .spawn()
+      .unwrap()
+      .wait()
+      .unwrap();
+    assert!(status.success());
+  };
+  run_fmt(&[]);
+  for name in names {
+    let expected = std::fs::read_to_string(
+      testdata_markdown_dir.join(format!("{name}_fixed.md")),
+    )
+    .unwrap();
+    assert_eq!(temp_dir.read_to_string(format!("{name}.md")), expected);
+  }
+
+  // formatting the output again leaves it as is
+  run_fmt(&["--check"]);
+}
+
 itest!(fmt_quiet_check_fmt_dir {
   args: "fmt --check --quiet fmt/regular/",
   output_str: Some(""),
@@ -182,6 +283,12 @@ itest!(fmt_check_ignore {
   exit_code: 0,
 });
 
+itest!(fmt_check_ignore_range_unmatched {
+  args: "fmt --check fmt/ignore_range/unmatched.ts",
+  output: "fmt/ignore_range/unmatched.out",
+  exit_code: 0,
+});
+
 itest!(fmt_check_parse_error {
   args: "fmt --check fmt/parse_error/parse_error.ts",
   output: "fmt/fmt_check_parse_error.out",
@@ -194,6 +301,29 @@ itest!(fmt_check_invalid_data {
   exit_code: 1,
 });
 
+itest!(fmt_check_compact {
+  args: "fmt --check --compact fmt/compact/unformatted.ts",
+  output: "fmt/compact/unformatted.out",
+  exit_code: 1,
+});
+
+itest!(fmt_check_yaml {
+  args: "fmt --check --compact --config fmt/yaml/deno.json fmt/yaml/",
+  output: "fmt/yaml/unformatted.out",
+  exit_code: 1,
+});
+
+itest!(fmt_check_diff {
+  args: "fmt --check --diff fmt/diff/unformatted.ts",
+  output: "fmt/diff/unformatted.out",
+  exit_code: 1,
+});
+
+itest!(fmt_check_unusual_paths {
+  args: "fmt --check unusual_paths/",
+  output: "unusual_paths/fmt.out",
+});
+
 itest!(fmt_stdin {
   args: "fmt -",
   input: Some("const a = 1\n"),
@@ -206,12 +336,61 @@ itest!(fmt_stdin_markdown {
   output_str: Some("# Hello Markdown\n\n```ts\nconsole.log(\"text\");\n```\n\n```cts\nconsole.log(5);\n```\n"),
 });
 
+itest!(fmt_stdin_markdown_prose_wrap_always {
+  args: "fmt --ext=md --prose-wrap=always --line-width=40 -",
+  input: Some("Deno is a simple, modern and secure runtime for JavaScript and TypeScript that uses V8 and is built in Rust.\n\n```ts\nconsole.log( \"text\")\n```\n"),
+  output_str: Some("Deno is a simple, modern and secure\nruntime for JavaScript and TypeScript\nthat uses V8 and is built in Rust.\n\n```ts\nconsole.log(\"text\");\n```\n"),
+});
+
+itest!(fmt_stdin_markdown_prose_wrap_never {
+  args: "fmt --ext=md --prose-wrap=never -",
+  input: Some("Deno is a simple,\nmodern and secure runtime.\n"),
+  output_str: Some("Deno is a simple, modern and secure runtime.\n"),
+});
+
+itest!(fmt_stdin_markdown_prose_wrap_preserve {
+  args: "fmt --ext=md --prose-wrap=preserve --line-width=20 -",
+  input: Some("Deno is a simple,\nmodern and secure runtime.\n"),
+  output_str: Some("Deno is a simple,\nmodern and secure runtime.\n"),
+});
+
 itest!(fmt_stdin_json {
   args: "fmt --ext=json -",
   input: Some("{    \"key\":   \"value\"}"),
   output_str: Some("{ \"key\": \"value\" }\n"),
 });
 
+itest!(fmt_stdin_filepath_markdown {
+  args: "fmt --stdin-filepath=fmt/stdin_filepath/docs/README.md -",
+  input: Some("# Hello      Markdown\n```ts\nconsole.log( \"text\");\n```"),
+  output_str: Some("# Hello Markdown\n\n```ts\nconsole.log(\"text\")\n```\n"),
+});
+
+itest!(fmt_stdin_filepath_config {
+  args: "fmt --stdin-filepath=fmt/stdin_filepath/main.ts -",
+  input: Some("const a = 1;\n"),
+  output_str: Some("const a = 1\n"),
+});
+
+itest!(fmt_stdin_filepath_ext {
+  args: "fmt --ext=json --stdin-filepath=fmt/stdin_filepath/data.txt -",
+  input: Some("{    \"key\":   \"value\"}"),
+  output_str: Some("{ \"key\": \"value\" }\n"),
+});
+
+itest!(fmt_stdin_filepath_excluded {
+  args: "fmt --stdin-filepath=fmt/stdin_filepath/vendor/lib.ts -",
+  input: Some("const a  =  1\n"),
+  output_str: Some("const a  =  1\n"),
+});
+
+itest!(fmt_stdin_filepath_unsupported_ext {
+  args: "fmt --stdin-filepath=fmt/stdin_filepath/main.py -",
+  input: Some("a = 1\n"),
+  output_str: Some("error: Unsupported file extension of --stdin-filepath \"fmt/stdin_filepath/main.py\". Pass --ext with one of the supported extensions: ts, tsx, js, jsx, md, json, jsonc\n"),
+  exit_code: 1,
+});
+
 itest!(fmt_stdin_check_formatted {
   args: "fmt --check -",
   input: Some("const a = 1;\n"),
@@ -251,3 +430,21 @@ itest!(fmt_with_malformed_config2 {
   output: "fmt/fmt_with_malformed_config2.out",
   exit_code: 1,
 });
+
+itest!(fmt_with_unknown_config_key {
+  args:
+    "fmt --check --config fmt/deno.unknown_key.jsonc fmt/regular/formatted1.js",
+  output: "fmt/fmt_with_unknown_config_key.out",
+});
+
+itest!(fmt_with_unknown_config_key_strict {
+  args: "fmt --check --strict-config --config fmt/deno.unknown_key.jsonc fmt/regular/formatted1.js",
+  output: "fmt/fmt_with_unknown_config_key_strict.out",
+  exit_code: 1,
+});
+
+itest!(fmt_with_invalid_config_type {
+  args: "fmt --config fmt/deno.invalid_type.jsonc",
+  output: "fmt/fmt_with_invalid_config_type.out",
+  exit_code: 1,
+});
@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use test_util as util;
 use test_util::TempDir;
 
@@ -45,6 +46,21 @@ itest!(info_ts_error {
   output: "info/031_info_ts_error.out",
 });
 
+itest!(info_import_list {
+  args: "info --import-list=info/import_list/entrypoints.txt",
+  output: "info/import_list.out",
+});
+
+itest!(info_preload {
+  args: "info --import-map=run/preload/import_map.json --preload=instrument run/preload/main.ts",
+  output: "run/preload/info.out",
+});
+
+itest!(info_import_list_json {
+  args: "info --json --import-list=info/import_list/entrypoints.txt",
+  output: "info/import_list_json.out",
+});
+
 itest!(info_flag {
   args: "info",
   output: "info/041_info_flag.out",
@@ -60,6 +76,11 @@ itest!(info_json {
   output: "info/info_json.out",
 });
 
+itest!(info_json_config_entrypoints {
+  args: "info --json --config-entrypoints --config info/config_entrypoints/deno.json",
+  output: "info/config_entrypoints.out",
+});
+
 itest!(info_json_location {
   args: "info --json --unstable --location https://deno.land",
   output: "info/info_json_location.out",
@@ -123,7 +144,79 @@ itest!(types_header_direct {
   http_server: true,
 });
 
+itest!(types_header_types_resolution {
+  args: "info --reload --types run/type_directives_01.ts",
+  output: "info/types_header_types.out",
+  http_server: true,
+});
+
+itest!(types_resolution_directive {
+  args: "info --types info/types_resolution/directive.ts",
+  output: "info/types_resolution/directive.out",
+});
+
+itest!(types_resolution_directive_json {
+  args: "info --types --json info/types_resolution/directive.ts",
+  output: "info/types_resolution/directive_json.out",
+});
+
+itest!(types_resolution_package_json {
+  args: "info --quiet --types info/types_resolution/package_json.ts",
+  output: "info/types_resolution/package_json.out",
+  envs: util::env_vars_for_npm_tests(),
+  http_server: true,
+});
+
+itest!(types_resolution_package_json_json {
+  args: "info --quiet --types --json npm:@denotest/types",
+  output: "info/types_resolution/package_json_json.out",
+  envs: util::env_vars_for_npm_tests(),
+  http_server: true,
+});
+
+itest!(types_resolution_adjacent_declaration {
+  args: "info --quiet --types info/types_resolution/adjacent_declaration.ts",
+  output: "info/types_resolution/adjacent_declaration.out",
+  envs: util::env_vars_for_npm_tests(),
+  http_server: true,
+});
+
 itest!(with_config_override {
   args: "info info/with_config/test.ts --config info/with_config/deno-override.json --import-map info/with_config/import_map.json",
   output: "info/with_config/with_config.out",
 });
+
+#[test]
+fn info_journal_summary() {
+  let t = TempDir::new();
+  let journal_path = t.path().join("journal.jsonl");
+
+  for args in [&["eval", "1"][..], &["eval", "throw new Error('secret')"]] {
+    util::deno_cmd()
+      .env("DENO_JOURNAL", &journal_path)
+      .current_dir(util::testdata_path())
+      .args(args)
+      .output()
+      .unwrap();
+  }
+  let journal = std::fs::read_to_string(&journal_path).unwrap();
+  assert_eq!(journal.lines().count(), 2);
+  assert!(journal.contains("\"subcommand\":\"eval\""));
+  assert!(!journal.contains("secret"));
+
+  let output = util::deno_cmd()
+    .env("DENO_JOURNAL", &journal_path)
+    .env("NO_COLOR", "1")
+    .current_dir(util::testdata_path())
+    .arg("info")
+    .arg("--journal-summary")
+    .arg("--json")
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  let summary: serde_json::Value =
+    serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(summary["invocations"], 2);
+  assert_eq!(summary["subcommands"]["eval"]["invocations"], 2);
+  assert_eq!(summary["subcommands"]["eval"]["failures"], 1);
+}
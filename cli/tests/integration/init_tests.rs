@@ -153,6 +153,8 @@ fn init_subcommand_with_quiet_arg() {
     .current_dir(cwd)
     .arg("init")
     .arg("--quiet")
+    // stderr is inherited, so make sure the prompts aren't shown
+    .stdin(Stdio::null())
     .stdout(Stdio::piped())
     .spawn()
     .unwrap()
@@ -203,3 +205,128 @@ fn init_subcommand_with_quiet_arg() {
     .unwrap();
   assert!(output.status.success());
 }
+
+#[test]
+fn init_subcommand_with_lib_arg() {
+  let temp_dir = TempDir::new();
+  let cwd = temp_dir.path();
+  let deno_dir = util::new_deno_dir();
+
+  // existing files are kept as is
+  std::fs::create_dir_all(cwd.join("my_lib")).unwrap();
+  std::fs::write(cwd.join("my_lib/mod.ts"), "export const a = 1;\n").unwrap();
+
+  let mut deno_cmd = util::deno_cmd_with_deno_dir(&deno_dir);
+  let output = deno_cmd
+    .current_dir(cwd)
+    .env("NO_COLOR", "1")
+    .arg("init")
+    .arg("--lib")
+    .arg("--name")
+    .arg("@scope/my_lib")
+    .arg("my_lib")
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert_contains!(stderr, "mod.ts already exists, skipping");
+  assert_contains!(stderr, "Library @scope/my_lib initialized");
+  assert_contains!(stderr, "cd my_lib");
+
+  let lib_dir = cwd.join("my_lib");
+  assert_eq!(
+    std::fs::read_to_string(lib_dir.join("mod.ts")).unwrap(),
+    "export const a = 1;\n"
+  );
+  assert!(lib_dir.join("mod_test.ts").exists());
+  assert!(lib_dir.join("scripts/bump.ts").exists());
+  let deno_json = std::fs::read_to_string(lib_dir.join("deno.json")).unwrap();
+  assert_contains!(deno_json, "\"name\": \"@scope/my_lib\"");
+  assert_contains!(deno_json, "\"version\": \"0.1.0\"");
+
+  let mut deno_cmd = util::deno_cmd_with_deno_dir(&deno_dir);
+  let output = deno_cmd
+    .current_dir(&lib_dir)
+    .env("NO_COLOR", "1")
+    .arg("task")
+    .arg("bump")
+    .arg("minor")
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert_contains!(stdout, "0.1.0 -> 0.2.0");
+  let deno_json = std::fs::read_to_string(lib_dir.join("deno.json")).unwrap();
+  assert_contains!(deno_json, "\"version\": \"0.2.0\"");
+  assert_contains!(deno_json, "\"name\": \"@scope/my_lib\"");
+  let version_ts = std::fs::read_to_string(lib_dir.join("version.ts")).unwrap();
+  assert_contains!(version_ts, "export const VERSION = \"0.2.0\";");
+}
+
+#[test]
+fn init_subcommand_with_project_options() {
+  let temp_dir = TempDir::new();
+  let cwd = temp_dir.path();
+  let deno_dir = util::new_deno_dir();
+
+  let mut deno_cmd = util::deno_cmd_with_deno_dir(&deno_dir);
+  let output = deno_cmd
+    .current_dir(cwd)
+    .env("NO_COLOR", "1")
+    .arg("init")
+    .arg("--name=my-app")
+    .arg("--no-tests")
+    .arg("--fmt-line-width=100")
+    .arg("--lint")
+    .arg("--jsx=preact")
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert_contains!(stderr, "Project my-app initialized");
+  assert!(!stderr.contains("without prompts"));
+  assert!(!stderr.contains("deno test"));
+  assert!(!stderr.contains("deno bench"));
+
+  assert!(cwd.join("main.ts").exists());
+  assert!(!cwd.join("main_test.ts").exists());
+  assert!(!cwd.join("main_bench.ts").exists());
+  let config = std::fs::read_to_string(cwd.join("deno.jsonc")).unwrap();
+  assert_contains!(config, "\"name\": \"my-app\"");
+  assert_contains!(config, "\"lineWidth\": 100");
+  assert_contains!(config, "\"singleQuote\": false");
+  assert_contains!(config, "\"tags\": [\n        \"recommended\"\n      ]");
+  assert_contains!(config, "\"jsxImportSource\": \"https://esm.sh/preact@");
+
+  // the generated configuration is formatted and lints cleanly
+  let mut deno_cmd = util::deno_cmd_with_deno_dir(&deno_dir);
+  let output = deno_cmd
+    .current_dir(cwd)
+    .arg("fmt")
+    .arg("--check")
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  let mut deno_cmd = util::deno_cmd_with_deno_dir(&deno_dir);
+  let output = deno_cmd
+    .current_dir(cwd)
+    .arg("lint")
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+}
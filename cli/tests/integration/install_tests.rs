@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::serde_json;
 use std::fs;
 use std::process::Command;
 use test_util as util;
@@ -164,6 +165,84 @@ fn installer_test_local_module_run() {
   assert_ends_with!(stdout_str, "hello, foo");
 }
 
+// runs the shims to check the arguments deno receives, as cmd and PowerShell
+// parse them before deno does
+#[cfg(windows)]
+#[test]
+fn installer_test_shims_pass_args() {
+  let temp_dir = TempDir::new();
+  let bin_dir = temp_dir.path().join("bin");
+  std::fs::create_dir(&bin_dir).unwrap();
+  let args = [
+    "100%",
+    "%PATH%",
+    "C:\\dir\\",
+    "a\"&calc",
+    "it's $HOME",
+    "say \"hi there\" \\",
+    "",
+  ];
+  let status = util::deno_cmd()
+    .current_dir(util::root_path())
+    .arg("install")
+    .arg("--name")
+    .arg("echo_args")
+    .arg("--root")
+    .arg(temp_dir.path())
+    .arg(util::testdata_path().join("install/echo_args.ts"))
+    .args(args)
+    .spawn()
+    .unwrap()
+    .wait()
+    .unwrap();
+  assert!(status.success());
+  let mut expected = args.to_vec();
+  expected.push("extra");
+  let expected = serde_json::to_string(&expected).unwrap();
+  let path = format!(
+    "{};{}",
+    util::target_dir().display(),
+    std::env::var("PATH").unwrap()
+  );
+
+  let output = Command::new(bin_dir.join("echo_args.cmd"))
+    .arg("extra")
+    .env("PATH", &path)
+    .output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(
+    std::str::from_utf8(&output.stdout).unwrap().trim(),
+    expected
+  );
+
+  // Windows PowerShell passes the arguments the legacy way, and PowerShell 7.3
+  // or later escapes them itself
+  for shell in ["powershell", "pwsh"] {
+    let result = Command::new(shell)
+      .arg("-NoProfile")
+      .arg("-ExecutionPolicy")
+      .arg("Bypass")
+      .arg("-File")
+      .arg(bin_dir.join("echo_args.ps1"))
+      .arg("extra")
+      .env("PATH", &path)
+      .output();
+    let output = match result {
+      Ok(output) => output,
+      // PowerShell 7 may not be installed
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+      Err(err) => panic!("failed to run {shell}: {err}"),
+    };
+    assert!(output.status.success());
+    assert_eq!(
+      std::str::from_utf8(&output.stdout).unwrap().trim(),
+      expected,
+      "arguments passed by {shell}"
+    );
+  }
+}
+
 #[test]
 fn installer_test_remote_module_run() {
   let _g = util::http_server();
@@ -28,6 +28,11 @@ itest!(all {
   exit_code: 1,
 });
 
+itest!(unusual_paths {
+  args: "lint unusual_paths/",
+  output: "unusual_paths/lint.out",
+});
+
 itest!(quiet {
   args: "lint --quiet lint/without_config/file1.js",
   output: "lint/expected_quiet.out",
@@ -48,6 +53,12 @@ itest!(compact {
     exit_code: 1,
 });
 
+itest!(compact_verbose {
+  args: "lint --compact --verbose lint/without_config/file1.js",
+  output: "lint/expected_compact_verbose.out",
+  exit_code: 1,
+});
+
 itest!(ignore {
   args:
     "lint --ignore=lint/without_config/file1.js,lint/without_config/malformed.js,lint/without_config/lint_with_config/ lint/without_config/",
@@ -75,6 +86,13 @@ itest!(stdin_json {
   exit_code: 1,
 });
 
+itest!(stdin_json_fixes {
+  args: "lint --json -",
+  input: Some("debugger;\nconsole.log(1);;\n"),
+  output: "lint/expected_fixes_json.out",
+  exit_code: 1,
+});
+
 itest!(rules {
   args: "lint --rules",
   output: "lint/expected_rules.out",
@@ -88,6 +106,19 @@ itest!(rules_quiet {
   exit_code: 0,
 });
 
+itest!(rules_for_file {
+  args:
+    "lint --rules --config lint/Deno.rules.jsonc --file=lint/with_config/a.ts",
+  output: "lint/expected_file_rules.out",
+  exit_code: 0,
+});
+
+itest!(rules_for_excluded_file {
+  args: "lint --rules --config lint/Deno.jsonc --file=lint/with_config/b.ts",
+  output: "lint/expected_file_rules_not_linted.out",
+  exit_code: 0,
+});
+
 itest!(lint_with_config {
   args: "lint --config lint/Deno.jsonc lint/with_config/",
   output: "lint/with_config.out",
@@ -119,6 +150,18 @@ itest!(lint_with_config_without_tags {
   exit_code: 1,
 });
 
+itest!(lint_with_rule_options {
+  args: "lint --config lint/Deno.rule_options.jsonc",
+  output: "lint/rule_options.out",
+  exit_code: 1,
+});
+
+itest!(lint_with_ignore_reasons {
+  args: "lint --config lint/Deno.ignore_reasons.jsonc",
+  output: "lint/ignore_reasons.out",
+  exit_code: 1,
+});
+
 itest!(lint_with_malformed_config {
   args: "lint --config lint/Deno.malformed.jsonc",
   output: "lint/with_malformed_config.out",
@@ -130,3 +173,20 @@ itest!(lint_with_malformed_config2 {
   output: "lint/with_malformed_config2.out",
   exit_code: 1,
 });
+
+itest!(deps {
+  args: "lint --deps --config=lint/deps/deno.json lint/deps/main.ts",
+  output: "lint/deps/main.out",
+});
+
+itest!(deps_strict {
+  args:
+    "lint --deps --strict-deps --config=lint/deps/deno.json lint/deps/main.ts",
+  output: "lint/deps/main.out",
+  exit_code: 1,
+});
+
+itest!(deps_json {
+  args: "lint --deps --json --config=lint/deps/deno.json lint/deps/main.ts",
+  output: "lint/deps/main_json.out",
+});
@@ -1119,6 +1119,56 @@ fn lsp_hover_asset() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_document_status() {
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import * as a from \"https://deno.land/x/a/mod.ts\";\n\nconsole.log(a);\n"
+      }
+    }),
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Value>(
+      "deno/documentStatus",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file.ts"
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert_eq!(
+    maybe_res,
+    Some(json!("deno:/status/file%3A%2F%2F%2Fa%2Ffile.ts"))
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, String>(
+      "deno/virtualTextDocument",
+      json!({
+        "textDocument": {
+          "uri": "deno:/status/file%3A%2F%2F%2Fa%2Ffile.ts"
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  let contents = maybe_res.unwrap();
+  assert!(contents.contains("`file:///a/file.ts`"));
+  assert!(contents.contains("  - Deno enabled: yes"));
+  assert!(contents.contains("  - Document: open in the editor (version 1)"));
+  assert!(contents.contains(
+    "|`https://deno.land/x/a/mod.ts`|code|`https://deno.land/x/a/mod.ts`|uncached|"
+  ));
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_hover_disabled() {
   let mut client = init("initialize_params_disabled.json");
@@ -2739,6 +2789,76 @@ fn lsp_rename() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_will_rename_files() {
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/utils.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "export const a = 1;\n"
+      }
+    }),
+  );
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import { a } from './utils.ts';\nexport * from \"./utils.ts\";\nawait import(\"./utils.ts\");\n"
+      }
+    }),
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request(
+      "workspace/willRenameFiles",
+      json!({
+        "files": [{
+          "oldUri": "file:///a/utils.ts",
+          "newUri": "file:///a/helpers.ts"
+        }]
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert_eq!(
+    maybe_res,
+    Some(json!({
+      "changes": {
+        "file:///a/file.ts": [
+          {
+            "range": {
+              "start": { "line": 0, "character": 18 },
+              "end": { "line": 0, "character": 30 }
+            },
+            "newText": "'./helpers.ts'"
+          },
+          {
+            "range": {
+              "start": { "line": 1, "character": 14 },
+              "end": { "line": 1, "character": 26 }
+            },
+            "newText": "\"./helpers.ts\""
+          },
+          {
+            "range": {
+              "start": { "line": 2, "character": 13 },
+              "end": { "line": 2, "character": 25 }
+            },
+            "newText": "\"./helpers.ts\""
+          }
+        ]
+      }
+    }))
+  );
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_selection_range() {
   let mut client = init("initialize_params.json");
@@ -3819,6 +3939,60 @@ fn lsp_completions() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_completions_cancelled() {
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "Deno."
+      }
+    }),
+  );
+  let completion_params = json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts"
+    },
+    "position": {
+      "line": 0,
+      "character": 5
+    },
+    "context": {
+      "triggerKind": 2,
+      "triggerCharacter": "."
+    }
+  });
+  let (maybe_res, maybe_err) = client
+    .write_request_and_cancel::<_, _, lsp::CompletionResponse>(
+      "textDocument/completion",
+      completion_params.clone(),
+    )
+    .unwrap();
+  // the request might have finished before the cancellation was received
+  if let Some(err) = maybe_err {
+    assert_eq!(err.code(), -32800); // RequestCancelled
+    assert!(maybe_res.is_none());
+  } else {
+    assert!(maybe_res.is_some());
+  }
+
+  // the server is still responsive after the cancellation
+  let (maybe_res, maybe_err) = client
+    .write_request("textDocument/completion", completion_params)
+    .unwrap();
+  assert!(maybe_err.is_none());
+  if let Some(lsp::CompletionResponse::List(list)) = maybe_res {
+    assert!(list.items.len() > 90);
+  } else {
+    panic!("unexpected response");
+  }
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_completions_private_fields() {
   let mut client = init("initialize_params.json");
@@ -4386,6 +4560,87 @@ fn lsp_completions_npm() {
   shutdown(&mut client);
 }
 
+#[test]
+fn lsp_completions_auto_import_npm() {
+  let _g = http_server();
+  let mut client = init("initialize_params.json");
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/main.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "import \"npm:@denotest/types\";\n",
+      }
+    }),
+  );
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, Value>(
+      "deno/cache",
+      json!({
+        "referrer": {
+          "uri": "file:///a/main.ts",
+        },
+        "uris": [
+          {
+            "uri": "npm:@denotest/types",
+          }
+        ]
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  assert!(maybe_res.is_some());
+  did_open(
+    &mut client,
+    json!({
+      "textDocument": {
+        "uri": "file:///a/file.ts",
+        "languageId": "typescript",
+        "version": 1,
+        "text": "export {};\n\n",
+      }
+    }),
+  );
+
+  let (maybe_res, maybe_err) = client
+    .write_request(
+      "textDocument/completion",
+      json!({
+        "textDocument": {
+          "uri": "file:///a/file.ts"
+        },
+        "position": {
+          "line": 2,
+          "character": 0,
+        },
+        "context": {
+          "triggerKind": 1,
+        }
+      }),
+    )
+    .unwrap();
+  assert!(maybe_err.is_none());
+  let item = match maybe_res {
+    Some(lsp::CompletionResponse::List(list)) => list
+      .items
+      .into_iter()
+      .find(|item| item.label == "Fizzbuzz")
+      .expect("completion items missing 'Fizzbuzz' symbol"),
+    _ => panic!("unexpected completion response"),
+  };
+  let (maybe_res, maybe_err) = client
+    .write_request::<_, _, lsp::CompletionItem>("completionItem/resolve", item)
+    .unwrap();
+  assert!(maybe_err.is_none());
+  let edits = maybe_res.unwrap().additional_text_edits.unwrap();
+  assert!(edits
+    .iter()
+    .any(|edit| edit.new_text.contains("from \"npm:@denotest/types\"")));
+  shutdown(&mut client);
+}
+
 #[test]
 fn lsp_npm_specifier_unopened_file() {
   let _g = http_server();
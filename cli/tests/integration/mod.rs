@@ -44,6 +44,8 @@ mod cache;
 mod cert;
 #[path = "check_tests.rs"]
 mod check;
+#[path = "ci_tests.rs"]
+mod ci;
 #[path = "compile_tests.rs"]
 mod compile;
 #[path = "coverage_tests.rs"]
@@ -68,6 +70,7 @@ mod install;
 mod js_unit_tests;
 #[path = "lint_tests.rs"]
 mod lint;
+#[cfg(feature = "lsp")]
 #[path = "lsp_tests.rs"]
 mod lsp;
 #[path = "npm_tests.rs"]
@@ -76,6 +79,8 @@ mod npm;
 mod repl;
 #[path = "run_tests.rs"]
 mod run;
+#[path = "storage_tests.rs"]
+mod storage;
 #[path = "task_tests.rs"]
 mod task;
 #[path = "test_tests.rs"]
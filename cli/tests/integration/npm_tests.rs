@@ -194,6 +194,12 @@ itest!(cached_only {
   exit_code: 1,
 });
 
+itest!(cached_only_dynamic_import {
+  args: "run --cached-only npm/cached_only/dynamic_import.ts",
+  output: "npm/cached_only/dynamic_import.out",
+  envs: env_vars_for_npm_tests(),
+});
+
 itest!(import_map {
     args: "run --allow-read --allow-env --import-map npm/import_map/import_map.json npm/import_map/main.js",
     output: "npm/import_map/main.out",
@@ -201,6 +207,13 @@ itest!(import_map {
     http_server: true,
   });
 
+itest!(import_map_subpath {
+    args: "run --allow-read --allow-env --import-map npm/import_map_subpath/import_map.json npm/import_map_subpath/main.js",
+    output: "npm/import_map_subpath/main.out",
+    envs: env_vars_for_npm_tests(),
+    http_server: true,
+  });
+
 itest!(lock_file {
     args: "run --allow-read --allow-env --lock npm/lock_file/lock.json npm/lock_file/main.js",
     output: "npm/lock_file/main.out",
@@ -885,6 +898,14 @@ itest!(info_cli_chalk_json {
   http_server: true,
 });
 
+itest!(info_cli_bin_entries {
+  args: "info --quiet npm:@denotest/bin",
+  output: "npm/info/bin.out",
+  exit_code: 0,
+  envs: env_vars_for_npm_tests(),
+  http_server: true,
+});
+
 #[test]
 fn lock_file_missing_top_level_package() {
   let _server = http_server();
@@ -1378,9 +1399,8 @@ fn auto_discover_lock_file() {
   assert_eq!(output.status.code(), Some(10));
 
   let stderr = String::from_utf8(output.stderr).unwrap();
-  assert!(stderr.contains(
-    "Integrity check failed for npm package: \"@denotest/bin@1.0.0\""
-  ));
+  assert!(stderr.contains("npm package integrity changed (1):"));
+  assert!(stderr.contains("npm:@denotest/bin@1.0.0"));
 }
 
 #[test]
@@ -1558,3 +1578,60 @@ itest!(create_require {
   envs: env_vars_for_npm_tests(),
   http_server: true,
 });
+
+#[test]
+fn cache_lockfile_only() {
+  let _server = http_server();
+
+  let deno_dir = util::new_deno_dir();
+  let temp_dir = util::TempDir::new();
+  temp_dir.write("deno.json", "{}");
+  let package_path = deno_dir
+    .path()
+    .join("npm")
+    .join("localhost_4545")
+    .join("npm")
+    .join("registry")
+    .join("@denotest")
+    .join("peer-dep-test-peer");
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(temp_dir.path())
+    .arg("cache")
+    .arg("--lockfile-only")
+    .arg("npm:@denotest/peer-dep-test-peer@1")
+    .envs(env_vars_for_npm_tests())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(output.status.success(), "{stderr}");
+  assert!(stderr.contains("  + @denotest/peer-dep-test-peer 1.0.0\n"));
+  assert!(stderr.contains("1 npm package locked (1 unique)"));
+  let lockfile =
+    std::fs::read_to_string(temp_dir.path().join("deno.lock")).unwrap();
+  assert!(lockfile.contains("\"@denotest/peer-dep-test-peer@1.0.0\""));
+  // only the registry information was downloaded
+  assert!(!package_path.join("1.0.0").exists());
+
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(temp_dir.path())
+    .arg("cache")
+    .arg("--lockfile-only")
+    .arg("--lock-write")
+    .arg("npm:@denotest/peer-dep-test-peer@2")
+    .envs(env_vars_for_npm_tests())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert!(output.status.success(), "{stderr}");
+  assert!(stderr.contains("  ~ @denotest/peer-dep-test-peer 1.0.0 -> 2.0.0\n"));
+  assert!(!package_path.join("2.0.0").exists());
+}
@@ -478,6 +478,22 @@ fn import_declarations() {
   assert_contains!(out, "hello!\n");
 }
 
+#[test]
+fn import_declarations_hoisted() {
+  let (out, err) = util::run_and_collect_output_with_args(
+    true,
+    vec!["repl", "--allow-read"],
+    Some(vec![
+      "printHello(); import { printHello } from './subdir/print_hello.ts';",
+      "printHello()",
+    ]),
+    Some(vec![("NO_COLOR".to_owned(), "1".to_owned())]),
+    false,
+  );
+  assert_contains!(out, "Hello\nundefined\nHello\n");
+  assert!(err.is_empty());
+}
+
 #[test]
 fn exports_stripped() {
   let (out, err) = util::run_and_collect_output(
@@ -952,7 +968,11 @@ fn npm_packages() {
     let (out, err) = util::run_and_collect_output_with_args(
       true,
       vec!["repl", "--quiet", "--allow-read", "--allow-env"],
-      Some(vec![r#"import foo from "npm:asdfawe52345asdf""#]),
+      Some(vec![
+        r#"import foo from "npm:asdfawe52345asdf""#,
+        r#"import chalk from "npm:chalk";"#,
+        "chalk.red('hel' + 'lo')",
+      ]),
       Some(env_vars.clone()),
       true,
     );
@@ -961,6 +981,7 @@ fn npm_packages() {
       out,
       "error: npm package 'asdfawe52345asdf' does not exist"
     );
+    assert_contains!(out, "hello");
     assert!(err.is_empty());
   }
 
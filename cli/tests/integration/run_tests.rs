@@ -201,6 +201,17 @@ itest!(_035_cached_only_flag {
   http_server: true,
 });
 
+itest!(cached_only_dynamic_import {
+  args: "run --cached-only --allow-net run/cached_only/dynamic_import.ts",
+  output: "run/cached_only/dynamic_import.out",
+  http_server: true,
+});
+
+itest!(cached_only_worker_dynamic_import {
+  args: "run --cached-only --allow-read --allow-net run/cached_only/worker.ts",
+  output: "run/cached_only/worker.out",
+});
+
 itest!(_038_checkjs {
   // checking if JS file is run through TS compiler
   args:
@@ -290,6 +301,84 @@ itest!(beforeunload_event {
   output: "run/before_unload.js.out",
 });
 
+itest!(exit_on_idle_timeout {
+  args: "run --quiet --allow-net --exit-on-idle-timeout=1s run/exit_on_idle_timeout.ts",
+  output: "run/exit_on_idle_timeout.ts.out",
+  exit_code: 124,
+});
+
+// timers that fire before the timeout count as activity
+itest!(exit_on_idle_timeout_timers {
+  args:
+    "run --quiet --exit-on-idle-timeout=1s run/exit_on_idle_timeout_timers.ts",
+  output_str: Some("done\n"),
+});
+
+itest!(diagnose_event_loop {
+  args: "run --quiet --diagnose-event-loop run/diagnose_event_loop.ts",
+  output: "run/diagnose_event_loop.ts.out",
+});
+
+// the report is also printed by `Deno.exit()`
+itest!(diagnose_event_loop_exit {
+  args: "run --quiet --diagnose-event-loop run/diagnose_event_loop.ts exit",
+  output: "run/diagnose_event_loop.ts.out",
+  exit_code: 3,
+});
+
+itest!(dir_entrypoint_mod {
+  args: "run --quiet run/dir_entrypoint/with_mod",
+  output_str: Some("mod.ts\n"),
+});
+
+itest!(dir_entrypoint_config {
+  args: "run --quiet run/dir_entrypoint/with_config/",
+  output_str: Some("cli.ts\n"),
+});
+
+itest!(dir_entrypoint_not_found {
+  args: "run --quiet run/dir_entrypoint/no_entrypoint",
+  output: "run/dir_entrypoint/no_entrypoint.out",
+  exit_code: 1,
+});
+
+itest!(print_import_order {
+  args: "run --quiet --print-import-order run/import_order/main.ts",
+  output: "run/import_order/main.out",
+});
+
+itest!(print_import_order_json {
+  args: "run --quiet --print-import-order=json run/import_order/main.ts",
+  output: "run/import_order/main_json.out",
+});
+
+itest!(report_unused_permissions {
+  args: "run --allow-env --allow-read --allow-hrtime --report-unused-permissions run/report_unused_permissions/main.ts",
+  output: "run/report_unused_permissions/main.out",
+});
+
+itest!(report_unused_permissions_json {
+  args: "run --quiet --allow-env=REPORT_UNUSED_PERMISSIONS_UNSET --allow-read=. --allow-hrtime --report-unused-permissions=json run/report_unused_permissions/main.ts",
+  output: "run/report_unused_permissions/main_json.out",
+});
+
+itest!(preload {
+  args: "run --quiet --import-map=run/preload/import_map.json --preload=./run/preload/first.ts --preload=instrument run/preload/main.ts",
+  output: "run/preload/main.out",
+});
+
+itest!(preload_error {
+  args: "run --quiet --preload=./run/preload/throws.ts run/preload/main.ts",
+  output: "run/preload/throws.out",
+  exit_code: 1,
+});
+
+itest!(preload_type_error {
+  args: "run --quiet --preload=./run/preload/type_error.ts run/preload/main.ts",
+  output: "run/preload/type_error.out",
+  exit_code: 1,
+});
+
 // tests the serialization of webstorage (both localStorage and sessionStorage)
 itest!(webstorage_serialization {
   args: "run run/webstorage/serialization.ts",
@@ -472,6 +561,44 @@ fn webstorage_main_module() {
   assert_eq!(output.stdout, b"Storage { length: 1, hello: \"deno\" }\n");
 }
 
+// test to ensure that `--storage-quota` limits the size of the persisted
+// storage.
+#[test]
+fn webstorage_storage_quota() {
+  let deno_dir = util::new_deno_dir();
+
+  let mut deno_cmd = util::deno_cmd_with_deno_dir(&deno_dir);
+  let output = deno_cmd
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg("--location")
+    .arg("https://example.com")
+    .arg("--storage-quota=16384")
+    .arg("run/webstorage/quota.ts")
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, b"QuotaExceededError\n");
+
+  let mut deno_cmd = util::deno_cmd_with_deno_dir(&deno_dir);
+  let output = deno_cmd
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg("--location")
+    .arg("https://example.org")
+    .arg("run/webstorage/quota.ts")
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert_eq!(output.stdout, b"quota not exceeded\n");
+}
+
 itest!(_075_import_local_query_hash {
   args: "run run/075_import_local_query_hash.ts",
   output: "run/075_import_local_query_hash.ts.out",
@@ -843,6 +970,18 @@ itest!(error_009_extensions_error {
   exit_code: 1,
 });
 
+itest!(stack_trace_recursion {
+  args: "run --stack-trace-limit=30 run/stack_trace_recursion.ts",
+  output: "run/stack_trace_recursion.ts.out",
+  exit_code: 1,
+});
+
+itest!(stack_trace_recursion_limit {
+  args: "run --stack-trace-limit=5 run/stack_trace_recursion.ts",
+  output: "run/stack_trace_recursion_limit.ts.out",
+  exit_code: 1,
+});
+
 itest!(error_011_bad_module_specifier {
   args: "run --reload run/error_011_bad_module_specifier.ts",
   exit_code: 1,
@@ -1023,6 +1162,12 @@ itest!(op_exit_op_set_exit_code_in_worker {
   output_str: Some(""),
 });
 
+itest!(worker_deno_exit {
+  args: "run --quiet --allow-read run/worker_deno_exit.ts",
+  output: "run/worker_deno_exit.ts.out",
+  exit_code: 0,
+});
+
 itest!(deno_exit_tampering {
   args: "run --no-check --unstable run/deno_exit_tampering.ts",
   output_str: Some(""),
@@ -1608,6 +1753,37 @@ itest!(classic_workers_event_loop {
   output: "run/classic_workers_event_loop.js.out",
 });
 
+itest!(config_permissions {
+  args: "run --config run/config_permissions/deno.json run/config_permissions/main.ts",
+  output: "run/config_permissions/main.out",
+});
+
+itest!(config_permissions_overridden_by_flag {
+  args: "run --config run/config_permissions/deno.json --allow-net=deno.land run/config_permissions/main.ts",
+  output: "run/config_permissions/override.out",
+});
+
+itest!(config_permissions_ignored {
+  args: "run --config run/config_permissions/deno.json --no-config-permissions run/config_permissions/main.ts",
+  output: "run/config_permissions/ignored.out",
+});
+
+itest!(scheme_handler {
+  args: "run --reload --check --enable-testing-features-do-not-use run/scheme_handler/main.ts",
+  output: "run/scheme_handler/main.out",
+});
+
+itest!(scheme_handler_dynamic_import {
+  args: "run --quiet --reload --allow-scheme=test-fixture --enable-testing-features-do-not-use run/scheme_handler/dynamic.ts",
+  output: "run/scheme_handler/dynamic.out",
+});
+
+itest!(scheme_handler_dynamic_import_denied {
+  args: "run --quiet --reload --enable-testing-features-do-not-use run/scheme_handler/dynamic.ts",
+  output: "run/scheme_handler/dynamic_denied.out",
+  exit_code: 1,
+});
+
 // FIXME(bartlomieju): disabled, because this test is very flaky on CI
 // itest!(local_sources_not_cached_in_memory {
 //   args: "run --allow-read --allow-write run/no_mem_cache.js",
@@ -1697,6 +1873,13 @@ itest!(worker_message_before_close {
   output: "run/worker_message_before_close.js.out",
 });
 
+// `Deno.exit()` closes the worker after dispatching `unload`, and the exit
+// code doesn't affect the main thread
+itest!(worker_exit_unload {
+  args: "run --quiet --reload --allow-read run/worker_exit_unload.js",
+  output: "run/worker_exit_unload.js.out",
+});
+
 itest!(worker_close_in_wasm_reactions {
   args:
     "run --quiet --reload --allow-read run/worker_close_in_wasm_reactions.js",
@@ -3858,3 +4041,14 @@ itest!(internal_dynamic_import {
   output: "run/internal_dynamic_import.ts.out",
   exit_code: 1,
 });
+
+itest!(user_agent_flag {
+  args: "run --quiet --user-agent=my-tool/1.2 run/user_agent.ts",
+  output: "run/user_agent.ts.out",
+});
+
+itest!(user_agent_flag_invalid {
+  args: "run --quiet --user-agent=my-tool/1.2\u{7f} run/user_agent.ts",
+  output: "run/user_agent_invalid.out",
+  exit_code: 1,
+});
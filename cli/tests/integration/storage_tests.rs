@@ -0,0 +1,67 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::process::Stdio;
+use test_util as util;
+use util::assert_contains;
+
+#[test]
+fn storage_list_show_clear() {
+  let deno_dir = util::new_deno_dir();
+
+  let run_deno = |args: &[&str]| {
+    let mut deno_cmd = util::deno_cmd_with_deno_dir(&deno_dir);
+    let output = deno_cmd
+      .current_dir(util::testdata_path())
+      .env("NO_COLOR", "1")
+      .args(args)
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap();
+    (
+      output.status.success(),
+      String::from_utf8(output.stdout).unwrap(),
+      String::from_utf8(output.stderr).unwrap(),
+    )
+  };
+
+  let (success, _, stderr) = run_deno(&["storage", "list"]);
+  assert!(success);
+  assert_contains!(stderr, "No persisted Web Storage data found.");
+
+  let (success, _, _) = run_deno(&[
+    "run",
+    "--location",
+    "https://example.com/a.ts",
+    "run/webstorage/setter.ts",
+  ]);
+  assert!(success);
+
+  let (success, stdout, _) = run_deno(&["storage", "list"]);
+  assert!(success);
+  assert_eq!(stdout, "https://example.com (1 entry, 9B)\n");
+
+  let (success, stdout, _) =
+    run_deno(&["storage", "show", "https://example.com/b.ts"]);
+  assert!(success);
+  assert_eq!(stdout, "{\n  \"hello\": \"deno\"\n}\n");
+
+  let (success, _, stderr) =
+    run_deno(&["storage", "show", "https://example.org"]);
+  assert!(!success);
+  assert_contains!(
+    stderr,
+    "No persisted Web Storage data found for https://example.org."
+  );
+
+  let (success, _, stderr) =
+    run_deno(&["storage", "clear", "https://example.com"]);
+  assert!(success);
+  assert_contains!(stderr, "Cleared https://example.com");
+
+  let (success, _, stderr) = run_deno(&["storage", "list"]);
+  assert!(success);
+  assert_contains!(stderr, "No persisted Web Storage data found.");
+}
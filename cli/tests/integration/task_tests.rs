@@ -10,6 +10,18 @@ itest!(task_no_args {
   exit_code: 1,
 });
 
+itest!(task_list {
+  args: "task --config task/listing/deno.json --list",
+  output: "task/task_list.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+itest!(task_list_json {
+  args: "task --config task/listing/deno.json --list --json",
+  output: "task/task_list_json.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
 itest!(task_cwd {
   args: "task -q --config task/deno.json --cwd .. echo_cwd",
   output: "task/task_cwd.out",
@@ -69,6 +81,14 @@ itest!(task_exit_code_5 {
   exit_code: 5,
 });
 
+// the exit code of `Deno.exit()` in a script run by a task is propagated
+itest!(task_deno_exit_code_3 {
+  args: "task --config task/deno.json deno_exit_3",
+  output: "task/task_deno_exit_code_3.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 3,
+});
+
 itest!(task_additional_args {
   args: "task -q --config task/deno.json echo 2",
   output: "task/task_additional_args.out",
@@ -128,3 +148,48 @@ itest!(task_piped_stdin {
   output: "task/task_piped_stdin.out",
   envs: vec![("NO_COLOR".to_string(), "1".to_string())],
 });
+
+#[cfg(unix)]
+itest!(task_process_substitution_input {
+  args: "task -q --config task/substitution/deno.json input",
+  output: "task/substitution/input.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+#[cfg(unix)]
+itest!(task_process_substitution_nested {
+  args: "task -q --config task/substitution/deno.json nested",
+  output: "task/substitution/nested.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+#[cfg(unix)]
+itest!(task_process_substitution_output {
+  args: "task -q --config task/substitution/deno.json output",
+  output: "task/substitution/output.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+});
+
+itest!(task_process_substitution_list {
+  args: "task -q --config task/substitution/deno.json list",
+  output: "task/substitution/list.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 1,
+});
+
+itest!(task_dry_run {
+  args: "task --config task/dry_run/deno.json --dry-run build",
+  output: "task/dry_run/build.out",
+  envs: vec![
+    ("NO_COLOR".to_string(), "1".to_string()),
+    ("GREETING".to_string(), "hello".to_string()),
+    ("API_TOKEN".to_string(), "abc".to_string())
+  ],
+});
+
+itest!(task_dry_run_not_found {
+  args: "task --config task/dry_run/deno.json --dry-run missing",
+  output: "task/dry_run/missing.out",
+  envs: vec![("NO_COLOR".to_string(), "1".to_string())],
+  exit_code: 1,
+});
@@ -25,6 +25,12 @@ itest!(overloads {
   output: "test/overloads.out",
 });
 
+itest!(preload {
+  args: "test --preload=./run/preload/first.ts test/preload/test.ts",
+  exit_code: 0,
+  output: "test/preload/main.out",
+});
+
 itest!(meta {
   args: "test test/meta.ts",
   exit_code: 0,
@@ -37,12 +43,30 @@ itest!(pass {
   output: "test/pass.out",
 });
 
+itest!(unusual_paths {
+  args: "test unusual_paths/",
+  exit_code: 0,
+  output: "unusual_paths/test.out",
+});
+
 itest!(ignore {
   args: "test test/ignore.ts",
   exit_code: 0,
   output: "test/ignore.out",
 });
 
+itest!(expected_failure {
+  args: "test test/expected_failure.ts",
+  exit_code: 0,
+  output: "test/expected_failure.out",
+});
+
+itest!(unexpectedly_passed {
+  args: "test test/unexpectedly_passed.ts",
+  exit_code: 1,
+  output: "test/unexpectedly_passed.out",
+});
+
 itest!(ignore_permissions {
   args: "test --unstable test/ignore_permissions.ts",
   exit_code: 0,
@@ -55,6 +79,137 @@ itest!(fail {
   output: "test/fail.out",
 });
 
+itest!(reporter_dot {
+  args: "test --reporter=dot test/pass.ts",
+  exit_code: 0,
+  output: "test/reporter_dot.out",
+});
+
+itest!(reporter_dot_fail {
+  args: "test --reporter=dot test/fail.ts",
+  exit_code: 1,
+  output: "test/reporter_dot_fail.out",
+});
+
+itest!(reporter_compact {
+  args: "test --reporter=compact test/ignore.ts",
+  exit_code: 0,
+  output: "test/reporter_compact.out",
+});
+
+itest!(reporter_compact_fail {
+  args: "test --reporter=compact --parallel test/fail.ts",
+  exit_code: 1,
+  output: "test/reporter_compact_fail.out",
+});
+
+itest!(reporter_junit {
+  args: "test --reporter=junit test/junit.ts",
+  exit_code: 1,
+  output: "test/reporter_junit.out",
+});
+
+itest!(reporter_junit_report_memory {
+  args: "test --reporter=junit --report-memory=10 test/hello_world.ts",
+  exit_code: 0,
+  output: "test/reporter_junit_report_memory.out",
+});
+
+itest!(reporter_tap {
+  args: "test --reporter=tap test/tap.ts",
+  exit_code: 1,
+  output: "test/reporter_tap.out",
+});
+
+#[test]
+fn reporter_tap_parallel() {
+  let output = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--reporter=tap")
+    .arg("--parallel")
+    .arg("test/tap.ts")
+    .arg("test/tap_parallel.ts")
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert_eq!(output.status.code(), Some(1));
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  let lines = stdout.lines().collect::<Vec<_>>();
+  assert_eq!(lines[0], "TAP version 14");
+
+  // the modules run at the same time, but the test points are numbered in
+  // the order they complete and each test is followed by the plan
+  let test_points = lines
+    .iter()
+    .enumerate()
+    .filter(|(_, line)| line.starts_with("ok ") || line.starts_with("not ok "))
+    .collect::<Vec<_>>();
+  let mut names = Vec::new();
+  for (number, (_, line)) in test_points.iter().enumerate() {
+    let line = line.strip_prefix("not ").unwrap_or(**line);
+    let prefix = format!("ok {} - ", number + 1);
+    assert!(line.starts_with(&prefix), "{line}");
+    names.push(line[prefix.len()..].trim_end_matches(" # SKIP"));
+  }
+  names.sort_unstable();
+  assert_eq!(
+    names,
+    [
+      "fails",
+      "ignored",
+      "other module passes",
+      "other module with steps",
+      "passes",
+      "with steps \\#1",
+    ]
+  );
+  assert_eq!(lines.last(), Some(&"1..6"));
+
+  // the steps of a test are printed together, right before it
+  let (index, line) = test_points
+    .iter()
+    .find(|(_, line)| line.ends_with(" - other module with steps"))
+    .unwrap();
+  assert_eq!(
+    lines[index - 4..*index],
+    [
+      "# Subtest: other module with steps",
+      "    ok 1 - first step",
+      "    ok 2 - second step",
+      "    1..2",
+    ]
+  );
+  assert!(line.starts_with("ok "));
+}
+
+itest!(reporter_custom {
+  args: "test --reporter=./test/custom_reporter.ts test/tap.ts",
+  exit_code: 1,
+  output: "test/reporter_custom.out",
+});
+
+itest!(reporter_custom_throws {
+  args: "test --reporter=./test/custom_reporter_throws.ts test/pass.ts",
+  exit_code: 1,
+  output: "test/reporter_custom_throws.out",
+});
+
+itest!(report_memory {
+  args: "test --report-memory=10 test/hello_world.ts",
+  exit_code: 0,
+  output: "test/report_memory.out",
+});
+
+itest!(args {
+  args: "test test/args.ts -- staging --url=https://staging.example.com",
+  exit_code: 0,
+  output: "test/args.out",
+});
+
 itest!(collect {
   args: "test --ignore=test/collect/ignore test/collect",
   exit_code: 0,
@@ -67,6 +222,24 @@ itest!(test_with_config {
   output: "test/collect.out",
 });
 
+itest!(test_with_config_v8_flags {
+  args: "test --config test/v8_flags/deno.json test/v8_flags",
+  exit_code: 0,
+  output: "test/v8_flags.out",
+});
+
+itest!(test_with_config_unknown_v8_flag {
+  args: "test --config test/v8_flags/deno.unknown_flag.json test/v8_flags",
+  exit_code: 0,
+  output: "test/v8_flags_unknown.out",
+});
+
+itest!(test_with_config_unknown_v8_flag_strict {
+  args: "test --strict-config --config test/v8_flags/deno.unknown_flag.json test/v8_flags",
+  exit_code: 1,
+  output: "test/v8_flags_unknown_strict.out",
+});
+
 itest!(test_with_config2 {
   args: "test --config test/collect/deno2.jsonc test/collect",
   exit_code: 0,
@@ -170,6 +343,17 @@ itest!(fail_fast {
   output: "test/fail_fast.out",
 });
 
+itest!(fail_fast_per_file {
+  args: "test --fail-fast-per-file=2 test/fail_fast_per_file/",
+  exit_code: 1,
+  output: "test/fail_fast_per_file.out",
+});
+
+itest!(report_unused_permissions {
+  args: "test --allow-env --allow-net --report-unused-permissions=json test/report_unused_permissions.ts",
+  output: "test/report_unused_permissions.out",
+});
+
 itest!(only {
   args: "test test/only.ts",
   exit_code: 1,
@@ -194,6 +378,24 @@ itest!(allow_all {
   output: "test/allow_all.out",
 });
 
+itest!(empty_files {
+  args: "test test/empty_files",
+  exit_code: 0,
+  output: "test/empty_files.out",
+});
+
+itest!(empty_files_fail {
+  args: "test --fail-on-empty-files test/empty_files",
+  exit_code: 1,
+  output: "test/empty_files_fail.out",
+});
+
+itest!(empty_files_filter {
+  args: "test --filter=foo test/empty_files",
+  exit_code: 0,
+  output: "test/empty_files_filter.out",
+});
+
 itest!(allow_none {
   args: "test --unstable test/allow_none.ts",
   exit_code: 1,
@@ -255,6 +457,24 @@ itest!(exit_sanitizer {
   exit_code: 1,
 });
 
+itest!(stack_trace_limit {
+  args: "test --stack-trace-limit=30 test/stack_traces/recursion.ts",
+  output: "test/stack_traces/recursion.out",
+  exit_code: 1,
+});
+
+itest!(full_stack_traces {
+  args: "test --full-stack-traces test/stack_traces/fail.ts",
+  output: "test/stack_traces/full.out",
+  exit_code: 1,
+});
+
+itest!(exit_unsanitized {
+  args: "test test/exit_unsanitized.ts",
+  output: "test/exit_unsanitized.out",
+  exit_code: 1,
+});
+
 itest!(clear_timeout {
   args: "test test/clear_timeout.ts",
   exit_code: 0,
@@ -285,10 +505,28 @@ itest!(filter {
   output: "test/filter.out",
 });
 
+itest!(tags {
+  args: "test --quiet --tags=slow,net --skip-tags=net test/tags.ts",
+  exit_code: 0,
+  output: "test/tags.out",
+});
+
+itest!(tags_filter {
+  args: "test --quiet --tags=slow --filter=ignored test/tags.ts",
+  exit_code: 0,
+  output: "test/tags_filter.out",
+});
+
+itest!(retries {
+  args: "test --quiet --allow-net --retries=2 test/retries.ts",
+  exit_code: 1,
+  output: "test/retries.out",
+});
+
 itest!(shuffle {
   args: "test --shuffle test/shuffle",
   exit_code: 0,
-  output_str: Some("[WILDCARD]"),
+  output_str: Some("[WILDCARD]Shuffling the tests with seed [WILDCARD] (use --shuffle=[WILDCARD] to reproduce the order)\n[WILDCARD]"),
 });
 
 itest!(shuffle_with_seed {
@@ -297,6 +535,12 @@ itest!(shuffle_with_seed {
   output: "test/shuffle.out",
 });
 
+itest!(timeout {
+  args: "test --quiet --timeout=1s test/timeout.ts",
+  exit_code: 1,
+  output: "test/timeout.out",
+});
+
 itest!(aggregate_error {
   args: "test --quiet test/aggregate_error.ts",
   exit_code: 1,
@@ -452,3 +696,98 @@ itest!(parallel_output {
   output: "test/parallel_output.out",
   exit_code: 1,
 });
+
+itest!(golden_file {
+  args: "test --allow-read test/golden_file/main.ts",
+  output: "test/golden_file/main.out",
+  exit_code: 1,
+});
+
+#[test]
+fn update_golden_files() {
+  let t = util::TempDir::new();
+  t.write(
+    "main.ts",
+    concat!(
+      "Deno.test(\"golden\", (t) => {\n",
+      "  t.assertGoldenFile(\"generated\\n\", \"./golden/nested/out.txt\");\n",
+      "});\n",
+    ),
+  );
+  let run_test = |args: &[&str], update_golden_env: bool| {
+    let mut command = util::deno_cmd();
+    command
+      .current_dir(t.path())
+      .env("NO_COLOR", "1")
+      .arg("test")
+      .args(args)
+      .arg("main.ts")
+      .stdout(std::process::Stdio::piped())
+      .stderr(std::process::Stdio::piped());
+    if update_golden_env {
+      command.env("UPDATE_GOLDEN", "1");
+    } else {
+      command.env_remove("UPDATE_GOLDEN");
+    }
+    let output = command.spawn().unwrap().wait_with_output().unwrap();
+    (
+      output.status.success(),
+      String::from_utf8(output.stdout).unwrap(),
+    )
+  };
+
+  // writing requires write permission
+  let (success, stdout) = run_test(&["--update-golden"], false);
+  assert!(!success);
+  assert!(stdout.contains("PermissionDenied: Requires write access"));
+  assert!(!t.path().join("golden").exists());
+
+  let (success, _) = run_test(&["--update-golden", "--allow-write"], false);
+  assert!(success);
+  assert_eq!(t.read_to_string("golden/nested/out.txt"), "generated\n");
+
+  let (success, _) = run_test(&["--allow-read"], false);
+  assert!(success);
+
+  t.write("golden/nested/out.txt", "outdated\n");
+  let (success, stdout) = run_test(&["--allow-read"], false);
+  assert!(!success);
+  assert!(stdout.contains("-outdated\n+generated\n"));
+
+  let (success, _) = run_test(&["--allow-write"], true);
+  assert!(success);
+  assert_eq!(t.read_to_string("golden/nested/out.txt"), "generated\n");
+}
+
+#[test]
+fn junit_path() {
+  let t = util::TempDir::new();
+  t.write("main_test.ts", "Deno.test(\"passes\", () => {});\n");
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("test")
+    .arg("--reporter=dot")
+    .arg("--junit-path=report.xml")
+    .arg("main_test.ts")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+
+  // the console keeps the selected reporter and the report goes to the file
+  let stdout = String::from_utf8(output.stdout).unwrap();
+  assert!(stdout.starts_with(".\n"), "{stdout}");
+  assert!(!stdout.contains("<testsuites"), "{stdout}");
+  let report = t.read_to_string("report.xml");
+  assert!(report.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+  assert!(report.contains(
+    "<testsuite name=\"./main_test.ts\" tests=\"1\" failures=\"0\" errors=\"0\" skipped=\"0\""
+  ));
+  assert!(
+    report.contains("<testcase name=\"passes\" classname=\"./main_test.ts\"")
+  );
+}
@@ -251,6 +251,64 @@ fn remote_module_test() {
   );
 }
 
+#[test]
+fn check_manifest() {
+  let _server = http_server();
+  let t = TempDir::new();
+
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("vendor")
+    .arg("http://localhost:4545/vendor/query_reexport.ts")
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(output.status.success());
+  assert!(t.path().join("vendor/manifest.json").exists());
+
+  let run_check = || {
+    util::deno_cmd()
+      .current_dir(t.path())
+      .env("NO_COLOR", "1")
+      .arg("vendor")
+      .arg("--check")
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap()
+  };
+
+  let output = run_check();
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr).trim(),
+    "Vendored files in vendor/ match the manifest.",
+  );
+  assert!(output.status.success());
+
+  t.write("vendor/localhost_4545/vendor/logger.ts", "export {};");
+  t.write("vendor/localhost_4545/extra.ts", "export {};");
+  std::fs::remove_file(
+    t.path()
+      .join("vendor/localhost_4545/vendor/query_reexport.ts"),
+  )
+  .unwrap();
+  let output = run_check();
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(stderr.contains("modified vendor/localhost_4545/vendor/logger.ts"));
+  assert!(
+    stderr.contains("missing vendor/localhost_4545/vendor/query_reexport.ts")
+  );
+  assert!(stderr.contains("extraneous vendor/localhost_4545/extra.ts"));
+  assert!(stderr.contains("(1 modified, 1 missing, 1 extraneous)"));
+  assert!(!output.status.success());
+}
+
 #[test]
 fn existing_import_map_no_remote() {
   let _server = http_server();
@@ -529,6 +587,174 @@ fn update_existing_config_test() {
   assert!(output.status.success());
 }
 
+#[test]
+fn json_output() {
+  let _server = http_server();
+  let t = TempDir::new();
+  t.write(
+    "my_app.ts",
+    "import {Logger} from 'http://localhost:4545/vendor/logger.ts'; new Logger().log('outputted');",
+  );
+  t.write("deno.json", "{\n}");
+
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("vendor")
+    .arg("--json")
+    .arg("--output")
+    .arg("vendor2")
+    .arg("my_app.ts")
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  // progress and logging is suppressed
+  assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "");
+  assert!(output.status.success());
+  let mut summary: serde_json::Value =
+    serde_json::from_slice(&output.stdout).unwrap();
+  assert!(summary["elapsed"].is_u64());
+  summary.as_object_mut().unwrap().remove("elapsed");
+  let config_file = summary["updatedConfigFile"].as_str().unwrap();
+  assert!(config_file.starts_with("file:///"));
+  assert!(config_file.ends_with("/deno.json"));
+  summary.as_object_mut().unwrap().remove("updatedConfigFile");
+  assert_eq!(
+    summary,
+    json!({
+      "outputDir": "vendor2",
+      "modules": 1,
+      "files": [{
+        "path": "localhost_4545/vendor/logger.ts",
+        "specifier": "http://localhost:4545/vendor/logger.ts",
+      }, {
+        "path": "import_map.json",
+        "specifier": null,
+      }, {
+        "path": "manifest.json",
+        "specifier": null,
+      }],
+      "importMap": "vendor2/import_map.json",
+      "warnings": [],
+    })
+  );
+
+  // errors are output as JSON too
+  let output = util::deno_cmd()
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("vendor")
+    .arg("--json")
+    .arg("--output")
+    .arg("vendor2")
+    .arg("my_app.ts")
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert!(!output.status.success());
+  let error: serde_json::Value =
+    serde_json::from_slice(&output.stdout).unwrap();
+  assert_eq!(error["error"]["class"], "Error");
+  assert!(error["error"]["message"]
+    .as_str()
+    .unwrap()
+    .starts_with("Output directory was not empty."));
+}
+
+#[test]
+fn import_map_only() {
+  let _server = http_server();
+  let t = TempDir::new();
+  t.write(
+    "my_app.ts",
+    "import {Logger} from 'http://localhost:4546/vendor/logger.ts'; import './local.ts'; new Logger().log('outputted');",
+  );
+  t.write("local.ts", "export {};");
+  let deno_dir = new_deno_dir();
+
+  let run_vendor = || {
+    util::deno_cmd_with_deno_dir(&deno_dir)
+      .current_dir(t.path())
+      .env("NO_COLOR", "1")
+      .arg("vendor")
+      .arg("--import-map-only=deps.json")
+      .arg("--lock=deno.lock")
+      .arg("my_app.ts")
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .spawn()
+      .unwrap()
+      .wait_with_output()
+      .unwrap()
+  };
+  let output = run_vendor();
+  assert!(output.status.success());
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr).trim(),
+    concat!(
+      "Download http://localhost:4546/vendor/logger.ts\n",
+      "Download http://localhost:4545/vendor/logger.ts\n",
+      "Pinned 1 specifier in deps.json (1 added, 0 updated, 0 removed).",
+    )
+  );
+  assert!(!t.path().join("vendor").exists());
+  let import_map: serde_json::Value =
+    serde_json::from_str(&t.read_to_string("deps.json")).unwrap();
+  assert_eq!(
+    import_map,
+    json!({
+      "imports": {
+        "http://localhost:4546/vendor/logger.ts": "http://localhost:4545/vendor/logger.ts",
+        "deno-vendor:manual": "data:,",
+      }
+    })
+  );
+  let lockfile: serde_json::Value =
+    serde_json::from_str(&t.read_to_string("deno.lock")).unwrap();
+  assert!(
+    lockfile["remote"]["http://localhost:4545/vendor/logger.ts"].is_string()
+  );
+
+  // manual entries are kept and nothing is written when up to date
+  let text = t.read_to_string("deps.json").replace(
+    r#""deno-vendor:manual": "data:,""#,
+    r#""deno-vendor:manual": "data:,",
+    "@/": "./src/""#,
+  );
+  t.write("deps.json", &text);
+  let output = run_vendor();
+  assert!(output.status.success());
+  assert_eq!(
+    String::from_utf8_lossy(&output.stderr).trim(),
+    "Import map deps.json is up to date."
+  );
+  assert_eq!(t.read_to_string("deps.json"), text);
+
+  // the import map can be used in place of the vendor directory
+  let output = util::deno_cmd_with_deno_dir(&deno_dir)
+    .current_dir(t.path())
+    .env("NO_COLOR", "1")
+    .arg("run")
+    .arg("--import-map=deps.json")
+    .arg("--cached-only")
+    .arg("my_app.ts")
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+  assert_eq!(String::from_utf8_lossy(&output.stderr).trim(), "");
+  assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "outputted");
+  assert!(output.status.success());
+}
+
 fn success_text(module_count: &str, dir: &str, has_import_map: bool) -> String {
   let mut text = format!("Vendored {module_count} into {dir} directory.");
   if has_import_map {
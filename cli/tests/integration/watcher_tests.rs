@@ -242,6 +242,87 @@ fn lint_all_files_on_each_change_test() {
   drop(t);
 }
 
+#[test]
+fn lint_watch_delta_test() {
+  let t = TempDir::new();
+  let badly_linted_original =
+    util::testdata_path().join("lint/watch/badly_linted.js");
+  let badly_linted_fixed1 =
+    util::testdata_path().join("lint/watch/badly_linted_fixed1.js");
+  let badly_linted = t.path().join("badly_linted.js");
+  std::fs::copy(badly_linted_original, &badly_linted).unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("lint")
+    .arg(&badly_linted)
+    .arg("--watch")
+    .arg("--unstable")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (_stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Lint finished", &mut stderr_lines);
+
+  std::fs::copy(badly_linted_fixed1, &badly_linted).unwrap();
+  assert_contains!(
+    read_line("fixed", &mut stderr_lines),
+    "1 new problem, 2 fixed"
+  );
+
+  check_alive_then_kill(child);
+}
+
+#[test]
+fn check_watch_test() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.ts");
+  write(
+    &file_to_watch,
+    "const a: string = 1;\nconst b: string = 2;\n",
+  )
+  .unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("check")
+    .arg("--watch")
+    .arg("--no-clear-screen")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (_stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Check run started at", &mut stderr_lines);
+  wait_contains("TS2322", &mut stderr_lines);
+  wait_contains("Check finished", &mut stderr_lines);
+
+  write(
+    &file_to_watch,
+    "const a: string = \"\";\nconst b: string = 2;\n",
+  )
+  .unwrap();
+  assert_contains!(
+    read_line("fixed", &mut stderr_lines),
+    "0 new problems, 1 fixed"
+  );
+
+  write(
+    &file_to_watch,
+    "const a: string = \"\";\nconst b: number = 2;\n",
+  )
+  .unwrap();
+  assert_contains!(
+    read_line("fixed", &mut stderr_lines),
+    "0 new problems, 1 fixed"
+  );
+
+  check_alive_then_kill(child);
+}
+
 #[test]
 fn fmt_watch_test() {
   let fmt_testdata_path = util::testdata_path().join("fmt");
@@ -978,6 +1059,53 @@ fn test_watch() {
   check_alive_then_kill(child);
 }
 
+#[flaky_test]
+fn test_watch_forwards_args() {
+  let t = TempDir::new();
+  let foo_test = t.path().join("foo_test.js");
+  write(
+    &foo_test,
+    "Deno.test(`first ${Deno.args.join(' ')}`, () => {});",
+  )
+  .unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("test")
+    .arg("--watch")
+    .arg("--no-check")
+    .arg(t.path())
+    .arg("--")
+    .arg("staging")
+    .arg("--url=https://staging.example.com")
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  wait_contains(
+    "first staging --url=https://staging.example.com",
+    &mut stdout_lines,
+  );
+  wait_contains("Test finished", &mut stderr_lines);
+
+  // The arguments are forwarded again after a restart
+  write(
+    &foo_test,
+    "Deno.test(`second ${Deno.args.join(' ')}`, () => {});",
+  )
+  .unwrap();
+  assert_contains!(stderr_lines.next().unwrap(), "Restarting");
+  wait_contains(
+    "second staging --url=https://staging.example.com",
+    &mut stdout_lines,
+  );
+  wait_contains("Test finished", &mut stderr_lines);
+  check_alive_then_kill(child);
+}
+
 #[flaky_test]
 fn test_watch_doc() {
   let t = TempDir::new();
@@ -1024,7 +1152,127 @@ fn test_watch_doc() {
 
   // We only need to scan for a Check file://.../foo.ts$3-6 line that
   // corresponds to the documentation block being type-checked.
-  assert_contains!(skip_restarting_line(&mut stderr_lines), "foo.ts$3-6");
+  wait_contains("foo.ts$3-6", &mut stderr_lines);
+  check_alive_then_kill(child);
+}
+
+#[flaky_test]
+fn test_watch_affected_tests() {
+  let t = TempDir::new();
+  let parser_file = t.path().join("parser.js");
+  let lexer_file = t.path().join("lexer.js");
+  let parser_test = t.path().join("parser_test.js");
+  let lexer_test = t.path().join("lexer_test.js");
+  write(&parser_file, "export function parse() {}").unwrap();
+  write(&lexer_file, "export function lex() {}").unwrap();
+  write(
+    parser_test,
+    r#"import { parse } from "./parser.js";
+import { lex } from "./lexer.js";
+Deno.test("parse a", () => parse());
+Deno.test("parse b", () => parse());
+Deno.test("lex", () => lex());
+"#,
+  )
+  .unwrap();
+  write(
+    &lexer_test,
+    r#"import { lex } from "./lexer.js";
+Deno.test("lexer", () => lex());
+"#,
+  )
+  .unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("test")
+    .arg("--watch")
+    .arg("--no-check")
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Test finished", &mut stderr_lines);
+
+  // only the tests using the changed module run again
+  write(&parser_file, "export function parse() { 1 + 1 }").unwrap();
+  wait_contains("2 tests affected by change to parser.js", &mut stderr_lines);
+  wait_contains("running 2 tests", &mut stdout_lines);
+  assert_contains!(stdout_lines.next().unwrap(), "parse a");
+  assert_contains!(stdout_lines.next().unwrap(), "parse b");
+  wait_contains("Test finished", &mut stderr_lines);
+
+  // a deleted test module is left out without restarting
+  std::fs::remove_file(&lexer_test).unwrap();
+  write(&lexer_file, "export function lex() { 2 + 2 }").unwrap();
+  wait_contains("1 test affected by change to lexer.js", &mut stderr_lines);
+  wait_contains("running 1 test", &mut stdout_lines);
+  assert_contains!(stdout_lines.next().unwrap(), "lex");
+  wait_contains("Test finished", &mut stderr_lines);
+  check_alive_then_kill(child);
+}
+
+#[test]
+fn bench_watch_affected_modules() {
+  let t = TempDir::new();
+  let parser_file = t.path().join("parser.js");
+  let parser_bench = t.path().join("parser_bench.js");
+  let lexer_bench = t.path().join("lexer_bench.js");
+  write(&parser_file, "export function parse() {}").unwrap();
+  write(
+    parser_bench,
+    r#"import { parse } from "./parser.js";
+Deno.bench("parse", () => parse());
+"#,
+  )
+  .unwrap();
+  write(lexer_bench, r#"Deno.bench("lex", () => {});"#).unwrap();
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("bench")
+    .arg("--watch")
+    .arg("--no-check")
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Bench finished", &mut stderr_lines);
+
+  // only the bench modules using the changed module run again
+  write(&parser_file, "export function parse() { 1 + 1 }").unwrap();
+  wait_contains(
+    "1 bench module selected by change to parser.js",
+    &mut stderr_lines,
+  );
+  wait_contains("parse ", &mut stdout_lines);
+  wait_contains("Bench finished", &mut stderr_lines);
+  check_alive_then_kill(child);
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("bench")
+    .arg("--watch")
+    .arg("--watch-run-all")
+    .arg("--no-check")
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (_, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("Bench finished", &mut stderr_lines);
+
+  write(&parser_file, "export function parse() { 2 + 2 }").unwrap();
+  wait_contains(
+    "2 bench modules selected by change to parser.js",
+    &mut stderr_lines,
+  );
+  wait_contains("Bench finished", &mut stderr_lines);
   check_alive_then_kill(child);
 }
 
@@ -1220,3 +1468,72 @@ fn run_watch_dynamic_imports() {
 
   check_alive_then_kill(child);
 }
+
+// Restarting the watcher many times should not grow memory usage, as the
+// previous worker is dropped and the caches are reused between runs.
+#[cfg(target_os = "linux")]
+#[test]
+fn run_watch_memory_is_stable_across_restarts() {
+  fn rss_kb(pid: u32) -> usize {
+    let status =
+      std::fs::read_to_string(format!("/proc/{pid}/status")).unwrap();
+    let line = status.lines().find(|l| l.starts_with("VmRSS:")).unwrap();
+    line
+      .trim_start_matches("VmRSS:")
+      .trim()
+      .trim_end_matches("kB")
+      .trim()
+      .parse()
+      .unwrap()
+  }
+
+  const RESTARTS: usize = 200;
+
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.ts");
+  let dep = t.path().join("dep.ts");
+  write(&dep, "export const values: number[] = [1, 2, 3];").unwrap();
+  let write_main = |i: usize| {
+    write(
+      &file_to_watch,
+      format!(
+        "import {{ values }} from \"./dep.ts\";\nconsole.log(\"run {i}\", values.length);\n"
+      ),
+    )
+    .unwrap();
+  };
+  write_main(0);
+
+  let mut child = util::deno_cmd()
+    .current_dir(util::testdata_path())
+    .arg("run")
+    .arg("--watch")
+    .arg("--no-clear-screen")
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .stdout(std::process::Stdio::piped())
+    .stderr(std::process::Stdio::piped())
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+  wait_contains("run 0 3", &mut stdout_lines);
+  wait_contains("finished", &mut stderr_lines);
+
+  let mut baseline_rss = 0;
+  for i in 1..=RESTARTS {
+    write_main(i);
+    wait_contains(&format!("run {i} 3"), &mut stdout_lines);
+    wait_contains("finished", &mut stderr_lines);
+    // let the allocator settle on the first few runs before measuring
+    if i == 10 {
+      baseline_rss = rss_kb(child.id());
+    }
+  }
+  let final_rss = rss_kb(child.id());
+  assert!(
+    final_rss < baseline_rss + 64 * 1024,
+    "RSS grew from {baseline_rss}kB to {final_rss}kB over {RESTARTS} restarts"
+  );
+
+  check_alive_then_kill(child);
+}
@@ -0,0 +1,5 @@
+Deno.bench("parse url", { group: "url", baseline: true }, () => {
+  new URL("https://deno.land/std/http/server.ts");
+});
+
+Deno.bench("noop", { group: "alone" }, () => {});
@@ -0,0 +1,5 @@
+Deno.bench("parse url 100x", { group: "url" }, () => {
+  for (let i = 0; i < 100; i++) {
+    new URL("https://deno.land/std/http/server.ts");
+  }
+});
@@ -0,0 +1,6 @@
+// The error is thrown on the third line of this module.
+function fail(message: string): never {
+  throw new Error(message);
+}
+
+fail("boom");
@@ -0,0 +1,24 @@
+import "./dep_00.ts";
+import "./dep_01.ts";
+import "./dep_02.ts";
+import "./dep_03.ts";
+import "./dep_04.ts";
+import "./dep_05.ts";
+import "./dep_06.ts";
+import "./dep_07.ts";
+import "./dep_08.ts";
+import "./dep_09.ts";
+import "./dep_10.ts";
+import "./dep_11.ts";
+import "./dep_12.ts";
+import "./dep_13.ts";
+import "./dep_14.ts";
+import "./dep_15.ts";
+import "./dep_16.ts";
+import "./dep_17.ts";
+import "./dep_18.ts";
+import "./dep_19.ts";
+import "./dep_20.ts";
+import "./dep_21.ts";
+import "./dep_22.ts";
+import "./dep_23.ts";
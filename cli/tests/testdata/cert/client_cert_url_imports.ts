@@ -0,0 +1,3 @@
+import { printHello } from "https://localhost:5552/subdir/mod2.ts";
+printHello();
+console.log("success");
@@ -0,0 +1,5 @@
+import { add, sum, VERSION } from "./mod.ts";
+
+console.log(sum(1, 2), add(1, 2));
+// deno-deprecated-ignore
+console.log(VERSION);
@@ -0,0 +1,11 @@
+/** @deprecated Use `add` instead. */
+export function sum(a: number, b: number): number {
+  return a + b;
+}
+
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+/** @deprecated */
+export const VERSION = "1.0.0";
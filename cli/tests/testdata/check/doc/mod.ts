@@ -0,0 +1,27 @@
+/**
+ * Adds two numbers.
+ *
+ * @example
+ * const sum: string = add(1, 2);
+ *
+ * @param a The first number.
+ * @param b The second number.
+ */
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+/**
+ * Subtracts two numbers.
+ *
+ * ```ts
+ * const difference: number = subtract(2, 1);
+ * ```
+ *
+ * ```ts ignore
+ * const ignored: string = subtract(2, 1);
+ * ```
+ */
+export function subtract(a: number, b: number): number {
+  return a - b;
+}
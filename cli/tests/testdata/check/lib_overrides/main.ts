@@ -0,0 +1,4 @@
+import { greet } from "./shared.ts";
+
+const w: Window = window;
+console.log(w, greet());
@@ -0,0 +1,5 @@
+import { add } from "./math.ts";
+
+const  x: number = add(1, 2);
+debugger;
+export const y: string = x;
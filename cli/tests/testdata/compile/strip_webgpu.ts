@@ -0,0 +1,2 @@
+const adapter = await navigator.gpu.requestAdapter();
+console.log(adapter);
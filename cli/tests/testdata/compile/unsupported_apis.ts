@@ -0,0 +1,2 @@
+const name = Deno.args[0] ?? "args";
+await import(`./${name}.ts`);
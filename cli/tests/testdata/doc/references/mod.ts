@@ -0,0 +1,27 @@
+import type { A } from "http://localhost:4545/subdir/export_types.ts";
+import type { Options } from "./types.ts";
+import type { Level, Settings } from "./reexports.ts";
+
+export type { Options } from "./types.ts";
+
+interface Internal {
+  value: number;
+}
+
+/** Creates a value. */
+export function create<T>(options: Options, value: T): A {
+  console.log(options, value);
+  return { a: "a" };
+}
+
+export function internal(): Internal {
+  return { value: 1 };
+}
+
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+export function configure(settings: Settings, level: Level): void {
+  console.log(settings, level);
+}
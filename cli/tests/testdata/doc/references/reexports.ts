@@ -0,0 +1,2 @@
+export type { Config as Settings } from "./config.ts";
+export * from "./types.ts";
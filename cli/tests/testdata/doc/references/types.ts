@@ -0,0 +1,5 @@
+export interface Options {
+  verbose: boolean;
+}
+
+export type Level = "debug" | "info";
@@ -0,0 +1,42 @@
+/**
+ * A module with a symbol of each kind.
+ *
+ * @module
+ */
+
+import { add } from "./ranges.ts";
+
+/**
+ * Adds the coordinates of a pair.
+ *
+ * @param pair The pair to sum.
+ */
+export function sum(pair: Pair): number {
+  return add(pair[0], pair[1]);
+}
+
+export const origin: Pair = [0, 0];
+
+export enum Direction {
+  Up,
+  Down,
+}
+
+export class Point {
+  constructor(public x: number, public y: number) {}
+}
+
+export type Pair = [number, number];
+
+export namespace Shapes {
+  /** A square. */
+  export interface Square {
+    side: number;
+  }
+
+  export const unit = 1;
+}
+
+export interface Circle {
+  radius: number;
+}
@@ -0,0 +1,10 @@
+const a = 1;
+// deno-fmt-ignore-start
+const table = [
+  1,    2,
+  300,  4,
+];
+// deno-fmt-ignore-end
+
+// deno-fmt-ignore-end
+const b = 2;
@@ -0,0 +1,3 @@
+import { shared } from "./shared.ts";
+
+export const mod = `mod ${shared}`;
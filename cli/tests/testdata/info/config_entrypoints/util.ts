@@ -0,0 +1,4 @@
+import { helper } from "./helper.ts";
+import { shared } from "./shared.ts";
+
+export const util = helper(shared);
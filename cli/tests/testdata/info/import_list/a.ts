@@ -0,0 +1,3 @@
+import { shared } from "./shared.ts";
+
+console.log(shared, "a");
@@ -0,0 +1,3 @@
+import { getValue } from "npm:@denotest/types-no-types-entry";
+
+console.log(getValue());
@@ -0,0 +1,4 @@
+// @deno-types="./foo.d.ts"
+import { foo } from "./foo.js";
+
+console.log(foo);
@@ -0,0 +1,4 @@
+import type { Fizzbuzz } from "npm:@denotest/types";
+
+const value: Fizzbuzz = { fizz: "fizz", buzz: "buzz" };
+console.log(value);
@@ -0,0 +1 @@
+console.log(JSON.stringify(Deno.args));
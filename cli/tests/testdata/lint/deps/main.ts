@@ -0,0 +1,3 @@
+import { value } from "used";
+
+console.log(value);
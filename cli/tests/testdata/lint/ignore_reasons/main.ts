@@ -0,0 +1,6 @@
+// deno-lint-ignore no-explicit-any -- the library has no types
+export const a: any = 1;
+// deno-lint-ignore no-explicit-any
+export const b: any = 1;
+// deno-lint-ignore no-explicit-any -- nothing is ignored
+export const c = 1;
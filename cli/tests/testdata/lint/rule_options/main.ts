@@ -0,0 +1,3 @@
+export function handler(ignoredEvent: string, context: string) {
+  const ignoredResult = 1;
+}
@@ -0,0 +1,6 @@
+const specifier = "npm:chalk@5";
+try {
+  await import(specifier);
+} catch (err) {
+  console.log(err.message);
+}
@@ -0,0 +1,11 @@
+import mod from "conditional";
+import client from "conditional/client";
+import clientFoo from "conditional/client/foo";
+import { getSubPathKind } from "conditional/client/bar";
+import { getKind } from "dual/main.mjs";
+
+console.log(mod);
+console.log(client);
+console.log(clientFoo);
+console.log(getSubPathKind());
+console.log(getKind());
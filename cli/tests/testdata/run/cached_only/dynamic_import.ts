@@ -0,0 +1,10 @@
+// --cached-only doesn't restrict the network access of the program itself
+const res = await fetch("http://localhost:4545/assets/hello.txt");
+console.log(await res.text());
+
+const specifier = "http://localhost:4545/run/001_hello.js";
+try {
+  await import(specifier);
+} catch (err) {
+  console.log(err.message);
+}
@@ -0,0 +1,8 @@
+const worker = new Worker(
+  import.meta.resolve("./worker_dynamic_import.js"),
+  { type: "module" },
+);
+worker.onmessage = (e) => {
+  console.log(e.data);
+  worker.terminate();
+};
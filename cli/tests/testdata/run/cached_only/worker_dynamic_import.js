@@ -0,0 +1,6 @@
+const specifier = "http://localhost:4545/run/001_hello.js";
+try {
+  await import(specifier);
+} catch (err) {
+  postMessage(err.message);
+}
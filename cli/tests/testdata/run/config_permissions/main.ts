@@ -0,0 +1,7 @@
+const read = await Deno.permissions.query({
+  name: "read",
+  path: new URL("./data.txt", import.meta.url),
+});
+const net = await Deno.permissions.query({ name: "net", host: "example.com" });
+const env = await Deno.permissions.query({ name: "env" });
+console.log(read.state, net.state, env.state);
@@ -0,0 +1,9 @@
+const start = Date.now();
+while (Date.now() - start < 100) {
+  // blocks the event loop
+}
+await new Promise((resolve) => setTimeout(resolve, 50));
+console.log("done");
+if (Deno.args[0] === "exit") {
+  Deno.exit(3);
+}
@@ -0,0 +1,3 @@
+const listener = Deno.listen({ port: 0 });
+console.log("listening");
+await listener.accept();
@@ -0,0 +1,4 @@
+for (let i = 0; i < 4; i++) {
+  await new Promise((resolve) => setTimeout(resolve, 500));
+}
+console.log("done");
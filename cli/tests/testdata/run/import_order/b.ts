@@ -0,0 +1,3 @@
+import { c } from "./c.ts";
+
+console.log(`b${c}`);
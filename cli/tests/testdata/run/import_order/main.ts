@@ -0,0 +1,4 @@
+import { a } from "./a.ts";
+
+console.log(a);
+await import("./b.ts");
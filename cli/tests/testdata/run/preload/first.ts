@@ -0,0 +1,5 @@
+// the main module only starts after the top-level await settles
+await new Promise((resolve) => setTimeout(resolve, 10));
+console.log("first preload");
+// deno-lint-ignore no-explicit-any
+(globalThis as any).preloaded = ["first"];
@@ -0,0 +1,2 @@
+// deno-lint-ignore no-explicit-any
+console.log("main", (globalThis as any).preloaded);
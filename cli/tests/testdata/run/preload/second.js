@@ -0,0 +1,2 @@
+console.log("second preload");
+globalThis.preloaded.push("second");
@@ -0,0 +1 @@
+console.log(Deno.env.get("REPORT_UNUSED_PERMISSIONS_UNSET") ?? "unset");
@@ -0,0 +1,4 @@
+const { default: specifier } = await import(
+  "test-fixture://fixtures/dynamic.ts"
+);
+console.log(specifier);
@@ -0,0 +1,4 @@
+import a from "test-fixture://fixtures/a.ts?imports=./b.ts";
+
+const value: string = a;
+console.log(value);
@@ -0,0 +1,8 @@
+function recurse(depth: number): number {
+  if (depth === 0) {
+    throw new Error("too deep");
+  }
+  return recurse(depth - 1) + 1;
+}
+
+recurse(20);
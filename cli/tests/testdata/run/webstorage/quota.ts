@@ -0,0 +1,9 @@
+const value = "a".repeat(1024);
+try {
+  for (let i = 0; i < 100; i++) {
+    localStorage.setItem(`key${i}`, value);
+  }
+  console.log("quota not exceeded");
+} catch (err) {
+  console.log(err.name);
+}
@@ -0,0 +1,11 @@
+// `Deno.exit()` in a worker only closes the worker, the exit code of the
+// main thread is unaffected.
+const worker = new Worker(
+  import.meta.resolve("./worker_deno_exit_worker.js"),
+  { type: "module" },
+);
+
+worker.postMessage("go");
+setTimeout(() => {
+  console.log("main still running");
+}, 100);
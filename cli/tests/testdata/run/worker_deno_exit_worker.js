@@ -0,0 +1,4 @@
+self.onmessage = () => {
+  console.log("worker exiting");
+  Deno.exit(3);
+};
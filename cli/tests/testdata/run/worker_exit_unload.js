@@ -0,0 +1,8 @@
+const worker = new Worker(
+  import.meta.resolve("../workers/exit_unload_worker.js"),
+  { type: "module" },
+);
+
+worker.addEventListener("message", (e) => {
+  console.log("worker dispatched", e.data);
+});
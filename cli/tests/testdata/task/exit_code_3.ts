@@ -0,0 +1,2 @@
+console.log("exiting");
+Deno.exit(3);
@@ -0,0 +1,6 @@
+Deno.test("args", () => {
+  const expected = ["staging", "--url=https://staging.example.com"];
+  if (JSON.stringify(Deno.args) !== JSON.stringify(expected)) {
+    throw new Error(`Unexpected args: ${JSON.stringify(Deno.args)}`);
+  }
+});
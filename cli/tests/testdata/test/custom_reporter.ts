@@ -0,0 +1,37 @@
+function basename(origin: string) {
+  return origin.slice(origin.lastIndexOf("/") + 1);
+}
+
+// deno-lint-ignore no-explicit-any
+function describe(event: any) {
+  if (event.step) {
+    return `${"  ".repeat(event.step.level)}${event.step.name}`;
+  }
+  return event.test?.name ?? basename(event.origin);
+}
+
+export default {
+  plan(event: { origin: string; total: number; filteredOut: number }) {
+    console.log(
+      `plan ${basename(event.origin)}: ${event.total} tests, ${event.filteredOut} filtered out`,
+    );
+  },
+  start(event: unknown) {
+    console.log(`start ${describe(event)}`);
+  },
+  // the callbacks may return promises, which are awaited
+  async result(
+    event: { status: string; error: string | null; duration: number },
+  ) {
+    await new Promise((resolve) => setTimeout(resolve, 1));
+    const error = event.error === null ? "" : ` (${event.error.split("\n")[0]})`;
+    console.log(`result ${describe(event)}: ${event.status}${error}`);
+  },
+  summary(
+    event: { passed: number; failed: number; ignored: number; passedSteps: number },
+  ) {
+    console.log(
+      `summary ${event.passed} passed, ${event.failed} failed, ${event.ignored} ignored, ${event.passedSteps} steps passed`,
+    );
+  },
+};
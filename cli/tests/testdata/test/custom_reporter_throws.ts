@@ -0,0 +1,5 @@
+export default {
+  result() {
+    throw new Error("reporter failed");
+  },
+};
@@ -0,0 +1,3 @@
+import { add } from "./helpers.ts";
+
+export { add };
@@ -0,0 +1,9 @@
+Deno.test({
+  name: "exit(0)",
+  sanitizeExit: false,
+  fn() {
+    Deno.exit(0);
+  },
+});
+
+Deno.test("not run", function () {});
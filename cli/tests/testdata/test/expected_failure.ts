@@ -0,0 +1,29 @@
+Deno.test({
+  name: "known bug",
+  expectedFailure: true,
+  fn() {
+    throw new Error("Not fixed yet.");
+  },
+});
+
+Deno.test("known broken step", async (t) => {
+  await t.step({
+    name: "broken step",
+    expectedFailure: true,
+    fn() {
+      throw new Error("Not fixed yet.");
+    },
+  });
+  await t.step("working step", () => {});
+});
+
+Deno.test({
+  name: "known bug with steps",
+  expectedFailure: true,
+  async fn(t) {
+    await t.step("working step", () => {});
+    await t.step("broken step", () => {
+      throw new Error("Not fixed yet.");
+    });
+  },
+});
@@ -0,0 +1,10 @@
+Deno.test("fails 1", () => {
+  throw new Error();
+});
+Deno.test("fails 2", () => {
+  throw new Error();
+});
+Deno.test("fails 3", () => {
+  throw new Error();
+});
+Deno.test("passes", () => {});
@@ -0,0 +1,4 @@
+Deno.test("fails", () => {
+  throw new Error();
+});
+Deno.test("passes", () => {});
@@ -0,0 +1,19 @@
+Deno.test("text matches", (t) => {
+  t.assertGoldenFile("Hello\nWorld\n", "./golden/greeting.txt");
+});
+
+Deno.test("binary matches", (t) => {
+  t.assertGoldenFile(new Uint8Array([0, 1, 2, 255]), "./golden/bytes.bin");
+});
+
+Deno.test("text mismatch", (t) => {
+  t.assertGoldenFile("Hello\nDeno\n", "./golden/greeting.txt");
+});
+
+Deno.test("binary mismatch", (t) => {
+  t.assertGoldenFile(new Uint8Array([0, 1, 3]), "./golden/bytes.bin");
+});
+
+Deno.test("missing golden file", (t) => {
+  t.assertGoldenFile("text", "./golden/missing.txt");
+});
@@ -0,0 +1,17 @@
+Deno.test("passes", () => {});
+
+Deno.test("fails", () => {
+  throw new Error('<boom> & "quoted"');
+});
+
+Deno.test({
+  name: "ignored",
+  ignore: true,
+  fn() {},
+});
+
+Deno.test("with steps", async (t) => {
+  await t.step("step", async (t) => {
+    await t.step("nested step", () => {});
+  });
+});
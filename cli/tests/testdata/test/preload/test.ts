@@ -0,0 +1,6 @@
+Deno.test("sees the preloaded value", () => {
+  // deno-lint-ignore no-explicit-any
+  if ((globalThis as any).preloaded?.[0] !== "first") {
+    throw new Error("preload was not evaluated");
+  }
+});
@@ -0,0 +1,3 @@
+Deno.test("reads an env var", () => {
+  Deno.env.get("REPORT_UNUSED_PERMISSIONS_UNSET");
+});
@@ -0,0 +1,39 @@
+let flakyAttempts = 0;
+Deno.test("flaky", () => {
+  flakyAttempts++;
+  if (flakyAttempts < 3) {
+    throw new Error(`attempt ${flakyAttempts}`);
+  }
+});
+
+// the listener left open by the first attempt is closed before the second one
+let listenerAttempts = 0;
+Deno.test("leaks a listener on the first attempt", () => {
+  listenerAttempts++;
+  const listener = Deno.listen({ hostname: "127.0.0.1", port: 4599 });
+  if (listenerAttempts === 1) {
+    throw new Error("attempt 1");
+  }
+  listener.close();
+});
+
+// the timer left by the first attempt doesn't fire during the second one
+let timerAttempts = 0;
+let timerFired = false;
+Deno.test("leaks a timer on the first attempt", async () => {
+  timerAttempts++;
+  if (timerAttempts === 1) {
+    setTimeout(() => timerFired = true, 50);
+    throw new Error("attempt 1");
+  }
+  await new Promise((resolve) => setTimeout(resolve, 100));
+  if (timerFired) {
+    throw new Error("the timer of the first attempt fired");
+  }
+});
+
+Deno.test("passes", () => {});
+
+Deno.test("fails", () => {
+  throw new Error("always");
+});
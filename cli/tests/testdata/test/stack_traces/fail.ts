@@ -0,0 +1,3 @@
+Deno.test("fail", () => {
+  throw new Error("fail");
+});
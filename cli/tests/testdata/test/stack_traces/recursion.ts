@@ -0,0 +1,10 @@
+function recurse(depth: number): number {
+  if (depth === 0) {
+    throw new Error("too deep");
+  }
+  return recurse(depth - 1) + 1;
+}
+
+Deno.test("recursion", () => {
+  recurse(20);
+});
@@ -0,0 +1,11 @@
+Deno.test("untagged", () => {});
+
+Deno.test({ name: "untagged ignored", ignore: true, fn() {} });
+
+Deno.test({ name: "slow", tags: ["slow"], fn() {} });
+
+Deno.test({ name: "slow ignored", tags: ["slow"], ignore: true, fn() {} });
+
+Deno.test({ name: "net", tags: ["net"], fn() {} });
+
+Deno.test({ name: "slow net", tags: ["slow", "net"], fn() {} });
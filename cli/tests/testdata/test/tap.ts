@@ -0,0 +1,19 @@
+Deno.test("passes", () => {
+  console.log("output");
+});
+
+Deno.test("fails", () => {
+  throw new Error("boom");
+});
+
+Deno.test({
+  name: "ignored",
+  ignore: true,
+  fn() {},
+});
+
+Deno.test("with steps #1", async (t) => {
+  await t.step("step", async (t) => {
+    await t.step("nested step", () => {});
+  });
+});
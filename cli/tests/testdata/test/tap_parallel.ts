@@ -0,0 +1,8 @@
+Deno.test("other module passes", () => {});
+
+Deno.test("other module with steps", async (t) => {
+  await t.step("first step", async () => {
+    await new Promise((resolve) => setTimeout(resolve, 10));
+  });
+  await t.step("second step", () => {});
+});
@@ -0,0 +1,11 @@
+Deno.test("sync loop", () => {
+  while (true) {
+    // never yields to the event loop
+  }
+});
+
+Deno.test("pending timer", { timeout: 500 }, async () => {
+  await new Promise((resolve) => setTimeout(resolve, 60_000));
+});
+
+Deno.test("passes", () => {});
@@ -0,0 +1,13 @@
+Deno.test({
+  name: "fixed bug",
+  expectedFailure: true,
+  fn() {},
+});
+
+Deno.test("fixed step", async (t) => {
+  await t.step({
+    name: "step",
+    expectedFailure: true,
+    fn() {},
+  });
+});
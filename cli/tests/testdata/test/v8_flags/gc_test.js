@@ -0,0 +1,6 @@
+Deno.test("gc is exposed", () => {
+  if (typeof globalThis.gc !== "function") {
+    throw new Error("gc is not exposed");
+  }
+  globalThis.gc();
+});
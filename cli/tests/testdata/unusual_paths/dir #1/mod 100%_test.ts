@@ -0,0 +1,7 @@
+import { add } from "./mod%20100%25.ts";
+
+Deno.test("add", () => {
+  if (add(1, 2) !== 3) {
+    throw new Error("1 + 2 should be 3");
+  }
+});
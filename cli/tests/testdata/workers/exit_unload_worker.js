@@ -0,0 +1,5 @@
+addEventListener("unload", () => {
+  postMessage("unload");
+});
+
+Deno.exit(3);
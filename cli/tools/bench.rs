@@ -4,15 +4,27 @@ use crate::args::BenchOptions;
 use crate::args::CliOptions;
 use crate::args::TypeCheckMode;
 use crate::colors;
+use crate::display::pluralize;
 use crate::display::write_json_to_stdout;
 use crate::graph_util::graph_valid_with_cli_options;
 use crate::ops;
+use crate::ops::bench::MeasureLock;
 use crate::proc_state::ProcState;
+use crate::tools::bench_baseline::relative_origin;
+use crate::tools::bench_baseline::Baseline;
+use crate::tools::bench_baseline::BaselineComparison;
 use crate::tools::test::format_test_error;
+use crate::tools::test::EmptyFiles;
 use crate::tools::test::TestFilter;
+use crate::tools::test_affected::collect_watch_changes;
+use crate::tools::test_affected::describe_changes;
+use crate::util::affinity::CorePool;
 use crate::util::file_watcher;
 use crate::util::file_watcher::ResolutionResult;
 use crate::util::fs::collect_specifiers;
+use crate::util::glob::no_files_found_error;
+use crate::util::memory::MemoryReport;
+use crate::util::memory::MemorySampler;
 use crate::util::path::is_supported_ext;
 use crate::version::get_user_agent;
 use crate::worker::create_main_worker_for_test_or_bench;
@@ -24,7 +36,9 @@ use deno_core::futures::future;
 use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
+use deno_core::serde_json::json;
 use deno_core::ModuleSpecifier;
+use deno_runtime::fmt_errors::StackTraceOptions;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::tokio_util::run_local;
@@ -34,9 +48,11 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::cell::RefCell;
 use std::collections::HashSet;
+use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedSender;
 
@@ -44,12 +60,23 @@ use tokio::sync::mpsc::UnboundedSender;
 struct BenchSpecifierOptions {
   filter: TestFilter,
   json: bool,
+  memory_sampler: Option<MemorySampler>,
+  profile_loop: Option<Option<Duration>>,
+  fail_on_empty_files: bool,
+  save_baseline: Option<PathBuf>,
+  maybe_comparison: Option<Arc<BaselineComparison>>,
+  jobs: NonZeroUsize,
+  warmup_only_parallel: bool,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BenchPlan {
   pub total: usize,
+  /// The number of benches the file registers, including the ignored ones
+  /// and the ones excluded by the filter.
+  pub registered: usize,
+  pub filtered_out: usize,
   pub origin: String,
   pub used_only: bool,
   pub names: Vec<String>,
@@ -62,6 +89,7 @@ pub enum BenchEvent {
   Output(String),
   Register(BenchDescription),
   Wait(usize),
+  Progress(usize, BenchLoopStats),
   Result(usize, BenchResult),
 }
 
@@ -69,6 +97,8 @@ pub enum BenchEvent {
 #[serde(rename_all = "camelCase")]
 pub enum BenchResult {
   Ok(BenchStats),
+  /// The result of a bench run with `--profile-loop`.
+  Looped(BenchLoopStats),
   Failed(Box<JsError>),
 }
 
@@ -78,6 +108,7 @@ pub struct BenchReport {
   pub failed: usize,
   pub failures: Vec<(BenchDescription, Box<JsError>)>,
   pub measurements: Vec<(BenchDescription, BenchStats)>,
+  pub memory: Option<MemoryReport>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
@@ -101,6 +132,24 @@ pub struct BenchStats {
   pub p999: f64,
 }
 
+/// The iterations of a bench run with `--profile-loop` so far.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchLoopStats {
+  pub iterations: u64,
+  /// The time spent in nanoseconds.
+  pub elapsed: f64,
+}
+
+impl BenchLoopStats {
+  fn mean(&self) -> f64 {
+    if self.iterations == 0 {
+      0.0
+    } else {
+      self.elapsed / self.iterations as f64
+    }
+  }
+}
+
 impl BenchReport {
   pub fn new() -> Self {
     Self {
@@ -108,6 +157,7 @@ impl BenchReport {
       failed: 0,
       failures: Vec::new(),
       measurements: Vec::new(),
+      memory: None,
     }
   }
 }
@@ -115,11 +165,17 @@ impl BenchReport {
 fn create_reporter(
   show_output: bool,
   json: bool,
+  maybe_comparison: Option<Arc<BaselineComparison>>,
+  stack_trace_options: StackTraceOptions,
 ) -> Box<dyn BenchReporter + Send> {
   if json {
     return Box::new(JsonReporter::new());
   }
-  Box::new(ConsoleReporter::new(show_output))
+  Box::new(ConsoleReporter::new(
+    show_output,
+    maybe_comparison,
+    stack_trace_options,
+  ))
 }
 
 pub trait BenchReporter {
@@ -129,6 +185,11 @@ pub trait BenchReporter {
   fn report_register(&mut self, desc: &BenchDescription);
   fn report_wait(&mut self, desc: &BenchDescription);
   fn report_output(&mut self, output: &str);
+  fn report_progress(
+    &mut self,
+    desc: &BenchDescription,
+    stats: &BenchLoopStats,
+  );
   fn report_result(&mut self, desc: &BenchDescription, result: &BenchResult);
 }
 
@@ -176,8 +237,15 @@ impl BenchReporter for JsonReporter {
   #[cold]
   fn report_plan(&mut self, _plan: &BenchPlan) {}
 
-  fn report_end(&mut self, _report: &BenchReport) {
-    match write_json_to_stdout(self) {
+  fn report_end(&mut self, report: &BenchReport) {
+    let result = match &report.memory {
+      Some(memory) => write_json_to_stdout(&json!({
+        "results": self,
+        "memory": memory,
+      })),
+      None => write_json_to_stdout(self),
+    };
+    match result {
       Ok(_) => (),
       Err(e) => println!("{e}"),
     }
@@ -189,6 +257,13 @@ impl BenchReporter for JsonReporter {
 
   fn report_output(&mut self, _output: &str) {}
 
+  fn report_progress(
+    &mut self,
+    _desc: &BenchDescription,
+    _stats: &BenchLoopStats,
+  ) {
+  }
+
   fn report_result(&mut self, desc: &BenchDescription, result: &BenchResult) {
     self.0.push(JsonReporterResult::new(
       desc.origin.clone(),
@@ -207,12 +282,25 @@ struct ConsoleReporter {
   group: Option<String>,
   baseline: bool,
   group_measurements: Vec<(BenchDescription, BenchStats)>,
+  /// The measurements of grouped benches of every module, to summarize the
+  /// groups whose benches come from more than one module.
+  all_group_measurements: IndexMap<String, Vec<(BenchDescription, BenchStats)>>,
   options: Option<mitata::reporter::Options>,
+  last_progress: Option<BenchLoopStats>,
+  /// Adds a column with the change from the baseline when set.
+  maybe_comparison: Option<Arc<BaselineComparison>>,
+  stack_trace_options: StackTraceOptions,
 }
 
 impl ConsoleReporter {
-  fn new(show_output: bool) -> Self {
+  fn new(
+    show_output: bool,
+    maybe_comparison: Option<Arc<BaselineComparison>>,
+    stack_trace_options: StackTraceOptions,
+  ) -> Self {
     Self {
+      last_progress: None,
+      maybe_comparison,
       show_output,
       group: None,
       options: None,
@@ -220,6 +308,8 @@ impl ConsoleReporter {
       name: String::new(),
       has_ungrouped: false,
       group_measurements: Vec::new(),
+      all_group_measurements: IndexMap::new(),
+      stack_trace_options,
     }
   }
 }
@@ -263,10 +353,14 @@ impl BenchReporter for ConsoleReporter {
       println!();
     }
 
+    let mut header = mitata::reporter::header(options);
+    if self.maybe_comparison.is_some() {
+      header.push_str(&format!(" {:>9}", "delta"));
+    }
     println!(
       "{}\n{}\n{}",
       colors::gray(&plan.origin),
-      mitata::reporter::header(options),
+      header,
       mitata::reporter::br(options)
     );
   }
@@ -310,11 +404,44 @@ impl BenchReporter for ConsoleReporter {
     }
   }
 
+  fn report_progress(
+    &mut self,
+    desc: &BenchDescription,
+    stats: &BenchLoopStats,
+  ) {
+    let (iterations, elapsed) = match &self.last_progress {
+      Some(last) => (
+        stats.iterations - last.iterations,
+        stats.elapsed - last.elapsed,
+      ),
+      None => (stats.iterations, stats.elapsed),
+    };
+    let per_second = if elapsed > 0.0 {
+      iterations as f64 / (elapsed / 1e9)
+    } else {
+      0.0
+    };
+    println!(
+      "{} {per_second:.0} iter/s {}",
+      colors::gray(format!("{}:", desc.name)),
+      colors::gray(format!("({} total)", stats.iterations)),
+    );
+    self.last_progress = Some(stats.clone());
+  }
+
   fn report_result(&mut self, desc: &BenchDescription, result: &BenchResult) {
     let options = self.options.as_ref().unwrap();
 
     match result {
       BenchResult::Ok(stats) => {
+        if let Some(group) = &desc.group {
+          self
+            .all_group_measurements
+            .entry(group.clone())
+            .or_default()
+            .push((desc.clone(), stats.clone()));
+        }
+
         let mut desc = desc.clone();
 
         if desc.baseline && !self.baseline {
@@ -323,25 +450,38 @@ impl BenchReporter for ConsoleReporter {
           desc.baseline = false;
         }
 
-        println!(
-          "{}",
-          mitata::reporter::benchmark(
-            &desc.name,
-            &mitata::reporter::BenchmarkStats {
-              avg: stats.avg,
-              min: stats.min,
-              max: stats.max,
-              p75: stats.p75,
-              p99: stats.p99,
-              p995: stats.p995,
-            },
-            options
-          )
+        let mut row = mitata::reporter::benchmark(
+          &desc.name,
+          &mitata::reporter::BenchmarkStats {
+            avg: stats.avg,
+            min: stats.min,
+            max: stats.max,
+            p75: stats.p75,
+            p99: stats.p99,
+            p995: stats.p995,
+          },
+          options,
         );
+        if let Some(comparison) = &self.maybe_comparison {
+          row.push(' ');
+          row.push_str(&comparison.format_delta(&desc, stats));
+        }
+        println!("{row}");
 
         self.group_measurements.push((desc, stats.clone()));
       }
 
+      BenchResult::Looped(stats) => {
+        self.last_progress = None;
+        println!(
+          "{}: {} iterations in {}, {} per iteration",
+          desc.name,
+          stats.iterations,
+          format_nanos(stats.elapsed),
+          format_nanos(stats.mean()),
+        );
+      }
+
       BenchResult::Failed(js_error) => {
         println!(
           "{}",
@@ -349,7 +489,7 @@ impl BenchReporter for ConsoleReporter {
             &desc.name,
             &mitata::reporter::Error {
               stack: None,
-              message: format_test_error(js_error),
+              message: format_test_error(js_error, &self.stack_trace_options),
             },
             options
           )
@@ -397,8 +537,98 @@ impl BenchReporter for ConsoleReporter {
     self.group_measurements.clear();
   }
 
-  fn report_end(&mut self, _: &BenchReport) {
+  fn report_end(&mut self, report: &BenchReport) {
     self.report_group_summary();
+
+    for (group, measurements) in &self.all_group_measurements {
+      let origins = measurements
+        .iter()
+        .map(|(desc, _)| desc.origin.as_str())
+        .collect::<HashSet<_>>();
+      if origins.len() < 2 {
+        continue;
+      }
+      if let Some(summary) = format_group_summary(group, measurements) {
+        println!("\n{summary}");
+      }
+    }
+
+    if let Some(memory) = &report.memory {
+      println!("\n{}", colors::gray(memory.to_string()));
+    }
+  }
+}
+
+/// Formats the summary of a group, which compares the mean time of each bench
+/// to the group's first baseline, or else to its fastest bench, and
+/// highlights the fastest bench. Groups of a single bench aren't summarized.
+fn format_group_summary(
+  group: &str,
+  measurements: &[(BenchDescription, BenchStats)],
+) -> Option<String> {
+  if measurements.len() < 2 {
+    return None;
+  }
+  let mut sorted = measurements.iter().collect::<Vec<_>>();
+  sorted.sort_by(|(_, a), (_, b)| a.avg.total_cmp(&b.avg));
+  let fastest = sorted[0];
+  let maybe_baseline = measurements.iter().find(|(desc, _)| desc.baseline);
+  let reference = maybe_baseline.unwrap_or(fastest);
+  let display_name = |desc: &BenchDescription| {
+    format!("{} ({})", desc.name, relative_origin(&desc.origin))
+  };
+  let names = sorted
+    .iter()
+    .map(|(desc, _)| display_name(desc))
+    .collect::<Vec<_>>();
+  let width = names.iter().map(|name| name.chars().count()).max().unwrap();
+
+  let mut summary =
+    colors::bold(format!("summary of group \"{group}\"")).to_string();
+  for (entry, name) in sorted.iter().zip(names) {
+    let (_, stats) = entry;
+    let name = format!("{name:<width$}");
+    let name = if std::ptr::eq(*entry, fastest) {
+      colors::green_bold(name).to_string()
+    } else {
+      name
+    };
+    let comparison = if std::ptr::eq(*entry, reference) {
+      let label = if maybe_baseline.is_some() {
+        "baseline"
+      } else {
+        "fastest"
+      };
+      colors::gray(label).to_string()
+    } else {
+      let ratio = if reference.1.avg == 0.0 {
+        1.0
+      } else {
+        stats.avg / reference.1.avg
+      };
+      let reference_name = display_name(&reference.0);
+      if ratio < 1.0 {
+        format!("{:.2}x faster than {reference_name}", 1.0 / ratio)
+      } else {
+        format!("{ratio:.2}x slower than {reference_name}")
+      }
+    };
+    summary.push_str(&format!("\n  {name}  {comparison}"));
+  }
+  Some(summary)
+}
+
+/// Formats a duration in nanoseconds with the largest unit that keeps the
+/// value above one.
+fn format_nanos(nanos: f64) -> String {
+  if nanos < 1e3 {
+    format!("{nanos:.2} ns")
+  } else if nanos < 1e6 {
+    format!("{:.2} µs", nanos / 1e3)
+  } else if nanos < 1e9 {
+    format!("{:.2} ms", nanos / 1e6)
+  } else {
+    format!("{:.2} s", nanos / 1e9)
   }
 }
 
@@ -428,18 +658,27 @@ async fn bench_specifier(
   specifier: ModuleSpecifier,
   channel: UnboundedSender<BenchEvent>,
   options: BenchSpecifierOptions,
+  maybe_measure_lock: Option<MeasureLock>,
 ) -> Result<(), AnyError> {
   let filter = options.filter;
+  let warmup_only_parallel = maybe_measure_lock.is_some();
   let mut worker = create_main_worker_for_test_or_bench(
     &ps,
     specifier,
     PermissionsContainer::new(permissions),
-    vec![ops::bench::init(channel, filter)],
+    vec![ops::bench::init(
+      channel,
+      filter,
+      options.memory_sampler,
+      maybe_measure_lock,
+    )],
     Default::default(),
   )
   .await?;
 
-  worker.run_bench_specifier().await
+  worker
+    .run_bench_specifier(options.profile_loop, warmup_only_parallel)
+    .await
 }
 
 /// Test a collection of specifiers with test modes concurrently.
@@ -450,79 +689,152 @@ async fn bench_specifiers(
   options: BenchSpecifierOptions,
 ) -> Result<(), AnyError> {
   let log_level = ps.options.log_level();
+  let stack_trace_options = ps.options.stack_trace_options();
+  let jobs = options.jobs.get();
+  if jobs > 1 && !options.warmup_only_parallel {
+    log::warn!(
+      "{}",
+      colors::yellow(format!(
+        "Warning: running {jobs} bench modules in parallel skews their timings. Use --warmup-only-parallel to measure them one at a time."
+      ))
+    );
+  }
 
-  let (sender, mut receiver) = unbounded_channel::<BenchEvent>();
+  // every bench module reports to its own channel, whose events are reported
+  // once the ones of the previous bench modules are, so the results are
+  // grouped by bench module in a deterministic order
+  let (senders, receivers): (Vec<_>, Vec<_>) = specifiers
+    .iter()
+    .map(|_| unbounded_channel::<BenchEvent>())
+    .unzip();
+  let maybe_core_pool = (jobs > 1).then(CorePool::from_allowed_cores);
+  let maybe_measure_lock =
+    options.warmup_only_parallel.then(MeasureLock::default);
 
   let option_for_handles = options.clone();
 
-  let join_handles = specifiers.into_iter().map(move |specifier| {
-    let ps = ps.clone();
-    let permissions = permissions.clone();
-    let specifier = specifier;
-    let sender = sender.clone();
-    let options = option_for_handles.clone();
-
-    tokio::task::spawn_blocking(move || {
-      let future = bench_specifier(ps, permissions, specifier, sender, options);
-
-      run_local(future)
-    })
-  });
+  let join_handles =
+    specifiers
+      .into_iter()
+      .zip(senders)
+      .map(move |(specifier, sender)| {
+        let ps = ps.clone();
+        let permissions = permissions.clone();
+        let options = option_for_handles.clone();
+        let maybe_core_pool = maybe_core_pool.clone();
+        let maybe_measure_lock = maybe_measure_lock.clone();
+
+        tokio::task::spawn_blocking(move || {
+          let _maybe_pinned_core = maybe_core_pool
+            .as_ref()
+            .and_then(|core_pool| core_pool.pin_current_thread());
+          let future = bench_specifier(
+            ps,
+            permissions,
+            specifier,
+            sender,
+            options,
+            maybe_measure_lock,
+          );
+
+          run_local(future)
+        })
+      });
 
   let join_stream = stream::iter(join_handles)
-    .buffer_unordered(1)
+    .buffer_unordered(jobs)
     .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
 
   let handler = {
     tokio::task::spawn(async move {
       let mut used_only = false;
       let mut report = BenchReport::new();
-      let mut reporter =
-        create_reporter(log_level != Some(Level::Error), options.json);
+      let mut empty_files = EmptyFiles::default();
+      let mut reporter = create_reporter(
+        log_level != Some(Level::Error),
+        options.json,
+        options.maybe_comparison.clone(),
+        stack_trace_options,
+      );
       let mut benches = IndexMap::new();
+      let mut last_progress = None;
+
+      for mut receiver in receivers {
+        loop {
+          let event = tokio::select! {
+            event = receiver.recv() => match event {
+              Some(event) => event,
+              None => break,
+            },
+            // Report what the loop did so far, as it only stops by itself when
+            // it has a duration.
+            _ = tokio::signal::ctrl_c(), if options.profile_loop.is_some() => {
+              if let Some((id, stats)) = last_progress.take() {
+                let desc = benches.get(&id).unwrap();
+                reporter.report_result(desc, &BenchResult::Looped(stats));
+              }
+              reporter.report_end(&report);
+              std::process::exit(130);
+            }
+          };
+          match event {
+            BenchEvent::Plan(plan) => {
+              report.total += plan.total;
+              empty_files.record_plan(
+                &plan.origin,
+                plan.registered,
+                plan.filtered_out,
+              );
+              if plan.used_only {
+                used_only = true;
+              }
 
-      while let Some(event) = receiver.recv().await {
-        match event {
-          BenchEvent::Plan(plan) => {
-            report.total += plan.total;
-            if plan.used_only {
-              used_only = true;
+              reporter.report_plan(&plan);
             }
 
-            reporter.report_plan(&plan);
-          }
+            BenchEvent::Register(desc) => {
+              reporter.report_register(&desc);
+              benches.insert(desc.id, desc);
+            }
 
-          BenchEvent::Register(desc) => {
-            reporter.report_register(&desc);
-            benches.insert(desc.id, desc);
-          }
+            BenchEvent::Wait(id) => {
+              reporter.report_wait(benches.get(&id).unwrap());
+            }
 
-          BenchEvent::Wait(id) => {
-            reporter.report_wait(benches.get(&id).unwrap());
-          }
+            BenchEvent::Output(output) => {
+              reporter.report_output(&output);
+            }
 
-          BenchEvent::Output(output) => {
-            reporter.report_output(&output);
-          }
+            BenchEvent::Progress(id, stats) => {
+              reporter.report_progress(benches.get(&id).unwrap(), &stats);
+              last_progress = Some((id, stats));
+            }
 
-          BenchEvent::Result(id, result) => {
-            let desc = benches.get(&id).unwrap();
-            reporter.report_result(desc, &result);
-            match result {
-              BenchResult::Ok(stats) => {
-                report.measurements.push((desc.clone(), stats));
-              }
+            BenchEvent::Result(id, result) => {
+              let desc = benches.get(&id).unwrap();
+              reporter.report_result(desc, &result);
+              match result {
+                BenchResult::Ok(stats) => {
+                  report.measurements.push((desc.clone(), stats));
+                }
 
-              BenchResult::Failed(failure) => {
-                report.failed += 1;
-                report.failures.push((desc.clone(), failure));
-              }
-            };
+                BenchResult::Looped(_) => {
+                  last_progress = None;
+                }
+
+                BenchResult::Failed(failure) => {
+                  report.failed += 1;
+                  report.failures.push((desc.clone(), failure));
+                }
+              };
+            }
           }
         }
       }
 
+      report.memory = options.memory_sampler.map(|sampler| sampler.stop());
       reporter.report_end(&report);
+      empty_files.report("benches");
 
       if used_only {
         return Err(generic_error(
@@ -534,6 +846,18 @@ async fn bench_specifiers(
         return Err(generic_error("Bench failed"));
       }
 
+      if let Some(comparison) = &options.maybe_comparison {
+        comparison.check(&report.measurements, !options.json)?;
+      }
+
+      if let Some(path) = &options.save_baseline {
+        Baseline::new(&report.measurements).write(path)?;
+      }
+
+      if options.fail_on_empty_files {
+        empty_files.check("benches")?;
+      }
+
       Ok(())
     })
   };
@@ -563,6 +887,21 @@ fn is_supported_bench_path(path: &Path) -> bool {
   }
 }
 
+/// Reads the baseline to compare the benches against, if any.
+fn read_baseline(
+  bench_options: &BenchOptions,
+) -> Result<Option<Arc<BaselineComparison>>, AnyError> {
+  let path = match &bench_options.baseline {
+    Some(path) => path,
+    None => return Ok(None),
+  };
+  Ok(Some(Arc::new(BaselineComparison::new(
+    Baseline::read(path)?,
+    bench_options.threshold,
+    bench_options.strict,
+  ))))
+}
+
 pub async fn run_benchmarks(
   cli_options: CliOptions,
   bench_options: BenchOptions,
@@ -578,11 +917,15 @@ pub async fn run_benchmarks(
     collect_specifiers(&bench_options.files, is_supported_bench_path)?;
 
   if specifiers.is_empty() {
-    return Err(generic_error("No bench modules found"));
+    return Err(no_files_found_error(
+      "No bench modules found",
+      &bench_options.files.include,
+    ));
   }
 
   check_specifiers(&ps, permissions.clone(), specifiers.clone()).await?;
 
+  let maybe_comparison = read_baseline(&bench_options)?;
   bench_specifiers(
     &ps,
     &permissions,
@@ -590,6 +933,13 @@ pub async fn run_benchmarks(
     BenchSpecifierOptions {
       filter: TestFilter::from_flag(&bench_options.filter),
       json: bench_options.json,
+      memory_sampler: bench_options.report_memory.map(MemorySampler::start),
+      profile_loop: bench_options.profile_loop,
+      fail_on_empty_files: bench_options.fail_on_empty_files,
+      save_baseline: bench_options.save_baseline.clone(),
+      maybe_comparison,
+      jobs: bench_options.jobs,
+      warmup_only_parallel: bench_options.warmup_only_parallel,
     },
   )
   .await?;
@@ -598,6 +948,17 @@ pub async fn run_benchmarks(
 }
 
 // TODO(bartlomieju): heavy duplication of code with `cli/tools/test.rs`
+/// The bench modules to run in watch mode after a change.
+struct BenchWatchRun {
+  modules: Vec<ModuleSpecifier>,
+  /// Which files triggered the run and how many bench modules they selected,
+  /// printed before the benchmarks run.
+  maybe_description: Option<String>,
+  /// The paths that changed since the previous run, whose parsed sources are
+  /// freed before it, or `None` on the first run.
+  changed_paths: Option<Vec<PathBuf>>,
+}
+
 pub async fn run_benchmarks_with_watch(
   cli_options: CliOptions,
   bench_options: BenchOptions,
@@ -609,6 +970,7 @@ pub async fn run_benchmarks_with_watch(
   let permissions =
     Permissions::from_options(&ps.options.permissions_options())?;
   let no_check = ps.options.type_check_mode() == TypeCheckMode::None;
+  let maybe_comparison = read_baseline(&bench_options)?;
 
   let ps = RefCell::new(ps);
 
@@ -623,92 +985,68 @@ pub async fn run_benchmarks_with_watch(
       let bench_modules =
         collect_specifiers(&bench_options.files, is_supported_bench_path)?;
 
-      let mut paths_to_watch = paths_to_watch_clone;
-      let mut modules_to_reload = if files_changed {
-        Vec::new()
-      } else {
-        bench_modules.clone()
-      };
       let graph = ps.create_graph(bench_modules.clone()).await?;
       graph_valid_with_cli_options(&graph, &bench_modules, &ps.options)?;
 
-      // TODO(@kitsonk) - This should be totally derivable from the graph.
-      for specifier in bench_modules {
-        fn get_dependencies<'a>(
-          graph: &'a deno_graph::ModuleGraph,
-          maybe_module: Option<&'a deno_graph::Module>,
-          // This needs to be accessible to skip getting dependencies if they're already there,
-          // otherwise this will cause a stack overflow with circular dependencies
-          output: &mut HashSet<&'a ModuleSpecifier>,
-          no_check: bool,
-        ) {
-          if let Some(module) = maybe_module {
-            for dep in module.dependencies.values() {
-              if let Some(specifier) = &dep.get_code() {
-                if !output.contains(specifier) {
-                  output.insert(specifier);
-                  get_dependencies(
-                    graph,
-                    graph.get(specifier),
-                    output,
-                    no_check,
-                  );
-                }
-              }
-              if !no_check {
-                if let Some(specifier) = &dep.get_type() {
-                  if !output.contains(specifier) {
-                    output.insert(specifier);
-                    get_dependencies(
-                      graph,
-                      graph.get(specifier),
-                      output,
-                      no_check,
-                    );
-                  }
-                }
-              }
-            }
-          }
-        }
-        // This bench module and all it's dependencies
-        let mut modules = HashSet::new();
-        modules.insert(&specifier);
-        get_dependencies(&graph, graph.get(&specifier), &mut modules, no_check);
-
-        paths_to_watch.extend(
-          modules
-            .iter()
-            .filter_map(|specifier| specifier.to_file_path().ok()),
-        );
+      let changes = collect_watch_changes(
+        &ps,
+        &graph,
+        &bench_modules,
+        changed.as_deref(),
+        no_check,
+      );
+      let mut paths_to_watch = paths_to_watch_clone;
+      paths_to_watch.extend(changes.paths_to_watch);
 
-        if let Some(changed) = &changed {
-          for path in changed.iter().filter_map(|path| {
-            deno_core::resolve_url_or_path(&path.to_string_lossy()).ok()
-          }) {
-            if modules.contains(&path) {
-              modules_to_reload.push(specifier);
-              break;
-            }
-          }
+      let changed = match changed {
+        Some(changed) => changed,
+        None => {
+          return Ok((
+            paths_to_watch,
+            BenchWatchRun {
+              modules: bench_modules,
+              maybe_description: None,
+              changed_paths: None,
+            },
+          ))
         }
-      }
+      };
+      let changed_config_paths = changes.changed_config_paths;
+      let used_changed_paths = changes.used_changed_paths;
+      let affected_modules = changes.affected_modules;
+
+      let (modules, changed_paths) = if !changed_config_paths.is_empty() {
+        (bench_modules, changed_config_paths)
+      } else if bench_options.watch_run_all && !affected_modules.is_empty() {
+        (bench_modules, used_changed_paths)
+      } else {
+        (affected_modules, used_changed_paths)
+      };
+      let run = BenchWatchRun {
+        maybe_description: (!modules.is_empty()).then(|| {
+          format!(
+            "{} selected by {}",
+            pluralize(modules.len(), "bench module", "bench modules"),
+            describe_changes(&changed_paths)
+          )
+        }),
+        modules,
+        changed_paths: Some(changed),
+      };
 
-      Ok((paths_to_watch, modules_to_reload))
+      Ok((paths_to_watch, run))
     }
     .map(move |result| {
       if files_changed
-        && matches!(result, Ok((_, ref modules)) if modules.is_empty())
+        && matches!(result, Ok((_, ref run)) if run.modules.is_empty())
       {
         ResolutionResult::Ignore
       } else {
         match result {
-          Ok((paths_to_watch, modules_to_reload)) => {
-            ResolutionResult::Restart {
-              paths_to_watch,
-              result: Ok(modules_to_reload),
-            }
-          }
+          Ok((paths_to_watch, run)) => ResolutionResult::Restart {
+            paths_to_watch,
+            result: Ok(run),
+          },
           Err(e) => ResolutionResult::Restart {
             paths_to_watch,
             result: Err(e),
@@ -718,17 +1056,25 @@ pub async fn run_benchmarks_with_watch(
     })
   };
 
-  let operation = |modules_to_reload: Vec<ModuleSpecifier>| {
+  let operation = |run: BenchWatchRun| {
     let permissions = &permissions;
     let bench_options = &bench_options;
-    ps.borrow_mut().reset_for_file_watcher();
+    let maybe_comparison = &maybe_comparison;
+    ps.borrow_mut()
+      .reset_for_file_watcher(run.changed_paths.as_deref());
     let ps = ps.borrow().clone();
 
     async move {
+      if let Some(description) = &run.maybe_description {
+        log::info!("{}", description);
+      }
+
+      // the bench modules are collected again, so the ones deleted since the
+      // change was resolved are left out
       let specifiers =
         collect_specifiers(&bench_options.files, is_supported_bench_path)?
           .into_iter()
-          .filter(|specifier| modules_to_reload.contains(specifier))
+          .filter(|specifier| run.modules.contains(specifier))
           .collect::<Vec<ModuleSpecifier>>();
 
       check_specifiers(&ps, permissions.clone(), specifiers.clone()).await?;
@@ -740,6 +1086,13 @@ pub async fn run_benchmarks_with_watch(
         BenchSpecifierOptions {
           filter: TestFilter::from_flag(&bench_options.filter),
           json: bench_options.json,
+          memory_sampler: bench_options.report_memory.map(MemorySampler::start),
+          profile_loop: bench_options.profile_loop,
+          fail_on_empty_files: bench_options.fail_on_empty_files,
+          save_baseline: bench_options.save_baseline.clone(),
+          maybe_comparison: maybe_comparison.clone(),
+          jobs: bench_options.jobs,
+          warmup_only_parallel: bench_options.warmup_only_parallel,
         },
       )
       .await?;
@@ -749,12 +1102,14 @@ pub async fn run_benchmarks_with_watch(
   };
 
   let clear_screen = !ps.borrow().options.no_clear_screen();
+  let stack_trace_options = ps.borrow().options.stack_trace_options();
   file_watcher::watch_func(
     resolver,
     operation,
     file_watcher::PrintConfig {
       job_name: "Bench".to_string(),
       clear_screen,
+      stack_trace_options,
     },
   )
   .await?;
@@ -0,0 +1,303 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The baselines of `deno bench`: the statistics of a run saved with
+//! `--save-baseline`, which later runs are compared against with `--baseline`
+//! to detect regressions.
+
+use std::path::Path;
+
+use deno_core::anyhow::Context;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::colors;
+use crate::tools::bench::BenchDescription;
+use crate::tools::bench::BenchStats;
+use crate::util::display::pluralize;
+use crate::version::get_user_agent;
+
+/// The statistics of a bench, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineBench {
+  /// The module of the bench, relative to the current directory when it's a
+  /// local file so that baselines can be shared between checkouts.
+  pub origin: String,
+  pub group: Option<String>,
+  pub name: String,
+  pub n: u64,
+  pub min: f64,
+  pub max: f64,
+  pub mean: f64,
+  pub p75: f64,
+  pub p99: f64,
+}
+
+impl BaselineBench {
+  fn matches(&self, desc: &BenchDescription) -> bool {
+    self.name == desc.name
+      && self.group == desc.group
+      && self.origin == relative_origin(&desc.origin)
+  }
+
+  fn display_name(&self) -> String {
+    format_name(&self.origin, self.group.as_deref(), &self.name)
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+  pub runtime: String,
+  pub cpu: String,
+  pub benches: Vec<BaselineBench>,
+}
+
+impl Baseline {
+  pub fn new(measurements: &[(BenchDescription, BenchStats)]) -> Self {
+    Self {
+      runtime: format!("{} {}", get_user_agent(), env!("TARGET")),
+      cpu: mitata::cpu::name(),
+      benches: measurements
+        .iter()
+        .map(|(desc, stats)| BaselineBench {
+          origin: relative_origin(&desc.origin),
+          group: desc.group.clone(),
+          name: desc.name.clone(),
+          n: stats.n,
+          min: stats.min,
+          max: stats.max,
+          mean: stats.avg,
+          p75: stats.p75,
+          p99: stats.p99,
+        })
+        .collect(),
+    }
+  }
+
+  pub fn read(path: &Path) -> Result<Self, AnyError> {
+    let text = std::fs::read_to_string(path)
+      .with_context(|| format!("Failed reading baseline {}", path.display()))?;
+    serde_json::from_str(&text)
+      .with_context(|| format!("Invalid baseline {}", path.display()))
+  }
+
+  pub fn write(&self, path: &Path) -> Result<(), AnyError> {
+    std::fs::write(path, serde_json::to_string_pretty(self)?)
+      .with_context(|| format!("Failed writing baseline {}", path.display()))
+  }
+
+  fn get(&self, desc: &BenchDescription) -> Option<&BaselineBench> {
+    self.benches.iter().find(|bench| bench.matches(desc))
+  }
+}
+
+/// Gets the path of a local module relative to the current directory, or
+/// else the specifier unchanged.
+pub fn relative_origin(origin: &str) -> String {
+  let maybe_relative_path = ModuleSpecifier::parse(origin)
+    .ok()
+    .and_then(|specifier| specifier.to_file_path().ok())
+    .zip(std::env::current_dir().ok())
+    .and_then(|(path, cwd)| {
+      path
+        .strip_prefix(cwd)
+        .ok()
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+    });
+  maybe_relative_path.unwrap_or_else(|| origin.to_string())
+}
+
+fn format_name(origin: &str, group: Option<&str>, name: &str) -> String {
+  match group {
+    Some(group) => format!("{group} / {name} ({origin})"),
+    None => format!("{name} ({origin})"),
+  }
+}
+
+/// The comparison of the benches of a run against a baseline.
+#[derive(Debug, Clone)]
+pub struct BaselineComparison {
+  baseline: Baseline,
+  /// The percentage by which the mean time of a bench may increase.
+  threshold: u32,
+  /// Whether benches missing from the baseline or from the run fail the
+  /// comparison.
+  strict: bool,
+}
+
+impl BaselineComparison {
+  pub fn new(baseline: Baseline, threshold: u32, strict: bool) -> Self {
+    Self {
+      baseline,
+      threshold,
+      strict,
+    }
+  }
+
+  /// Gets the change of the mean time from the baseline in percent, or
+  /// `None` when the bench isn't in the baseline.
+  pub fn delta(
+    &self,
+    desc: &BenchDescription,
+    stats: &BenchStats,
+  ) -> Option<f64> {
+    let bench = self.baseline.get(desc)?;
+    if bench.mean == 0.0 {
+      return Some(0.0);
+    }
+    Some((stats.avg - bench.mean) / bench.mean * 100.0)
+  }
+
+  fn is_regression(&self, delta: f64) -> bool {
+    delta > self.threshold as f64
+  }
+
+  /// Formats the delta column of a bench, which is red when the bench
+  /// regressed and green when it improved by more than the threshold.
+  pub fn format_delta(
+    &self,
+    desc: &BenchDescription,
+    stats: &BenchStats,
+  ) -> String {
+    match self.delta(desc, stats) {
+      Some(delta) => {
+        let text = format!("{:>9}", format!("{delta:+.2}%"));
+        if self.is_regression(delta) {
+          colors::red(text).to_string()
+        } else if delta < -(self.threshold as f64) {
+          colors::green(text).to_string()
+        } else {
+          colors::gray(text).to_string()
+        }
+      }
+      None => colors::gray(format!("{:>9}", "new")).to_string(),
+    }
+  }
+
+  /// Prints which benches regressed and which are only in the baseline or
+  /// only in the run, then fails if any bench regressed, or if the benches
+  /// differ when the comparison is strict.
+  pub fn check(
+    &self,
+    measurements: &[(BenchDescription, BenchStats)],
+    print_summary: bool,
+  ) -> Result<(), AnyError> {
+    let mut regressed = Vec::new();
+    let mut added = Vec::new();
+    for (desc, stats) in measurements {
+      let name = format_name(
+        &relative_origin(&desc.origin),
+        desc.group.as_deref(),
+        &desc.name,
+      );
+      match self.delta(desc, stats) {
+        Some(delta) if self.is_regression(delta) => {
+          regressed.push(format!("{name}: {delta:+.2}%"))
+        }
+        Some(_) => {}
+        None => added.push(name),
+      }
+    }
+    let is_run = |bench: &BaselineBench| {
+      measurements.iter().any(|(desc, _)| bench.matches(desc))
+    };
+    let missing = self
+      .baseline
+      .benches
+      .iter()
+      .filter(|bench| !is_run(bench))
+      .map(|bench| bench.display_name())
+      .collect::<Vec<_>>();
+
+    if print_summary {
+      print_list(
+        &format!("Regressed by more than {}%", self.threshold),
+        &regressed,
+      );
+      print_list("Missing from this run", &missing);
+      print_list("Not in the baseline", &added);
+    }
+
+    if !regressed.is_empty() {
+      return Err(generic_error(format!(
+        "{} regressed by more than {}% compared to the baseline",
+        pluralize(regressed.len(), "benchmark", "benchmarks"),
+        self.threshold
+      )));
+    }
+    if self.strict && !(missing.is_empty() && added.is_empty()) {
+      return Err(generic_error(
+        "The benchmarks differ from the baseline's, which fails with --strict",
+      ));
+    }
+    Ok(())
+  }
+}
+
+fn print_list(title: &str, names: &[String]) {
+  if names.is_empty() {
+    return;
+  }
+  println!("\n{title}:");
+  for name in names {
+    println!("  {name}");
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn desc(name: &str) -> BenchDescription {
+    BenchDescription {
+      id: 0,
+      name: name.to_string(),
+      origin: "https://deno.land/x/mod_bench.ts".to_string(),
+      baseline: false,
+      group: None,
+    }
+  }
+
+  fn stats(avg: f64) -> BenchStats {
+    BenchStats {
+      n: 100,
+      min: avg,
+      max: avg,
+      avg,
+      p75: avg,
+      p99: avg,
+      p995: avg,
+      p999: avg,
+    }
+  }
+
+  #[test]
+  fn compares_against_baseline() {
+    let baseline =
+      Baseline::new(&[(desc("a"), stats(100.0)), (desc("b"), stats(100.0))]);
+    assert_eq!(
+      baseline.benches[0].origin,
+      "https://deno.land/x/mod_bench.ts"
+    );
+    let comparison = BaselineComparison::new(baseline.clone(), 5, false);
+    assert_eq!(comparison.delta(&desc("a"), &stats(110.0)), Some(10.0));
+    assert_eq!(comparison.delta(&desc("c"), &stats(110.0)), None);
+
+    // within the threshold, with "b" missing and "c" added
+    let measurements = vec![(desc("a"), stats(104.0)), (desc("c"), stats(1.0))];
+    assert!(comparison.check(&measurements, false).is_ok());
+    let strict = BaselineComparison::new(baseline, 5, true);
+    assert!(strict.check(&measurements, false).is_err());
+
+    let err = comparison
+      .check(&[(desc("a"), stats(106.0))], false)
+      .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "1 benchmark regressed by more than 5% compared to the baseline"
+    );
+  }
+}
@@ -11,6 +11,7 @@ use deno_runtime::colors;
 use crate::args::BundleFlags;
 use crate::args::CliOptions;
 use crate::args::Flags;
+use crate::args::SourceMapMode;
 use crate::args::TsConfigType;
 use crate::args::TypeCheckMode;
 use crate::graph_util::create_graph_and_maybe_check;
@@ -34,7 +35,8 @@ pub async fn bundle(
 
       log::debug!(">>>>> bundle START");
       let ps = ProcState::from_options(cli_options).await?;
-      let graph = create_graph_and_maybe_check(module_specifier, &ps).await?;
+      let graph =
+        create_graph_and_maybe_check(vec![module_specifier], &ps).await?;
 
       let mut paths_to_watch: Vec<PathBuf> = graph
         .specifiers()
@@ -68,15 +70,36 @@ pub async fn bundle(
 
   let operation = |(ps, graph): (ProcState, Arc<deno_graph::ModuleGraph>)| {
     let out_file = &bundle_flags.out_file;
+    let sourcemap = bundle_flags.sourcemap;
     async move {
       // at the moment, we don't support npm specifiers in deno bundle, so show an error
       error_for_any_npm_specifier(&graph)?;
 
-      let bundle_output = bundle_module_graph(graph.as_ref(), &ps)?;
+      let bundle_output = bundle_module_graph(graph.as_ref(), &ps, sourcemap)?;
       log::debug!(">>>>> bundle END");
 
       if let Some(out_file) = out_file {
-        let output_bytes = bundle_output.code.as_bytes();
+        let mut code = bundle_output.code;
+        let maybe_map_out_file = bundle_output.maybe_map.as_ref().map(|_| {
+          let ext = if let Some(curr_ext) = out_file.extension() {
+            format!("{}.map", curr_ext.to_string_lossy())
+          } else {
+            "map".to_string()
+          };
+          out_file.with_extension(ext)
+        });
+        if let Some(map_out_file) = &maybe_map_out_file {
+          // the map is next to the bundle, so it's referred to by its name
+          let map_file_name = map_out_file.file_name().unwrap();
+          if !code.ends_with('\n') {
+            code.push('\n');
+          }
+          code.push_str(&format!(
+            "//# sourceMappingURL={}\n",
+            map_file_name.to_string_lossy()
+          ));
+        }
+        let output_bytes = code.as_bytes();
         let output_len = output_bytes.len();
         util::fs::write_file(out_file, output_bytes, 0o644)?;
         log::info!(
@@ -85,15 +108,11 @@ pub async fn bundle(
           out_file,
           colors::gray(display::human_size(output_len as f64))
         );
-        if let Some(bundle_map) = bundle_output.maybe_map {
+        if let (Some(bundle_map), Some(map_out_file)) =
+          (bundle_output.maybe_map, maybe_map_out_file)
+        {
           let map_bytes = bundle_map.as_bytes();
           let map_len = map_bytes.len();
-          let ext = if let Some(curr_ext) = out_file.extension() {
-            format!("{}.map", curr_ext.to_string_lossy())
-          } else {
-            "map".to_string()
-          };
-          let map_out_file = out_file.with_extension(ext);
           util::fs::write_file(&map_out_file, map_bytes, 0o644)?;
           log::info!(
             "{} {:?} ({})",
@@ -117,6 +136,7 @@ pub async fn bundle(
       util::file_watcher::PrintConfig {
         job_name: "Bundle".to_string(),
         clear_screen: !cli_options.no_clear_screen(),
+        stack_trace_options: cli_options.stack_trace_options(),
       },
     )
     .await?;
@@ -136,6 +156,7 @@ pub async fn bundle(
 fn bundle_module_graph(
   graph: &deno_graph::ModuleGraph,
   ps: &ProcState,
+  sourcemap: Option<SourceMapMode>,
 ) -> Result<deno_emit::BundleEmit, AnyError> {
   log::info!("{} {}", colors::green("Bundle"), graph.roots[0]);
 
@@ -148,11 +169,18 @@ fn bundle_module_graph(
     }
   }
 
+  let mut emit_options: deno_ast::EmitOptions =
+    ts_config_result.ts_config.into();
+  if let Some(sourcemap) = sourcemap {
+    emit_options.inline_source_map = sourcemap == SourceMapMode::Inline;
+    emit_options.source_map = sourcemap == SourceMapMode::External;
+  }
+
   deno_emit::bundle_graph(
     graph,
     deno_emit::BundleOptions {
       bundle_type: deno_emit::BundleType::Module,
-      emit_options: ts_config_result.ts_config.into(),
+      emit_options,
       emit_ignore_directives: true,
     },
   )
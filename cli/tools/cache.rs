@@ -0,0 +1,685 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use deno_ast::MediaType;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::resolve_url_or_path;
+use deno_core::ModuleSpecifier;
+use deno_graph::ModuleAnalyzer;
+use deno_graph::ModuleGraph;
+use deno_graph::ModuleInfo;
+use deno_lockfile::NpmContent;
+use deno_runtime::colors;
+use deno_runtime::ops::tty::ConsoleSize;
+use deno_runtime::permissions::PermissionsContainer;
+use serde::Serialize;
+
+use crate::args::read_import_list;
+use crate::args::CacheFlags;
+use crate::args::Flags;
+use crate::args::Lockfile;
+use crate::graph_util::graph_lock_or_exit;
+use crate::graph_util::graph_valid_with_cli_options;
+use crate::npm::resolve_graph_npm_info;
+use crate::npm::NpmPackageId;
+use crate::proc_state::ProcState;
+use crate::semver::Version;
+use crate::tools::cache_bundle::export_cache_bundle;
+use crate::tools::cache_bundle::import_cache_bundle;
+use crate::tools::emit_dir::emit_to_dir;
+use crate::util::display;
+use crate::util::draw_thread::DrawThread;
+use crate::util::draw_thread::DrawThreadRenderer;
+
+pub async fn cache(
+  flags: Flags,
+  cache_flags: CacheFlags,
+) -> Result<(), AnyError> {
+  if let Some(import_dir) = &cache_flags.import_dir {
+    let ps = ProcState::build(flags).await?;
+    return import_cache_bundle(&ps, import_dir, cache_flags.overwrite);
+  }
+  let json = cache_flags.json;
+  let (summary, modules_total) = match cache_modules(flags, cache_flags).await {
+    Ok(result) => result,
+    Err(err) => {
+      if json {
+        display::write_json_error_to_stdout(&err)?;
+      }
+      return Err(err);
+    }
+  };
+
+  if json {
+    display::write_json_to_stdout(&summary)?;
+  } else {
+    // this is intentionally not logged so that it is still
+    // output when `--quiet` is specified
+    eprintln!("{summary}");
+  }
+
+  if summary.failures.is_empty() {
+    Ok(())
+  } else {
+    let mut message = format!(
+      "Failed to cache {} of {}:",
+      summary.failures.len(),
+      display::pluralize(modules_total, "module", "modules"),
+    );
+    for failure in &summary.failures {
+      message.push_str(&format!("\n  {}", failure.specifier));
+      for line in failure.error.lines() {
+        message.push_str(&format!("\n    {line}"));
+      }
+    }
+    bail!(message)
+  }
+}
+
+/// Caches the modules, returning the summary along with the number of
+/// modules that were requested.
+async fn cache_modules(
+  flags: Flags,
+  cache_flags: CacheFlags,
+) -> Result<(CacheSummary, usize), AnyError> {
+  let ps = ProcState::build(flags).await?;
+  let mut files = cache_flags.files.clone();
+  if let Some(import_list) = &cache_flags.import_list {
+    files.extend(read_import_list(import_list)?);
+  }
+  // When the modules come from an import list, a module that fails is
+  // reported at the end instead of preventing the others from being cached.
+  let keep_going = cache_flags.import_list.is_some();
+  let mut failures = Vec::new();
+  let mut roots = Vec::new();
+  for file in &files {
+    match resolve_url_or_path(file) {
+      Ok(root) => {
+        if !roots.contains(&root) {
+          roots.push(root);
+        }
+      }
+      Err(err) if keep_going => failures.push(CacheFailure {
+        specifier: file.to_string(),
+        error: err.to_string(),
+      }),
+      Err(err) => return Err(err.into()),
+    }
+  }
+  let modules_total = roots.len() + failures.len();
+  let start = Instant::now();
+
+  // Build the graph up front so that progress can be reported while the
+  // modules are being fetched. The subsequent load will reuse the fetched
+  // and analyzed modules.
+  let reporter = Arc::new(GraphProgressReporter::default());
+  let maybe_draw_thread_guard =
+    if !ps.options.is_quiet() && DrawThread::is_supported() {
+      Some(DrawThread::add_entry(reporter.clone()))
+    } else {
+      None
+    };
+  let analyzer = TimedModuleAnalyzer::new(ps.parsed_source_cache.as_analyzer());
+  let mut loader = ps.create_graph_loader();
+  let graph = ps
+    .create_graph_with_options(
+      roots.clone(),
+      &mut loader,
+      &analyzer,
+      Some(&*reporter),
+      !cache_flags.lockfile_only,
+    )
+    .await?;
+  drop(maybe_draw_thread_guard);
+  let graph_elapsed = start.elapsed();
+  let parse_elapsed = analyzer.elapsed();
+
+  if keep_going {
+    roots.retain(|root| {
+      match graph_valid_with_cli_options(&graph, &[root.clone()], &ps.options) {
+        Ok(()) => true,
+        Err(err) => {
+          failures.push(CacheFailure {
+            specifier: root.to_string(),
+            error: err.to_string(),
+          });
+          false
+        }
+      }
+    });
+  }
+
+  let check_start = Instant::now();
+  let mut npm_changes = None;
+  if cache_flags.lockfile_only {
+    npm_changes = Some(update_lockfile_only(&ps, &graph, &roots).await?);
+  } else {
+    if !roots.is_empty() {
+      ps.prepare_module_load(
+        roots.clone(),
+        false,
+        ps.options.ts_type_lib_window(),
+        PermissionsContainer::allow_all(),
+        PermissionsContainer::allow_all(),
+      )
+      .await?;
+    }
+    ps.cache_module_emits()?;
+    if let Some(emit_dir) = &cache_flags.emit_dir {
+      emit_to_dir(&ps, &roots, emit_dir, cache_flags.emit_remote)?;
+    }
+    if let Some(export_dir) = &cache_flags.export_dir {
+      export_cache_bundle(&ps, &graph, export_dir)?;
+    }
+  }
+  let check_elapsed = check_start.elapsed();
+
+  let file_stats = ps.file_fetcher.stats();
+  let npm_stats = ps.npm_cache.stats();
+  let mut fetched = Vec::new();
+  let mut warnings = Vec::new();
+  for module in graph.modules() {
+    let source = match module.specifier.scheme() {
+      "http" | "https"
+        if file_stats.was_downloaded(module.specifier.as_str()) =>
+      {
+        FetchedModuleSource::Downloaded
+      }
+      "http" | "https" => FetchedModuleSource::Cached,
+      _ => FetchedModuleSource::Local,
+    };
+    if let Some(warning) = ps
+      .file_fetcher
+      .get_source(&module.specifier)
+      .and_then(|file| file.maybe_headers)
+      .and_then(|headers| headers.get("x-deno-warning").cloned())
+    {
+      warnings.push(CacheWarning {
+        specifier: module.specifier.to_string(),
+        message: warning,
+      });
+    }
+    fetched.push(FetchedModule {
+      specifier: module.specifier.to_string(),
+      size: module.size(),
+      source,
+    });
+  }
+  let npm_packages = ps.npm_resolver.snapshot().all_packages();
+  let resolved_npm_packages = npm_packages
+    .iter()
+    .map(|package| format!("{}@{}", package.id.name, package.id.version))
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .map(|nv| ResolvedNpmPackage {
+      downloaded: npm_stats.was_downloaded(&nv),
+      package: nv,
+    })
+    .collect();
+  let summary = CacheSummary {
+    modules: graph.modules().count(),
+    modules_downloaded: file_stats.downloaded(),
+    modules_cached: file_stats.cached(),
+    npm_packages: npm_packages.len(),
+    npm_packages_downloaded: npm_stats.downloaded(),
+    bytes_downloaded: file_stats.bytes() + npm_stats.bytes(),
+    elapsed: CacheElapsed {
+      total: start.elapsed().as_millis(),
+      fetch: graph_elapsed.saturating_sub(parse_elapsed).as_millis(),
+      parse: parse_elapsed.as_millis(),
+      check: check_elapsed.as_millis(),
+    },
+    fetched,
+    resolved_npm_packages,
+    warnings,
+    failures,
+    npm_changes,
+  };
+  Ok((summary, modules_total))
+}
+
+/// Resolves the npm packages of the graph and updates the lockfile with them,
+/// without downloading the packages or type checking the modules.
+async fn update_lockfile_only(
+  ps: &ProcState,
+  graph: &ModuleGraph,
+  roots: &[ModuleSpecifier],
+) -> Result<NpmLockfileChanges, AnyError> {
+  let lockfile = match &ps.lockfile {
+    Some(lockfile) => lockfile.clone(),
+    None => bail!(
+      "--lockfile-only requires a lock file. Specify one with --lock or use a configuration file."
+    ),
+  };
+  let previous_npm = {
+    let lockfile = lockfile.lock();
+    if lockfile.overwrite {
+      // the lockfile is rewritten from scratch, so compare with the one on disk
+      Lockfile::new(lockfile.filename.clone(), false)?.content.npm
+    } else {
+      lockfile.content.npm.clone()
+    }
+  };
+
+  if !roots.is_empty() {
+    graph_valid_with_cli_options(graph, roots, &ps.options)?;
+    let graph = graph.segment(roots);
+    graph_lock_or_exit(&graph, &mut lockfile.lock());
+    let npm_package_reqs = resolve_graph_npm_info(&graph).package_reqs;
+    ps.npm_resolver
+      .resolve_package_reqs(npm_package_reqs)
+      .await?;
+  }
+
+  let lockfile = lockfile.lock();
+  lockfile.write()?;
+  Ok(NpmLockfileChanges::new(
+    &previous_npm,
+    &lockfile.content.npm,
+  ))
+}
+
+/// The versions of the npm packages of a lockfile, by package name.
+fn npm_package_versions(
+  npm: &NpmContent,
+) -> BTreeMap<String, BTreeSet<Version>> {
+  let mut versions: BTreeMap<String, BTreeSet<Version>> = BTreeMap::new();
+  for id in npm.packages.keys() {
+    // the same version may be locked several times with different peer
+    // dependencies
+    if let Ok(id) = NpmPackageId::from_serialized(id) {
+      versions.entry(id.name).or_default().insert(id.version);
+    }
+  }
+  versions
+}
+
+fn versions_to_strings(versions: &BTreeSet<Version>) -> Vec<String> {
+  versions.iter().map(|v| v.to_string()).collect()
+}
+
+/// The versions of an npm package before and after updating the lockfile.
+/// The package was added when there were no versions before and removed
+/// when there are none after.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmPackageChange {
+  name: String,
+  from: Vec<String>,
+  to: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmDuplicatePackage {
+  name: String,
+  versions: Vec<String>,
+}
+
+/// The changes to the npm packages of a lockfile, along with how well the
+/// resolved packages are deduplicated.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmLockfileChanges {
+  changes: Vec<NpmPackageChange>,
+  /// The number of locked packages, counting each resolution of the peer
+  /// dependencies of a package version.
+  packages: usize,
+  unique_packages: usize,
+  duplicates: Vec<NpmDuplicatePackage>,
+}
+
+impl NpmLockfileChanges {
+  pub fn new(previous: &NpmContent, current: &NpmContent) -> Self {
+    let previous_versions = npm_package_versions(previous);
+    let current_versions = npm_package_versions(current);
+    let empty = BTreeSet::new();
+    let names = previous_versions
+      .keys()
+      .chain(current_versions.keys())
+      .collect::<BTreeSet<_>>();
+    let changes = names
+      .into_iter()
+      .filter_map(|name| {
+        let from = previous_versions.get(name).unwrap_or(&empty);
+        let to = current_versions.get(name).unwrap_or(&empty);
+        (from != to).then(|| NpmPackageChange {
+          name: name.clone(),
+          from: versions_to_strings(from),
+          to: versions_to_strings(to),
+        })
+      })
+      .collect();
+    let duplicates = current_versions
+      .iter()
+      .filter(|(_, versions)| versions.len() > 1)
+      .map(|(name, versions)| NpmDuplicatePackage {
+        name: name.clone(),
+        versions: versions_to_strings(versions),
+      })
+      .collect();
+    Self {
+      changes,
+      packages: current.packages.len(),
+      unique_packages: current_versions.len(),
+      duplicates,
+    }
+  }
+}
+
+impl std::fmt::Display for NpmLockfileChanges {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if self.changes.is_empty() {
+      writeln!(f, "No changes to the locked npm packages")?;
+    }
+    for change in &self.changes {
+      if change.from.is_empty() {
+        writeln!(
+          f,
+          "  {} {} {}",
+          colors::green("+"),
+          change.name,
+          change.to.join(", ")
+        )?;
+      } else if change.to.is_empty() {
+        writeln!(
+          f,
+          "  {} {} {}",
+          colors::red("-"),
+          change.name,
+          change.from.join(", ")
+        )?;
+      } else {
+        writeln!(
+          f,
+          "  {} {} {} -> {}",
+          colors::yellow("~"),
+          change.name,
+          change.from.join(", "),
+          change.to.join(", ")
+        )?;
+      }
+    }
+    write!(
+      f,
+      "{} locked ({} unique",
+      display::pluralize(self.packages, "npm package", "npm packages"),
+      self.unique_packages,
+    )?;
+    if self.duplicates.is_empty() {
+      write!(f, ")")
+    } else {
+      write!(f, ", {} duplicated:", self.duplicates.len())?;
+      for duplicate in &self.duplicates {
+        write!(f, " {} ({})", duplicate.name, duplicate.versions.join(", "))?;
+      }
+      write!(f, ")")
+    }
+  }
+}
+
+/// A module of an import list that could not be cached.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheFailure {
+  specifier: String,
+  error: String,
+}
+
+/// Where the source of a module of the graph came from.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum FetchedModuleSource {
+  Local,
+  Cached,
+  Downloaded,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FetchedModule {
+  specifier: String,
+  size: usize,
+  source: FetchedModuleSource,
+}
+
+/// An npm package of the resolution, by name and version.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResolvedNpmPackage {
+  package: String,
+  downloaded: bool,
+}
+
+/// A warning sent by the server of a remote module.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheWarning {
+  specifier: String,
+  message: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheElapsed {
+  /// The wall clock time, which includes more than the phases below.
+  total: u128,
+  fetch: u128,
+  parse: u128,
+  check: u128,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheSummary {
+  modules: usize,
+  modules_downloaded: usize,
+  modules_cached: usize,
+  npm_packages: usize,
+  npm_packages_downloaded: usize,
+  bytes_downloaded: u64,
+  elapsed: CacheElapsed,
+  fetched: Vec<FetchedModule>,
+  resolved_npm_packages: Vec<ResolvedNpmPackage>,
+  warnings: Vec<CacheWarning>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  failures: Vec<CacheFailure>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  npm_changes: Option<NpmLockfileChanges>,
+}
+
+impl std::fmt::Display for CacheSummary {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{} {} ({} downloaded, {} from cache)",
+      colors::green("Cached"),
+      display::pluralize(self.modules, "module", "modules"),
+      self.modules_downloaded,
+      self.modules_cached,
+    )?;
+    if self.npm_packages > 0 {
+      write!(
+        f,
+        ", {} ({} downloaded)",
+        display::pluralize(self.npm_packages, "npm package", "npm packages"),
+        self.npm_packages_downloaded,
+      )?;
+    }
+    write!(
+      f,
+      ", {} transferred {}",
+      display::human_size(self.bytes_downloaded as f64),
+      colors::gray(format!(
+        "({} total, fetch {}, parse {}, check {})",
+        display::human_elapsed(self.elapsed.total),
+        display::human_elapsed(self.elapsed.fetch),
+        display::human_elapsed(self.elapsed.parse),
+        display::human_elapsed(self.elapsed.check),
+      )),
+    )?;
+    if let Some(npm_changes) = &self.npm_changes {
+      write!(f, "\n{npm_changes}")?;
+    }
+    Ok(())
+  }
+}
+
+#[derive(Debug)]
+struct GraphProgress {
+  specifier: ModuleSpecifier,
+  modules_done: usize,
+  modules_total: usize,
+}
+
+/// Reports the modules loaded while building a graph to the draw thread.
+#[derive(Debug, Default)]
+struct GraphProgressReporter {
+  maybe_progress: Mutex<Option<GraphProgress>>,
+}
+
+impl deno_graph::source::Reporter for GraphProgressReporter {
+  fn on_load(
+    &self,
+    specifier: &ModuleSpecifier,
+    modules_done: usize,
+    modules_total: usize,
+  ) {
+    *self.maybe_progress.lock() = Some(GraphProgress {
+      specifier: specifier.clone(),
+      modules_done,
+      modules_total,
+    });
+  }
+}
+
+impl DrawThreadRenderer for GraphProgressReporter {
+  fn render(&self, _size: &ConsoleSize) -> String {
+    match &*self.maybe_progress.lock() {
+      Some(progress) => format!(
+        "{} {} {}",
+        colors::green("Fetch"),
+        colors::gray(format!(
+          "[{}/{}]",
+          progress.modules_done, progress.modules_total
+        )),
+        progress.specifier,
+      ),
+      None => String::new(),
+    }
+  }
+}
+
+/// Wraps a module analyzer in order to track the time spent analyzing.
+struct TimedModuleAnalyzer {
+  inner: Box<dyn ModuleAnalyzer>,
+  elapsed: Mutex<Duration>,
+}
+
+impl TimedModuleAnalyzer {
+  pub fn new(inner: Box<dyn ModuleAnalyzer>) -> Self {
+    Self {
+      inner,
+      elapsed: Default::default(),
+    }
+  }
+
+  pub fn elapsed(&self) -> Duration {
+    *self.elapsed.lock()
+  }
+}
+
+impl ModuleAnalyzer for TimedModuleAnalyzer {
+  fn analyze(
+    &self,
+    specifier: &ModuleSpecifier,
+    source: Arc<str>,
+    media_type: MediaType,
+  ) -> Result<ModuleInfo, deno_ast::Diagnostic> {
+    let start = Instant::now();
+    let result = self.inner.analyze(specifier, source, media_type);
+    *self.elapsed.lock() += start.elapsed();
+    result
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use deno_lockfile::NpmPackageInfo;
+  use test_util::strip_ansi_codes;
+
+  fn npm_content(ids: &[&str]) -> NpmContent {
+    NpmContent {
+      specifiers: Default::default(),
+      packages: ids
+        .iter()
+        .map(|id| {
+          (
+            id.to_string(),
+            NpmPackageInfo {
+              integrity: "sha512-test".to_string(),
+              dependencies: Default::default(),
+            },
+          )
+        })
+        .collect(),
+    }
+  }
+
+  fn change(name: &str, from: &[&str], to: &[&str]) -> NpmPackageChange {
+    NpmPackageChange {
+      name: name.to_string(),
+      from: from.iter().map(|v| v.to_string()).collect(),
+      to: to.iter().map(|v| v.to_string()).collect(),
+    }
+  }
+
+  #[test]
+  fn npm_lockfile_changes() {
+    let previous = npm_content(&["a@1.0.0", "b@1.0.0", "c@1.0.0"]);
+    let current = npm_content(&[
+      "a@1.0.0",
+      "b@1.10.0",
+      "b@1.2.0",
+      "d@2.0.0_a@1.0.0",
+      "d@2.0.0",
+    ]);
+    let changes = NpmLockfileChanges::new(&previous, &current);
+    assert_eq!(
+      changes,
+      NpmLockfileChanges {
+        changes: vec![
+          change("b", &["1.0.0"], &["1.2.0", "1.10.0"]),
+          change("c", &["1.0.0"], &[]),
+          change("d", &[], &["2.0.0"]),
+        ],
+        packages: 5,
+        unique_packages: 3,
+        duplicates: vec![NpmDuplicatePackage {
+          name: "b".to_string(),
+          versions: vec!["1.2.0".to_string(), "1.10.0".to_string()],
+        }],
+      }
+    );
+    assert_eq!(
+      strip_ansi_codes(&changes.to_string()),
+      concat!(
+        "  ~ b 1.0.0 -> 1.2.0, 1.10.0\n",
+        "  - c 1.0.0\n",
+        "  + d 2.0.0\n",
+        "5 npm packages locked (3 unique, 1 duplicated: b (1.2.0, 1.10.0))",
+      )
+    );
+
+    let changes = NpmLockfileChanges::new(&current, &current);
+    assert!(changes.changes.is_empty());
+  }
+}
@@ -0,0 +1,565 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Portable cache bundles, for `deno cache --export` and `deno cache --import`.
+//! A bundle holds the cache entries of a module graph with the layout of the
+//! `deps` and `npm` folders of DENO_DIR, so that it can be merged into the
+//! DENO_DIR of another machine.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::io::Write;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_graph::ModuleGraph;
+use deno_runtime::colors;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::cache::CachedUrlMetadata;
+use crate::cache::HttpCache;
+use crate::npm::RealNpmRegistryApi;
+use crate::proc_state::ProcState;
+use crate::semver::Version;
+use crate::tools::emit_dir::path_to_slash;
+use crate::util::checksum;
+use crate::util::display::pluralize;
+use crate::util::fs::resolve_from_cwd;
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+const DEPS_DIR_NAME: &str = "deps";
+const NPM_DIR_NAME: &str = "npm";
+/// Left in a package folder while it's being extracted.
+const NPM_PACKAGE_SYNC_LOCK_FILENAME: &str = ".deno_sync_lock";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheBundleManifest {
+  version: u32,
+  entries: Vec<CacheBundleEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum CacheBundleEntryKind {
+  /// A remote module or redirect, along with its headers.
+  Remote,
+  /// The information of an npm package from the registry.
+  NpmPackageInfo,
+  /// An extracted npm package.
+  NpmPackage,
+}
+
+/// A cache entry, which is imported as a whole.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheBundleEntry {
+  kind: CacheBundleEntryKind,
+  /// The url of a remote module, or the name of an npm package, followed by
+  /// its version for an extracted package.
+  name: String,
+  /// The checksums of the files of the entry, by their path in the bundle.
+  files: BTreeMap<String, String>,
+}
+
+/// Copies the cache entries of the remote modules and npm packages of the
+/// graph to the bundle directory, along with a manifest of their files.
+pub fn export_cache_bundle(
+  ps: &ProcState,
+  graph: &ModuleGraph,
+  bundle_dir: &Path,
+) -> Result<(), AnyError> {
+  let bundle_dir = resolve_from_cwd(bundle_dir)?;
+  if bundle_dir.join(MANIFEST_FILE_NAME).exists() {
+    bail!(
+      "{} already contains a cache bundle. Remove it or export to another directory.",
+      bundle_dir.display()
+    );
+  }
+  let deps_dir = ps.dir.deps_folder_path();
+  let npm_dir = ps.npm_cache.as_readonly().get_cache_location();
+  let http_cache = HttpCache::new(&deps_dir);
+  let mut entries = Vec::new();
+
+  // the redirects are cached as well, to be followed without the network
+  let remote_urls = graph
+    .modules()
+    .map(|module| &module.specifier)
+    .chain(graph.redirects.keys())
+    .filter(|specifier| matches!(specifier.scheme(), "http" | "https"))
+    .collect::<BTreeSet<_>>();
+  for url in remote_urls {
+    let path = match http_cache.get_cache_filename(url) {
+      Some(path) if path.exists() => path,
+      _ => continue,
+    };
+    let metadata_path = CachedUrlMetadata::filename(&path);
+    let mut files = BTreeMap::new();
+    for path in [path, metadata_path] {
+      let relative_path = path.strip_prefix(&deps_dir).unwrap();
+      export_file(
+        &bundle_dir,
+        DEPS_DIR_NAME,
+        &path,
+        relative_path,
+        &mut files,
+      )?;
+    }
+    entries.push(CacheBundleEntry {
+      kind: CacheBundleEntryKind::Remote,
+      name: url.to_string(),
+      files,
+    });
+  }
+
+  let registry_url = RealNpmRegistryApi::default_url();
+  let packages = ps
+    .npm_resolver
+    .snapshot()
+    .all_packages()
+    .into_iter()
+    .map(|package| (package.id.name, package.id.version))
+    .collect::<BTreeSet<_>>();
+  let names = packages
+    .iter()
+    .map(|(name, _)| name.clone())
+    .collect::<BTreeSet<_>>();
+  for name in names {
+    let path = ps
+      .npm_cache
+      .package_name_folder(&name, &registry_url)
+      .join("registry.json");
+    if !path.exists() {
+      continue;
+    }
+    let mut files = BTreeMap::new();
+    let relative_path = path.strip_prefix(&npm_dir).unwrap();
+    export_file(&bundle_dir, NPM_DIR_NAME, &path, relative_path, &mut files)?;
+    entries.push(CacheBundleEntry {
+      kind: CacheBundleEntryKind::NpmPackageInfo,
+      name,
+      files,
+    });
+  }
+  for (name, version) in packages {
+    let folder = ps.npm_cache.package_folder_for_name_and_version(
+      &name,
+      &version,
+      &registry_url,
+    );
+    if !folder.exists() || folder.join(NPM_PACKAGE_SYNC_LOCK_FILENAME).exists()
+    {
+      continue;
+    }
+    let mut files = BTreeMap::new();
+    for path in collect_files(&folder)? {
+      let relative_path = path.strip_prefix(&npm_dir).unwrap();
+      export_file(&bundle_dir, NPM_DIR_NAME, &path, relative_path, &mut files)?;
+    }
+    entries.push(CacheBundleEntry {
+      kind: CacheBundleEntryKind::NpmPackage,
+      name: format!("{name}@{version}"),
+      files,
+    });
+  }
+
+  let manifest = CacheBundleManifest {
+    version: MANIFEST_VERSION,
+    entries,
+  };
+  let mut manifest_text = serde_json::to_string_pretty(&manifest)?;
+  manifest_text.push('\n');
+  std::fs::write(bundle_dir.join(MANIFEST_FILE_NAME), manifest_text)
+    .with_context(|| format!("Writing {}", bundle_dir.display()))?;
+
+  log::info!(
+    "{} {} to {}",
+    colors::green("Exported"),
+    pluralize(manifest.entries.len(), "cache entry", "cache entries"),
+    bundle_dir.display(),
+  );
+  Ok(())
+}
+
+fn export_file(
+  bundle_dir: &Path,
+  dir_name: &str,
+  path: &Path,
+  relative_path: &Path,
+  files: &mut BTreeMap<String, String>,
+) -> Result<(), AnyError> {
+  let bundle_path = Path::new(dir_name).join(relative_path);
+  let output_path = bundle_dir.join(&bundle_path);
+  std::fs::create_dir_all(output_path.parent().unwrap())
+    .with_context(|| format!("Creating {}", output_path.display()))?;
+  let checksum = copy_with_checksum(path, &output_path)?;
+  files.insert(path_to_slash(&bundle_path), checksum);
+  Ok(())
+}
+
+/// The files of a directory and its subdirectories. Symlinks are ignored.
+fn collect_files(dir: &Path) -> Result<Vec<PathBuf>, AnyError> {
+  let mut files = Vec::new();
+  let mut dirs = vec![dir.to_path_buf()];
+  while let Some(dir) = dirs.pop() {
+    let read_dir = std::fs::read_dir(&dir)
+      .with_context(|| format!("Reading {}", dir.display()))?;
+    for entry in read_dir {
+      let entry = entry?;
+      let file_type = entry.file_type()?;
+      if file_type.is_dir() {
+        dirs.push(entry.path());
+      } else if file_type.is_file() {
+        files.push(entry.path());
+      }
+    }
+  }
+  files.sort();
+  Ok(files)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ImportSummary {
+  imported: usize,
+  unchanged: usize,
+  /// The entries that already existed with other content.
+  skipped: Vec<String>,
+}
+
+/// Merges the entries of a bundle into DENO_DIR, after verifying the
+/// checksums of all of its files. Entries already cached with other content
+/// are skipped, unless `overwrite` is set.
+pub fn import_cache_bundle(
+  ps: &ProcState,
+  bundle_dir: &Path,
+  overwrite: bool,
+) -> Result<(), AnyError> {
+  let bundle_dir = resolve_from_cwd(bundle_dir)?;
+  let manifest_path = bundle_dir.join(MANIFEST_FILE_NAME);
+  let manifest_text = std::fs::read_to_string(&manifest_path)
+    .with_context(|| format!("Reading {}", manifest_path.display()))?;
+  let manifest: CacheBundleManifest = serde_json::from_str(&manifest_text)
+    .with_context(|| format!("Parsing {}", manifest_path.display()))?;
+  if manifest.version != MANIFEST_VERSION {
+    bail!(
+      "Unsupported cache bundle version {} in {}, expected {}.",
+      manifest.version,
+      manifest_path.display(),
+      MANIFEST_VERSION
+    );
+  }
+
+  let deps_dir = ps.dir.deps_folder_path();
+  let npm_dir = ps.npm_cache.as_readonly().get_cache_location();
+  // everything is verified before writing anything in DENO_DIR, so that a
+  // corrupted bundle isn't partially imported. The files are copied to a
+  // staging directory while they're verified, so that a large bundle doesn't
+  // have to fit in memory and a file changing in the bundle afterwards isn't
+  // imported unverified.
+  let staging_dir =
+    secure_tempfile::TempDir::new_in(deps_dir.parent().unwrap())
+      .context("Creating the staging directory of the cache bundle import")?;
+  let registry_url = RealNpmRegistryApi::default_url();
+  let mut entries = Vec::with_capacity(manifest.entries.len());
+  for entry in &manifest.entries {
+    // the folder of an npm package is removed when it's overwritten, so it
+    // must not be able to point outside of the npm cache
+    let maybe_npm_package_folder = match entry.kind {
+      CacheBundleEntryKind::NpmPackage => {
+        let (name, version) = parse_npm_package_entry_name(&entry.name)?;
+        let folder = ps.npm_cache.package_folder_for_name_and_version(
+          &name,
+          &version,
+          &registry_url,
+        );
+        if !folder.starts_with(&npm_dir) {
+          bail!(
+            "Invalid npm package in the manifest of the cache bundle: {}",
+            entry.name
+          );
+        }
+        Some(folder)
+      }
+      _ => None,
+    };
+    let mut files = Vec::with_capacity(entry.files.len());
+    for (bundle_path, expected_checksum) in &entry.files {
+      let target_path = resolve_target_path(bundle_path, &deps_dir, &npm_dir)?;
+      let path = bundle_dir.join(bundle_path);
+      let staged_path = staging_dir.path().join(entries.len().to_string());
+      let staged_path = staged_path.with_extension(files.len().to_string());
+      if copy_with_checksum(&path, &staged_path)? != *expected_checksum {
+        bail!(
+          "The checksum of {} doesn't match the manifest of the cache bundle.",
+          path.display()
+        );
+      }
+      files.push(ImportFile {
+        staged_path,
+        target_path,
+        checksum: expected_checksum,
+      });
+    }
+    entries.push((entry, maybe_npm_package_folder, files));
+  }
+
+  let mut summary = ImportSummary::default();
+  for (entry, maybe_npm_package_folder, files) in entries {
+    if files.iter().any(|file| file.target_path.exists()) {
+      if is_entry_unchanged(entry.kind, &files) {
+        summary.unchanged += 1;
+        continue;
+      }
+      if !overwrite {
+        summary.skipped.push(entry.name.clone());
+        continue;
+      }
+      // the files that aren't in the bundle must not be left behind
+      if let Some(folder) = maybe_npm_package_folder {
+        if folder.exists() {
+          std::fs::remove_dir_all(&folder)
+            .with_context(|| format!("Removing {}", folder.display()))?;
+        }
+      }
+    }
+    for file in files {
+      let path = &file.target_path;
+      std::fs::create_dir_all(path.parent().unwrap())
+        .with_context(|| format!("Creating {}", path.display()))?;
+      // the npm cache can be on another file system than the staging
+      // directory
+      std::fs::rename(&file.staged_path, path)
+        .or_else(|_| std::fs::copy(&file.staged_path, path).map(|_| ()))
+        .with_context(|| format!("Writing {}", path.display()))?;
+    }
+    summary.imported += 1;
+  }
+
+  log::info!(
+    "{} {} from {} ({} already cached)",
+    colors::green("Imported"),
+    pluralize(summary.imported, "cache entry", "cache entries"),
+    bundle_dir.display(),
+    summary.unchanged,
+  );
+  if !summary.skipped.is_empty() {
+    log::warn!(
+      "{} Skipped {} already cached with other content, use --overwrite to replace them:",
+      colors::yellow("Warning"),
+      pluralize(summary.skipped.len(), "entry", "entries"),
+    );
+    for name in &summary.skipped {
+      log::warn!("  {name}");
+    }
+  }
+  Ok(())
+}
+
+/// Resolves the path of a file of the bundle in DENO_DIR, refusing the paths
+/// that would escape it.
+fn resolve_target_path(
+  bundle_path: &str,
+  deps_dir: &Path,
+  npm_dir: &Path,
+) -> Result<PathBuf, AnyError> {
+  let path = Path::new(bundle_path);
+  if !path
+    .components()
+    .all(|component| matches!(component, Component::Normal(_)))
+  {
+    bail!("Invalid path in the manifest of the cache bundle: {bundle_path}");
+  }
+  let mut components = path.components();
+  let dir = match components.next() {
+    Some(Component::Normal(name)) if name == DEPS_DIR_NAME => deps_dir,
+    Some(Component::Normal(name)) if name == NPM_DIR_NAME => npm_dir,
+    _ => {
+      bail!("Invalid path in the manifest of the cache bundle: {bundle_path}")
+    }
+  };
+  let relative_path = components.as_path();
+  if relative_path.as_os_str().is_empty() {
+    bail!("Invalid path in the manifest of the cache bundle: {bundle_path}");
+  }
+  Ok(dir.join(relative_path))
+}
+
+/// A verified file of the bundle, copied to the staging directory, and where
+/// it goes in DENO_DIR.
+struct ImportFile<'a> {
+  staged_path: PathBuf,
+  target_path: PathBuf,
+  checksum: &'a str,
+}
+
+/// Copies a file, returning the checksum of the bytes written, which is
+/// computed while they're copied so that it's the one of the copy.
+fn copy_with_checksum(from: &Path, to: &Path) -> Result<String, AnyError> {
+  struct CopyingReader<R: Read, W: Write> {
+    reader: R,
+    writer: W,
+  }
+
+  impl<R: Read, W: Write> Read for CopyingReader<R, W> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+      let read = self.reader.read(buf)?;
+      self.writer.write_all(&buf[..read])?;
+      Ok(read)
+    }
+  }
+
+  let reader = std::fs::File::open(from)
+    .with_context(|| format!("Reading {}", from.display()))?;
+  let writer = std::fs::File::create(to)
+    .with_context(|| format!("Writing {}", to.display()))?;
+  checksum::gen_from_reader(CopyingReader { reader, writer })
+    .with_context(|| format!("Copying {} to {}", from.display(), to.display()))
+}
+
+/// Whether the files of the entry are already cached. The headers of remote
+/// modules include when they were cached, so only the modules are compared.
+fn is_entry_unchanged(
+  kind: CacheBundleEntryKind,
+  files: &[ImportFile],
+) -> bool {
+  files
+    .iter()
+    .filter(|file| {
+      kind != CacheBundleEntryKind::Remote
+        || !file
+          .target_path
+          .to_string_lossy()
+          .ends_with(".metadata.json")
+    })
+    .all(|file| {
+      std::fs::File::open(&file.target_path)
+        .and_then(checksum::gen_from_reader)
+        .map_or(false, |existing| existing == file.checksum)
+    })
+}
+
+/// Parses the name and version of an npm package entry, refusing the names
+/// that aren't npm package names since they're used as paths in DENO_DIR.
+fn parse_npm_package_entry_name(
+  entry_name: &str,
+) -> Result<(String, Version), AnyError> {
+  // the name of scoped packages starts with an `@`
+  match entry_name.rsplit_once('@') {
+    Some((name, version)) if is_valid_npm_package_name(name) => {
+      Ok((name.to_string(), Version::parse_from_npm(version)?))
+    }
+    _ => {
+      bail!(
+        "Invalid npm package in the manifest of the cache bundle: {entry_name}"
+      )
+    }
+  }
+}
+
+fn is_valid_npm_package_name(name: &str) -> bool {
+  fn is_valid_part(part: &str) -> bool {
+    !part.is_empty()
+      && !part.starts_with('.')
+      && !part.contains(['/', '\\', ':'])
+  }
+
+  match name.split_once('/') {
+    Some((scope, name)) => {
+      scope.len() > 1
+        && scope.starts_with('@')
+        && is_valid_part(&scope[1..])
+        && is_valid_part(name)
+    }
+    None => !name.starts_with('@') && is_valid_part(name),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_resolve_target_path() {
+    let deps_dir = Path::new("/deno_dir/deps");
+    let npm_dir = Path::new("/deno_dir/npm");
+    assert_eq!(
+      resolve_target_path("deps/https/deno.land/abc", deps_dir, npm_dir)
+        .unwrap(),
+      deps_dir.join("https").join("deno.land").join("abc"),
+    );
+    assert_eq!(
+      resolve_target_path(
+        "npm/registry.npmjs.org/chalk/5.0.0/package.json",
+        deps_dir,
+        npm_dir
+      )
+      .unwrap(),
+      npm_dir
+        .join("registry.npmjs.org")
+        .join("chalk")
+        .join("5.0.0")
+        .join("package.json"),
+    );
+    for path in ["deps", "gen/abc", "deps/../gen/abc", "/deps/abc"] {
+      assert!(
+        resolve_target_path(path, deps_dir, npm_dir).is_err(),
+        "{path}"
+      );
+    }
+  }
+
+  #[test]
+  fn test_copy_with_checksum() {
+    let temp_dir = test_util::TempDir::new();
+    let bytes = "console.log(1);\n".repeat(10_000);
+    temp_dir.write("a.js", &bytes);
+    let checksum = copy_with_checksum(
+      &temp_dir.path().join("a.js"),
+      &temp_dir.path().join("b.js"),
+    )
+    .unwrap();
+    assert_eq!(checksum, checksum::gen(&[bytes.as_bytes()]));
+    assert_eq!(temp_dir.read_to_string("b.js"), bytes);
+  }
+
+  #[test]
+  fn test_parse_npm_package_entry_name() {
+    assert_eq!(
+      parse_npm_package_entry_name("chalk@5.0.0").unwrap(),
+      (
+        "chalk".to_string(),
+        Version::parse_from_npm("5.0.0").unwrap()
+      )
+    );
+    assert_eq!(
+      parse_npm_package_entry_name("@types/node@18.11.9").unwrap(),
+      (
+        "@types/node".to_string(),
+        Version::parse_from_npm("18.11.9").unwrap()
+      )
+    );
+    assert!(parse_npm_package_entry_name("chalk").is_err());
+    assert!(parse_npm_package_entry_name("@types/node").is_err());
+    for name in [
+      "../../../x@1.0.0",
+      "..@1.0.0",
+      "@types/../../x@1.0.0",
+      "@types/node/x@1.0.0",
+      "chalk/x@1.0.0",
+      "@types//node@1.0.0",
+      "@/node@1.0.0",
+      "..\\..\\x@1.0.0",
+      "/x@1.0.0",
+      "@1.0.0",
+    ] {
+      assert!(parse_npm_package_entry_name(name).is_err(), "{name}");
+    }
+  }
+}
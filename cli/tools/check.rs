@@ -1,26 +1,516 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+use deno_ast::swc::ast::Decl;
+use deno_ast::swc::ast::ExportSpecifier;
+use deno_ast::swc::ast::ModuleDecl;
+use deno_ast::swc::ast::ModuleExportName;
+use deno_ast::swc::ast::ModuleItem;
+use deno_ast::swc::ast::Pat;
+use deno_ast::swc::ast::TsModuleName;
 use deno_ast::MediaType;
 use deno_ast::ModuleSpecifier;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::resolve_url_or_path;
+use deno_core::serde_json::json;
 use deno_graph::ModuleGraph;
 use deno_graph::ModuleKind;
 use deno_runtime::colors;
+use deno_runtime::permissions::PermissionsContainer;
+use log::info;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::Serialize;
 
+use crate::args::CheckFlags;
+use crate::args::Flags;
+use crate::args::LibOverride;
 use crate::args::TsConfig;
 use crate::args::TypeCheckMode;
 use crate::cache::FastInsecureHasher;
 use crate::cache::TypeCheckCache;
+use crate::file_fetcher::File;
 use crate::npm::NpmPackageResolver;
+use crate::proc_state::ProcState;
+use crate::tools::emit_dir::emit_to_dir;
+use crate::tools::test::fetch_inline_files;
+use crate::tools::test::InlineFile;
+use crate::tools::test::InlineFileOptions;
 use crate::tsc;
 use crate::tsc::Diagnostics;
 use crate::tsc::Stats;
+use crate::util::display::write_json_to_stdout;
+use crate::util::file_watcher;
+use crate::util::file_watcher::ResolutionResult;
+use crate::util::file_watcher::WatchDiagnostics;
 use crate::version;
 
+/// Type-checks the files, then writes their transpiled JavaScript to the
+/// emit directory when one is specified.
+pub async fn check_files(
+  flags: Flags,
+  check_flags: CheckFlags,
+) -> Result<(), AnyError> {
+  let ps = ProcState::build(flags).await?;
+  let result = ps.load_and_type_check_files(&check_flags.files).await;
+  let deprecations = if check_flags.deprecations {
+    match result {
+      Ok(()) => Diagnostics::default(),
+      Err(err) => {
+        let diagnostics = err.downcast::<Diagnostics>()?;
+        let errors =
+          diagnostics.filter(|d| (!d.is_deprecation()).then(|| d.clone()));
+        if !errors.is_empty() {
+          return Err(errors.into());
+        }
+        diagnostics
+      }
+    }
+  } else {
+    result?;
+    Diagnostics::default()
+  };
+  if let Some(emit_dir) = &check_flags.emit_dir {
+    let roots = check_flags
+      .files
+      .iter()
+      .map(|file| resolve_url_or_path(file))
+      .collect::<Result<Vec<_>, _>>()?;
+    emit_to_dir(&ps, &roots, emit_dir, check_flags.emit_remote)?;
+  }
+  if check_flags.deprecations {
+    report_deprecations(&deprecations, check_flags.json)?;
+  }
+  Ok(())
+}
+
+/// A usage of a deprecated API in the JSON output of `--deprecations`, with
+/// one-based lines and columns.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeprecationUsage<'a> {
+  file: Option<&'a str>,
+  line: Option<u64>,
+  col: Option<u64>,
+  message: String,
+  declaring_module: Option<&'a str>,
+}
+
+impl<'a> From<&'a tsc::Diagnostic> for DeprecationUsage<'a> {
+  fn from(diagnostic: &'a tsc::Diagnostic) -> Self {
+    let declaration = diagnostic
+      .related_information
+      .as_ref()
+      .and_then(|related_information| related_information.first());
+    Self {
+      file: diagnostic.file_name.as_deref(),
+      line: diagnostic.start.as_ref().map(|start| start.line + 1),
+      col: diagnostic.start.as_ref().map(|start| start.character + 1),
+      message: check_diagnostic_message(diagnostic),
+      declaring_module: declaration
+        .and_then(|declaration| declaration.file_name.as_deref()),
+    }
+  }
+}
+
+/// Prints the usages of deprecated APIs, failing when there are any.
+fn report_deprecations(
+  deprecations: &Diagnostics,
+  json: bool,
+) -> Result<(), AnyError> {
+  if json {
+    let usages = deprecations
+      .iter()
+      .map(DeprecationUsage::from)
+      .collect::<Vec<_>>();
+    write_json_to_stdout(&usages)?;
+  } else {
+    for diagnostic in deprecations.iter() {
+      eprintln!("{diagnostic}\n");
+    }
+  }
+  let count = deprecations.iter().count();
+  match count {
+    0 => Ok(()),
+    1 => Err(generic_error("Found 1 usage of a deprecated API")),
+    _ => Err(generic_error(format!(
+      "Found {count} usages of deprecated APIs"
+    ))),
+  }
+}
+
+/// Type checks the provided files on every change to them, their local
+/// dependencies or the configuration. Runs after the first one only output
+/// the diagnostics that are new along with a summary of how they changed.
+pub async fn check_with_watch(
+  flags: Flags,
+  check_flags: CheckFlags,
+) -> Result<(), AnyError> {
+  let clear_screen = !flags.no_clear_screen;
+  let stack_trace_options = flags.stack_trace_options();
+  let last_exit_code = Arc::new(AtomicI32::new(0));
+  let watch_diagnostics = Arc::new(Mutex::new(WatchDiagnostics::default()));
+
+  let resolver = |changed: Option<Vec<PathBuf>>| {
+    let flags = flags.clone();
+    let files = check_flags.files.clone();
+    async move {
+      // re-create the state on every run so that changes to the
+      // configuration file and import map are respected
+      let result = async {
+        let ps = ProcState::build(flags).await?;
+        let roots = files
+          .iter()
+          .map(|file| resolve_url_or_path(file))
+          .collect::<Result<Vec<_>, _>>()?;
+        let graph = ps.create_graph(roots.clone()).await?;
+        let mut paths_to_watch = graph
+          .specifiers()
+          .filter_map(|(specifier, _)| specifier.to_file_path().ok())
+          .collect::<Vec<_>>();
+        paths_to_watch.extend(ps.options.watch_config_paths());
+        Ok::<_, AnyError>((paths_to_watch, ps, roots))
+      }
+      .await;
+
+      match result {
+        Ok((paths_to_watch, ps, roots)) => {
+          if let Some(changed) = &changed {
+            if !changed.iter().any(|path| paths_to_watch.contains(path)) {
+              return ResolutionResult::Ignore;
+            }
+          }
+          ResolutionResult::Restart {
+            paths_to_watch,
+            result: Ok((ps, roots)),
+          }
+        }
+        Err(err) => ResolutionResult::Restart {
+          paths_to_watch: Vec::new(),
+          result: Err(err),
+        },
+      }
+    }
+  };
+
+  let operation = |(ps, roots): (ProcState, Vec<ModuleSpecifier>)| {
+    let last_exit_code = last_exit_code.clone();
+    let watch_diagnostics = watch_diagnostics.clone();
+    async move {
+      // errors are considered a failure until the run completes
+      last_exit_code.store(1, Ordering::Relaxed);
+      if !clear_screen {
+        file_watcher::print_run_started("Check");
+      }
+      let lib = ps.options.ts_type_lib_window();
+      let result = ps
+        .prepare_module_load(
+          roots,
+          false,
+          lib,
+          PermissionsContainer::allow_all(),
+          PermissionsContainer::allow_all(),
+        )
+        .await;
+      let diagnostics = match result {
+        Ok(()) => Diagnostics::default(),
+        Err(err) => err.downcast::<Diagnostics>()?,
+      };
+
+      let keys = diagnostics
+        .iter()
+        .map(check_diagnostic_key)
+        .collect::<Vec<_>>();
+      let (is_new, maybe_delta) = watch_diagnostics.lock().update(&keys);
+      let mut is_new = is_new.into_iter();
+      let new_diagnostics =
+        diagnostics.filter(|d| is_new.next().unwrap().then(|| d.clone()));
+      if !new_diagnostics.is_empty() {
+        eprintln!("{}: {}", colors::red_bold("error"), new_diagnostics);
+      }
+      if let Some(delta) = maybe_delta {
+        info!("{}", delta);
+      }
+      let exit_code = if diagnostics.is_empty() { 0 } else { 1 };
+      last_exit_code.store(exit_code, Ordering::Relaxed);
+      Ok(())
+    }
+  };
+
+  file_watcher::watch_until_interrupted(
+    file_watcher::watch_func(
+      resolver,
+      operation,
+      file_watcher::PrintConfig {
+        job_name: "Check".to_string(),
+        clear_screen,
+        stack_trace_options,
+      },
+    ),
+    last_exit_code.clone(),
+  )
+  .await
+}
+
+/// Type checks the code blocks in the documentation of the provided files
+/// without executing them. Diagnostics are reported at the position of the
+/// code in the original markdown file or JSDoc comment.
+pub async fn check_doc(
+  flags: Flags,
+  check_flags: CheckFlags,
+) -> Result<(), AnyError> {
+  let ps = ProcState::build(flags).await?;
+  let specifiers = check_flags
+    .files
+    .iter()
+    .map(|file| resolve_url_or_path(file))
+    .collect::<Result<Vec<_>, _>>()?;
+  let mut inline_files = fetch_inline_files(
+    &ps,
+    specifiers,
+    InlineFileOptions {
+      unfenced_examples: true,
+    },
+  )
+  .await?;
+  if inline_files.is_empty() {
+    info!("No code blocks found to check.");
+    return Ok(());
+  }
+
+  let mut origins = HashMap::new();
+  for inline_file in &mut inline_files {
+    if !origins.contains_key(&inline_file.origin) {
+      let file = ps
+        .file_fetcher
+        .fetch(&inline_file.origin, PermissionsContainer::allow_all())
+        .await?;
+      let origin = DocOrigin {
+        lines: file.source.lines().map(ToString::to_string).collect(),
+        exports: get_module_exports(&file)?,
+      };
+      origins.insert(inline_file.origin.clone(), origin);
+    }
+    let exports = &origins[&inline_file.origin].exports;
+    add_implicit_imports(inline_file, exports);
+  }
+
+  let roots = inline_files
+    .iter()
+    .map(|inline_file| inline_file.file.specifier.clone())
+    .collect::<Vec<_>>();
+  let inline_files_by_specifier = inline_files
+    .iter()
+    .map(|inline_file| {
+      (inline_file.file.specifier.to_string(), inline_file.clone())
+    })
+    .collect::<HashMap<_, _>>();
+  for inline_file in inline_files {
+    ps.file_fetcher.insert_cached(inline_file.file);
+  }
+
+  let lib = ps.options.ts_type_lib_window();
+  let result = ps
+    .prepare_module_load(
+      roots,
+      false,
+      lib,
+      PermissionsContainer::allow_all(),
+      PermissionsContainer::allow_all(),
+    )
+    .await;
+  match result {
+    Ok(()) => Ok(()),
+    Err(err) => match err.downcast::<Diagnostics>() {
+      Ok(diagnostics) => Err(
+        diagnostics
+          .filter(|d| {
+            Some(remap_inline_diagnostic(
+              d,
+              &inline_files_by_specifier,
+              &origins,
+            ))
+          })
+          .into(),
+      ),
+      Err(err) => Err(err),
+    },
+  }
+}
+
+/// A file that code blocks were extracted from.
+struct DocOrigin {
+  lines: Vec<String>,
+  exports: Vec<String>,
+}
+
+/// Gets the names exported by a JavaScript or TypeScript module so that they
+/// can be brought into scope for the examples in its JSDoc comments.
+fn get_module_exports(file: &File) -> Result<Vec<String>, AnyError> {
+  if file.media_type == MediaType::Unknown {
+    return Ok(Vec::new());
+  }
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier: file.specifier.to_string(),
+    text_info: deno_ast::SourceTextInfo::new(file.source.clone()),
+    media_type: file.media_type,
+    capture_tokens: false,
+    maybe_syntax: None,
+    scope_analysis: false,
+  })?;
+
+  let mut exports = Vec::new();
+  for item in &parsed_source.module().body {
+    let module_decl = match item {
+      ModuleItem::ModuleDecl(module_decl) => module_decl,
+      ModuleItem::Stmt(_) => continue,
+    };
+    match module_decl {
+      ModuleDecl::ExportDecl(export_decl) => match &export_decl.decl {
+        Decl::Class(decl) => exports.push(decl.ident.sym.to_string()),
+        Decl::Fn(decl) => exports.push(decl.ident.sym.to_string()),
+        Decl::Var(decl) => {
+          for declarator in &decl.decls {
+            if let Pat::Ident(ident) = &declarator.name {
+              exports.push(ident.id.sym.to_string());
+            }
+          }
+        }
+        Decl::TsInterface(decl) => exports.push(decl.id.sym.to_string()),
+        Decl::TsTypeAlias(decl) => exports.push(decl.id.sym.to_string()),
+        Decl::TsEnum(decl) => exports.push(decl.id.sym.to_string()),
+        Decl::TsModule(decl) => {
+          if let TsModuleName::Ident(ident) = &decl.id {
+            exports.push(ident.sym.to_string());
+          }
+        }
+      },
+      ModuleDecl::ExportNamed(named_export) => {
+        for specifier in &named_export.specifiers {
+          if let ExportSpecifier::Named(named) = specifier {
+            let name = named.exported.as_ref().unwrap_or(&named.orig);
+            if let ModuleExportName::Ident(ident) = name {
+              if &*ident.sym != "default" {
+                exports.push(ident.sym.to_string());
+              }
+            }
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  Ok(exports)
+}
+
+/// Imports the exports of the file an example was extracted from that the
+/// example references, but does not import or declare itself.
+fn add_implicit_imports(inline_file: &mut InlineFile, exports: &[String]) {
+  let source = &inline_file.file.source;
+  let names = exports
+    .iter()
+    .filter(|name| {
+      let word_regex =
+        Regex::new(&format!(r"(^|[^\w$]){}($|[^\w$])", regex::escape(name)))
+          .unwrap();
+      let declaration_regex = Regex::new(&format!(
+        r"(import|const|let|var|function|class|interface|type|enum)\b[^;=\n]*[^\w$]{}($|[^\w$])",
+        regex::escape(name)
+      ))
+      .unwrap();
+      word_regex.is_match(source) && !declaration_regex.is_match(source)
+    })
+    .cloned()
+    .collect::<Vec<_>>();
+  if names.is_empty() {
+    return;
+  }
+
+  let new_source = format!(
+    "import {{ {} }} from \"{}\";\n{}",
+    names.join(", "),
+    inline_file.origin,
+    source,
+  );
+  inline_file.file.source = new_source.into();
+  // point any diagnostics on the synthesized import at the start of the block
+  let first_origin = inline_file
+    .line_origins
+    .first()
+    .copied()
+    .unwrap_or_default();
+  inline_file.line_origins.insert(0, first_origin);
+}
+
+/// Maps the position of a diagnostic in a module synthesized from a code
+/// block to its position in the original file.
+fn remap_inline_diagnostic(
+  diagnostic: &tsc::Diagnostic,
+  inline_files: &HashMap<String, InlineFile>,
+  origins: &HashMap<ModuleSpecifier, DocOrigin>,
+) -> tsc::Diagnostic {
+  let mut diagnostic = diagnostic.clone();
+  if let Some(inline_file) = diagnostic
+    .file_name
+    .as_ref()
+    .and_then(|file_name| inline_files.get(file_name))
+  {
+    let remap_position = |position: tsc::Position| match inline_file
+      .line_origins
+      .get(position.line as usize)
+    {
+      Some((line, column)) => tsc::Position {
+        line: *line as u64,
+        character: position.character + *column as u64,
+      },
+      None => position,
+    };
+    diagnostic.file_name = Some(inline_file.origin.to_string());
+    diagnostic.start = diagnostic.start.map(remap_position);
+    diagnostic.end = diagnostic.end.map(remap_position);
+    // show the line as written in the original file so that the underline
+    // lines up with the remapped position
+    if let Some(start) = &diagnostic.start {
+      diagnostic.source_line = origins
+        .get(&inline_file.origin)
+        .and_then(|origin| origin.lines.get(start.line as usize))
+        .cloned();
+    }
+  }
+  diagnostic.related_information =
+    diagnostic.related_information.map(|related_information| {
+      related_information
+        .iter()
+        .map(|d| remap_inline_diagnostic(d, inline_files, origins))
+        .collect()
+    });
+  diagnostic
+}
+
+fn check_diagnostic_key(diagnostic: &tsc::Diagnostic) -> String {
+  file_watcher::diagnostic_key(
+    diagnostic.file_name.as_deref().unwrap_or_default(),
+    diagnostic.code,
+    &check_diagnostic_message(diagnostic),
+    diagnostic.source_line.as_deref().unwrap_or_default(),
+  )
+}
+
+fn check_diagnostic_message(diagnostic: &tsc::Diagnostic) -> String {
+  match (&diagnostic.message_text, &diagnostic.message_chain) {
+    (Some(message_text), _) => message_text.clone(),
+    (None, Some(message_chain)) => message_chain.format_message(0),
+    (None, None) => String::new(),
+  }
+}
+
 /// Options for performing a check of a module graph. Note that the decision to
 /// emit or not is determined by the `ts_config` settings.
 pub struct CheckOptions {
@@ -44,6 +534,12 @@ pub struct CheckOptions {
   /// Although this could be derived from the graph, this helps
   /// speed things up.
   pub has_node_builtin_specifier: bool,
+  /// The libraries to type check the roots matching the patterns with,
+  /// instead of the ones of `ts_config`.
+  pub lib_overrides: Vec<LibOverride>,
+  /// If true, the usages of APIs marked `@deprecated` are reported as
+  /// warnings along with the diagnostics.
+  pub report_deprecations: bool,
 }
 
 /// The result of a check of a module graph.
@@ -62,6 +558,94 @@ pub fn check(
   cache: &TypeCheckCache,
   npm_resolver: &NpmPackageResolver,
   options: CheckOptions,
+) -> Result<CheckResult, AnyError> {
+  let roots_by_lib = group_roots_by_lib(&graph.roots, &options.lib_overrides);
+  if roots_by_lib
+    .iter()
+    .all(|(maybe_index, _)| maybe_index.is_none())
+  {
+    return check_graph(graph, cache, npm_resolver, options);
+  }
+
+  // The roots with different libraries are type checked as separate programs,
+  // so that the globals of one set of libraries don't leak into the modules
+  // of another. Each program is cached on its own, so a change only type
+  // checks the programs with the changed modules again. A module imported by
+  // roots of several groups is type checked in each of their programs, as
+  // its types can depend on the libraries.
+  let segments = roots_by_lib
+    .into_iter()
+    .map(|(maybe_index, roots)| (maybe_index, Arc::new(graph.segment(&roots))))
+    .collect::<Vec<_>>();
+  let mut result = CheckResult::default();
+  for (maybe_index, segment) in &segments {
+    let mut ts_config = options.ts_config.clone();
+    if let Some(index) = maybe_index {
+      ts_config.merge(&json!({ "lib": options.lib_overrides[*index].lib }));
+    }
+    let segment_result = check_graph(
+      segment.clone(),
+      cache,
+      npm_resolver,
+      CheckOptions {
+        type_check_mode: options.type_check_mode,
+        debug: options.debug,
+        maybe_config_specifier: options.maybe_config_specifier.clone(),
+        ts_config,
+        log_checks: options.log_checks,
+        reload: options.reload,
+        has_node_builtin_specifier: options.has_node_builtin_specifier,
+        lib_overrides: Vec::new(),
+        report_deprecations: options.report_deprecations,
+      },
+    )?;
+    // a module which is type checked with its own libraries as well is only
+    // reported from there, as the diagnostics of a shared module can differ
+    // between the libraries
+    let diagnostics = segment_result.diagnostics.filter(|diagnostic| {
+      let maybe_specifier = diagnostic
+        .file_name
+        .as_ref()
+        .and_then(|file_name| ModuleSpecifier::parse(file_name).ok());
+      let is_reported_elsewhere = maybe_specifier.map_or(false, |specifier| {
+        let own_index =
+          LibOverride::find_index(&options.lib_overrides, &specifier);
+        own_index != *maybe_index
+          && segments.iter().any(|(maybe_index, segment)| {
+            *maybe_index == own_index && segment.get(&specifier).is_some()
+          })
+      });
+      (!is_reported_elsewhere).then(|| diagnostic.clone())
+    });
+    result.diagnostics.extend_unique(diagnostics);
+    result.stats.0.extend(segment_result.stats.0);
+  }
+  Ok(result)
+}
+
+/// Groups the roots by the index of the library override that applies to
+/// them, keeping the order of the roots.
+fn group_roots_by_lib(
+  roots: &[ModuleSpecifier],
+  lib_overrides: &[LibOverride],
+) -> Vec<(Option<usize>, Vec<ModuleSpecifier>)> {
+  let mut groups: Vec<(Option<usize>, Vec<ModuleSpecifier>)> = Vec::new();
+  for root in roots {
+    let maybe_index = LibOverride::find_index(lib_overrides, root);
+    match groups.iter_mut().find(|(index, _)| *index == maybe_index) {
+      Some((_, group_roots)) => group_roots.push(root.clone()),
+      None => groups.push((maybe_index, vec![root.clone()])),
+    }
+  }
+  groups
+}
+
+/// Type checks the module graph as a single program.
+fn check_graph(
+  graph: Arc<ModuleGraph>,
+  cache: &TypeCheckCache,
+  npm_resolver: &NpmPackageResolver,
+  options: CheckOptions,
 ) -> Result<CheckResult, AnyError> {
   let check_js = options.ts_config.get_check_js();
   let check_hash = match get_check_hash(&graph, &options) {
@@ -107,6 +691,7 @@ pub fn check(
     maybe_config_specifier: options.maybe_config_specifier,
     maybe_npm_resolver: Some(npm_resolver.clone()),
     maybe_tsbuildinfo,
+    report_deprecations: options.report_deprecations,
     root_names,
   })?;
 
@@ -164,6 +749,7 @@ fn get_check_hash(
     TypeCheckMode::Local => 1,
     TypeCheckMode::None => 2,
   });
+  hasher.write_u8(options.report_deprecations as u8);
   hasher.write(&options.ts_config.as_bytes());
 
   let check_js = options.ts_config.get_check_js();
@@ -0,0 +1,381 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! `deno ci`: checks the formatting of a project, lints it and type-checks it
+//! in one pass. The files are collected and parsed once, and the parsed
+//! sources are shared by the formatter, the linter and the module graph of
+//! the type check.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParsedSource;
+use deno_ast::SourceTextInfo;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::error::JsStackFrame;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_runtime::colors;
+use deno_runtime::fmt_errors::format_location;
+use log::info;
+use serde::Serialize;
+
+use crate::args::CiFlags;
+use crate::args::FilesConfig;
+use crate::args::Flags;
+use crate::args::FmtFlags;
+use crate::args::FmtOptionsConfig;
+use crate::args::LintFlags;
+use crate::cache::ParsedSourceCache;
+use crate::proc_state::ProcState;
+use crate::tools::fmt::format_file;
+use crate::tools::fmt::format_parsed_source;
+use crate::tools::fmt::is_supported_ext_fmt;
+use crate::tools::fmt::read_file_contents;
+use crate::tools::fmt::run_parallelized;
+use crate::tools::fmt::warn_unmatched_ignore_directives;
+use crate::tools::lint::format_diagnostic;
+use crate::tools::lint::CliLinter;
+use crate::tsc;
+use crate::util::diff::diff;
+use crate::util::display::pluralize;
+use crate::util::editorconfig::EditorConfigResolver;
+use crate::util::fs::FileCollector;
+use crate::util::glob::no_files_found_error;
+use crate::util::glob::FilePatterns;
+use crate::util::path::is_supported_ext;
+use crate::util::path::path_to_specifier;
+use crate::util::path::specifier_to_file_path;
+
+/// The problems found in a file by the checks.
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileReport {
+  #[serde(skip)]
+  text: String,
+  #[serde(skip)]
+  maybe_formatted_text: Option<String>,
+  not_formatted: bool,
+  lint: Vec<LintDiagnostic>,
+  check: Vec<tsc::Diagnostic>,
+  errors: Vec<String>,
+}
+
+impl FileReport {
+  fn has_problems(&self) -> bool {
+    self.not_formatted
+      || !self.lint.is_empty()
+      || !self.check.is_empty()
+      || !self.errors.is_empty()
+  }
+}
+
+/// The report of all the checks, as output with `--json`.
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CiReport {
+  /// The reports of the files with problems, by path.
+  files: BTreeMap<String, FileReport>,
+  /// The errors which don't belong to a file.
+  errors: Vec<String>,
+  checked_files: usize,
+  success: bool,
+}
+
+/// Which checks apply to the files collected for the project.
+struct FileChecks {
+  fmt_patterns: Option<FilePatterns>,
+  fmt_options: FmtOptionsConfig,
+  editorconfig: EditorConfigResolver,
+  lint_patterns: Option<FilePatterns>,
+  linter: CliLinter,
+}
+
+pub async fn ci(flags: Flags, ci_flags: CiFlags) -> Result<i32, AnyError> {
+  let ps = ProcState::build(flags).await?;
+  let fmt_options = ps.options.resolve_fmt_options(FmtFlags {
+    check: true,
+    files: ci_flags.files.clone(),
+    ..Default::default()
+  })?;
+  let lint_options = ps.options.resolve_lint_options(LintFlags {
+    files: ci_flags.files.clone(),
+    ..Default::default()
+  })?;
+
+  let cwd = std::env::current_dir()?;
+  let fmt_patterns = fmt_options.files.to_patterns(&cwd)?;
+  let lint_patterns = lint_options.files.to_patterns(&cwd)?;
+  let linter = CliLinter::new(&lint_options)?;
+  if !ci_flags.no_lint && linter.rules().is_empty() {
+    bail!("No lint rules have been configured")
+  }
+
+  let files = collect_ci_files(&[&fmt_options.files, &lint_options.files])?
+    .into_iter()
+    .filter(|path| {
+      fmt_patterns.matches_path(path)
+        || (is_supported_ext(path) && lint_patterns.matches_path(path))
+    })
+    .collect::<Vec<_>>();
+  if files.is_empty() {
+    return Err(no_files_found_error(
+      "No target files found.",
+      &ci_flags.files.include,
+    ));
+  }
+
+  let checks = Arc::new(FileChecks {
+    fmt_patterns: (!ci_flags.no_fmt).then_some(fmt_patterns),
+    fmt_options: fmt_options.options,
+    editorconfig: EditorConfigResolver::default(),
+    lint_patterns: (!ci_flags.no_lint).then_some(lint_patterns),
+    linter,
+  });
+  let reports = Arc::new(Mutex::new(BTreeMap::new()));
+  run_parallelized(files.clone(), {
+    let reports = reports.clone();
+    let parsed_source_cache = ps.parsed_source_cache.clone();
+    move |file_path| {
+      let report = check_file(&file_path, &checks, &parsed_source_cache)?;
+      reports
+        .lock()
+        .insert(file_path.display().to_string(), report);
+      Ok(())
+    }
+  })
+  .await?;
+
+  let mut report = CiReport {
+    files: std::mem::take(&mut *reports.lock()),
+    checked_files: files.len(),
+    ..Default::default()
+  };
+  if !ci_flags.no_check {
+    let roots = files
+      .iter()
+      .filter(|path| is_supported_ext(path))
+      .map(|path| path.to_string_lossy().to_string())
+      .collect::<Vec<_>>();
+    if let Err(err) = ps.load_and_type_check_files(&roots).await {
+      match err.downcast::<tsc::Diagnostics>() {
+        Ok(diagnostics) => add_type_diagnostics(&mut report, &diagnostics),
+        Err(err) => report.errors.push(format!("{err:#}")),
+      }
+    }
+  }
+
+  report
+    .files
+    .retain(|_, file_report| file_report.has_problems());
+  report.success = report.files.is_empty() && report.errors.is_empty();
+  if ci_flags.json {
+    println!("{}", serde_json::to_string_pretty(&report)?);
+  } else {
+    print_report(&report);
+  }
+  Ok(if report.success { 0 } else { 1 })
+}
+
+/// Collects the files of all the checks in one walk of the file system. The
+/// paths are filtered by each check afterwards, so only the paths excluded by
+/// all of them are skipped while walking.
+fn collect_ci_files(files: &[&FilesConfig]) -> Result<Vec<PathBuf>, AnyError> {
+  let include = if files.iter().any(|files| files.include.is_empty()) {
+    Vec::new()
+  } else {
+    files
+      .iter()
+      .flat_map(|files| files.include.iter().cloned())
+      .collect()
+  };
+  let exclude = files[0]
+    .exclude
+    .iter()
+    .filter(|path| files[1..].iter().all(|f| f.exclude.contains(path)))
+    .cloned()
+    .collect::<Vec<_>>();
+  let mut collected = FileCollector::new(is_supported_ext_fmt)
+    .ignore_git_folder()
+    .ignore_node_modules()
+    .add_ignore_paths(&exclude)
+    .collect_files(&include)?;
+  collected.sort();
+  collected.dedup();
+  Ok(collected)
+}
+
+/// Parses a JavaScript or TypeScript file with everything the formatter and
+/// the linter need, then stores it so that building the module graph doesn't
+/// parse it again.
+fn parse_file(
+  file_path: &Path,
+  text: &str,
+  parsed_source_cache: &ParsedSourceCache,
+) -> Result<ParsedSource, AnyError> {
+  let specifier = path_to_specifier(file_path)?;
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier: specifier.to_string(),
+    text_info: SourceTextInfo::new(text.into()),
+    media_type: MediaType::from(file_path),
+    capture_tokens: true,
+    scope_analysis: true,
+    maybe_syntax: None,
+  })?;
+  parsed_source_cache
+    .as_store()
+    .set_parsed_source(specifier, parsed_source.clone());
+  Ok(parsed_source)
+}
+
+fn check_file(
+  file_path: &Path,
+  checks: &FileChecks,
+  parsed_source_cache: &ParsedSourceCache,
+) -> Result<FileReport, AnyError> {
+  let mut report = FileReport {
+    text: read_file_contents(file_path)?.text,
+    ..Default::default()
+  };
+  let matches = |maybe_patterns: &Option<FilePatterns>| {
+    maybe_patterns
+      .as_ref()
+      .map_or(false, |patterns| patterns.matches_path(file_path))
+  };
+  let maybe_parsed_source = if is_supported_ext(file_path) {
+    match parse_file(file_path, &report.text, parsed_source_cache) {
+      Ok(parsed_source) => Some(parsed_source),
+      Err(err) => {
+        report.errors.push(err.to_string());
+        return Ok(report);
+      }
+    }
+  } else {
+    None
+  };
+
+  if matches(&checks.fmt_patterns) {
+    warn_unmatched_ignore_directives(file_path, &report.text);
+    let properties = checks.editorconfig.properties(file_path);
+    let fmt_options = checks.fmt_options.with_editorconfig(&properties);
+    let result = match &maybe_parsed_source {
+      Some(parsed_source) => format_parsed_source(parsed_source, &fmt_options),
+      None => format_file(file_path, &report.text, &fmt_options),
+    };
+    match result {
+      Ok(maybe_formatted_text) => {
+        report.not_formatted = maybe_formatted_text.is_some();
+        report.maybe_formatted_text = maybe_formatted_text;
+      }
+      Err(err) => report
+        .errors
+        .push(format!("Error checking formatting: {err}")),
+    }
+  }
+
+  let maybe_lint_source = maybe_parsed_source
+    .as_ref()
+    .filter(|_| matches(&checks.lint_patterns));
+  if let Some(parsed_source) = maybe_lint_source {
+    let mut diagnostics = checks.linter.lint_parsed_source(parsed_source);
+    diagnostics
+      .sort_by_key(|d| (d.range.start.line_index, d.range.start.column_index));
+    report.lint = diagnostics;
+  }
+
+  Ok(report)
+}
+
+/// Adds the type diagnostics to the reports of their files.
+fn add_type_diagnostics(report: &mut CiReport, diagnostics: &tsc::Diagnostics) {
+  for diagnostic in diagnostics.iter() {
+    let maybe_path = diagnostic
+      .file_name
+      .as_ref()
+      .and_then(|file_name| ModuleSpecifier::parse(file_name).ok())
+      .map(|specifier| match specifier_to_file_path(&specifier) {
+        Ok(path) => path.display().to_string(),
+        Err(_) => specifier.to_string(),
+      });
+    match maybe_path {
+      Some(path) => report
+        .files
+        .entry(path)
+        .or_default()
+        .check
+        .push(diagnostic.clone()),
+      None => report.errors.push(diagnostic.to_string()),
+    }
+  }
+}
+
+fn print_report(report: &CiReport) {
+  let mut not_formatted_count = 0;
+  let mut lint_count = 0;
+  let mut type_count = 0;
+  let mut error_count = report.errors.len();
+  for (path, file_report) in &report.files {
+    info!("{} {}", colors::red_bold("error:"), colors::bold(path));
+    if let Some(formatted_text) = &file_report.maybe_formatted_text {
+      not_formatted_count += 1;
+      info!("{}", colors::bold("Not formatted:"));
+      info!("{}", diff(&file_report.text, formatted_text));
+    }
+    let source_lines = file_report.text.split('\n').collect::<Vec<_>>();
+    for d in &file_report.lint {
+      lint_count += 1;
+      let message_line = format!("({}) {}", colors::red(&d.code), &d.message);
+      let message = format_diagnostic(
+        &d.code,
+        &message_line,
+        &source_lines,
+        &d.range,
+        d.hint.as_ref(),
+        &format_location(&JsStackFrame::from_location(
+          Some(path.clone()),
+          Some(d.range.start.line_index as i64 + 1),
+          Some(d.range.start.column_index as i64 + 1),
+        )),
+      );
+      info!("{}\n", message);
+    }
+    for d in &file_report.check {
+      type_count += 1;
+      info!("{}\n", d);
+    }
+    for err in &file_report.errors {
+      error_count += 1;
+      info!("{}\n", err);
+    }
+  }
+  for err in &report.errors {
+    info!("{} {}\n", colors::red_bold("error:"), err);
+  }
+
+  info!(
+    "Checked {}",
+    pluralize(report.checked_files, "file", "files")
+  );
+  if !report.success {
+    let problems = [
+      (
+        not_formatted_count,
+        "not formatted file",
+        "not formatted files",
+      ),
+      (lint_count, "lint problem", "lint problems"),
+      (type_count, "type error", "type errors"),
+      (error_count, "error", "errors"),
+    ]
+    .into_iter()
+    .filter(|(count, _, _)| *count > 0)
+    .map(|(count, singular, plural)| pluralize(count, singular, plural))
+    .collect::<Vec<_>>();
+    info!("Found {}", problems.join(", "));
+  }
+}
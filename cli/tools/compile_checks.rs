@@ -0,0 +1,400 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Finds the code which doesn't work in executables made by `deno compile`,
+//! so that it's reported at compile time instead of when the executable runs.
+
+use std::fmt;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::noop_visit_type;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::MediaType;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRangedForSpanned;
+use deno_core::error::AnyError;
+use deno_core::resolve_import;
+use deno_core::ModuleSpecifier;
+use deno_graph::ModuleGraph;
+use deno_graph::ModuleKind;
+
+use crate::cache::ParsedSourceCache;
+
+/// Tells whether a specifier imported by a module is embedded.
+type IsEmbeddedFn<'a> = dyn Fn(&str) -> bool + 'a;
+
+/// Code which doesn't work in compiled executables.
+struct Limitation {
+  /// The name of the limitation, shown with the warnings.
+  code: &'static str,
+  message: &'static str,
+  matches: fn(&ast::Expr, &IsEmbeddedFn<'_>) -> bool,
+}
+
+/// The functions of the `Deno` namespace which read a file at a path.
+const FILE_READ_APIS: &[&str] = &[
+  "readFile",
+  "readFileSync",
+  "readTextFile",
+  "readTextFileSync",
+  "open",
+  "openSync",
+];
+
+/// The limitations of compiled executables, where `matches` is called for
+/// each expression with a function telling whether a specifier imported by
+/// the module is embedded.
+const LIMITATIONS: &[Limitation] = &[
+  Limitation {
+    code: "worker",
+    message: "Workers aren't supported in compiled executables, and creating one aborts the executable.",
+    matches: |expr, _| match expr {
+      ast::Expr::New(new_expr) => is_ident(&new_expr.callee, "Worker"),
+      _ => false,
+    },
+  },
+  Limitation {
+    code: "dlopen",
+    message: "Dynamic libraries aren't embedded in compiled executables, so the library must exist at this path where the executable runs.",
+    matches: |expr, _| call_of_deno_api(expr, &["dlopen"]).is_some(),
+  },
+  Limitation {
+    code: "dynamic-import",
+    message: "The module imported can't be known at compile time, so it isn't embedded in the executable.",
+    matches: |expr, _| match expr {
+      ast::Expr::Call(call_expr) => {
+        matches!(call_expr.callee, ast::Callee::Import(_))
+          && call_expr
+            .args
+            .first()
+            .map_or(false, |arg| !is_static_string(&arg.expr))
+      }
+      _ => false,
+    },
+  },
+  Limitation {
+    code: "import-meta-resolve",
+    message: "The module resolved isn't embedded in the executable, as it isn't imported by the program.",
+    matches: |expr, is_embedded| {
+      let call_expr = match expr {
+        ast::Expr::Call(call_expr) => call_expr,
+        _ => return false,
+      };
+      let is_import_meta_resolve = match &call_expr.callee {
+        ast::Callee::Expr(callee) => match callee.as_ref() {
+          ast::Expr::Member(member_expr) => {
+            matches!(member_expr.obj.as_ref(), ast::Expr::MetaProp(_))
+              && is_prop(&member_expr.prop, "resolve")
+          }
+          _ => false,
+        },
+        _ => false,
+      };
+      if !is_import_meta_resolve {
+        return false;
+      }
+      match call_expr.args.first().map(|arg| arg.expr.as_ref()) {
+        Some(ast::Expr::Lit(ast::Lit::Str(str_lit))) => {
+          !is_embedded(&str_lit.value)
+        }
+        Some(expr) => !is_static_string(expr),
+        None => false,
+      }
+    },
+  },
+  Limitation {
+    code: "module-relative-file",
+    message: "Files read relative to `import.meta.url` aren't embedded in the executable, so they're read from where the module was at compile time.",
+    matches: |expr, _| match call_of_deno_api(expr, FILE_READ_APIS) {
+      Some(call_expr) => call_expr
+        .args
+        .first()
+        .map_or(false, |arg| is_import_meta_relative_url(&arg.expr)),
+      None => false,
+    },
+  },
+];
+
+/// The code which needs the capabilities that the runtime embedded by
+/// `deno compile --strip` leaves out, which only exist with `--unstable`.
+const STRIPPED_LIMITATIONS: &[Limitation] = &[Limitation {
+  code: "webgpu",
+  message:
+    "The WebGPU API isn't included in executables compiled with --strip.",
+  matches: |expr, _| match expr {
+    ast::Expr::Member(member_expr) => {
+      is_ident(&member_expr.obj, "navigator")
+        && is_prop(&member_expr.prop, "gpu")
+    }
+    _ => false,
+  },
+}];
+
+fn is_ident(expr: &ast::Expr, name: &str) -> bool {
+  matches!(expr, ast::Expr::Ident(ident) if &*ident.sym == name)
+}
+
+fn is_prop(prop: &ast::MemberProp, name: &str) -> bool {
+  matches!(prop, ast::MemberProp::Ident(ident) if &*ident.sym == name)
+}
+
+/// Whether the expression is a string known at compile time.
+fn is_static_string(expr: &ast::Expr) -> bool {
+  match expr {
+    ast::Expr::Lit(ast::Lit::Str(_)) => true,
+    ast::Expr::Tpl(tpl) => tpl.exprs.is_empty(),
+    _ => false,
+  }
+}
+
+/// Gets the call, if the expression calls one of the functions of the
+/// `Deno` namespace.
+fn call_of_deno_api<'a>(
+  expr: &'a ast::Expr,
+  names: &[&str],
+) -> Option<&'a ast::CallExpr> {
+  let call_expr = match expr {
+    ast::Expr::Call(call_expr) => call_expr,
+    _ => return None,
+  };
+  match &call_expr.callee {
+    ast::Callee::Expr(callee) => match callee.as_ref() {
+      ast::Expr::Member(member_expr)
+        if is_ident(&member_expr.obj, "Deno")
+          && names.iter().any(|name| is_prop(&member_expr.prop, name)) =>
+      {
+        Some(call_expr)
+      }
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+/// Whether the expression is `new URL(..., import.meta.url)`.
+fn is_import_meta_relative_url(expr: &ast::Expr) -> bool {
+  let new_expr = match expr {
+    ast::Expr::New(new_expr) if is_ident(&new_expr.callee, "URL") => new_expr,
+    _ => return false,
+  };
+  let base = match new_expr.args.as_ref().and_then(|args| args.get(1)) {
+    Some(arg) => arg.expr.as_ref(),
+    None => return false,
+  };
+  matches!(
+    base,
+    ast::Expr::Member(member_expr)
+      if matches!(member_expr.obj.as_ref(), ast::Expr::MetaProp(_))
+        && is_prop(&member_expr.prop, "url")
+  )
+}
+
+/// An occurrence of code which doesn't work in compiled executables.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CompileWarning {
+  pub code: &'static str,
+  pub message: &'static str,
+  pub specifier: ModuleSpecifier,
+  /// The 1-based line of the code.
+  pub line: usize,
+  /// The 1-based column of the code.
+  pub column: usize,
+}
+
+impl fmt::Display for CompileWarning {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "({}) {}\n    at {}:{}:{}",
+      self.code, self.message, self.specifier, self.line, self.column
+    )
+  }
+}
+
+struct LimitationCollector<'a> {
+  limitations: &'static [Limitation],
+  parsed_source: &'a ParsedSource,
+  is_embedded: &'a IsEmbeddedFn<'a>,
+  warnings: Vec<CompileWarning>,
+}
+
+impl Visit for LimitationCollector<'_> {
+  noop_visit_type!();
+
+  fn visit_expr(&mut self, expr: &ast::Expr) {
+    for limitation in self.limitations {
+      if (limitation.matches)(expr, self.is_embedded) {
+        let position = self
+          .parsed_source
+          .text_info()
+          .line_and_column_index(expr.range().start);
+        self.warnings.push(CompileWarning {
+          code: limitation.code,
+          message: limitation.message,
+          specifier: ModuleSpecifier::parse(self.parsed_source.specifier())
+            .unwrap(),
+          line: position.line_index + 1,
+          column: position.column_index + 1,
+        });
+      }
+    }
+    expr.visit_children_with(self);
+  }
+}
+
+fn check_parsed_source(
+  limitations: &'static [Limitation],
+  parsed_source: &ParsedSource,
+  is_embedded: &IsEmbeddedFn<'_>,
+) -> Vec<CompileWarning> {
+  let mut collector = LimitationCollector {
+    limitations,
+    parsed_source,
+    is_embedded,
+    warnings: Vec::new(),
+  };
+  parsed_source.module().visit_with(&mut collector);
+  collector.warnings
+}
+
+/// Finds the code of the modules of the graph which doesn't work in compiled
+/// executables.
+pub fn check_graph(
+  graph: &ModuleGraph,
+  parsed_source_cache: &ParsedSourceCache,
+) -> Result<Vec<CompileWarning>, AnyError> {
+  check_graph_limitations(graph, parsed_source_cache, LIMITATIONS)
+}
+
+/// Finds the code of the modules of the graph which needs the capabilities
+/// left out of executables compiled with `--strip`.
+pub fn check_graph_for_strip(
+  graph: &ModuleGraph,
+  parsed_source_cache: &ParsedSourceCache,
+) -> Result<Vec<CompileWarning>, AnyError> {
+  check_graph_limitations(graph, parsed_source_cache, STRIPPED_LIMITATIONS)
+}
+
+fn check_graph_limitations(
+  graph: &ModuleGraph,
+  parsed_source_cache: &ParsedSourceCache,
+  limitations: &'static [Limitation],
+) -> Result<Vec<CompileWarning>, AnyError> {
+  let mut warnings = Vec::new();
+  for module in graph.modules() {
+    if module.kind == ModuleKind::External
+      || !matches!(
+        module.media_type,
+        MediaType::JavaScript
+          | MediaType::Jsx
+          | MediaType::Mjs
+          | MediaType::Cjs
+          | MediaType::TypeScript
+          | MediaType::Mts
+          | MediaType::Cts
+          | MediaType::Tsx
+      )
+    {
+      continue;
+    }
+    let parsed_source =
+      match parsed_source_cache.get_parsed_source_from_module(module)? {
+        Some(parsed_source) => parsed_source,
+        None => continue,
+      };
+    let is_embedded = |specifier: &str| {
+      let maybe_resolved = match module
+        .dependencies
+        .get(specifier)
+        .and_then(|dependency| dependency.get_code())
+      {
+        Some(resolved) => Some(resolved.clone()),
+        None => resolve_import(specifier, module.specifier.as_str()).ok(),
+      };
+      maybe_resolved.map_or(false, |resolved| graph.get(&resolved).is_some())
+    };
+    warnings.extend(check_parsed_source(
+      limitations,
+      &parsed_source,
+      &is_embedded,
+    ));
+  }
+  Ok(warnings)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_ast::SourceTextInfo;
+
+  fn check(source: &str) -> Vec<(&'static str, usize, usize)> {
+    check_limitations(LIMITATIONS, source)
+  }
+
+  fn check_limitations(
+    limitations: &'static [Limitation],
+    source: &str,
+  ) -> Vec<(&'static str, usize, usize)> {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: "file:///main.ts".to_string(),
+      text_info: SourceTextInfo::new(source.into()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    check_parsed_source(limitations, &parsed_source, &|specifier| {
+      specifier == "./a.ts"
+    })
+    .into_iter()
+    .map(|warning| (warning.code, warning.line, warning.column))
+    .collect()
+  }
+
+  #[test]
+  fn finds_limitations() {
+    assert_eq!(
+      check(
+        r#"const worker = new Worker(new URL("./worker.ts", import.meta.url));
+const lib = Deno.dlopen("./lib.so", {});
+await import("./a.ts");
+await import(`./a.ts`);
+await import(`./${name}.ts`);
+import.meta.resolve("./a.ts");
+import.meta.resolve("./b.ts");
+const text = await Deno.readTextFile(new URL("./data.txt", import.meta.url));
+await Deno.readTextFile("./data.txt");
+"#
+      ),
+      vec![
+        ("worker", 1, 16),
+        ("dlopen", 2, 13),
+        ("dynamic-import", 5, 7),
+        ("import-meta-resolve", 7, 1),
+        ("module-relative-file", 8, 20),
+      ]
+    );
+  }
+
+  #[test]
+  fn limitations_in_nested_expressions() {
+    assert_eq!(
+      check("function f() { return () => [new Worker(url)]; }"),
+      vec![("worker", 1, 30)]
+    );
+    assert_eq!(check("class Worker {}\nconst ok = Deno.cwd();"), vec![]);
+  }
+
+  #[test]
+  fn finds_stripped_limitations() {
+    assert_eq!(
+      check_limitations(
+        STRIPPED_LIMITATIONS,
+        "const adapter = await navigator.gpu.requestAdapter();\nnavigator.userAgent;",
+      ),
+      vec![("webgpu", 1, 23)]
+    );
+    assert_eq!(check("const gpu = navigator.gpu;"), vec![]);
+  }
+}
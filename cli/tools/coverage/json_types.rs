@@ -52,7 +52,8 @@ pub struct TakePreciseCoverageReturnObject {
   pub timestamp: f64,
 }
 
-// TODO(bartlomieju): remove me
+/// The coverage of a whole process, as written by Node.js to the directory of
+/// `NODE_V8_COVERAGE`.
 #[derive(Eq, PartialEq, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessCoverage {
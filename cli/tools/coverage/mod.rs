@@ -8,6 +8,7 @@ use crate::emit::get_source_hash;
 use crate::proc_state::ProcState;
 use crate::tools::fmt::format_json;
 use crate::util::fs::FileCollector;
+use crate::util::path::normalize_file_specifier;
 use crate::util::text_encoding::source_map_from_code;
 
 use deno_ast::MediaType;
@@ -20,6 +21,7 @@ use deno_core::serde_json;
 use deno_core::sourcemap::SourceMap;
 use deno_core::url::Url;
 use deno_core::LocalInspectorSession;
+use percent_encoding::percent_decode_str;
 use regex::Regex;
 use std::fs;
 use std::fs::File;
@@ -37,6 +39,10 @@ mod range_tree;
 
 use json_types::*;
 
+/// The file `deno test --coverage` writes the metadata of the test run to,
+/// which isn't a coverage profile.
+pub const TEST_RUN_METADATA_FILE_NAME: &str = "test_run_metadata.json";
+
 pub struct CoverageCollector {
   pub dir: PathBuf,
   session: LocalInspectorSession,
@@ -120,11 +126,24 @@ impl CoverageCollector {
     Ok(())
   }
 
-  pub async fn stop_collecting(&mut self) -> Result<(), AnyError> {
+  /// Writes the collected coverage to the coverage directory and returns the
+  /// URLs of the user scripts that had any code executed.
+  pub async fn stop_collecting(&mut self) -> Result<Vec<String>, AnyError> {
     fs::create_dir_all(&self.dir)?;
 
     let script_coverages = self.take_precise_coverage().await?.result;
+    let mut covered_script_urls = Vec::new();
     for script_coverage in script_coverages {
+      if is_user_script_url(&script_coverage.url)
+        && script_coverage
+          .functions
+          .iter()
+          .flat_map(|function| &function.ranges)
+          .any(|range| range.count > 0)
+      {
+        covered_script_urls.push(script_coverage.url.clone());
+      }
+
       let filename = format!("{}.json", Uuid::new_v4());
       let filepath = self.dir.join(filename);
 
@@ -142,10 +161,18 @@ impl CoverageCollector {
     self.disable_debugger().await?;
     self.disable_profiler().await?;
 
-    Ok(())
+    covered_script_urls.sort();
+    covered_script_urls.dedup();
+    Ok(covered_script_urls)
   }
 }
 
+fn is_user_script_url(url: &str) -> bool {
+  url.starts_with("file:")
+    || url.starts_with("http:")
+    || url.starts_with("https:")
+}
+
 struct BranchCoverageItem {
   line_index: usize,
   block_number: usize,
@@ -563,6 +590,9 @@ fn collect_coverages(
   let mut coverages: Vec<ScriptCoverage> = Vec::new();
   let file_paths = FileCollector::new(|file_path| {
     file_path.extension().map_or(false, |ext| ext == "json")
+      && file_path
+        .file_name()
+        .map_or(true, |name| name != TEST_RUN_METADATA_FILE_NAME)
   })
   .ignore_git_folder()
   .ignore_node_modules()
@@ -571,15 +601,71 @@ fn collect_coverages(
 
   for file_path in file_paths {
     let json = fs::read_to_string(file_path.as_path())?;
-    let new_coverage: ScriptCoverage = serde_json::from_str(&json)?;
-    coverages.push(new_coverage);
+    let new_coverages = parse_coverage_file(&json).with_context(|| {
+      format!(
+        "Failed to parse coverage profile \"{}\".",
+        file_path.display()
+      )
+    })?;
+    coverages.extend(new_coverages);
   }
 
+  // profiles written by other tools may encode the URLs of the same files
+  // differently, which would keep their coverage from being merged
+  for coverage in &mut coverages {
+    if let Ok(url) = Url::parse(&coverage.url) {
+      coverage.url = normalize_file_specifier(&url).to_string();
+    }
+  }
   coverages.sort_by_key(|k| k.url.clone());
 
   Ok(coverages)
 }
 
+/// Parses a coverage profile, which is either the coverage of a single script
+/// written by `deno test --coverage` or the coverage of a whole process
+/// written by Node.js to `NODE_V8_COVERAGE`.
+fn parse_coverage_file(json: &str) -> Result<Vec<ScriptCoverage>, AnyError> {
+  let value: serde_json::Value = serde_json::from_str(json)?;
+  if value.get("result").is_none() {
+    return Ok(vec![serde_json::from_value(value)?]);
+  }
+  let process_coverage: ProcessCoverage = serde_json::from_value(value)?;
+  Ok(
+    process_coverage
+      .result
+      .into_iter()
+      .filter_map(normalize_node_script_coverage)
+      .collect(),
+  )
+}
+
+/// Node.js reports the scripts of CommonJS modules by path and the ones of ES
+/// modules by file URL, while Deno always uses URLs. Scripts which aren't
+/// files, like the internals of Node.js, are skipped.
+fn normalize_node_script_coverage(
+  mut script_coverage: ScriptCoverage,
+) -> Option<ScriptCoverage> {
+  let maybe_url = if script_coverage.url.starts_with("file:") {
+    Url::parse(&script_coverage.url).ok()
+  } else {
+    Url::from_file_path(&script_coverage.url).ok()
+  };
+  match maybe_url {
+    Some(url) if url.scheme() == "file" => {
+      script_coverage.url = url.to_string();
+      Some(script_coverage)
+    }
+    _ => {
+      log::debug!(
+        "Skipping the coverage of unsupported script \"{}\".",
+        script_coverage.url
+      );
+      None
+    }
+  }
+}
+
 fn filter_coverages(
   coverages: Vec<ScriptCoverage>,
   include: Vec<String>,
@@ -599,8 +685,12 @@ fn filter_coverages(
         || e.url.ends_with("$deno$test.js")
         || e.url.ends_with(".snap");
 
-      let is_included = include.iter().any(|p| p.is_match(&e.url));
-      let is_excluded = exclude.iter().any(|p| p.is_match(&e.url));
+      // the patterns may be written against the decoded file names, like
+      // "my tests/" rather than "my%20tests/"
+      let decoded_url = percent_decode_str(&e.url).decode_utf8_lossy();
+      let matches = |p: &Regex| p.is_match(&e.url) || p.is_match(&decoded_url);
+      let is_included = include.iter().any(matches);
+      let is_excluded = exclude.iter().any(matches);
 
       (include.is_empty() || is_included) && !is_excluded && !is_internal
     })
@@ -0,0 +1,509 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Analysis of the dependencies used by the linted files, reporting unused
+//! import map and lockfile entries as well as remote packages that are
+//! imported at more than one version.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::sync::Arc;
+
+use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_graph::ModuleGraph;
+use deno_lockfile::LockfileContent;
+use deno_runtime::colors;
+use import_map::ImportMap;
+use import_map::SpecifierMap;
+use serde::Serialize;
+
+use crate::args::CliOptions;
+use crate::args::Flags;
+use crate::args::LintFlags;
+use crate::npm::NpmPackageReference;
+use crate::npm::NpmResolutionSnapshot;
+use crate::proc_state::ProcState;
+use crate::tools::lint::collect_lint_files;
+use crate::util::display;
+
+pub async fn analyze(
+  flags: Flags,
+  lint_flags: LintFlags,
+) -> Result<i32, AnyError> {
+  let json = lint_flags.json;
+  let strict = lint_flags.strict_deps;
+  let cli_options = CliOptions::from_flags(flags)?;
+  let lint_options = cli_options.resolve_lint_options(lint_flags)?;
+  if lint_options.is_stdin {
+    bail!("Analyzing dependencies is not supported when linting stdin.");
+  }
+  let roots = collect_lint_files(&lint_options.files)?
+    .into_iter()
+    .filter_map(|path| ModuleSpecifier::from_file_path(path).ok())
+    .collect::<Vec<_>>();
+  if roots.is_empty() {
+    bail!("No target files found.");
+  }
+
+  let ps = ProcState::from_options(Arc::new(cli_options)).await?;
+  // take the lockfile content before building the graph so that the
+  // entries added while resolving are not considered used
+  let maybe_lockfile_content = ps
+    .lockfile
+    .as_ref()
+    .map(|lockfile| lockfile.lock().content.clone());
+  let graph = ps.create_graph(roots).await?;
+  let report = DependencyReport::new(
+    &graph,
+    ps.maybe_import_map.as_deref(),
+    maybe_lockfile_content.as_ref(),
+    &ps.npm_resolver.snapshot(),
+  );
+
+  if json {
+    display::write_json_to_stdout(&report)?;
+  } else {
+    // this is intentionally not logged so that it is still
+    // output when `--quiet` is specified
+    print!("{report}");
+  }
+
+  if strict && report.issue_count() > 0 {
+    Ok(1)
+  } else {
+    Ok(0)
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnusedImportMapEntry {
+  scope: Option<String>,
+  key: String,
+  value: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnusedLockfileEntries {
+  remote: Vec<String>,
+  npm_specifiers: Vec<String>,
+  npm_packages: Vec<String>,
+}
+
+impl UnusedLockfileEntries {
+  pub fn len(&self) -> usize {
+    self.remote.len() + self.npm_specifiers.len() + self.npm_packages.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DuplicateVersion {
+  version: String,
+  importers: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DuplicateDependency {
+  package: String,
+  versions: Vec<DuplicateVersion>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DependencyReport {
+  unused_import_map_entries: Vec<UnusedImportMapEntry>,
+  unused_lockfile_entries: UnusedLockfileEntries,
+  duplicates: Vec<DuplicateDependency>,
+}
+
+impl DependencyReport {
+  pub fn new(
+    graph: &ModuleGraph,
+    maybe_import_map: Option<&ImportMap>,
+    maybe_lockfile_content: Option<&LockfileContent>,
+    npm_snapshot: &NpmResolutionSnapshot,
+  ) -> Self {
+    let imports = collect_imports(graph);
+    Self {
+      unused_import_map_entries: maybe_import_map
+        .map(|import_map| find_unused_import_map_entries(import_map, &imports))
+        .unwrap_or_default(),
+      unused_lockfile_entries: maybe_lockfile_content
+        .map(|content| find_unused_lockfile_entries(graph, content, &imports))
+        .unwrap_or_default(),
+      duplicates: find_duplicates(&imports, npm_snapshot),
+    }
+  }
+
+  pub fn issue_count(&self) -> usize {
+    self.unused_import_map_entries.len()
+      + self.unused_lockfile_entries.len()
+      + self.duplicates.len()
+  }
+}
+
+impl std::fmt::Display for DependencyReport {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if !self.unused_import_map_entries.is_empty() {
+      writeln!(
+        f,
+        "{} ({}):",
+        colors::bold("Unused import map entries"),
+        self.unused_import_map_entries.len()
+      )?;
+      for entry in &self.unused_import_map_entries {
+        let mut line = format!("  \"{}\"", entry.key);
+        if let Some(value) = &entry.value {
+          write!(line, " -> {value}")?;
+        }
+        if let Some(scope) = &entry.scope {
+          write!(line, " {}", colors::gray(format!("(scope {scope})")))?;
+        }
+        writeln!(f, "{line}")?;
+      }
+    }
+
+    let lockfile_entries = &self.unused_lockfile_entries;
+    if !lockfile_entries.is_empty() {
+      writeln!(
+        f,
+        "{} ({}):",
+        colors::bold("Unused lockfile entries"),
+        lockfile_entries.len()
+      )?;
+      for specifier in &lockfile_entries.remote {
+        writeln!(f, "  {specifier}")?;
+      }
+      for package_req in &lockfile_entries.npm_specifiers {
+        writeln!(f, "  npm:{package_req}")?;
+      }
+      for package_id in &lockfile_entries.npm_packages {
+        writeln!(f, "  npm:{package_id} {}", colors::gray("(package)"))?;
+      }
+    }
+
+    if !self.duplicates.is_empty() {
+      writeln!(
+        f,
+        "{} ({}):",
+        colors::bold("Packages imported at multiple versions"),
+        self.duplicates.len()
+      )?;
+      for duplicate in &self.duplicates {
+        writeln!(f, "  {}", duplicate.package)?;
+        for version in &duplicate.versions {
+          writeln!(f, "    {} imported by:", colors::yellow(&version.version))?;
+          for importer in &version.importers {
+            writeln!(f, "      {importer}")?;
+          }
+        }
+      }
+    }
+
+    match self.issue_count() {
+      0 => writeln!(f, "{}", colors::green("No dependency issues found.")),
+      1 => writeln!(f, "{}", colors::red("Found 1 dependency issue.")),
+      count => writeln!(
+        f,
+        "{}",
+        colors::red(format!("Found {count} dependency issues."))
+      ),
+    }
+  }
+}
+
+/// An import found in the module graph.
+#[derive(Debug)]
+struct GraphImport<'a> {
+  referrer: &'a ModuleSpecifier,
+  /// The specifier as it was written in the referrer.
+  raw_specifier: &'a str,
+  /// The specifier after being resolved, for example with an import map.
+  specifier: &'a ModuleSpecifier,
+}
+
+fn collect_imports(graph: &ModuleGraph) -> Vec<GraphImport> {
+  let mut imports = Vec::new();
+  for module in graph.modules() {
+    if let Some(types_dep) = &module.maybe_types_dependency {
+      if let Some(specifier) = types_dep.dependency.maybe_specifier() {
+        imports.push(GraphImport {
+          referrer: &module.specifier,
+          raw_specifier: &types_dep.specifier,
+          specifier,
+        });
+      }
+    }
+    for (raw_specifier, dep) in &module.dependencies {
+      for resolution in [&dep.maybe_code, &dep.maybe_type] {
+        if let Some(specifier) = resolution.maybe_specifier() {
+          imports.push(GraphImport {
+            referrer: &module.specifier,
+            raw_specifier,
+            specifier,
+          });
+        }
+      }
+    }
+  }
+  imports
+}
+
+fn find_unused_import_map_entries(
+  import_map: &ImportMap,
+  imports: &[GraphImport],
+) -> Vec<UnusedImportMapEntry> {
+  fn collect_unused(
+    scope: Option<&str>,
+    specifier_map: &SpecifierMap,
+    imports: &[GraphImport],
+    unused: &mut Vec<UnusedImportMapEntry>,
+  ) {
+    for entry in specifier_map.entries() {
+      let is_used = imports.iter().any(|import| {
+        let in_scope = match scope {
+          Some(scope) => import.referrer.as_str().starts_with(scope),
+          None => true,
+        };
+        in_scope
+          && (import_map_key_matches(entry.raw_key, import.raw_specifier)
+            || import_map_key_matches(
+              entry.key,
+              import
+                .referrer
+                .join(import.raw_specifier)
+                .as_ref()
+                .map(|s| s.as_str())
+                .unwrap_or(import.raw_specifier),
+            ))
+      });
+      if !is_used {
+        unused.push(UnusedImportMapEntry {
+          scope: scope.map(ToOwned::to_owned),
+          key: entry.raw_key.to_string(),
+          value: entry.value.map(|v| v.to_string()),
+        });
+      }
+    }
+  }
+
+  let mut unused = Vec::new();
+  collect_unused(None, import_map.imports(), imports, &mut unused);
+  for scope in import_map.scopes() {
+    collect_unused(Some(scope.key), scope.imports, imports, &mut unused);
+  }
+  unused
+}
+
+/// Gets if an import map key applies to the provided specifier, where keys
+/// ending in a slash match any specifier that starts with them.
+fn import_map_key_matches(key: &str, specifier: &str) -> bool {
+  if key.ends_with('/') {
+    specifier.starts_with(key)
+  } else {
+    key == specifier
+  }
+}
+
+fn find_unused_lockfile_entries(
+  graph: &ModuleGraph,
+  lockfile_content: &LockfileContent,
+  imports: &[GraphImport],
+) -> UnusedLockfileEntries {
+  let graph_specifiers = graph
+    .specifiers()
+    .map(|(specifier, _)| specifier.as_str())
+    .collect::<HashSet<_>>();
+  let remote = lockfile_content
+    .remote()
+    .keys()
+    .filter(|specifier| !graph_specifiers.contains(specifier.as_str()))
+    .cloned()
+    .collect();
+
+  let npm = &lockfile_content.npm;
+  let used_package_reqs = imports
+    .iter()
+    .filter_map(|import| {
+      NpmPackageReference::from_specifier(import.specifier).ok()
+    })
+    .map(|reference| reference.req.to_string())
+    .collect::<HashSet<_>>();
+  let npm_specifiers = npm
+    .specifiers
+    .keys()
+    .filter(|package_req| !used_package_reqs.contains(*package_req))
+    .cloned()
+    .collect();
+
+  // walk the locked npm packages from the used specifiers to find
+  // the packages that are no longer referenced
+  let mut used_packages = HashSet::new();
+  let mut pending = npm
+    .specifiers
+    .iter()
+    .filter(|(package_req, _)| used_package_reqs.contains(*package_req))
+    .map(|(_, package_id)| package_id.as_str())
+    .collect::<Vec<_>>();
+  while let Some(package_id) = pending.pop() {
+    if used_packages.insert(package_id) {
+      if let Some(info) = npm.packages.get(package_id) {
+        pending.extend(info.dependencies.values().map(|id| id.as_str()));
+      }
+    }
+  }
+  let npm_packages = npm
+    .packages
+    .keys()
+    .filter(|package_id| !used_packages.contains(package_id.as_str()))
+    .cloned()
+    .collect();
+
+  UnusedLockfileEntries {
+    remote,
+    npm_specifiers,
+    npm_packages,
+  }
+}
+
+fn find_duplicates(
+  imports: &[GraphImport],
+  npm_snapshot: &NpmResolutionSnapshot,
+) -> Vec<DuplicateDependency> {
+  // package -> version -> importers
+  let mut packages: BTreeMap<String, BTreeMap<String, BTreeSet<String>>> =
+    BTreeMap::new();
+  for import in imports {
+    let maybe_package = if let Ok(reference) =
+      NpmPackageReference::from_specifier(import.specifier)
+    {
+      npm_snapshot
+        .resolve_package_from_deno_module(&reference.req)
+        .ok()
+        .map(|package| {
+          (
+            format!("npm:{}", package.id.name),
+            package.id.version.to_string(),
+          )
+        })
+    } else {
+      parse_remote_package(import.specifier)
+    };
+    let (package, version) = match maybe_package {
+      Some(package) => package,
+      None => continue,
+    };
+    // ignore the imports within the package itself
+    if parse_remote_package(import.referrer)
+      == Some((package.clone(), version.clone()))
+    {
+      continue;
+    }
+    packages
+      .entry(package)
+      .or_default()
+      .entry(version)
+      .or_default()
+      .insert(import.referrer.to_string());
+  }
+
+  packages
+    .into_iter()
+    .filter(|(_, versions)| versions.len() > 1)
+    .map(|(package, versions)| DuplicateDependency {
+      package,
+      versions: versions
+        .into_iter()
+        .map(|(version, importers)| DuplicateVersion {
+          version,
+          importers: importers.into_iter().collect(),
+        })
+        .collect(),
+    })
+    .collect()
+}
+
+/// Gets the package and version of a versioned remote specifier, for
+/// example `deno.land/x/oak` and `v11.1.0` for
+/// `https://deno.land/x/oak@v11.1.0/mod.ts`.
+fn parse_remote_package(
+  specifier: &ModuleSpecifier,
+) -> Option<(String, String)> {
+  if !matches!(specifier.scheme(), "http" | "https") {
+    return None;
+  }
+  let mut package = specifier.host_str()?.to_string();
+  if let Some(port) = specifier.port() {
+    write!(package, ":{port}").unwrap();
+  }
+  let mut maybe_scope = None;
+  for segment in specifier.path_segments()? {
+    if segment.starts_with('@') && !segment[1..].contains('@') {
+      maybe_scope = Some(segment);
+      continue;
+    }
+    package.push('/');
+    if let Some(scope) = maybe_scope.take() {
+      package.push_str(scope);
+      package.push('/');
+    }
+    match segment.rfind('@') {
+      Some(index) if index > 0 && index + 1 < segment.len() => {
+        package.push_str(&segment[..index]);
+        return Some((package, segment[index + 1..].to_string()));
+      }
+      _ => package.push_str(segment),
+    }
+  }
+  None
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_parse_remote_package() {
+    fn parse(specifier: &str) -> Option<(String, String)> {
+      parse_remote_package(&ModuleSpecifier::parse(specifier).unwrap())
+    }
+
+    assert_eq!(
+      parse("https://deno.land/x/oak@v11.1.0/mod.ts"),
+      Some(("deno.land/x/oak".to_string(), "v11.1.0".to_string()))
+    );
+    assert_eq!(
+      parse("https://deno.land/std@0.170.0/path/mod.ts"),
+      Some(("deno.land/std".to_string(), "0.170.0".to_string()))
+    );
+    assert_eq!(
+      parse("https://esm.sh/@scope/pkg@1.2.3?target=deno"),
+      Some(("esm.sh/@scope/pkg".to_string(), "1.2.3".to_string()))
+    );
+    assert_eq!(
+      parse("http://localhost:4545/pkg@2.0.0/mod.ts"),
+      Some(("localhost:4545/pkg".to_string(), "2.0.0".to_string()))
+    );
+    assert_eq!(parse("https://deno.land/x/oak/mod.ts"), None);
+    assert_eq!(parse("https://esm.sh/@scope/pkg"), None);
+    assert_eq!(parse("file:///pkg@1.0.0/mod.ts"), None);
+  }
+
+  #[test]
+  fn test_import_map_key_matches() {
+    assert!(import_map_key_matches("oak", "oak"));
+    assert!(!import_map_key_matches("oak", "oak/mod.ts"));
+    assert!(import_map_key_matches("std/", "std/path/mod.ts"));
+    assert!(!import_map_key_matches("std/", "std"));
+  }
+}
@@ -0,0 +1,383 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::args::resolve_entrypoint;
+use crate::args::DocFlags;
+use crate::args::DocSourceFileFlag;
+use crate::args::Flags;
+use crate::colors;
+use crate::display::write_json_to_stdout;
+use crate::display::write_to_stdout_ignore_sigpipe;
+use crate::file_fetcher::File;
+use crate::proc_state::ProcState;
+use crate::tsc::get_types_declaration_file_text;
+use deno_ast::swc::ast;
+use deno_ast::swc::common::comments::CommentKind;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::LineAndColumnIndex;
+use deno_ast::MediaType;
+use deno_ast::ParsedSource;
+use deno_ast::SourcePos;
+use deno_ast::SourceRange;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use deno_doc as doc;
+use deno_graph::ModuleSpecifier;
+use deno_graph::ParsedSourceStore;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+mod references;
+
+/// The version of the `deno doc --json` output. It must be bumped, along with
+/// the schema in `cli/schemas`, whenever the output changes in a way that
+/// isn't backwards compatible.
+const DOC_JSON_VERSION: u32 = 1;
+
+static DOC_JSON_SCHEMA: &str = include_str!("../../schemas/doc.v1.json");
+
+pub async fn print_docs(
+  flags: Flags,
+  doc_flags: DocFlags,
+) -> Result<(), AnyError> {
+  if doc_flags.json_schema {
+    return write_to_stdout_ignore_sigpipe(DOC_JSON_SCHEMA.as_bytes())
+      .map_err(AnyError::from);
+  }
+
+  let ps = ProcState::build(flags).await?;
+
+  let mut module_imports = references::ModuleImports::default();
+  let mut doc_nodes = match doc_flags.source_file {
+    DocSourceFileFlag::Builtin => {
+      let source_file_specifier =
+        ModuleSpecifier::parse("internal://lib.deno.d.ts").unwrap();
+      let content = get_types_declaration_file_text(ps.options.unstable())?;
+      let mut loader = deno_graph::source::MemoryLoader::new(
+        vec![(
+          source_file_specifier.to_string(),
+          deno_graph::source::Source::Module {
+            specifier: source_file_specifier.to_string(),
+            content,
+            maybe_headers: None,
+          },
+        )],
+        Vec::new(),
+      );
+      let analyzer = deno_graph::CapturingModuleAnalyzer::default();
+      let mut graph = deno_graph::ModuleGraph::default();
+      graph
+        .build(
+          vec![source_file_specifier.clone()],
+          &mut loader,
+          deno_graph::BuildOptions {
+            module_analyzer: Some(&analyzer),
+            ..Default::default()
+          },
+        )
+        .await;
+      let doc_parser = doc::DocParser::new(
+        graph,
+        doc_flags.private,
+        ps.parsed_source_cache.as_capturing_parser(),
+      );
+      doc_parser.parse_module(&source_file_specifier)?.definitions
+    }
+    DocSourceFileFlag::Path(source_file) => {
+      let module_specifier = resolve_entrypoint(&source_file)?;
+
+      // If the root module has external types, the module graph won't redirect it,
+      // so instead create a dummy file which exports everything from the actual file being documented.
+      let root_specifier = resolve_url_or_path("./$deno$doc.ts").unwrap();
+      let root = File {
+        local: PathBuf::from("./$deno$doc.ts"),
+        maybe_types: None,
+        media_type: MediaType::TypeScript,
+        source: format!("export * from \"{module_specifier}\";").into(),
+        specifier: root_specifier.clone(),
+        maybe_headers: None,
+      };
+
+      // Save our fake file into file fetcher cache.
+      ps.file_fetcher.insert_cached(root);
+
+      let graph = ps.create_graph(vec![root_specifier.clone()]).await?;
+      if doc_flags.show_references {
+        module_imports = references::ModuleImports::new(&graph);
+      }
+      let doc_parser = doc::DocParser::new(
+        graph,
+        doc_flags.private,
+        ps.parsed_source_cache.as_capturing_parser(),
+      );
+      doc_parser.parse_with_reexports(&root_specifier)?
+    }
+  };
+
+  if doc_flags.show_references {
+    return references::print_references(
+      &serde_json::to_value(&doc_nodes)?,
+      &module_imports,
+      &*ps.parsed_source_cache.as_store(),
+      doc_flags.json,
+      doc_flags.filter.as_deref(),
+    );
+  }
+
+  if doc_flags.json {
+    let mut nodes = serde_json::to_value(&doc_nodes)?;
+    add_source_ranges(&mut nodes, &*ps.parsed_source_cache.as_store());
+    write_json_to_stdout(&DocJson {
+      version: DOC_JSON_VERSION,
+      nodes,
+    })
+  } else {
+    doc_nodes.retain(|doc_node| doc_node.kind != doc::DocNodeKind::Import);
+    let details = if let Some(filter) = doc_flags.filter {
+      let nodes =
+        doc::find_nodes_by_name_recursively(doc_nodes, filter.clone());
+      if nodes.is_empty() {
+        bail!("Node {} was not found!", filter);
+      }
+      format!(
+        "{}",
+        doc::DocPrinter::new(&nodes, colors::use_color(), doc_flags.private)
+      )
+    } else {
+      format!(
+        "{}",
+        doc::DocPrinter::new(
+          &doc_nodes,
+          colors::use_color(),
+          doc_flags.private
+        )
+      )
+    };
+
+    write_to_stdout_ignore_sigpipe(details.as_bytes()).map_err(AnyError::from)
+  }
+}
+
+#[derive(Serialize)]
+struct DocJson {
+  version: u32,
+  nodes: Value,
+}
+
+/// A position in the source of a module. Lines are 1-based and columns are
+/// 0-based, which matches the `location` of doc nodes.
+#[derive(Serialize)]
+struct DocPosition {
+  byte: usize,
+  line: usize,
+  col: usize,
+}
+
+#[derive(Serialize)]
+struct DocRange {
+  start: DocPosition,
+  end: DocPosition,
+}
+
+/// Adds a `range` to every node with a `location`, and a `jsDocRange` to the
+/// ones with JSDoc, so the original source text can be sliced out.
+fn add_source_ranges(value: &mut Value, store: &dyn ParsedSourceStore) {
+  let mut indexes = HashMap::new();
+  add_source_ranges_inner(value, store, &mut indexes);
+}
+
+fn add_source_ranges_inner(
+  value: &mut Value,
+  store: &dyn ParsedSourceStore,
+  indexes: &mut HashMap<String, Option<SourceIndex>>,
+) {
+  match value {
+    Value::Array(items) => {
+      for item in items {
+        add_source_ranges_inner(item, store, indexes);
+      }
+    }
+    Value::Object(map) => {
+      let location = map.get("location").and_then(|location| {
+        Some((
+          location.get("filename")?.as_str()?.to_string(),
+          location.get("line")?.as_u64()? as usize,
+          location.get("col")?.as_u64()? as usize,
+        ))
+      });
+      if let Some((filename, line, col)) = location {
+        let index = indexes.entry(filename.clone()).or_insert_with(|| {
+          let specifier = ModuleSpecifier::parse(&filename).ok()?;
+          store.get_parsed_source(&specifier).map(SourceIndex::new)
+        });
+        if let Some(index) = index {
+          if let Some(pos) = index.source_pos(line, col) {
+            map.insert(
+              "range".to_string(),
+              serde_json::to_value(index.declaration_range(pos)).unwrap(),
+            );
+            if map.contains_key("jsDoc") {
+              if let Some(range) = index.js_doc_range(pos) {
+                map.insert(
+                  "jsDocRange".to_string(),
+                  serde_json::to_value(range).unwrap(),
+                );
+              }
+            }
+          }
+        }
+      }
+      for (_, value) in map.iter_mut() {
+        add_source_ranges_inner(value, store, indexes);
+      }
+    }
+    _ => {}
+  }
+}
+
+struct SourceIndex {
+  parsed_source: ParsedSource,
+  /// The byte offset of the end of the widest declaration starting at
+  /// each byte offset.
+  ends: HashMap<usize, usize>,
+}
+
+impl SourceIndex {
+  fn new(parsed_source: ParsedSource) -> Self {
+    let mut collector = DeclarationCollector {
+      text_info: parsed_source.text_info(),
+      ends: HashMap::new(),
+    };
+    parsed_source.module().visit_with(&mut collector);
+    let ends = collector.ends;
+    Self {
+      parsed_source,
+      ends,
+    }
+  }
+
+  fn text_info(&self) -> &SourceTextInfo {
+    self.parsed_source.text_info()
+  }
+
+  fn source_pos(&self, line: usize, col: usize) -> Option<SourcePos> {
+    if line == 0 || line > self.text_info().lines_count() {
+      return None;
+    }
+    Some(self.text_info().loc_to_source_pos(LineAndColumnIndex {
+      line_index: line - 1,
+      column_index: col,
+    }))
+  }
+
+  fn position(&self, pos: SourcePos) -> DocPosition {
+    let text_info = self.text_info();
+    let loc = text_info.line_and_column_index(pos);
+    DocPosition {
+      byte: pos.as_byte_index(text_info.range().start),
+      line: loc.line_index + 1,
+      col: loc.column_index,
+    }
+  }
+
+  fn to_doc_range(&self, range: SourceRange) -> DocRange {
+    DocRange {
+      start: self.position(range.start),
+      end: self.position(range.end),
+    }
+  }
+
+  /// Gets the range of the declaration starting at the position. When no
+  /// declaration is known to start there, the range covers the position.
+  fn declaration_range(&self, start: SourcePos) -> DocRange {
+    let start_index = start.as_byte_index(self.text_info().range().start);
+    let end = match self.ends.get(&start_index) {
+      Some(end_index) => start + (end_index - start_index),
+      None => start,
+    };
+    self.to_doc_range(SourceRange::new(start, end))
+  }
+
+  fn js_doc_range(&self, start: SourcePos) -> Option<DocRange> {
+    let comment = self
+      .parsed_source
+      .comments()
+      .get_leading(start)?
+      .iter()
+      .rev()
+      .find(|comment| {
+        comment.kind == CommentKind::Block && comment.text.starts_with('*')
+      })?;
+    Some(self.to_doc_range(comment.range()))
+  }
+}
+
+/// Collects the ranges of the AST nodes that doc nodes can be created for.
+struct DeclarationCollector<'a> {
+  text_info: &'a SourceTextInfo,
+  ends: HashMap<usize, usize>,
+}
+
+impl DeclarationCollector<'_> {
+  fn add(&mut self, range: SourceRange) {
+    let source_start = self.text_info.range().start;
+    let start = range.start.as_byte_index(source_start);
+    let end = range.end.as_byte_index(source_start);
+    let entry = self.ends.entry(start).or_insert(end);
+    *entry = (*entry).max(end);
+  }
+}
+
+impl Visit for DeclarationCollector<'_> {
+  fn visit_module_item(&mut self, node: &ast::ModuleItem) {
+    self.add(node.range());
+    node.visit_children_with(self);
+  }
+
+  fn visit_stmt(&mut self, node: &ast::Stmt) {
+    self.add(node.range());
+    node.visit_children_with(self);
+  }
+
+  fn visit_decl(&mut self, node: &ast::Decl) {
+    self.add(node.range());
+    node.visit_children_with(self);
+  }
+
+  fn visit_var_declarator(&mut self, node: &ast::VarDeclarator) {
+    self.add(node.range());
+    node.visit_children_with(self);
+  }
+
+  fn visit_class_member(&mut self, node: &ast::ClassMember) {
+    self.add(node.range());
+    node.visit_children_with(self);
+  }
+
+  fn visit_ts_type_element(&mut self, node: &ast::TsTypeElement) {
+    self.add(node.range());
+    node.visit_children_with(self);
+  }
+
+  fn visit_ts_enum_member(&mut self, node: &ast::TsEnumMember) {
+    self.add(node.range());
+    node.visit_children_with(self);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn json_schema_matches_version() {
+    let schema: Value = serde_json::from_str(DOC_JSON_SCHEMA).unwrap();
+    assert_eq!(schema["properties"]["version"]["const"], DOC_JSON_VERSION);
+  }
+}
@@ -0,0 +1,601 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use crate::colors;
+use crate::util::display::write_json_to_stdout;
+use crate::util::display::write_to_stdout_ignore_sigpipe;
+use deno_ast::swc::ast;
+use deno_ast::ParsedSource;
+use deno_core::error::AnyError;
+use deno_core::serde::Serialize;
+use deno_core::serde_json::Value;
+use deno_graph::ModuleGraph;
+use deno_graph::ModuleSpecifier;
+use deno_graph::ParsedSourceStore;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::Write;
+use std::rc::Rc;
+
+/// Where the module declaring a referenced type comes from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum TypeOrigin {
+  Local,
+  Std,
+  Remote,
+  Npm,
+  Builtin,
+}
+
+impl TypeOrigin {
+  fn from_specifier(specifier: &ModuleSpecifier) -> Self {
+    match specifier.scheme() {
+      "file" => TypeOrigin::Local,
+      "npm" => TypeOrigin::Npm,
+      "node" | "internal" => TypeOrigin::Builtin,
+      _ => {
+        let path = specifier.path();
+        if specifier.host_str() == Some("deno.land")
+          && (path.starts_with("/std@") || path.starts_with("/std/"))
+        {
+          TypeOrigin::Std
+        } else {
+          TypeOrigin::Remote
+        }
+      }
+    }
+  }
+}
+
+impl fmt::Display for TypeOrigin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      TypeOrigin::Local => write!(f, "local"),
+      TypeOrigin::Std => write!(f, "std"),
+      TypeOrigin::Remote => write!(f, "remote"),
+      TypeOrigin::Npm => write!(f, "npm"),
+      TypeOrigin::Builtin => write!(f, "builtin"),
+    }
+  }
+}
+
+/// The resolved specifiers of the imports of each module in a graph,
+/// preferring the specifiers of the types.
+#[derive(Default)]
+pub struct ModuleImports(
+  HashMap<ModuleSpecifier, HashMap<String, ModuleSpecifier>>,
+);
+
+impl ModuleImports {
+  pub fn new(graph: &ModuleGraph) -> Self {
+    let mut modules = HashMap::new();
+    for module in graph.modules() {
+      let mut imports = HashMap::new();
+      for (text, dep) in &module.dependencies {
+        let resolved = dep.maybe_type.ok().or_else(|| dep.maybe_code.ok());
+        if let Some(resolved) = resolved {
+          let specifier = graph.resolve(&resolved.specifier);
+          // a JavaScript module can point to its types with a types
+          // reference directive or an `X-TypeScript-Types` header
+          let specifier = graph
+            .get(&specifier)
+            .and_then(|module| module.maybe_types_dependency.as_ref())
+            .and_then(|types| types.dependency.maybe_specifier())
+            .map(|types_specifier| graph.resolve(types_specifier))
+            .unwrap_or(specifier);
+          imports.insert(text.clone(), specifier);
+        }
+      }
+      modules.insert(module.specifier.clone(), imports);
+    }
+    Self(modules)
+  }
+
+  fn resolve(
+    &self,
+    referrer: &ModuleSpecifier,
+    text: &str,
+  ) -> Option<&ModuleSpecifier> {
+    self.0.get(referrer)?.get(text)
+  }
+}
+
+/// The names declared at the top level of a module, the names it imports and
+/// the names it exports from other modules.
+#[derive(Default)]
+struct ModuleScope {
+  declarations: HashSet<String>,
+  /// The local names of the imports, mapped to the specifier they're imported
+  /// from and the name they're exported as there, which is `None` for
+  /// namespace imports.
+  imports: HashMap<String, (String, Option<String>)>,
+  /// The names exported with an export specifier, mapped to their local name,
+  /// like `export { a as b }`.
+  local_exports: HashMap<String, String>,
+  /// The names re-exported from other modules, mapped to the specifier and
+  /// the name they're exported as there, like `export { a as b } from "./a.ts"`.
+  reexports: HashMap<String, (String, String)>,
+  /// The specifiers of the `export * from` declarations.
+  star_reexports: Vec<String>,
+}
+
+impl ModuleScope {
+  fn new(parsed_source: &ParsedSource) -> Self {
+    let mut scope = ModuleScope::default();
+    for item in &parsed_source.module().body {
+      match item {
+        ast::ModuleItem::ModuleDecl(ast::ModuleDecl::Import(import)) => {
+          let src = import.src.value.to_string();
+          for specifier in &import.specifiers {
+            let (local, maybe_imported) = match specifier {
+              ast::ImportSpecifier::Named(s) => (
+                &s.local,
+                Some(match &s.imported {
+                  Some(imported) => module_export_name(imported),
+                  None => s.local.sym.to_string(),
+                }),
+              ),
+              ast::ImportSpecifier::Default(s) => {
+                (&s.local, Some("default".to_string()))
+              }
+              ast::ImportSpecifier::Namespace(s) => (&s.local, None),
+            };
+            scope
+              .imports
+              .insert(local.sym.to_string(), (src.clone(), maybe_imported));
+          }
+        }
+        ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportNamed(export)) => {
+          for specifier in &export.specifiers {
+            let (orig, exported) = match specifier {
+              ast::ExportSpecifier::Named(s) => {
+                let orig = module_export_name(&s.orig);
+                let exported = match &s.exported {
+                  Some(exported) => module_export_name(exported),
+                  None => orig.clone(),
+                };
+                (orig, exported)
+              }
+              ast::ExportSpecifier::Default(s) => {
+                ("default".to_string(), s.exported.sym.to_string())
+              }
+              ast::ExportSpecifier::Namespace(_) => continue,
+            };
+            match &export.src {
+              Some(src) => {
+                scope
+                  .reexports
+                  .insert(exported, (src.value.to_string(), orig));
+              }
+              None => {
+                scope.local_exports.insert(exported, orig);
+              }
+            }
+          }
+        }
+        ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportAll(export)) => {
+          scope.star_reexports.push(export.src.value.to_string());
+        }
+        ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportDecl(export)) => {
+          scope.declarations.extend(decl_names(&export.decl));
+        }
+        ast::ModuleItem::Stmt(ast::Stmt::Decl(decl)) => {
+          scope.declarations.extend(decl_names(decl));
+        }
+        _ => {}
+      }
+    }
+    scope
+  }
+}
+
+fn module_export_name(name: &ast::ModuleExportName) -> String {
+  match name {
+    ast::ModuleExportName::Ident(ident) => ident.sym.to_string(),
+    ast::ModuleExportName::Str(str) => str.value.to_string(),
+  }
+}
+
+/// Finds the modules declaring the names used in a module, following the
+/// imports and the re-exports of the modules in between to the end.
+struct DeclarationResolver<'a> {
+  module_imports: &'a ModuleImports,
+  store: &'a dyn ParsedSourceStore,
+  scopes: HashMap<ModuleSpecifier, Option<Rc<ModuleScope>>>,
+}
+
+impl<'a> DeclarationResolver<'a> {
+  fn new(
+    module_imports: &'a ModuleImports,
+    store: &'a dyn ParsedSourceStore,
+  ) -> Self {
+    Self {
+      module_imports,
+      store,
+      scopes: HashMap::new(),
+    }
+  }
+
+  fn scope(&mut self, specifier: &ModuleSpecifier) -> Option<Rc<ModuleScope>> {
+    let store = self.store;
+    self
+      .scopes
+      .entry(specifier.clone())
+      .or_insert_with(|| {
+        store
+          .get_parsed_source(specifier)
+          .map(|parsed_source| Rc::new(ModuleScope::new(&parsed_source)))
+      })
+      .clone()
+  }
+
+  /// Gets the module declaring a name that's declared in or imported into the
+  /// module, or `None` when it's neither, like a global.
+  fn resolve_local(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    name: &str,
+  ) -> Option<ModuleSpecifier> {
+    let scope = self.scope(specifier)?;
+    if scope.declarations.contains(name) {
+      return Some(specifier.clone());
+    }
+    let (text, maybe_imported) = scope.imports.get(name)?;
+    let imported_specifier =
+      self.module_imports.resolve(specifier, text)?.clone();
+    match maybe_imported {
+      Some(imported) => Some(
+        self
+          .resolve_export(&imported_specifier, imported, &mut HashSet::new())
+          // the chain can only be followed through the modules in the graph
+          .unwrap_or(imported_specifier),
+      ),
+      None => Some(imported_specifier),
+    }
+  }
+
+  /// Gets the module declaring a name exported by the module, or `None` when
+  /// the module doesn't export it or isn't in the graph.
+  fn resolve_export(
+    &mut self,
+    specifier: &ModuleSpecifier,
+    name: &str,
+    visited: &mut HashSet<(ModuleSpecifier, String)>,
+  ) -> Option<ModuleSpecifier> {
+    if !visited.insert((specifier.clone(), name.to_string())) {
+      return None;
+    }
+    let scope = self.scope(specifier)?;
+    if scope.declarations.contains(name) {
+      return Some(specifier.clone());
+    }
+    if let Some(local) = scope.local_exports.get(name) {
+      if scope.declarations.contains(local) {
+        return Some(specifier.clone());
+      }
+      let (text, maybe_imported) = scope.imports.get(local)?;
+      let imported_specifier =
+        self.module_imports.resolve(specifier, text)?.clone();
+      return match maybe_imported {
+        Some(imported) => self
+          .resolve_export(&imported_specifier, imported, visited)
+          .or(Some(imported_specifier)),
+        None => Some(imported_specifier),
+      };
+    }
+    if let Some((text, orig)) = scope.reexports.get(name) {
+      let reexported_specifier =
+        self.module_imports.resolve(specifier, text)?.clone();
+      return self
+        .resolve_export(&reexported_specifier, orig, visited)
+        .or(Some(reexported_specifier));
+    }
+    // a star re-export only provides the names the module actually exports,
+    // so it can't be assumed for modules outside of the graph
+    for text in &scope.star_reexports {
+      if let Some(reexported_specifier) =
+        self.module_imports.resolve(specifier, text).cloned()
+      {
+        if let Some(declaring_specifier) =
+          self.resolve_export(&reexported_specifier, name, visited)
+        {
+          return Some(declaring_specifier);
+        }
+      }
+    }
+    None
+  }
+}
+
+fn decl_names(decl: &ast::Decl) -> Vec<String> {
+  match decl {
+    ast::Decl::Class(decl) => vec![decl.ident.sym.to_string()],
+    ast::Decl::Fn(decl) => vec![decl.ident.sym.to_string()],
+    ast::Decl::Var(decl) => decl
+      .decls
+      .iter()
+      .filter_map(|declarator| match &declarator.name {
+        ast::Pat::Ident(ident) => Some(ident.id.sym.to_string()),
+        _ => None,
+      })
+      .collect(),
+    ast::Decl::TsInterface(decl) => vec![decl.id.sym.to_string()],
+    ast::Decl::TsTypeAlias(decl) => vec![decl.id.sym.to_string()],
+    ast::Decl::TsEnum(decl) => vec![decl.id.sym.to_string()],
+    ast::Decl::TsModule(decl) => match &decl.id {
+      ast::TsModuleName::Ident(ident) => vec![ident.sym.to_string()],
+      ast::TsModuleName::Str(_) => vec![],
+    },
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeReference {
+  name: String,
+  specifier: ModuleSpecifier,
+  origin: TypeOrigin,
+  /// Whether a symbol with the name is exported by the documented module.
+  exported: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolReferences {
+  name: String,
+  kind: String,
+  location: Value,
+  references: Vec<TypeReference>,
+  exposes_remote_types: bool,
+  exposes_non_exported_types: bool,
+}
+
+#[derive(Serialize)]
+struct ReferencesJson {
+  symbols: Vec<SymbolReferences>,
+}
+
+/// Prints, for each exported symbol, the modules that declare the types its
+/// signature references. Types that aren't declared in or imported into the
+/// module of the symbol, like globals and type parameters, are left out.
+pub fn print_references(
+  doc_nodes: &Value,
+  module_imports: &ModuleImports,
+  store: &dyn ParsedSourceStore,
+  json: bool,
+  maybe_filter: Option<&str>,
+) -> Result<(), AnyError> {
+  let nodes = doc_nodes
+    .as_array()
+    .into_iter()
+    .flatten()
+    .filter(|node| node["kind"] != "import" && node["kind"] != "moduleDoc")
+    .collect::<Vec<_>>();
+  let exported_names = nodes
+    .iter()
+    .filter_map(|node| node["name"].as_str())
+    .collect::<HashSet<_>>();
+
+  let mut resolver = DeclarationResolver::new(module_imports, store);
+  let mut symbols = Vec::new();
+  for node in nodes {
+    let name = node["name"].as_str().unwrap_or_default();
+    if matches!(maybe_filter, Some(filter) if filter != name) {
+      continue;
+    }
+    let mut type_names = BTreeSet::new();
+    let mut type_params = HashSet::new();
+    collect_type_names(node, &mut type_names, &mut type_params);
+
+    let maybe_specifier = node["location"]["filename"]
+      .as_str()
+      .and_then(|filename| ModuleSpecifier::parse(filename).ok());
+    let mut references = Vec::new();
+    if let Some(specifier) = maybe_specifier {
+      for type_name in type_names
+        .iter()
+        .filter(|name| !type_params.contains(*name))
+      {
+        if let Some(declaring_specifier) =
+          resolver.resolve_local(&specifier, type_name)
+        {
+          references.push(TypeReference {
+            name: type_name.clone(),
+            origin: TypeOrigin::from_specifier(&declaring_specifier),
+            specifier: declaring_specifier,
+            exported: exported_names.contains(type_name.as_str()),
+          });
+        }
+      }
+    }
+
+    symbols.push(SymbolReferences {
+      name: name.to_string(),
+      kind: node["kind"].as_str().unwrap_or_default().to_string(),
+      location: node["location"].clone(),
+      exposes_remote_types: references
+        .iter()
+        .any(|r| matches!(r.origin, TypeOrigin::Remote | TypeOrigin::Npm)),
+      exposes_non_exported_types: references
+        .iter()
+        .any(|r| r.origin == TypeOrigin::Local && !r.exported),
+      references,
+    });
+  }
+
+  if json {
+    write_json_to_stdout(&ReferencesJson { symbols })
+  } else {
+    write_to_stdout_ignore_sigpipe(format_references(&symbols).as_bytes())
+      .map_err(AnyError::from)
+  }
+}
+
+/// Collects the names of the types referenced in a doc node and the names of
+/// the type parameters it declares. Qualified names are reduced to their
+/// first part, which is what's declared or imported in the module.
+fn collect_type_names(
+  value: &Value,
+  type_names: &mut BTreeSet<String>,
+  type_params: &mut HashSet<String>,
+) {
+  match value {
+    Value::Array(items) => {
+      for item in items {
+        collect_type_names(item, type_names, type_params);
+      }
+    }
+    Value::Object(map) => {
+      if let Some(type_name) = map.get("typeName").and_then(|v| v.as_str()) {
+        type_names.insert(first_name_part(type_name));
+      }
+      if let Some(extends) = map
+        .get("classDef")
+        .and_then(|class_def| class_def.get("extends"))
+        .and_then(|v| v.as_str())
+      {
+        type_names.insert(first_name_part(extends));
+      }
+      if let Some(Value::Array(params)) = map.get("typeParams") {
+        for param in params {
+          // type arguments of a type reference don't have a name
+          if let Some(name) = param.get("name").and_then(|v| v.as_str()) {
+            type_params.insert(name.to_string());
+          }
+        }
+      }
+      if let Some(name) = map
+        .get("typeParam")
+        .and_then(|param| param.get("name"))
+        .and_then(|v| v.as_str())
+      {
+        type_params.insert(name.to_string());
+      }
+      for value in map.values() {
+        collect_type_names(value, type_names, type_params);
+      }
+    }
+    _ => {}
+  }
+}
+
+fn first_name_part(name: &str) -> String {
+  name.split('.').next().unwrap_or(name).to_string()
+}
+
+fn format_references(symbols: &[SymbolReferences]) -> String {
+  let mut text = String::new();
+  for symbol in symbols {
+    let location = match (
+      symbol.location["filename"].as_str(),
+      symbol.location["line"].as_u64(),
+      symbol.location["col"].as_u64(),
+    ) {
+      (Some(filename), Some(line), Some(col)) => {
+        format!("{}:{}:{}", filename, line, col + 1)
+      }
+      _ => String::new(),
+    };
+    write!(
+      text,
+      "{} {}",
+      colors::bold(&symbol.name),
+      colors::gray(location)
+    )
+    .unwrap();
+    if symbol.exposes_remote_types {
+      write!(text, " {}", colors::yellow("exposes remote types")).unwrap();
+    }
+    if symbol.exposes_non_exported_types {
+      write!(text, " {}", colors::yellow("exposes non-exported types"))
+        .unwrap();
+    }
+    writeln!(text).unwrap();
+    if symbol.references.is_empty() {
+      writeln!(text, "  {}", colors::gray("no type references")).unwrap();
+    }
+    for reference in &symbol.references {
+      write!(
+        text,
+        "  {} {} ({}",
+        colors::cyan(&reference.name),
+        reference.specifier,
+        reference.origin
+      )
+      .unwrap();
+      if reference.origin == TypeOrigin::Local && !reference.exported {
+        write!(text, ", not exported").unwrap();
+      }
+      writeln!(text, ")").unwrap();
+    }
+  }
+  text
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_core::serde_json::json;
+
+  #[test]
+  fn type_origin_from_specifier() {
+    let origin = |specifier: &str| {
+      TypeOrigin::from_specifier(&ModuleSpecifier::parse(specifier).unwrap())
+    };
+    assert_eq!(origin("file:///project/mod.ts"), TypeOrigin::Local);
+    assert_eq!(
+      origin("https://deno.land/std@0.177.0/http/server.ts"),
+      TypeOrigin::Std
+    );
+    assert_eq!(
+      origin("https://deno.land/std/http/server.ts"),
+      TypeOrigin::Std
+    );
+    assert_eq!(origin("https://deno.land/x/oak/mod.ts"), TypeOrigin::Remote);
+    assert_eq!(origin("https://esm.sh/preact"), TypeOrigin::Remote);
+    assert_eq!(origin("npm:chalk@5"), TypeOrigin::Npm);
+    assert_eq!(origin("node:http"), TypeOrigin::Builtin);
+  }
+
+  #[test]
+  fn collects_type_names() {
+    let node = json!({
+      "name": "serve",
+      "kind": "function",
+      "functionDef": {
+        "params": [{
+          "kind": "identifier",
+          "name": "options",
+          "tsType": {
+            "repr": "ServeInit",
+            "kind": "typeRef",
+            "typeRef": {
+              "typeName": "http.ServeInit",
+              "typeParams": [{
+                "repr": "T",
+                "kind": "typeRef",
+                "typeRef": { "typeName": "T", "typeParams": null }
+              }]
+            }
+          }
+        }],
+        "returnType": {
+          "repr": "Promise",
+          "kind": "typeRef",
+          "typeRef": { "typeName": "Promise", "typeParams": null }
+        },
+        "typeParams": [{ "name": "T" }]
+      }
+    });
+    let mut type_names = BTreeSet::new();
+    let mut type_params = HashSet::new();
+    collect_type_names(&node, &mut type_names, &mut type_params);
+    assert_eq!(
+      type_names.into_iter().collect::<Vec<_>>(),
+      vec!["Promise", "T", "http"]
+    );
+    assert_eq!(type_params, HashSet::from(["T".to_string()]));
+  }
+}
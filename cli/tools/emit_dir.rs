@@ -0,0 +1,367 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::ParsedSource;
+use deno_ast::SourceTextInfo;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_graph::DefaultModuleAnalyzer;
+use deno_graph::ModuleKind;
+use deno_runtime::colors;
+use serde::Serialize;
+
+use crate::proc_state::ProcState;
+use crate::tools::vendor::specifiers::get_unique_path;
+use crate::util::checksum;
+use crate::util::display::pluralize;
+use crate::util::fs::resolve_from_cwd;
+use crate::util::path::relative_specifier;
+use crate::util::path::root_url_to_safe_local_dirname;
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Describes the files written to the emit directory, so that builds using
+/// the output can be cached. The modules are keyed by their path relative to
+/// the directory containing the local modules, or by their url for remote
+/// modules.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EmitManifest {
+  modules: BTreeMap<String, EmittedModule>,
+  /// The remote modules and npm packages that were not emitted.
+  skipped: BTreeSet<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EmittedModule {
+  output: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  source_map: Option<String>,
+  checksum: String,
+}
+
+/// Writes the transpiled JavaScript of the modules reachable from the roots to
+/// the emit directory, mirroring the layout of the local modules. Remote
+/// modules are written under `deps/` when `emit_remote` is set, otherwise they
+/// are skipped along with npm packages, which are always left as `npm:`
+/// specifiers.
+pub fn emit_to_dir(
+  ps: &ProcState,
+  roots: &[ModuleSpecifier],
+  emit_dir: &Path,
+  emit_remote: bool,
+) -> Result<(), AnyError> {
+  let emit_dir = resolve_from_cwd(emit_dir)?;
+  let graph = ps.graph().segment(roots);
+  let mut manifest = EmitManifest::default();
+
+  let mut modules = Vec::new();
+  for module in graph.modules() {
+    match module.specifier.scheme() {
+      "file" => {}
+      "http" | "https" if emit_remote => {}
+      "http" | "https" | "npm" => {
+        manifest.skipped.insert(module.specifier.to_string());
+        continue;
+      }
+      _ => continue,
+    }
+    if module.kind != ModuleKind::External
+      && module.maybe_source.is_some()
+      && is_emittable(module.media_type)
+    {
+      modules.push(module);
+    }
+  }
+  modules.sort_by(|a, b| a.specifier.cmp(&b.specifier));
+
+  let local_paths = modules
+    .iter()
+    .filter_map(|module| module.specifier.to_file_path().ok())
+    .collect::<Vec<_>>();
+  let base_dir = common_dir(&local_paths);
+  let mut unique_paths = HashSet::new();
+  let mut output_paths = HashMap::new();
+  for module in &modules {
+    let (relative_path, manifest_key) = match &base_dir {
+      Some(base_dir) if module.specifier.scheme() == "file" => {
+        let path = module.specifier.to_file_path().unwrap();
+        let relative_path = path.strip_prefix(base_dir).unwrap().to_owned();
+        let key = path_to_slash(&relative_path);
+        (relative_path, key)
+      }
+      _ => (
+        Path::new("deps")
+          .join(root_url_to_safe_local_dirname(&module.specifier)),
+        module.specifier.to_string(),
+      ),
+    };
+    let output_path = get_unique_path(
+      with_output_extension(&emit_dir.join(relative_path), module.media_type),
+      &mut unique_paths,
+    );
+    output_paths.insert(module.specifier.clone(), (output_path, manifest_key));
+  }
+
+  for module in &modules {
+    let (output_path, manifest_key) = &output_paths[&module.specifier];
+    let output_specifier =
+      ModuleSpecifier::from_file_path(output_path).unwrap();
+    let source = module.maybe_source.clone().unwrap();
+    let (mut code, maybe_source_map) = if module.media_type == MediaType::Json {
+      (source.to_string(), None)
+    } else {
+      let parsed_source = ps.parsed_source_cache.get_or_parse_module(
+        &module.specifier,
+        source,
+        module.media_type,
+      )?;
+      // specifiers of the modules that were emitted are rewritten to point
+      // at their output, everything else is left as is
+      let code = rewrite_specifiers(&parsed_source, |text| {
+        let dependency = module.dependencies.get(text)?;
+        let specifier = graph.resolve(dependency.get_code()?);
+        let (dependency_path, _) = output_paths.get(&specifier)?;
+        let dependency_specifier =
+          ModuleSpecifier::from_file_path(dependency_path).unwrap();
+        relative_specifier(&output_specifier, &dependency_specifier)
+          .filter(|new_text| new_text != text)
+      });
+      if is_transpiled(module.media_type) {
+        // the source map refers to the rewritten source, which only differs
+        // from the original in the specifiers
+        let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+          specifier: module.specifier.to_string(),
+          text_info: SourceTextInfo::from_string(code),
+          media_type: module.media_type,
+          capture_tokens: false,
+          scope_analysis: false,
+          maybe_syntax: None,
+        })?;
+        let transpiled_source = parsed_source.transpile(&ps.emit_options)?;
+        (transpiled_source.text, transpiled_source.source_map)
+      } else {
+        (code, None)
+      }
+    };
+
+    let mut maybe_source_map_key = None;
+    if let Some(source_map) = maybe_source_map {
+      let file_name = output_path.file_name().unwrap().to_string_lossy();
+      let source_map_path =
+        output_path.with_file_name(format!("{file_name}.map"));
+      code.push_str(&format!("\n//# sourceMappingURL={file_name}.map\n"));
+      write_file(&source_map_path, &source_map)?;
+      maybe_source_map_key = Some(path_to_slash(
+        source_map_path.strip_prefix(&emit_dir).unwrap(),
+      ));
+    }
+    write_file(output_path, &code)?;
+    manifest.modules.insert(
+      manifest_key.clone(),
+      EmittedModule {
+        output: path_to_slash(output_path.strip_prefix(&emit_dir).unwrap()),
+        source_map: maybe_source_map_key,
+        checksum: checksum::gen(&[code.as_bytes()]),
+      },
+    );
+  }
+
+  let mut manifest_text = serde_json::to_string_pretty(&manifest)?;
+  manifest_text.push('\n');
+  write_file(&emit_dir.join(MANIFEST_FILE_NAME), &manifest_text)?;
+
+  log::info!(
+    "{} {} to {}",
+    colors::green("Emitted"),
+    pluralize(modules.len(), "module", "modules"),
+    emit_dir.display(),
+  );
+  Ok(())
+}
+
+fn is_emittable(media_type: MediaType) -> bool {
+  is_transpiled(media_type)
+    || matches!(
+      media_type,
+      MediaType::JavaScript | MediaType::Mjs | MediaType::Cjs | MediaType::Json
+    )
+}
+
+fn is_transpiled(media_type: MediaType) -> bool {
+  matches!(
+    media_type,
+    MediaType::TypeScript
+      | MediaType::Mts
+      | MediaType::Cts
+      | MediaType::Jsx
+      | MediaType::Tsx
+  )
+}
+
+/// Replaces the extension of a source file with the extension of its output.
+/// Remote modules might not have an extension, in which case it's appended.
+fn with_output_extension(path: &Path, media_type: MediaType) -> PathBuf {
+  let extension = match media_type {
+    MediaType::Mts | MediaType::Mjs => "mjs",
+    MediaType::Cts | MediaType::Cjs => "cjs",
+    MediaType::Json => "json",
+    _ => "js",
+  };
+  if MediaType::from(path) == MediaType::Unknown {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{extension}"));
+    PathBuf::from(path)
+  } else {
+    path.with_extension(extension)
+  }
+}
+
+/// Replaces the specifiers of the imports and exports in the module with the
+/// ones returned by `get_new_specifier`, keeping their quotes. The ranges come
+/// from the same analysis the module graph is built from, so strings that
+/// merely look like a specifier are left alone.
+fn rewrite_specifiers(
+  parsed_source: &ParsedSource,
+  get_new_specifier: impl Fn(&str) -> Option<String>,
+) -> String {
+  let text_info = parsed_source.text_info();
+  let text = text_info.text_str();
+  let source_start = text_info.range().start;
+  let mut dependencies =
+    DefaultModuleAnalyzer::module_info(parsed_source).dependencies;
+  dependencies
+    .sort_by(|a, b| a.specifier_range.start.cmp(&b.specifier_range.start));
+
+  let mut code = String::with_capacity(text.len());
+  let mut last_index = 0;
+  for dependency in dependencies {
+    let new_specifier = match get_new_specifier(&dependency.specifier) {
+      Some(new_specifier) => new_specifier,
+      None => continue,
+    };
+    let range = dependency.specifier_range.as_source_range(text_info);
+    // the range includes the quotes
+    let start = range.start.as_byte_index(source_start) + 1;
+    let end = range.end.as_byte_index(source_start) - 1;
+    code.push_str(&text[last_index..start]);
+    code.push_str(&new_specifier);
+    last_index = end;
+  }
+  code.push_str(&text[last_index..]);
+  code
+}
+
+/// Gets the deepest directory containing all the paths.
+fn common_dir(paths: &[PathBuf]) -> Option<PathBuf> {
+  let mut paths = paths.iter();
+  let mut common = paths.next()?.parent()?.to_owned();
+  for path in paths {
+    while !path.starts_with(&common) {
+      common = common.parent()?.to_owned();
+    }
+  }
+  Some(common)
+}
+
+pub fn path_to_slash(path: &Path) -> String {
+  path
+    .components()
+    .map(|c| c.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+fn write_file(path: &Path, text: &str) -> Result<(), AnyError> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)
+      .with_context(|| format!("Creating {}", parent.display()))?;
+  }
+  std::fs::write(path, text)
+    .with_context(|| format!("Writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_rewrite_specifiers() {
+    let code = r#"import { a } from "./a.ts";
+export * from './b.tsx';
+const c = await import("./c.ts");
+console.log("./a.ts", "./c.ts");
+import { d } from "./d.ts";
+"#;
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: "file:///main.ts".to_string(),
+      text_info: SourceTextInfo::from_string(code.to_string()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let code = rewrite_specifiers(&parsed_source, |text| match text {
+      "./a.ts" => Some("./a.js".to_string()),
+      "./b.tsx" => Some("./b.js".to_string()),
+      "./c.ts" => Some("./c.js".to_string()),
+      _ => None,
+    });
+    assert_eq!(
+      code,
+      r#"import { a } from "./a.js";
+export * from './b.js';
+const c = await import("./c.js");
+console.log("./a.ts", "./c.ts");
+import { d } from "./d.ts";
+"#
+    );
+  }
+
+  #[test]
+  fn test_common_dir() {
+    let root = if cfg!(windows) { "C:\\" } else { "/" };
+    let path = |text: &str| PathBuf::from(root).join(text);
+    assert_eq!(common_dir(&[]), None);
+    assert_eq!(
+      common_dir(&[path("project/src/main.ts")]),
+      Some(path("project/src"))
+    );
+    assert_eq!(
+      common_dir(&[
+        path("project/src/main.ts"),
+        path("project/src/util/mod.ts"),
+        path("project/test.ts"),
+      ]),
+      Some(path("project"))
+    );
+  }
+
+  #[test]
+  fn test_with_output_extension() {
+    let run = |path: &str, media_type: MediaType| {
+      path_to_slash(&with_output_extension(Path::new(path), media_type))
+    };
+    assert_eq!(run("src/main.ts", MediaType::TypeScript), "src/main.js");
+    assert_eq!(run("src/app.tsx", MediaType::Tsx), "src/app.js");
+    assert_eq!(run("src/mod.mts", MediaType::Mts), "src/mod.mjs");
+    assert_eq!(run("src/mod.cts", MediaType::Cts), "src/mod.cjs");
+    assert_eq!(run("data.json", MediaType::Json), "data.json");
+    assert_eq!(
+      run("deps/esm.sh/react@18.2.0", MediaType::JavaScript),
+      "deps/esm.sh/react@18.2.0.js"
+    );
+  }
+}
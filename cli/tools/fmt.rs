@@ -11,15 +11,27 @@ use crate::args::CliOptions;
 use crate::args::FilesConfig;
 use crate::args::FmtOptions;
 use crate::args::FmtOptionsConfig;
+use crate::args::NewLineKind;
 use crate::args::ProseWrap;
 use crate::colors;
+use crate::tools::fmt_yaml;
+use crate::tools::fmt_yaml::YamlFormatOptions;
+use crate::util::diff::changed_positions;
+use crate::util::diff::colored_unified_diff;
 use crate::util::diff::diff;
+use crate::util::editorconfig::EditorConfigResolver;
 use crate::util::file_watcher;
 use crate::util::file_watcher::ResolutionResult;
+use crate::util::fs::normalize_path;
 use crate::util::fs::FileCollector;
+use crate::util::glob::no_files_found_error;
 use crate::util::path::get_extension;
 use crate::util::text_encoding;
+use deno_ast::swc::common::comments::CommentKind;
+use deno_ast::MediaType;
 use deno_ast::ParsedSource;
+use deno_ast::SourceRangedForSpanned;
+use deno_ast::SourceTextInfo;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
@@ -30,11 +42,13 @@ use deno_core::parking_lot::Mutex;
 use log::debug;
 use log::info;
 use log::warn;
+use std::collections::HashMap;
 use std::fs;
 use std::io::stdin;
 use std::io::stdout;
 use std::io::Read;
 use std::io::Write;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicUsize;
@@ -54,6 +68,14 @@ pub async fn format(
 
   let files = fmt_options.files;
   let check = fmt_options.check;
+  let no_cache = fmt_options.no_cache;
+  let check_output = if fmt_options.compact {
+    CheckOutput::Compact
+  } else if fmt_options.diff {
+    CheckOutput::UnifiedDiff
+  } else {
+    CheckOutput::Diff
+  };
   let fmt_config_options = fmt_options.options;
 
   let resolver = |changed: Option<Vec<PathBuf>>| {
@@ -95,19 +117,40 @@ pub async fn format(
   };
   let deno_dir = &cli_options.resolve_deno_dir()?;
   let operation = |(paths, fmt_options): (Vec<PathBuf>, FmtOptionsConfig)| async move {
-    let incremental_cache = Arc::new(IncrementalCache::new(
-      &deno_dir.fmt_incremental_cache_db_file_path(),
-      &fmt_options,
-      &paths,
-    ));
-    if check {
-      check_source_files(paths, fmt_options, incremental_cache.clone()).await?;
+    let editorconfig = EditorConfigResolver::default();
+    let file_fmt_options = paths
+      .iter()
+      .map(|path| {
+        let properties = editorconfig.properties(path);
+        (path.clone(), fmt_options.with_editorconfig(&properties))
+      })
+      .collect::<HashMap<_, _>>();
+    let incremental_cache = Arc::new(if no_cache {
+      IncrementalCache::disabled()
     } else {
-      format_source_files(paths, fmt_options, incremental_cache.clone())
-        .await?;
-    }
+      // the formatting of the files also depends on their .editorconfig files
+      IncrementalCache::new(
+        &deno_dir.fmt_incremental_cache_db_file_path(),
+        &(&fmt_options, editorconfig.loaded_files()),
+        &paths,
+      )
+    });
+    let file_fmt_options = Arc::new(file_fmt_options);
+    let result = if check {
+      check_source_files(
+        paths,
+        file_fmt_options,
+        check_output,
+        incremental_cache.clone(),
+      )
+      .await
+    } else {
+      format_source_files(paths, file_fmt_options, incremental_cache.clone())
+        .await
+    };
+    // the files found to be formatted are cached even when others aren't
     incremental_cache.wait_completion().await;
-    Ok(())
+    result
   };
 
   if cli_options.watch_paths().is_some() {
@@ -117,15 +160,19 @@ pub async fn format(
       file_watcher::PrintConfig {
         job_name: "Fmt".to_string(),
         clear_screen: !cli_options.no_clear_screen(),
+        stack_trace_options: cli_options.stack_trace_options(),
       },
     )
     .await?;
   } else {
-    let files = collect_fmt_files(&files).and_then(|files| {
-      if files.is_empty() {
-        Err(generic_error("No target files found."))
+    let files = collect_fmt_files(&files).and_then(|collected| {
+      if collected.is_empty() {
+        Err(no_files_found_error(
+          "No target files found.",
+          &files.include,
+        ))
       } else {
-        Ok(files)
+        Ok(collected)
       }
     })?;
     operation((files, fmt_config_options)).await?;
@@ -153,53 +200,204 @@ fn format_markdown(
     file_text,
     &markdown_config,
     move |tag, text, line_width| {
-      let tag = tag.to_lowercase();
-      if matches!(
-        tag.as_str(),
-        "ts"
-          | "tsx"
-          | "js"
-          | "jsx"
-          | "cjs"
-          | "cts"
-          | "mjs"
-          | "mts"
-          | "javascript"
-          | "typescript"
-          | "json"
-          | "jsonc"
-      ) {
-        // It's important to tell dprint proper file extension, otherwise
-        // it might parse the file twice.
-        let extension = match tag.as_str() {
-          "javascript" => "js",
-          "typescript" => "ts",
-          rest => rest,
-        };
-
-        if matches!(extension, "json" | "jsonc") {
-          let mut json_config = get_resolved_json_config(fmt_options);
-          json_config.line_width = line_width;
-          dprint_plugin_json::format_text(text, &json_config)
-        } else {
-          let fake_filename =
-            PathBuf::from(format!("deno_fmt_stdin.{extension}"));
-          let mut codeblock_config =
-            get_resolved_typescript_config(fmt_options);
-          codeblock_config.line_width = line_width;
-          dprint_plugin_typescript::format_text(
-            &fake_filename,
-            text,
-            &codeblock_config,
-          )
-        }
-      } else {
-        Ok(None)
-      }
+      Ok(format_code_block(tag, text, line_width, fmt_options))
     },
   )
 }
 
+/// Formats a fenced code block of a markdown file or of a JSDoc comment.
+/// Blocks of other languages, and ones which fail to parse, are left as they
+/// are rather than failing the whole file.
+fn format_code_block(
+  tag: &str,
+  text: &str,
+  line_width: u32,
+  fmt_options: &FmtOptionsConfig,
+) -> Option<String> {
+  if fmt_options.code_blocks == Some(false) {
+    return None;
+  }
+  let tag = tag.to_lowercase();
+  if !matches!(
+    tag.as_str(),
+    "ts"
+      | "tsx"
+      | "js"
+      | "jsx"
+      | "cjs"
+      | "cts"
+      | "mjs"
+      | "mts"
+      | "javascript"
+      | "typescript"
+      | "json"
+      | "jsonc"
+  ) {
+    return None;
+  }
+  // It's important to tell dprint proper file extension, otherwise
+  // it might parse the file twice.
+  let extension = match tag.as_str() {
+    "javascript" => "js",
+    "typescript" => "ts",
+    rest => rest,
+  };
+
+  let result = if matches!(extension, "json" | "jsonc") {
+    let mut json_config = get_resolved_json_config(fmt_options);
+    json_config.line_width = line_width;
+    dprint_plugin_json::format_text(text, &json_config)
+  } else {
+    let fake_filename = PathBuf::from(format!("deno_fmt_stdin.{extension}"));
+    let mut codeblock_config = get_resolved_typescript_config(fmt_options);
+    codeblock_config.line_width = line_width;
+    dprint_plugin_typescript::format_text(
+      &fake_filename,
+      text,
+      &codeblock_config,
+    )
+  };
+  match result {
+    Ok(maybe_formatted_text) => maybe_formatted_text,
+    Err(err) => {
+      debug!("Leaving a {} code block unformatted: {:#}", tag, err);
+      None
+    }
+  }
+}
+
+/// Formats the fenced code blocks in the JSDoc comments of a JavaScript or
+/// TypeScript file.
+fn format_jsdoc_code_blocks(
+  specifier: &str,
+  media_type: MediaType,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Option<String> {
+  if fmt_options.code_blocks == Some(false) || !file_text.contains("```") {
+    return None;
+  }
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier: specifier.to_string(),
+    text_info: SourceTextInfo::new(file_text.into()),
+    media_type,
+    capture_tokens: false,
+    scope_analysis: false,
+    maybe_syntax: None,
+  })
+  .ok()?;
+  let source_start = parsed_source.text_info().range().start;
+  let mut comment_ranges = parsed_source
+    .comments()
+    .get_vec()
+    .iter()
+    .filter(|comment| {
+      comment.kind == CommentKind::Block && comment.text.starts_with('*')
+    })
+    .map(|comment| {
+      let range = comment.range();
+      range.start.as_byte_index(source_start)
+        ..range.end.as_byte_index(source_start)
+    })
+    .collect::<Vec<_>>();
+  comment_ranges.sort_by_key(|range| range.start);
+  comment_ranges.dedup();
+
+  let line_width = get_resolved_typescript_config(fmt_options).line_width;
+  let mut text = String::with_capacity(file_text.len());
+  let mut last_end = 0;
+  for range in comment_ranges {
+    let comment = &file_text[range.clone()];
+    if let Some(formatted_comment) =
+      format_jsdoc_comment(comment, line_width, fmt_options)
+    {
+      text.push_str(&file_text[last_end..range.start]);
+      text.push_str(&formatted_comment);
+      last_end = range.end;
+    }
+  }
+  if last_end == 0 {
+    return None;
+  }
+  text.push_str(&file_text[last_end..]);
+  Some(text)
+}
+
+/// Splits a line of a JSDoc comment into its prefix, which is the
+/// indentation and the leading `*` followed by a space, and its content.
+fn split_jsdoc_line(line: &str) -> Option<(&str, &str)> {
+  let rest = line.trim_start().strip_prefix('*')?;
+  let content = rest.strip_prefix(' ').unwrap_or(rest);
+  Some((&line[..line.len() - content.len()], content))
+}
+
+/// Formats the fenced code blocks of a JSDoc comment, keeping the indentation
+/// and the `*` of the comment's lines.
+fn format_jsdoc_comment(
+  comment: &str,
+  line_width: u32,
+  fmt_options: &FmtOptionsConfig,
+) -> Option<String> {
+  let lines = comment.split('\n').collect::<Vec<_>>();
+  let mut output = Vec::with_capacity(lines.len());
+  let mut has_changes = false;
+  let mut index = 0;
+  while index < lines.len() {
+    let line = lines[index];
+    output.push(line.to_string());
+    index += 1;
+    let maybe_fence = split_jsdoc_line(line).and_then(|(_, content)| {
+      let info = content.trim_start().strip_prefix("```")?;
+      let code_line_count = lines[index..].iter().position(|line| {
+        split_jsdoc_line(line).map_or(false, |(_, c)| c.trim() == "```")
+      })?;
+      Some((content, info, code_line_count))
+    });
+    let (content, info, code_line_count) = match maybe_fence {
+      Some(fence) => fence,
+      None => continue,
+    };
+    let code_lines = &lines[index..index + code_line_count];
+    // the language may be followed by attributes, like `ts ignore`
+    let tag = info
+      .split(|c: char| c.is_whitespace() || c == ',')
+      .next()
+      .unwrap_or_default();
+    let maybe_code = code_lines
+      .iter()
+      .map(|line| split_jsdoc_line(line).map(|(_, content)| content))
+      .collect::<Option<Vec<_>>>();
+    // the code lines are indented like the opening fence
+    let prefix = &line[..line.len() - content.trim_start().len()];
+    let maybe_formatted_code = maybe_code.and_then(|code| {
+      let code = code.join("\n");
+      let code_line_width = line_width
+        .saturating_sub(prefix.chars().count() as u32)
+        .max(1);
+      format_code_block(tag, &code, code_line_width, fmt_options)
+        .map(|formatted_code| formatted_code.trim_end().to_string())
+        .filter(|formatted_code| *formatted_code != code)
+    });
+    match maybe_formatted_code {
+      Some(formatted_code) => {
+        for code_line in formatted_code.split('\n') {
+          if code_line.is_empty() {
+            output.push(prefix.trim_end().to_string());
+          } else {
+            output.push(format!("{prefix}{code_line}"));
+          }
+        }
+        has_changes = true;
+      }
+      None => output.extend(code_lines.iter().map(|line| line.to_string())),
+    }
+    // the closing fence
+    output.push(lines[index + code_line_count].to_string());
+    index += code_line_count + 1;
+  }
+  has_changes.then(|| output.join("\n"))
+}
+
 /// Formats JSON and JSONC using the rules provided by .deno()
 /// of configuration builder of <https://github.com/dprint/dprint-plugin-json>.
 /// See <https://github.com/dprint/dprint-plugin-json/blob/cfa1052dbfa0b54eb3d814318034cdc514c813d7/src/configuration/builder.rs#L87> for configuration.
@@ -211,39 +409,288 @@ pub fn format_json(
   dprint_plugin_json::format_text(file_text, &config)
 }
 
-/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, or MD file.
+/// Formats YAML with the line based formatter of `fmt_yaml`. A file is left
+/// as it is when one of its leading comments is `# deno-fmt-ignore-file`.
+pub fn format_yaml(
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+) -> Result<Option<String>, AnyError> {
+  let ignore_file = file_text
+    .lines()
+    .map(|line| line.trim())
+    .take_while(|line| line.starts_with('#'))
+    .any(|line| line[1..].trim() == "deno-fmt-ignore-file");
+  if ignore_file {
+    return Ok(None);
+  }
+  let config = get_resolved_yaml_config(fmt_options);
+  fmt_yaml::format_text(file_text, &config)
+}
+
+/// Formats a single TS, TSX, JS, JSX, JSONC, JSON, MD, or YAML file.
 pub fn format_file(
   file_path: &Path,
   file_text: &str,
   fmt_options: &FmtOptionsConfig,
 ) -> Result<Option<String>, AnyError> {
-  let ext = get_extension(file_path).unwrap_or_default();
-  if matches!(
-    ext.as_str(),
-    "md" | "mkd" | "mkdn" | "mdwn" | "mdown" | "markdown"
-  ) {
-    format_markdown(file_text, fmt_options)
-  } else if matches!(ext.as_str(), "json" | "jsonc") {
-    format_json(file_text, fmt_options)
-  } else {
-    let config = get_resolved_typescript_config(fmt_options);
-    dprint_plugin_typescript::format_text(file_path, file_text, &config)
-  }
+  let syntax = CommentSyntax::from_path(file_path);
+  let maybe_formatted_text =
+    format_with_ignored_ranges(file_text, syntax, || {
+      let ext = get_extension(file_path).unwrap_or_default();
+      if matches!(
+        ext.as_str(),
+        "md" | "mkd" | "mkdn" | "mdwn" | "mdown" | "markdown"
+      ) {
+        format_markdown(file_text, fmt_options)
+      } else if matches!(ext.as_str(), "json" | "jsonc") {
+        format_json(file_text, fmt_options)
+      } else if matches!(ext.as_str(), "yml" | "yaml") {
+        format_yaml(file_text, fmt_options)
+      } else {
+        let config = get_resolved_typescript_config(fmt_options);
+        let maybe_formatted_text =
+          dprint_plugin_typescript::format_text(file_path, file_text, &config)?;
+        let maybe_code_blocks_text = format_jsdoc_code_blocks(
+          &file_path.to_string_lossy(),
+          MediaType::from(file_path),
+          maybe_formatted_text.as_deref().unwrap_or(file_text),
+          fmt_options,
+        );
+        Ok(maybe_code_blocks_text.or(maybe_formatted_text))
+      }
+    })?;
+  Ok(with_new_line_kind(
+    file_text,
+    maybe_formatted_text,
+    fmt_options,
+  ))
 }
 
 pub fn format_parsed_source(
   parsed_source: &ParsedSource,
   fmt_options: &FmtOptionsConfig,
 ) -> Result<Option<String>, AnyError> {
-  dprint_plugin_typescript::format_parsed_source(
-    parsed_source,
-    &get_resolved_typescript_config(fmt_options),
-  )
+  let file_text = parsed_source.text_info().text_str();
+  let syntax = CommentSyntax::Script;
+  let maybe_formatted_text =
+    format_with_ignored_ranges(file_text, syntax, || {
+      let maybe_formatted_text =
+        dprint_plugin_typescript::format_parsed_source(
+          parsed_source,
+          &get_resolved_typescript_config(fmt_options),
+        )?;
+      let maybe_code_blocks_text = format_jsdoc_code_blocks(
+        parsed_source.specifier(),
+        parsed_source.media_type(),
+        maybe_formatted_text.as_deref().unwrap_or(file_text),
+        fmt_options,
+      );
+      Ok(maybe_code_blocks_text.or(maybe_formatted_text))
+    })?;
+  Ok(with_new_line_kind(
+    file_text,
+    maybe_formatted_text,
+    fmt_options,
+  ))
+}
+
+/// Converts the line endings to CRLF when the options ask for it, since the
+/// formatters always output LF.
+fn with_new_line_kind(
+  file_text: &str,
+  maybe_formatted_text: Option<String>,
+  fmt_options: &FmtOptionsConfig,
+) -> Option<String> {
+  match fmt_options.new_line_kind {
+    Some(NewLineKind::CarriageReturnLineFeed) => {
+      let text = maybe_formatted_text.as_deref().unwrap_or(file_text);
+      let text = text.replace("\r\n", "\n").replace('\n', "\r\n");
+      (text != file_text).then_some(text)
+    }
+    Some(NewLineKind::LineFeed) | None => maybe_formatted_text,
+  }
+}
+
+const IGNORE_START_DIRECTIVE: &str = "deno-fmt-ignore-start";
+const IGNORE_END_DIRECTIVE: &str = "deno-fmt-ignore-end";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IgnoreDirective {
+  Start,
+  End,
 }
 
+impl IgnoreDirective {
+  fn from_line(line: &str, syntax: CommentSyntax) -> Option<Self> {
+    match syntax.comment_text(line.trim())?.trim() {
+      IGNORE_START_DIRECTIVE => Some(IgnoreDirective::Start),
+      IGNORE_END_DIRECTIVE => Some(IgnoreDirective::End),
+      _ => None,
+    }
+  }
+
+  fn as_str(&self) -> &'static str {
+    match self {
+      IgnoreDirective::Start => IGNORE_START_DIRECTIVE,
+      IgnoreDirective::End => IGNORE_END_DIRECTIVE,
+    }
+  }
+}
+
+/// The comments the ignore directives of a file are written in, which only
+/// include the comments valid in the file's language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommentSyntax {
+  /// `//` and `/* */` comments in JS, TS, and JSON.
+  Script,
+  /// `<!-- -->` comments in markdown.
+  Markdown,
+  /// `#` comments in YAML.
+  Yaml,
+}
+
+impl CommentSyntax {
+  fn from_path(file_path: &Path) -> Self {
+    let ext = get_extension(file_path).unwrap_or_default();
+    match ext.as_str() {
+      "md" | "mkd" | "mkdn" | "mdwn" | "mdown" | "markdown" => {
+        CommentSyntax::Markdown
+      }
+      "yml" | "yaml" => CommentSyntax::Yaml,
+      _ => CommentSyntax::Script,
+    }
+  }
+
+  fn comment_text(self, line: &str) -> Option<&str> {
+    match self {
+      CommentSyntax::Script => line
+        .strip_prefix("//")
+        .or_else(|| line.strip_prefix("/*").and_then(|t| t.strip_suffix("*/"))),
+      CommentSyntax::Markdown => line
+        .strip_prefix("<!--")
+        .and_then(|t| t.strip_suffix("-->")),
+      CommentSyntax::Yaml => line.strip_prefix('#'),
+    }
+  }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct IgnoredRanges {
+  /// Byte ranges of the text between matched start and end directives.
+  ranges: Vec<Range<usize>>,
+  /// Directives without a matching pair along with their one-based line.
+  unmatched: Vec<(usize, IgnoreDirective)>,
+}
+
+/// Finds the text between `deno-fmt-ignore-start` and `deno-fmt-ignore-end`
+/// comment directives, which each must be on their own line.
+fn get_ignored_ranges(
+  file_text: &str,
+  syntax: CommentSyntax,
+) -> Result<IgnoredRanges, AnyError> {
+  let mut result = IgnoredRanges::default();
+  let mut maybe_start: Option<(usize, usize)> = None;
+  let mut offset = 0;
+  for (index, line) in file_text.split_inclusive('\n').enumerate() {
+    let line_number = index + 1;
+    match IgnoreDirective::from_line(line, syntax) {
+      Some(IgnoreDirective::Start) => {
+        if let Some((start_line_number, _)) = maybe_start {
+          bail!(
+            "Nested {IGNORE_START_DIRECTIVE} directive on line {line_number}. The directive on line {start_line_number} was not closed."
+          );
+        }
+        maybe_start = Some((line_number, offset + line.len()));
+      }
+      Some(IgnoreDirective::End) => match maybe_start.take() {
+        Some((_, start)) => result.ranges.push(start..offset),
+        None => result.unmatched.push((line_number, IgnoreDirective::End)),
+      },
+      None => {}
+    }
+    offset += line.len();
+  }
+  if let Some((line_number, _)) = maybe_start {
+    result.unmatched.push((line_number, IgnoreDirective::Start));
+  }
+  Ok(result)
+}
+
+/// Warns about any ignore directives in the file that have no matching pair.
+pub fn warn_unmatched_ignore_directives(file_path: &Path, file_text: &str) {
+  // errors are surfaced when formatting the file
+  let syntax = CommentSyntax::from_path(file_path);
+  if let Ok(ignored_ranges) = get_ignored_ranges(file_text, syntax) {
+    for (line_number, directive) in ignored_ranges.unmatched {
+      warn!(
+        "{} Unmatched {} directive at {}:{}",
+        colors::yellow("Warning"),
+        directive.as_str(),
+        file_path.display(),
+        line_number,
+      );
+    }
+  }
+}
+
+/// Formats the text using the provided function, then restores the original
+/// text between any ignore start and end directives byte-for-byte.
+fn format_with_ignored_ranges(
+  file_text: &str,
+  syntax: CommentSyntax,
+  format: impl FnOnce() -> Result<Option<String>, AnyError>,
+) -> Result<Option<String>, AnyError> {
+  let ignored_ranges = get_ignored_ranges(file_text, syntax)?.ranges;
+  let formatted_text = match format()? {
+    Some(formatted_text) => formatted_text,
+    None => return Ok(None),
+  };
+  if ignored_ranges.is_empty() {
+    return Ok(Some(formatted_text));
+  }
+
+  let formatted_ranges = get_ignored_ranges(&formatted_text, syntax)?.ranges;
+  if formatted_ranges.len() != ignored_ranges.len() {
+    bail!(
+      "Formatting changed the {IGNORE_START_DIRECTIVE} and {IGNORE_END_DIRECTIVE} directives."
+    );
+  }
+  let mut text = String::with_capacity(formatted_text.len());
+  let mut last_end = 0;
+  for (original, formatted) in ignored_ranges.iter().zip(formatted_ranges) {
+    text.push_str(&formatted_text[last_end..formatted.start]);
+    text.push_str(&file_text[original.clone()]);
+    last_end = formatted.end;
+  }
+  text.push_str(&formatted_text[last_end..]);
+
+  // the only differences may have been within the ignored ranges
+  if text == file_text {
+    Ok(None)
+  } else {
+    Ok(Some(text))
+  }
+}
+
+/// How `deno fmt --check` shows the changes of the not formatted files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CheckOutput {
+  /// The changed lines side by side with their line numbers.
+  Diff,
+  /// A unified diff, with `--diff`.
+  UnifiedDiff,
+  /// A `path:line:col: Not formatted` line per changed line, with
+  /// `--compact`.
+  Compact,
+}
+
+/// The number of hunks of a file's unified diff shown with `--diff`.
+const MAX_DIFF_HUNKS: usize = 20;
+
 async fn check_source_files(
   paths: Vec<PathBuf>,
-  fmt_options: FmtOptionsConfig,
+  file_fmt_options: Arc<HashMap<PathBuf, FmtOptionsConfig>>,
+  check_output: CheckOutput,
   incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let not_formatted_files_count = Arc::new(AtomicUsize::new(0));
@@ -258,20 +705,50 @@ async fn check_source_files(
     move |file_path| {
       checked_files_count.fetch_add(1, Ordering::Relaxed);
       let file_text = read_file_contents(&file_path)?.text;
+      warn_unmatched_ignore_directives(&file_path, &file_text);
 
       // skip checking the file if we know it's formatted
       if incremental_cache.is_file_same(&file_path, &file_text) {
         return Ok(());
       }
 
-      match format_file(&file_path, &file_text, &fmt_options) {
+      let fmt_options = &file_fmt_options[&file_path];
+      match format_file(&file_path, &file_text, fmt_options) {
         Ok(Some(formatted_text)) => {
           not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
           let _g = output_lock.lock();
-          let diff = diff(&file_text, &formatted_text);
-          info!("");
-          info!("{} {}:", colors::bold("from"), file_path.display());
-          info!("{}", diff);
+          match check_output {
+            CheckOutput::Diff => {
+              let diff = diff(&file_text, &formatted_text);
+              info!("");
+              info!("{} {}:", colors::bold("from"), file_path.display());
+              info!("{}", diff);
+            }
+            CheckOutput::UnifiedDiff => {
+              let path = file_path.display().to_string();
+              let diff = colored_unified_diff(
+                &path,
+                &format!("{path} (formatted)"),
+                &file_text,
+                &formatted_text,
+                MAX_DIFF_HUNKS,
+              );
+              info!("");
+              info!("{}", diff.trim_end());
+            }
+            CheckOutput::Compact => {
+              for (line, column) in
+                changed_positions(&file_text, &formatted_text)
+              {
+                info!(
+                  "{}:{}:{}: Not formatted",
+                  file_path.display(),
+                  line,
+                  column
+                );
+              }
+            }
+          }
         }
         Ok(None) => {
           // When checking formatting, only update the incremental cache when
@@ -324,7 +801,7 @@ async fn check_source_files(
 
 async fn format_source_files(
   paths: Vec<PathBuf>,
-  fmt_options: FmtOptionsConfig,
+  file_fmt_options: Arc<HashMap<PathBuf, FmtOptionsConfig>>,
   incremental_cache: Arc<IncrementalCache>,
 ) -> Result<(), AnyError> {
   let formatted_files_count = Arc::new(AtomicUsize::new(0));
@@ -337,6 +814,7 @@ async fn format_source_files(
     move |file_path| {
       checked_files_count.fetch_add(1, Ordering::Relaxed);
       let file_contents = read_file_contents(&file_path)?;
+      warn_unmatched_ignore_directives(&file_path, &file_contents.text);
 
       // skip formatting the file if we know it's formatted
       if incremental_cache.is_file_same(&file_path, &file_contents.text) {
@@ -346,7 +824,7 @@ async fn format_source_files(
       match format_ensure_stable(
         &file_path,
         &file_contents.text,
-        &fmt_options,
+        &file_fmt_options[&file_path],
         format_file,
       ) {
         Ok(Some(formatted_text)) => {
@@ -456,15 +934,45 @@ fn format_ensure_stable(
 }
 
 /// Format stdin and write result to stdout.
-/// Treats input as TypeScript or as set by `--ext` flag.
+/// Treats input as TypeScript or as set by `--ext` flag, and as the contents
+/// of the file at `--stdin-filepath` when passed.
 /// Compatible with `--check` flag.
 fn format_stdin(fmt_options: FmtOptions) -> Result<(), AnyError> {
   let mut source = String::new();
   if stdin().read_to_string(&mut source).is_err() {
     bail!("Failed to read from stdin");
   }
-  let file_path = PathBuf::from(format!("_stdin.{}", fmt_options.ext));
-  let formatted_text = format_file(&file_path, &source, &fmt_options.options)?;
+  let cwd = std::env::current_dir()
+    .context("Failed to get current working directory")?;
+  // stdin is formatted like a file in the current directory by default
+  let stdin_filepath = normalize_path(
+    cwd.join(
+      fmt_options
+        .stdin_filepath
+        .as_deref()
+        .unwrap_or_else(|| Path::new("_stdin")),
+    ),
+  );
+  if fmt_options.stdin_filepath.is_some()
+    && !fmt_options
+      .files
+      .to_patterns(&cwd)?
+      .matches_path(&stdin_filepath)
+  {
+    debug!(
+      "Not formatting stdin because {} is excluded",
+      stdin_filepath.display()
+    );
+    if !fmt_options.check {
+      stdout().write_all(source.as_bytes())?;
+    }
+    return Ok(());
+  }
+  let file_path = stdin_filepath.with_extension(&fmt_options.ext);
+  warn_unmatched_ignore_directives(&file_path, &source);
+  let properties = EditorConfigResolver::default().properties(&stdin_filepath);
+  let options = fmt_options.options.with_editorconfig(&properties);
+  let formatted_text = format_file(&file_path, &source, &options)?;
   if fmt_options.check {
     if formatted_text.is_some() {
       println!("Not formatted stdin");
@@ -572,12 +1080,30 @@ fn get_resolved_json_config(
   builder.build()
 }
 
-struct FileContents {
-  text: String,
-  had_bom: bool,
+fn get_resolved_yaml_config(options: &FmtOptionsConfig) -> YamlFormatOptions {
+  let yaml_options = options.yaml.clone().unwrap_or_default();
+  let indent_width = yaml_options
+    .indent_width
+    .or(options.indent_width)
+    .unwrap_or(2);
+  let single_quote = yaml_options
+    .single_quote
+    .or(options.single_quote)
+    .unwrap_or(false);
+
+  // YAML can't be indented with tabs, so `useTabs` doesn't apply
+  YamlFormatOptions {
+    indent_width: indent_width as usize,
+    single_quote,
+  }
+}
+
+pub struct FileContents {
+  pub text: String,
+  pub had_bom: bool,
 }
 
-fn read_file_contents(file_path: &Path) -> Result<FileContents, AnyError> {
+pub fn read_file_contents(file_path: &Path) -> Result<FileContents, AnyError> {
   let file_bytes = fs::read(file_path)
     .with_context(|| format!("Error reading {}", file_path.display()))?;
   let charset = text_encoding::detect_charset(&file_bytes);
@@ -654,7 +1180,7 @@ where
 
 /// This function is similar to is_supported_ext but adds additional extensions
 /// supported by `deno fmt`.
-fn is_supported_ext_fmt(path: &Path) -> bool {
+pub fn is_supported_ext_fmt(path: &Path) -> bool {
   if let Some(ext) = get_extension(path) {
     matches!(
       ext.as_str(),
@@ -674,6 +1200,8 @@ fn is_supported_ext_fmt(path: &Path) -> bool {
         | "mdwn"
         | "mdown"
         | "markdown"
+        | "yml"
+        | "yaml"
     )
   } else {
     false
@@ -683,6 +1211,7 @@ fn is_supported_ext_fmt(path: &Path) -> bool {
 #[cfg(test)]
 mod test {
   use super::*;
+  use crate::args::YamlFmtOptionsConfig;
 
   #[test]
   fn test_is_supported_ext_fmt() {
@@ -709,6 +1238,8 @@ mod test {
     assert!(is_supported_ext_fmt(Path::new("foo.JSONC")));
     assert!(is_supported_ext_fmt(Path::new("foo.json")));
     assert!(is_supported_ext_fmt(Path::new("foo.JsON")));
+    assert!(is_supported_ext_fmt(Path::new(".github/workflows/ci.yml")));
+    assert!(is_supported_ext_fmt(Path::new("foo.YAML")));
   }
 
   #[test]
@@ -774,4 +1305,310 @@ mod test {
 
     assert_eq!(result, Some("11".to_string()));
   }
+
+  #[test]
+  fn test_get_ignored_ranges() {
+    let text = concat!(
+      "a\n",
+      "// deno-fmt-ignore-start\n",
+      "b\n",
+      "  /* deno-fmt-ignore-end */\n",
+      "// deno-fmt-ignore-end\n",
+      "<!-- deno-fmt-ignore-start -->\n",
+      "# deno-fmt-ignore-start\n",
+      "// deno-fmt-ignore-start\n",
+      "c\n",
+    );
+    let result = get_ignored_ranges(text, CommentSyntax::Script).unwrap();
+    assert_eq!(
+      result,
+      IgnoredRanges {
+        ranges: vec![27..29],
+        unmatched: vec![(5, IgnoreDirective::End), (8, IgnoreDirective::Start)],
+      }
+    );
+    assert_eq!(&text[result.ranges[0].clone()], "b\n");
+
+    let text = concat!(
+      "# deno-fmt-ignore-start\n",
+      "a: 1\n",
+      "// deno-fmt-ignore-end\n",
+      "#deno-fmt-ignore-end\n",
+    );
+    let result = get_ignored_ranges(text, CommentSyntax::Yaml).unwrap();
+    assert_eq!(
+      result,
+      IgnoredRanges {
+        ranges: vec![24..52],
+        unmatched: vec![],
+      }
+    );
+
+    let err = get_ignored_ranges(
+      concat!(
+        "// deno-fmt-ignore-start\n",
+        "// deno-fmt-ignore-start\n",
+        "// deno-fmt-ignore-end\n",
+        "// deno-fmt-ignore-end\n",
+      ),
+      CommentSyntax::Script,
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+      err.to_string(),
+      concat!(
+        "Nested deno-fmt-ignore-start directive on line 2. ",
+        "The directive on line 1 was not closed."
+      )
+    );
+  }
+
+  #[test]
+  fn test_format_file_ignored_ranges() {
+    let text = concat!(
+      "const a   =  1;\n",
+      "// deno-fmt-ignore-start\n",
+      "const table = [\n",
+      "  1,    2,\n",
+      "  300,  4,\n",
+      "];\n",
+      "// deno-fmt-ignore-end\n",
+      "const b   =  2;\n",
+    );
+    let result =
+      format_file(&PathBuf::from("mod.ts"), text, &Default::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+      result,
+      concat!(
+        "const a = 1;\n",
+        "// deno-fmt-ignore-start\n",
+        "const table = [\n",
+        "  1,    2,\n",
+        "  300,  4,\n",
+        "];\n",
+        "// deno-fmt-ignore-end\n",
+        "const b = 2;\n",
+      )
+    );
+
+    // only differences within the ignored ranges means it's formatted
+    let result =
+      format_file(&PathBuf::from("mod.ts"), &result, &Default::default())
+        .unwrap();
+    assert_eq!(result, None);
+  }
+
+  #[test]
+  fn test_format_file_ignored_ranges_markdown_and_json() {
+    let text = concat!(
+      "#   Title\n",
+      "\n",
+      "<!-- deno-fmt-ignore-start -->\n",
+      "| a   | b |\n",
+      "|--|--|\n",
+      "<!-- deno-fmt-ignore-end -->\n",
+    );
+    let result =
+      format_file(&PathBuf::from("mod.md"), text, &Default::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+      result,
+      concat!(
+        "# Title\n",
+        "\n",
+        "<!-- deno-fmt-ignore-start -->\n",
+        "| a   | b |\n",
+        "|--|--|\n",
+        "<!-- deno-fmt-ignore-end -->\n",
+      )
+    );
+
+    let text = concat!(
+      "{\n",
+      "  // deno-fmt-ignore-start\n",
+      "  \"a\":    [1,   2],\n",
+      "  // deno-fmt-ignore-end\n",
+      "  \"b\":    1\n",
+      "}\n",
+    );
+    let result =
+      format_file(&PathBuf::from("mod.jsonc"), text, &Default::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+      result,
+      concat!(
+        "{\n",
+        "  // deno-fmt-ignore-start\n",
+        "  \"a\":    [1,   2],\n",
+        "  // deno-fmt-ignore-end\n",
+        "  \"b\": 1\n",
+        "}\n",
+      )
+    );
+  }
+
+  #[test]
+  fn test_format_file_ignored_ranges_markdown_heading() {
+    // a heading is not a comment in markdown
+    let text = concat!(
+      "# deno-fmt-ignore-start\n",
+      "\n",
+      "#   Title\n",
+      "\n",
+      "# deno-fmt-ignore-end\n",
+    );
+    let result = get_ignored_ranges(text, CommentSyntax::Markdown).unwrap();
+    assert_eq!(result, IgnoredRanges::default());
+    let result =
+      format_file(&PathBuf::from("mod.md"), text, &Default::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+      result,
+      concat!(
+        "# deno-fmt-ignore-start\n",
+        "\n",
+        "# Title\n",
+        "\n",
+        "# deno-fmt-ignore-end\n",
+      )
+    );
+  }
+
+  #[test]
+  fn test_format_file_jsdoc_code_blocks() {
+    let text = concat!(
+      "/**\n",
+      " * Adds numbers.\n",
+      " *\n",
+      " * ```ts\n",
+      " * const sum = add(1,2)\n",
+      " *\n",
+      " * console.log( sum )\n",
+      " * ```\n",
+      " *\n",
+      " * ```ts\n",
+      " * const broken = (\n",
+      " * ```\n",
+      " */\n",
+      "export function add(a:number, b:number) {\n",
+      "  return a + b;\n",
+      "}\n",
+    );
+    let result =
+      format_file(&PathBuf::from("mod.ts"), text, &Default::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+      result,
+      concat!(
+        "/**\n",
+        " * Adds numbers.\n",
+        " *\n",
+        " * ```ts\n",
+        " * const sum = add(1, 2);\n",
+        " *\n",
+        " * console.log(sum);\n",
+        " * ```\n",
+        " *\n",
+        " * ```ts\n",
+        " * const broken = (\n",
+        " * ```\n",
+        " */\n",
+        "export function add(a: number, b: number) {\n",
+        "  return a + b;\n",
+        "}\n",
+      )
+    );
+
+    // the blocks are left alone when opted out
+    let options = FmtOptionsConfig {
+      code_blocks: Some(false),
+      ..Default::default()
+    };
+    let result = format_file(&PathBuf::from("mod.ts"), text, &options)
+      .unwrap()
+      .unwrap();
+    assert!(result.contains(" * const sum = add(1,2)\n"));
+  }
+
+  #[test]
+  fn test_format_file_markdown_code_blocks() {
+    let text = concat!(
+      "# Example\n",
+      "\n",
+      "```ts\n",
+      "const a=1\n",
+      "```\n",
+      "\n",
+      "```js\n",
+      "const b = (\n",
+      "```\n",
+    );
+    let result =
+      format_file(&PathBuf::from("mod.md"), text, &Default::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+      result,
+      concat!(
+        "# Example\n",
+        "\n",
+        "```ts\n",
+        "const a = 1;\n",
+        "```\n",
+        "\n",
+        "```js\n",
+        "const b = (\n",
+        "```\n",
+      )
+    );
+
+    let options = FmtOptionsConfig {
+      code_blocks: Some(false),
+      ..Default::default()
+    };
+    assert_eq!(
+      format_file(&PathBuf::from("mod.md"), text, &options).unwrap(),
+      None
+    );
+  }
+
+  #[test]
+  fn test_format_file_yaml() {
+    let text = "a:   1\nb:\n    - 'x'\n";
+    let result =
+      format_file(&PathBuf::from("mod.yml"), text, &Default::default())
+        .unwrap()
+        .unwrap();
+    assert_eq!(result, "a: 1\nb:\n  - \"x\"\n");
+
+    // the yaml options take precedence over the general ones
+    let options = FmtOptionsConfig {
+      use_tabs: Some(true),
+      indent_width: Some(2),
+      single_quote: Some(false),
+      yaml: Some(YamlFmtOptionsConfig {
+        indent_width: Some(4),
+        single_quote: Some(true),
+      }),
+      ..Default::default()
+    };
+    let result = format_file(&PathBuf::from("mod.yaml"), text, &options)
+      .unwrap()
+      .unwrap();
+    assert_eq!(result, "a: 1\nb:\n    - 'x'\n");
+
+    let text = "# deno-fmt-ignore-file\na:   1\n";
+    assert_eq!(
+      format_file(&PathBuf::from("mod.yml"), text, &Default::default())
+        .unwrap(),
+      None
+    );
+  }
 }
@@ -0,0 +1,580 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Formats YAML files for `deno fmt`. The formatter works on the lines of the
+//! file and only changes what doesn't change the data: the indentation of the
+//! block collections, the spaces after `-` and `:`, the quotes of the strings
+//! which don't need escapes in the preferred quotes, the trailing whitespace
+//! and the blank lines. The content of block scalars, values spanning several
+//! lines and the node following a `# deno-fmt-ignore` comment are kept as
+//! they are, only moved along with the node they belong to. Since the file
+//! isn't parsed, the indentation the formatter relies on is checked instead:
+//! a file using tabs as indentation or with a node that isn't aligned with
+//! its siblings is refused, rather than formatted into another structure.
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+
+const IGNORE_COMMENT: &str = "deno-fmt-ignore";
+
+#[derive(Clone, Copy, Debug)]
+pub struct YamlFormatOptions {
+  pub indent_width: usize,
+  pub single_quote: bool,
+}
+
+/// The column of a node in the file and the one it's formatted at.
+#[derive(Clone, Copy, Debug)]
+struct Level {
+  original: usize,
+  formatted: usize,
+  /// How much deeper than the node its nested nodes are formatted.
+  child_indent: usize,
+}
+
+enum Value {
+  /// The value is empty, a comment or only has anchors and tags, so the
+  /// following deeper lines are nested nodes.
+  Nested,
+  /// A `|` or `>` scalar, which has an explicit indentation when its header
+  /// has a digit.
+  BlockScalar { explicit_indent: bool },
+  /// Any other value, which may continue on the following deeper lines.
+  Inline,
+}
+
+struct Node {
+  text: String,
+  levels: Vec<Level>,
+  /// The dash or key the value belongs to.
+  owner: Level,
+  value: Value,
+}
+
+pub fn format_text(
+  text: &str,
+  options: &YamlFormatOptions,
+) -> Result<Option<String>, AnyError> {
+  let mut formatter = Formatter {
+    options,
+    levels: vec![Level {
+      original: 0,
+      formatted: 0,
+      child_indent: options.indent_width,
+    }],
+    output: Vec::new(),
+  };
+  formatter.format(&text.lines().collect::<Vec<_>>())?;
+  while formatter.output.last().map(|line| line.is_empty()) == Some(true) {
+    formatter.output.pop();
+  }
+  let mut formatted_text = formatter.output.join("\n");
+  if !formatted_text.is_empty() {
+    formatted_text.push('\n');
+  }
+  if formatted_text == text {
+    Ok(None)
+  } else {
+    Ok(Some(formatted_text))
+  }
+}
+
+struct Formatter<'a> {
+  options: &'a YamlFormatOptions,
+  levels: Vec<Level>,
+  output: Vec<String>,
+}
+
+impl<'a> Formatter<'a> {
+  fn format(&mut self, lines: &[&str]) -> Result<(), AnyError> {
+    let mut ignore_next = false;
+    let mut index = 0;
+    while index < lines.len() {
+      let line = lines[index];
+      index += 1;
+      let content = line.trim();
+      if content.is_empty() {
+        // keep at most one blank line, and none at the start of the file
+        if self.output.last().map(|line| !line.is_empty()) == Some(true) {
+          self.output.push(String::new());
+        }
+        continue;
+      }
+      let column = indent_of(line);
+      if line[column..].starts_with('\t') {
+        bail!("Tabs can't be used as indentation in YAML (line {index}).");
+      }
+      if content.starts_with('#') {
+        ignore_next = content.trim_start_matches('#').trim() == IGNORE_COMMENT;
+        let formatted = self.comment_indent(column);
+        self.push_line(formatted, content);
+        continue;
+      }
+      if column == 0
+        && (is_marker(content, "---")
+          || is_marker(content, "...")
+          || content.starts_with('%'))
+      {
+        self.levels.truncate(1);
+        self.output.push(content.to_string());
+        continue;
+      }
+
+      let formatted = match self.node_indent(column) {
+        Some(formatted) => formatted,
+        None => bail!(
+          "The indentation of line {index} doesn't match the one of the previous lines."
+        ),
+      };
+      if ignore_next {
+        ignore_next = false;
+        self.levels.push(Level {
+          original: column,
+          formatted,
+          child_indent: self.options.indent_width,
+        });
+        self.push_line(formatted, line[column..].trim_end());
+        let delta = formatted as isize - column as isize;
+        index = self.push_shifted(lines, index, column, |_| delta);
+        continue;
+      }
+
+      let node = self.format_node(content, column, formatted);
+      self.push_line(formatted, &node.text);
+      self.levels.extend(node.levels);
+      let owner = node.owner;
+      index = match node.value {
+        Value::Nested => index,
+        Value::BlockScalar { explicit_indent } => {
+          self.push_shifted(lines, index, owner.original, |content_column| {
+            if explicit_indent {
+              // the indentation is relative to the owner
+              owner.formatted as isize - owner.original as isize
+            } else {
+              (owner.formatted + owner.child_indent) as isize
+                - content_column as isize
+            }
+          })
+        }
+        Value::Inline => {
+          let delta = owner.formatted as isize - owner.original as isize;
+          self.push_shifted(lines, index, owner.original, |_| delta)
+        }
+      };
+    }
+    Ok(())
+  }
+
+  /// Returns the formatted column of a node line, and leaves the levels of
+  /// the nodes it's nested in. Returns `None` when the line is less indented
+  /// than the previous one without being aligned with one of its parents.
+  fn node_indent(&mut self, column: usize) -> Option<usize> {
+    let mut left_level = false;
+    while self.levels.len() > 1 && self.levels.last().unwrap().original > column
+    {
+      self.levels.pop();
+      left_level = true;
+    }
+    let level = *self.levels.last().unwrap();
+    if level.original == column {
+      if self.levels.len() > 1 {
+        self.levels.pop();
+      }
+      Some(level.formatted)
+    } else if left_level {
+      None
+    } else {
+      Some(level.formatted + level.child_indent)
+    }
+  }
+
+  /// Returns the formatted column of a comment, which doesn't nest nodes.
+  fn comment_indent(&self, column: usize) -> usize {
+    let level = self
+      .levels
+      .iter()
+      .rev()
+      .find(|level| level.original <= column)
+      .unwrap_or(&self.levels[0]);
+    if level.original == column {
+      level.formatted
+    } else {
+      level.formatted + level.child_indent
+    }
+  }
+
+  fn format_node(
+    &self,
+    content: &str,
+    column: usize,
+    formatted: usize,
+  ) -> Node {
+    let mut text = String::new();
+    let mut owner = Level {
+      original: column,
+      formatted,
+      child_indent: self.options.indent_width,
+    };
+    let mut levels = vec![owner];
+    let mut rest = content;
+    let (mut original, mut formatted) = (column, formatted);
+    while rest == "-" || rest.starts_with("- ") {
+      // the nodes of a sequence entry are aligned with the content after
+      // its dash
+      owner = Level {
+        original,
+        formatted,
+        child_indent: 2,
+      };
+      text.push('-');
+      let after_dash = &rest[1..];
+      rest = after_dash.trim_start();
+      if rest.is_empty() {
+        levels.last_mut().unwrap().child_indent = 2;
+        break;
+      }
+      text.push(' ');
+      original += 1 + after_dash.len() - rest.len();
+      formatted += 2;
+      levels.push(Level {
+        original,
+        formatted,
+        child_indent: self.options.indent_width,
+      });
+    }
+    if rest.is_empty() {
+      return Node {
+        text,
+        levels,
+        owner,
+        value: Value::Nested,
+      };
+    }
+
+    let value = match split_key(rest) {
+      Some((key, value)) => {
+        owner = Level {
+          original,
+          formatted,
+          child_indent: self.options.indent_width,
+        };
+        text.push_str(&self.format_quoted(key));
+        text.push(':');
+        value.trim_start()
+      }
+      None => rest,
+    };
+    if value.is_empty() {
+      return Node {
+        text,
+        levels,
+        owner,
+        value: Value::Nested,
+      };
+    }
+    if text.ends_with(':') {
+      text.push(' ');
+    }
+    let value = if is_nested_value(value) {
+      text.push_str(value);
+      Value::Nested
+    } else if let Some(explicit_indent) = block_scalar_header(value) {
+      text.push_str(value);
+      Value::BlockScalar { explicit_indent }
+    } else {
+      text.push_str(&self.format_scalar(value));
+      Value::Inline
+    };
+    Node {
+      text,
+      levels,
+      owner,
+      value,
+    }
+  }
+
+  /// Changes the quotes of a value which is a single quoted string, followed
+  /// by nothing but a comment.
+  fn format_scalar(&self, value: &str) -> String {
+    match quoted_end(value) {
+      Some(end) => {
+        let rest = &value[end + 1..];
+        if rest.is_empty() || rest.trim_start().starts_with('#') {
+          format!("{}{}", self.format_quoted(&value[..=end]), rest)
+        } else {
+          value.to_string()
+        }
+      }
+      None => value.to_string(),
+    }
+  }
+
+  /// Changes the quotes of a string to the preferred ones, unless its content
+  /// would need escapes in them.
+  fn format_quoted(&self, text: &str) -> String {
+    if text.len() < 2 || quoted_end(text) != Some(text.len() - 1) {
+      return text.to_string();
+    }
+    let content = &text[1..text.len() - 1];
+    if text.starts_with('\'') && !self.options.single_quote {
+      let content = content.replace("''", "'");
+      if !content.contains('"') && !content.contains('\\') {
+        return format!("\"{content}\"");
+      }
+    } else if text.starts_with('"')
+      && self.options.single_quote
+      && !content.contains('\'')
+      && !content.contains('\\')
+    {
+      return format!("'{content}'");
+    }
+    text.to_string()
+  }
+
+  fn push_line(&mut self, column: usize, content: &str) {
+    self
+      .output
+      .push(format!("{}{}", " ".repeat(column), content));
+  }
+
+  /// Moves the lines from `index` which are deeper than `column` by the delta
+  /// returned for the column of the first of them, keeping their content and
+  /// blank lines. Returns the index of the first line after them.
+  fn push_shifted(
+    &mut self,
+    lines: &[&str],
+    index: usize,
+    column: usize,
+    get_delta: impl FnOnce(usize) -> isize,
+  ) -> usize {
+    let mut end = index;
+    for (i, line) in lines.iter().enumerate().skip(index) {
+      if line.trim().is_empty() {
+        continue;
+      }
+      if indent_of(line) <= column {
+        break;
+      }
+      end = i + 1;
+    }
+    let content_column = match lines[index..end]
+      .iter()
+      .find(|line| !line.trim().is_empty())
+    {
+      Some(line) => indent_of(line),
+      None => return index,
+    };
+    let delta = get_delta(content_column);
+    for line in &lines[index..end] {
+      let line_column = indent_of(line);
+      if line.trim().is_empty() && line_column <= content_column {
+        self.output.push(String::new());
+      } else {
+        let formatted = (line_column as isize + delta).max(0) as usize;
+        self.push_line(formatted, &line[line_column..]);
+      }
+    }
+    end
+  }
+}
+
+fn indent_of(line: &str) -> usize {
+  line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_marker(content: &str, marker: &str) -> bool {
+  content == marker
+    || (content.starts_with(marker) && content[marker.len()..].starts_with(' '))
+}
+
+/// Returns the index of the closing quote of a string starting the text.
+fn quoted_end(text: &str) -> Option<usize> {
+  let bytes = text.as_bytes();
+  let quote = *bytes.first()?;
+  if quote != b'\'' && quote != b'"' {
+    return None;
+  }
+  let mut i = 1;
+  while i < bytes.len() {
+    match bytes[i] {
+      b'\\' if quote == b'"' => i += 1,
+      b'\'' if quote == b'\'' && bytes.get(i + 1) == Some(&b'\'') => i += 1,
+      byte if byte == quote => return Some(i),
+      _ => {}
+    }
+    i += 1;
+  }
+  None
+}
+
+/// Splits a node at the colon of its key, returning the key and the value.
+fn split_key(content: &str) -> Option<(&str, &str)> {
+  let colon = if let Some(end) = quoted_end(content) {
+    let after_key = &content[end + 1..];
+    let colon = end + 1 + after_key.len() - after_key.trim_start().len();
+    if content[colon..].starts_with(':') {
+      colon
+    } else {
+      return None;
+    }
+  } else if content.starts_with(|c: char| "[{&*!|>%@`?'\"#".contains(c)) {
+    return None;
+  } else {
+    let bytes = content.as_bytes();
+    (0..bytes.len()).find(|&i| {
+      (bytes[i] == b':' && bytes.get(i + 1).map_or(true, |&b| b == b' '))
+        || (bytes[i] == b'#' && i > 0 && bytes[i - 1] == b' ')
+    })?
+  };
+  if content.as_bytes()[colon] != b':' {
+    // a comment starts before any colon
+    return None;
+  }
+  let value = &content[colon + 1..];
+  if value.is_empty() || value.starts_with(' ') {
+    Some((&content[..colon], value))
+  } else {
+    None
+  }
+}
+
+/// Whether a value is a comment or only has anchors and tags.
+fn is_nested_value(value: &str) -> bool {
+  value
+    .split_whitespace()
+    .take_while(|token| !token.starts_with('#'))
+    .all(|token| token.starts_with('&') || token.starts_with('!'))
+}
+
+/// Returns whether the header of a block scalar has an explicit indentation,
+/// or `None` when the value isn't a block scalar.
+fn block_scalar_header(value: &str) -> Option<bool> {
+  if !value.starts_with('|') && !value.starts_with('>') {
+    return None;
+  }
+  let header = value.split(" #").next().unwrap().trim_end();
+  if header[1..]
+    .chars()
+    .all(|c| c.is_ascii_digit() || c == '+' || c == '-')
+  {
+    Some(header.chars().any(|c| c.is_ascii_digit()))
+  } else {
+    None
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn format(text: &str, indent_width: usize, single_quote: bool) -> String {
+    format_text(
+      text,
+      &YamlFormatOptions {
+        indent_width,
+        single_quote,
+      },
+    )
+    .unwrap()
+    .unwrap_or_else(|| text.to_string())
+  }
+
+  fn format_err(text: &str) -> String {
+    format_text(
+      text,
+      &YamlFormatOptions {
+        indent_width: 2,
+        single_quote: false,
+      },
+    )
+    .unwrap_err()
+    .to_string()
+  }
+
+  #[test]
+  fn reindents_collections() {
+    let text = "\n\na:\n   b:   1\n   c:\n      - x\n      -   y: 1\n          z: 2\n\n\n\nd: 3   \n";
+    assert_eq!(
+      format(text, 2, false),
+      "a:\n  b: 1\n  c:\n    - x\n    - y: 1\n      z: 2\n\nd: 3\n"
+    );
+    assert_eq!(
+      format(text, 4, false),
+      "a:\n    b: 1\n    c:\n        - x\n        - y: 1\n          z: 2\n\nd: 3\n"
+    );
+  }
+
+  #[test]
+  fn changes_quotes_without_escapes() {
+    let text = "'a': 'b'\nc: 'it''s'\nd: \"e\" # f\ng: 'h\"'\ni: 'j' k\n";
+    assert_eq!(
+      format(text, 2, false),
+      "\"a\": \"b\"\nc: \"it's\"\nd: \"e\" # f\ng: 'h\"'\ni: 'j' k\n"
+    );
+    let text = "a: \"b\"\nc: \"it's\"\nd: \"\\n\"\n";
+    assert_eq!(format(text, 2, true), "a: 'b'\nc: \"it's\"\nd: \"\\n\"\n");
+  }
+
+  #[test]
+  fn keeps_block_scalars_and_multiline_values() {
+    let text = "a:\n    b: |\n        x  \n\n          y\n    c: >2\n       z\n    d: 'long\n        text'\n    e: 'f'\n";
+    assert_eq!(
+      format(text, 2, false),
+      "a:\n  b: |\n    x  \n\n      y\n  c: >2\n     z\n  d: 'long\n      text'\n  e: \"f\"\n"
+    );
+  }
+
+  #[test]
+  fn keeps_ignored_nodes() {
+    let text = "a:\n    # deno-fmt-ignore\n    b:   'x'\n    c:   'y'\n";
+    assert_eq!(
+      format(text, 2, false),
+      "a:\n  # deno-fmt-ignore\n  b:   'x'\n  c: \"y\"\n"
+    );
+  }
+
+  #[test]
+  fn keeps_formatted_text() {
+    let text = "a:\n  b: 1\n";
+    let options = YamlFormatOptions {
+      indent_width: 2,
+      single_quote: false,
+    };
+    assert_eq!(format_text(text, &options).unwrap(), None);
+  }
+
+  #[test]
+  fn refuses_tab_indentation() {
+    // formatting the tab away would make `b` a sibling of `a`
+    assert_eq!(
+      format_err("a:\n\tb: 1\n"),
+      "Tabs can't be used as indentation in YAML (line 2)."
+    );
+    assert_eq!(
+      format_err("a:\n  b:\n  \tc: 1\n"),
+      "Tabs can't be used as indentation in YAML (line 3)."
+    );
+    // tabs are kept in the content of block scalars
+    assert_eq!(format("a: |\n  \tb\n", 2, false), "a: |\n  \tb\n");
+  }
+
+  #[test]
+  fn refuses_inconsistent_indentation() {
+    assert_eq!(
+      format_err("a:\n   b: 1\n  c: 2\n"),
+      "The indentation of line 3 doesn't match the one of the previous lines."
+    );
+    assert_eq!(
+      format_err("a:\n  - x\n - y\n"),
+      "The indentation of line 3 doesn't match the one of the previous lines."
+    );
+    assert_eq!(
+      format_err("a:\n  b:\n    c: 1\n d: 2\n"),
+      "The indentation of line 4 doesn't match the one of the previous lines."
+    );
+    // the nodes aligned with one of their parents are valid
+    assert_eq!(
+      format("a:\n   b:\n      c: 1\n   d: 2\ne: 3\n", 2, false),
+      "a:\n  b:\n    c: 1\n  d: 2\ne: 3\n"
+    );
+  }
+}
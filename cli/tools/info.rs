@@ -1,13 +1,15 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt;
 use std::fmt::Write;
+use std::path::PathBuf;
 
 use deno_ast::ModuleSpecifier;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
-use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_graph::Dependency;
@@ -16,38 +18,126 @@ use deno_graph::ModuleGraph;
 use deno_graph::ModuleGraphError;
 use deno_graph::Resolution;
 use deno_runtime::colors;
+use deno_runtime::deno_node::NodeResolutionMode;
+use deno_runtime::deno_node::PackageJson;
+use deno_runtime::permissions::PermissionsContainer;
+use serde::Serialize;
 
+use crate::args::read_import_list;
+use crate::args::resolve_entrypoint;
+use crate::args::CacheSetting;
+use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::args::InfoFlags;
 use crate::display;
+use crate::graph_util::graph_valid_with_cli_options;
+use crate::journal;
+use crate::node;
+use crate::npm::NpmDependencyEntryKind;
 use crate::npm::NpmPackageId;
 use crate::npm::NpmPackageReference;
 use crate::npm::NpmPackageReq;
 use crate::npm::NpmPackageResolver;
+use crate::npm::NpmPackageVersionInfo;
+use crate::npm::NpmRegistryApi;
 use crate::npm::NpmResolutionPackage;
 use crate::npm::NpmResolutionSnapshot;
 use crate::proc_state::ProcState;
+use crate::semver::Version;
+use crate::semver::VersionReq;
 use crate::util::checksum;
 
 pub async fn info(flags: Flags, info_flags: InfoFlags) -> Result<(), AnyError> {
+  if info_flags.journal_summary {
+    return journal::print_summary(info_flags.json);
+  }
   let ps = ProcState::build(flags).await?;
-  if let Some(specifier) = info_flags.file {
-    let specifier = resolve_url_or_path(&specifier)?;
+  let has_preload_modules = !ps.options.preload_modules().is_empty();
+  if info_flags.import_list.is_some() || has_preload_modules {
+    // preloaded modules are shown as roots before the main module, in the
+    // order they're evaluated
+    let mut entries = ps
+      .options
+      .preload_modules()
+      .iter()
+      .map(|specifier| {
+        (specifier.clone(), ps.resolve_preload_module(specifier))
+      })
+      .collect::<Vec<_>>();
+    let mut files = info_flags.file.iter().cloned().collect::<Vec<_>>();
+    if let Some(import_list) = &info_flags.import_list {
+      files.extend(read_import_list(import_list)?);
+    }
+    entries.extend(files.into_iter().map(|file| {
+      let result = resolve_entrypoint(&file);
+      (file, result)
+    }));
+    info_import_list(&ps, entries, &info_flags).await?;
+  } else if let Some(specifier) = info_flags.file {
+    let specifier = resolve_entrypoint(&specifier)?;
+    let maybe_npm_req = NpmPackageReference::from_specifier(&specifier)
+      .ok()
+      .map(|reference| reference.req);
+    // resolved before the module graph, which adds the package to the lockfile
+    let maybe_npm_summary = match maybe_npm_req {
+      Some(req) => Some(NpmPackageSummary::resolve(&ps, req).await?),
+      None => None,
+    };
+    if let Some(summary) = &maybe_npm_summary {
+      if !info_flags.json {
+        return info_npm_package(&specifier, summary);
+      }
+    }
     let mut loader = ps.create_graph_loader();
     loader.enable_loading_cache_info(); // for displaying the cache information
+    if info_flags.types {
+      loader.enable_recording_types_headers();
+    }
     let graph = ps
       .create_graph_with_loader(vec![specifier], &mut loader)
       .await?;
 
+    let maybe_types_info = if info_flags.types {
+      Some(TypesInfo::build(
+        &graph,
+        &ps.npm_resolver,
+        &loader.types_headers(),
+      ))
+    } else {
+      None
+    };
+
     if info_flags.json {
       let mut json_graph = json!(graph);
       add_npm_packages_to_json(&mut json_graph, &ps.npm_resolver);
+      if let Some(types_info) = &maybe_types_info {
+        add_types_resolution_to_json(&mut json_graph, types_info);
+      }
+      if let Some(summary) = &maybe_npm_summary {
+        json_graph
+          .as_object_mut()
+          .unwrap()
+          .insert("npmPackage".to_string(), json!(summary));
+      }
       display::write_json_to_stdout(&json_graph)?;
     } else {
       let mut output = String::new();
-      GraphDisplayContext::write(&graph, &ps.npm_resolver, &mut output)?;
+      GraphDisplayContext::write(
+        &graph,
+        &ps.npm_resolver,
+        maybe_types_info.as_ref(),
+        &mut output,
+      )?;
       display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
     }
+  } else if info_flags.config_entrypoints {
+    let config_entrypoints = ps.options.resolve_config_entrypoints()?;
+    if config_entrypoints.is_empty() {
+      return Err(generic_error(
+        "No entry points found in the \"exports\" or \"main\" field of the configuration file.",
+      ));
+    }
+    info_config_entrypoints(&ps, config_entrypoints, &info_flags).await?;
   } else {
     // If it was just "deno info" print location of caches and exit
     print_cache_info(
@@ -59,6 +149,444 @@ pub async fn info(flags: Flags, info_flags: InfoFlags) -> Result<(), AnyError> {
   Ok(())
 }
 
+/// Shows the dependencies of all the entry points of the configuration file
+/// as a single JSON document. Each module is only listed once in the module
+/// table, and the modules reachable from each root are given in `reachable`
+/// as the indexes of those modules in the table, in the order of the roots.
+async fn info_config_entrypoints(
+  ps: &ProcState,
+  roots: Vec<ModuleSpecifier>,
+  info_flags: &InfoFlags,
+) -> Result<(), AnyError> {
+  let mut loader = ps.create_graph_loader();
+  loader.enable_loading_cache_info(); // for displaying the cache information
+  if info_flags.types {
+    loader.enable_recording_types_headers();
+  }
+  let graph = ps
+    .create_graph_with_loader(roots.clone(), &mut loader)
+    .await?;
+
+  let mut json_graph = json!(graph);
+  add_npm_packages_to_json(&mut json_graph, &ps.npm_resolver);
+  if info_flags.types {
+    let types_info =
+      TypesInfo::build(&graph, &ps.npm_resolver, &loader.types_headers());
+    add_types_resolution_to_json(&mut json_graph, &types_info);
+  }
+  let module_indexes = json_graph["modules"]
+    .as_array()
+    .map(|modules| {
+      modules
+        .iter()
+        .enumerate()
+        .filter_map(|(index, module)| {
+          Some((module.get("specifier")?.as_str()?.to_string(), index))
+        })
+        .collect::<HashMap<_, _>>()
+    })
+    .unwrap_or_default();
+  let reachable = roots
+    .iter()
+    .map(|root| {
+      let mut indexes = graph
+        .segment(&[root.clone()])
+        .modules()
+        .filter_map(|module| {
+          module_indexes.get(module.specifier.as_str()).copied()
+        })
+        .collect::<Vec<_>>();
+      indexes.sort_unstable();
+      indexes
+    })
+    .collect::<Vec<_>>();
+  json_graph["reachable"] = json!(reachable);
+  display::write_json_to_stdout(&json_graph)
+}
+
+/// Shows the information about the modules of an import list, which are
+/// loaded into a single graph. A module that fails is reported without
+/// preventing the others from being shown.
+async fn info_import_list(
+  ps: &ProcState,
+  resolved_files: Vec<(String, Result<ModuleSpecifier, AnyError>)>,
+  info_flags: &InfoFlags,
+) -> Result<(), AnyError> {
+  let mut entries = Vec::with_capacity(resolved_files.len());
+  let mut roots = Vec::with_capacity(resolved_files.len());
+  for (file, result) in resolved_files {
+    if let Ok(root) = &result {
+      if roots.contains(root) {
+        continue;
+      }
+      roots.push(root.clone());
+    }
+    entries.push((file, result));
+  }
+
+  let mut loader = ps.create_graph_loader();
+  loader.enable_loading_cache_info(); // for displaying the cache information
+  if info_flags.types {
+    loader.enable_recording_types_headers();
+  }
+  let graph = ps.create_graph_with_loader(roots, &mut loader).await?;
+  let maybe_types_info = if info_flags.types {
+    Some(TypesInfo::build(
+      &graph,
+      &ps.npm_resolver,
+      &loader.types_headers(),
+    ))
+  } else {
+    None
+  };
+
+  if info_flags.json {
+    let mut json_graph = json!(graph);
+    add_npm_packages_to_json(&mut json_graph, &ps.npm_resolver);
+    if let Some(types_info) = &maybe_types_info {
+      add_types_resolution_to_json(&mut json_graph, types_info);
+    }
+    let summaries = entries
+      .iter()
+      .map(|(file, result)| match result {
+        Ok(root) => RootSummary::build(&graph, root, &ps.options),
+        Err(err) => RootSummary {
+          specifier: file.to_string(),
+          modules: None,
+          size: None,
+          error: Some(err.to_string()),
+        },
+      })
+      .collect::<Vec<_>>();
+    json_graph["rootSummaries"] = json!(summaries);
+    display::write_json_to_stdout(&json_graph)
+  } else {
+    let mut output = String::new();
+    for (i, (file, result)) in entries.iter().enumerate() {
+      if i > 0 {
+        writeln!(output)?;
+      }
+      writeln!(output, "{} {}", colors::bold("root:"), file)?;
+      let root = match result {
+        Ok(root) => root,
+        Err(err) => {
+          writeln!(output, "{} {}", colors::red("error:"), err)?;
+          continue;
+        }
+      };
+      if let Err(err) = graph.try_get(&graph.resolve(root)) {
+        writeln!(output, "{} {}", colors::red("error:"), err)?;
+        continue;
+      }
+      GraphDisplayContext::write(
+        &graph.segment(&[root.clone()]),
+        &ps.npm_resolver,
+        maybe_types_info.as_ref(),
+        &mut output,
+      )?;
+    }
+    display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+    Ok(())
+  }
+}
+
+/// The summary of a root of an import list in the JSON output, next to the
+/// module table of the combined graph.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RootSummary {
+  specifier: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  modules: Option<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  size: Option<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  error: Option<String>,
+}
+
+impl RootSummary {
+  fn build(
+    graph: &ModuleGraph,
+    root: &ModuleSpecifier,
+    options: &CliOptions,
+  ) -> Self {
+    let segment = graph.segment(&[root.clone()]);
+    Self {
+      specifier: root.to_string(),
+      modules: Some(segment.modules().count()),
+      size: Some(segment.modules().map(|m| m.size()).sum()),
+      error: graph_valid_with_cli_options(graph, &[root.clone()], options)
+        .err()
+        .map(|err| err.to_string()),
+    }
+  }
+}
+
+/// Why an npm package given as the specifier resolved to its version.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum NpmVersionReason {
+  Lockfile { path: PathBuf },
+  DistTag { tag: String },
+  Range { range: String },
+}
+
+impl fmt::Display for NpmVersionReason {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Lockfile { path } => write!(f, "locked in {}", path.display()),
+      Self::DistTag { tag } => write!(f, "the \"{tag}\" dist-tag"),
+      Self::Range { range } => {
+        write!(f, "the highest version matching \"{range}\"")
+      }
+    }
+  }
+}
+
+/// The information about an npm package given as the specifier.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmPackageSummary {
+  id: String,
+  resolved_by: NpmVersionReason,
+  dist_tags: BTreeMap<String, String>,
+  tarball: String,
+  /// The size of the tarball of the package, when the registry tells it.
+  size: Option<u64>,
+  /// The size of the package unpacked in the cache.
+  unpacked_size: Option<u64>,
+  /// The unpacked size of the package along with its dependencies.
+  total_unpacked_size: u64,
+  cache_folder: Option<PathBuf>,
+  bin: BTreeMap<String, String>,
+  peer_dependency_warnings: Vec<String>,
+  #[serde(skip)]
+  package: NpmResolutionPackage,
+  #[serde(skip)]
+  npm_info: NpmInfo,
+}
+
+impl NpmPackageSummary {
+  /// Resolves the package from the registry metadata and the resolution of
+  /// the package. The module graph of such a specifier only has an
+  /// unresolved reference to the package.
+  async fn resolve(
+    ps: &ProcState,
+    req: NpmPackageReq,
+  ) -> Result<Self, AnyError> {
+    let npm_resolver = &ps.npm_resolver;
+    let was_locked = npm_resolver.snapshot().package_reqs().contains_key(&req);
+    npm_resolver.add_package_reqs(vec![req.clone()]).await?;
+    let snapshot = npm_resolver.snapshot();
+    let package = snapshot.resolve_package_from_deno_module(&req)?.clone();
+    let package_info = npm_resolver.api().package_info(&req.name).await?;
+
+    let mut npm_info = NpmInfo::default();
+    npm_info.fill_package_info(&package, npm_resolver, &snapshot);
+    let mut packages = npm_info.packages.values().collect::<Vec<_>>();
+    packages.sort_by(|a, b| a.id.cmp(&b.id));
+    let mut peer_dependency_warnings = Vec::new();
+    for package in packages {
+      let maybe_version_info = npm_resolver
+        .api()
+        .package_version_info(&package.id.name, &package.id.version)
+        .await?;
+      if let Some(version_info) = maybe_version_info {
+        peer_dependency_warnings
+          .extend(find_peer_dependency_warnings(package, &version_info)?);
+      }
+    }
+
+    let resolved_by = match (&ps.lockfile, &req.version_req) {
+      (Some(lockfile), _) if was_locked => NpmVersionReason::Lockfile {
+        path: lockfile.lock().filename.clone(),
+      },
+      (_, Some(version_req)) if version_req.tag().is_none() => {
+        NpmVersionReason::Range {
+          range: version_req.version_text().to_string(),
+        }
+      }
+      (_, maybe_version_req) => NpmVersionReason::DistTag {
+        tag: maybe_version_req
+          .as_ref()
+          .and_then(|version_req| version_req.tag())
+          .unwrap_or("latest")
+          .to_string(),
+      },
+    };
+    let size = if ps.options.cache_setting() == CacheSetting::Only {
+      None
+    } else {
+      match ps.http_client.content_length(&package.dist.tarball).await {
+        Ok(maybe_size) => maybe_size,
+        Err(err) => {
+          log::debug!(
+            "Failed getting the size of {}. {:#}",
+            package.dist.tarball,
+            err
+          );
+          None
+        }
+      }
+    };
+    let maybe_cache_folder = npm_resolver
+      .resolve_package_folder_from_deno_module(&req)
+      .ok();
+    let maybe_bin = maybe_cache_folder.as_ref().and_then(|folder| {
+      PackageJson::load_skip_read_permission(folder.join("package.json"))
+        .ok()
+        .and_then(|package_json| package_json.bin)
+    });
+    Ok(Self {
+      id: package.id.as_serialized(),
+      resolved_by,
+      dist_tags: package_info.dist_tags.clone().into_iter().collect(),
+      tarball: package.dist.tarball.clone(),
+      size,
+      unpacked_size: npm_info.package_sizes.get(&package.id).copied(),
+      total_unpacked_size: npm_info.package_sizes.values().sum(),
+      cache_folder: maybe_cache_folder,
+      bin: bin_entries(&package.id.name, maybe_bin.as_ref()),
+      peer_dependency_warnings,
+      package,
+      npm_info,
+    })
+  }
+}
+
+/// Shows the information about an npm package given as the specifier.
+fn info_npm_package(
+  specifier: &ModuleSpecifier,
+  summary: &NpmPackageSummary,
+) -> Result<(), AnyError> {
+  let size_text = |maybe_size: Option<u64>| {
+    maybe_size
+      .map(|size| display::human_size(size as f64))
+      .unwrap_or_else(|| "unknown".to_string())
+  };
+  let mut output = String::new();
+  writeln!(output, "{} {}", colors::bold("npm package:"), summary.id)?;
+  writeln!(
+    output,
+    "{} {}",
+    colors::bold("resolved by:"),
+    summary.resolved_by
+  )?;
+  writeln!(
+    output,
+    "{} {}",
+    colors::bold("dist-tags:"),
+    summary
+      .dist_tags
+      .iter()
+      .map(|(tag, version)| format!("{tag}: {version}"))
+      .collect::<Vec<_>>()
+      .join(", ")
+  )?;
+  writeln!(output, "{} {}", colors::bold("tarball:"), summary.tarball)?;
+  if let Some(cache_folder) = &summary.cache_folder {
+    writeln!(
+      output,
+      "{} {}",
+      colors::bold("cache:"),
+      cache_folder.display()
+    )?;
+  }
+  writeln!(
+    output,
+    "{} {} unique",
+    colors::bold("dependencies:"),
+    summary.npm_info.packages.len() - 1
+  )?;
+  writeln!(
+    output,
+    "{} {} ({} unpacked, {} with dependencies)",
+    colors::bold("size:"),
+    size_text(summary.size),
+    size_text(summary.unpacked_size),
+    display::human_size(summary.total_unpacked_size as f64)
+  )?;
+  if !summary.bin.is_empty() {
+    writeln!(output, "{}", colors::bold("bin:"))?;
+    for (name, path) in &summary.bin {
+      writeln!(output, "  {name}: {path}")?;
+    }
+  }
+  for warning in &summary.peer_dependency_warnings {
+    writeln!(output, "{} {}", colors::yellow("warning:"), warning)?;
+  }
+  writeln!(output)?;
+  let mut root_node = TreeNode::from_text(format!(
+    "{} - {} {}",
+    specifier,
+    summary.package.id.version,
+    maybe_size_to_text(summary.unpacked_size)
+  ));
+  let mut seen = HashSet::from([summary.id.clone()]);
+  root_node.children = summary.npm_info.build_deps(&summary.package, &mut seen);
+  print_tree_node(&root_node, &mut output)?;
+  display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+  Ok(())
+}
+
+/// Gets the executables of a package from the "bin" of its package.json,
+/// which is either the path of a single executable named after the package
+/// or the paths of the executables by their names.
+fn bin_entries(
+  package_name: &str,
+  maybe_bin: Option<&serde_json::Value>,
+) -> BTreeMap<String, String> {
+  match maybe_bin {
+    Some(serde_json::Value::String(path)) => {
+      let name = package_name.rsplit('/').next().unwrap_or(package_name);
+      BTreeMap::from([(name.to_string(), path.clone())])
+    }
+    Some(serde_json::Value::Object(entries)) => entries
+      .iter()
+      .filter_map(|(name, path)| {
+        Some((name.clone(), path.as_str()?.to_string()))
+      })
+      .collect(),
+    _ => BTreeMap::new(),
+  }
+}
+
+/// Finds the required peer dependencies of a package which didn't resolve to
+/// a version they allow.
+fn find_peer_dependency_warnings(
+  package: &NpmResolutionPackage,
+  version_info: &NpmPackageVersionInfo,
+) -> Result<Vec<String>, AnyError> {
+  let mut entries = version_info.dependencies_as_entries()?;
+  entries.sort();
+  let mut warnings = Vec::new();
+  for entry in entries {
+    if entry.kind != NpmDependencyEntryKind::Peer {
+      continue;
+    }
+    let allows = |version_req: &VersionReq, version: &Version| {
+      version_req.tag().is_some() || version_req.matches(version)
+    };
+    let requirement = format!(
+      "{}@{} requires peer {}@{}",
+      package.id.name, package.id.version, entry.name, entry.version_req
+    );
+    match package.dependencies.get(&entry.bare_specifier) {
+      Some(id)
+        if allows(&entry.version_req, &id.version)
+          || entry
+            .peer_dep_version_req
+            .as_ref()
+            .map_or(false, |version_req| allows(version_req, &id.version)) => {}
+      Some(id) => {
+        warnings.push(format!("{requirement}, but {} was resolved", id.version))
+      }
+      None => warnings.push(format!("{requirement}, which was not resolved")),
+    }
+  }
+  Ok(warnings)
+}
+
 fn print_cache_info(
   state: &ProcState,
   json: bool,
@@ -224,6 +752,225 @@ fn add_npm_packages_to_json(
   json.insert("npmPackages".to_string(), json_packages.into());
 }
 
+fn add_types_resolution_to_json(
+  json: &mut serde_json::Value,
+  types_info: &TypesInfo,
+) {
+  let modules = json
+    .as_object_mut()
+    .and_then(|json| json.get_mut("modules"))
+    .and_then(|m| m.as_array_mut());
+  if let Some(modules) = modules {
+    for module in modules.iter_mut() {
+      let maybe_resolution = module
+        .get("specifier")
+        .and_then(|s| s.as_str())
+        .and_then(|s| ModuleSpecifier::parse(s).ok())
+        .and_then(|s| types_info.get(&s));
+      if let Some(module) = module.as_object_mut() {
+        let value = match maybe_resolution {
+          Some(resolution) => json!({
+            "specifier": resolution.specifier.to_string(),
+            "kind": resolution.kind.as_str(),
+          }),
+          None => serde_json::Value::Null,
+        };
+        module.insert("typesResolution".to_string(), value);
+      }
+    }
+  }
+}
+
+/// The mechanism that selected the declaration file used for a module.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum TypesResolutionKind {
+  /// A `// @deno-types` directive on an import or a
+  /// `/// <reference types="..." />` directive in a JavaScript module.
+  Directive,
+  /// The `X-TypeScript-Types` header of a remote module.
+  Header,
+  /// The `types` or `typings` field (or a `types` export condition) in the
+  /// package.json of an npm package.
+  PackageJson,
+  /// A declaration file sitting next to the resolved JavaScript file of an
+  /// npm package.
+  AdjacentDeclaration,
+}
+
+impl TypesResolutionKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Directive => "directive",
+      Self::Header => "header",
+      Self::PackageJson => "packageJson",
+      Self::AdjacentDeclaration => "adjacentDeclaration",
+    }
+  }
+}
+
+impl fmt::Display for TypesResolutionKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+#[derive(Debug, Clone)]
+struct TypesResolution {
+  specifier: ModuleSpecifier,
+  kind: TypesResolutionKind,
+}
+
+/// The declaration file and its provenance for each module in a graph.
+///
+/// The module graph only keeps the resolved types dependency of a module, so
+/// the `X-TypeScript-Types` headers recorded by the loader tell which of them
+/// come from a header rather than from a directive.
+#[derive(Default)]
+struct TypesInfo {
+  resolutions: HashMap<ModuleSpecifier, TypesResolution>,
+}
+
+impl TypesInfo {
+  fn build(
+    graph: &ModuleGraph,
+    npm_resolver: &NpmPackageResolver,
+    types_headers: &HashMap<ModuleSpecifier, String>,
+  ) -> Self {
+    let mut info = TypesInfo::default();
+
+    // a `@deno-types` directive on an import takes precedence over any types
+    // the imported module provides itself
+    for module in graph.modules() {
+      for dep in module.dependencies.values() {
+        if let (Some(code), Some(types)) =
+          (dep.maybe_code.ok(), dep.maybe_type.ok())
+        {
+          // when a module is imported with different directives, the first
+          // one wins like it does when type checking
+          info
+            .resolutions
+            .entry(graph.resolve(&code.specifier))
+            .or_insert_with(|| TypesResolution {
+              specifier: graph.resolve(&types.specifier),
+              kind: TypesResolutionKind::Directive,
+            });
+        }
+      }
+    }
+
+    for module in graph.modules() {
+      if info.resolutions.contains_key(&module.specifier) {
+        continue;
+      }
+      if let Ok(reference) =
+        NpmPackageReference::from_specifier(&module.specifier)
+      {
+        if let Some(resolution) =
+          resolve_npm_types_resolution(&reference, npm_resolver)
+        {
+          info
+            .resolutions
+            .insert(module.specifier.clone(), resolution);
+        }
+      } else if let Some(types_dependency) = &module.maybe_types_dependency {
+        if let Some(resolved) = types_dependency.dependency.ok() {
+          let is_header = types_headers
+            .get(&module.specifier)
+            .map_or(false, |header| *header == types_dependency.specifier);
+          let kind = if is_header {
+            TypesResolutionKind::Header
+          } else {
+            TypesResolutionKind::Directive
+          };
+          info.resolutions.insert(
+            module.specifier.clone(),
+            TypesResolution {
+              specifier: graph.resolve(&resolved.specifier),
+              kind,
+            },
+          );
+        }
+      }
+    }
+
+    info
+  }
+
+  fn get(&self, specifier: &ModuleSpecifier) -> Option<&TypesResolution> {
+    self.resolutions.get(specifier)
+  }
+}
+
+fn resolve_npm_types_resolution(
+  reference: &NpmPackageReference,
+  npm_resolver: &NpmPackageResolver,
+) -> Option<TypesResolution> {
+  let types_url = node::node_resolve_npm_reference(
+    reference,
+    NodeResolutionMode::Types,
+    npm_resolver,
+    &mut PermissionsContainer::allow_all(),
+  )
+  .ok()
+  .flatten()?
+  .into_url();
+  let code_url = node::node_resolve_npm_reference(
+    reference,
+    NodeResolutionMode::Execution,
+    npm_resolver,
+    &mut PermissionsContainer::allow_all(),
+  )
+  .ok()
+  .flatten()
+  .map(|r| r.into_url());
+  let has_package_json_types = npm_resolver
+    .resolve_package_folder_from_deno_module(&reference.req)
+    .ok()
+    .and_then(|folder| {
+      PackageJson::load(
+        npm_resolver,
+        &mut PermissionsContainer::allow_all(),
+        folder.join("package.json"),
+      )
+      .ok()
+    })
+    .map(|package_json| package_json.types.is_some())
+    .unwrap_or(false);
+  let kind = match code_url {
+    Some(code_url)
+      if !has_package_json_types
+        && is_adjacent_declaration(&code_url, &types_url) =>
+    {
+      TypesResolutionKind::AdjacentDeclaration
+    }
+    _ => TypesResolutionKind::PackageJson,
+  };
+  Some(TypesResolution {
+    specifier: types_url,
+    kind,
+  })
+}
+
+fn is_adjacent_declaration(
+  code_url: &ModuleSpecifier,
+  types_url: &ModuleSpecifier,
+) -> bool {
+  fn strip_extension(path: &str) -> &str {
+    for ext in [".d.ts", ".d.mts", ".d.cts"] {
+      if let Some(path) = path.strip_suffix(ext) {
+        return path;
+      }
+    }
+    match path.rfind('.') {
+      Some(index) if index > path.rfind('/').unwrap_or(0) => &path[..index],
+      _ => path,
+    }
+  }
+
+  code_url != types_url
+    && strip_extension(code_url.path()) == strip_extension(types_url.path())
+}
+
 struct TreeNode {
   text: String,
   children: Vec<TreeNode>,
@@ -362,11 +1109,45 @@ impl NpmInfo {
       .and_then(|package_req| self.resolved_reqs.get(package_req))
       .and_then(|id| self.packages.get(id))
   }
+
+  /// Builds the tree of the dependencies of a package, where the
+  /// dependencies of the packages already in `seen` are left out.
+  fn build_deps(
+    &self,
+    package: &NpmResolutionPackage,
+    seen: &mut HashSet<String>,
+  ) -> Vec<TreeNode> {
+    let mut deps = package.dependencies.values().collect::<Vec<_>>();
+    deps.sort();
+    let mut children = Vec::with_capacity(deps.len());
+    for dep_id in deps.into_iter() {
+      let maybe_size = self.package_sizes.get(dep_id).cloned();
+      let size_str = maybe_size_to_text(maybe_size);
+      let mut child = TreeNode::from_text(format!(
+        "npm:{} {}",
+        dep_id.as_serialized(),
+        size_str
+      ));
+      if let Some(package) = self.packages.get(dep_id) {
+        if !package.dependencies.is_empty() {
+          let was_seen = !seen.insert(package.id.as_serialized());
+          if was_seen {
+            child.text = format!("{} {}", child.text, colors::gray("*"));
+          } else {
+            child.children.extend(self.build_deps(package, seen));
+          }
+        }
+      }
+      children.push(child);
+    }
+    children
+  }
 }
 
 struct GraphDisplayContext<'a> {
   graph: &'a ModuleGraph,
   npm_info: NpmInfo,
+  maybe_types_info: Option<&'a TypesInfo>,
   seen: HashSet<String>,
 }
 
@@ -374,6 +1155,7 @@ impl<'a> GraphDisplayContext<'a> {
   pub fn write<TWrite: Write>(
     graph: &'a ModuleGraph,
     npm_resolver: &'a NpmPackageResolver,
+    maybe_types_info: Option<&'a TypesInfo>,
     writer: &mut TWrite,
   ) -> fmt::Result {
     let npm_snapshot = npm_resolver.snapshot();
@@ -381,6 +1163,7 @@ impl<'a> GraphDisplayContext<'a> {
     Self {
       graph,
       npm_info,
+      maybe_types_info,
       seen: Default::default(),
     }
     .into_writer(writer)
@@ -535,7 +1318,22 @@ impl<'a> GraphDisplayContext<'a> {
           .as_ref()
           .map(|s| s.as_bytes().len() as u64),
       };
-      format!("{} {}", header_text, maybe_size_to_text(maybe_size))
+      let header_text =
+        format!("{} {}", header_text, maybe_size_to_text(maybe_size));
+      match self
+        .maybe_types_info
+        .and_then(|info| info.get(&module.specifier))
+      {
+        Some(resolution) => format!(
+          "{} {}",
+          header_text,
+          colors::gray(format!(
+            "[types: {} ({})]",
+            resolution.specifier, resolution.kind
+          ))
+        ),
+        None => header_text,
+      }
     };
 
     let mut tree_node = TreeNode::from_text(header_text);
@@ -550,7 +1348,9 @@ impl<'a> GraphDisplayContext<'a> {
       }
       match &package_or_specifier {
         Package(package) => {
-          tree_node.children.extend(self.build_npm_deps(package));
+          tree_node
+            .children
+            .extend(self.npm_info.build_deps(package, &mut self.seen));
         }
         Specifier(_) => {
           for dep in module.dependencies.values() {
@@ -562,37 +1362,6 @@ impl<'a> GraphDisplayContext<'a> {
     tree_node
   }
 
-  fn build_npm_deps(
-    &mut self,
-    package: &NpmResolutionPackage,
-  ) -> Vec<TreeNode> {
-    let mut deps = package.dependencies.values().collect::<Vec<_>>();
-    deps.sort();
-    let mut children = Vec::with_capacity(deps.len());
-    for dep_id in deps.into_iter() {
-      let maybe_size = self.npm_info.package_sizes.get(dep_id).cloned();
-      let size_str = maybe_size_to_text(maybe_size);
-      let mut child = TreeNode::from_text(format!(
-        "npm:{} {}",
-        dep_id.as_serialized(),
-        size_str
-      ));
-      if let Some(package) = self.npm_info.packages.get(dep_id) {
-        if !package.dependencies.is_empty() {
-          let was_seen = !self.seen.insert(package.id.as_serialized());
-          if was_seen {
-            child.text = format!("{} {}", child.text, colors::gray("*"));
-          } else {
-            let package = package.clone();
-            child.children.extend(self.build_npm_deps(&package));
-          }
-        }
-      }
-      children.push(child);
-    }
-    children
-  }
-
   fn build_error_info(
     &mut self,
     err: &ModuleGraphError,
@@ -1,12 +1,19 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::InitFlags;
+use crate::args::InitJsxFramework;
 use crate::colors;
 use crate::deno_std;
+use crate::util::display::shell_quote;
+use crate::util::prompt;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
 use log::info;
+use log::warn;
 use std::io::Write;
+use std::num::NonZeroU32;
 use std::path::Path;
 
 fn create_file(
@@ -14,15 +21,245 @@ fn create_file(
   filename: &str,
   content: &str,
 ) -> Result<(), AnyError> {
+  let path = dir.join(filename);
+  if path.exists() {
+    warn!(
+      "{} {} already exists, skipping",
+      colors::yellow("Warning"),
+      filename
+    );
+    return Ok(());
+  }
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
   let mut file = std::fs::OpenOptions::new()
     .write(true)
     .create_new(true)
-    .open(dir.join(filename))
+    .open(&path)
     .with_context(|| format!("Failed to create {filename} file"))?;
   file.write_all(content.as_bytes())?;
   Ok(())
 }
 
+fn dir_name(dir: &Path) -> String {
+  dir
+    .file_name()
+    .map(|name| name.to_string_lossy().to_string())
+    .unwrap_or_else(|| "mod".to_string())
+}
+
+/// Gets the package name for a library, falling back to the name of the
+/// directory it is created in.
+fn resolve_lib_name(init_flags: &InitFlags, dir: &Path) -> String {
+  match &init_flags.name {
+    Some(name) => name.clone(),
+    None => dir_name(dir),
+  }
+}
+
+const DEFAULT_FMT_LINE_WIDTH: u32 = 80;
+const REACT_JSX_IMPORT_SOURCE: &str = "https://esm.sh/react@18.2.0";
+const PREACT_JSX_IMPORT_SOURCE: &str = "https://esm.sh/preact@10.13.0";
+
+/// What a project is scaffolded with, from the flags or from the answers to
+/// the prompts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProjectOptions {
+  name: Option<String>,
+  tests: bool,
+  fmt: Option<ProjectFmtOptions>,
+  lint: bool,
+  jsx: InitJsxFramework,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProjectFmtOptions {
+  use_tabs: bool,
+  line_width: NonZeroU32,
+  single_quote: bool,
+}
+
+impl ProjectOptions {
+  fn from_flags(init_flags: &InitFlags) -> Self {
+    Self {
+      name: init_flags.name.clone(),
+      tests: !init_flags.no_tests,
+      fmt: init_flags.fmt.then(|| ProjectFmtOptions {
+        use_tabs: init_flags.fmt_use_tabs,
+        line_width: init_flags
+          .fmt_line_width
+          .unwrap_or_else(|| NonZeroU32::new(DEFAULT_FMT_LINE_WIDTH).unwrap()),
+        single_quote: init_flags.fmt_single_quote,
+      }),
+      lint: init_flags.lint,
+      jsx: init_flags.jsx.unwrap_or(InitJsxFramework::None),
+    }
+  }
+
+  /// Asks for each option. Every answer has a flag giving the same result,
+  /// which is the command printed once the project is initialized.
+  fn prompt(dir: &Path) -> Result<Self, AnyError> {
+    let name = prompt::input("Project name", dir_name(dir))?;
+    let tests = prompt::confirm("Create test and benchmark files?", true)?;
+    let fmt = if prompt::confirm("Add a fmt configuration?", false)? {
+      let use_tabs =
+        prompt::select("Indentation", &["Spaces", "Tabs"], 0)? == 1;
+      let line_width = prompt::input(
+        "Line width",
+        NonZeroU32::new(DEFAULT_FMT_LINE_WIDTH).unwrap(),
+      )?;
+      let single_quote =
+        prompt::select("Quotes", &["Double", "Single"], 0)? == 1;
+      Some(ProjectFmtOptions {
+        use_tabs,
+        line_width,
+        single_quote,
+      })
+    } else {
+      None
+    };
+    let lint = prompt::confirm("Add a lint configuration?", false)?;
+    let jsx =
+      match prompt::select("JSX framework", &["None", "React", "Preact"], 0)? {
+        1 => InitJsxFramework::React,
+        2 => InitJsxFramework::Preact,
+        _ => InitJsxFramework::None,
+      };
+    Ok(Self {
+      name: Some(name),
+      tests,
+      fmt,
+      lint,
+      jsx,
+    })
+  }
+
+  /// The flags that initialize the same project without prompts.
+  fn to_flags(&self) -> Vec<String> {
+    let mut flags = vec!["--yes".to_string()];
+    if let Some(name) = &self.name {
+      flags.push(format!("--name={}", shell_quote(name)));
+    }
+    if !self.tests {
+      flags.push("--no-tests".to_string());
+    }
+    if let Some(fmt) = &self.fmt {
+      flags.push("--fmt".to_string());
+      if fmt.use_tabs {
+        flags.push("--fmt-use-tabs".to_string());
+      }
+      if fmt.line_width.get() != DEFAULT_FMT_LINE_WIDTH {
+        flags.push(format!("--fmt-line-width={}", fmt.line_width));
+      }
+      if fmt.single_quote {
+        flags.push("--fmt-single-quote".to_string());
+      }
+    }
+    if self.lint {
+      flags.push("--lint".to_string());
+    }
+    match self.jsx {
+      InitJsxFramework::None => {}
+      InitJsxFramework::React => flags.push("--jsx=react".to_string()),
+      InitJsxFramework::Preact => flags.push("--jsx=preact".to_string()),
+    }
+    flags
+  }
+
+  fn config_text(&self) -> Result<String, AnyError> {
+    let mut config = serde_json::Map::new();
+    if let Some(name) = &self.name {
+      config.insert("name".to_string(), json!(name));
+    }
+    config.insert(
+      "tasks".to_string(),
+      json!({ "dev": "deno run --watch main.ts" }),
+    );
+    if let Some(fmt) = &self.fmt {
+      config.insert(
+        "fmt".to_string(),
+        json!({
+          "options": {
+            "useTabs": fmt.use_tabs,
+            "lineWidth": fmt.line_width.get(),
+            "singleQuote": fmt.single_quote,
+          }
+        }),
+      );
+    }
+    if self.lint {
+      config.insert(
+        "lint".to_string(),
+        json!({ "rules": { "tags": ["recommended"] } }),
+      );
+    }
+    let maybe_jsx_import_source = match self.jsx {
+      InitJsxFramework::None => None,
+      InitJsxFramework::React => Some(REACT_JSX_IMPORT_SOURCE),
+      InitJsxFramework::Preact => Some(PREACT_JSX_IMPORT_SOURCE),
+    };
+    if let Some(jsx_import_source) = maybe_jsx_import_source {
+      config.insert(
+        "compilerOptions".to_string(),
+        json!({
+          "jsx": "react-jsx",
+          "jsxImportSource": jsx_import_source,
+        }),
+      );
+    }
+    let mut text = serde_json::to_string_pretty(&config)?;
+    text.push('\n');
+    Ok(text)
+  }
+}
+
+fn init_lib(init_flags: &InitFlags, dir: &Path) -> Result<(), AnyError> {
+  let name = resolve_lib_name(init_flags, dir);
+
+  create_file(dir, "mod.ts", include_str!("./templates/lib/mod.ts"))?;
+  create_file(
+    dir,
+    "version.ts",
+    include_str!("./templates/lib/version.ts"),
+  )?;
+  let mod_test_ts = include_str!("./templates/lib/mod_test.ts")
+    .replace("{CURRENT_STD_URL}", deno_std::CURRENT_STD_URL.as_str());
+  create_file(dir, "mod_test.ts", &mod_test_ts)?;
+  create_file(
+    dir,
+    "scripts/bump.ts",
+    include_str!("./templates/lib/bump.ts"),
+  )?;
+  let deno_json = include_str!("./templates/lib/deno.json")
+    .replace("\"{NAME}\"", &serde_json::to_string(&name)?);
+  create_file(dir, "deno.json", &deno_json)?;
+
+  info!(
+    "✅ {}",
+    colors::green(format!("Library {name} initialized"))
+  );
+  info!("");
+  info!("{}", colors::gray("Run these commands to get started"));
+  info!("");
+  if let Some(dir) = &init_flags.dir {
+    info!("  cd {}", dir);
+    info!("");
+  }
+  info!("  {}", colors::gray("# Run the tests"));
+  info!("  deno task test");
+  info!("");
+  info!("  {}", colors::gray("# Type check the library"));
+  info!("  deno task check");
+  info!("");
+  info!(
+    "  {}",
+    colors::gray("# Bump the version (major, minor or patch)")
+  );
+  info!("  deno task bump patch");
+  Ok(())
+}
+
 pub async fn init_project(init_flags: InitFlags) -> Result<(), AnyError> {
   let cwd =
     std::env::current_dir().context("Can't read current working directory.")?;
@@ -34,19 +271,53 @@ pub async fn init_project(init_flags: InitFlags) -> Result<(), AnyError> {
     cwd
   };
 
+  if init_flags.lib {
+    return init_lib(&init_flags, &dir);
+  }
+
+  let is_interactive = !init_flags.yes
+    && !init_flags.has_project_options()
+    && prompt::is_interactive();
+  let options = if is_interactive {
+    ProjectOptions::prompt(&dir)?
+  } else {
+    ProjectOptions::from_flags(&init_flags)
+  };
+
   let main_ts = include_str!("./templates/main.ts");
   create_file(&dir, "main.ts", main_ts)?;
 
-  let main_test_ts = include_str!("./templates/main_test.ts")
-    .replace("{CURRENT_STD_URL}", deno_std::CURRENT_STD_URL.as_str());
-  create_file(&dir, "main_test.ts", &main_test_ts)?;
-  let main_bench_ts = include_str!("./templates/main_bench.ts");
-  create_file(&dir, "main_bench.ts", main_bench_ts)?;
+  if options.tests {
+    let main_test_ts = include_str!("./templates/main_test.ts")
+      .replace("{CURRENT_STD_URL}", deno_std::CURRENT_STD_URL.as_str());
+    create_file(&dir, "main_test.ts", &main_test_ts)?;
+    let main_bench_ts = include_str!("./templates/main_bench.ts");
+    create_file(&dir, "main_bench.ts", main_bench_ts)?;
+  }
 
-  create_file(&dir, "deno.jsonc", include_str!("./templates/deno.jsonc"))?;
+  create_file(&dir, "deno.jsonc", &options.config_text()?)?;
 
-  info!("✅ {}", colors::green("Project initialized"));
+  match &options.name {
+    Some(name) => info!(
+      "✅ {}",
+      colors::green(format!("Project {name} initialized"))
+    ),
+    None => info!("✅ {}", colors::green("Project initialized")),
+  }
   info!("");
+  if is_interactive {
+    info!(
+      "{}",
+      colors::gray("Initialize the same project without prompts with")
+    );
+    info!("");
+    let mut command = options.to_flags();
+    if let Some(dir) = &init_flags.dir {
+      command.push(shell_quote(dir));
+    }
+    info!("  deno init {}", command.join(" "));
+    info!("");
+  }
   info!("{}", colors::gray("Run these commands to get started"));
   info!("");
   if let Some(dir) = init_flags.dir {
@@ -61,11 +332,132 @@ pub async fn init_project(init_flags: InitFlags) -> Result<(), AnyError> {
     colors::gray("# Run the program and watch for file changes")
   );
   info!("  deno task dev");
-  info!("");
-  info!("  {}", colors::gray("# Run the tests"));
-  info!("  deno test");
-  info!("");
-  info!("  {}", colors::gray("# Run the benchmarks"));
-  info!("  deno bench");
+  if options.tests {
+    info!("");
+    info!("  {}", colors::gray("# Run the tests"));
+    info!("  deno test");
+    info!("");
+    info!("  {}", colors::gray("# Run the benchmarks"));
+    info!("  deno bench");
+  }
   Ok(())
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::args::flags_from_vec;
+  use crate::args::DenoSubcommand;
+
+  fn options_from_args(args: &[String]) -> ProjectOptions {
+    let mut argv = vec!["deno".to_string(), "init".to_string()];
+    argv.extend(args.iter().cloned());
+    match flags_from_vec(argv).unwrap().subcommand {
+      DenoSubcommand::Init(init_flags) => {
+        ProjectOptions::from_flags(&init_flags)
+      }
+      _ => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn default_config_text() {
+    let options = ProjectOptions::from_flags(&InitFlags::default());
+    assert_eq!(
+      options.config_text().unwrap(),
+      r#"{
+  "tasks": {
+    "dev": "deno run --watch main.ts"
+  }
+}
+"#
+    );
+  }
+
+  #[test]
+  fn config_text_with_all_sections() {
+    let options = ProjectOptions {
+      name: Some("my-app".to_string()),
+      tests: true,
+      fmt: Some(ProjectFmtOptions {
+        use_tabs: true,
+        line_width: NonZeroU32::new(100).unwrap(),
+        single_quote: false,
+      }),
+      lint: true,
+      jsx: InitJsxFramework::Preact,
+    };
+    assert_eq!(
+      options.config_text().unwrap(),
+      r#"{
+  "name": "my-app",
+  "tasks": {
+    "dev": "deno run --watch main.ts"
+  },
+  "fmt": {
+    "options": {
+      "useTabs": true,
+      "lineWidth": 100,
+      "singleQuote": false
+    }
+  },
+  "lint": {
+    "rules": {
+      "tags": [
+        "recommended"
+      ]
+    }
+  },
+  "compilerOptions": {
+    "jsx": "react-jsx",
+    "jsxImportSource": "https://esm.sh/preact@10.13.0"
+  }
+}
+"#
+    );
+  }
+
+  #[test]
+  fn answers_reproducible_with_flags() {
+    let answers = [
+      ProjectOptions {
+        name: Some("app".to_string()),
+        tests: true,
+        fmt: None,
+        lint: false,
+        jsx: InitJsxFramework::None,
+      },
+      ProjectOptions {
+        name: Some("@scope/app".to_string()),
+        tests: false,
+        fmt: Some(ProjectFmtOptions {
+          use_tabs: true,
+          line_width: NonZeroU32::new(120).unwrap(),
+          single_quote: true,
+        }),
+        lint: true,
+        jsx: InitJsxFramework::React,
+      },
+      ProjectOptions {
+        name: Some("my app".to_string()),
+        tests: true,
+        fmt: Some(ProjectFmtOptions {
+          use_tabs: false,
+          line_width: NonZeroU32::new(80).unwrap(),
+          single_quote: false,
+        }),
+        lint: false,
+        jsx: InitJsxFramework::Preact,
+      },
+    ];
+    for options in answers {
+      let args = options
+        .to_flags()
+        .into_iter()
+        // the shell removes the quotes
+        .map(|arg| arg.replace('\'', ""))
+        .collect::<Vec<_>>();
+      assert_eq!(options_from_args(&args), options);
+    }
+  }
+}
@@ -0,0 +1,44 @@
+// Updates the version in deno.json and version.ts.
+//
+// Usage: deno task bump <major|minor|patch|x.y.z>
+
+const increment = Deno.args[0] ?? "patch";
+
+const config = JSON.parse(await Deno.readTextFile("deno.json"));
+const current = String(config.version ?? "0.0.0");
+const match = /^(\d+)\.(\d+)\.(\d+)$/.exec(current);
+if (!match) {
+  console.error(`Invalid version in deno.json: ${current}`);
+  Deno.exit(1);
+}
+const [major, minor, patch] = match.slice(1).map(Number);
+
+let next: string;
+switch (increment) {
+  case "major":
+    next = `${major + 1}.0.0`;
+    break;
+  case "minor":
+    next = `${major}.${minor + 1}.0`;
+    break;
+  case "patch":
+    next = `${major}.${minor}.${patch + 1}`;
+    break;
+  default:
+    if (!/^\d+\.\d+\.\d+$/.test(increment)) {
+      console.error(
+        `Expected "major", "minor", "patch" or a version, got: ${increment}`,
+      );
+      Deno.exit(1);
+    }
+    next = increment;
+}
+
+config.version = next;
+await Deno.writeTextFile("deno.json", JSON.stringify(config, null, 2) + "\n");
+await Deno.writeTextFile(
+  "version.ts",
+  `// This file is updated by \`deno task bump\`.\nexport const VERSION = "${next}";\n`,
+);
+
+console.log(`${current} -> ${next}`);
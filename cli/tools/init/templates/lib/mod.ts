@@ -0,0 +1,5 @@
+export { VERSION } from "./version.ts";
+
+export function add(a: number, b: number): number {
+  return a + b;
+}
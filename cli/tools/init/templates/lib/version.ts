@@ -0,0 +1,2 @@
+// This file is updated by `deno task bump`.
+export const VERSION = "0.1.0";
@@ -2,14 +2,17 @@
 
 use crate::args::resolve_no_prompt;
 use crate::args::CaData;
+use crate::args::ConfigFile;
 use crate::args::ConfigFlag;
 use crate::args::Flags;
 use crate::args::InstallFlags;
+use crate::args::InstallSubcommand;
 use crate::args::TypeCheckMode;
 use crate::http_util::HttpClient;
 use crate::npm::NpmPackageReference;
 use crate::proc_state::ProcState;
 use crate::util::fs::canonicalize_path_maybe_not_exists;
+use crate::util::path::specifier_to_file_path;
 use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
@@ -48,24 +51,58 @@ fn validate_name(exec_name: &str) -> Result<(), AnyError> {
 }
 
 #[cfg(windows)]
-/// On Windows, 2 files are generated.
-/// One compatible with cmd & powershell with a .cmd extension
-/// A second compatible with git bash / MINGW64
-/// Generate batch script to satisfy that.
+/// On Windows, 3 files are generated.
+/// A batch script with a .cmd extension for cmd
+/// A PowerShell script with a .ps1 extension, which PowerShell prefers over
+/// the batch script as it passes the arguments without cmd parsing them
+/// A shell script without extension for git bash / MINGW64
 fn generate_executable_file(shim_data: &ShimData) -> Result<(), AnyError> {
-  let args: Vec<String> =
-    shim_data.args.iter().map(|c| format!("\"{c}\"")).collect();
+  use crate::util::windows::quote_arg;
+  use crate::util::windows::quote_batch_arg;
+  use crate::util::windows::quote_powershell_arg;
+  use shell_escape::unix::escape;
+
   let template = format!(
     "% generated by deno install %\n@deno {} %*\n",
-    args
+    shim_data
+      .args
       .iter()
-      .map(|arg| arg.replace('%', "%%"))
+      .map(|arg| quote_batch_arg(arg))
       .collect::<Vec<_>>()
       .join(" ")
   );
   let mut file = File::create(&shim_data.file_path)?;
   file.write_all(template.as_bytes())?;
 
+  // write file for powershell, which only escapes the quotes in the arguments
+  // of native commands since 7.3. Before, it passes the arguments verbatim
+  // unless they contain whitespace outside quotes, so they're quoted for
+  // deno's command line already.
+  let template = format!(
+    r#"# generated by deno install
+if ($PSNativeCommandArgumentPassing -in 'Standard', 'Windows') {{
+  & deno {} @args
+}} else {{
+  & deno {} @args
+}}
+exit $LASTEXITCODE
+"#,
+    shim_data
+      .args
+      .iter()
+      .map(|arg| quote_powershell_arg(arg))
+      .collect::<Vec<_>>()
+      .join(" "),
+    shim_data
+      .args
+      .iter()
+      .map(|arg| quote_powershell_arg(&quote_arg(arg)))
+      .collect::<Vec<_>>()
+      .join(" "),
+  );
+  let mut file = File::create(shim_data.file_path.with_extension("ps1"))?;
+  file.write_all(template.as_bytes())?;
+
   // write file for bash
   // create filepath without extensions
   let template = format!(
@@ -73,7 +110,12 @@ fn generate_executable_file(shim_data: &ShimData) -> Result<(), AnyError> {
 # generated by deno install
 deno {} "$@"
 "#,
-    args.join(" "),
+    shim_data
+      .args
+      .iter()
+      .map(|arg| escape(arg.into()).into_owned())
+      .collect::<Vec<_>>()
+      .join(" "),
   );
   let mut file = File::create(shim_data.file_path.with_extension(""))?;
   file.write_all(template.as_bytes())?;
@@ -191,6 +233,12 @@ pub fn uninstall(name: String, root: Option<PathBuf>) -> Result<(), AnyError> {
 
   let file_path = installation_dir.join(&name);
 
+  // the shim of the shell, which exists on every platform, tells which
+  // subcommand it invokes
+  let maybe_subcommand = fs::read_to_string(&file_path)
+    .ok()
+    .and_then(|shim| get_shim_subcommand(&shim));
+
   let mut removed = false;
 
   if file_path.exists() {
@@ -200,11 +248,13 @@ pub fn uninstall(name: String, root: Option<PathBuf>) -> Result<(), AnyError> {
   };
 
   if cfg!(windows) {
-    let file_path = file_path.with_extension("cmd");
-    if file_path.exists() {
-      fs::remove_file(&file_path)?;
-      log::info!("deleted {}", file_path.to_string_lossy());
-      removed = true
+    for ext in ["cmd", "ps1"] {
+      let file_path = file_path.with_extension(ext);
+      if file_path.exists() {
+        fs::remove_file(&file_path)?;
+        log::info!("deleted {}", file_path.to_string_lossy());
+        removed = true
+      }
     }
   }
 
@@ -215,11 +265,21 @@ pub fn uninstall(name: String, root: Option<PathBuf>) -> Result<(), AnyError> {
   // There might be some extra files to delete
   // Note: tsconfig.json is legacy. We renamed it to deno.json.
   // Remove cleaning it up after January 2024
-  for ext in ["tsconfig.json", "deno.json", "lock.json"] {
-    let file_path = file_path.with_extension(ext);
-    if file_path.exists() {
-      fs::remove_file(&file_path)?;
-      log::info!("deleted {}", file_path.to_string_lossy());
+  let extra_file_exts: &[&str] = match maybe_subcommand {
+    Some(InstallSubcommand::Task) => &["deno.json"],
+    Some(InstallSubcommand::Run) | None => {
+      &["tsconfig.json", "deno.json", "lock.json"]
+    }
+  };
+  for ext in extra_file_exts {
+    for file_path in [
+      file_path.with_extension(ext),
+      get_hidden_file_with_ext(&file_path, ext),
+    ] {
+      if file_path.exists() {
+        fs::remove_file(&file_path)?;
+        log::info!("deleted {}", file_path.to_string_lossy());
+      }
     }
   }
 
@@ -231,11 +291,13 @@ pub async fn install_command(
   flags: Flags,
   install_flags: InstallFlags,
 ) -> Result<(), AnyError> {
-  // ensure the module is cached
-  ProcState::build(flags.clone())
-    .await?
-    .load_and_type_check_files(&[install_flags.module_url.clone()])
-    .await?;
+  if install_flags.subcommand == InstallSubcommand::Run {
+    // ensure the module is cached
+    ProcState::build(flags.clone())
+      .await?
+      .load_and_type_check_files(&[install_flags.module_url.clone()])
+      .await?;
+  }
 
   // create the install shim
   create_install_shim(flags, install_flags).await
@@ -270,6 +332,8 @@ async fn create_install_shim(
   log::info!("✅ Successfully installed {}", shim_data.name);
   log::info!("{}", shim_data.file_path.display());
   if cfg!(windows) {
+    let display_path = shim_data.file_path.with_extension("ps1");
+    log::info!("{} (PowerShell)", display_path.display());
     let display_path = shim_data.file_path.with_extension("");
     log::info!("{} (shell)", display_path.display());
   }
@@ -287,6 +351,7 @@ async fn create_install_shim(
   Ok(())
 }
 
+#[derive(Debug)]
 struct ShimData {
   name: String,
   installation_dir: PathBuf,
@@ -306,13 +371,27 @@ async fn resolve_shim_data(
   };
   let installation_dir = root.join("bin");
 
-  // Check if module_url is remote
-  let module_url = resolve_url_or_path(&install_flags.module_url)?;
-
-  let name = if install_flags.name.is_some() {
-    install_flags.name.clone()
-  } else {
-    infer_name_from_url(&module_url).await
+  let name = match install_flags.subcommand {
+    InstallSubcommand::Run => {
+      if install_flags.name.is_some() {
+        install_flags.name.clone()
+      } else {
+        // Check if module_url is remote
+        let module_url = resolve_url_or_path(&install_flags.module_url)?;
+        infer_name_from_url(&module_url).await
+      }
+    }
+    InstallSubcommand::Task => {
+      if install_flags.args.is_empty() {
+        return Err(generic_error(
+          "A task name must follow the configuration file. Aborting.",
+        ));
+      }
+      install_flags
+        .name
+        .clone()
+        .or_else(|| install_flags.args.first().cloned())
+    }
   };
 
   let name = match name {
@@ -330,6 +409,32 @@ async fn resolve_shim_data(
   }
 
   let mut extra_files: Vec<(PathBuf, String)> = vec![];
+  let executable_args = match install_flags.subcommand {
+    InstallSubcommand::Run => {
+      resolve_run_args(flags, install_flags, &file_path, &mut extra_files)?
+    }
+    InstallSubcommand::Task => {
+      resolve_task_args(flags, install_flags, &file_path, &mut extra_files)?
+    }
+  };
+
+  Ok(ShimData {
+    name,
+    installation_dir,
+    file_path,
+    args: executable_args,
+    extra_files,
+  })
+}
+
+/// Gets the arguments of an executable that runs the module with `deno run`.
+fn resolve_run_args(
+  flags: &Flags,
+  install_flags: &InstallFlags,
+  file_path: &Path,
+  extra_files: &mut Vec<(PathBuf, String)>,
+) -> Result<Vec<String>, AnyError> {
+  let module_url = resolve_url_or_path(&install_flags.module_url)?;
 
   let mut executable_args = vec!["run".to_string()];
   executable_args.extend_from_slice(&flags.to_permission_args());
@@ -341,21 +446,19 @@ async fn resolve_shim_data(
     executable_args.push("--cert".to_string());
     executable_args.push(ca_file.to_owned())
   }
-  if let Some(log_level) = flags.log_level {
-    if log_level == Level::Error {
-      executable_args.push("--quiet".to_string());
-    } else {
-      executable_args.push("--log-level".to_string());
-      let log_level = match log_level {
-        Level::Debug => "debug",
-        Level::Info => "info",
-        _ => {
-          return Err(generic_error(format!("invalid log level {log_level}")))
-        }
-      };
-      executable_args.push(log_level.to_string());
-    }
+  if let Some(cert_file) = &flags.tls_client_cert {
+    executable_args.push("--tls-client-cert".to_string());
+    executable_args.push(cert_file.to_owned());
+  }
+  if let Some(key_file) = &flags.tls_client_key {
+    executable_args.push("--tls-client-key".to_string());
+    executable_args.push(key_file.to_owned());
+  }
+  if let Some(hosts) = &flags.tls_client_cert_hosts {
+    executable_args
+      .push(format!("--tls-client-cert-hosts={}", hosts.join(",")));
   }
+  push_log_level_args(flags, &mut executable_args)?;
 
   // we should avoid a default branch here to ensure we continue to cover any
   // changes to this flag.
@@ -398,6 +501,10 @@ async fn resolve_shim_data(
     executable_args.push(seed.to_string());
   }
 
+  if let Some(storage_quota) = flags.storage_quota {
+    executable_args.push(format!("--storage-quota={storage_quota}"));
+  }
+
   if let Some(inspect) = flags.inspect {
     executable_args.push(format!("--inspect={inspect}"));
   }
@@ -413,7 +520,7 @@ async fn resolve_shim_data(
   }
 
   if let ConfigFlag::Path(config_path) = &flags.config_flag {
-    let copy_path = get_hidden_file_with_ext(&file_path, "deno.json");
+    let copy_path = get_hidden_file_with_ext(file_path, "deno.json");
     executable_args.push("--config".to_string());
     executable_args.push(copy_path.to_str().unwrap().to_string());
     extra_files.push((
@@ -431,7 +538,7 @@ async fn resolve_shim_data(
     // always use a lockfile for an npm entrypoint unless --no-lock
     || NpmPackageReference::from_specifier(&module_url).is_ok()
   {
-    let copy_path = get_hidden_file_with_ext(&file_path, "lock.json");
+    let copy_path = get_hidden_file_with_ext(file_path, "lock.json");
     executable_args.push("--lock".to_string());
     executable_args.push(copy_path.to_str().unwrap().to_string());
 
@@ -451,12 +558,174 @@ async fn resolve_shim_data(
   executable_args.push(module_url.to_string());
   executable_args.extend_from_slice(&install_flags.args);
 
-  Ok(ShimData {
-    name,
-    installation_dir,
-    file_path,
-    args: executable_args,
-    extra_files,
+  Ok(executable_args)
+}
+
+/// Gets the arguments of an executable that runs a task with `deno task`,
+/// using a copy of the configuration file so that the installed task doesn't
+/// change along with the project. The task still runs in the directory of the
+/// original configuration file.
+fn resolve_task_args(
+  flags: &Flags,
+  install_flags: &InstallFlags,
+  file_path: &Path,
+  extra_files: &mut Vec<(PathBuf, String)>,
+) -> Result<Vec<String>, AnyError> {
+  let run_only_flags = get_run_only_flags(flags);
+  if !run_only_flags.is_empty() {
+    return Err(generic_error(format!(
+      "The following flags can't be used with --subcommand=task: {}",
+      run_only_flags.join(", ")
+    )));
+  }
+
+  let config_file = ConfigFile::read(&install_flags.module_url)?;
+  let task_name = &install_flags.args[0];
+  let has_task = config_file
+    .to_tasks_config()?
+    .map_or(false, |tasks| tasks.contains_key(task_name));
+  if !has_task {
+    return Err(generic_error(format!(
+      "Task \"{}\" not found in {}",
+      task_name, config_file.specifier
+    )));
+  }
+  let config_path = specifier_to_file_path(&config_file.specifier)?;
+
+  // the global flags precede the subcommand, as `deno task` passes the
+  // arguments following its flags to the task
+  let mut executable_args = vec![];
+  push_log_level_args(flags, &mut executable_args)?;
+  if flags.unstable {
+    executable_args.push("--unstable".to_string());
+  }
+  executable_args.push("task".to_string());
+
+  let copy_path = get_hidden_file_with_ext(file_path, "deno.json");
+  executable_args.push("--config".to_string());
+  executable_args.push(copy_path.to_str().unwrap().to_string());
+  executable_args.push("--cwd".to_string());
+  executable_args
+    .push(config_path.parent().unwrap().to_str().unwrap().to_string());
+  extra_files.push((
+    copy_path,
+    fs::read_to_string(&config_path)
+      .with_context(|| format!("error reading {}", config_path.display()))?,
+  ));
+
+  executable_args.extend_from_slice(&install_flags.args);
+  Ok(executable_args)
+}
+
+fn push_log_level_args(
+  flags: &Flags,
+  executable_args: &mut Vec<String>,
+) -> Result<(), AnyError> {
+  if let Some(log_level) = flags.log_level {
+    if log_level == Level::Error {
+      executable_args.push("--quiet".to_string());
+    } else {
+      executable_args.push("--log-level".to_string());
+      let log_level = match log_level {
+        Level::Debug => "debug",
+        Level::Info => "info",
+        _ => {
+          return Err(generic_error(format!("invalid log level {log_level}")))
+        }
+      };
+      executable_args.push(log_level.to_string());
+    }
+  }
+
+  Ok(())
+}
+
+/// Gets the flags that only apply to executables which run a module, so that
+/// they aren't silently dropped when installing a task.
+fn get_run_only_flags(flags: &Flags) -> Vec<&'static str> {
+  let mut run_only_flags = vec![];
+  if !flags.to_permission_args().is_empty() {
+    run_only_flags.push("--allow-*");
+  }
+  if flags.location.is_some() {
+    run_only_flags.push("--location");
+  }
+  if let Some(CaData::File(_)) = &flags.ca_data {
+    run_only_flags.push("--cert");
+  }
+  if flags.tls_client_cert.is_some() {
+    run_only_flags.push("--tls-client-cert");
+  }
+  if flags.type_check_mode != TypeCheckMode::None {
+    run_only_flags.push("--check");
+  }
+  if flags.no_remote {
+    run_only_flags.push("--no-remote");
+  }
+  if flags.no_npm {
+    run_only_flags.push("--no-npm");
+  }
+  if flags.lock_write {
+    run_only_flags.push("--lock-write");
+  }
+  if flags.lock.is_some() {
+    run_only_flags.push("--lock");
+  }
+  if flags.no_lock {
+    run_only_flags.push("--no-lock");
+  }
+  if flags.cached_only {
+    run_only_flags.push("--cached-only");
+  }
+  if flags.no_prompt {
+    run_only_flags.push("--no-prompt");
+  }
+  if !flags.v8_flags.is_empty() {
+    run_only_flags.push("--v8-flags");
+  }
+  if flags.seed.is_some() {
+    run_only_flags.push("--seed");
+  }
+  if flags.storage_quota.is_some() {
+    run_only_flags.push("--storage-quota");
+  }
+  if flags.inspect.is_some() {
+    run_only_flags.push("--inspect");
+  }
+  if flags.inspect_brk.is_some() {
+    run_only_flags.push("--inspect-brk");
+  }
+  if flags.import_map_path.is_some() {
+    run_only_flags.push("--import-map");
+  }
+  match flags.config_flag {
+    ConfigFlag::Discover => {}
+    ConfigFlag::Path(_) => run_only_flags.push("--config"),
+    ConfigFlag::Disabled => run_only_flags.push("--no-config"),
+  }
+  run_only_flags
+}
+
+/// Gets the subcommand invoked by a shim generated by `deno install`, or
+/// `None` if the file isn't such a shim.
+fn get_shim_subcommand(shim: &str) -> Option<InstallSubcommand> {
+  if !shim.contains("generated by deno install") {
+    return None;
+  }
+  shim.lines().find_map(|line| {
+    let args = line
+      .strip_prefix("exec deno ")
+      .or_else(|| line.strip_prefix("deno "))
+      .or_else(|| line.strip_prefix("@deno "))?;
+    // the global flags may precede the subcommand
+    args
+      .split_whitespace()
+      .map(|arg| arg.trim_matches(|c| c == '"' || c == '\''))
+      .find_map(|arg| match arg {
+        "run" => Some(InstallSubcommand::Run),
+        "task" => Some(InstallSubcommand::Task),
+        _ => None,
+      })
   })
 }
 
@@ -632,6 +901,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -666,6 +936,7 @@ mod tests {
         name: None,
         root: Some(env::temp_dir()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -688,6 +959,7 @@ mod tests {
         name: None,
         root: Some(env::temp_dir()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -712,6 +984,7 @@ mod tests {
         name: None,
         root: Some(env::temp_dir()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -738,6 +1011,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(env::temp_dir()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -766,6 +1040,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(env::temp_dir()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -799,6 +1074,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(env::temp_dir()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -828,6 +1104,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(env::temp_dir()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -858,6 +1135,7 @@ mod tests {
         name: None,
         root: Some(temp_dir.clone()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -892,6 +1170,7 @@ mod tests {
         name: None,
         root: Some(env::temp_dir()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -927,6 +1206,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -956,6 +1236,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -976,6 +1257,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await;
@@ -997,6 +1279,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: true,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await;
@@ -1004,6 +1287,13 @@ mod tests {
     // Assert modified
     let file_content_2 = fs::read_to_string(&file_path).unwrap();
     assert!(file_content_2.contains("cat.ts"));
+    if cfg!(windows) {
+      // all the shims are regenerated
+      for ext in ["ps1", ""] {
+        let file_content = fs::read_to_string(file_path.with_extension(ext));
+        assert!(file_content.unwrap().contains("cat.ts"));
+      }
+    }
   }
 
   #[tokio::test]
@@ -1029,6 +1319,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: true,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await;
@@ -1042,8 +1333,6 @@ mod tests {
     assert!(content == "{}");
   }
 
-  // TODO: enable on Windows after fixing batch escaping
-  #[cfg(not(windows))]
   #[tokio::test]
   async fn install_shell_escaping() {
     let temp_dir = TempDir::new();
@@ -1058,6 +1347,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -1071,7 +1361,9 @@ mod tests {
     assert!(file_path.exists());
     let content = fs::read_to_string(file_path).unwrap();
     if cfg!(windows) {
-      // TODO: see comment above this test
+      assert!(content.contains(
+        r#""run" "--no-config" "http://localhost:4545/echo_server.ts" "\"^""#
+      ));
     } else {
       assert!(content.contains(
         r#"run --no-config 'http://localhost:4545/echo_server.ts' '"'"#
@@ -1079,6 +1371,53 @@ mod tests {
     }
   }
 
+  #[cfg(windows)]
+  #[tokio::test]
+  async fn install_shell_escaping_windows() {
+    let temp_dir = TempDir::new();
+    let bin_dir = temp_dir.path().join("bin");
+    std::fs::create_dir(&bin_dir).unwrap();
+
+    create_install_shim(
+      Flags::default(),
+      InstallFlags {
+        module_url: "http://localhost:4545/echo_server.ts".to_string(),
+        args: vec![
+          "100%".to_string(),
+          "%PATH%".to_string(),
+          "C:\\dir\\".to_string(),
+          "a\"&calc".to_string(),
+          "it's $HOME".to_string(),
+        ],
+        name: Some("echo_test".to_string()),
+        root: Some(temp_dir.path().to_path_buf()),
+        force: false,
+        subcommand: InstallSubcommand::Run,
+      },
+    )
+    .await
+    .unwrap();
+
+    let file_path = bin_dir.join("echo_test.cmd");
+    let content = fs::read_to_string(file_path).unwrap();
+    assert!(content.contains(
+      r#""100%%" "%%PATH%%" "C:\dir\\" "a\"^&calc^" "it's $HOME" %*"#
+    ));
+
+    let file_path = bin_dir.join("echo_test.ps1");
+    let content = fs::read_to_string(file_path).unwrap();
+    assert!(content
+      .contains(r#"'100%' '%PATH%' 'C:\dir\' 'a"&calc' 'it''s $HOME' @args"#));
+    assert!(content.contains(
+      r#"'"100%"' '"%PATH%"' '"C:\dir\\"' '"a\"&calc"' '"it''s $HOME"' @args"#
+    ));
+
+    let file_path = bin_dir.join("echo_test");
+    let content = fs::read_to_string(file_path).unwrap();
+    assert!(content
+      .contains(r#"'100%' '%PATH%' 'C:\dir\' 'a"&calc' 'it'\''s $HOME' "$@""#));
+  }
+
   #[tokio::test]
   async fn install_unicode() {
     let temp_dir = TempDir::new();
@@ -1098,6 +1437,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: false,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await
@@ -1142,6 +1482,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: true,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await;
@@ -1184,6 +1525,7 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_path_buf()),
         force: true,
+        subcommand: InstallSubcommand::Run,
       },
     )
     .await;
@@ -1206,6 +1548,153 @@ mod tests {
     assert!(content.contains(&expected_string));
   }
 
+  #[tokio::test]
+  async fn install_task() {
+    let temp_dir = TempDir::new();
+    let project_dir = temp_dir.path().join("project");
+    fs::create_dir(&project_dir).unwrap();
+    let config_file_path = project_dir.join("deno.json");
+    let config = r#"{ "tasks": { "build": "echo build" } }"#;
+    fs::write(&config_file_path, config).unwrap();
+
+    let shim_data = resolve_shim_data(
+      &Flags {
+        log_level: Some(Level::Error),
+        ..Flags::default()
+      },
+      &InstallFlags {
+        module_url: config_file_path.to_string_lossy().to_string(),
+        args: vec!["build".to_string(), "--release".to_string()],
+        name: None,
+        root: Some(temp_dir.path().to_path_buf()),
+        force: false,
+        subcommand: InstallSubcommand::Task,
+      },
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(shim_data.name, "build");
+    let copy_path = get_hidden_file_with_ext(&shim_data.file_path, "deno.json");
+    let project_dir = canonicalize_path(&project_dir).unwrap();
+    assert_eq!(
+      shim_data.args,
+      vec![
+        "--quiet",
+        "task",
+        "--config",
+        copy_path.to_str().unwrap(),
+        "--cwd",
+        project_dir.to_str().unwrap(),
+        "build",
+        "--release",
+      ]
+    );
+    assert_eq!(shim_data.extra_files, vec![(copy_path, config.to_string())]);
+  }
+
+  #[tokio::test]
+  async fn install_task_invalid() {
+    let temp_dir = TempDir::new();
+    let config_file_path = temp_dir.path().join("deno.json");
+    fs::write(
+      &config_file_path,
+      r#"{ "tasks": { "build": "echo build" } }"#,
+    )
+    .unwrap();
+    let install_flags = |args: Vec<&str>| InstallFlags {
+      module_url: config_file_path.to_string_lossy().to_string(),
+      args: args.into_iter().map(String::from).collect(),
+      name: Some("mytool".to_string()),
+      root: Some(temp_dir.path().to_path_buf()),
+      force: false,
+      subcommand: InstallSubcommand::Task,
+    };
+
+    let err = resolve_shim_data(
+      &Flags {
+        allow_net: Some(vec![]),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      },
+      &install_flags(vec!["build"]),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "The following flags can't be used with --subcommand=task: --allow-*, --check"
+    );
+
+    let err = resolve_shim_data(&Flags::default(), &install_flags(vec![]))
+      .await
+      .unwrap_err();
+    assert!(err.to_string().contains("A task name must follow"));
+
+    let err =
+      resolve_shim_data(&Flags::default(), &install_flags(vec!["test"]))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().starts_with("Task \"test\" not found in"));
+  }
+
+  #[test]
+  fn shim_subcommand() {
+    assert_eq!(
+      get_shim_subcommand(
+        "#!/bin/sh\n# generated by deno install\nexec deno run --allow-net 'https://deno.land/x/mod.ts' \"$@\"\n"
+      ),
+      Some(InstallSubcommand::Run)
+    );
+    assert_eq!(
+      get_shim_subcommand(
+        "% generated by deno install %\n@deno \"--quiet\" \"task\" \"--config\" \"deno.json\" \"build\" %*\n"
+      ),
+      Some(InstallSubcommand::Task)
+    );
+    assert_eq!(
+      get_shim_subcommand("#!/bin/sh\nexec deno run mod.ts\n"),
+      None
+    );
+  }
+
+  #[tokio::test]
+  async fn uninstall_task() {
+    let temp_dir = TempDir::new();
+    let bin_dir = temp_dir.path().join("bin");
+    let config_file_path = temp_dir.path().join("deno.json");
+    fs::write(
+      &config_file_path,
+      r#"{ "tasks": { "build": "echo build" } }"#,
+    )
+    .unwrap();
+
+    create_install_shim(
+      Flags::default(),
+      InstallFlags {
+        module_url: config_file_path.to_string_lossy().to_string(),
+        args: vec!["build".to_string()],
+        name: Some("mytool".to_string()),
+        root: Some(temp_dir.path().to_path_buf()),
+        force: false,
+        subcommand: InstallSubcommand::Task,
+      },
+    )
+    .await
+    .unwrap();
+
+    let copy_path = bin_dir.join(".mytool.deno.json");
+    assert!(copy_path.exists());
+
+    uninstall("mytool".to_string(), Some(temp_dir.path().to_path_buf()))
+      .unwrap();
+
+    assert!(!bin_dir.join("mytool").exists());
+    assert!(!copy_path.exists());
+    // the original configuration file is left alone
+    assert!(config_file_path.exists());
+  }
+
   #[test]
   fn uninstall_basic() {
     let temp_dir = TempDir::new();
@@ -1215,6 +1704,7 @@ mod tests {
     let mut file_path = bin_dir.join("echo_test");
     File::create(&file_path).unwrap();
     if cfg!(windows) {
+      File::create(file_path.with_extension("ps1")).unwrap();
       file_path = file_path.with_extension("cmd");
       File::create(&file_path).unwrap();
     }
@@ -1245,6 +1735,7 @@ mod tests {
     if cfg!(windows) {
       file_path = file_path.with_extension("cmd");
       assert!(!file_path.exists());
+      assert!(!file_path.with_extension("ps1").exists());
     }
   }
 }
@@ -8,22 +8,37 @@
 //! the same functions as ops available in JS runtime.
 use crate::args::CliOptions;
 use crate::args::FilesConfig;
+use crate::args::Flags;
+use crate::args::LintFlags;
 use crate::args::LintOptions;
 use crate::args::LintReporterKind;
 use crate::args::LintRulesConfig;
 use crate::colors;
 use crate::tools::fmt::run_parallelized;
+use crate::tools::lint_ignores::filter_explanation_diagnostics;
+use crate::tools::lint_ignores::get_ignore_directive_diagnostics;
+use crate::tools::lint_ignores::get_unused_directive_removal_range;
+use crate::tools::lint_ignores::has_explanations;
+use crate::tools::lint_ignores::IGNORE_DIRECTIVE_CODE;
+use crate::tools::lint_ignores::UNUSED_IGNORE_MESSAGE;
+use crate::tools::lint_rule_options::get_rule_options_schema;
+use crate::tools::lint_rule_options::LintRuleOptions;
 use crate::util::file_watcher;
 use crate::util::file_watcher::ResolutionResult;
+use crate::util::file_watcher::WatchDiagnostics;
 use crate::util::fs::FileCollector;
+use crate::util::glob::no_files_found_error;
 use crate::util::path::is_supported_ext;
+use crate::util::path::path_to_specifier;
 use deno_ast::MediaType;
+use deno_ast::ParsedSource;
 use deno_core::anyhow::bail;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsStackFrame;
 use deno_core::serde_json;
 use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::diagnostic::Range;
 use deno_lint::linter::Linter;
 use deno_lint::linter::LinterBuilder;
 use deno_lint::rules;
@@ -37,6 +52,7 @@ use std::io::stdin;
 use std::io::Read;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI32;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -45,77 +61,52 @@ use crate::cache::IncrementalCache;
 
 static STDIN_FILE_NAME: &str = "_stdin.ts";
 
-fn create_reporter(kind: LintReporterKind) -> Box<dyn LintReporter + Send> {
+fn create_reporter(
+  kind: LintReporterKind,
+  verbose: bool,
+) -> Box<dyn LintReporter + Send> {
   match kind {
-    LintReporterKind::Pretty => Box::new(PrettyLintReporter::new()),
+    LintReporterKind::Pretty => Box::new(PrettyLintReporter::new(verbose)),
     LintReporterKind::Json => Box::new(JsonLintReporter::new()),
-    LintReporterKind::Compact => Box::new(CompactLintReporter::new()),
+    LintReporterKind::Compact => Box::new(CompactLintReporter::new(verbose)),
   }
 }
 
+/// Gets the URL of the documentation of a lint rule.
+pub fn get_rule_docs_url(code: &str) -> String {
+  format!("https://lint.deno.land/#{code}")
+}
+
 pub async fn lint(
   cli_options: CliOptions,
   lint_options: LintOptions,
 ) -> Result<(), AnyError> {
   // Try to get lint rules. If none were set use recommended rules.
-  let lint_rules = get_configured_rules(lint_options.rules);
+  let linter = CliLinter::new(&lint_options)?;
 
-  if lint_rules.is_empty() {
+  if linter.rules().is_empty() {
     bail!("No rules have been configured")
   }
 
   let files = lint_options.files;
   let reporter_kind = lint_options.reporter_kind;
-
-  let resolver = |changed: Option<Vec<PathBuf>>| {
-    let files_changed = changed.is_some();
-    let result = collect_lint_files(&files).map(|files| {
-      if let Some(paths) = changed {
-        files
-          .iter()
-          .any(|path| paths.contains(path))
-          .then_some(files)
-          .unwrap_or_else(|| [].to_vec())
-      } else {
-        files
-      }
-    });
-
-    let paths_to_watch = files.include.clone();
-
-    async move {
-      if files_changed && matches!(result, Ok(ref files) if files.is_empty()) {
-        ResolutionResult::Ignore
-      } else {
-        ResolutionResult::Restart {
-          paths_to_watch,
-          result,
-        }
-      }
-    }
-  };
+  let verbose = lint_options.verbose;
 
   let has_error = Arc::new(AtomicBool::new(false));
   let deno_dir = cli_options.resolve_deno_dir()?;
   let operation = |paths: Vec<PathBuf>| async {
     let incremental_cache = Arc::new(IncrementalCache::new(
       &deno_dir.lint_incremental_cache_db_file_path(),
-      // use a hash of the rule names in order to bust the cache
-      &{
-        // ensure this is stable by sorting it
-        let mut names = lint_rules.iter().map(|r| r.code()).collect::<Vec<_>>();
-        names.sort_unstable();
-        names
-      },
+      &incremental_cache_state(&linter),
       &paths,
     ));
     let target_files_len = paths.len();
     let reporter_lock =
-      Arc::new(Mutex::new(create_reporter(reporter_kind.clone())));
+      Arc::new(Mutex::new(create_reporter(reporter_kind.clone(), verbose)));
 
     run_parallelized(paths, {
       let has_error = has_error.clone();
-      let lint_rules = lint_rules.clone();
+      let linter = linter.clone();
       let reporter_lock = reporter_lock.clone();
       let incremental_cache = incremental_cache.clone();
       move |file_path| {
@@ -126,7 +117,7 @@ pub async fn lint(
           return Ok(());
         }
 
-        let r = lint_file(&file_path, file_text, lint_rules);
+        let r = lint_file(&file_path, file_text, &linter);
         if let Ok((file_diagnostics, file_text)) = &r {
           if file_diagnostics.is_empty() {
             // update the incremental cache if there were no diagnostics
@@ -148,55 +139,256 @@ pub async fn lint(
     incremental_cache.wait_completion().await;
     reporter_lock.lock().unwrap().close(target_files_len);
 
-    Ok(())
+    Ok::<(), AnyError>(())
+  };
+  if lint_options.is_stdin {
+    let reporter_lock =
+      Arc::new(Mutex::new(create_reporter(reporter_kind, verbose)));
+    let r = lint_stdin(&linter);
+    handle_lint_result(
+      STDIN_FILE_NAME,
+      r,
+      reporter_lock.clone(),
+      has_error.clone(),
+    );
+    reporter_lock.lock().unwrap().close(1);
+  } else {
+    let target_files = collect_lint_files(&files).and_then(|collected| {
+      if collected.is_empty() {
+        Err(no_files_found_error(
+          "No target files found.",
+          &files.include,
+        ))
+      } else {
+        Ok(collected)
+      }
+    })?;
+    debug!("Found {} files", target_files.len());
+    operation(target_files).await?;
+  };
+  let has_error = has_error.load(Ordering::Relaxed);
+  if has_error {
+    std::process::exit(1);
+  }
+
+  Ok(())
+}
+
+struct LintWatchRun {
+  linter: CliLinter,
+  reporter_kind: LintReporterKind,
+  verbose: bool,
+  incremental_cache_path: PathBuf,
+  paths: Vec<PathBuf>,
+}
+
+/// Resolves the files to lint for a run of the watcher, re-reading the
+/// configuration file so that changes to it are respected. Returns `None`
+/// when none of the changed paths affect the linted files.
+fn resolve_lint_watch_run(
+  flags: &Flags,
+  lint_flags: &LintFlags,
+  changed: Option<&[PathBuf]>,
+) -> Result<Option<(Vec<PathBuf>, LintWatchRun)>, AnyError> {
+  let cli_options = CliOptions::from_flags(flags.clone())?;
+  let lint_options = cli_options.resolve_lint_options(lint_flags.clone())?;
+  let linter = CliLinter::new(&lint_options)?;
+  if linter.rules().is_empty() {
+    bail!("No rules have been configured")
+  }
+
+  let config_paths = cli_options.watch_config_paths();
+  let paths = collect_lint_files(&lint_options.files)?;
+  if let Some(changed) = changed {
+    if !changed
+      .iter()
+      .any(|path| paths.contains(path) || config_paths.contains(path))
+    {
+      return Ok(None);
+    }
+  }
+
+  let mut paths_to_watch = lint_options.files.include;
+  if paths_to_watch.is_empty() {
+    paths_to_watch.push(PathBuf::from("."));
+  }
+  paths_to_watch.extend(config_paths);
+
+  Ok(Some((
+    paths_to_watch,
+    LintWatchRun {
+      linter,
+      reporter_kind: lint_options.reporter_kind,
+      verbose: lint_options.verbose,
+      incremental_cache_path: cli_options
+        .resolve_deno_dir()?
+        .lint_incremental_cache_db_file_path(),
+      paths,
+    },
+  )))
+}
+
+/// Lints files on every change to them or the configuration. Runs after the
+/// first one only output the diagnostics that are new along with a summary
+/// of how the problems changed.
+pub async fn lint_with_watch(
+  flags: Flags,
+  lint_flags: LintFlags,
+) -> Result<(), AnyError> {
+  let cli_options = CliOptions::from_flags(flags.clone())?;
+  if cli_options
+    .resolve_lint_options(lint_flags.clone())?
+    .is_stdin
+  {
+    return Err(generic_error(
+      "Lint watch on standard input is not supported.",
+    ));
+  }
+  let clear_screen = !cli_options.no_clear_screen();
+  let stack_trace_options = cli_options.stack_trace_options();
+  let last_exit_code = Arc::new(AtomicI32::new(0));
+  let watch_diagnostics = Arc::new(Mutex::new(WatchDiagnostics::default()));
+
+  let resolver = |changed: Option<Vec<PathBuf>>| {
+    let result =
+      resolve_lint_watch_run(&flags, &lint_flags, changed.as_deref());
+    async move {
+      match result {
+        Ok(None) => ResolutionResult::Ignore,
+        Ok(Some((paths_to_watch, run))) => ResolutionResult::Restart {
+          paths_to_watch,
+          result: Ok(run),
+        },
+        Err(err) => ResolutionResult::Restart {
+          paths_to_watch: Vec::new(),
+          result: Err(err),
+        },
+      }
+    }
   };
-  if cli_options.watch_paths().is_some() {
-    if lint_options.is_stdin {
-      return Err(generic_error(
-        "Lint watch on standard input is not supported.",
+
+  let operation = |run: LintWatchRun| {
+    let last_exit_code = last_exit_code.clone();
+    let watch_diagnostics = watch_diagnostics.clone();
+    async move {
+      // errors are considered a failure until the run completes
+      last_exit_code.store(1, Ordering::Relaxed);
+      if !clear_screen {
+        file_watcher::print_run_started("Lint");
+      }
+      let incremental_cache = Arc::new(IncrementalCache::new(
+        &run.incremental_cache_path,
+        &incremental_cache_state(&run.linter),
+        &run.paths,
       ));
+      let target_files_len = run.paths.len();
+      let results = Arc::new(Mutex::new(Vec::new()));
+      run_parallelized(run.paths, {
+        let linter = run.linter.clone();
+        let incremental_cache = incremental_cache.clone();
+        let results = results.clone();
+        move |file_path| {
+          let file_text = fs::read_to_string(&file_path)?;
+          if incremental_cache.is_file_same(&file_path, &file_text) {
+            return Ok(());
+          }
+          let r = lint_file(&file_path, file_text, &linter);
+          if let Ok((file_diagnostics, file_text)) = &r {
+            if file_diagnostics.is_empty() {
+              incremental_cache.update_file(&file_path, file_text)
+            }
+          }
+          results.lock().unwrap().push((file_path, r));
+          Ok(())
+        }
+      })
+      .await?;
+      incremental_cache.wait_completion().await;
+
+      let mut results = std::mem::take(&mut *results.lock().unwrap());
+      results.sort_by(|a, b| a.0.cmp(&b.0));
+      let mut keys = Vec::new();
+      for (_, result) in results.iter_mut() {
+        if let Ok((file_diagnostics, source)) = result {
+          sort_diagnostics(file_diagnostics);
+          let source_lines = source.split('\n').collect::<Vec<_>>();
+          keys.extend(
+            file_diagnostics
+              .iter()
+              .map(|d| lint_diagnostic_key(d, &source_lines)),
+          );
+        }
+      }
+      let (is_new, maybe_delta) =
+        watch_diagnostics.lock().unwrap().update(&keys);
+
+      let mut reporter = create_reporter(run.reporter_kind, run.verbose);
+      let mut is_new = is_new.into_iter();
+      let mut has_error = false;
+      for (file_path, result) in results {
+        match result {
+          Ok((file_diagnostics, source)) => {
+            for d in file_diagnostics.iter() {
+              has_error = true;
+              if is_new.next().unwrap() {
+                reporter.visit_diagnostic(d, &source);
+              }
+            }
+          }
+          Err(err) => {
+            has_error = true;
+            reporter.visit_error(&file_path.to_string_lossy(), &err);
+          }
+        }
+      }
+      reporter.close(target_files_len);
+      if let Some(delta) = maybe_delta {
+        info!("{}", delta);
+      }
+      last_exit_code.store(has_error as i32, Ordering::Relaxed);
+      Ok(())
     }
+  };
+
+  file_watcher::watch_until_interrupted(
     file_watcher::watch_func(
       resolver,
       operation,
       file_watcher::PrintConfig {
         job_name: "Lint".to_string(),
-        clear_screen: !cli_options.no_clear_screen(),
+        clear_screen,
+        stack_trace_options,
       },
-    )
-    .await?;
-  } else {
-    if lint_options.is_stdin {
-      let reporter_lock = Arc::new(Mutex::new(create_reporter(reporter_kind)));
-      let r = lint_stdin(lint_rules);
-      handle_lint_result(
-        STDIN_FILE_NAME,
-        r,
-        reporter_lock.clone(),
-        has_error.clone(),
-      );
-      reporter_lock.lock().unwrap().close(1);
-    } else {
-      let target_files = collect_lint_files(&files).and_then(|files| {
-        if files.is_empty() {
-          Err(generic_error("No target files found."))
-        } else {
-          Ok(files)
-        }
-      })?;
-      debug!("Found {} files", target_files.len());
-      operation(target_files).await?;
-    };
-    let has_error = has_error.load(Ordering::Relaxed);
-    if has_error {
-      std::process::exit(1);
-    }
-  }
+    ),
+    last_exit_code.clone(),
+  )
+  .await
+}
 
-  Ok(())
+/// Gets the state of the incremental cache, which is busted when the rules
+/// or their options change.
+fn incremental_cache_state(linter: &CliLinter) -> serde_json::Value {
+  // ensure this is stable by sorting it
+  let mut names = linter.rules.iter().map(|r| r.code()).collect::<Vec<_>>();
+  names.sort_unstable();
+  serde_json::json!({
+    "rules": names,
+    "options": linter.rule_options.config(),
+    "reportIgnores": linter.report_ignores,
+  })
 }
 
-fn collect_lint_files(files: &FilesConfig) -> Result<Vec<PathBuf>, AnyError> {
+fn lint_diagnostic_key(d: &LintDiagnostic, source_lines: &[&str]) -> String {
+  let line_text = source_lines
+    .get(d.range.start.line_index)
+    .copied()
+    .unwrap_or_default();
+  file_watcher::diagnostic_key(&d.filename, &d.code, &d.message, line_text)
+}
+
+pub fn collect_lint_files(
+  files: &FilesConfig,
+) -> Result<Vec<PathBuf>, AnyError> {
   FileCollector::new(is_supported_ext)
     .ignore_git_folder()
     .ignore_node_modules()
@@ -204,8 +396,14 @@ fn collect_lint_files(files: &FilesConfig) -> Result<Vec<PathBuf>, AnyError> {
     .collect_files(&files.include)
 }
 
-pub fn print_rules_list(json: bool) {
-  let lint_rules = rules::get_recommended_rules();
+pub fn print_rules_list(json: bool, maybe_filter_tags: Option<&[String]>) {
+  let lint_rules = match maybe_filter_tags {
+    Some(filter_tags) => rules::get_all_rules()
+      .into_iter()
+      .filter(|rule| has_any_tag(rule.as_ref(), filter_tags))
+      .collect(),
+    None => rules::get_recommended_rules(),
+  };
 
   if json {
     let json_rules: Vec<serde_json::Value> = lint_rules
@@ -215,6 +413,7 @@ pub fn print_rules_list(json: bool) {
           "code": rule.code(),
           "tags": rule.tags(),
           "docs": rule.docs(),
+          "options": get_rule_options_schema(rule.code()),
         })
       })
       .collect();
@@ -226,12 +425,159 @@ pub fn print_rules_list(json: bool) {
     println!("Available rules:");
     for rule in lint_rules.iter() {
       println!(" - {}", rule.code());
-      println!("   help: https://lint.deno.land/#{}", rule.code());
+      println!("   help: {}", get_rule_docs_url(rule.code()));
       println!();
     }
   }
 }
 
+fn has_any_tag(rule: &dyn LintRule, tags: &[String]) -> bool {
+  rule.tags().iter().any(|tag| tags.iter().any(|t| t == tag))
+}
+
+/// The decision of the lint configuration which enables or disables a rule.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum RuleSource {
+  /// Recommended rule, when the configuration doesn't specify tags.
+  Default,
+  /// Rule with one of the tags of the configuration.
+  Tags,
+  /// Rule listed in the `include` of the configuration.
+  Include,
+  /// Rule listed in the `exclude` of the configuration, which is disabled.
+  Exclude,
+}
+
+impl std::fmt::Display for RuleSource {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RuleSource::Default => write!(f, "default"),
+      RuleSource::Tags => write!(f, "tags"),
+      RuleSource::Include => write!(f, "include"),
+      RuleSource::Exclude => write!(f, "exclude"),
+    }
+  }
+}
+
+/// Resolves why each rule is enabled or disabled by the rules configuration,
+/// following the same logic as `get_configured_rules`. Rules which aren't
+/// mentioned by the configuration in any way are omitted.
+fn resolve_rule_sources(
+  rules: &LintRulesConfig,
+) -> Vec<(Arc<dyn LintRule>, RuleSource)> {
+  let default_tags = vec!["recommended".to_string()];
+  let tags = rules.tags.as_ref().unwrap_or(&default_tags);
+  let contains = |codes: &Option<Vec<String>>, code: &str| {
+    codes
+      .as_ref()
+      .map(|codes| codes.iter().any(|c| c == code))
+      .unwrap_or(false)
+  };
+  let mut rule_sources = rules::get_all_rules()
+    .into_iter()
+    .filter_map(|rule| {
+      let source = if contains(&rules.exclude, rule.code()) {
+        RuleSource::Exclude
+      } else if has_any_tag(rule.as_ref(), tags) {
+        if rules.tags.is_some() {
+          RuleSource::Tags
+        } else {
+          RuleSource::Default
+        }
+      } else if contains(&rules.include, rule.code()) {
+        RuleSource::Include
+      } else {
+        return None;
+      };
+      Some((rule, source))
+    })
+    .collect::<Vec<_>>();
+  rule_sources.sort_by_key(|(rule, _)| rule.code());
+  rule_sources
+}
+
+/// Prints the rules that apply to the file of `--rules --file` with the
+/// configuration file, along with the decision that enabled or disabled each
+/// of them.
+pub fn print_file_rules(
+  cli_options: &CliOptions,
+  lint_flags: LintFlags,
+) -> Result<(), AnyError> {
+  let file = lint_flags.maybe_rules_file.clone().unwrap();
+  let json = lint_flags.json;
+  let maybe_filter_tags = lint_flags.maybe_rules_filter_tags.clone();
+  let lint_options = cli_options.resolve_lint_options(lint_flags)?;
+  let specifier = path_to_specifier(&file)?;
+  let is_linted = lint_options.files.matches_specifier(&specifier);
+  let rule_sources = if is_linted {
+    resolve_rule_sources(&lint_options.rules)
+      .into_iter()
+      .filter(|(rule, _)| match &maybe_filter_tags {
+        Some(filter_tags) => has_any_tag(rule.as_ref(), filter_tags),
+        None => true,
+      })
+      .collect()
+  } else {
+    Vec::new()
+  };
+  let maybe_config_specifier = cli_options.maybe_config_file_specifier();
+
+  if json {
+    let json_rules = rule_sources
+      .iter()
+      .map(|(rule, source)| {
+        serde_json::json!({
+          "code": rule.code(),
+          "tags": rule.tags(),
+          "active": *source != RuleSource::Exclude,
+          "source": source,
+        })
+      })
+      .collect::<Vec<_>>();
+    let json_str = serde_json::to_string_pretty(&serde_json::json!({
+      "file": specifier,
+      "config": maybe_config_specifier,
+      "linted": is_linted,
+      "rules": json_rules,
+    }))
+    .unwrap();
+    println!("{json_str}");
+    return Ok(());
+  }
+
+  let config_name = match &maybe_config_specifier {
+    Some(specifier) => specifier.to_string(),
+    None => "the default configuration".to_string(),
+  };
+  if !is_linted {
+    println!("{} is not linted with {}", file.display(), config_name);
+    return Ok(());
+  }
+  println!("Rules for {} with {}:", file.display(), config_name);
+  for (rule, source) in &rule_sources {
+    if *source == RuleSource::Exclude {
+      continue;
+    }
+    println!(" - {} ({})", rule.code(), source);
+    if !rule.tags().is_empty() {
+      println!("   tags: {}", rule.tags().join(", "));
+    }
+  }
+  let excluded_rules = rule_sources
+    .iter()
+    .filter(|(_, source)| *source == RuleSource::Exclude)
+    .collect::<Vec<_>>();
+  if !excluded_rules.is_empty() {
+    println!();
+    println!("Excluded rules:");
+    for (rule, _) in excluded_rules {
+      println!(" - {}", rule.code());
+    }
+  }
+  Ok(())
+}
+
 pub fn create_linter(
   media_type: MediaType,
   rules: Vec<Arc<dyn LintRule>>,
@@ -244,38 +590,144 @@ pub fn create_linter(
     .build()
 }
 
+/// Creates a linter which disregards the `deno-lint-ignore` directives, to
+/// find the diagnostics they suppress.
+pub fn create_unsuppressed_linter(
+  media_type: MediaType,
+  rules: Vec<Arc<dyn LintRule>>,
+) -> Linter {
+  LinterBuilder::default()
+    .ignore_file_directive("deno-lint-ignore-file")
+    .ignore_diagnostic_directive("deno-lint-ignore-disabled")
+    .media_type(media_type)
+    .rules(rules)
+    .build()
+}
+
+/// Lints with the configured rules and options. `deno lint`, `deno ci` and
+/// the language server all lint through it, so they report the same
+/// diagnostics.
+#[derive(Clone)]
+pub struct CliLinter {
+  rules: Vec<Arc<dyn LintRule>>,
+  rule_options: Arc<LintRuleOptions>,
+  report_ignores: bool,
+}
+
+impl CliLinter {
+  pub fn new(lint_options: &LintOptions) -> Result<Self, AnyError> {
+    Ok(Self {
+      rules: get_configured_rules(lint_options.rules.clone()),
+      rule_options: Arc::new(LintRuleOptions::resolve(
+        &lint_options.rules.options,
+      )?),
+      report_ignores: lint_options.report_unused_or_bare_ignores,
+    })
+  }
+
+  pub fn rules(&self) -> &[Arc<dyn LintRule>] {
+    &self.rules
+  }
+
+  /// Parses and lints the text of a file.
+  pub fn lint_source(
+    &self,
+    file_name: String,
+    media_type: MediaType,
+    source_code: String,
+  ) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
+    let linter = create_linter(media_type, self.rules.clone());
+    let (parsed_source, diagnostics) =
+      linter.lint(file_name, source_code.clone())?;
+    Ok((self.finish(&parsed_source, diagnostics), source_code))
+  }
+
+  /// Lints a file which is already parsed.
+  pub fn lint_parsed_source(
+    &self,
+    parsed_source: &ParsedSource,
+  ) -> Vec<LintDiagnostic> {
+    let linter = create_linter(parsed_source.media_type(), self.rules.clone());
+    let diagnostics = linter.lint_with_ast(parsed_source);
+    self.finish(parsed_source, diagnostics)
+  }
+
+  /// Applies the options of the rules and the explanations of the ignore
+  /// directives to the diagnostics of `deno_lint`, and adds the ones of the
+  /// ignore directives when they're reported.
+  fn finish(
+    &self,
+    parsed_source: &ParsedSource,
+    diagnostics: Vec<LintDiagnostic>,
+  ) -> Vec<LintDiagnostic> {
+    let unsuppressed_diagnostics = if self.report_ignores
+      || has_explanations(parsed_source)
+    {
+      create_unsuppressed_linter(parsed_source.media_type(), self.rules.clone())
+        .lint_with_ast(parsed_source)
+    } else {
+      Vec::new()
+    };
+    let diagnostics = filter_explanation_diagnostics(
+      parsed_source,
+      diagnostics,
+      &self.rules,
+      &unsuppressed_diagnostics,
+    );
+    let mut diagnostics = self
+      .rule_options
+      .filter_diagnostics(parsed_source, diagnostics);
+
+    if self.report_ignores {
+      let unsuppressed_diagnostics = self
+        .rule_options
+        .filter_diagnostics(parsed_source, unsuppressed_diagnostics);
+      let ignore_diagnostics = get_ignore_directive_diagnostics(
+        parsed_source,
+        parsed_source.specifier(),
+        &unsuppressed_diagnostics,
+      );
+      // the unused directives are only reported once, with their fix
+      diagnostics.retain(|d| {
+        d.code != "ban-unused-ignore"
+          || !ignore_diagnostics.iter().any(|ignore_d| {
+            ignore_d.message == UNUSED_IGNORE_MESSAGE
+              && ignore_d.range.start.line_index == d.range.start.line_index
+          })
+      });
+      diagnostics.extend(ignore_diagnostics);
+    }
+
+    diagnostics
+  }
+}
+
 fn lint_file(
   file_path: &PathBuf,
   source_code: String,
-  lint_rules: Vec<Arc<dyn LintRule>>,
+  linter: &CliLinter,
 ) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
   let file_name = file_path.to_string_lossy().to_string();
   let media_type = MediaType::from(file_path);
-
-  let linter = create_linter(media_type, lint_rules);
-
-  let (_, file_diagnostics) = linter.lint(file_name, source_code.clone())?;
-
-  Ok((file_diagnostics, source_code))
+  linter.lint_source(file_name, media_type, source_code)
 }
 
 /// Lint stdin and write result to stdout.
 /// Treats input as TypeScript.
 /// Compatible with `--json` flag.
 fn lint_stdin(
-  lint_rules: Vec<Arc<dyn LintRule>>,
+  linter: &CliLinter,
 ) -> Result<(Vec<LintDiagnostic>, String), AnyError> {
   let mut source_code = String::new();
   if stdin().read_to_string(&mut source_code).is_err() {
     return Err(generic_error("Failed to read from stdin"));
   }
 
-  let linter = create_linter(MediaType::TypeScript, lint_rules);
-
-  let (_, file_diagnostics) =
-    linter.lint(STDIN_FILE_NAME.to_string(), source_code.clone())?;
-
-  Ok((file_diagnostics, source_code))
+  linter.lint_source(
+    STDIN_FILE_NAME.to_string(),
+    MediaType::TypeScript,
+    source_code,
+  )
 }
 
 fn handle_lint_result(
@@ -291,7 +743,7 @@ fn handle_lint_result(
       sort_diagnostics(&mut file_diagnostics);
       for d in file_diagnostics.iter() {
         has_error.store(true, Ordering::Relaxed);
-        reporter.visit_diagnostic(d, source.split('\n').collect());
+        reporter.visit_diagnostic(d, &source);
       }
     }
     Err(err) => {
@@ -302,7 +754,7 @@ fn handle_lint_result(
 }
 
 trait LintReporter {
-  fn visit_diagnostic(&mut self, d: &LintDiagnostic, source_lines: Vec<&str>);
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, source: &str);
   fn visit_error(&mut self, file_path: &str, err: &AnyError);
   fn close(&mut self, check_count: usize);
 }
@@ -315,21 +767,26 @@ struct LintError {
 
 struct PrettyLintReporter {
   lint_count: u32,
+  verbose: bool,
 }
 
 impl PrettyLintReporter {
-  fn new() -> PrettyLintReporter {
-    PrettyLintReporter { lint_count: 0 }
+  fn new(verbose: bool) -> PrettyLintReporter {
+    PrettyLintReporter {
+      lint_count: 0,
+      verbose,
+    }
   }
 }
 
 impl LintReporter for PrettyLintReporter {
-  fn visit_diagnostic(&mut self, d: &LintDiagnostic, source_lines: Vec<&str>) {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, source: &str) {
     self.lint_count += 1;
 
     let pretty_message = format!("({}) {}", colors::red(&d.code), &d.message);
+    let source_lines = source.split('\n').collect::<Vec<_>>();
 
-    let message = format_diagnostic(
+    let mut message = format_diagnostic(
       &d.code,
       &pretty_message,
       &source_lines,
@@ -344,6 +801,13 @@ impl LintReporter for PrettyLintReporter {
         Some(d.range.start.column_index as i64 + 1),
       )),
     );
+    if self.verbose {
+      message.push_str(&format!(
+        "\n    {} {}",
+        colors::cyan("docs:"),
+        get_rule_docs_url(&d.code)
+      ));
+    }
 
     eprintln!("{message}\n");
   }
@@ -370,25 +834,38 @@ impl LintReporter for PrettyLintReporter {
 
 struct CompactLintReporter {
   lint_count: u32,
+  verbose: bool,
 }
 
 impl CompactLintReporter {
-  fn new() -> CompactLintReporter {
-    CompactLintReporter { lint_count: 0 }
+  fn new(verbose: bool) -> CompactLintReporter {
+    CompactLintReporter {
+      lint_count: 0,
+      verbose,
+    }
   }
 }
 
 impl LintReporter for CompactLintReporter {
-  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source: &str) {
     self.lint_count += 1;
 
+    let docs = if self.verbose {
+      format!(" {}", get_rule_docs_url(&d.code))
+    } else {
+      "".to_string()
+    };
+    // `path:line:col: message (rule)` is understood by the default
+    // errorformat of most editors, so keep each diagnostic on a single line
+    let message = d.message.split_whitespace().collect::<Vec<_>>().join(" ");
     eprintln!(
-      "{}: line {}, col {} - {} ({})",
+      "{}:{}:{}: {} ({}){}",
       d.filename,
       d.range.start.line_index + 1,
       d.range.start.column_index + 1,
-      d.message,
-      d.code
+      message,
+      d.code,
+      docs
     )
   }
 
@@ -460,9 +937,9 @@ pub fn format_diagnostic(
     "".to_string()
   };
   let help = format!(
-    "    {} for further information visit https://lint.deno.land/#{}",
+    "    {} for further information visit {}",
     colors::cyan("help:"),
-    diagnostic_code
+    get_rule_docs_url(diagnostic_code)
   );
 
   format!(
@@ -475,9 +952,50 @@ pub fn format_diagnostic(
   )
 }
 
+/// A diagnostic as serialized by `deno lint --json`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonLintDiagnostic {
+  #[serde(flatten)]
+  diagnostic: LintDiagnostic,
+  doc_url: String,
+  fixes: Vec<LintFix>,
+}
+
+/// An edit that resolves a diagnostic, replacing the text in `range` with
+/// `text`.
+#[derive(Serialize)]
+struct LintFix {
+  description: String,
+  range: Range,
+  text: String,
+}
+
+/// Gets the edits that resolve a diagnostic. Only removals which can't
+/// change what the program does are offered: unused ignore directives are
+/// comments and `no-extra-semi` doesn't report empty statements that are the
+/// body of another statement.
+fn get_fixes(d: &LintDiagnostic, source: &str) -> Vec<LintFix> {
+  match d.code.as_str() {
+    IGNORE_DIRECTIVE_CODE if d.message == UNUSED_IGNORE_MESSAGE => {
+      vec![LintFix {
+        description: "Remove the directive".to_string(),
+        range: get_unused_directive_removal_range(d, source),
+        text: "".to_string(),
+      }]
+    }
+    "no-extra-semi" => vec![LintFix {
+      description: "Remove the semicolon".to_string(),
+      range: d.range.clone(),
+      text: "".to_string(),
+    }],
+    _ => Vec::new(),
+  }
+}
+
 #[derive(Serialize)]
 struct JsonLintReporter {
-  diagnostics: Vec<LintDiagnostic>,
+  diagnostics: Vec<JsonLintDiagnostic>,
   errors: Vec<LintError>,
 }
 
@@ -491,8 +1009,12 @@ impl JsonLintReporter {
 }
 
 impl LintReporter for JsonLintReporter {
-  fn visit_diagnostic(&mut self, d: &LintDiagnostic, _source_lines: Vec<&str>) {
-    self.diagnostics.push(d.clone());
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic, source: &str) {
+    self.diagnostics.push(JsonLintDiagnostic {
+      diagnostic: d.clone(),
+      doc_url: get_rule_docs_url(&d.code),
+      fixes: get_fixes(d, source),
+    });
   }
 
   fn visit_error(&mut self, file_path: &str, err: &AnyError) {
@@ -503,7 +1025,9 @@ impl LintReporter for JsonLintReporter {
   }
 
   fn close(&mut self, _check_count: usize) {
-    sort_diagnostics(&mut self.diagnostics);
+    self
+      .diagnostics
+      .sort_by(|a, b| compare_diagnostics(&a.diagnostic, &b.diagnostic));
     let json = serde_json::to_string_pretty(&self);
     println!("{}", json.unwrap());
   }
@@ -511,23 +1035,27 @@ impl LintReporter for JsonLintReporter {
 
 fn sort_diagnostics(diagnostics: &mut [LintDiagnostic]) {
   // Sort so that we guarantee a deterministic output which is useful for tests
-  diagnostics.sort_by(|a, b| {
-    use std::cmp::Ordering;
-    let file_order = a.filename.cmp(&b.filename);
-    match file_order {
-      Ordering::Equal => {
-        let line_order =
-          a.range.start.line_index.cmp(&b.range.start.line_index);
-        match line_order {
-          Ordering::Equal => {
-            a.range.start.column_index.cmp(&b.range.start.column_index)
-          }
-          _ => line_order,
+  diagnostics.sort_by(compare_diagnostics);
+}
+
+fn compare_diagnostics(
+  a: &LintDiagnostic,
+  b: &LintDiagnostic,
+) -> std::cmp::Ordering {
+  use std::cmp::Ordering;
+  let file_order = a.filename.cmp(&b.filename);
+  match file_order {
+    Ordering::Equal => {
+      let line_order = a.range.start.line_index.cmp(&b.range.start.line_index);
+      match line_order {
+        Ordering::Equal => {
+          a.range.start.column_index.cmp(&b.range.start.column_index)
         }
+        _ => line_order,
       }
-      _ => file_order,
     }
-  });
+    _ => file_order,
+  }
 }
 
 pub fn get_configured_rules(rules: LintRulesConfig) -> Vec<Arc<dyn LintRule>> {
@@ -549,6 +1077,7 @@ mod test {
 
   use super::*;
   use crate::args::LintRulesConfig;
+  use std::collections::HashMap;
 
   #[test]
   fn recommended_rules_when_no_tags_in_config() {
@@ -556,6 +1085,7 @@ mod test {
       exclude: Some(vec!["no-debugger".to_string()]),
       include: None,
       tags: None,
+      options: Default::default(),
     };
     let rules = get_configured_rules(rules_config);
     let mut rule_names = rules
@@ -571,4 +1101,139 @@ mod test {
     recommended_rule_names.sort();
     assert_eq!(rule_names, recommended_rule_names);
   }
+
+  #[test]
+  fn rule_sources() {
+    let rules_config = LintRulesConfig {
+      exclude: Some(vec!["no-debugger".to_string()]),
+      include: Some(vec!["ban-untagged-todo".to_string()]),
+      tags: None,
+      options: Default::default(),
+    };
+    let rule_sources = resolve_rule_sources(&rules_config)
+      .into_iter()
+      .map(|(rule, source)| (rule.code(), source))
+      .collect::<HashMap<_, _>>();
+    assert_eq!(
+      rule_sources.get("ban-untagged-todo"),
+      Some(&RuleSource::Include)
+    );
+    assert_eq!(rule_sources.get("no-debugger"), Some(&RuleSource::Exclude));
+    assert_eq!(rule_sources.get("no-empty"), Some(&RuleSource::Default));
+    let active_rules = rule_sources
+      .iter()
+      .filter(|(_, source)| **source != RuleSource::Exclude)
+      .count();
+    assert_eq!(active_rules, get_configured_rules(rules_config).len());
+
+    let rules_config = LintRulesConfig {
+      exclude: None,
+      include: None,
+      tags: Some(vec![]),
+      options: Default::default(),
+    };
+    assert!(resolve_rule_sources(&rules_config).is_empty());
+  }
+
+  #[test]
+  fn cli_linter_applies_rule_options_to_parsed_sources() {
+    let linter = CliLinter::new(&LintOptions {
+      rules: LintRulesConfig {
+        exclude: None,
+        include: None,
+        tags: None,
+        options: [(
+          "no-unused-vars".to_string(),
+          serde_json::json!({ "varsIgnorePattern": "^_" }),
+        )]
+        .into_iter()
+        .collect(),
+      },
+      files: Default::default(),
+      is_stdin: false,
+      reporter_kind: LintReporterKind::Pretty,
+      verbose: false,
+      report_unused_or_bare_ignores: false,
+    })
+    .unwrap();
+    let source = "const _a = 1;\nconst b = 2;\n";
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: "file:///a.ts".to_string(),
+      text_info: deno_ast::SourceTextInfo::new(source.into()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: true,
+      scope_analysis: true,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    let messages = |diagnostics: Vec<LintDiagnostic>| {
+      diagnostics
+        .into_iter()
+        .map(|d| d.message)
+        .collect::<Vec<_>>()
+    };
+    let (diagnostics, _) = linter
+      .lint_source(
+        "file:///a.ts".to_string(),
+        MediaType::TypeScript,
+        source.to_string(),
+      )
+      .unwrap();
+    assert_eq!(messages(diagnostics), vec!["`b` is never used"]);
+    assert_eq!(
+      messages(linter.lint_parsed_source(&parsed_source)),
+      vec!["`b` is never used"]
+    );
+  }
+
+  fn lint_source(source: &str) -> Vec<LintDiagnostic> {
+    let linter =
+      create_linter(MediaType::JavaScript, rules::get_recommended_rules());
+    let (_, diagnostics) = linter
+      .lint("file:///a.js".to_string(), source.to_string())
+      .unwrap();
+    diagnostics
+  }
+
+  fn apply_fixes(source: &str, fixes: &[LintFix]) -> String {
+    let mut text = source.to_string();
+    for fix in fixes.iter().rev() {
+      text.replace_range(
+        fix.range.start.byte_index..fix.range.end.byte_index,
+        &fix.text,
+      );
+    }
+    text
+  }
+
+  #[test]
+  fn fixes_for_rules() {
+    let cases = [
+      ("no-extra-semi", "console.log(1);;\n", "console.log(1);\n"),
+      (
+        "no-extra-semi",
+        "let a = 1;\n;[a].forEach(console.log);\n",
+        "let a = 1;\n[a].forEach(console.log);\n",
+      ),
+    ];
+    for (code, source, expected) in cases {
+      let diagnostics = lint_source(source);
+      let d = diagnostics.iter().find(|d| d.code == code).unwrap();
+      let fixes = get_fixes(d, source);
+      assert_eq!(apply_fixes(source, &fixes), expected);
+    }
+  }
+
+  #[test]
+  fn no_fixes_for_other_rules() {
+    // removing the debugger statement would make `console.log` conditional
+    // and `let` isn't hoisted like `var`
+    let source =
+      "try {} catch {}\nif (f()) debugger;\nconsole.log(a);\nvar a;\n";
+    let diagnostics = lint_source(source);
+    assert!(!diagnostics.is_empty());
+    for d in diagnostics.iter() {
+      assert!(get_fixes(d, source).is_empty());
+    }
+  }
 }
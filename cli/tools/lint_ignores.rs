@@ -0,0 +1,374 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Reports the `deno-lint-ignore` directives which don't explain why the
+//! diagnostics are ignored, or which don't suppress any diagnostic, when
+//! `lint.reportUnusedOrBareIgnores` is set in the configuration file. The
+//! explanation follows `--` after the codes of the rules, like
+//! `// deno-lint-ignore no-explicit-any -- the library has no types`.
+
+use deno_ast::ParsedSource;
+use deno_ast::SourcePos;
+use deno_ast::SourceRangedForSpanned;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::diagnostic::Position;
+use deno_lint::diagnostic::Range;
+use deno_lint::rules::get_all_rules;
+use deno_lint::rules::LintRule;
+use std::sync::Arc;
+
+/// The code of the diagnostics of the directives, which isn't the one of a
+/// rule of `deno_lint`.
+pub const IGNORE_DIRECTIVE_CODE: &str = "ban-unused-ignore-reason";
+pub const UNUSED_IGNORE_MESSAGE: &str =
+  "Ignore directive doesn't suppress any diagnostic";
+const BARE_IGNORE_MESSAGE: &str = "Ignore directive has no explanation";
+
+const IGNORE_DIRECTIVE: &str = "deno-lint-ignore";
+const EXPLANATION_SEPARATOR: &str = "--";
+
+/// The rules of `deno_lint` which report the words of an explanation as codes
+/// of unknown or unused rules, as it doesn't know about explanations.
+const UNKNOWN_RULE_CODE: &str = "ban-unknown-rule-code";
+const UNUSED_RULE_CODE: &str = "ban-unused-ignore";
+
+struct IgnoreDirective {
+  range: Range,
+  codes: Vec<String>,
+  has_separator: bool,
+  explanation: Vec<String>,
+}
+
+impl IgnoreDirective {
+  fn is_used(
+    &self,
+    code: &str,
+    unsuppressed_diagnostics: &[LintDiagnostic],
+  ) -> bool {
+    let next_line_index = self.range.end.line_index + 1;
+    unsuppressed_diagnostics
+      .iter()
+      .any(|d| d.range.start.line_index == next_line_index && d.code == code)
+  }
+}
+
+fn collect_ignore_directives(
+  parsed_source: &ParsedSource,
+) -> Vec<IgnoreDirective> {
+  parsed_source
+    .comments()
+    .get_vec()
+    .iter()
+    .filter_map(|comment| {
+      let mut words = comment.text.split_whitespace();
+      if words.next() != Some(IGNORE_DIRECTIVE) {
+        return None;
+      }
+      let mut has_separator = false;
+      let codes = words
+        .by_ref()
+        .take_while(|word| {
+          has_separator = *word == EXPLANATION_SEPARATOR;
+          !has_separator
+        })
+        .map(|word| word.to_string())
+        .collect();
+      let range = comment.range();
+      Some(IgnoreDirective {
+        range: Range {
+          start: get_position(parsed_source, range.start),
+          end: get_position(parsed_source, range.end),
+        },
+        codes,
+        has_separator,
+        explanation: words.map(|word| word.to_string()).collect(),
+      })
+    })
+    .collect()
+}
+
+fn get_position(parsed_source: &ParsedSource, pos: SourcePos) -> Position {
+  let text_info = parsed_source.text_info();
+  let line_and_column = text_info.line_and_column_index(pos);
+  Position {
+    line_index: line_and_column.line_index,
+    column_index: line_and_column.column_index,
+    byte_index: pos.as_byte_index(text_info.range().start),
+  }
+}
+
+/// Whether a directive of the file has an explanation, so that
+/// `filter_explanation_diagnostics` needs the unsuppressed diagnostics.
+pub fn has_explanations(parsed_source: &ParsedSource) -> bool {
+  collect_ignore_directives(parsed_source)
+    .iter()
+    .any(|directive| directive.has_separator)
+}
+
+/// Replaces the diagnostics of `deno_lint` about the codes of the directives
+/// which have an explanation, as it takes the separator and the words of the
+/// explanation for codes, with the ones about the codes before the separator.
+/// `unsuppressed_diagnostics` are the diagnostics of the file when the
+/// directives are disregarded, which tell if a code is used.
+pub fn filter_explanation_diagnostics(
+  parsed_source: &ParsedSource,
+  diagnostics: Vec<LintDiagnostic>,
+  rules: &[Arc<dyn LintRule>],
+  unsuppressed_diagnostics: &[LintDiagnostic],
+) -> Vec<LintDiagnostic> {
+  let directives = collect_ignore_directives(parsed_source)
+    .into_iter()
+    .filter(|directive| directive.has_separator)
+    .collect::<Vec<_>>();
+  if directives.is_empty() {
+    return diagnostics;
+  }
+
+  let (directive_diagnostics, mut diagnostics): (Vec<_>, Vec<_>) =
+    diagnostics.into_iter().partition(|d| {
+      (d.code == UNKNOWN_RULE_CODE || d.code == UNUSED_RULE_CODE)
+        && directives.iter().any(|directive| {
+          directive.range.start.byte_index == d.range.start.byte_index
+        })
+    });
+  if directive_diagnostics.is_empty() {
+    return diagnostics;
+  }
+
+  let all_codes = get_all_rules()
+    .iter()
+    .map(|rule| rule.code())
+    .collect::<Vec<_>>();
+  for directive in directives.iter() {
+    let reported = |code: &str| {
+      directive_diagnostics.iter().find(|d| {
+        d.code == code
+          && d.range.start.byte_index == directive.range.start.byte_index
+      })
+    };
+    // the diagnostics are only reported again when `deno_lint` reports them,
+    // which depends on the configuration of the rules
+    if let Some(reported) = reported(UNKNOWN_RULE_CODE) {
+      for code in directive.codes.iter() {
+        if !all_codes.contains(&code.as_str()) {
+          diagnostics.push(LintDiagnostic {
+            message: format!("Unknown rule for code \"{code}\""),
+            ..reported.clone()
+          });
+        }
+      }
+    }
+    if let Some(reported) = reported(UNUSED_RULE_CODE) {
+      for code in directive.codes.iter() {
+        if rules.iter().any(|rule| rule.code() == code)
+          && !directive.is_used(code, unsuppressed_diagnostics)
+        {
+          diagnostics.push(LintDiagnostic {
+            message: format!("Ignore for code \"{code}\" was not used."),
+            ..reported.clone()
+          });
+        }
+      }
+    }
+  }
+  diagnostics
+}
+
+/// Gets the diagnostics of the directives which have no explanation or which
+/// don't suppress any of `unsuppressed_diagnostics`, the diagnostics of the
+/// file when the directives are disregarded.
+pub fn get_ignore_directive_diagnostics(
+  parsed_source: &ParsedSource,
+  filename: &str,
+  unsuppressed_diagnostics: &[LintDiagnostic],
+) -> Vec<LintDiagnostic> {
+  collect_ignore_directives(parsed_source)
+    .into_iter()
+    .filter_map(|directive| {
+      let is_used = if directive.codes.is_empty() {
+        let next_line_index = directive.range.end.line_index + 1;
+        unsuppressed_diagnostics
+          .iter()
+          .any(|d| d.range.start.line_index == next_line_index)
+      } else {
+        directive
+          .codes
+          .iter()
+          .any(|code| directive.is_used(code, unsuppressed_diagnostics))
+      };
+      let (message, hint) = if !is_used {
+        (UNUSED_IGNORE_MESSAGE, "Remove the directive")
+      } else if directive.explanation.is_empty() {
+        (
+          BARE_IGNORE_MESSAGE,
+          "Explain why the diagnostics are ignored after `--`, like `// deno-lint-ignore no-explicit-any -- the library has no types`",
+        )
+      } else {
+        return None;
+      };
+      Some(LintDiagnostic {
+        range: directive.range,
+        filename: filename.to_string(),
+        message: message.to_string(),
+        code: IGNORE_DIRECTIVE_CODE.to_string(),
+        hint: Some(hint.to_string()),
+      })
+    })
+    .collect()
+}
+
+/// Gets the range removing the unused directive of the diagnostic, which is
+/// the whole line when the directive is the only thing on it.
+pub fn get_unused_directive_removal_range(
+  d: &LintDiagnostic,
+  source: &str,
+) -> Range {
+  let start = d.range.start.byte_index;
+  let end = d.range.end.byte_index;
+  let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+  let line_end = source[end..]
+    .find('\n')
+    .map(|i| end + i + 1)
+    .unwrap_or(source.len());
+  if source[line_start..start].trim().is_empty()
+    && source[end..line_end].trim().is_empty()
+  {
+    Range {
+      start: Position {
+        line_index: d.range.start.line_index,
+        column_index: 0,
+        byte_index: line_start,
+      },
+      end: Position {
+        line_index: d.range.end.line_index + 1,
+        column_index: 0,
+        byte_index: line_end,
+      },
+    }
+  } else {
+    d.range.clone()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::tools::lint::create_linter;
+  use crate::tools::lint::create_unsuppressed_linter;
+  use deno_ast::MediaType;
+  use deno_lint::rules;
+
+  fn lint(source: &str) -> Vec<(usize, String, String)> {
+    let linter =
+      create_linter(MediaType::TypeScript, rules::get_recommended_rules());
+    let (parsed_source, diagnostics) = linter
+      .lint("file:///a.ts".to_string(), source.to_string())
+      .unwrap();
+    let unsuppressed_diagnostics = create_unsuppressed_linter(
+      MediaType::TypeScript,
+      rules::get_recommended_rules(),
+    )
+    .lint_with_ast(&parsed_source);
+    let mut diagnostics = filter_explanation_diagnostics(
+      &parsed_source,
+      diagnostics,
+      &rules::get_recommended_rules(),
+      &unsuppressed_diagnostics,
+    );
+    diagnostics.extend(get_ignore_directive_diagnostics(
+      &parsed_source,
+      "file:///a.ts",
+      &unsuppressed_diagnostics,
+    ));
+    let mut diagnostics = diagnostics
+      .into_iter()
+      .map(|d| (d.range.start.line_index, d.code, d.message))
+      .collect::<Vec<_>>();
+    diagnostics.sort();
+    diagnostics
+  }
+
+  #[test]
+  fn reports_bare_and_unused_ignores() {
+    let source = concat!(
+      "// deno-lint-ignore no-explicit-any -- the library has no types\n",
+      "export const a: any = 1;\n",
+      "// deno-lint-ignore no-explicit-any\n",
+      "export const b: any = 1;\n",
+      "// deno-lint-ignore no-explicit-any -- nothing to ignore\n",
+      "export const c = 1;\n",
+    );
+    let diagnostics = lint(source);
+    // the words of the explanations aren't taken for codes
+    assert!(!diagnostics
+      .iter()
+      .any(|(line_index, _, _)| *line_index == 0));
+    assert_eq!(
+      diagnostics
+        .into_iter()
+        .filter(|(_, code, _)| code == IGNORE_DIRECTIVE_CODE)
+        .map(|(line_index, _, message)| (line_index, message))
+        .collect::<Vec<_>>(),
+      vec![
+        (2, BARE_IGNORE_MESSAGE.to_string()),
+        (4, UNUSED_IGNORE_MESSAGE.to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_the_codes_of_explained_ignores() {
+    let source = concat!(
+      "// deno-lint-ignore no-explicit-any no-such-rule -- no-var is fine\n",
+      "export const a: any = 1;\n",
+      "// deno-lint-ignore no-var -- kept for no-explicit-any\n",
+      "export const b = 1;\n",
+    );
+    assert_eq!(
+      lint(source)
+        .into_iter()
+        .filter(|(_, code, _)| code != IGNORE_DIRECTIVE_CODE)
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          0,
+          "ban-unknown-rule-code".to_string(),
+          "Unknown rule for code \"no-such-rule\"".to_string(),
+        ),
+        (
+          2,
+          "ban-unused-ignore".to_string(),
+          "Ignore for code \"no-var\" was not used.".to_string(),
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn removes_unused_directive_lines() {
+    let source = "const a = 1;\n  // deno-lint-ignore no-var\nlet b = a;\n";
+    let start = source.find("//").unwrap();
+    let end = source.find("\nlet").unwrap();
+    let d = LintDiagnostic {
+      range: Range {
+        start: Position {
+          line_index: 1,
+          column_index: 2,
+          byte_index: start,
+        },
+        end: Position {
+          line_index: 1,
+          column_index: end - start + 2,
+          byte_index: end,
+        },
+      },
+      filename: "file:///a.ts".to_string(),
+      message: UNUSED_IGNORE_MESSAGE.to_string(),
+      code: IGNORE_DIRECTIVE_CODE.to_string(),
+      hint: None,
+    };
+    let range = get_unused_directive_removal_range(&d, source);
+    let mut text = source.to_string();
+    text.replace_range(range.start.byte_index..range.end.byte_index, "");
+    assert_eq!(text, "const a = 1;\nlet b = a;\n");
+  }
+}
@@ -0,0 +1,408 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! The options of lint rules, set in the configuration file with an object
+//! under the code of the rule in `lint.rules`, like
+//! `"no-unused-vars": { "argsIgnorePattern": "^_" }`. The rules of
+//! `deno_lint` don't take options, so the options leave out some of the
+//! diagnostics of their rule.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::noop_visit_type;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRangedForSpanned;
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use deno_lint::diagnostic::LintDiagnostic;
+use deno_lint::rules;
+use regex::Regex;
+
+use crate::colors;
+
+struct RuleOption {
+  rule: &'static str,
+  name: &'static str,
+  description: &'static str,
+}
+
+/// The options of the rules, which are all regular expressions.
+const RULE_OPTIONS: &[RuleOption] = &[
+  RuleOption {
+    rule: "no-unused-vars",
+    name: "argsIgnorePattern",
+    description:
+      "A regular expression matching the names of the unused function parameters which aren't reported.",
+  },
+  RuleOption {
+    rule: "no-unused-vars",
+    name: "varsIgnorePattern",
+    description:
+      "A regular expression matching the names of the unused variables, other than function parameters, which aren't reported.",
+  },
+];
+
+/// Gets the JSON schema of the options the rule accepts.
+pub fn get_rule_options_schema(code: &str) -> Value {
+  let properties = RULE_OPTIONS
+    .iter()
+    .filter(|option| option.rule == code)
+    .map(|option| {
+      (
+        option.name.to_string(),
+        json!({
+          "type": "string",
+          "format": "regex",
+          "description": option.description,
+        }),
+      )
+    })
+    .collect::<serde_json::Map<_, _>>();
+  json!({
+    "type": "object",
+    "properties": properties,
+    "additionalProperties": false,
+  })
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LintRuleOptions {
+  /// The options which were resolved, keyed by the code of their rule.
+  config: BTreeMap<String, BTreeMap<String, String>>,
+  unused_args_ignore_pattern: Option<Regex>,
+  unused_vars_ignore_pattern: Option<Regex>,
+}
+
+impl LintRuleOptions {
+  /// Resolves the options of the rules in the configuration. The options of
+  /// unknown rules and the unknown options of a rule are only warned about,
+  /// as they may be meant for another version of Deno.
+  pub fn resolve(options: &BTreeMap<String, Value>) -> Result<Self, AnyError> {
+    let all_rules = rules::get_all_rules();
+    let mut resolved = Self::default();
+    for (code, value) in options {
+      if !all_rules.iter().any(|rule| rule.code() == code) {
+        warn(&format!(
+          "Unknown lint rule \"{code}\" in the lint configuration, its options are ignored."
+        ));
+        continue;
+      }
+      let rule_options = value.as_object().ok_or_else(|| {
+        anyhow!("The options of the lint rule \"{code}\" must be an object")
+      })?;
+      for (name, value) in rule_options {
+        if !RULE_OPTIONS
+          .iter()
+          .any(|option| option.rule == code && option.name == name)
+        {
+          warn(&format!(
+            "Unknown option \"{name}\" of the lint rule \"{code}\", it is ignored."
+          ));
+          continue;
+        }
+        let pattern = value.as_str().ok_or_else(|| {
+          anyhow!(
+            "The option \"{name}\" of the lint rule \"{code}\" must be a string"
+          )
+        })?;
+        let regex = Regex::new(pattern).map_err(|err| {
+          anyhow!(
+            "Invalid regular expression in the option \"{name}\" of the lint rule \"{code}\": {err}"
+          )
+        })?;
+        match (code.as_str(), name.as_str()) {
+          ("no-unused-vars", "argsIgnorePattern") => {
+            resolved.unused_args_ignore_pattern = Some(regex);
+          }
+          ("no-unused-vars", "varsIgnorePattern") => {
+            resolved.unused_vars_ignore_pattern = Some(regex);
+          }
+          _ => unreachable!(),
+        }
+        resolved
+          .config
+          .entry(code.clone())
+          .or_default()
+          .insert(name.clone(), pattern.to_string());
+      }
+    }
+    Ok(resolved)
+  }
+
+  /// Gets the options which were resolved, to bust the incremental cache
+  /// when they change.
+  pub fn config(&self) -> &BTreeMap<String, BTreeMap<String, String>> {
+    &self.config
+  }
+
+  /// Removes the diagnostics which the options of their rule leave out.
+  pub fn filter_diagnostics(
+    &self,
+    parsed_source: &ParsedSource,
+    diagnostics: Vec<LintDiagnostic>,
+  ) -> Vec<LintDiagnostic> {
+    if self.unused_args_ignore_pattern.is_none()
+      && self.unused_vars_ignore_pattern.is_none()
+    {
+      return diagnostics;
+    }
+    let bindings = collect_bindings(parsed_source);
+    diagnostics
+      .into_iter()
+      .filter(|d| !self.is_ignored(d, &bindings))
+      .collect()
+  }
+
+  fn is_ignored(
+    &self,
+    d: &LintDiagnostic,
+    bindings: &HashMap<usize, Binding>,
+  ) -> bool {
+    if d.code != "no-unused-vars" {
+      return false;
+    }
+    // the diagnostic is reported on the identifier of the binding
+    let binding = match bindings.get(&d.range.start.byte_index) {
+      Some(binding) => binding,
+      None => return false,
+    };
+    let maybe_pattern = if binding.is_param {
+      &self.unused_args_ignore_pattern
+    } else {
+      &self.unused_vars_ignore_pattern
+    };
+    maybe_pattern
+      .as_ref()
+      .map(|pattern| pattern.is_match(&binding.name))
+      .unwrap_or(false)
+  }
+}
+
+fn warn(message: &str) {
+  log::warn!("{} {}", colors::yellow("Warning"), message);
+}
+
+struct Binding {
+  name: String,
+  /// Whether the binding is a function parameter, which `argsIgnorePattern`
+  /// applies to instead of `varsIgnorePattern`.
+  is_param: bool,
+}
+
+/// Collects the bindings declared in a module, keyed by the byte index of
+/// their identifier.
+fn collect_bindings(parsed_source: &ParsedSource) -> HashMap<usize, Binding> {
+  let mut collector = BindingCollector {
+    parsed_source,
+    bindings: HashMap::new(),
+  };
+  parsed_source.program_ref().visit_with(&mut collector);
+  collector.bindings
+}
+
+struct BindingCollector<'a> {
+  parsed_source: &'a ParsedSource,
+  bindings: HashMap<usize, Binding>,
+}
+
+impl BindingCollector<'_> {
+  fn add_ident(&mut self, ident: &ast::Ident, is_param: bool) {
+    let text_info = self.parsed_source.text_info();
+    let byte_index = ident.range().start.as_byte_index(text_info.range().start);
+    // the parameters are added before their children are visited
+    self.bindings.entry(byte_index).or_insert_with(|| Binding {
+      name: ident.sym.to_string(),
+      is_param,
+    });
+  }
+
+  fn add_param(&mut self, pat: &ast::Pat) {
+    match pat {
+      ast::Pat::Ident(binding) => self.add_ident(&binding.id, true),
+      ast::Pat::Array(array) => {
+        for elem in array.elems.iter().flatten() {
+          self.add_param(elem);
+        }
+      }
+      ast::Pat::Rest(rest) => self.add_param(&rest.arg),
+      ast::Pat::Object(object) => {
+        for prop in &object.props {
+          match prop {
+            ast::ObjectPatProp::KeyValue(key_value) => {
+              self.add_param(&key_value.value)
+            }
+            ast::ObjectPatProp::Assign(assign) => {
+              self.add_ident(&assign.key, true)
+            }
+            ast::ObjectPatProp::Rest(rest) => self.add_param(&rest.arg),
+          }
+        }
+      }
+      ast::Pat::Assign(assign) => self.add_param(&assign.left),
+      ast::Pat::Invalid(_) | ast::Pat::Expr(_) => {}
+    }
+  }
+}
+
+impl Visit for BindingCollector<'_> {
+  noop_visit_type!();
+
+  fn visit_param(&mut self, param: &ast::Param) {
+    self.add_param(&param.pat);
+    param.visit_children_with(self);
+  }
+
+  fn visit_arrow_expr(&mut self, arrow: &ast::ArrowExpr) {
+    for pat in &arrow.params {
+      self.add_param(pat);
+    }
+    arrow.visit_children_with(self);
+  }
+
+  fn visit_binding_ident(&mut self, binding: &ast::BindingIdent) {
+    self.add_ident(&binding.id, false);
+  }
+
+  fn visit_assign_pat_prop(&mut self, prop: &ast::AssignPatProp) {
+    self.add_ident(&prop.key, false);
+    prop.visit_children_with(self);
+  }
+
+  fn visit_fn_decl(&mut self, decl: &ast::FnDecl) {
+    self.add_ident(&decl.ident, false);
+    decl.visit_children_with(self);
+  }
+
+  fn visit_class_decl(&mut self, decl: &ast::ClassDecl) {
+    self.add_ident(&decl.ident, false);
+    decl.visit_children_with(self);
+  }
+
+  fn visit_import_named_specifier(
+    &mut self,
+    specifier: &ast::ImportNamedSpecifier,
+  ) {
+    self.add_ident(&specifier.local, false);
+  }
+
+  fn visit_import_default_specifier(
+    &mut self,
+    specifier: &ast::ImportDefaultSpecifier,
+  ) {
+    self.add_ident(&specifier.local, false);
+  }
+
+  fn visit_import_star_as_specifier(
+    &mut self,
+    specifier: &ast::ImportStarAsSpecifier,
+  ) {
+    self.add_ident(&specifier.local, false);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::tools::lint::create_linter;
+  use deno_ast::MediaType;
+
+  fn lint(source: &str, options: Value) -> Vec<String> {
+    let options = serde_json::from_value(options).unwrap();
+    let rule_options = LintRuleOptions::resolve(&options).unwrap();
+    let linter =
+      create_linter(MediaType::TypeScript, rules::get_recommended_rules());
+    let (parsed_source, diagnostics) = linter
+      .lint("file:///a.ts".to_string(), source.to_string())
+      .unwrap();
+    let mut messages = rule_options
+      .filter_diagnostics(&parsed_source, diagnostics)
+      .into_iter()
+      .map(|d| d.message)
+      .collect::<Vec<_>>();
+    messages.sort();
+    messages
+  }
+
+  #[test]
+  fn ignores_unused_vars_matching_patterns() {
+    let source = "export function f(ignoredArg: number, arg: number) {\n  const ignoredVar = 1;\n  const other = 2;\n}\n";
+    assert_eq!(lint(source, json!({})).len(), 4);
+    assert_eq!(
+      lint(
+        source,
+        json!({
+          "no-unused-vars": { "argsIgnorePattern": "^ignored" },
+        })
+      ),
+      vec![
+        "`arg` is never used",
+        "`ignoredVar` is never used",
+        "`other` is never used",
+      ]
+    );
+    assert_eq!(
+      lint(
+        source,
+        json!({
+          "no-unused-vars": {
+            "argsIgnorePattern": "^ignored",
+            "varsIgnorePattern": "^ignored",
+          },
+        })
+      ),
+      vec!["`arg` is never used", "`other` is never used"]
+    );
+  }
+
+  #[test]
+  fn tells_params_from_vars_by_binding() {
+    // the parameter and the variable have the same name on the same line
+    let source =
+      "export function f(ignored: number) { { const ignored = 1; } }\n";
+    assert_eq!(
+      lint(
+        source,
+        json!({
+          "no-unused-vars": { "argsIgnorePattern": "^ignored" },
+        })
+      ),
+      vec!["`ignored` is never used"]
+    );
+  }
+
+  #[test]
+  fn resolves_options() {
+    let options = serde_json::from_value(json!({
+      "no-unused-vars": { "argsIgnorePattern": "^_", "unknownOption": 1 },
+      "unknown-rule": { "max": 3 },
+    }))
+    .unwrap();
+    let rule_options = LintRuleOptions::resolve(&options).unwrap();
+    assert_eq!(
+      serde_json::to_value(rule_options.config()).unwrap(),
+      json!({ "no-unused-vars": { "argsIgnorePattern": "^_" } })
+    );
+
+    let options = serde_json::from_value(json!({
+      "no-unused-vars": { "argsIgnorePattern": "(" },
+    }))
+    .unwrap();
+    assert!(LintRuleOptions::resolve(&options).is_err());
+  }
+
+  #[test]
+  fn rule_options_schema() {
+    let schema = get_rule_options_schema("no-unused-vars");
+    assert_eq!(schema["properties"]["argsIgnorePattern"]["type"], "string");
+    assert_eq!(
+      get_rule_options_schema("no-debugger")["properties"],
+      json!({})
+    );
+  }
+}
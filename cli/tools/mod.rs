@@ -1,19 +1,31 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 pub mod bench;
+pub mod bench_baseline;
 pub mod bundle;
+pub mod cache;
+pub mod cache_bundle;
 pub mod check;
+pub mod ci;
+pub mod compile_checks;
 pub mod coverage;
+pub mod deps;
 pub mod doc;
+pub mod emit_dir;
 pub mod fmt;
+pub mod fmt_yaml;
 pub mod info;
 pub mod init;
 pub mod installer;
 pub mod lint;
+pub mod lint_ignores;
+pub mod lint_rule_options;
 pub mod repl;
 pub mod run;
 pub mod standalone;
+pub mod storage;
 pub mod task;
 pub mod test;
+pub mod test_affected;
 pub mod upgrade;
 pub mod vendor;
@@ -4,6 +4,7 @@ use crate::args::Flags;
 use crate::args::ReplFlags;
 use crate::colors;
 use crate::proc_state::ProcState;
+use crate::util::draw_thread::DrawThread;
 use crate::worker::create_main_worker;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
@@ -145,12 +146,16 @@ pub async fn run(flags: Flags, repl_flags: ReplFlags) -> Result<i32, AnyError> {
   }
 
   loop {
+    // progress bars of modules downloaded while the prompt is shown would be
+    // drawn over it, so they're only shown while a line is evaluated
+    DrawThread::hide();
     let line = read_line_and_poll(
       &mut repl_session,
       &mut rustyline_channel.1,
       editor.clone(),
     )
     .await;
+    DrawThread::show();
     match line {
       Ok(line) => {
         editor.set_should_exit_on_interrupt(false);
@@ -3,6 +3,7 @@
 use crate::colors;
 use crate::lsp::ReplLanguageServer;
 use crate::npm::NpmPackageReference;
+use crate::npm::NpmPackageReq;
 use crate::ProcState;
 use deno_ast::swc::ast as swc_ast;
 use deno_ast::swc::visit::noop_visit_type;
@@ -11,6 +12,8 @@ use deno_ast::swc::visit::VisitWith;
 use deno_ast::DiagnosticsError;
 use deno_ast::ImportsNotUsedAsValues;
 use deno_ast::ModuleSpecifier;
+use deno_ast::ParsedSource;
+use deno_ast::SourceRangedForSpanned;
 use deno_core::error::AnyError;
 use deno_core::futures::channel::mpsc::UnboundedReceiver;
 use deno_core::futures::FutureExt;
@@ -21,6 +24,7 @@ use deno_core::LocalInspectorSession;
 use deno_graph::source::Resolver;
 use deno_runtime::deno_node;
 use deno_runtime::worker::MainWorker;
+use std::collections::HashSet;
 
 use super::cdp;
 
@@ -94,6 +98,8 @@ pub struct ReplSession {
   pub context_id: u64,
   pub language_server: ReplLanguageServer,
   has_initialized_node_runtime: bool,
+  /// The npm packages imported by the session, which are only resolved once.
+  npm_package_reqs: HashSet<NpmPackageReq>,
   referrer: ModuleSpecifier,
   // FIXME(bartlomieju): this field should be used to listen
   // for "exceptionThrown" notifications
@@ -151,6 +157,7 @@ impl ReplSession {
       context_id,
       language_server,
       has_initialized_node_runtime: false,
+      npm_package_reqs: HashSet::new(),
       referrer,
       notification_rx,
     };
@@ -390,14 +397,10 @@ impl ReplSession {
     &mut self,
     expression: &str,
   ) -> Result<TsEvaluateResponse, AnyError> {
-    let parsed_module = deno_ast::parse_module(deno_ast::ParseParams {
-      specifier: "repl.ts".to_string(),
-      text_info: deno_ast::SourceTextInfo::from_string(expression.to_string()),
-      media_type: deno_ast::MediaType::TypeScript,
-      capture_tokens: false,
-      maybe_syntax: None,
-      scope_analysis: false,
-    })?;
+    let mut parsed_module = parse_repl_source(expression.to_string())?;
+    if let Some(hoisted_text) = hoist_imports(&parsed_module) {
+      parsed_module = parse_repl_source(hoisted_text)?;
+    }
 
     self
       .check_for_npm_or_node_imports(&parsed_module.program())
@@ -456,6 +459,11 @@ impl ReplSession {
       .flat_map(|url| NpmPackageReference::from_specifier(url).ok())
       .map(|r| r.req)
       .collect::<Vec<_>>();
+    let new_npm_imports = npm_imports
+      .iter()
+      .filter(|req| !self.npm_package_reqs.contains(req))
+      .cloned()
+      .collect::<Vec<_>>();
     let has_node_specifier =
       resolved_imports.iter().any(|url| url.scheme() == "node");
     if !npm_imports.is_empty() || has_node_specifier {
@@ -473,8 +481,11 @@ impl ReplSession {
       self
         .proc_state
         .npm_resolver
-        .add_package_reqs(npm_imports)
+        .add_package_reqs(new_npm_imports.clone())
         .await?;
+      // only remembered once they're resolved and cached, so that a failed
+      // import is retried by the next line importing the package
+      self.npm_package_reqs.extend(new_npm_imports);
 
       // prevent messages in the repl about @types/node not being cached
       if has_node_specifier {
@@ -518,6 +529,66 @@ impl ReplSession {
   }
 }
 
+fn parse_repl_source(text: String) -> Result<ParsedSource, AnyError> {
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier: "repl.ts".to_string(),
+    text_info: deno_ast::SourceTextInfo::from_string(text),
+    media_type: deno_ast::MediaType::TypeScript,
+    capture_tokens: false,
+    maybe_syntax: None,
+    scope_analysis: false,
+  })?;
+  Ok(parsed_source)
+}
+
+/// Moves the top level import declarations of the source before its other
+/// statements, or returns `None` when they already are. Imports are hoisted
+/// in a module, but the REPL transpiles them to variable declarations, which
+/// would otherwise only be initialized once the statements before them ran.
+fn hoist_imports(parsed_source: &ParsedSource) -> Option<String> {
+  let program = parsed_source.program();
+  let module = match &*program {
+    swc_ast::Program::Module(module) => module,
+    swc_ast::Program::Script(_) => return None,
+  };
+  let text_info = parsed_source.text_info();
+  let source_start = text_info.range().start;
+  let mut import_ranges = Vec::new();
+  let mut has_statement = false;
+  let mut needs_hoisting = false;
+  for item in &module.body {
+    match item {
+      swc_ast::ModuleItem::ModuleDecl(swc_ast::ModuleDecl::Import(
+        import_decl,
+      )) => {
+        needs_hoisting |= has_statement;
+        let range = import_decl.range();
+        import_ranges.push(
+          range.start.as_byte_index(source_start)
+            ..range.end.as_byte_index(source_start),
+        );
+      }
+      _ => has_statement = true,
+    }
+  }
+  if !needs_hoisting {
+    return None;
+  }
+
+  let text = text_info.text_str();
+  let mut imports = String::new();
+  let mut statements = String::new();
+  let mut last_end = 0;
+  for range in import_ranges {
+    imports.push_str(&text[range.clone()]);
+    imports.push('\n');
+    statements.push_str(&text[last_end..range.start]);
+    last_end = range.end;
+  }
+  statements.push_str(&text[last_end..]);
+  Some(format!("{imports}{statements}"))
+}
+
 /// Walk an AST and get all import specifiers for analysis if any of them is
 /// an npm specifier.
 struct ImportCollector {
@@ -569,3 +640,24 @@ impl Visit for ImportCollector {
     }
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn hoist(text: &str) -> Option<String> {
+    hoist_imports(&parse_repl_source(text.to_string()).unwrap())
+  }
+
+  #[test]
+  fn hoists_imports() {
+    assert_eq!(
+      hoist("printHello(); import { printHello } from './mod.ts'; 1"),
+      Some(
+        "import { printHello } from './mod.ts';\nprintHello();  1".to_string()
+      )
+    );
+    assert_eq!(hoist("import a from 'a'; import b from 'b'; a + b"), None);
+    assert_eq!(hoist("const a = await import('a');"), None);
+  }
+}
@@ -1,6 +1,7 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use std::io::Read;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use deno_ast::MediaType;
@@ -10,6 +11,7 @@ use deno_core::resolve_url_or_path;
 use deno_runtime::permissions::Permissions;
 use deno_runtime::permissions::PermissionsContainer;
 
+use crate::args::resolve_entrypoint;
 use crate::args::EvalFlags;
 use crate::args::Flags;
 use crate::args::RunFlags;
@@ -42,6 +44,7 @@ To grant permissions, set them before the script argument. For example:
   // map specified and bare specifier is used on the command line - this should
   // probably call `ProcState::resolve` instead
   let ps = ProcState::build(flags).await?;
+  ps.options.log_permissions_config();
 
   // Run a background task that checks for available upgrades. If an earlier
   // run of this background task found a new version of Deno.
@@ -54,7 +57,7 @@ To grant permissions, set them before the script argument. For example:
   {
     ModuleSpecifier::parse(&run_flags.script)?
   } else {
-    resolve_url_or_path(&run_flags.script)?
+    resolve_entrypoint(&run_flags.script)?
   };
   let permissions = PermissionsContainer::new(Permissions::from_options(
     &ps.options.permissions_options(),
@@ -67,6 +70,7 @@ To grant permissions, set them before the script argument. For example:
 
 pub async fn run_from_stdin(flags: Flags) -> Result<i32, AnyError> {
   let ps = ProcState::build(flags).await?;
+  ps.options.log_permissions_config();
   let main_module = resolve_url_or_path("./$deno$stdin.ts").unwrap();
   let mut worker = create_main_worker(
     &ps,
@@ -100,13 +104,15 @@ pub async fn run_from_stdin(flags: Flags) -> Result<i32, AnyError> {
 // code properly.
 async fn run_with_watch(flags: Flags, script: String) -> Result<i32, AnyError> {
   let flags = Arc::new(flags);
-  let main_module = resolve_url_or_path(&script)?;
+  let main_module = resolve_entrypoint(&script)?;
   let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
   let mut ps =
     ProcState::build_for_file_watcher((*flags).clone(), sender.clone()).await?;
+  ps.options.log_permissions_config();
 
-  let operation = |main_module: ModuleSpecifier| {
-    ps.reset_for_file_watcher();
+  let operation = |main_module: ModuleSpecifier,
+                   changed_paths: Option<Vec<PathBuf>>| {
+    ps.reset_for_file_watcher(changed_paths.as_deref());
     let ps = ps.clone();
     Ok(async move {
       let permissions = PermissionsContainer::new(Permissions::from_options(
@@ -126,6 +132,7 @@ async fn run_with_watch(flags: Flags, script: String) -> Result<i32, AnyError> {
     util::file_watcher::PrintConfig {
       job_name: "Process".to_string(),
       clear_screen: !flags.no_clear_screen,
+      stack_trace_options: flags.stack_trace_options(),
     },
   )
   .await?;
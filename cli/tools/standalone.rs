@@ -9,6 +9,7 @@ use crate::graph_util::error_for_any_npm_specifier;
 use crate::http_util::HttpClient;
 use crate::standalone::Metadata;
 use crate::standalone::MAGIC_TRAILER;
+use crate::util::display;
 use crate::util::path::path_has_trailing_slash;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
@@ -32,29 +33,79 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use super::compile_checks::check_graph;
+use super::compile_checks::check_graph_for_strip;
 use super::installer::infer_name_from_url;
 
+/// The zstd level used for the embedded program with `--strip`. It's slow to
+/// compress, but decompression speed doesn't depend on the level.
+const STRIP_COMPRESSION_LEVEL: i32 = 19;
+
+/// The environment variable with the path of the runtime embedded by
+/// `--strip` when compiling for the current target, instead of downloading
+/// it. This is the `deno` executable built without the `tsc`, `lsp` and
+/// `webgpu` features.
+const STRIPPED_BASE_BINARY_ENV_VAR: &str = "DENORT_BIN";
+
 pub async fn compile(
   flags: Flags,
   compile_flags: CompileFlags,
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(flags).await?;
+  ps.options.log_permissions_config();
   let module_specifier = resolve_url_or_path(&compile_flags.source_file)?;
   let deno_dir = &ps.dir;
 
   let output_path =
     resolve_compile_executable_output_path(&compile_flags).await?;
 
-  let graph = Arc::try_unwrap(
-    create_graph_and_maybe_check(module_specifier.clone(), &ps).await?,
-  )
-  .unwrap();
+  // preloaded modules are embedded along with the main module
+  let preload_modules = ps.resolve_preload_modules()?;
+  let mut roots = vec![module_specifier.clone()];
+  roots.extend(preload_modules.iter().cloned());
+  let graph =
+    Arc::try_unwrap(create_graph_and_maybe_check(roots, &ps).await?).unwrap();
 
   // at the moment, we don't support npm specifiers in deno_compile, so show an error
   error_for_any_npm_specifier(&graph)?;
 
+  let warnings = check_graph(&graph, &ps.parsed_source_cache)?;
+  for warning in &warnings {
+    log::warn!("{} {}", colors::yellow("Warning"), warning);
+  }
+  if compile_flags.deny_warnings && !warnings.is_empty() {
+    bail!(
+      "Found {} use{} of code that doesn't work in compiled executables.",
+      warnings.len(),
+      if warnings.len() == 1 { "" } else { "s" }
+    );
+  }
+  // the capabilities left out by --strip are only exposed with --unstable
+  if compile_flags.strip && ps.options.unstable() {
+    let uses = check_graph_for_strip(&graph, &ps.parsed_source_cache)?;
+    if !uses.is_empty() {
+      bail!(
+        "The program uses capabilities which aren't included with --strip:{}",
+        uses
+          .iter()
+          .map(|use_| format!("\n  {use_}"))
+          .collect::<String>()
+      );
+    }
+  }
+
   let parser = ps.parsed_source_cache.as_capturing_parser();
-  let eszip = eszip::EszipV2::from_graph(graph, &parser, Default::default())?;
+  let emit_options = if compile_flags.strip {
+    // without source maps, stack traces point at the transpiled code
+    deno_ast::EmitOptions {
+      inline_source_map: false,
+      inline_sources: false,
+      ..Default::default()
+    }
+  } else {
+    Default::default()
+  };
+  let eszip = eszip::EszipV2::from_graph(graph, &parser, emit_options)?;
 
   log::info!(
     "{} {}",
@@ -63,37 +114,107 @@ pub async fn compile(
   );
 
   // Select base binary based on target
-  let original_binary =
-    get_base_binary(&ps.http_client, deno_dir, compile_flags.target.clone())
-      .await?;
+  let original_binary = get_base_binary(
+    &ps.http_client,
+    deno_dir,
+    compile_flags.target.clone(),
+    compile_flags.strip,
+  )
+  .await?;
+  let maybe_unstripped_base_binary_len = if compile_flags.strip {
+    Some(
+      get_unstripped_base_binary_len(
+        &ps.http_client,
+        deno_dir,
+        compile_flags.target.clone(),
+      )
+      .await?,
+    )
+  } else {
+    None
+  };
 
-  let final_bin = create_standalone_binary(
+  let standalone_binary = create_standalone_binary(
     original_binary,
     eszip,
     module_specifier,
+    preload_modules,
     &compile_flags,
     ps,
   )
   .await?;
 
+  if let Some(unstripped_base_binary_len) = maybe_unstripped_base_binary_len {
+    // without --strip, the full runtime embeds the uncompressed program
+    let unstripped_len =
+      unstripped_base_binary_len + standalone_binary.program_len;
+    log::info!(
+      "{} executable from {} to {}",
+      colors::green("Strip"),
+      display::human_size(unstripped_len as f64),
+      display::human_size(standalone_binary.bytes.len() as f64),
+    );
+  }
+
   log::info!("{} {}", colors::green("Emit"), output_path.display());
 
-  write_standalone_binary(output_path, final_bin).await?;
+  write_standalone_binary(output_path, standalone_binary.bytes).await?;
   Ok(())
 }
 
+/// Gets the runtime the program is embedded in. With `--strip`, this is the
+/// runtime without the TypeScript compiler, the type definitions, the
+/// language server and WebGPU.
 async fn get_base_binary(
   client: &HttpClient,
   deno_dir: &DenoDir,
   target: Option<String>,
+  strip: bool,
 ) -> Result<Vec<u8>, AnyError> {
+  if target.is_none() {
+    let path = if !strip {
+      std::env::current_exe()?
+    } else if let Some(path) = env::var_os(STRIPPED_BASE_BINARY_ENV_VAR) {
+      PathBuf::from(path)
+    } else {
+      return download_base_binary_for_target(client, deno_dir, None, strip)
+        .await;
+    };
+    return tokio::fs::read(&path)
+      .await
+      .with_context(|| format!("Reading base binary: {}", path.display()));
+  }
+  download_base_binary_for_target(client, deno_dir, target, strip).await
+}
+
+/// The size of the runtime embedded without `--strip`, which is reported
+/// along with the size of the stripped executable.
+async fn get_unstripped_base_binary_len(
+  client: &HttpClient,
+  deno_dir: &DenoDir,
+  target: Option<String>,
+) -> Result<usize, AnyError> {
   if target.is_none() {
     let path = std::env::current_exe()?;
-    return Ok(tokio::fs::read(path).await?);
+    return Ok(fs::metadata(path)?.len() as usize);
   }
+  let base_binary =
+    download_base_binary_for_target(client, deno_dir, target, false).await?;
+  Ok(base_binary.len())
+}
 
+async fn download_base_binary_for_target(
+  client: &HttpClient,
+  deno_dir: &DenoDir,
+  target: Option<String>,
+  strip: bool,
+) -> Result<Vec<u8>, AnyError> {
   let target = target.unwrap_or_else(|| env!("TARGET").to_string());
-  let binary_name = format!("deno-{target}.zip");
+  let binary_name = if strip {
+    format!("denort-{target}.zip")
+  } else {
+    format!("deno-{target}.zip")
+  };
 
   let binary_path_suffix = if crate::version::is_canary() {
     format!("canary/{}/{}", crate::version::GIT_COMMIT_HASH, binary_name)
@@ -150,16 +271,29 @@ async fn download_base_binary(
   Ok(())
 }
 
+/// A standalone binary and the size of the program and metadata appended to
+/// the base binary before `--strip` compresses the program.
+struct StandaloneBinary {
+  bytes: Vec<u8>,
+  program_len: usize,
+}
+
 /// This functions creates a standalone deno binary by appending a bundle
 /// and magic trailer to the currently executing binary.
 async fn create_standalone_binary(
   mut original_bin: Vec<u8>,
   eszip: eszip::EszipV2,
   entrypoint: ModuleSpecifier,
+  preload_modules: Vec<ModuleSpecifier>,
   compile_flags: &CompileFlags,
   ps: ProcState,
-) -> Result<Vec<u8>, AnyError> {
+) -> Result<StandaloneBinary, AnyError> {
   let mut eszip_archive = eszip.into_bytes();
+  let original_eszip_len = eszip_archive.len();
+  if compile_flags.strip {
+    eszip_archive =
+      zstd::bulk::compress(&eszip_archive, STRIP_COMPRESSION_LEVEL)?;
+  }
 
   let ca_data = match ps.options.ca_data() {
     Some(CaData::File(ca_file)) => {
@@ -184,11 +318,27 @@ async fn create_standalone_binary(
       .options
       .unsafely_ignore_certificate_errors()
       .clone(),
+    user_agent: ps.options.user_agent(),
     log_level: ps.options.log_level(),
     ca_stores: ps.options.ca_stores().clone(),
     ca_data,
+    exit_on_idle_timeout: ps.options.exit_on_idle_timeout(),
+    report_unused_permissions: ps.options.report_unused_permissions(),
     entrypoint,
+    preload_modules,
     maybe_import_map,
+    deno_version: crate::version::deno(),
+    target: compile_flags
+      .target
+      .clone()
+      .unwrap_or_else(|| env!("TARGET").to_string()),
+    compiled_at: if env::var_os("SOURCE_DATE_EPOCH").is_some() {
+      None
+    } else {
+      Some(chrono::Utc::now().to_rfc3339())
+    },
+    metadata_flag: !compile_flags.no_metadata_flag,
+    compressed: compile_flags.strip,
   };
   let mut metadata = serde_json::to_string(&metadata)?.as_bytes().to_vec();
 
@@ -198,6 +348,7 @@ async fn create_standalone_binary(
   trailer.write_all(&eszip_pos.to_be_bytes())?;
   trailer.write_all(&metadata_pos.to_be_bytes())?;
 
+  let program_len = original_eszip_len + metadata.len() + trailer.len();
   let mut final_bin = Vec::with_capacity(
     original_bin.len() + eszip_archive.len() + trailer.len(),
   );
@@ -206,7 +357,10 @@ async fn create_standalone_binary(
   final_bin.append(&mut metadata);
   final_bin.append(&mut trailer);
 
-  Ok(final_bin)
+  Ok(StandaloneBinary {
+    bytes: final_bin,
+    program_len,
+  })
 }
 
 /// This function writes out a final binary to specified path. If output path
@@ -344,6 +498,9 @@ mod test {
       output: Some(PathBuf::from("./file")),
       args: Vec::new(),
       target: Some("x86_64-unknown-linux-gnu".to_string()),
+      no_metadata_flag: false,
+      strip: false,
+      deny_warnings: false,
     })
     .await
     .unwrap();
@@ -361,6 +518,9 @@ mod test {
       output: Some(PathBuf::from("./file")),
       args: Vec::new(),
       target: Some("x86_64-pc-windows-msvc".to_string()),
+      no_metadata_flag: false,
+      strip: false,
+      deny_warnings: false,
     })
     .await
     .unwrap();
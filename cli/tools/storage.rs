@@ -0,0 +1,172 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::url::Url;
+use deno_runtime::colors;
+use deno_runtime::deno_webstorage::rusqlite::params;
+use deno_runtime::deno_webstorage::rusqlite::Connection;
+use deno_runtime::deno_webstorage::LOCAL_STORAGE_FILE_NAME;
+use deno_runtime::deno_webstorage::ORIGIN_FILE_NAME;
+
+use crate::args::CliOptions;
+use crate::args::Flags;
+use crate::args::StorageFlags;
+use crate::display;
+use crate::util::checksum;
+
+/// The directory holding the data persisted by `localStorage` for an origin.
+struct OriginStorage {
+  dir: PathBuf,
+  /// The origin recorded in the directory. Data persisted by older versions
+  /// of Deno doesn't have one.
+  origin: Option<String>,
+}
+
+impl OriginStorage {
+  fn display_name(&self) -> String {
+    match &self.origin {
+      Some(origin) => origin.clone(),
+      None => format!(
+        "{} {}",
+        self.dir.file_name().unwrap().to_string_lossy(),
+        colors::gray("(unknown origin)")
+      ),
+    }
+  }
+
+  fn open(&self) -> Result<Option<Connection>, AnyError> {
+    let path = self.dir.join(LOCAL_STORAGE_FILE_NAME);
+    if !path.exists() {
+      return Ok(None);
+    }
+    let conn = Connection::open(&path)
+      .with_context(|| format!("Failed opening {}", path.display()))?;
+    Ok(Some(conn))
+  }
+
+  /// Gets the number of entries and their size in bytes.
+  fn read_stats(&self) -> Result<(u64, u64), AnyError> {
+    let conn = match self.open()? {
+      Some(conn) => conn,
+      None => return Ok((0, 0)),
+    };
+    let mut stmt = conn.prepare(
+      "SELECT COUNT(*), COALESCE(SUM(LENGTH(CAST(key AS BLOB)) + LENGTH(CAST(value AS BLOB))), 0) FROM data",
+    )?;
+    let stats =
+      stmt.query_row(params![], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    Ok(stats)
+  }
+
+  fn read_entries(&self) -> Result<BTreeMap<String, String>, AnyError> {
+    let conn = match self.open()? {
+      Some(conn) => conn,
+      None => return Ok(BTreeMap::new()),
+    };
+    let mut stmt = conn.prepare("SELECT key, value FROM data")?;
+    let entries = stmt
+      .query_map(params![], |row| Ok((row.get(0)?, row.get(1)?)))?
+      .collect::<Result<_, _>>()?;
+    Ok(entries)
+  }
+}
+
+pub async fn storage(
+  flags: Flags,
+  storage_flags: StorageFlags,
+) -> Result<(), AnyError> {
+  let cli_options = CliOptions::from_flags(flags)?;
+  let storage_dir = cli_options.resolve_deno_dir()?.origin_data_folder_path();
+  match storage_flags {
+    StorageFlags::List => list(&storage_dir),
+    StorageFlags::Show { origin } => {
+      let storage = find_origin_storage(&storage_dir, &origin)?;
+      display::write_json_to_stdout(&storage.read_entries()?)
+    }
+    StorageFlags::Clear { origin } => {
+      let storage = find_origin_storage(&storage_dir, &origin)?;
+      std::fs::remove_dir_all(&storage.dir).with_context(|| {
+        format!("Failed removing {}", storage.dir.display())
+      })?;
+      log::info!("{} {}", colors::green("Cleared"), storage.display_name());
+      Ok(())
+    }
+  }
+}
+
+fn list(storage_dir: &Path) -> Result<(), AnyError> {
+  let storages = read_origin_storages(storage_dir)?;
+  if storages.is_empty() {
+    log::info!("No persisted Web Storage data found.");
+    return Ok(());
+  }
+  for storage in storages {
+    let (entries, bytes) = storage.read_stats()?;
+    println!(
+      "{} {}",
+      storage.display_name(),
+      colors::gray(format!(
+        "({} {}, {})",
+        entries,
+        if entries == 1 { "entry" } else { "entries" },
+        display::human_size(bytes as f64)
+      ))
+    );
+  }
+  Ok(())
+}
+
+fn read_origin_storages(
+  storage_dir: &Path,
+) -> Result<Vec<OriginStorage>, AnyError> {
+  let entries = match std::fs::read_dir(storage_dir) {
+    Ok(entries) => entries,
+    Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+    Err(err) => return Err(err.into()),
+  };
+  let mut storages = Vec::new();
+  for entry in entries {
+    let entry = entry?;
+    if !entry.file_type()?.is_dir() {
+      continue;
+    }
+    let dir = entry.path();
+    let origin = std::fs::read_to_string(dir.join(ORIGIN_FILE_NAME)).ok();
+    storages.push(OriginStorage { dir, origin });
+  }
+  storages.sort_by_key(|storage| storage.display_name());
+  Ok(storages)
+}
+
+/// Finds the storage directory of an origin. The directories are named after
+/// a hash of the storage key, which is either the origin of `--location` or
+/// the specifier of the configuration file or main module.
+fn find_origin_storage(
+  storage_dir: &Path,
+  origin: &str,
+) -> Result<OriginStorage, AnyError> {
+  let mut storage_keys = vec![origin.to_string()];
+  if let Ok(url) = Url::parse(origin) {
+    let url_origin = url.origin();
+    if url_origin.is_tuple() {
+      storage_keys.push(url_origin.ascii_serialization());
+    }
+  }
+  for storage_key in storage_keys {
+    let dir = storage_dir.join(checksum::gen(&[storage_key.as_bytes()]));
+    if dir.is_dir() {
+      return Ok(OriginStorage {
+        dir,
+        origin: Some(storage_key),
+      });
+    }
+  }
+  bail!("No persisted Web Storage data found for {}.", origin)
+}
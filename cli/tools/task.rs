@@ -1,24 +1,115 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::Flags;
+use crate::args::TaskDefinition;
 use crate::args::TaskFlags;
 use crate::colors;
 use crate::proc_state::ProcState;
+use crate::util::display;
+use crate::util::display::shell_quote;
 use crate::util::fs::canonicalize_path;
+use crate::util::fs::normalize_path;
+use crate::util::glob::is_glob_pattern;
+use crate::util::glob::PathOrPattern;
+use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
-use std::collections::BTreeMap;
+use deno_core::futures::future::LocalBoxFuture;
+use deno_core::futures::FutureExt;
+use deno_core::serde::Serialize;
+use deno_task_shell::parser::Command;
+use deno_task_shell::parser::CommandInner;
+use deno_task_shell::parser::PipeSequenceOperator;
+use deno_task_shell::parser::Pipeline;
+use deno_task_shell::parser::PipelineInner;
+use deno_task_shell::parser::Redirect;
+use deno_task_shell::parser::RedirectFd;
+use deno_task_shell::parser::RedirectOp;
+use deno_task_shell::parser::Sequence;
+use deno_task_shell::parser::SequentialList;
+use deno_task_shell::parser::SimpleCommand;
+use deno_task_shell::parser::Word;
+use deno_task_shell::parser::WordPart;
+use indexmap::IndexMap;
+use once_cell::unsync::OnceCell;
+use std::cell::Cell;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
+use walkdir::WalkDir;
 
-fn print_available_tasks(tasks_config: BTreeMap<String, String>) {
+/// Commands longer than this are truncated when listing tasks without a
+/// description.
+const MAX_COMMAND_WIDTH: usize = 60;
+
+fn print_available_tasks(tasks_config: &IndexMap<String, TaskDefinition>) {
   eprintln!("{}", colors::green("Available tasks:"));
 
-  for name in tasks_config.keys() {
-    eprintln!("- {}", colors::cyan(name));
-    eprintln!("    {}", tasks_config[name])
+  // tasks without a group are listed first, followed by each group in the
+  // order it first appears
+  let mut ungrouped = Vec::new();
+  let mut groups: IndexMap<&str, Vec<_>> = IndexMap::new();
+  for (name, task) in tasks_config {
+    match &task.group {
+      Some(group) => {
+        groups.entry(group.as_str()).or_default().push((name, task))
+      }
+      None => ungrouped.push((name, task)),
+    }
+  }
+  let name_width = tasks_config
+    .keys()
+    .map(|name| name.chars().count())
+    .max()
+    .unwrap_or(0);
+
+  print_tasks(&ungrouped, name_width);
+  for (group, tasks) in groups {
+    eprintln!();
+    eprintln!("{}", colors::bold(format!("{group}:")));
+    print_tasks(&tasks, name_width);
+  }
+}
+
+fn print_tasks(tasks: &[(&String, &TaskDefinition)], name_width: usize) {
+  for (name, task) in tasks {
+    let padding = " ".repeat(name_width - name.chars().count());
+    let summary = match &task.description {
+      Some(description) => description.clone(),
+      None => colors::gray(truncate_command(&task.command)).to_string(),
+    };
+    eprintln!("  {}{}  {}", colors::cyan(name), padding, summary);
+  }
+}
+
+fn truncate_command(command: &str) -> String {
+  if command.chars().count() <= MAX_COMMAND_WIDTH {
+    command.to_string()
+  } else {
+    let truncated = command
+      .chars()
+      .take(MAX_COMMAND_WIDTH - 3)
+      .collect::<String>();
+    format!("{}...", truncated.trim_end())
+  }
+}
+
+fn print_tasks_json(
+  tasks_config: &IndexMap<String, TaskDefinition>,
+) -> Result<(), AnyError> {
+  #[derive(Serialize)]
+  struct TaskJson<'a> {
+    name: &'a str,
+    #[serde(flatten)]
+    task: &'a TaskDefinition,
   }
+
+  let tasks = tasks_config
+    .iter()
+    .map(|(name, task)| TaskJson { name, task })
+    .collect::<Vec<_>>();
+  display::write_json_to_stdout(&tasks)
 }
 
 pub async fn execute_script(
@@ -34,8 +125,17 @@ pub async fn execute_script(
     bail!("Only local configuration files are supported")
   };
 
+  if task_flags.list {
+    if task_flags.json {
+      print_tasks_json(&tasks_config)?;
+    } else {
+      print_available_tasks(&tasks_config);
+    }
+    return Ok(0);
+  }
+
   if task_flags.task.is_empty() {
-    print_available_tasks(tasks_config);
+    print_available_tasks(&tasks_config);
     return Ok(1);
   }
 
@@ -44,29 +144,19 @@ pub async fn execute_script(
     None => config_file_path.parent().unwrap().to_owned(),
   };
   let task_name = task_flags.task;
-  let maybe_script = tasks_config.get(&task_name);
+  let maybe_task = tasks_config.get(&task_name);
 
-  if let Some(script) = maybe_script {
+  if let Some(task) = maybe_task {
+    let script = &task.command;
     let additional_args = ps
       .options
       .argv()
       .iter()
-      // surround all the additional arguments in double quotes
-      // and santize any command substition
-      .map(|a| format!("\"{}\"", a.replace('"', "\\\"").replace('$', "\\$")))
+      .map(|arg| quote_task_shell_arg(arg))
       .collect::<Vec<_>>()
       .join(" ");
     let script = format!("{script} {additional_args}");
     let script = script.trim();
-    log::info!(
-      "{} {} {}",
-      colors::green("Task"),
-      colors::cyan(&task_name),
-      script,
-    );
-    let seq_list = deno_task_shell::parser::parse(script)
-      .with_context(|| format!("Error parsing script '{task_name}'."))?;
-
     // get the starting env vars (the PWD env var will be set by deno_task_shell)
     let mut env_vars = std::env::vars().collect::<HashMap<String, String>>();
     const INIT_CWD_NAME: &str = "INIT_CWD";
@@ -78,11 +168,1083 @@ pub async fn execute_script(
       }
     }
 
-    let exit_code = deno_task_shell::execute(seq_list, env_vars, &cwd).await;
-    Ok(exit_code)
+    if task_flags.dry_run {
+      return dry_run_script(
+        &task_name,
+        script,
+        env_vars,
+        &cwd,
+        task_flags.show_secrets,
+      );
+    }
+    log::info!(
+      "{} {} {}",
+      colors::green("Task"),
+      colors::cyan(&task_name),
+      script,
+    );
+
+    let substitution_files = SubstitutionFiles::default();
+    run_script(
+      &task_name,
+      script.to_string(),
+      &env_vars,
+      &cwd,
+      &substitution_files,
+    )
+    .await
   } else {
     eprintln!("Task not found: {task_name}");
-    print_available_tasks(tasks_config);
+    print_available_tasks(&tasks_config);
     Ok(1)
   }
 }
+
+/// Quotes an argument for a script of deno_task_shell, which splits the
+/// command lines of the programs it spawns itself, on every platform. Single
+/// quotes are used as nothing is expanded in them and, unlike in double
+/// quotes, a backslash is never an escape, which would break the Windows
+/// paths ending with one. An embedded single quote is put in double quotes.
+fn quote_task_shell_arg(arg: &str) -> String {
+  format!("'{}'", arg.replace('\'', "'\"'\"'"))
+}
+
+/// Runs the script of a task. deno_task_shell doesn't support process
+/// substitution, so `<(...)` and `>(...)` are emulated with temporary files:
+/// the output of a `<(...)` command is written to its file before the script
+/// runs, and a `>(...)` command reads what the script wrote to its file
+/// afterwards. Unlike a shell, the commands don't run concurrently with the
+/// script, which would require the script to open the files in order.
+fn run_script<'a>(
+  task_name: &'a str,
+  script: String,
+  env_vars: &'a HashMap<String, String>,
+  cwd: &'a Path,
+  substitution_files: &'a SubstitutionFiles,
+) -> LocalBoxFuture<'a, Result<i32, AnyError>> {
+  async move {
+    let (script, substitutions) =
+      extract_process_substitutions(&script, &mut || {
+        substitution_files.next_path()
+      })
+      .with_context(|| format!("Error parsing script '{task_name}'."))?;
+    if cfg!(windows) {
+      if let Some(substitution) = substitutions.first() {
+        bail!(
+          concat!(
+            "Process substitution '{}' in task '{}' is not supported on ",
+            "Windows. Write the output of the command to a file and pass ",
+            "the file's path instead.",
+          ),
+          substitution,
+          task_name,
+        );
+      }
+    }
+
+    // like in a shell, the exit codes of the substituted commands are ignored
+    for substitution in &substitutions {
+      if substitution.kind == SubstitutionKind::Input {
+        let script =
+          format!("{} > \"{}\"", substitution.command, substitution.path);
+        run_script(task_name, script, env_vars, cwd, substitution_files)
+          .await?;
+      }
+    }
+
+    let seq_list = deno_task_shell::parser::parse(&script)
+      .with_context(|| format!("Error parsing script '{task_name}'."))?;
+    let exit_code =
+      deno_task_shell::execute(seq_list, env_vars.clone(), cwd).await;
+
+    for substitution in &substitutions {
+      if substitution.kind == SubstitutionKind::Output {
+        let script =
+          format!("cat \"{}\" | {}", substitution.path, substitution.command);
+        run_script(task_name, script, env_vars, cwd, substitution_files)
+          .await?;
+      }
+    }
+
+    Ok(exit_code)
+  }
+  .boxed_local()
+}
+
+/// The temporary files backing the process substitutions of a task. The
+/// directory is only created once a substitution is found.
+#[derive(Default)]
+struct SubstitutionFiles {
+  dir: OnceCell<secure_tempfile::TempDir>,
+  count: Cell<usize>,
+}
+
+impl SubstitutionFiles {
+  fn next_path(&self) -> Result<String, AnyError> {
+    let dir = self.dir.get_or_try_init(secure_tempfile::TempDir::new)?;
+    let index = self.count.get();
+    self.count.set(index + 1);
+    Ok(dir.path().join(index.to_string()).display().to_string())
+  }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum SubstitutionKind {
+  /// `<(...)`, the script reads the output of the command.
+  Input,
+  /// `>(...)`, the script writes the input of the command.
+  Output,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+struct ProcessSubstitution {
+  kind: SubstitutionKind,
+  command: String,
+  path: String,
+}
+
+impl std::fmt::Display for ProcessSubstitution {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let prefix = match self.kind {
+      SubstitutionKind::Input => '<',
+      SubstitutionKind::Output => '>',
+    };
+    write!(f, "{}({})", prefix, self.command)
+  }
+}
+
+/// Replaces the top level process substitutions of a script with the quoted
+/// paths returned by `next_path`. Substitutions nested in the commands are
+/// left to be extracted when those commands run.
+fn extract_process_substitutions(
+  script: &str,
+  next_path: &mut dyn FnMut() -> Result<String, AnyError>,
+) -> Result<(String, Vec<ProcessSubstitution>), AnyError> {
+  let chars = script.chars().collect::<Vec<_>>();
+  let mut text = String::with_capacity(script.len());
+  let mut substitutions = Vec::new();
+  let mut scanner = QuoteScanner::default();
+  let mut index = 0;
+  while index < chars.len() {
+    let c = chars[index];
+    let is_word_start = index == 0 || chars[index - 1].is_whitespace();
+    if scanner.is_unquoted()
+      && is_word_start
+      && matches!(c, '<' | '>')
+      && chars.get(index + 1) == Some(&'(')
+    {
+      let start = index + 2;
+      let end = find_closing_paren(&chars, start).ok_or_else(|| {
+        anyhow!("Unterminated process substitution '{}('.", c)
+      })?;
+      let command = chars[start..end].iter().collect::<String>();
+      let command = command.trim().to_string();
+      if command.is_empty() {
+        bail!("Empty process substitution '{}()'.", c);
+      }
+      if has_list_operator(&command) {
+        bail!(
+          "Process substitution '{}({})' must be a single command or pipeline.",
+          c,
+          command
+        );
+      }
+      let path = next_path()?;
+      text.push_str(&format!("\"{path}\""));
+      substitutions.push(ProcessSubstitution {
+        kind: if c == '<' {
+          SubstitutionKind::Input
+        } else {
+          SubstitutionKind::Output
+        },
+        command,
+        path,
+      });
+      index = end + 1;
+      continue;
+    }
+    scanner.next(c);
+    text.push(c);
+    index += 1;
+  }
+  Ok((text, substitutions))
+}
+
+/// Gets the index of the parenthesis closing the one before `start`.
+fn find_closing_paren(chars: &[char], start: usize) -> Option<usize> {
+  let mut scanner = QuoteScanner::default();
+  let mut depth = 1;
+  for (index, c) in chars.iter().enumerate().skip(start) {
+    if scanner.is_unquoted() {
+      match c {
+        '(' => depth += 1,
+        ')' => {
+          depth -= 1;
+          if depth == 0 {
+            return Some(index);
+          }
+        }
+        _ => {}
+      }
+    }
+    scanner.next(*c);
+  }
+  None
+}
+
+/// Whether the command has a `;`, `&&`, `||` or `&` outside of quotes and
+/// parentheses. The `&` of the `&>` and `2>&1` redirects isn't an operator.
+fn has_list_operator(command: &str) -> bool {
+  let chars = command.chars().collect::<Vec<_>>();
+  let mut scanner = QuoteScanner::default();
+  let mut depth = 0;
+  for (index, c) in chars.iter().enumerate() {
+    if scanner.is_unquoted() {
+      let prev = index.checked_sub(1).map(|i| chars[i]);
+      let next = chars.get(index + 1).copied();
+      match c {
+        '(' => depth += 1,
+        ')' => depth -= 1,
+        ';' if depth == 0 => return true,
+        '|' if depth == 0 && next == Some('|') => return true,
+        '&' if depth == 0 && prev != Some('>') && next != Some('>') => {
+          return true
+        }
+        _ => {}
+      }
+    }
+    scanner.next(*c);
+  }
+  false
+}
+
+/// Tracks whether the characters of a script are inside quotes or escaped.
+#[derive(Default)]
+struct QuoteScanner {
+  quote: Option<char>,
+  escaped: bool,
+}
+
+impl QuoteScanner {
+  /// Whether the next character is outside of quotes and not escaped.
+  fn is_unquoted(&self) -> bool {
+    self.quote.is_none() && !self.escaped
+  }
+
+  fn next(&mut self, c: char) {
+    if self.escaped {
+      self.escaped = false;
+      return;
+    }
+    match (self.quote, c) {
+      (Some('\''), '\'') => self.quote = None,
+      (Some('\''), _) => {}
+      (_, '\\') => self.escaped = true,
+      (Some('"'), '"') => self.quote = None,
+      (None, '\'' | '"') => self.quote = Some(c),
+      _ => {}
+    }
+  }
+}
+
+/// The parts of the names of environment variables whose values are masked
+/// by `deno task --dry-run`, unless `--show-secrets` is passed.
+const SECRET_NAME_PATTERNS: [&str; 2] = ["TOKEN", "SECRET"];
+const MASKED_VALUE: &str = "***";
+
+/// The commands deno_task_shell implements itself.
+const BUILT_IN_COMMANDS: [&str; 16] = [
+  "cat", "cd", "cp", "echo", "exit", "export", "false", "head", "mkdir", "mv",
+  "pwd", "rm", "sleep", "true", "unset", "xargs",
+];
+
+fn is_secret_name(name: &str) -> bool {
+  let name = name.to_uppercase();
+  SECRET_NAME_PATTERNS
+    .iter()
+    .any(|pattern| name.contains(pattern))
+}
+
+/// Prints the commands the script of a task would run, without running them.
+/// Fails when an executable can't be found.
+fn dry_run_script(
+  task_name: &str,
+  script: &str,
+  env_vars: HashMap<String, String>,
+  cwd: &Path,
+  show_secrets: bool,
+) -> Result<i32, AnyError> {
+  let mut count = 0;
+  let (script, substitutions) =
+    extract_process_substitutions(script, &mut || {
+      count += 1;
+      Ok(format!("<substitution {count}>"))
+    })
+    .with_context(|| format!("Error parsing script '{task_name}'."))?;
+  let parse = |script: &str| {
+    deno_task_shell::parser::parse(script)
+      .with_context(|| format!("Error parsing script '{task_name}'."))
+  };
+
+  let mut dry_run = DryRun::new(env_vars, cwd, show_secrets);
+  let mut lines = Vec::new();
+  for substitution in &substitutions {
+    if substitution.kind == SubstitutionKind::Input {
+      let list = parse(&substitution.command)?;
+      lines.push(format!("{}: <({})", substitution.path, dry_run.list(&list)));
+    }
+  }
+  lines.push(dry_run.list(&parse(&script)?));
+  for substitution in &substitutions {
+    if substitution.kind == SubstitutionKind::Output {
+      let list = parse(&substitution.command)?;
+      lines.push(format!("{}: >({})", substitution.path, dry_run.list(&list)));
+    }
+  }
+
+  println!(
+    "{} {} {}",
+    colors::green("Task"),
+    colors::cyan(task_name),
+    colors::gray("(dry run)")
+  );
+  println!("{} {}", colors::bold("Directory:"), cwd.display());
+  println!("{}", colors::bold("Commands:"));
+  for line in lines {
+    println!("  {line}");
+  }
+  println!("{}", colors::bold("Executables:"));
+  let name_width = dry_run
+    .executables
+    .keys()
+    .map(|name| name.chars().count())
+    .max()
+    .unwrap_or(0);
+  let mut not_found = Vec::new();
+  for (name, executable) in &dry_run.executables {
+    let padding = " ".repeat(name_width - name.chars().count());
+    let location = match executable {
+      Executable::BuiltIn => colors::gray("built-in").to_string(),
+      Executable::Path(path) => path.display().to_string(),
+      Executable::NotFound => {
+        not_found.push(name.as_str());
+        colors::red("not found").to_string()
+      }
+    };
+    println!("  {}{}  {}", colors::cyan(name), padding, location);
+  }
+  if !not_found.is_empty() {
+    bail!(
+      "Task '{}' runs executables which weren't found: {}",
+      task_name,
+      not_found.join(", ")
+    );
+  }
+  Ok(0)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Executable {
+  BuiltIn,
+  Path(PathBuf),
+  NotFound,
+}
+
+/// A piece of a word being expanded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WordPiece {
+  /// Text which is split and matched as a glob pattern.
+  Unquoted,
+  /// Text which is kept as it is.
+  Quoted,
+  /// The value of a secret variable, which is shown masked.
+  Masked,
+  /// A command substitution, which can only be known by running it.
+  Command,
+}
+
+/// An argument of a command after expansion. `text` is what the command
+/// receives, while `display` is how it's shown in the preview.
+#[derive(Debug, Default)]
+struct ExpandedArg {
+  text: String,
+  display: String,
+  /// Whether the text contains a command substitution.
+  is_unknown: bool,
+  /// Whether the text contains the value of a secret variable.
+  is_masked: bool,
+}
+
+/// A variable of the script. The value is masked when it's the one of a
+/// secret variable or when it's copied from one.
+#[derive(Debug, Clone)]
+struct DryRunVar {
+  value: String,
+  is_masked: bool,
+}
+
+/// Expands the words of a script and resolves its executables the way
+/// deno_task_shell does, for `deno task --dry-run`. The variables set by the
+/// script and the directories it changes to are followed in order, without
+/// regard to whether the commands before would succeed.
+struct DryRun {
+  vars: HashMap<String, DryRunVar>,
+  cwd: PathBuf,
+  show_secrets: bool,
+  executables: IndexMap<String, Executable>,
+}
+
+impl DryRun {
+  fn new(
+    mut vars: HashMap<String, String>,
+    cwd: &Path,
+    show_secrets: bool,
+  ) -> Self {
+    vars.insert("PWD".to_string(), cwd.display().to_string());
+    Self {
+      vars: vars
+        .into_iter()
+        .map(|(name, value)| {
+          let is_masked = !show_secrets && is_secret_name(&name);
+          (name, DryRunVar { value, is_masked })
+        })
+        .collect(),
+      cwd: cwd.to_path_buf(),
+      show_secrets,
+      executables: IndexMap::new(),
+    }
+  }
+
+  fn list(&mut self, list: &SequentialList) -> String {
+    let mut text = String::new();
+    for (index, item) in list.items.iter().enumerate() {
+      if index > 0 {
+        text.push_str(if list.items[index - 1].is_async {
+          " "
+        } else {
+          "; "
+        });
+      }
+      text.push_str(&self.sequence(&item.sequence));
+      if item.is_async {
+        text.push_str(" &");
+      }
+    }
+    text
+  }
+
+  fn sequence(&mut self, sequence: &Sequence) -> String {
+    match sequence {
+      Sequence::ShellVar(var) => {
+        let value = self.assignment(&var.name, &var.value);
+        self.set_var(&var.name, value.text, value.is_masked);
+        format!("{}={}", var.name, value.display)
+      }
+      Sequence::Pipeline(pipeline) => self.pipeline(pipeline),
+      Sequence::BooleanList(list) => format!(
+        "{} {} {}",
+        self.sequence(&list.current),
+        list.op.as_str(),
+        self.sequence(&list.next)
+      ),
+    }
+  }
+
+  fn pipeline(&mut self, pipeline: &Pipeline) -> String {
+    let text = self.pipeline_inner(&pipeline.inner);
+    if pipeline.negated {
+      format!("! {text}")
+    } else {
+      text
+    }
+  }
+
+  fn pipeline_inner(&mut self, inner: &PipelineInner) -> String {
+    match inner {
+      PipelineInner::Command(command) => self.command(command),
+      PipelineInner::PipeSequence(sequence) => {
+        let op = match sequence.op {
+          PipeSequenceOperator::Stdout => "|",
+          PipeSequenceOperator::StdoutStderr => "|&",
+        };
+        format!(
+          "{} {} {}",
+          self.command(&sequence.current),
+          op,
+          self.pipeline_inner(&sequence.next)
+        )
+      }
+    }
+  }
+
+  fn command(&mut self, command: &Command) -> String {
+    let text = match &command.inner {
+      CommandInner::Simple(command) => self.simple_command(command),
+      CommandInner::Subshell(list) => format!("({})", self.list(list)),
+    };
+    match &command.redirect {
+      Some(redirect) => format!("{} {}", text, self.redirect(redirect)),
+      None => text,
+    }
+  }
+
+  fn simple_command(&mut self, command: &SimpleCommand) -> String {
+    let mut words = Vec::new();
+    for env_var in &command.env_vars {
+      let value = self.assignment(&env_var.name, &env_var.value);
+      words.push(format!("{}={}", env_var.name, value.display));
+    }
+    let args = command
+      .args
+      .iter()
+      .flat_map(|arg| self.word(arg))
+      .collect::<Vec<_>>();
+    if let Some(name) = args.first().filter(|name| !name.is_unknown) {
+      if !self.executables.contains_key(&name.text) {
+        let executable = self.resolve_executable(&name.text);
+        self.executables.insert(name.text.clone(), executable);
+      }
+      match (name.text.as_str(), &args[1..]) {
+        ("cd", [dir]) => self.cwd = normalize_path(self.cwd.join(&dir.text)),
+        ("export", vars) => {
+          for var in vars {
+            if let Some((name, value)) = var.text.split_once('=') {
+              self.set_var(name, value.to_string(), var.is_masked);
+            }
+          }
+        }
+        _ => {}
+      }
+    }
+    words.extend(args.into_iter().map(|arg| arg.display));
+    words.join(" ")
+  }
+
+  fn redirect(&mut self, redirect: &Redirect) -> String {
+    let fd = match &redirect.maybe_fd {
+      Some(RedirectFd::Fd(fd)) => fd.to_string(),
+      Some(RedirectFd::StdoutStderr) => "&".to_string(),
+      None => String::new(),
+    };
+    let op = match &redirect.op {
+      RedirectOp::Redirect => ">",
+      RedirectOp::Append => ">>",
+    };
+    let file = self
+      .word(&redirect.io_file)
+      .into_iter()
+      .map(|arg| arg.display)
+      .collect::<Vec<_>>()
+      .join(" ");
+    format!("{fd}{op} {file}")
+  }
+
+  fn set_var(&mut self, name: &str, value: String, is_masked: bool) {
+    let is_masked = is_masked || !self.show_secrets && is_secret_name(name);
+    self
+      .vars
+      .insert(name.to_string(), DryRunVar { value, is_masked });
+  }
+
+  /// Expands the value of a variable, which is neither split nor matched as
+  /// a glob pattern.
+  fn assignment(&mut self, name: &str, value: &Word) -> ExpandedArg {
+    let mut pieces = Vec::new();
+    self.word_parts(value.parts(), true, &mut pieces, &mut Vec::new());
+    let mut value = self.arg(pieces);
+    if !self.show_secrets && is_secret_name(name) {
+      value.display = MASKED_VALUE.to_string();
+      value.is_masked = true;
+    }
+    value
+  }
+
+  fn word(&mut self, word: &Word) -> Vec<ExpandedArg> {
+    let mut pieces = Vec::new();
+    let mut args = Vec::new();
+    self.word_parts(word.parts(), false, &mut pieces, &mut args);
+    if !pieces.is_empty() {
+      args.extend(self.glob_or_arg(pieces));
+    }
+    args
+  }
+
+  /// Expands the parts of a word into `pieces`, moving the pieces to `args`
+  /// whenever an unquoted variable splits the word like the shell does.
+  fn word_parts(
+    &mut self,
+    parts: &[WordPart],
+    is_quoted: bool,
+    pieces: &mut Vec<(WordPiece, String)>,
+    args: &mut Vec<ExpandedArg>,
+  ) {
+    for part in parts {
+      match part {
+        WordPart::Text(text) => {
+          let kind = if is_quoted {
+            WordPiece::Quoted
+          } else {
+            WordPiece::Unquoted
+          };
+          pieces.push((kind, text.clone()));
+        }
+        WordPart::Quoted(parts) => {
+          // an empty string is still an argument
+          pieces.push((WordPiece::Quoted, String::new()));
+          self.word_parts(parts, true, pieces, args);
+        }
+        WordPart::Variable(name) => {
+          let (value, is_masked) = match self.vars.get(name) {
+            Some(var) => (var.value.clone(), var.is_masked),
+            None => (String::new(), false),
+          };
+          if is_quoted || is_masked {
+            let kind = if is_masked {
+              WordPiece::Masked
+            } else {
+              WordPiece::Quoted
+            };
+            pieces.push((kind, value));
+            continue;
+          }
+          for (index, text) in
+            value.split(' ').filter(|text| !text.is_empty()).enumerate()
+          {
+            if index > 0 {
+              args.extend(self.glob_or_arg(std::mem::take(pieces)));
+            }
+            pieces.push((WordPiece::Unquoted, text.to_string()));
+          }
+        }
+        WordPart::Command(list) => {
+          let text = format!("$({})", self.list(list));
+          pieces.push((WordPiece::Command, text));
+        }
+      }
+    }
+  }
+
+  fn arg(&self, pieces: Vec<(WordPiece, String)>) -> ExpandedArg {
+    let mut arg = ExpandedArg::default();
+    for (kind, text) in pieces {
+      arg.text.push_str(&text);
+      match kind {
+        WordPiece::Unquoted | WordPiece::Quoted if text.is_empty() => {}
+        WordPiece::Unquoted | WordPiece::Quoted => {
+          arg.display.push_str(&shell_quote(&text))
+        }
+        WordPiece::Masked => {
+          arg.display.push_str(MASKED_VALUE);
+          arg.is_masked = true;
+        }
+        WordPiece::Command => {
+          arg.display.push_str(&text);
+          arg.is_unknown = true;
+        }
+      }
+    }
+    if arg.display.is_empty() {
+      arg.display = shell_quote("");
+    }
+    arg
+  }
+
+  /// Expands the word to the paths it matches when its unquoted text has
+  /// glob syntax, or else to itself like when nothing matches.
+  fn glob_or_arg(&self, pieces: Vec<(WordPiece, String)>) -> Vec<ExpandedArg> {
+    let is_pattern = pieces.iter().any(|(kind, text)| {
+      *kind == WordPiece::Unquoted && text.contains(['*', '?', '['])
+    });
+    if is_pattern {
+      let mut pattern = String::new();
+      for (kind, text) in &pieces {
+        if *kind == WordPiece::Unquoted {
+          pattern.push_str(text);
+          continue;
+        }
+        for c in text.chars() {
+          if matches!(c, '*' | '?' | '[' | ']' | '{' | '}') {
+            pattern.push_str(&format!("[{c}]"));
+          } else {
+            pattern.push(c);
+          }
+        }
+      }
+      let paths = self.glob(&pattern);
+      if !paths.is_empty() {
+        return paths
+          .into_iter()
+          .map(|path| ExpandedArg {
+            display: shell_quote(&path),
+            text: path,
+            is_unknown: false,
+            is_masked: false,
+          })
+          .collect();
+      }
+    }
+    vec![self.arg(pieces)]
+  }
+
+  /// Gets the paths matching the pattern in sorted order, relative to the
+  /// current directory unless the pattern is absolute. Like in a shell,
+  /// hidden files are only matched by a pattern starting with a dot.
+  fn glob(&self, pattern: &str) -> Vec<String> {
+    if pattern.starts_with('!') {
+      return Vec::new();
+    }
+    let glob = match PathOrPattern::new(Path::new(pattern), &self.cwd) {
+      Ok(glob @ PathOrPattern::Pattern(_)) => glob,
+      _ => return Vec::new(),
+    };
+    let segments = pattern
+      .split('/')
+      .filter(|segment| !segment.is_empty() && *segment != ".")
+      .collect::<Vec<_>>();
+    let first_glob_segment = segments
+      .iter()
+      .position(|segment| is_glob_pattern(segment))
+      .unwrap_or(0);
+    let max_depth = if pattern.contains("**") {
+      usize::MAX
+    } else {
+      segments.len() - first_glob_segment
+    };
+    let matches_hidden = segments[first_glob_segment..]
+      .iter()
+      .any(|segment| segment.starts_with('.'));
+    let base = glob.base_path();
+    let cwd = canonicalize_path(&self.cwd).unwrap_or_else(|_| self.cwd.clone());
+    let mut paths = WalkDir::new(base)
+      .min_depth(1)
+      .max_depth(max_depth)
+      .into_iter()
+      .filter_entry(|entry| {
+        matches_hidden || !entry.file_name().to_string_lossy().starts_with('.')
+      })
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| glob.matches_path(entry.path()))
+      .map(|entry| {
+        let path = entry.path();
+        let path = if Path::new(pattern).is_absolute() {
+          path
+        } else {
+          path.strip_prefix(&cwd).unwrap_or(path)
+        };
+        path.display().to_string()
+      })
+      .collect::<Vec<_>>();
+    paths.sort();
+    paths
+  }
+
+  /// Resolves an executable like deno_task_shell does: paths are resolved
+  /// against the current directory, and names are searched for in the
+  /// current directory, then in the directories of `PATH`.
+  fn resolve_executable(&self, name: &str) -> Executable {
+    if BUILT_IN_COMMANDS.contains(&name) {
+      return Executable::BuiltIn;
+    }
+    let is_path = name.contains('/') || cfg!(windows) && name.contains('\\');
+    let search_dirs = if Path::new(name).is_absolute() || is_path {
+      vec![self.cwd.clone()]
+    } else {
+      let mut search_dirs = vec![self.cwd.clone()];
+      if let Some(path) = self.vars.get("PATH") {
+        search_dirs.extend(std::env::split_paths(&path.value));
+      }
+      search_dirs
+    };
+    let names = if cfg!(windows) {
+      let path_ext = self
+        .vars
+        .get("PATHEXT")
+        .map(|path_ext| path_ext.value.as_str())
+        .unwrap_or(".EXE;.CMD;.BAT;.COM");
+      let upper_name = name.to_uppercase();
+      let exts = path_ext
+        .split(';')
+        .map(|ext| ext.trim().to_uppercase())
+        .filter(|ext| !ext.is_empty())
+        .collect::<Vec<_>>();
+      if exts.is_empty() || exts.iter().any(|ext| upper_name.ends_with(ext)) {
+        vec![name.to_string()]
+      } else {
+        exts.iter().map(|ext| format!("{name}{ext}")).collect()
+      }
+    } else {
+      vec![name.to_string()]
+    };
+    for dir in search_dirs {
+      for name in &names {
+        let path = normalize_path(dir.join(name));
+        if path.is_file() {
+          return Executable::Path(path);
+        }
+      }
+    }
+    Executable::NotFound
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  fn extract(script: &str) -> Result<(String, Vec<String>), AnyError> {
+    let mut count = 0;
+    let (text, substitutions) =
+      extract_process_substitutions(script, &mut || {
+        count += 1;
+        Ok(format!("/tmp/{count}"))
+      })?;
+    Ok((text, substitutions.iter().map(|s| s.to_string()).collect()))
+  }
+
+  #[test]
+  fn process_substitutions() {
+    assert_eq!(
+      extract("diff <(deno fmt --check a.ts) <(cat expected)").unwrap(),
+      (
+        "diff \"/tmp/1\" \"/tmp/2\"".to_string(),
+        vec![
+          "<(deno fmt --check a.ts)".to_string(),
+          "<(cat expected)".to_string()
+        ]
+      )
+    );
+    assert_eq!(
+      extract("echo 1 | tee >(cat) >( wc -c )").unwrap(),
+      (
+        "echo 1 | tee \"/tmp/1\" \"/tmp/2\"".to_string(),
+        vec![">(cat)".to_string(), ">(wc -c)".to_string()]
+      )
+    );
+    assert_eq!(
+      extract("echo 1 > >(cat)").unwrap(),
+      (
+        "echo 1 > \"/tmp/1\"".to_string(),
+        vec![">(cat)".to_string()]
+      )
+    );
+  }
+
+  #[test]
+  fn process_substitutions_nested() {
+    // nested substitutions are extracted when the command runs
+    assert_eq!(
+      extract("cat <(cat <(echo 1) $(echo 2))").unwrap(),
+      (
+        "cat \"/tmp/1\"".to_string(),
+        vec!["<(cat <(echo 1) $(echo 2))".to_string()]
+      )
+    );
+    assert_eq!(
+      extract("cat <(echo ')' \")\" \\))").unwrap(),
+      (
+        "cat \"/tmp/1\"".to_string(),
+        vec!["<(echo ')' \")\" \\))".to_string()]
+      )
+    );
+    assert_eq!(
+      extract("cat <(deno eval 'console.log(1)' 2>&1)").unwrap().1,
+      vec!["<(deno eval 'console.log(1)' 2>&1)".to_string()]
+    );
+    assert_eq!(
+      extract("cat <(echo 1 | (cat && echo 2))").unwrap().1,
+      vec!["<(echo 1 | (cat && echo 2))".to_string()]
+    );
+  }
+
+  #[test]
+  fn process_substitutions_ignored() {
+    for script in [
+      "echo '<(echo 1)'",
+      "echo \"<(echo 1)\"",
+      "echo \\<(echo 1)",
+      "echo a<(echo 1)",
+      "echo 1 2>(echo 1)",
+      "echo $(echo 1) <in",
+      "echo 1 >out",
+    ] {
+      assert_eq!(extract(script).unwrap(), (script.to_string(), vec![]));
+    }
+  }
+
+  #[test]
+  fn process_substitutions_invalid() {
+    assert_eq!(
+      extract("cat <(echo 1").unwrap_err().to_string(),
+      "Unterminated process substitution '<('."
+    );
+    assert_eq!(
+      extract("cat <( )").unwrap_err().to_string(),
+      "Empty process substitution '<()'."
+    );
+    for script in [
+      "cat <(echo 1 && echo 2)",
+      "cat <(echo 1 || echo 2)",
+      "cat <(echo 1; echo 2)",
+      "cat <(sleep 1 & echo 2)",
+    ] {
+      assert!(extract(script)
+        .unwrap_err()
+        .to_string()
+        .ends_with("must be a single command or pipeline."));
+    }
+  }
+
+  fn dry_run(
+    script: &str,
+    vars: &[(&str, &str)],
+    cwd: &Path,
+    show_secrets: bool,
+  ) -> (String, DryRun) {
+    let vars = vars
+      .iter()
+      .map(|(name, value)| (name.to_string(), value.to_string()))
+      .collect();
+    let mut dry_run = DryRun::new(vars, cwd, show_secrets);
+    let list = deno_task_shell::parser::parse(script).unwrap();
+    (dry_run.list(&list), dry_run)
+  }
+
+  #[test]
+  fn quotes_task_shell_arg() {
+    assert_eq!(quote_task_shell_arg(""), "''");
+    assert_eq!(quote_task_shell_arg("$HOME"), "'$HOME'");
+    assert_eq!(quote_task_shell_arg("it's"), r#"'it'"'"'s'"#);
+    assert_eq!(quote_task_shell_arg(r"C:\dir\"), r"'C:\dir\'");
+  }
+
+  #[test]
+  fn quotes_task_args() {
+    // nothing is expanded and the arguments are kept whole
+    let args = ["a b", "$HOME", "$(pwd)", "say \"hi\"", "C:\\dir\\"];
+    let script = format!(
+      "echo {}",
+      args
+        .iter()
+        .map(|arg| quote_task_shell_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+    );
+    assert_eq!(
+      dry_run(&script, &[], &std::env::temp_dir(), false).0,
+      r#"echo 'a b' '$HOME' '$(pwd)' 'say "hi"' 'C:\dir\'"#
+    );
+  }
+
+  #[test]
+  fn dry_run_expands_variables() {
+    let cwd = std::env::temp_dir();
+    let vars = [("NAME", "a  b"), ("EMPTY", "")];
+    assert_eq!(
+      dry_run("echo $NAME \"$NAME\" \"\" $EMPTY", &vars, &cwd, false).0,
+      "echo a b 'a  b' ''"
+    );
+    // assignments before a command only apply to the command
+    assert_eq!(
+      dry_run("FOO=1 echo $FOO && echo \"$(pwd)\"", &vars, &cwd, false).0,
+      "FOO=1 echo && echo $(pwd)"
+    );
+    assert_eq!(
+      dry_run(
+        "NAME=c; echo $NAME; export X=1 && echo $X",
+        &vars,
+        &cwd,
+        false
+      )
+      .0,
+      "NAME=c; echo c; export X=1 && echo 1"
+    );
+    assert_eq!(
+      dry_run(
+        "cat < in.txt | head -n 1 > 'out file' &",
+        &vars,
+        &cwd,
+        false
+      )
+      .0,
+      "cat < in.txt | head -n 1 > 'out file' &"
+    );
+  }
+
+  #[test]
+  fn dry_run_masks_secrets() {
+    let cwd = std::env::temp_dir();
+    let vars = [("GITHUB_TOKEN", "abc"), ("NAME", "a")];
+    let script = "echo $GITHUB_TOKEN \"Bearer $GITHUB_TOKEN\" $NAME && \
+      DB_SECRET=$NAME echo";
+    assert_eq!(
+      dry_run(script, &vars, &cwd, false).0,
+      "echo *** 'Bearer '*** a && DB_SECRET=*** echo"
+    );
+    assert_eq!(
+      dry_run(script, &vars, &cwd, true).0,
+      "echo abc 'Bearer abc' a && DB_SECRET=a echo"
+    );
+    // the values copied from secret variables stay masked
+    let script = "KEY=$GITHUB_TOKEN; curl -H \"Authorization: $KEY\" && \
+      export OTHER=$KEY && echo $OTHER";
+    assert_eq!(
+      dry_run(script, &vars, &cwd, false).0,
+      "KEY=***; curl -H 'Authorization: '*** && export OTHER=*** && echo ***"
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn dry_run_expands_globs() {
+    let temp_dir = TempDir::new();
+    temp_dir.write("a.ts", "");
+    temp_dir.write("b.ts", "");
+    temp_dir.write(".hidden.ts", "");
+    temp_dir.create_dir_all("sub");
+    temp_dir.write("sub/c.ts", "");
+    let (text, _) = dry_run(
+      "echo *.ts sub/*.ts '*.ts' *.md **/c.ts .*.ts",
+      &[],
+      temp_dir.path(),
+      false,
+    );
+    assert_eq!(
+      text,
+      "echo a.ts b.ts sub/c.ts '*.ts' '*.md' sub/c.ts .hidden.ts"
+    );
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn dry_run_resolves_executables() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("bin");
+    temp_dir.write("bin/tool", "");
+    temp_dir.write("script.sh", "");
+    let path = temp_dir.path().join("bin").display().to_string();
+    let (_, dry_run) = dry_run(
+      "echo 1 | ./script.sh && tool $(missing-tool) || missing-tool",
+      &[("PATH", &path)],
+      temp_dir.path(),
+      false,
+    );
+    let executables = dry_run.executables.into_iter().collect::<Vec<_>>();
+    assert_eq!(
+      executables,
+      vec![
+        ("echo".to_string(), Executable::BuiltIn),
+        (
+          "./script.sh".to_string(),
+          Executable::Path(temp_dir.path().join("script.sh"))
+        ),
+        // the command substitution is expanded before the command runs
+        ("missing-tool".to_string(), Executable::NotFound),
+        (
+          "tool".to_string(),
+          Executable::Path(temp_dir.path().join("bin/tool"))
+        ),
+      ]
+    );
+  }
+}
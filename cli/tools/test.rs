@@ -3,6 +3,7 @@
 use crate::args::CliOptions;
 use crate::args::FilesConfig;
 use crate::args::TestOptions;
+use crate::args::TestReporterConfig;
 use crate::args::TypeCheckMode;
 use crate::colors;
 use crate::display;
@@ -10,12 +11,24 @@ use crate::file_fetcher::File;
 use crate::graph_util::graph_valid_with_cli_options;
 use crate::ops;
 use crate::proc_state::ProcState;
+use crate::tools::coverage::TEST_RUN_METADATA_FILE_NAME;
+use crate::tools::test_affected::analyze_test_module;
+use crate::tools::test_affected::collect_watch_changes;
+use crate::tools::test_affected::describe_affected_tests;
+use crate::tools::test_affected::find_affected_tests;
+use crate::tools::test_affected::AffectedTests;
 use crate::util::checksum;
+use crate::util::console::console_size;
+use crate::util::display::print_unused_permissions;
 use crate::util::file_watcher;
 use crate::util::file_watcher::ResolutionResult;
 use crate::util::fs::collect_specifiers;
+use crate::util::glob::no_files_found_error;
+use crate::util::memory::MemoryReport;
+use crate::util::memory::MemorySampler;
 use crate::util::path::get_extension;
 use crate::util::path::is_supported_ext;
+use crate::worker::create_main_worker;
 use crate::worker::create_main_worker_for_test_or_bench;
 
 use deno_ast::swc::common::comments::CommentKind;
@@ -28,10 +41,15 @@ use deno_core::futures::future;
 use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
+use deno_core::parking_lot::Condvar;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
 use deno_core::url::Url;
+use deno_core::v8;
 use deno_core::ModuleSpecifier;
 use deno_runtime::fmt_errors::format_js_error;
+use deno_runtime::fmt_errors::StackTraceOptions;
 use deno_runtime::ops::io::Stdio;
 use deno_runtime::ops::io::StdioPipe;
 use deno_runtime::permissions::Permissions;
@@ -39,13 +57,17 @@ use deno_runtime::permissions::PermissionsContainer;
 use deno_runtime::tokio_util::run_local;
 use indexmap::IndexMap;
 use log::Level;
+use once_cell::sync::Lazy;
+use percent_encoding::percent_decode_str;
 use rand::rngs::SmallRng;
 use rand::seq::SliceRandom;
 use rand::SeedableRng;
 use regex::Regex;
 use serde::Deserialize;
+use serde::Serialize;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Write as _;
 use std::io::Read;
@@ -58,7 +80,9 @@ use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Notify;
 
 /// The test mode is used to determine how a specifier is to be tested.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -78,6 +102,9 @@ pub struct TestFilter {
   pub regex: Option<Regex>,
   pub include: Option<Vec<String>>,
   pub exclude: Vec<String>,
+  /// When not empty, only the tests with one of these tags are included.
+  pub tags: Vec<String>,
+  pub skip_tags: Vec<String>,
 }
 
 impl TestFilter {
@@ -103,6 +130,16 @@ impl TestFilter {
     true
   }
 
+  /// Whether `--tags` and `--skip-tags` include a test with these tags, which
+  /// applies on top of the name filters.
+  pub fn includes_tags(&self, tags: &[String]) -> bool {
+    if !self.tags.is_empty() && !tags.iter().any(|tag| self.tags.contains(tag))
+    {
+      return false;
+    }
+    !tags.iter().any(|tag| self.skip_tags.contains(tag))
+  }
+
   pub fn from_flag(flag: &Option<String>) -> Self {
     let mut substring = None;
     let mut regex = None;
@@ -123,7 +160,7 @@ impl TestFilter {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct TestLocation {
   pub file_name: String,
@@ -131,16 +168,18 @@ pub struct TestLocation {
   pub column_number: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct TestDescription {
   pub id: usize,
   pub name: String,
   pub origin: String,
   pub location: TestLocation,
+  pub tags: Vec<String>,
 }
 
 impl TestDescription {
+  #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
   pub fn static_id(&self) -> String {
     checksum::gen(&[self.location.file_name.as_bytes(), self.name.as_bytes()])
   }
@@ -160,10 +199,14 @@ pub enum TestResult {
   Ok,
   Ignored,
   Failed(Box<JsError>),
+  /// A test with the `expectedFailure` option that failed.
+  ExpectedFailure(Box<JsError>),
+  /// A test with the `expectedFailure` option that passed.
+  UnexpectedlyPassed,
   Cancelled,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestStepDescription {
   pub id: usize,
@@ -177,6 +220,7 @@ pub struct TestStepDescription {
 }
 
 impl TestStepDescription {
+  #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
   pub fn static_id(&self) -> String {
     checksum::gen(&[
       self.location.file_name.as_bytes(),
@@ -194,6 +238,8 @@ pub enum TestStepResult {
   Ignored,
   Failed(Option<Box<JsError>>),
   Pending(Option<Box<JsError>>),
+  ExpectedFailure(Option<Box<JsError>>),
+  UnexpectedlyPassed,
 }
 
 impl TestStepResult {
@@ -206,12 +252,14 @@ impl TestStepResult {
   }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TestPlan {
   pub origin: String,
   pub total: usize,
   pub filtered_out: usize,
+  /// How many of the tests filtered out were excluded by their tags.
+  pub excluded_by_tag: usize,
   pub used_only: bool,
 }
 
@@ -223,33 +271,67 @@ pub enum TestEvent {
   Wait(usize),
   Output(Vec<u8>),
   Result(usize, TestResult, u64),
+  /// A test failed and runs again, for the given retry of `--retries`.
+  Retry(usize, usize),
   UncaughtError(String, Box<JsError>),
   StepRegister(TestStepDescription),
   StepWait(usize),
   StepResult(usize, TestStepResult, u64),
+  /// The origin of a module and how many of its tests didn't run because of
+  /// `--fail-fast-per-file`.
+  FailFastCutoff(String, usize),
 }
 
+/// The error reported for a test with the `expectedFailure` option that
+/// passed.
+pub const UNEXPECTED_PASS_MESSAGE: &str =
+  "Test was expected to fail, but it passed. Remove the \"expectedFailure\" option if it was fixed.";
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct TestSummary {
   pub total: usize,
   pub passed: usize,
   pub failed: usize,
   pub ignored: usize,
+  /// The tests which failed and then passed on a retry.
+  pub flaky: usize,
   pub passed_steps: usize,
   pub failed_steps: usize,
   pub pending_steps: usize,
   pub ignored_steps: usize,
+  pub expected_failures: usize,
+  pub expected_failure_steps: usize,
+  pub unexpectedly_passed_steps: usize,
   pub filtered_out: usize,
+  pub excluded_by_tag: usize,
   pub measured: usize,
+  /// The tests which didn't run because of `--fail-fast-per-file`.
+  pub fail_fast_skipped: usize,
   pub failures: Vec<(TestDescription, Box<JsError>)>,
+  /// The tests with the `expectedFailure` option that passed, which fail the
+  /// test run.
+  pub unexpected_passes: Vec<TestDescription>,
   pub uncaught_errors: Vec<(String, Box<JsError>)>,
+  pub memory: Option<MemoryReport>,
 }
 
 #[derive(Debug, Clone)]
 struct TestSpecifierOptions {
   concurrent_jobs: NonZeroUsize,
   fail_fast: Option<NonZeroUsize>,
+  fail_fast_per_file: Option<NonZeroUsize>,
   filter: TestFilter,
+  /// The names of the tests to run in some of the modules, in watch mode when
+  /// a change only affects some of their tests.
+  included_tests: HashMap<ModuleSpecifier, Vec<String>>,
+  /// The number of documentation tests in each module, which are type checked
+  /// instead of being run.
+  doc_tests: HashMap<String, usize>,
+  reporter: TestReporterConfig,
+  junit_path: Option<PathBuf>,
+  memory_sampler: Option<MemorySampler>,
+  fail_on_empty_files: bool,
+  update_golden: bool,
 }
 
 impl TestSummary {
@@ -259,22 +341,282 @@ impl TestSummary {
       passed: 0,
       failed: 0,
       ignored: 0,
+      flaky: 0,
       passed_steps: 0,
       failed_steps: 0,
       pending_steps: 0,
       ignored_steps: 0,
+      expected_failures: 0,
+      expected_failure_steps: 0,
+      unexpectedly_passed_steps: 0,
       filtered_out: 0,
+      excluded_by_tag: 0,
       measured: 0,
+      fail_fast_skipped: 0,
       failures: Vec::new(),
+      unexpected_passes: Vec::new(),
       uncaught_errors: Vec::new(),
+      memory: None,
     }
   }
 
   fn has_failed(&self) -> bool {
-    self.failed > 0 || !self.failures.is_empty()
+    self.failed > 0
+      || !self.failures.is_empty()
+      || !self.unexpected_passes.is_empty()
+  }
+}
+
+/// The files which didn't run anything, either because they don't register
+/// anything or because the filter didn't match what they register.
+#[derive(Debug, Default)]
+pub struct EmptyFiles {
+  empty: Vec<String>,
+  filtered_out: Vec<String>,
+}
+
+impl EmptyFiles {
+  /// Records the plan of a file, given how many things it registers and how
+  /// many of them the filter excludes.
+  pub fn record_plan(
+    &mut self,
+    origin: &str,
+    registered: usize,
+    filtered_out: usize,
+  ) {
+    if registered == 0 {
+      self.empty.push(origin.to_string());
+    } else if filtered_out == registered {
+      self.filtered_out.push(origin.to_string());
+    }
+  }
+
+  /// Warns about the files which didn't run anything, where `kind` is what
+  /// they should register, like "tests".
+  pub fn report(&self, kind: &str) {
+    let cwd =
+      Url::from_directory_path(std::env::current_dir().unwrap()).unwrap();
+    if !self.empty.is_empty() {
+      log::warn!(
+        "{} {} no {kind}:",
+        colors::yellow("Warning"),
+        display::pluralize(self.empty.len(), "file contains", "files contain"),
+      );
+      for origin in &self.empty {
+        log::warn!("  {}", to_relative_path_or_remote_url(&cwd, origin));
+      }
+    }
+    if !self.filtered_out.is_empty() {
+      log::warn!(
+        "{} The filter matched none of the {kind} of {}:",
+        colors::yellow("Warning"),
+        display::pluralize(self.filtered_out.len(), "file", "files"),
+      );
+      for origin in &self.filtered_out {
+        log::warn!("  {}", to_relative_path_or_remote_url(&cwd, origin));
+      }
+    }
+  }
+
+  /// Errors if there are files which don't register anything, as opposed to
+  /// files where the filter didn't match anything.
+  pub fn check(&self, kind: &str) -> Result<(), AnyError> {
+    if self.empty.is_empty() {
+      return Ok(());
+    }
+    Err(generic_error(format!(
+      "Failed because {} no {kind}",
+      display::pluralize(self.empty.len(), "file contains", "files contain")
+    )))
+  }
+}
+
+/// The version of the schema of the test run metadata, bumped when a field
+/// is removed or changes meaning.
+const TEST_RUN_METADATA_VERSION: u32 = 1;
+
+/// What a `deno test --coverage` run records about each test module next to
+/// the coverage profiles, so that tooling can map the changed source files
+/// to the test modules covering them.
+#[derive(Debug, Default)]
+struct TestRunMetadata {
+  modules: BTreeMap<String, TestModuleMetadata>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TestModuleMetadata {
+  specifier: String,
+  /// In milliseconds.
+  duration: u64,
+  passed: bool,
+  tests: Vec<TestCaseMetadata>,
+  /// The file and remote scripts with at least one covered range.
+  covered_scripts: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TestCaseMetadata {
+  name: String,
+  /// In milliseconds.
+  duration: u64,
+  result: &'static str,
+}
+
+impl TestRunMetadata {
+  fn module(&mut self, origin: &str) -> &mut TestModuleMetadata {
+    self.modules.entry(origin.to_string()).or_insert_with(|| {
+      TestModuleMetadata {
+        specifier: origin.to_string(),
+        passed: true,
+        ..Default::default()
+      }
+    })
+  }
+
+  fn record_module(
+    &mut self,
+    origin: &str,
+    duration: Duration,
+    covered_scripts: Vec<String>,
+    passed: bool,
+  ) {
+    let module = self.module(origin);
+    module.duration = duration.as_millis() as u64;
+    module.covered_scripts = covered_scripts;
+    module.passed &= passed;
+  }
+
+  fn record_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let (result, passed) = match result {
+      TestResult::Ok => ("ok", true),
+      TestResult::Ignored => ("ignored", true),
+      TestResult::Failed(_) => ("failed", false),
+      TestResult::ExpectedFailure(_) => ("expectedFailure", true),
+      TestResult::UnexpectedlyPassed => ("unexpectedlyPassed", false),
+      TestResult::Cancelled => ("cancelled", false),
+    };
+    let module = self.module(&description.origin);
+    module.passed &= passed;
+    module.tests.push(TestCaseMetadata {
+      name: description.name.clone(),
+      duration: elapsed,
+      result,
+    });
+  }
+
+  fn record_uncaught_error(&mut self, origin: &str) {
+    self.module(origin).passed = false;
+  }
+
+  fn write(&self, coverage_dir: &Path) -> Result<(), AnyError> {
+    #[derive(Serialize)]
+    struct TestRunMetadataJson<'a> {
+      version: u32,
+      modules: Vec<&'a TestModuleMetadata>,
+    }
+
+    let json = TestRunMetadataJson {
+      version: TEST_RUN_METADATA_VERSION,
+      modules: self.modules.values().collect(),
+    };
+    std::fs::create_dir_all(coverage_dir)?;
+    std::fs::write(
+      coverage_dir.join(TEST_RUN_METADATA_FILE_NAME),
+      serde_json::to_string_pretty(&json)?,
+    )?;
+    Ok(())
+  }
+}
+
+trait TestReporter {
+  fn report_register(&mut self, description: &TestDescription);
+  fn report_plan(&mut self, plan: &TestPlan);
+  fn report_wait(&mut self, description: &TestDescription);
+  fn report_output(&mut self, output: &[u8]);
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  );
+  fn report_retry(&mut self, description: &TestDescription, attempt: usize);
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError);
+  fn report_step_register(&mut self, description: &TestStepDescription);
+  fn report_step_wait(&mut self, description: &TestStepDescription);
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  );
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration);
+  /// Waits until the reporter handled all the events, failing if it
+  /// couldn't.
+  fn finish(&mut self) -> Result<(), AnyError> {
+    Ok(())
   }
 }
 
+/// Creates the reporter for the console. When a JUnit report path is given,
+/// the JUnit report is written there instead and the console falls back to
+/// the pretty reporter if the JUnit reporter was selected.
+fn create_reporter(
+  ps: &ProcState,
+  kind: TestReporterConfig,
+  maybe_junit_path: Option<PathBuf>,
+  parallel: bool,
+  echo_output: bool,
+) -> Result<Box<dyn TestReporter + Send>, AnyError> {
+  let stack_trace_options = ps.options.stack_trace_options();
+  let console_reporter: Box<dyn TestReporter + Send> = match kind {
+    TestReporterConfig::Pretty => Box::new(PrettyTestReporter::new(
+      parallel,
+      echo_output,
+      stack_trace_options,
+    )),
+    TestReporterConfig::Dot => {
+      Box::new(DotTestReporter::new(stack_trace_options))
+    }
+    TestReporterConfig::Compact => {
+      Box::new(CompactTestReporter::new(stack_trace_options))
+    }
+    TestReporterConfig::Junit => match maybe_junit_path {
+      Some(_) => Box::new(PrettyTestReporter::new(
+        parallel,
+        echo_output,
+        stack_trace_options,
+      )),
+      None => {
+        return Ok(Box::new(JunitTestReporter::new(None, stack_trace_options)))
+      }
+    },
+    TestReporterConfig::Tap => {
+      Box::new(TapTestReporter::new(echo_output, stack_trace_options))
+    }
+    TestReporterConfig::Module(specifier) => Box::new(CustomTestReporter::new(
+      ps,
+      deno_core::resolve_url_or_path(&specifier)?,
+    )),
+  };
+  Ok(match maybe_junit_path {
+    Some(path) => Box::new(MultiTestReporter {
+      reporters: vec![
+        console_reporter,
+        Box::new(JunitTestReporter::new(Some(path), stack_trace_options)),
+      ],
+    }),
+    None => console_reporter,
+  })
+}
+
 struct PrettyTestReporter {
   parallel: bool,
   echo_output: bool,
@@ -283,10 +625,17 @@ struct PrettyTestReporter {
   cwd: Url,
   did_have_user_output: bool,
   started_tests: bool,
+  /// The last retry of the tests which were retried.
+  retries: HashMap<usize, usize>,
+  stack_trace_options: StackTraceOptions,
 }
 
 impl PrettyTestReporter {
-  fn new(parallel: bool, echo_output: bool) -> PrettyTestReporter {
+  fn new(
+    parallel: bool,
+    echo_output: bool,
+    stack_trace_options: StackTraceOptions,
+  ) -> PrettyTestReporter {
     PrettyTestReporter {
       parallel,
       echo_output,
@@ -295,6 +644,8 @@ impl PrettyTestReporter {
       cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
       did_have_user_output: false,
       started_tests: false,
+      retries: HashMap::new(),
+      stack_trace_options,
     }
   }
 
@@ -307,7 +658,7 @@ impl PrettyTestReporter {
         "{}",
         colors::gray(format!(
           "{} => ",
-          self.to_relative_path_or_remote_url(&description.origin)
+          to_relative_path_or_remote_url(&self.cwd, &description.origin)
         ))
       );
     }
@@ -318,19 +669,6 @@ impl PrettyTestReporter {
     self.last_wait_id = Some(description.id);
   }
 
-  fn to_relative_path_or_remote_url(&self, path_or_url: &str) -> String {
-    let url = Url::parse(path_or_url).unwrap();
-    if url.scheme() == "file" {
-      if let Some(mut r) = self.cwd.make_relative(&url) {
-        if !r.starts_with("../") {
-          r = format!("./{r}");
-        }
-        return r;
-      }
-    }
-    path_or_url.to_string()
-  }
-
   fn force_report_step_wait(&mut self, description: &TestStepDescription) {
     self.write_output_end();
     if !self.in_new_line {
@@ -354,6 +692,12 @@ impl PrettyTestReporter {
       TestStepResult::Ignored => colors::yellow("ignored").to_string(),
       TestStepResult::Pending(_) => colors::gray("pending").to_string(),
       TestStepResult::Failed(_) => colors::red("FAILED").to_string(),
+      TestStepResult::ExpectedFailure(_) => {
+        colors::yellow("failed as expected").to_string()
+      }
+      TestStepResult::UnexpectedlyPassed => {
+        colors::red("UNEXPECTEDLY PASSED").to_string()
+      }
     };
 
     self.write_output_end();
@@ -368,7 +712,7 @@ impl PrettyTestReporter {
     );
 
     if let Some(js_error) = result.error() {
-      let err_string = format_test_error(js_error);
+      let err_string = format_test_error(js_error, &self.stack_trace_options);
       let err_string = format!("{}: {}", colors::red_bold("error"), err_string);
       for line in err_string.lines() {
         println!("{}{}", "  ".repeat(description.level + 1), line);
@@ -384,7 +728,9 @@ impl PrettyTestReporter {
       self.did_have_user_output = false;
     }
   }
+}
 
+impl TestReporter for PrettyTestReporter {
   fn report_register(&mut self, _description: &TestDescription) {}
 
   fn report_plan(&mut self, plan: &TestPlan) {
@@ -398,7 +744,7 @@ impl PrettyTestReporter {
         "running {} {} from {}",
         plan.total,
         inflection,
-        self.to_relative_path_or_remote_url(&plan.origin)
+        to_relative_path_or_remote_url(&self.cwd, &plan.origin)
       ))
     );
     self.in_new_line = true;
@@ -449,8 +795,27 @@ impl PrettyTestReporter {
       TestResult::Ok => colors::green("ok").to_string(),
       TestResult::Ignored => colors::yellow("ignored").to_string(),
       TestResult::Failed(_) => colors::red("FAILED").to_string(),
+      TestResult::ExpectedFailure(_) => {
+        colors::yellow("failed as expected").to_string()
+      }
+      TestResult::UnexpectedlyPassed => {
+        colors::red("UNEXPECTEDLY PASSED").to_string()
+      }
       TestResult::Cancelled => colors::gray("cancelled").to_string(),
     };
+    let status = match (result, self.retries.remove(&description.id)) {
+      (TestResult::Ok, Some(retry)) => format!(
+        "{} {}",
+        status,
+        colors::yellow(format!("(passed on retry {retry})"))
+      ),
+      (TestResult::Failed(_), Some(retry)) => format!(
+        "{} {}",
+        status,
+        colors::gray(format!("({} attempts)", retry + 1))
+      ),
+      _ => status,
+    };
 
     println!(
       " {} {}",
@@ -460,13 +825,17 @@ impl PrettyTestReporter {
     self.in_new_line = true;
   }
 
+  fn report_retry(&mut self, description: &TestDescription, attempt: usize) {
+    self.retries.insert(description.id, attempt);
+  }
+
   fn report_uncaught_error(&mut self, origin: &str, _error: &JsError) {
     if !self.in_new_line {
       println!();
     }
     println!(
       "Uncaught error from {} {}",
-      self.to_relative_path_or_remote_url(origin),
+      to_relative_path_or_remote_url(&self.cwd, origin),
       colors::red("FAILED")
     );
     self.in_new_line = true;
@@ -508,7 +877,7 @@ impl PrettyTestReporter {
         "{}",
         colors::gray(format!(
           "{} =>",
-          self.to_relative_path_or_remote_url(&description.origin)
+          to_relative_path_or_remote_url(&self.cwd, &description.origin)
         ))
       );
       print!(" {} ...", root.name);
@@ -523,190 +892,1427 @@ impl PrettyTestReporter {
   }
 
   fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
-    if !summary.failures.is_empty() || !summary.uncaught_errors.is_empty() {
-      #[allow(clippy::type_complexity)] // Type alias doesn't look better here
-      let mut failures_by_origin: BTreeMap<
-        String,
-        (Vec<(&TestDescription, &JsError)>, Option<&JsError>),
-      > = BTreeMap::default();
-      let mut failure_titles = vec![];
-      for (description, js_error) in &summary.failures {
-        let (failures, _) = failures_by_origin
-          .entry(description.origin.clone())
-          .or_default();
-        failures.push((description, js_error.as_ref()));
-      }
-      for (origin, js_error) in &summary.uncaught_errors {
-        let (_, uncaught_error) =
-          failures_by_origin.entry(origin.clone()).or_default();
-        let _ = uncaught_error.insert(js_error.as_ref());
-      }
-      println!("\n{}\n", colors::white_bold_on_red(" ERRORS "));
-      for (origin, (failures, uncaught_error)) in failures_by_origin {
-        for (description, js_error) in failures {
-          let failure_title = format!(
-            "{} {}",
-            &description.name,
-            colors::gray(format!(
-              "=> {}:{}:{}",
-              self.to_relative_path_or_remote_url(
-                &description.location.file_name
-              ),
-              description.location.line_number,
-              description.location.column_number
-            ))
-          );
-          println!("{}", &failure_title);
-          println!(
-            "{}: {}",
-            colors::red_bold("error"),
-            format_test_error(js_error)
-          );
-          println!();
-          failure_titles.push(failure_title);
-        }
-        if let Some(js_error) = uncaught_error {
-          let failure_title = format!(
-            "{} (uncaught error)",
-            self.to_relative_path_or_remote_url(&origin)
-          );
-          println!("{}", &failure_title);
-          println!(
-            "{}: {}",
-            colors::red_bold("error"),
-            format_test_error(js_error)
-          );
-          println!("This error was not caught from a test and caused the test runner to fail on the referenced module.");
-          println!("It most likely originated from a dangling promise, event/timeout handler or top-level code.");
-          println!();
-          failure_titles.push(failure_title);
-        }
+    report_failures(&self.cwd, summary, &self.stack_trace_options);
+    report_summary_line(summary, elapsed);
+    self.in_new_line = true;
+  }
+}
+
+/// Prints a single character per test, wrapping at the console width. The
+/// details of failures are printed once all tests have run.
+struct DotTestReporter {
+  cwd: Url,
+  width: usize,
+  column: usize,
+  stack_trace_options: StackTraceOptions,
+}
+
+impl DotTestReporter {
+  fn new(stack_trace_options: StackTraceOptions) -> DotTestReporter {
+    DotTestReporter {
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      width: console_size()
+        .map(|size| size.cols as usize)
+        .filter(|cols| *cols > 0)
+        .unwrap_or(80),
+      column: 0,
+      stack_trace_options,
+    }
+  }
+
+  fn print_char(&mut self, c: impl std::fmt::Display) {
+    if self.column >= self.width {
+      println!();
+      self.column = 0;
+    }
+    print!("{c}");
+    self.column += 1;
+    // flush for faster feedback when line buffered
+    std::io::stdout().flush().unwrap();
+  }
+}
+
+impl TestReporter for DotTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+  fn report_plan(&mut self, _plan: &TestPlan) {}
+  fn report_wait(&mut self, _description: &TestDescription) {}
+  fn report_output(&mut self, _output: &[u8]) {}
+
+  fn report_result(
+    &mut self,
+    _description: &TestDescription,
+    result: &TestResult,
+    _elapsed: u64,
+  ) {
+    match result {
+      TestResult::Ok => self.print_char(colors::green(".")),
+      TestResult::Ignored => self.print_char(colors::yellow("i")),
+      TestResult::ExpectedFailure(_) => self.print_char(colors::yellow("x")),
+      TestResult::UnexpectedlyPassed => self.print_char(colors::red("X")),
+      TestResult::Failed(_) | TestResult::Cancelled => {
+        self.print_char(colors::red("F"))
       }
-      println!("{}\n", colors::white_bold_on_red(" FAILURES "));
-      for failure_title in failure_titles {
-        println!("{failure_title}");
+    }
+  }
+
+  fn report_retry(&mut self, _description: &TestDescription, _attempt: usize) {}
+
+  fn report_uncaught_error(&mut self, _origin: &str, _error: &JsError) {}
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    _description: &TestStepDescription,
+    _result: &TestStepResult,
+    _elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    if self.column > 0 {
+      println!();
+      self.column = 0;
+    }
+    report_failures(&self.cwd, summary, &self.stack_trace_options);
+    report_summary_line(summary, elapsed);
+  }
+}
+
+#[derive(Debug, Default)]
+struct CompactModuleState {
+  total: Option<usize>,
+  passed: usize,
+  failed: usize,
+  ignored: usize,
+  expected_failures: usize,
+  unexpectedly_passed: usize,
+  elapsed: u64,
+  has_uncaught_error: bool,
+}
+
+impl CompactModuleState {
+  fn is_done(&self) -> bool {
+    match self.total {
+      Some(total) => {
+        self.passed
+          + self.failed
+          + self.ignored
+          + self.expected_failures
+          + self.unexpectedly_passed
+          >= total
       }
+      None => false,
+    }
+  }
+}
+
+/// Prints a single line per test module once all of its tests have run. The
+/// details of failures are printed once all tests have run.
+struct CompactTestReporter {
+  cwd: Url,
+  modules: IndexMap<String, CompactModuleState>,
+  stack_trace_options: StackTraceOptions,
+}
+
+impl CompactTestReporter {
+  fn new(stack_trace_options: StackTraceOptions) -> CompactTestReporter {
+    CompactTestReporter {
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      modules: IndexMap::new(),
+      stack_trace_options,
     }
+  }
 
-    let status = if summary.has_failed() {
+  fn report_module(&self, origin: &str, state: &CompactModuleState) {
+    let status = if state.failed > 0
+      || state.unexpectedly_passed > 0
+      || state.has_uncaught_error
+    {
       colors::red("FAILED").to_string()
     } else {
       colors::green("ok").to_string()
     };
+    let mut counts =
+      format!("{} passed | {} failed", state.passed, state.failed);
+    if state.ignored > 0 {
+      write!(counts, " | {} ignored", state.ignored).unwrap();
+    }
+    if state.expected_failures > 0 {
+      write!(counts, " | {} failed as expected", state.expected_failures)
+        .unwrap();
+    }
+    if state.unexpectedly_passed > 0 {
+      write!(
+        counts,
+        " | {} unexpectedly passed",
+        state.unexpectedly_passed
+      )
+      .unwrap();
+    }
+    if state.has_uncaught_error {
+      counts.push_str(" | uncaught error");
+    }
+    println!(
+      "{} ... {} {} {}",
+      to_relative_path_or_remote_url(&self.cwd, origin),
+      status,
+      counts,
+      colors::gray(format!(
+        "({})",
+        display::human_elapsed(state.elapsed.into())
+      )),
+    );
+  }
+
+  fn maybe_report_module(&mut self, origin: &str) {
+    if let Some(state) = self.modules.get(origin) {
+      if state.is_done() {
+        let state = self.modules.remove(origin).unwrap();
+        self.report_module(origin, &state);
+      }
+    }
+  }
+}
+
+impl TestReporter for CompactTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    self.modules.entry(plan.origin.clone()).or_default().total =
+      Some(plan.total);
+    self.maybe_report_module(&plan.origin);
+  }
+
+  fn report_wait(&mut self, _description: &TestDescription) {}
+  fn report_output(&mut self, _output: &[u8]) {}
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let state = self.modules.entry(description.origin.clone()).or_default();
+    match result {
+      TestResult::Ok => state.passed += 1,
+      TestResult::Ignored => state.ignored += 1,
+      TestResult::ExpectedFailure(_) => state.expected_failures += 1,
+      TestResult::UnexpectedlyPassed => state.unexpectedly_passed += 1,
+      TestResult::Failed(_) | TestResult::Cancelled => state.failed += 1,
+    }
+    state.elapsed += elapsed;
+    self.maybe_report_module(&description.origin);
+  }
+
+  fn report_retry(&mut self, _description: &TestDescription, _attempt: usize) {}
+
+  fn report_uncaught_error(&mut self, origin: &str, _error: &JsError) {
+    let state = self.modules.entry(origin.to_string()).or_default();
+    state.has_uncaught_error = true;
+    if state.total.is_none() {
+      // the module failed before registering its tests
+      let state = self.modules.remove(origin).unwrap();
+      self.report_module(origin, &state);
+    }
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    _description: &TestStepDescription,
+    _result: &TestStepResult,
+    _elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    // report the modules that didn't finish, for example due to --fail-fast
+    for (origin, state) in std::mem::take(&mut self.modules) {
+      self.report_module(&origin, &state);
+    }
+    report_failures(&self.cwd, summary, &self.stack_trace_options);
+    report_summary_line(summary, elapsed);
+  }
+}
+
+static ANSI_ESCAPE_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(
+    r"[\x1b\x9b][\[()#;?]*(?:[0-9]{1,4}(?:;[0-9]{0,4})*)?[0-9A-PRZcf-nqry=><]",
+  )
+  .unwrap()
+});
+
+#[derive(Debug)]
+enum JunitOutcome {
+  Passed,
+  Skipped,
+  Failed {
+    message: &'static str,
+    maybe_details: Option<String>,
+  },
+  /// An uncaught error of the module, outside of the tests.
+  Error(String),
+}
+
+#[derive(Debug)]
+struct JunitTestCase {
+  name: String,
+  /// In milliseconds.
+  elapsed: u64,
+  outcome: JunitOutcome,
+}
+
+#[derive(Debug, Default)]
+struct JunitTestSuite {
+  cases: Vec<JunitTestCase>,
+}
+
+impl JunitTestSuite {
+  fn count(&self, predicate: impl Fn(&JunitOutcome) -> bool) -> usize {
+    self
+      .cases
+      .iter()
+      .filter(|case| predicate(&case.outcome))
+      .count()
+  }
+
+  fn failures(&self) -> usize {
+    self.count(|outcome| matches!(outcome, JunitOutcome::Failed { .. }))
+  }
+
+  fn errors(&self) -> usize {
+    self.count(|outcome| matches!(outcome, JunitOutcome::Error(_)))
+  }
+
+  fn skipped(&self) -> usize {
+    self.count(|outcome| matches!(outcome, JunitOutcome::Skipped))
+  }
+
+  fn elapsed(&self) -> u64 {
+    self.cases.iter().map(|case| case.elapsed).sum()
+  }
+}
+
+/// Writes a JUnit XML report once all tests have run, with a test suite per
+/// test module, to stdout or to a file. Test steps are reported as separate
+/// test cases named after the path of the step, like "test > step".
+struct JunitTestReporter {
+  cwd: Url,
+  maybe_path: Option<PathBuf>,
+  suites: BTreeMap<String, JunitTestSuite>,
+  stack_trace_options: StackTraceOptions,
+}
+
+impl JunitTestReporter {
+  fn new(
+    maybe_path: Option<PathBuf>,
+    stack_trace_options: StackTraceOptions,
+  ) -> JunitTestReporter {
+    JunitTestReporter {
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      maybe_path,
+      suites: BTreeMap::new(),
+      stack_trace_options,
+    }
+  }
+
+  fn add_case(&mut self, origin: &str, case: JunitTestCase) {
+    self
+      .suites
+      .entry(origin.to_string())
+      .or_default()
+      .cases
+      .push(case);
+  }
+
+  /// Renders the report. The peak memory usage of the run, when reported, is
+  /// written as the properties of the test suites, in bytes.
+  fn render(
+    &self,
+    elapsed: &Duration,
+    maybe_memory: Option<&MemoryReport>,
+  ) -> String {
+    let mut xml = String::new();
+    writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+      xml,
+      r#"<testsuites name="deno test" tests="{}" failures="{}" errors="{}" skipped="{}" time="{}">"#,
+      self.suites.values().map(|s| s.cases.len()).sum::<usize>(),
+      self.suites.values().map(|s| s.failures()).sum::<usize>(),
+      self.suites.values().map(|s| s.errors()).sum::<usize>(),
+      self.suites.values().map(|s| s.skipped()).sum::<usize>(),
+      format_junit_time(elapsed.as_millis() as u64),
+    )
+    .unwrap();
+    for (origin, suite) in &self.suites {
+      let suite_name =
+        escape_xml(&to_relative_path_or_remote_url(&self.cwd, origin));
+      writeln!(
+        xml,
+        r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{}">"#,
+        suite_name,
+        suite.cases.len(),
+        suite.failures(),
+        suite.errors(),
+        suite.skipped(),
+        format_junit_time(suite.elapsed()),
+      )
+      .unwrap();
+      // the schema only allows the properties in the test suites, and before
+      // their test cases, so the ones of the run are repeated in each of them
+      if let Some(memory) = maybe_memory {
+        writeln!(xml, "    <properties>").unwrap();
+        for (name, value) in [
+          ("peak_rss", memory.peak_rss),
+          ("peak_heap_used", memory.peak_heap_used),
+          ("peak_heap_total", memory.peak_heap_total),
+        ] {
+          writeln!(xml, r#"      <property name="{name}" value="{value}"/>"#)
+            .unwrap();
+        }
+        writeln!(xml, "    </properties>").unwrap();
+      }
+      for case in &suite.cases {
+        let attributes = format!(
+          r#"name="{}" classname="{}" time="{}""#,
+          escape_xml(&case.name),
+          suite_name,
+          format_junit_time(case.elapsed),
+        );
+        match &case.outcome {
+          JunitOutcome::Passed => {
+            writeln!(xml, "    <testcase {attributes}/>").unwrap();
+          }
+          JunitOutcome::Skipped => {
+            writeln!(xml, "    <testcase {attributes}>").unwrap();
+            writeln!(xml, "      <skipped/>").unwrap();
+            writeln!(xml, "    </testcase>").unwrap();
+          }
+          JunitOutcome::Failed {
+            message,
+            maybe_details,
+          } => {
+            writeln!(xml, "    <testcase {attributes}>").unwrap();
+            match maybe_details {
+              Some(details) => writeln!(
+                xml,
+                r#"      <failure message="{}">{}</failure>"#,
+                message,
+                escape_xml(details),
+              ),
+              None => {
+                writeln!(xml, r#"      <failure message="{message}"/>"#)
+              }
+            }
+            .unwrap();
+            writeln!(xml, "    </testcase>").unwrap();
+          }
+          JunitOutcome::Error(details) => {
+            writeln!(xml, "    <testcase {attributes}>").unwrap();
+            writeln!(
+              xml,
+              r#"      <error message="uncaught error">{}</error>"#,
+              escape_xml(details),
+            )
+            .unwrap();
+            writeln!(xml, "    </testcase>").unwrap();
+          }
+        }
+      }
+      writeln!(xml, "  </testsuite>").unwrap();
+    }
+    writeln!(xml, "</testsuites>").unwrap();
+    xml
+  }
+}
+
+impl TestReporter for JunitTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    self.suites.entry(plan.origin.clone()).or_default();
+  }
+
+  fn report_wait(&mut self, _description: &TestDescription) {}
+  fn report_output(&mut self, _output: &[u8]) {}
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let outcome = match result {
+      TestResult::Ok | TestResult::ExpectedFailure(_) => JunitOutcome::Passed,
+      TestResult::Ignored => JunitOutcome::Skipped,
+      TestResult::Failed(error) => JunitOutcome::Failed {
+        message: "failed",
+        maybe_details: Some(format_junit_error(
+          error,
+          &self.stack_trace_options,
+        )),
+      },
+      TestResult::UnexpectedlyPassed => JunitOutcome::Failed {
+        message: "passed unexpectedly",
+        maybe_details: None,
+      },
+      TestResult::Cancelled => JunitOutcome::Failed {
+        message: "cancelled",
+        maybe_details: None,
+      },
+    };
+    self.add_case(
+      &description.origin,
+      JunitTestCase {
+        name: description.name.clone(),
+        elapsed,
+        outcome,
+      },
+    );
+  }
+
+  fn report_retry(&mut self, _description: &TestDescription, _attempt: usize) {}
+
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError) {
+    self.add_case(
+      origin,
+      JunitTestCase {
+        name: "(uncaught error)".to_string(),
+        elapsed: 0,
+        outcome: JunitOutcome::Error(format_junit_error(
+          error,
+          &self.stack_trace_options,
+        )),
+      },
+    );
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let outcome = match result {
+      TestStepResult::Ok | TestStepResult::ExpectedFailure(_) => {
+        JunitOutcome::Passed
+      }
+      TestStepResult::Ignored => JunitOutcome::Skipped,
+      TestStepResult::Failed(maybe_error) => JunitOutcome::Failed {
+        message: "failed",
+        maybe_details: maybe_error
+          .as_deref()
+          .map(|error| format_junit_error(error, &self.stack_trace_options)),
+      },
+      TestStepResult::Pending(maybe_error) => JunitOutcome::Failed {
+        message: "incomplete",
+        maybe_details: maybe_error
+          .as_deref()
+          .map(|error| format_junit_error(error, &self.stack_trace_options)),
+      },
+      TestStepResult::UnexpectedlyPassed => JunitOutcome::Failed {
+        message: "passed unexpectedly",
+        maybe_details: None,
+      },
+    };
+
+    let mut names = vec![description.name.as_str()];
+    let mut parent_id = description.parent_id;
+    while let Some(parent) = test_steps.get(&parent_id) {
+      names.push(&parent.name);
+      parent_id = parent.parent_id;
+    }
+    names.push(&description.root_name);
+    names.reverse();
+    self.add_case(
+      &description.origin,
+      JunitTestCase {
+        name: names.join(" > "),
+        elapsed,
+        outcome,
+      },
+    );
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    let xml = self.render(elapsed, summary.memory.as_ref());
+    match &self.maybe_path {
+      Some(path) => {
+        if let Err(err) = std::fs::write(path, xml) {
+          log::error!(
+            "{} Failed writing the JUnit report to {}: {}",
+            colors::red_bold("error:"),
+            path.display(),
+            err
+          );
+        }
+      }
+      None => print!("{xml}"),
+    }
+  }
+}
+
+fn format_junit_error(error: &JsError, options: &StackTraceOptions) -> String {
+  ANSI_ESCAPE_RE
+    .replace_all(&format_test_error(error, options), "")
+    .to_string()
+}
+
+/// Formats milliseconds as the seconds JUnit expects.
+fn format_junit_time(elapsed: u64) -> String {
+  format!("{:.3}", elapsed as f64 / 1000.0)
+}
+
+/// Escapes the text of an XML attribute or element, dropping the control
+/// characters XML 1.0 doesn't allow.
+fn escape_xml(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '&' => escaped.push_str("&amp;"),
+      '<' => escaped.push_str("&lt;"),
+      '>' => escaped.push_str("&gt;"),
+      '"' => escaped.push_str("&quot;"),
+      '\'' => escaped.push_str("&apos;"),
+      '\t' | '\n' | '\r' => escaped.push(c),
+      c if (c as u32) < 0x20 => {}
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// Whether a TAP test point is ok, its directive and, for failures, the
+/// message and the error of its YAML diagnostic.
+#[derive(Debug)]
+struct TapOutcome {
+  ok: bool,
+  maybe_directive: Option<&'static str>,
+  /// The message of a failure and the stack of its error.
+  maybe_failure: Option<(String, Option<String>)>,
+}
+
+impl TapOutcome {
+  fn ok() -> Self {
+    Self {
+      ok: true,
+      maybe_directive: None,
+      maybe_failure: None,
+    }
+  }
+
+  fn skipped() -> Self {
+    Self {
+      ok: true,
+      maybe_directive: Some("SKIP"),
+      maybe_failure: None,
+    }
+  }
+
+  fn failed(message: impl Into<String>, maybe_stack: Option<String>) -> Self {
+    Self {
+      ok: false,
+      maybe_directive: None,
+      maybe_failure: Some((message.into(), maybe_stack)),
+    }
+  }
+
+  fn failed_with_error(error: &JsError, options: &StackTraceOptions) -> Self {
+    let message = ANSI_ESCAPE_RE
+      .replace_all(&error.exception_message, "")
+      .to_string();
+    Self::failed(message, Some(format_junit_error(error, options)))
+  }
+}
+
+/// Prints the results in the TAP version 14 format, with a test point per
+/// test and the plan once all tests have run. The steps of a test are
+/// printed as subtests along with the test once it completes, so that the
+/// lines of tests running in parallel aren't interleaved.
+struct TapTestReporter {
+  cwd: Url,
+  echo_output: bool,
+  test_count: usize,
+  /// The lines of the completed steps of a test or step, by its id, along
+  /// with the number of steps.
+  step_lines: HashMap<usize, (usize, Vec<String>)>,
+  /// The output of the tests since the last test point. With `--parallel`
+  /// it can't be told apart between the modules running at the same time.
+  output: Vec<u8>,
+  stack_trace_options: StackTraceOptions,
+}
+
+impl TapTestReporter {
+  fn new(
+    echo_output: bool,
+    stack_trace_options: StackTraceOptions,
+  ) -> TapTestReporter {
+    println!("TAP version 14");
+    TapTestReporter {
+      cwd: Url::from_directory_path(std::env::current_dir().unwrap()).unwrap(),
+      echo_output,
+      test_count: 0,
+      step_lines: HashMap::new(),
+      output: Vec::new(),
+      stack_trace_options,
+    }
+  }
+
+  fn print_test_point(
+    &mut self,
+    name: &str,
+    location: Option<&TestLocation>,
+    outcome: TapOutcome,
+    maybe_steps: Option<(usize, Vec<String>)>,
+  ) {
+    self.test_count += 1;
+    let lines = format_tap_test_point(
+      self.test_count,
+      name,
+      location,
+      outcome,
+      maybe_steps,
+    );
+    self.print_lines(lines);
+  }
+
+  /// Prints the lines preceded by the buffered output with a single write,
+  /// so that they're never split by a partial line of output.
+  fn print_lines(&mut self, lines: Vec<String>) {
+    let mut text = String::new();
+    if !self.output.is_empty() {
+      // the output of tests is printed as comments to keep the report valid
+      for line in String::from_utf8_lossy(&self.output).lines() {
+        writeln!(text, "# {line}").unwrap();
+      }
+      self.output.clear();
+    }
+    for line in lines {
+      writeln!(text, "{line}").unwrap();
+    }
+    let mut stdout = std::io::stdout().lock();
+    let _ = stdout.write_all(text.as_bytes());
+    let _ = stdout.flush();
+  }
+}
+
+impl TestReporter for TapTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+  fn report_plan(&mut self, _plan: &TestPlan) {}
+  fn report_wait(&mut self, _description: &TestDescription) {}
+
+  fn report_output(&mut self, output: &[u8]) {
+    if self.echo_output {
+      self.output.extend_from_slice(output);
+    }
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    _elapsed: u64,
+  ) {
+    let outcome = match result {
+      TestResult::Ok | TestResult::ExpectedFailure(_) => TapOutcome::ok(),
+      TestResult::Ignored => TapOutcome::skipped(),
+      TestResult::Failed(error) => {
+        TapOutcome::failed_with_error(error, &self.stack_trace_options)
+      }
+      TestResult::UnexpectedlyPassed => {
+        TapOutcome::failed(UNEXPECTED_PASS_MESSAGE, None)
+      }
+      TestResult::Cancelled => TapOutcome::failed("cancelled", None),
+    };
+    let maybe_steps = self.step_lines.remove(&description.id);
+    self.print_test_point(
+      &description.name,
+      Some(&description.location),
+      outcome,
+      maybe_steps,
+    );
+  }
+
+  fn report_retry(&mut self, _description: &TestDescription, _attempt: usize) {}
+
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError) {
+    let name = format!(
+      "{} (uncaught error)",
+      to_relative_path_or_remote_url(&self.cwd, origin)
+    );
+    self.print_test_point(
+      &name,
+      None,
+      TapOutcome::failed_with_error(error, &self.stack_trace_options),
+      None,
+    );
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    _elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let outcome = match result {
+      TestStepResult::Ok | TestStepResult::ExpectedFailure(_) => {
+        TapOutcome::ok()
+      }
+      TestStepResult::Ignored => TapOutcome::skipped(),
+      TestStepResult::Failed(Some(error)) => {
+        TapOutcome::failed_with_error(error, &self.stack_trace_options)
+      }
+      TestStepResult::Failed(None) => TapOutcome::failed("failed", None),
+      TestStepResult::Pending(maybe_error) => TapOutcome::failed(
+        "incomplete",
+        maybe_error
+          .as_deref()
+          .map(|error| format_junit_error(error, &self.stack_trace_options)),
+      ),
+      TestStepResult::UnexpectedlyPassed => {
+        TapOutcome::failed(UNEXPECTED_PASS_MESSAGE, None)
+      }
+    };
+    let maybe_steps = self.step_lines.remove(&description.id);
+    let (count, lines) =
+      self.step_lines.entry(description.parent_id).or_default();
+    *count += 1;
+    lines.extend(format_tap_test_point(
+      *count,
+      &description.name,
+      Some(&description.location),
+      outcome,
+      maybe_steps,
+    ));
+  }
+
+  fn report_summary(&mut self, _summary: &TestSummary, _elapsed: &Duration) {
+    let plan = format!("1..{}", self.test_count);
+    self.print_lines(vec![plan]);
+  }
+}
+
+/// Formats a test point, preceded by its subtests and followed by the YAML
+/// diagnostic of a failure. The lines of the subtests are indented by four
+/// spaces and the ones of the diagnostic by two.
+fn format_tap_test_point(
+  number: usize,
+  name: &str,
+  location: Option<&TestLocation>,
+  outcome: TapOutcome,
+  maybe_steps: Option<(usize, Vec<String>)>,
+) -> Vec<String> {
+  let mut lines = Vec::new();
+  if let Some((count, step_lines)) = maybe_steps {
+    lines.push(format!("# Subtest: {}", escape_tap(name)));
+    lines.extend(step_lines.into_iter().map(|line| format!("    {line}")));
+    lines.push(format!("    1..{count}"));
+  }
+  let mut line = format!(
+    "{} {} - {}",
+    if outcome.ok { "ok" } else { "not ok" },
+    number,
+    escape_tap(name)
+  );
+  if let Some(directive) = outcome.maybe_directive {
+    write!(line, " # {directive}").unwrap();
+  }
+  lines.push(line);
+  if let Some((message, maybe_stack)) = outcome.maybe_failure {
+    lines.push("  ---".to_string());
+    lines.push(format!("  message: {}", yaml_string(&message)));
+    lines.push("  severity: fail".to_string());
+    if let Some(location) = location {
+      lines.push(format!("  file: {}", yaml_string(&location.file_name)));
+      lines.push(format!("  line: {}", location.line_number));
+    }
+    if let Some(stack) = maybe_stack {
+      lines.push("  stack: |-".to_string());
+      for stack_line in stack.lines() {
+        lines.push(format!("    {stack_line}"));
+      }
+    }
+    lines.push("  ...".to_string());
+  }
+  lines
+}
+
+/// Escapes the description of a TAP test point, where `#` would start a
+/// directive, and keeps it on a single line.
+fn escape_tap(text: &str) -> String {
+  let mut escaped = String::with_capacity(text.len());
+  for c in text.chars() {
+    match c {
+      '\\' | '#' => {
+        escaped.push('\\');
+        escaped.push(c);
+      }
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      c => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// Quotes a string for YAML, which accepts JSON strings.
+fn yaml_string(text: &str) -> String {
+  serde_json::to_string(text).unwrap()
+}
+
+/// Forwards the events to several reporters, like the console reporter and
+/// the one writing the JUnit report to a file.
+struct MultiTestReporter {
+  reporters: Vec<Box<dyn TestReporter + Send>>,
+}
+
+impl TestReporter for MultiTestReporter {
+  fn report_register(&mut self, description: &TestDescription) {
+    for reporter in &mut self.reporters {
+      reporter.report_register(description);
+    }
+  }
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    for reporter in &mut self.reporters {
+      reporter.report_plan(plan);
+    }
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    for reporter in &mut self.reporters {
+      reporter.report_wait(description);
+    }
+  }
+
+  fn report_output(&mut self, output: &[u8]) {
+    for reporter in &mut self.reporters {
+      reporter.report_output(output);
+    }
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    for reporter in &mut self.reporters {
+      reporter.report_result(description, result, elapsed);
+    }
+  }
+
+  fn report_retry(&mut self, description: &TestDescription, attempt: usize) {
+    for reporter in &mut self.reporters {
+      reporter.report_retry(description, attempt);
+    }
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError) {
+    for reporter in &mut self.reporters {
+      reporter.report_uncaught_error(origin, error);
+    }
+  }
+
+  fn report_step_register(&mut self, description: &TestStepDescription) {
+    for reporter in &mut self.reporters {
+      reporter.report_step_register(description);
+    }
+  }
+
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    for reporter in &mut self.reporters {
+      reporter.report_step_wait(description);
+    }
+  }
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    tests: &IndexMap<usize, TestDescription>,
+    test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    for reporter in &mut self.reporters {
+      reporter.report_step_result(
+        description,
+        result,
+        elapsed,
+        tests,
+        test_steps,
+      );
+    }
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    for reporter in &mut self.reporters {
+      reporter.report_summary(summary, elapsed);
+    }
+  }
+
+  fn finish(&mut self) -> Result<(), AnyError> {
+    for reporter in &mut self.reporters {
+      reporter.finish()?;
+    }
+    Ok(())
+  }
+}
+
+/// A reporter from the module passed to `--reporter`. The object the module
+/// exports by default gets the events of the run in its callbacks, which may
+/// return promises:
+///
+/// - `plan(event)` before the tests of a module run, with the `origin` of the
+///   module, the `total` of tests to run and how many were `filteredOut`.
+/// - `start(event)` when a test or step starts, with its `origin` and the
+///   description of the `test` or `step`.
+/// - `result(event)` when a test or step finishes, with its `status`, the
+///   `error` if it failed and its `duration` in milliseconds. An uncaught
+///   error of a module is reported without a test.
+/// - `summary(event)` once all tests have run, with the counts of the run
+///   and its `duration`.
+///
+/// The module runs in its own runtime on a separate thread, without the
+/// testing APIs and with the permissions given to the tests. When it throws,
+/// the events after that aren't reported and the run fails.
+struct CustomTestReporter {
+  specifier: ModuleSpecifier,
+  maybe_sender: Option<UnboundedSender<(&'static str, serde_json::Value)>>,
+  maybe_handle: Option<std::thread::JoinHandle<Result<(), AnyError>>>,
+  stack_trace_options: StackTraceOptions,
+}
+
+impl CustomTestReporter {
+  fn new(ps: &ProcState, specifier: ModuleSpecifier) -> Self {
+    let (sender, receiver) = unbounded_channel();
+    let stack_trace_options = ps.options.stack_trace_options();
+    let ps = ps.clone();
+    let reporter_specifier = specifier.clone();
+    let handle = std::thread::spawn(move || {
+      run_local(run_custom_reporter(ps, reporter_specifier, receiver))
+    });
+    Self {
+      specifier,
+      maybe_sender: Some(sender),
+      maybe_handle: Some(handle),
+      stack_trace_options,
+    }
+  }
+
+  fn send(&self, callback: &'static str, event: serde_json::Value) {
+    if let Some(sender) = &self.maybe_sender {
+      // the reporter stopped receiving events if it threw, which is reported
+      // once the run finishes
+      let _ = sender.send((callback, event));
+    }
+  }
+
+  /// Creates the event of a result, where the description is the `test` or
+  /// `step` which finished.
+  fn result_event(
+    &self,
+    origin: &str,
+    (kind, description): (&str, serde_json::Value),
+    status: &str,
+    maybe_error: Option<&JsError>,
+    elapsed: u64,
+  ) -> serde_json::Value {
+    json!({
+      "origin": origin,
+      (kind): description,
+      "status": status,
+      "error": maybe_error
+        .map(|error| format_junit_error(error, &self.stack_trace_options)),
+      "duration": elapsed,
+    })
+  }
+}
+
+/// Runs the module of a custom reporter and passes it the events until all
+/// of them were sent.
+async fn run_custom_reporter(
+  ps: ProcState,
+  specifier: ModuleSpecifier,
+  mut receiver: UnboundedReceiver<(&'static str, serde_json::Value)>,
+) -> Result<(), AnyError> {
+  let permissions =
+    Permissions::from_options(&ps.options.permissions_options())?;
+  let mut worker =
+    create_main_worker(&ps, specifier, PermissionsContainer::new(permissions))
+      .await?;
+  let reporter = worker.load_test_reporter().await?;
+  while let Some((callback, event)) = receiver.recv().await {
+    worker
+      .call_test_reporter(&reporter, callback, event)
+      .await?;
+  }
+  Ok(())
+}
+
+impl TestReporter for CustomTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    self.send("plan", json!(plan));
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    self.send(
+      "start",
+      json!({ "origin": description.origin, "test": description }),
+    );
+  }
+
+  fn report_output(&mut self, _output: &[u8]) {}
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let (status, maybe_error) = match result {
+      TestResult::Ok => ("ok", None),
+      TestResult::Ignored => ("ignored", None),
+      TestResult::Failed(error) => ("failed", Some(error.as_ref())),
+      TestResult::ExpectedFailure(error) => {
+        ("expectedFailure", Some(error.as_ref()))
+      }
+      TestResult::UnexpectedlyPassed => ("unexpectedlyPassed", None),
+      TestResult::Cancelled => ("cancelled", None),
+    };
+    self.send(
+      "result",
+      self.result_event(
+        &description.origin,
+        ("test", json!(description)),
+        status,
+        maybe_error,
+        elapsed,
+      ),
+    );
+  }
+
+  fn report_retry(&mut self, _description: &TestDescription, _attempt: usize) {}
+
+  fn report_uncaught_error(&mut self, origin: &str, error: &JsError) {
+    self.send(
+      "result",
+      self.result_event(
+        origin,
+        ("test", serde_json::Value::Null),
+        "failed",
+        Some(error),
+        0,
+      ),
+    );
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_wait(&mut self, description: &TestStepDescription) {
+    self.send(
+      "start",
+      json!({ "origin": description.origin, "step": description }),
+    );
+  }
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let status = match result {
+      TestStepResult::Ok => "ok",
+      TestStepResult::Ignored => "ignored",
+      TestStepResult::Failed(_) => "failed",
+      TestStepResult::Pending(_) => "pending",
+      TestStepResult::ExpectedFailure(_) => "expectedFailure",
+      TestStepResult::UnexpectedlyPassed => "unexpectedlyPassed",
+    };
+    self.send(
+      "result",
+      self.result_event(
+        &description.origin,
+        ("step", json!(description)),
+        status,
+        result.error(),
+        elapsed,
+      ),
+    );
+  }
+
+  fn report_summary(&mut self, summary: &TestSummary, elapsed: &Duration) {
+    self.send(
+      "summary",
+      json!({
+        "total": summary.total,
+        "passed": summary.passed,
+        "failed": summary.failed,
+        "ignored": summary.ignored,
+        "flaky": summary.flaky,
+        "expectedFailures": summary.expected_failures,
+        "unexpectedlyPassed": summary.unexpected_passes.len(),
+        "passedSteps": summary.passed_steps,
+        "failedSteps": summary.failed_steps,
+        "pendingSteps": summary.pending_steps,
+        "ignoredSteps": summary.ignored_steps,
+        "filteredOut": summary.filtered_out,
+        "duration": elapsed.as_millis() as u64,
+      }),
+    );
+  }
+
+  fn finish(&mut self) -> Result<(), AnyError> {
+    // the channel closes once the sender is dropped, which ends the reporter
+    self.maybe_sender.take();
+    let handle = match self.maybe_handle.take() {
+      Some(handle) => handle,
+      None => return Ok(()),
+    };
+    handle.join().unwrap().map_err(|err| {
+      let message = match err.downcast_ref::<JsError>() {
+        Some(js_error) => format_js_error(js_error, &self.stack_trace_options),
+        None => format!("{err:#}"),
+      };
+      generic_error(format!(
+        "The test reporter {} threw an error: {}",
+        self.specifier, message
+      ))
+    })
+  }
+}
+
+/// Prints the errors and the list of failures after all tests have run.
+fn report_failures(
+  cwd: &Url,
+  summary: &TestSummary,
+  stack_trace_options: &StackTraceOptions,
+) {
+  if !summary.failures.is_empty()
+    || !summary.unexpected_passes.is_empty()
+    || !summary.uncaught_errors.is_empty()
+  {
+    // the unexpected passes don't have an error
+    #[allow(clippy::type_complexity)] // Type alias doesn't look better here
+    let mut failures_by_origin: BTreeMap<
+      String,
+      (Vec<(&TestDescription, Option<&JsError>)>, Option<&JsError>),
+    > = BTreeMap::default();
+    let mut failure_titles = vec![];
+    for (description, js_error) in &summary.failures {
+      let (failures, _) = failures_by_origin
+        .entry(description.origin.clone())
+        .or_default();
+      failures.push((description, Some(js_error.as_ref())));
+    }
+    for description in &summary.unexpected_passes {
+      let (failures, _) = failures_by_origin
+        .entry(description.origin.clone())
+        .or_default();
+      failures.push((description, None));
+    }
+    for (origin, js_error) in &summary.uncaught_errors {
+      let (_, uncaught_error) =
+        failures_by_origin.entry(origin.clone()).or_default();
+      let _ = uncaught_error.insert(js_error.as_ref());
+    }
+    println!("\n{}\n", colors::white_bold_on_red(" ERRORS "));
+    for (origin, (failures, uncaught_error)) in failures_by_origin {
+      for (description, js_error) in failures {
+        let failure_title = format!(
+          "{} {}",
+          &description.name,
+          colors::gray(format!(
+            "=> {}:{}:{}",
+            to_relative_path_or_remote_url(
+              cwd,
+              &description.location.file_name
+            ),
+            description.location.line_number,
+            description.location.column_number
+          ))
+        );
+        println!("{}", &failure_title);
+        let message = match js_error {
+          Some(js_error) => format_test_error(js_error, stack_trace_options),
+          None => UNEXPECTED_PASS_MESSAGE.to_string(),
+        };
+        println!("{}: {}", colors::red_bold("error"), message);
+        println!();
+        failure_titles.push(failure_title);
+      }
+      if let Some(js_error) = uncaught_error {
+        let failure_title = format!(
+          "{} (uncaught error)",
+          to_relative_path_or_remote_url(cwd, &origin)
+        );
+        println!("{}", &failure_title);
+        println!(
+          "{}: {}",
+          colors::red_bold("error"),
+          format_test_error(js_error, stack_trace_options)
+        );
+        println!("This error was not caught from a test and caused the test runner to fail on the referenced module.");
+        println!("It most likely originated from a dangling promise, event/timeout handler or top-level code.");
+        println!();
+        failure_titles.push(failure_title);
+      }
+    }
+    println!("{}\n", colors::white_bold_on_red(" FAILURES "));
+    for failure_title in failure_titles {
+      println!("{failure_title}");
+    }
+  }
+}
+
+/// Prints the final line with the counts of passed, failed and ignored tests.
+fn report_summary_line(summary: &TestSummary, elapsed: &Duration) {
+  let status = if summary.has_failed() {
+    colors::red("FAILED").to_string()
+  } else {
+    colors::green("ok").to_string()
+  };
+
+  let get_steps_text = |count: usize| -> String {
+    if count == 0 {
+      String::new()
+    } else {
+      format!(" ({})", display::pluralize(count, "step", "steps"))
+    }
+  };
+
+  let mut summary_result = String::new();
+
+  write!(
+    summary_result,
+    "{} passed{} | {} failed{}",
+    summary.passed,
+    get_steps_text(summary.passed_steps),
+    summary.failed,
+    get_steps_text(summary.failed_steps + summary.pending_steps),
+  )
+  .unwrap();
 
-    let get_steps_text = |count: usize| -> String {
-      if count == 0 {
-        String::new()
-      } else if count == 1 {
-        " (1 step)".to_string()
-      } else {
-        format!(" ({count} steps)")
-      }
-    };
+  if summary.flaky > 0 {
+    write!(summary_result, " | {} flaky", summary.flaky).unwrap();
+  }
 
-    let mut summary_result = String::new();
+  let expected_failure_steps = get_steps_text(summary.expected_failure_steps);
+  if summary.expected_failures > 0 || !expected_failure_steps.is_empty() {
+    write!(
+      summary_result,
+      " | {} failed as expected{}",
+      summary.expected_failures, expected_failure_steps
+    )
+    .unwrap()
+  }
 
+  let unexpectedly_passed_steps =
+    get_steps_text(summary.unexpectedly_passed_steps);
+  if !summary.unexpected_passes.is_empty()
+    || !unexpectedly_passed_steps.is_empty()
+  {
     write!(
       summary_result,
-      "{} passed{} | {} failed{}",
-      summary.passed,
-      get_steps_text(summary.passed_steps),
-      summary.failed,
-      get_steps_text(summary.failed_steps + summary.pending_steps),
+      " | {} unexpectedly passed{}",
+      summary.unexpected_passes.len(),
+      unexpectedly_passed_steps
     )
-    .unwrap();
+    .unwrap()
+  }
 
-    let ignored_steps = get_steps_text(summary.ignored_steps);
-    if summary.ignored > 0 || !ignored_steps.is_empty() {
-      write!(
-        summary_result,
-        " | {} ignored{}",
-        summary.ignored, ignored_steps
-      )
-      .unwrap()
-    }
+  let ignored_steps = get_steps_text(summary.ignored_steps);
+  if summary.ignored > 0 || !ignored_steps.is_empty() {
+    write!(
+      summary_result,
+      " | {} ignored{}",
+      summary.ignored, ignored_steps
+    )
+    .unwrap()
+  }
+
+  if summary.measured > 0 {
+    write!(summary_result, " | {} measured", summary.measured,).unwrap();
+  }
+
+  if summary.fail_fast_skipped > 0 {
+    write!(
+      summary_result,
+      " | {} skipped by --fail-fast-per-file",
+      summary.fail_fast_skipped
+    )
+    .unwrap()
+  }
 
-    if summary.measured > 0 {
-      write!(summary_result, " | {} measured", summary.measured,).unwrap();
+  if summary.filtered_out > 0 {
+    write!(summary_result, " | {} filtered out", summary.filtered_out).unwrap();
+    if summary.excluded_by_tag > 0 {
+      write!(summary_result, " ({} by tag)", summary.excluded_by_tag).unwrap()
     }
+  };
 
-    if summary.filtered_out > 0 {
-      write!(summary_result, " | {} filtered out", summary.filtered_out)
-        .unwrap()
-    };
+  println!(
+    "\n{} | {} {}\n",
+    status,
+    summary_result,
+    colors::gray(format!("({})", display::human_elapsed(elapsed.as_millis()))),
+  );
 
-    println!(
-      "\n{} | {} {}\n",
-      status,
-      summary_result,
-      colors::gray(format!(
-        "({})",
-        display::human_elapsed(elapsed.as_millis())
-      )),
-    );
-    self.in_new_line = true;
+  if let Some(memory) = &summary.memory {
+    println!("{}\n", colors::gray(memory.to_string()));
   }
 }
 
-fn abbreviate_test_error(js_error: &JsError) -> JsError {
-  let mut js_error = js_error.clone();
-  let frames = std::mem::take(&mut js_error.frames);
-
-  // check if there are any stack frames coming from user code
-  let should_filter = frames.iter().any(|f| {
-    if let Some(file_name) = &f.file_name {
-      !(file_name.starts_with("[internal:")
-        || file_name.starts_with("internal:"))
-    } else {
-      true
+fn to_relative_path_or_remote_url(cwd: &Url, path_or_url: &str) -> String {
+  let url = Url::parse(path_or_url).unwrap();
+  if url.scheme() == "file" {
+    if let Some(r) = cwd.make_relative(&url) {
+      // show the file names as they are rather than percent-encoded
+      let r = percent_decode_str(&r).decode_utf8_lossy();
+      if !r.starts_with("../") {
+        return format!("./{r}");
+      }
+      return r.to_string();
     }
-  });
-
-  if should_filter {
-    let mut frames = frames
-      .into_iter()
-      .rev()
-      .skip_while(|f| {
-        if let Some(file_name) = &f.file_name {
-          file_name.starts_with("[internal:")
-            || file_name.starts_with("internal:")
-        } else {
-          false
-        }
-      })
-      .into_iter()
-      .collect::<Vec<_>>();
-    frames.reverse();
-    js_error.frames = frames;
-  } else {
-    js_error.frames = frames;
   }
+  path_or_url.to_string()
+}
 
-  js_error.cause = js_error
-    .cause
-    .as_ref()
-    .map(|e| Box::new(abbreviate_test_error(e)));
-  js_error.aggregated = js_error
-    .aggregated
-    .as_ref()
-    .map(|es| es.iter().map(abbreviate_test_error).collect());
-  js_error
-}
-
-// This function prettifies `JsError` and applies some changes specifically for
-// test runner purposes:
-//
-// - filter out stack frames:
-//   - if stack trace consists of mixed user and internal code, the frames
-//     below the first user code frame are filtered out
-//   - if stack trace consists only of internal code it is preserved as is
-pub fn format_test_error(js_error: &JsError) -> String {
-  let mut js_error = abbreviate_test_error(js_error);
+// This function prettifies `JsError` for test runner purposes. The frames of
+// internal code are dropped like for any error, unless the stack trace only
+// consists of internal code or `--full-stack-traces` is passed.
+pub fn format_test_error(
+  js_error: &JsError,
+  options: &StackTraceOptions,
+) -> String {
+  let mut js_error = js_error.clone();
   js_error.exception_message = js_error
     .exception_message
     .trim_start_matches("Uncaught ")
     .to_string();
-  format_js_error(&js_error)
+  format_js_error(&js_error, options)
 }
 
 /// Test a single specifier as documentation containing test programs, an executable test module or
@@ -719,27 +2325,146 @@ async fn test_specifier(
   sender: TestEventSender,
   fail_fast_tracker: FailFastTracker,
   options: TestSpecifierOptions,
-) -> Result<(), AnyError> {
-  let stdout = StdioPipe::File(sender.stdout());
-  let stderr = StdioPipe::File(sender.stderr());
-  let mut worker = create_main_worker_for_test_or_bench(
-    ps,
+) -> Result<Vec<String>, AnyError> {
+  let timeout_watchdog = TestTimeoutWatchdog::default();
+  let registrations = TestRegistrations::default();
+  let mut filter = options.filter.clone();
+  if let Some(names) = options.included_tests.get(&specifier) {
+    filter.include = Some(names.clone());
+  }
+  let mut start_index = 0;
+  let result = loop {
+    let stdout = StdioPipe::File(sender.stdout());
+    let stderr = StdioPipe::File(sender.stderr());
+    let mut worker = create_main_worker_for_test_or_bench(
+      ps,
+      specifier.clone(),
+      PermissionsContainer::new(permissions.clone()),
+      vec![ops::testing::init(
+        sender.clone(),
+        fail_fast_tracker.clone(),
+        filter.clone(),
+        options.memory_sampler.clone(),
+        options.update_golden,
+        Some(timeout_watchdog.clone()),
+        registrations.clone(),
+      )],
+      Stdio {
+        stdin: StdioPipe::Inherit,
+        stdout,
+        stderr,
+      },
+    )
+    .await?;
+    timeout_watchdog.set_isolate_handle(worker.isolate_handle());
+
+    let result = tokio::select! {
+      result = worker.run_test_specifier(mode.clone(), start_index) => result,
+      _ = timeout_watchdog.timed_out() => Ok(Vec::new()),
+    };
+    match timeout_watchdog.take_timed_out() {
+      // the worker was terminated, so the tests after the one that timed out
+      // run in a new worker
+      Some(test) => {
+        fail_fast_tracker.add_failure();
+        sender.clone().send(TestEvent::Result(
+          test.id,
+          TestResult::Failed(Box::new(test_timeout_error(test.timeout))),
+          test.timeout.as_millis() as u64,
+        ))?;
+        start_index = test.index + 1;
+      }
+      None => break result,
+    }
+  };
+  timeout_watchdog.stop();
+  result
+}
+
+fn test_timeout_error(timeout: Duration) -> JsError {
+  let message = format!(
+    "Test timed out after {}",
+    display::human_elapsed(timeout.as_millis())
+  );
+  JsError {
+    name: Some("TimeoutError".to_string()),
+    exception_message: format!("TimeoutError: {message}"),
+    message: Some(message),
+    stack: None,
+    cause: None,
+    frames: vec![],
+    source_line: None,
+    source_line_frame_index: None,
+    aggregated: None,
+  }
+}
+
+/// A module synthesized from a code block found in a markdown file or in a
+/// JSDoc comment.
+#[derive(Debug, Clone)]
+pub struct InlineFile {
+  pub file: File,
+  /// The specifier of the file the code block was extracted from.
+  pub origin: ModuleSpecifier,
+  /// The zero-indexed line and column in the original file where each line
+  /// of the synthesized source starts.
+  pub line_origins: Vec<(usize, usize)>,
+}
+
+/// Options for extracting code blocks from documentation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InlineFileOptions {
+  /// Also extract the `@example` sections of JSDoc comments that do not
+  /// contain a fenced code block.
+  pub unfenced_examples: bool,
+}
+
+/// Creates the inline file for the lines of a code block. The `text` must
+/// start at the beginning of the line `text_line_index` of the original file.
+fn create_inline_file(
+  specifier: &ModuleSpecifier,
+  text: &str,
+  text_line_index: usize,
+  line_range: (usize, usize),
+  media_type: MediaType,
+  lines_regex: &Regex,
+) -> InlineFile {
+  // TODO(caspervonb) generate an inline source map
+  let mut file_source = String::new();
+  let mut line_origins = Vec::new();
+  for line in lines_regex.captures_iter(text) {
+    let content = line.get(1).unwrap();
+    let line_start = text[..content.start()]
+      .rfind('\n')
+      .map(|index| index + 1)
+      .unwrap_or(0);
+    let line_index = text_line_index + text[..line_start].matches('\n').count();
+    let column = text[line_start..content.start()].chars().count();
+    line_origins.push((line_index, column));
+    writeln!(file_source, "{}", content.as_str()).unwrap();
+  }
+
+  let file_specifier = deno_core::resolve_url_or_path(&format!(
+    "{}${}-{}{}",
     specifier,
-    PermissionsContainer::new(permissions),
-    vec![ops::testing::init(
-      sender,
-      fail_fast_tracker,
-      options.filter,
-    )],
-    Stdio {
-      stdin: StdioPipe::Inherit,
-      stdout,
-      stderr,
+    line_range.0,
+    line_range.1,
+    media_type.as_ts_extension(),
+  ))
+  .unwrap();
+
+  InlineFile {
+    file: File {
+      local: file_specifier.to_file_path().unwrap(),
+      maybe_types: None,
+      media_type,
+      source: file_source.into(),
+      specifier: file_specifier,
+      maybe_headers: None,
     },
-  )
-  .await?;
-
-  worker.run_test_specifier(mode).await
+    origin: specifier.clone(),
+    line_origins,
+  }
 }
 
 fn extract_files_from_regex_blocks(
@@ -749,7 +2474,7 @@ fn extract_files_from_regex_blocks(
   file_line_index: usize,
   blocks_regex: &Regex,
   lines_regex: &Regex,
-) -> Result<Vec<File>, AnyError> {
+) -> Result<Vec<InlineFile>, AnyError> {
   let files = blocks_regex
     .captures_iter(source)
     .filter_map(|block| {
@@ -794,43 +2519,95 @@ fn extract_files_from_regex_blocks(
       let line_count = block.get(0).unwrap().as_str().split('\n').count();
 
       let body = block.get(2).unwrap();
-      let text = body.as_str();
+      let body_line_index = file_line_index
+        + source[0..body.start()]
+          .chars()
+          .filter(|c| *c == '\n')
+          .count();
 
-      // TODO(caspervonb) generate an inline source map
-      let mut file_source = String::new();
-      for line in lines_regex.captures_iter(text) {
-        let text = line.get(1).unwrap();
-        writeln!(file_source, "{}", text.as_str()).unwrap();
-      }
-
-      let file_specifier = deno_core::resolve_url_or_path(&format!(
-        "{}${}-{}{}",
+      Some(create_inline_file(
         specifier,
-        file_line_index + line_offset + 1,
-        file_line_index + line_offset + line_count + 1,
-        file_media_type.as_ts_extension(),
+        body.as_str(),
+        body_line_index,
+        (
+          file_line_index + line_offset + 1,
+          file_line_index + line_offset + line_count + 1,
+        ),
+        file_media_type,
+        lines_regex,
       ))
-      .unwrap();
-
-      Some(File {
-        local: file_specifier.to_file_path().unwrap(),
-        maybe_types: None,
-        media_type: file_media_type,
-        source: file_source.into(),
-        specifier: file_specifier,
-        maybe_headers: None,
-      })
     })
     .collect();
 
   Ok(files)
 }
 
+/// Extracts the `@example` sections of a JSDoc comment that are not written
+/// as a fenced code block. A section ends at the next tag or at the end of
+/// the comment.
+fn extract_files_from_unfenced_examples(
+  specifier: &ModuleSpecifier,
+  comment_text: &str,
+  media_type: MediaType,
+  file_line_index: usize,
+  lines_regex: &Regex,
+) -> Vec<InlineFile> {
+  fn tag_content(line: &str) -> &str {
+    line.trim_start().trim_start_matches('*').trim_start()
+  }
+
+  let mut lines = Vec::new();
+  let mut offset = 0;
+  for line in comment_text.split('\n') {
+    lines.push((offset, line));
+    offset += line.len() + 1;
+  }
+
+  let mut files = Vec::new();
+  let mut index = 0;
+  while index < lines.len() {
+    if !tag_content(lines[index].1).starts_with("@example") {
+      index += 1;
+      continue;
+    }
+    let example_index = index;
+    index += 1;
+    while index < lines.len() && !tag_content(lines[index].1).starts_with('@') {
+      index += 1;
+    }
+    let body_start = match lines.get(example_index + 1) {
+      Some((offset, _)) => *offset,
+      None => continue,
+    };
+    let body_end = lines
+      .get(index)
+      .map(|(offset, _)| *offset)
+      .unwrap_or(comment_text.len());
+    let body = &comment_text[body_start..body_end];
+    if body.contains("```") || body.lines().all(|l| tag_content(l).is_empty()) {
+      continue;
+    }
+    files.push(create_inline_file(
+      specifier,
+      body,
+      file_line_index + example_index + 1,
+      (
+        file_line_index + example_index + 1,
+        file_line_index + index + 1,
+      ),
+      media_type,
+      lines_regex,
+    ));
+  }
+  files
+}
+
 fn extract_files_from_source_comments(
   specifier: &ModuleSpecifier,
   source: Arc<str>,
   media_type: MediaType,
-) -> Result<Vec<File>, AnyError> {
+  options: InlineFileOptions,
+) -> Result<Vec<InlineFile>, AnyError> {
   let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
     specifier: specifier.as_str().to_string(),
     text_info: deno_ast::SourceTextInfo::new(source),
@@ -853,14 +2630,26 @@ fn extract_files_from_source_comments(
       true
     })
     .flat_map(|comment| {
-      extract_files_from_regex_blocks(
+      let file_line_index =
+        parsed_source.text_info().line_index(comment.start());
+      let mut files = extract_files_from_regex_blocks(
         specifier,
         &comment.text,
         media_type,
-        parsed_source.text_info().line_index(comment.start()),
+        file_line_index,
         &blocks_regex,
         &lines_regex,
-      )
+      )?;
+      if options.unfenced_examples {
+        files.extend(extract_files_from_unfenced_examples(
+          specifier,
+          &comment.text,
+          media_type,
+          file_line_index,
+          &lines_regex,
+        ));
+      }
+      Ok::<_, AnyError>(files)
     })
     .flatten()
     .collect();
@@ -872,7 +2661,7 @@ fn extract_files_from_fenced_blocks(
   specifier: &ModuleSpecifier,
   source: &str,
   media_type: MediaType,
-) -> Result<Vec<File>, AnyError> {
+) -> Result<Vec<InlineFile>, AnyError> {
   // The pattern matches code blocks as well as anything in HTML comment syntax,
   // but it stores the latter without any capturing groups. This way, a simple
   // check can be done to see if a block is inside a comment (and skip typechecking)
@@ -891,10 +2680,13 @@ fn extract_files_from_fenced_blocks(
   )
 }
 
-async fn fetch_inline_files(
+/// Extracts the code blocks in the documentation of the provided specifiers,
+/// which are either markdown files or modules with JSDoc comments.
+pub async fn fetch_inline_files(
   ps: &ProcState,
   specifiers: Vec<ModuleSpecifier>,
-) -> Result<Vec<File>, AnyError> {
+  options: InlineFileOptions,
+) -> Result<Vec<InlineFile>, AnyError> {
   let mut files = Vec::new();
   for specifier in specifiers {
     let fetch_permissions = PermissionsContainer::allow_all();
@@ -911,6 +2703,7 @@ async fn fetch_inline_files(
         &file.specifier,
         file.source,
         file.media_type,
+        options,
       )
     };
 
@@ -920,12 +2713,13 @@ async fn fetch_inline_files(
   Ok(files)
 }
 
-/// Type check a collection of module and document specifiers.
+/// Type check a collection of module and document specifiers, returning how
+/// many documentation tests each of them contains.
 pub async fn check_specifiers(
   ps: &ProcState,
   permissions: Permissions,
   specifiers: Vec<(ModuleSpecifier, TestMode)>,
-) -> Result<(), AnyError> {
+) -> Result<HashMap<String, usize>, AnyError> {
   let lib = ps.options.ts_type_lib_window();
   let inline_files = fetch_inline_files(
     ps,
@@ -939,17 +2733,23 @@ pub async fn check_specifiers(
         }
       })
       .collect(),
+    InlineFileOptions::default(),
   )
   .await?;
 
+  let mut doc_tests = HashMap::new();
+  for inline_file in &inline_files {
+    *doc_tests.entry(inline_file.origin.to_string()).or_insert(0) += 1;
+  }
+
   if !inline_files.is_empty() {
     let specifiers = inline_files
       .iter()
-      .map(|file| file.specifier.clone())
+      .map(|inline_file| inline_file.file.specifier.clone())
       .collect();
 
-    for file in inline_files {
-      ps.file_fetcher.insert_cached(file);
+    for inline_file in inline_files {
+      ps.file_fetcher.insert_cached(inline_file.file);
     }
 
     ps.prepare_module_load(
@@ -962,7 +2762,7 @@ pub async fn check_specifiers(
     .await?;
   }
 
-  let module_specifiers = specifiers
+  let mut module_specifiers = specifiers
     .into_iter()
     .filter_map(|(specifier, mode)| {
       if mode != TestMode::Documentation {
@@ -971,7 +2771,8 @@ pub async fn check_specifiers(
         None
       }
     })
-    .collect();
+    .collect::<Vec<_>>();
+  module_specifiers.extend(ps.resolve_preload_modules()?);
 
   ps.prepare_module_load(
     module_specifiers,
@@ -982,7 +2783,7 @@ pub async fn check_specifiers(
   )
   .await?;
 
-  Ok(())
+  Ok(doc_tests)
 }
 
 /// Test a collection of specifiers with test modes concurrently.
@@ -990,10 +2791,15 @@ async fn test_specifiers(
   ps: &ProcState,
   permissions: &Permissions,
   specifiers_with_mode: Vec<(ModuleSpecifier, TestMode)>,
-  options: TestSpecifierOptions,
+  mut options: TestSpecifierOptions,
 ) -> Result<(), AnyError> {
   let log_level = ps.options.log_level();
   let specifiers_with_mode = if let Some(seed) = ps.options.shuffle_tests() {
+    log::info!(
+      "Shuffling the tests with seed {} {}",
+      seed,
+      colors::gray(format!("(use --shuffle={seed} to reproduce the order)"))
+    );
     let mut rng = SmallRng::seed_from_u64(seed);
     let mut specifiers_with_mode = specifiers_with_mode;
     specifiers_with_mode.sort_by_key(|(specifier, _)| specifier.clone());
@@ -1006,6 +2812,19 @@ async fn test_specifiers(
   let (sender, mut receiver) = unbounded_channel::<TestEvent>();
   let sender = TestEventSender::new(sender);
   let concurrent_jobs = options.concurrent_jobs;
+  let fail_fast_tracker =
+    FailFastTracker::new(options.fail_fast, options.fail_fast_per_file);
+  let reporter_kind = options.reporter.clone();
+  let maybe_junit_path = options.junit_path.clone();
+  let memory_sampler = options.memory_sampler.clone();
+  let fail_on_empty_files = options.fail_on_empty_files;
+  let doc_tests = std::mem::take(&mut options.doc_tests);
+  let maybe_coverage_dir = ps.options.coverage_dir().map(PathBuf::from);
+  let maybe_run_metadata = maybe_coverage_dir
+    .as_ref()
+    .map(|_| Arc::new(Mutex::new(TestRunMetadata::default())));
+  let handler_run_metadata = maybe_run_metadata.clone();
+  let tasks_run_metadata = maybe_run_metadata.clone();
 
   let join_handles =
     specifiers_with_mode
@@ -1015,7 +2834,8 @@ async fn test_specifiers(
         let permissions = permissions.clone();
         let mut sender = sender.clone();
         let options = options.clone();
-        let fail_fast_tracker = FailFastTracker::new(options.fail_fast);
+        let fail_fast_tracker = fail_fast_tracker.for_file();
+        let maybe_run_metadata = tasks_run_metadata.clone();
 
         tokio::task::spawn_blocking(move || {
           if fail_fast_tracker.should_stop() {
@@ -1023,6 +2843,7 @@ async fn test_specifiers(
           }
 
           let origin = specifier.to_string();
+          let started = Instant::now();
           let file_result = run_local(test_specifier(
             &ps,
             permissions,
@@ -1032,6 +2853,18 @@ async fn test_specifiers(
             fail_fast_tracker,
             options,
           ));
+          if let Some(run_metadata) = &maybe_run_metadata {
+            let (covered_scripts, passed) = match &file_result {
+              Ok(covered_scripts) => (covered_scripts.clone(), true),
+              Err(_) => (Vec::new(), false),
+            };
+            run_metadata.lock().record_module(
+              &origin,
+              started.elapsed(),
+              covered_scripts,
+              passed,
+            );
+          }
           if let Err(error) = file_result {
             if error.is::<JsError>() {
               sender.send(TestEvent::UncaughtError(
@@ -1050,10 +2883,13 @@ async fn test_specifiers(
     .buffer_unordered(concurrent_jobs.get())
     .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
 
-  let mut reporter = Box::new(PrettyTestReporter::new(
+  let mut reporter = create_reporter(
+    ps,
+    reporter_kind,
+    maybe_junit_path,
     concurrent_jobs.get() > 1,
     log_level != Some(Level::Error),
-  ));
+  )?;
 
   let handler = {
     tokio::task::spawn(async move {
@@ -1061,7 +2897,9 @@ async fn test_specifiers(
       let mut tests = IndexMap::new();
       let mut test_steps = IndexMap::new();
       let mut tests_with_result = HashSet::new();
+      let mut retried_tests = HashSet::new();
       let mut summary = TestSummary::new();
+      let mut empty_files = EmptyFiles::default();
       let mut used_only = false;
 
       while let Some(event) = receiver.recv().await {
@@ -1074,6 +2912,14 @@ async fn test_specifiers(
           TestEvent::Plan(plan) => {
             summary.total += plan.total;
             summary.filtered_out += plan.filtered_out;
+            summary.excluded_by_tag += plan.excluded_by_tag;
+            empty_files.record_plan(
+              &plan.origin,
+              plan.total
+                + plan.filtered_out
+                + doc_tests.get(&plan.origin).copied().unwrap_or(0),
+              plan.filtered_out,
+            );
 
             if plan.used_only {
               used_only = true;
@@ -1094,6 +2940,9 @@ async fn test_specifiers(
             if tests_with_result.insert(id) {
               let description = tests.get(&id).unwrap();
               match &result {
+                TestResult::Ok if retried_tests.contains(&id) => {
+                  summary.flaky += 1;
+                }
                 TestResult::Ok => {
                   summary.passed += 1;
                 }
@@ -1104,26 +2953,56 @@ async fn test_specifiers(
                   summary.failed += 1;
                   summary.failures.push((description.clone(), error.clone()));
                 }
+                TestResult::ExpectedFailure(_) => {
+                  summary.expected_failures += 1;
+                }
+                TestResult::UnexpectedlyPassed => {
+                  summary.unexpected_passes.push(description.clone());
+                }
                 TestResult::Cancelled => {
                   unreachable!("should be handled in TestEvent::UncaughtError");
                 }
               }
+              if let Some(run_metadata) = &handler_run_metadata {
+                run_metadata.lock().record_result(
+                  description,
+                  &result,
+                  elapsed,
+                );
+              }
               reporter.report_result(description, &result, elapsed);
             }
           }
 
+          TestEvent::Retry(id, attempt) => {
+            retried_tests.insert(id);
+            reporter.report_retry(tests.get(&id).unwrap(), attempt);
+          }
+
           TestEvent::UncaughtError(origin, error) => {
             reporter.report_uncaught_error(&origin, &error);
             summary.failed += 1;
             summary.uncaught_errors.push((origin.clone(), error));
+            let mut maybe_run_metadata =
+              handler_run_metadata.as_ref().map(|m| m.lock());
+            if let Some(run_metadata) = &mut maybe_run_metadata {
+              run_metadata.record_uncaught_error(&origin);
+            }
             for desc in tests.values() {
               if desc.origin == origin && tests_with_result.insert(desc.id) {
                 summary.failed += 1;
+                if let Some(run_metadata) = &mut maybe_run_metadata {
+                  run_metadata.record_result(desc, &TestResult::Cancelled, 0);
+                }
                 reporter.report_result(desc, &TestResult::Cancelled, 0);
               }
             }
           }
 
+          TestEvent::FailFastCutoff(_origin, skipped) => {
+            summary.fail_fast_skipped += skipped;
+          }
+
           TestEvent::StepRegister(description) => {
             reporter.report_step_register(&description);
             test_steps.insert(description.id, description);
@@ -1147,6 +3026,12 @@ async fn test_specifiers(
               TestStepResult::Pending(_) => {
                 summary.pending_steps += 1;
               }
+              TestStepResult::ExpectedFailure(_) => {
+                summary.expected_failure_steps += 1;
+              }
+              TestStepResult::UnexpectedlyPassed => {
+                summary.unexpectedly_passed_steps += 1;
+              }
             }
 
             reporter.report_step_result(
@@ -1161,7 +3046,10 @@ async fn test_specifiers(
       }
 
       let elapsed = Instant::now().duration_since(earlier);
+      summary.memory = memory_sampler.map(|sampler| sampler.stop());
       reporter.report_summary(&summary, &elapsed);
+      reporter.finish()?;
+      empty_files.report("tests");
 
       if used_only {
         return Err(generic_error(
@@ -1169,16 +3057,26 @@ async fn test_specifiers(
         ));
       }
 
-      if summary.failed > 0 {
+      if summary.failed > 0 || !summary.unexpected_passes.is_empty() {
         return Err(generic_error("Test failed"));
       }
 
+      if fail_on_empty_files {
+        empty_files.check("tests")?;
+      }
+
       Ok(())
     })
   };
 
   let (join_results, result) = future::join(join_stream, handler).await;
 
+  if let (Some(coverage_dir), Some(run_metadata)) =
+    (maybe_coverage_dir, maybe_run_metadata)
+  {
+    run_metadata.lock().write(&coverage_dir)?;
+  }
+
   // propagate any errors
   for join_result in join_results {
     join_result??;
@@ -1317,29 +3215,64 @@ pub async fn run_tests(
   .await?;
 
   if !test_options.allow_none && specifiers_with_mode.is_empty() {
-    return Err(generic_error("No test modules found"));
+    return Err(no_files_found_error(
+      "No test modules found",
+      &test_options.files.include,
+    ));
   }
 
-  check_specifiers(&ps, permissions.clone(), specifiers_with_mode.clone())
-    .await?;
+  let doc_tests =
+    check_specifiers(&ps, permissions.clone(), specifiers_with_mode.clone())
+      .await?;
 
   if test_options.no_run {
     return Ok(());
   }
 
-  test_specifiers(
+  let result = test_specifiers(
     &ps,
     &permissions,
     specifiers_with_mode,
     TestSpecifierOptions {
       concurrent_jobs: test_options.concurrent_jobs,
       fail_fast: test_options.fail_fast,
-      filter: TestFilter::from_flag(&test_options.filter),
+      fail_fast_per_file: test_options.fail_fast_per_file,
+      filter: TestFilter {
+        tags: test_options.tags.clone(),
+        skip_tags: test_options.skip_tags.clone(),
+        ..TestFilter::from_flag(&test_options.filter)
+      },
+      included_tests: HashMap::new(),
+      doc_tests,
+      reporter: test_options.reporter,
+      junit_path: test_options.junit_path,
+      memory_sampler: test_options.report_memory.map(MemorySampler::start),
+      fail_on_empty_files: test_options.fail_on_empty_files,
+      update_golden: test_options.update_golden,
     },
   )
-  .await?;
+  .await;
 
-  Ok(())
+  // the permissions of the test modules are clones sharing the usage, so
+  // the report covers all of them
+  if let Some(format) = ps.options.report_unused_permissions() {
+    print_unused_permissions(&permissions, format);
+  }
+
+  result
+}
+
+/// The test modules to run in watch mode after a change.
+struct TestWatchRun {
+  modules: Vec<ModuleSpecifier>,
+  /// The tests to run in the modules where the change only affects some of
+  /// their tests.
+  included_tests: HashMap<ModuleSpecifier, Vec<String>>,
+  /// Which tests the change affects, printed before they run.
+  maybe_description: Option<String>,
+  /// The paths that changed since the previous run, whose parsed sources are
+  /// freed before it, or `None` on the first run.
+  changed_paths: Option<Vec<PathBuf>>,
 }
 
 pub async fn run_tests_with_watch(
@@ -1370,93 +3303,128 @@ pub async fn run_tests_with_watch(
         collect_specifiers(&test_options.files, is_supported_test_path)
       }?;
 
-      let mut paths_to_watch = paths_to_watch_clone;
-      let mut modules_to_reload = if files_changed {
-        Vec::new()
-      } else {
-        test_modules.clone()
-      };
       let graph = ps.create_graph(test_modules.clone()).await?;
       graph_valid_with_cli_options(&graph, &test_modules, &ps.options)?;
 
-      // TODO(@kitsonk) - This should be totally derivable from the graph.
-      for specifier in test_modules {
-        fn get_dependencies<'a>(
-          graph: &'a deno_graph::ModuleGraph,
-          maybe_module: Option<&'a deno_graph::Module>,
-          // This needs to be accessible to skip getting dependencies if they're already there,
-          // otherwise this will cause a stack overflow with circular dependencies
-          output: &mut HashSet<&'a ModuleSpecifier>,
-          no_check: bool,
+      let changes = collect_watch_changes(
+        &ps,
+        &graph,
+        &test_modules,
+        changed.as_deref(),
+        no_check,
+      );
+      let mut paths_to_watch = paths_to_watch_clone;
+      paths_to_watch.extend(changes.paths_to_watch);
+
+      let mut affected_modules = Vec::new();
+      for specifier in &changes.affected_modules {
+        let maybe_analysis = graph
+          .get(specifier)
+          .and_then(|module| {
+            ps.parsed_source_cache
+              .get_parsed_source_from_module(module)
+              .ok()
+              .flatten()
+          })
+          .map(|parsed_source| analyze_test_module(&parsed_source));
+        if let Some(affected) = find_affected_tests(
+          &graph,
+          specifier,
+          maybe_analysis.as_ref(),
+          &changes.changed_specifiers,
+          no_check,
         ) {
-          if let Some(module) = maybe_module {
-            for dep in module.dependencies.values() {
-              if let Some(specifier) = &dep.get_code() {
-                if !output.contains(specifier) {
-                  output.insert(specifier);
-                  get_dependencies(
-                    graph,
-                    graph.get(specifier),
-                    output,
-                    no_check,
-                  );
-                }
-              }
-              if !no_check {
-                if let Some(specifier) = &dep.get_type() {
-                  if !output.contains(specifier) {
-                    output.insert(specifier);
-                    get_dependencies(
-                      graph,
-                      graph.get(specifier),
-                      output,
-                      no_check,
-                    );
-                  }
-                }
-              }
-            }
-          }
+          let maybe_test_count = match &affected {
+            AffectedTests::Named(names) => Some(names.len()),
+            AffectedTests::All => maybe_analysis
+              .and_then(|analysis| analysis.tests)
+              .map(|tests| tests.len()),
+          };
+          affected_modules.push((
+            specifier.clone(),
+            affected,
+            maybe_test_count,
+          ));
         }
+      }
 
-        // This test module and all it's dependencies
-        let mut modules = HashSet::new();
-        modules.insert(&specifier);
-        get_dependencies(&graph, graph.get(&specifier), &mut modules, no_check);
-
-        paths_to_watch.extend(
-          modules
-            .iter()
-            .filter_map(|specifier| specifier.to_file_path().ok()),
-        );
+      let changed = match changed {
+        Some(changed) => changed,
+        None => {
+          return Ok((
+            paths_to_watch,
+            TestWatchRun {
+              modules: test_modules,
+              included_tests: HashMap::new(),
+              maybe_description: None,
+              changed_paths: None,
+            },
+          ))
+        }
+      };
+      let changed_config_paths = changes.changed_config_paths;
+      let used_changed_paths = changes.used_changed_paths;
 
-        if let Some(changed) = &changed {
-          for path in changed.iter().filter_map(|path| {
-            deno_core::resolve_url_or_path(&path.to_string_lossy()).ok()
-          }) {
-            if modules.contains(&path) {
-              modules_to_reload.push(specifier);
-              break;
-            }
+      let run = if !changed_config_paths.is_empty()
+        || (test_options.watch_all_on_change && !affected_modules.is_empty())
+      {
+        let changed_paths = if changed_config_paths.is_empty() {
+          used_changed_paths
+        } else {
+          changed_config_paths
+        };
+        TestWatchRun {
+          maybe_description: Some(describe_affected_tests(
+            0,
+            test_modules.len(),
+            &changed_paths,
+          )),
+          modules: test_modules,
+          included_tests: HashMap::new(),
+          changed_paths: Some(changed),
+        }
+      } else {
+        let mut modules = Vec::new();
+        let mut included_tests = HashMap::new();
+        let mut test_count = 0;
+        let mut whole_modules = 0;
+        for (specifier, affected, maybe_test_count) in affected_modules {
+          match maybe_test_count {
+            Some(count) => test_count += count,
+            None => whole_modules += 1,
+          }
+          if let AffectedTests::Named(names) = affected {
+            included_tests.insert(specifier.clone(), names);
           }
+          modules.push(specifier);
         }
-      }
+        TestWatchRun {
+          maybe_description: (!modules.is_empty()).then(|| {
+            describe_affected_tests(
+              test_count,
+              whole_modules,
+              &used_changed_paths,
+            )
+          }),
+          modules,
+          included_tests,
+          changed_paths: Some(changed),
+        }
+      };
 
-      Ok((paths_to_watch, modules_to_reload))
+      Ok((paths_to_watch, run))
     }
     .map(move |result| {
       if files_changed
-        && matches!(result, Ok((_, ref modules)) if modules.is_empty())
+        && matches!(result, Ok((_, ref run)) if run.modules.is_empty())
       {
         ResolutionResult::Ignore
       } else {
         match result {
-          Ok((paths_to_watch, modules_to_reload)) => {
-            ResolutionResult::Restart {
-              paths_to_watch,
-              result: Ok(modules_to_reload),
-            }
-          }
+          Ok((paths_to_watch, run)) => ResolutionResult::Restart {
+            paths_to_watch,
+            result: Ok(run),
+          },
           Err(e) => ResolutionResult::Restart {
             paths_to_watch,
             result: Err(e),
@@ -1466,13 +3434,20 @@ pub async fn run_tests_with_watch(
     })
   };
 
-  let operation = |modules_to_reload: Vec<ModuleSpecifier>| {
+  let operation = |run: TestWatchRun| {
     let permissions = &permissions;
     let test_options = &test_options;
-    ps.borrow_mut().reset_for_file_watcher();
+    ps.borrow_mut()
+      .reset_for_file_watcher(run.changed_paths.as_deref());
     let ps = ps.borrow().clone();
 
     async move {
+      if let Some(description) = &run.maybe_description {
+        log::info!("{}", description);
+      }
+
+      // the test modules are collected again, so the ones deleted since the
+      // change was resolved are left out
       let specifiers_with_mode = fetch_specifiers_with_test_mode(
         &ps,
         &test_options.files,
@@ -1480,11 +3455,15 @@ pub async fn run_tests_with_watch(
       )
       .await?
       .into_iter()
-      .filter(|(specifier, _)| modules_to_reload.contains(specifier))
+      .filter(|(specifier, _)| run.modules.contains(specifier))
       .collect::<Vec<(ModuleSpecifier, TestMode)>>();
 
-      check_specifiers(&ps, permissions.clone(), specifiers_with_mode.clone())
-        .await?;
+      let doc_tests = check_specifiers(
+        &ps,
+        permissions.clone(),
+        specifiers_with_mode.clone(),
+      )
+      .await?;
 
       if test_options.no_run {
         return Ok(());
@@ -1497,7 +3476,19 @@ pub async fn run_tests_with_watch(
         TestSpecifierOptions {
           concurrent_jobs: test_options.concurrent_jobs,
           fail_fast: test_options.fail_fast,
-          filter: TestFilter::from_flag(&test_options.filter),
+          fail_fast_per_file: test_options.fail_fast_per_file,
+          filter: TestFilter {
+            tags: test_options.tags.clone(),
+            skip_tags: test_options.skip_tags.clone(),
+            ..TestFilter::from_flag(&test_options.filter)
+          },
+          included_tests: run.included_tests,
+          doc_tests,
+          reporter: test_options.reporter.clone(),
+          junit_path: test_options.junit_path.clone(),
+          memory_sampler: test_options.report_memory.map(MemorySampler::start),
+          fail_on_empty_files: test_options.fail_on_empty_files,
+          update_golden: test_options.update_golden,
         },
       )
       .await?;
@@ -1507,12 +3498,14 @@ pub async fn run_tests_with_watch(
   };
 
   let clear_screen = !ps.borrow().options.no_clear_screen();
+  let stack_trace_options = ps.borrow().options.stack_trace_options();
   file_watcher::watch_func(
     resolver,
     operation,
     file_watcher::PrintConfig {
       job_name: "Test".to_string(),
       clear_screen,
+      stack_trace_options,
     },
   )
   .await?;
@@ -1520,23 +3513,172 @@ pub async fn run_tests_with_watch(
   Ok(())
 }
 
-/// Tracks failures for the `--fail-fast` argument in
-/// order to tell when to stop running tests.
+/// The ids of the tests of a module in the order they're registered, so that
+/// the tests keep their ids when the module is evaluated again in a new worker
+/// after a test timed out.
+#[derive(Clone, Default)]
+pub struct TestRegistrations(Arc<Mutex<Vec<usize>>>);
+
+impl TestRegistrations {
+  /// Gets the id of the test registered at `index`, or registers the test
+  /// with the id returned by `next_id`. Returns whether it was registered.
+  pub fn get_or_register(
+    &self,
+    index: usize,
+    next_id: impl FnOnce() -> usize,
+  ) -> (usize, bool) {
+    let mut ids = self.0.lock();
+    match ids.get(index) {
+      Some(id) => (*id, false),
+      None => {
+        let id = next_id();
+        ids.push(id);
+        (id, true)
+      }
+    }
+  }
+}
+
+/// A test running with a timeout.
+#[derive(Debug, Clone, Copy)]
+pub struct TimedTest {
+  pub id: usize,
+  /// The index of the test in the order the tests of the module run.
+  pub index: usize,
+  pub timeout: Duration,
+  started: Instant,
+}
+
+#[derive(Default)]
+struct TestTimeoutState {
+  maybe_isolate_handle: Option<v8::IsolateHandle>,
+  maybe_running: Option<TimedTest>,
+  maybe_timed_out: Option<TimedTest>,
+  is_watching: bool,
+  is_stopped: bool,
+}
+
+#[derive(Default)]
+struct TestTimeoutWatchdogInner {
+  state: Mutex<TestTimeoutState>,
+  condvar: Condvar,
+  timed_out: Notify,
+}
+
+/// Terminates the worker running the tests of a module when a test runs for
+/// longer than its timeout, which also stops synchronous code. The thread
+/// watching the tests is started along with the first test that has a
+/// timeout and runs until `stop()` is called.
+#[derive(Clone, Default)]
+pub struct TestTimeoutWatchdog(Arc<TestTimeoutWatchdogInner>);
+
+impl TestTimeoutWatchdog {
+  /// Sets the isolate terminated when a test times out.
+  pub fn set_isolate_handle(&self, isolate_handle: v8::IsolateHandle) {
+    self.0.state.lock().maybe_isolate_handle = Some(isolate_handle);
+  }
+
+  pub fn test_started(&self, id: usize, index: usize, timeout: Duration) {
+    let mut state = self.0.state.lock();
+    state.maybe_running = Some(TimedTest {
+      id,
+      index,
+      timeout,
+      started: Instant::now(),
+    });
+    if !state.is_watching {
+      state.is_watching = true;
+      let inner = self.0.clone();
+      std::thread::spawn(move || watch_test_timeouts(&inner));
+    }
+    self.0.condvar.notify_one();
+  }
+
+  pub fn test_finished(&self) {
+    self.0.state.lock().maybe_running = None;
+  }
+
+  /// Resolves once a test timed out.
+  pub async fn timed_out(&self) {
+    loop {
+      self.0.timed_out.notified().await;
+      if self.0.state.lock().maybe_timed_out.is_some() {
+        return;
+      }
+    }
+  }
+
+  /// Takes the test that timed out, if any.
+  pub fn take_timed_out(&self) -> Option<TimedTest> {
+    self.0.state.lock().maybe_timed_out.take()
+  }
+
+  pub fn stop(&self) {
+    self.0.state.lock().is_stopped = true;
+    self.0.condvar.notify_one();
+  }
+}
+
+fn watch_test_timeouts(inner: &TestTimeoutWatchdogInner) {
+  let mut state = inner.state.lock();
+  while !state.is_stopped {
+    match state.maybe_running {
+      Some(test) if test.started.elapsed() >= test.timeout => {
+        state.maybe_running = None;
+        state.maybe_timed_out = Some(test);
+        if let Some(isolate_handle) = &state.maybe_isolate_handle {
+          isolate_handle.terminate_execution();
+        }
+        inner.timed_out.notify_one();
+      }
+      Some(test) => {
+        inner
+          .condvar
+          .wait_until(&mut state, test.started + test.timeout);
+      }
+      None => inner.condvar.wait(&mut state),
+    }
+  }
+}
+
+/// Tracks failures for the `--fail-fast` and `--fail-fast-per-file`
+/// arguments in order to tell when to stop running tests.
 #[derive(Clone)]
 pub struct FailFastTracker {
   max_count: Option<usize>,
   failure_count: Arc<AtomicUsize>,
+  max_count_per_file: Option<usize>,
+  file_failure_count: Arc<AtomicUsize>,
 }
 
 impl FailFastTracker {
-  pub fn new(fail_fast: Option<NonZeroUsize>) -> Self {
+  pub fn new(
+    fail_fast: Option<NonZeroUsize>,
+    fail_fast_per_file: Option<NonZeroUsize>,
+  ) -> Self {
     Self {
       max_count: fail_fast.map(|v| v.into()),
       failure_count: Default::default(),
+      max_count_per_file: fail_fast_per_file.map(|v| v.into()),
+      file_failure_count: Default::default(),
+    }
+  }
+
+  /// Creates the tracker of a test module, which shares the count of all
+  /// failures with this one and counts the failures of the module.
+  pub fn for_file(&self) -> Self {
+    Self {
+      max_count: self.max_count,
+      failure_count: self.failure_count.clone(),
+      max_count_per_file: self.max_count_per_file,
+      file_failure_count: Default::default(),
     }
   }
 
   pub fn add_failure(&self) -> bool {
+    self
+      .file_failure_count
+      .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
     if let Some(max_count) = &self.max_count {
       self
         .failure_count
@@ -1554,6 +3696,18 @@ impl FailFastTracker {
       false
     }
   }
+
+  /// Whether the remaining tests of the module should be skipped.
+  pub fn should_stop_file(&self) -> bool {
+    if let Some(max_count) = &self.max_count_per_file {
+      self
+        .file_failure_count
+        .load(std::sync::atomic::Ordering::SeqCst)
+        >= *max_count
+    } else {
+      false
+    }
+  }
 }
 
 #[derive(Clone)]
@@ -1739,6 +3893,103 @@ mod inner_test {
     assert!(!is_supported_test_ext(Path::new("foo.JsON")));
   }
 
+  #[test]
+  fn test_escape_xml() {
+    assert_eq!(
+      escape_xml("<a href='b'>\"c\" & d</a>"),
+      "&lt;a href=&apos;b&apos;&gt;&quot;c&quot; &amp; d&lt;/a&gt;"
+    );
+    assert_eq!(escape_xml("a\tb\nc\u{1b}[0m\u{0}"), "a\tb\nc[0m");
+  }
+
+  #[test]
+  fn test_escape_tap() {
+    assert_eq!(escape_tap("a # b \\ c"), "a \\# b \\\\ c");
+    assert_eq!(escape_tap("a\nb\r"), "a\\nb\\r");
+  }
+
+  #[test]
+  fn test_filter_includes_tags() {
+    let tags = |tags: &[&str]| -> Vec<String> {
+      tags.iter().map(|tag| tag.to_string()).collect()
+    };
+    let filter = TestFilter::default();
+    assert!(filter.includes_tags(&[]));
+    assert!(filter.includes_tags(&tags(&["slow"])));
+
+    let filter = TestFilter {
+      tags: tags(&["slow", "net"]),
+      skip_tags: tags(&["flaky"]),
+      ..Default::default()
+    };
+    assert!(!filter.includes_tags(&[]));
+    assert!(filter.includes_tags(&tags(&["slow"])));
+    assert!(filter.includes_tags(&tags(&["db", "net"])));
+    assert!(!filter.includes_tags(&tags(&["db"])));
+    assert!(!filter.includes_tags(&tags(&["slow", "flaky"])));
+
+    let filter = TestFilter {
+      skip_tags: tags(&["slow"]),
+      ..Default::default()
+    };
+    assert!(filter.includes_tags(&[]));
+    assert!(!filter.includes_tags(&tags(&["slow"])));
+  }
+
+  #[test]
+  fn test_format_tap_test_point() {
+    let location = TestLocation {
+      file_name: "file:///a_test.ts".to_string(),
+      line_number: 4,
+      column_number: 6,
+    };
+    let steps = format_tap_test_point(
+      1,
+      "step",
+      Some(&location),
+      TapOutcome::failed("incomplete", None),
+      None,
+    );
+    assert_eq!(
+      format_tap_test_point(
+        3,
+        "test",
+        Some(&location),
+        TapOutcome::failed("failed", None),
+        Some((
+          2,
+          [vec!["ok 1 - skipped # SKIP".to_string()], steps].concat()
+        )),
+      ),
+      vec![
+        "# Subtest: test",
+        "    ok 1 - skipped # SKIP",
+        "    not ok 2 - step",
+        "      ---",
+        "      message: \"incomplete\"",
+        "      severity: fail",
+        "      file: \"file:///a_test.ts\"",
+        "      line: 4",
+        "      ...",
+        "    1..2",
+        "not ok 3 - test",
+        "  ---",
+        "  message: \"failed\"",
+        "  severity: fail",
+        "  file: \"file:///a_test.ts\"",
+        "  line: 4",
+        "  ...",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_format_junit_time() {
+    assert_eq!(format_junit_time(0), "0.000");
+    assert_eq!(format_junit_time(1234), "1.234");
+    assert_eq!(format_junit_time(5), "0.005");
+  }
+
   #[test]
   fn test_is_supported_test_path() {
     assert!(is_supported_test_path(Path::new(
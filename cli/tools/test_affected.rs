@@ -0,0 +1,683 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Finds the tests affected by a change in `deno test --watch`, so that only
+//! they run again. The modules a test uses are found statically: the imports
+//! it references, directly or through the functions, classes and variables of
+//! its module, are followed through the module graph.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ParsedSource;
+use deno_core::ModuleSpecifier;
+use deno_graph::ModuleGraph;
+
+use crate::proc_state::ProcState;
+use crate::util::display::pluralize;
+use crate::util::fs::canonicalize_path_maybe_not_exists;
+use crate::util::path::path_to_specifier;
+
+/// Adds the modules a module depends on to `output`, following the
+/// dependencies of the dependencies. The types are skipped when not type
+/// checking.
+pub fn get_dependencies<'a>(
+  graph: &'a ModuleGraph,
+  maybe_module: Option<&'a deno_graph::Module>,
+  // This needs to be accessible to skip getting dependencies if they're already there,
+  // otherwise this will cause a stack overflow with circular dependencies
+  output: &mut HashSet<&'a ModuleSpecifier>,
+  no_check: bool,
+) {
+  if let Some(module) = maybe_module {
+    for dep in module.dependencies.values() {
+      add_dependency(graph, dep, output, no_check);
+    }
+  }
+}
+
+fn add_dependency<'a>(
+  graph: &'a ModuleGraph,
+  dep: &'a deno_graph::Dependency,
+  output: &mut HashSet<&'a ModuleSpecifier>,
+  no_check: bool,
+) {
+  if let Some(specifier) = &dep.get_code() {
+    if !output.contains(specifier) {
+      output.insert(specifier);
+      get_dependencies(graph, graph.get(specifier), output, no_check);
+    }
+  }
+  if !no_check {
+    if let Some(specifier) = &dep.get_type() {
+      if !output.contains(specifier) {
+        output.insert(specifier);
+        get_dependencies(graph, graph.get(specifier), output, no_check);
+      }
+    }
+  }
+}
+
+/// A test registered with `Deno.test()` at the top level of its module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticTest {
+  pub name: String,
+  /// The specifiers imported by the module which the test uses.
+  pub imports: HashSet<String>,
+}
+
+/// The tests of a test module, found without running it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestModuleAnalysis {
+  /// The tests of the module, or `None` when some of them can't be found
+  /// statically, e.g. when their names are computed or they're registered in
+  /// a loop or by a library.
+  pub tests: Option<Vec<StaticTest>>,
+  /// The specifiers imported by the module which every test depends on, like
+  /// the imports evaluated only for their side effects.
+  pub shared_imports: HashSet<String>,
+}
+
+/// The identifiers and dynamically imported specifiers a node references.
+#[derive(Debug, Default)]
+struct References {
+  idents: HashSet<String>,
+  imports: HashSet<String>,
+}
+
+impl References {
+  fn of<N: VisitWith<Self>>(node: &N) -> Self {
+    let mut references = Self::default();
+    node.visit_with(&mut references);
+    references
+  }
+
+  fn extend(&mut self, other: Self) {
+    self.idents.extend(other.idents);
+    self.imports.extend(other.imports);
+  }
+}
+
+impl Visit for References {
+  fn visit_ident(&mut self, ident: &ast::Ident) {
+    self.idents.insert(ident.sym.to_string());
+  }
+
+  fn visit_call_expr(&mut self, call_expr: &ast::CallExpr) {
+    if matches!(call_expr.callee, ast::Callee::Import(_)) {
+      if let Some(ast::Expr::Lit(ast::Lit::Str(specifier))) =
+        call_expr.args.first().map(|arg| arg.expr.as_ref())
+      {
+        self.imports.insert(specifier.value.to_string());
+      }
+    }
+    call_expr.visit_children_with(self);
+  }
+}
+
+/// Counts the uses of `Deno.test` and of bindings of `Deno` itself, which
+/// tell whether every test of a module was found.
+#[derive(Default)]
+struct DenoTestCounter {
+  count: usize,
+}
+
+impl Visit for DenoTestCounter {
+  fn visit_member_expr(&mut self, member_expr: &ast::MemberExpr) {
+    if is_deno_test(member_expr) {
+      self.count += 1;
+    }
+    member_expr.visit_children_with(self);
+  }
+
+  fn visit_var_declarator(&mut self, declarator: &ast::VarDeclarator) {
+    // handles `const { test } = Deno` and `const D = Deno`
+    if matches!(
+      declarator.init.as_deref(),
+      Some(ast::Expr::Ident(ident)) if &*ident.sym == "Deno"
+    ) {
+      self.count += 1;
+    }
+    declarator.visit_children_with(self);
+  }
+}
+
+/// Collects the names bound by a pattern.
+#[derive(Default)]
+struct BindingCollector {
+  names: Vec<String>,
+}
+
+impl Visit for BindingCollector {
+  fn visit_binding_ident(&mut self, binding_ident: &ast::BindingIdent) {
+    self.names.push(binding_ident.id.sym.to_string());
+  }
+}
+
+fn is_deno_test(member_expr: &ast::MemberExpr) -> bool {
+  matches!(member_expr.obj.as_ref(), ast::Expr::Ident(ident) if &*ident.sym == "Deno")
+    && matches!(&member_expr.prop, ast::MemberProp::Ident(ident) if &*ident.sym == "test")
+}
+
+/// Gets the call, if the statement is a call of `Deno.test()`.
+fn as_deno_test_call(stmt: &ast::Stmt) -> Option<&ast::CallExpr> {
+  let call_expr = match stmt {
+    ast::Stmt::Expr(expr_stmt) => match expr_stmt.expr.as_ref() {
+      ast::Expr::Call(call_expr) => call_expr,
+      _ => return None,
+    },
+    _ => return None,
+  };
+  match &call_expr.callee {
+    ast::Callee::Expr(callee) => match callee.as_ref() {
+      ast::Expr::Member(member_expr) if is_deno_test(member_expr) => {
+        Some(call_expr)
+      }
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+fn fn_name(expr: Option<&ast::Expr>) -> Option<String> {
+  match expr? {
+    ast::Expr::Fn(fn_expr) => {
+      fn_expr.ident.as_ref().map(|ident| ident.sym.to_string())
+    }
+    _ => None,
+  }
+}
+
+/// Gets the name of a test from the arguments of `Deno.test()`, when it's
+/// known statically.
+fn test_name(call_expr: &ast::CallExpr) -> Option<String> {
+  if call_expr.args.iter().any(|arg| arg.spread.is_some()) {
+    return None;
+  }
+  let mut args = call_expr.args.iter().map(|arg| arg.expr.as_ref());
+  match args.next()? {
+    // `Deno.test("name", fn)` and `Deno.test("name", options, fn)`
+    ast::Expr::Lit(ast::Lit::Str(lit_str)) => Some(lit_str.value.to_string()),
+    ast::Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+      Some(tpl.quasis[0].raw.to_string())
+    }
+    // `Deno.test(function name() {})`
+    expr @ ast::Expr::Fn(_) => fn_name(Some(expr)),
+    // `Deno.test({ name: "name", fn })` and
+    // `Deno.test(options, function name() {})`
+    ast::Expr::Object(obj_lit) => {
+      let maybe_name = obj_lit.props.iter().find_map(|prop| match prop {
+        ast::PropOrSpread::Prop(prop) => match prop.as_ref() {
+          ast::Prop::KeyValue(key_value_prop) => {
+            let is_name = match &key_value_prop.key {
+              ast::PropName::Ident(ident) => &*ident.sym == "name",
+              ast::PropName::Str(lit_str) => &*lit_str.value == "name",
+              _ => false,
+            };
+            match key_value_prop.value.as_ref() {
+              ast::Expr::Lit(ast::Lit::Str(lit_str)) if is_name => {
+                Some(lit_str.value.to_string())
+              }
+              _ => None,
+            }
+          }
+          _ => None,
+        },
+        ast::PropOrSpread::Spread(_) => None,
+      });
+      maybe_name.or_else(|| fn_name(args.next()))
+    }
+    _ => None,
+  }
+}
+
+/// Adds what a top level declaration references, by the names it binds.
+fn add_declaration(
+  decl: &ast::Decl,
+  declarations: &mut HashMap<String, References>,
+  shared: &mut References,
+) {
+  match decl {
+    ast::Decl::Fn(fn_decl) => {
+      declarations
+        .insert(fn_decl.ident.sym.to_string(), References::of(fn_decl));
+    }
+    ast::Decl::Class(class_decl) => {
+      declarations
+        .insert(class_decl.ident.sym.to_string(), References::of(class_decl));
+    }
+    ast::Decl::Var(var_decl) => {
+      for declarator in &var_decl.decls {
+        let mut bindings = BindingCollector::default();
+        declarator.name.visit_with(&mut bindings);
+        for name in bindings.names {
+          declarations.insert(name, References::of(declarator));
+        }
+      }
+    }
+    ast::Decl::TsInterface(interface_decl) => {
+      declarations.insert(
+        interface_decl.id.sym.to_string(),
+        References::of(interface_decl),
+      );
+    }
+    ast::Decl::TsTypeAlias(type_alias_decl) => {
+      declarations.insert(
+        type_alias_decl.id.sym.to_string(),
+        References::of(type_alias_decl),
+      );
+    }
+    ast::Decl::TsEnum(enum_decl) => {
+      declarations
+        .insert(enum_decl.id.sym.to_string(), References::of(enum_decl));
+    }
+    ast::Decl::TsModule(module_decl) => {
+      shared.extend(References::of(module_decl));
+    }
+  }
+}
+
+/// Finds the tests of a module and the imports each of them uses.
+pub fn analyze_test_module(parsed_source: &ParsedSource) -> TestModuleAnalysis {
+  let module = parsed_source.module();
+  // the bindings of imports to their specifiers
+  let mut imports = HashMap::new();
+  // the top level declarations to what they reference
+  let mut declarations = HashMap::new();
+  let mut shared = References::default();
+  let mut tests = Vec::new();
+
+  for item in &module.body {
+    match item {
+      ast::ModuleItem::ModuleDecl(ast::ModuleDecl::Import(import_decl)) => {
+        let specifier = import_decl.src.value.to_string();
+        if import_decl.specifiers.is_empty() {
+          shared.imports.insert(specifier.clone());
+        }
+        for import_specifier in &import_decl.specifiers {
+          let local = match import_specifier {
+            ast::ImportSpecifier::Named(named) => &named.local,
+            ast::ImportSpecifier::Default(default) => &default.local,
+            ast::ImportSpecifier::Namespace(namespace) => &namespace.local,
+          };
+          imports.insert(local.sym.to_string(), specifier.clone());
+        }
+      }
+      ast::ModuleItem::ModuleDecl(ast::ModuleDecl::ExportDecl(export_decl)) => {
+        add_declaration(&export_decl.decl, &mut declarations, &mut shared);
+      }
+      ast::ModuleItem::Stmt(ast::Stmt::Decl(decl)) => {
+        add_declaration(decl, &mut declarations, &mut shared);
+      }
+      ast::ModuleItem::Stmt(stmt) => match as_deno_test_call(stmt) {
+        Some(call_expr) => {
+          tests.push((test_name(call_expr), References::of(&call_expr.args)))
+        }
+        None => shared.extend(References::of(stmt)),
+      },
+      ast::ModuleItem::ModuleDecl(module_decl) => {
+        shared.extend(References::of(module_decl))
+      }
+    }
+  }
+
+  let resolve_imports = |references: &References| {
+    let mut resolved = references.imports.clone();
+    let mut seen = HashSet::new();
+    let mut pending = references.idents.iter().collect::<Vec<_>>();
+    while let Some(ident) = pending.pop() {
+      if !seen.insert(ident) {
+        continue;
+      }
+      if let Some(specifier) = imports.get(ident) {
+        resolved.insert(specifier.clone());
+      }
+      if let Some(references) = declarations.get(ident) {
+        resolved.extend(references.imports.iter().cloned());
+        pending.extend(references.idents.iter());
+      }
+    }
+    resolved
+  };
+
+  let mut counter = DenoTestCounter::default();
+  module.visit_with(&mut counter);
+  let is_complete = !tests.is_empty()
+    && counter.count == tests.len()
+    && tests.iter().all(|(maybe_name, _)| maybe_name.is_some());
+
+  TestModuleAnalysis {
+    tests: is_complete.then(|| {
+      tests
+        .iter()
+        .map(|(maybe_name, references)| StaticTest {
+          name: maybe_name.clone().unwrap(),
+          imports: resolve_imports(references),
+        })
+        .collect()
+    }),
+    shared_imports: resolve_imports(&shared),
+  }
+}
+
+/// The tests of a test module to run again after a change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AffectedTests {
+  /// The whole module, when it changed itself or its tests aren't known.
+  All,
+  /// The tests with these names.
+  Named(Vec<String>),
+}
+
+/// Finds the tests of the test module affected by the changed modules, or
+/// `None` when the changes don't affect it.
+pub fn find_affected_tests(
+  graph: &ModuleGraph,
+  specifier: &ModuleSpecifier,
+  maybe_analysis: Option<&TestModuleAnalysis>,
+  changed: &HashSet<ModuleSpecifier>,
+  no_check: bool,
+) -> Option<AffectedTests> {
+  if changed.contains(specifier) {
+    return Some(AffectedTests::All);
+  }
+  let module = graph.get(specifier)?;
+  let uses_changed = |maybe_imports: Option<&HashSet<String>>| {
+    let mut modules = HashSet::new();
+    match maybe_imports {
+      Some(imports) => {
+        for import in imports {
+          if let Some(dep) = module.dependencies.get(import) {
+            add_dependency(graph, dep, &mut modules, no_check);
+          }
+        }
+      }
+      None => get_dependencies(graph, Some(module), &mut modules, no_check),
+    }
+    modules.iter().any(|specifier| changed.contains(*specifier))
+  };
+
+  if !uses_changed(None) {
+    return None;
+  }
+  let analysis = match maybe_analysis {
+    Some(analysis) if analysis.tests.is_some() => analysis,
+    _ => return Some(AffectedTests::All),
+  };
+  if uses_changed(Some(&analysis.shared_imports)) {
+    return Some(AffectedTests::All);
+  }
+  let mut names = Vec::new();
+  for test in analysis.tests.iter().flatten() {
+    if uses_changed(Some(&test.imports)) && !names.contains(&test.name) {
+      names.push(test.name.clone());
+    }
+  }
+  if names.is_empty() {
+    None
+  } else {
+    Some(AffectedTests::Named(names))
+  }
+}
+
+/// What the changed files of a watch run affect among the root modules of
+/// `deno test --watch` or `deno bench --watch`.
+pub struct WatchChanges {
+  /// The configuration files, the import map, the lockfile and the local
+  /// modules of the graph.
+  pub paths_to_watch: Vec<PathBuf>,
+  pub changed_specifiers: HashSet<ModuleSpecifier>,
+  /// The root modules which depend on a changed module, or are one.
+  pub affected_modules: Vec<ModuleSpecifier>,
+  /// The changed configuration files, the import map or the lockfile, which
+  /// can affect every module.
+  pub changed_config_paths: Vec<PathBuf>,
+  /// The changed files which the root modules depend on.
+  pub used_changed_paths: Vec<PathBuf>,
+}
+
+/// Finds what the changed files affect among the root modules, which are in
+/// the graph. `changed` is `None` on the first run.
+pub fn collect_watch_changes(
+  ps: &ProcState,
+  graph: &ModuleGraph,
+  root_modules: &[ModuleSpecifier],
+  changed: Option<&[PathBuf]>,
+  no_check: bool,
+) -> WatchChanges {
+  let config_paths = ps
+    .options
+    .watch_config_paths()
+    .into_iter()
+    .chain(ps.lockfile.as_ref().and_then(|lockfile| {
+      canonicalize_path_maybe_not_exists(&lockfile.lock().filename).ok()
+    }))
+    .collect::<Vec<_>>();
+  let mut paths_to_watch = config_paths.clone();
+
+  let changed = changed.unwrap_or_default();
+  let changed_specifiers = changed
+    .iter()
+    .filter_map(|path| path_to_specifier(path).ok())
+    .collect::<HashSet<_>>();
+  let mut used_changed_specifiers = HashSet::new();
+  let mut affected_modules = Vec::new();
+  for specifier in root_modules {
+    // The root module and all its dependencies
+    let mut modules = HashSet::new();
+    modules.insert(specifier);
+    get_dependencies(graph, graph.get(specifier), &mut modules, no_check);
+
+    paths_to_watch.extend(
+      modules
+        .iter()
+        .filter_map(|specifier| specifier.to_file_path().ok()),
+    );
+
+    let used_changed = modules
+      .iter()
+      .filter(|specifier| changed_specifiers.contains(*specifier))
+      .map(|specifier| (*specifier).clone())
+      .collect::<Vec<_>>();
+    if !used_changed.is_empty() {
+      used_changed_specifiers.extend(used_changed);
+      affected_modules.push(specifier.clone());
+    }
+  }
+
+  let changed_config_paths = changed
+    .iter()
+    .filter(|path| config_paths.contains(path))
+    .cloned()
+    .collect();
+  let used_changed_paths = changed
+    .iter()
+    .filter(|path| {
+      path_to_specifier(path).map_or(false, |specifier| {
+        used_changed_specifiers.contains(&specifier)
+      })
+    })
+    .cloned()
+    .collect();
+  WatchChanges {
+    paths_to_watch,
+    changed_specifiers,
+    affected_modules,
+    changed_config_paths,
+    used_changed_paths,
+  }
+}
+
+/// Describes which tests a change affects, e.g. "2 tests affected by change to
+/// src/parser.ts". `whole_modules` is the number of modules which run all of
+/// their tests without knowing how many there are.
+pub fn describe_affected_tests(
+  test_count: usize,
+  whole_modules: usize,
+  changed: &[PathBuf],
+) -> String {
+  let mut parts = Vec::new();
+  if test_count > 0 {
+    parts.push(pluralize(test_count, "test", "tests"));
+  }
+  if whole_modules > 0 {
+    parts.push(format!(
+      "all tests of {}",
+      pluralize(whole_modules, "module", "modules")
+    ));
+  }
+  let mut description = format!(
+    "{} affected by {}",
+    parts.join(" and "),
+    describe_changes(changed)
+  );
+  description[..1].make_ascii_uppercase();
+  description
+}
+
+/// Describes the changed files, e.g. "change to src/parser.ts", with the
+/// paths relative to the current directory.
+pub fn describe_changes(changed: &[PathBuf]) -> String {
+  let cwd = std::env::current_dir().ok();
+  let paths = changed
+    .iter()
+    .map(|path| {
+      cwd
+        .as_ref()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+    })
+    .collect::<Vec<_>>();
+  format!(
+    "{} to {}",
+    if paths.len() == 1 {
+      "change"
+    } else {
+      "changes"
+    },
+    paths.join(", ")
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_ast::MediaType;
+  use deno_ast::SourceTextInfo;
+
+  fn analyze(source: &str) -> TestModuleAnalysis {
+    let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+      specifier: "file:///a_test.ts".to_string(),
+      text_info: SourceTextInfo::new(source.into()),
+      media_type: MediaType::TypeScript,
+      capture_tokens: false,
+      scope_analysis: false,
+      maybe_syntax: None,
+    })
+    .unwrap();
+    analyze_test_module(&parsed_source)
+  }
+
+  fn set(specifiers: &[&str]) -> HashSet<String> {
+    specifiers.iter().map(|s| s.to_string()).collect()
+  }
+
+  #[test]
+  fn finds_the_imports_of_each_test() {
+    let analysis = analyze(
+      r#"import "./setup.ts";
+import { parse } from "./src/parser.ts";
+import * as lexer from "./src/lexer.ts";
+import { assertEquals } from "./asserts.ts";
+import type { Token } from "./src/token.ts";
+
+function tokens(source: string): Token[] {
+  return lexer.tokenize(source);
+}
+
+const fixture = await Deno.readTextFile("fixture.txt");
+
+Deno.test("parse", () => {
+  assertEquals(parse(fixture), {});
+});
+
+Deno.test({
+  name: "tokenize",
+  fn() {
+    assertEquals(tokens("1 + 2").length, 3);
+  },
+});
+
+Deno.test(async function lazy() {
+  await import("./src/lazy.ts");
+});
+"#,
+    );
+    assert_eq!(
+      analysis,
+      TestModuleAnalysis {
+        tests: Some(vec![
+          StaticTest {
+            name: "parse".to_string(),
+            imports: set(&["./src/parser.ts", "./asserts.ts"]),
+          },
+          StaticTest {
+            name: "tokenize".to_string(),
+            imports: set(&["./src/lexer.ts", "./src/token.ts", "./asserts.ts"]),
+          },
+          StaticTest {
+            name: "lazy".to_string(),
+            imports: set(&["./src/lazy.ts"]),
+          },
+        ]),
+        shared_imports: set(&["./setup.ts"]),
+      }
+    );
+  }
+
+  #[test]
+  fn tests_not_found_statically() {
+    // computed names
+    let analysis = analyze("const name = 'a';\nDeno.test(name, () => {});");
+    assert_eq!(analysis.tests, None);
+    // tests registered in a loop
+    let analysis = analyze(
+      "Deno.test('a', () => {});\nfor (const n of [1, 2]) {\n  Deno.test(`${n}`, () => {});\n}",
+    );
+    assert_eq!(analysis.tests, None);
+    // aliases of `Deno.test`
+    let analysis = analyze("const { test } = Deno;\ntest('a', () => {});");
+    assert_eq!(analysis.tests, None);
+    // tests registered by a library
+    let analysis =
+      analyze("import { it } from './bdd.ts';\nit('a', () => {});");
+    assert_eq!(analysis.tests, None);
+    assert_eq!(analysis.shared_imports, set(&["./bdd.ts"]));
+  }
+
+  #[test]
+  fn describes_affected_tests() {
+    let cwd = std::env::current_dir().unwrap();
+    assert_eq!(
+      describe_affected_tests(2, 0, &[cwd.join("src/parser.ts")]),
+      "2 tests affected by change to src/parser.ts"
+    );
+    assert_eq!(
+      describe_affected_tests(
+        1,
+        2,
+        &[cwd.join("a.ts"), PathBuf::from("/b.ts")]
+      ),
+      "1 test and all tests of 2 modules affected by changes to a.ts, /b.ts"
+    );
+    assert_eq!(
+      describe_affected_tests(0, 1, &[cwd.join("a.ts")]),
+      "All tests of 1 module affected by change to a.ts"
+    );
+  }
+}
@@ -7,19 +7,24 @@ use crate::args::UpgradeFlags;
 use crate::colors;
 use crate::http_util::HttpClient;
 use crate::proc_state::ProcState;
+use crate::semver::Version;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 use crate::version;
 
+use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::futures::future::BoxFuture;
 use deno_core::futures::FutureExt;
+use deno_core::serde_json;
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 use std::borrow::Cow;
 use std::env;
 use std::fs;
+use std::num::NonZeroUsize;
 use std::ops::Sub;
 use std::path::Path;
 use std::path::PathBuf;
@@ -31,14 +36,83 @@ static ARCHIVE_NAME: Lazy<String> =
 
 const RELEASE_URL: &str = "https://github.com/denoland/deno/releases";
 
+const RELEASES_API_URL: &str =
+  "https://api.github.com/repos/denoland/deno/releases";
+
+// Maximum number of releases the GitHub API returns per page.
+const RELEASES_API_PAGE_SIZE: usize = 100;
+
+// Number of versions printed per page by `deno upgrade --list`.
+const LIST_PAGE_SIZE: usize = 20;
+
 // How often query server for new version. In hours.
 const UPGRADE_CHECK_INTERVAL: i64 = 24;
 
 const UPGRADE_CHECK_FETCH_DELAY: Duration = Duration::from_millis(500);
 
+/// The channel a version of Deno is released to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ReleaseChannel {
+  Stable,
+  Rc,
+  Canary,
+}
+
+impl ReleaseChannel {
+  pub fn from_flags(upgrade_flags: &UpgradeFlags) -> Self {
+    if upgrade_flags.canary {
+      Self::Canary
+    } else if upgrade_flags.rc {
+      Self::Rc
+    } else {
+      Self::Stable
+    }
+  }
+
+  /// The channel of the currently running executable.
+  pub fn current() -> Self {
+    if version::is_canary() {
+      Self::Canary
+    } else {
+      Version::parse_from_npm(&version::deno())
+        .ok()
+        .and_then(|version| Self::of_version(&version))
+        .unwrap_or(Self::Stable)
+    }
+  }
+
+  /// The channel a release version was published to, or `None` for
+  /// pre-releases that aren't release candidates.
+  pub fn of_version(version: &Version) -> Option<Self> {
+    match version.pre.first() {
+      None => Some(Self::Stable),
+      Some(pre) if pre.starts_with("rc") => Some(Self::Rc),
+      Some(_) => None,
+    }
+  }
+
+  pub fn parse(text: &str) -> Option<Self> {
+    match text {
+      "stable" => Some(Self::Stable),
+      "rc" => Some(Self::Rc),
+      "canary" => Some(Self::Canary),
+      _ => None,
+    }
+  }
+
+  pub fn name(&self) -> &'static str {
+    match self {
+      Self::Stable => "stable",
+      Self::Rc => "rc",
+      Self::Canary => "canary",
+    }
+  }
+}
+
 /// Environment necessary for doing the update checker.
 /// An alternate trait implementation can be provided for testing purposes.
 trait UpdateCheckerEnvironment: Clone + Send + Sync {
+  fn channel(&self) -> ReleaseChannel;
   fn latest_version(&self) -> BoxFuture<'static, Result<String, AnyError>>;
   fn current_version(&self) -> Cow<str>;
   fn read_check_file(&self) -> String;
@@ -50,6 +124,7 @@ trait UpdateCheckerEnvironment: Clone + Send + Sync {
 struct RealUpdateCheckerEnvironment {
   http_client: HttpClient,
   cache_file_path: PathBuf,
+  channel: ReleaseChannel,
   current_time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -58,6 +133,7 @@ impl RealUpdateCheckerEnvironment {
     Self {
       http_client,
       cache_file_path,
+      channel: ReleaseChannel::current(),
       // cache the current time
       current_time: chrono::Utc::now(),
     }
@@ -65,13 +141,20 @@ impl RealUpdateCheckerEnvironment {
 }
 
 impl UpdateCheckerEnvironment for RealUpdateCheckerEnvironment {
+  fn channel(&self) -> ReleaseChannel {
+    self.channel
+  }
+
   fn latest_version(&self) -> BoxFuture<'static, Result<String, AnyError>> {
     let http_client = self.http_client.clone();
+    let channel = self.channel;
     async move {
-      if version::is_canary() {
-        get_latest_canary_version(&http_client).await
-      } else {
-        get_latest_release_version(&http_client).await
+      match channel {
+        ReleaseChannel::Canary => get_latest_canary_version(&http_client).await,
+        ReleaseChannel::Rc => get_latest_rc_version(&http_client).await,
+        ReleaseChannel::Stable => {
+          get_latest_release_version(&http_client).await
+        }
       }
     }
     .boxed()
@@ -101,7 +184,8 @@ struct UpdateChecker<TEnvironment: UpdateCheckerEnvironment> {
 
 impl<TEnvironment: UpdateCheckerEnvironment> UpdateChecker<TEnvironment> {
   pub fn new(env: TEnvironment) -> Self {
-    let maybe_file = CheckVersionFile::parse(env.read_check_file());
+    let maybe_file =
+      CheckVersionFile::parse(&env.read_check_file(), env.channel());
     Self { env, maybe_file }
   }
 
@@ -133,8 +217,8 @@ impl<TEnvironment: UpdateCheckerEnvironment> UpdateChecker<TEnvironment> {
       return None;
     }
 
-    if let Ok(current) = semver::Version::parse(&self.env.current_version()) {
-      if let Ok(latest) = semver::Version::parse(&file.latest_version) {
+    if let Ok(current) = Version::parse_from_npm(&self.env.current_version()) {
+      if let Ok(latest) = Version::parse_from_npm(&file.latest_version) {
         if current >= latest {
           return None;
         }
@@ -155,9 +239,10 @@ impl<TEnvironment: UpdateCheckerEnvironment> UpdateChecker<TEnvironment> {
   /// Store that we showed the update message to the user.
   pub fn store_prompted(self) {
     if let Some(file) = self.maybe_file {
-      self.env.write_check_file(
-        &file.with_last_prompt(self.env.current_time()).serialize(),
-      );
+      let file = file.with_last_prompt(self.env.current_time());
+      self
+        .env
+        .write_check_file(&file.update_text(&self.env.read_check_file()));
     }
   }
 }
@@ -204,27 +289,42 @@ pub fn check_for_upgrades(http_client: HttpClient, cache_file_path: PathBuf) {
   // Print a message if an update is available
   if let Some(upgrade_version) = update_checker.should_prompt() {
     if log::log_enabled!(log::Level::Info) && atty::is(atty::Stream::Stderr) {
-      if version::is_canary() {
-        eprint!(
-          "{} ",
-          colors::green("A new canary release of Deno is available.")
-        );
-        eprintln!(
-          "{}",
-          colors::italic_gray("Run `deno upgrade --canary` to install it.")
-        );
-      } else {
-        eprint!(
-          "{} {} → {} ",
-          colors::green("A new release of Deno is available:"),
-          colors::cyan(version::deno()),
-          colors::cyan(&upgrade_version)
-        );
-        eprintln!(
-          "{}",
-          colors::italic_gray("Run `deno upgrade` to install it.")
-        );
-        print_release_notes(&version::deno(), &upgrade_version);
+      match update_checker.env.channel() {
+        ReleaseChannel::Canary => {
+          eprint!(
+            "{} ",
+            colors::green("A new canary release of Deno is available.")
+          );
+          eprintln!(
+            "{}",
+            colors::italic_gray("Run `deno upgrade --canary` to install it.")
+          );
+        }
+        ReleaseChannel::Rc => {
+          eprint!(
+            "{} {} → {} ",
+            colors::green("A new release candidate of Deno is available:"),
+            colors::cyan(version::deno()),
+            colors::cyan(&upgrade_version)
+          );
+          eprintln!(
+            "{}",
+            colors::italic_gray("Run `deno upgrade --rc` to install it.")
+          );
+        }
+        ReleaseChannel::Stable => {
+          eprint!(
+            "{} {} → {} ",
+            colors::green("A new release of Deno is available:"),
+            colors::cyan(version::deno()),
+            colors::cyan(&upgrade_version)
+          );
+          eprintln!(
+            "{}",
+            colors::italic_gray("Run `deno upgrade` to install it.")
+          );
+          print_release_notes(&version::deno(), &upgrade_version);
+        }
       }
 
       update_checker.store_prompted();
@@ -243,18 +343,17 @@ async fn fetch_and_store_latest_version<
     Err(_) => return,
   };
 
-  env.write_check_file(
-    &CheckVersionFile {
-      // put a date in the past here so that prompt can be shown on next run
-      last_prompt: env
-        .current_time()
-        .sub(chrono::Duration::hours(UPGRADE_CHECK_INTERVAL + 1)),
-      last_checked: env.current_time(),
-      current_version: env.current_version().to_string(),
-      latest_version,
-    }
-    .serialize(),
-  );
+  let file = CheckVersionFile {
+    channel: env.channel(),
+    // put a date in the past here so that prompt can be shown on next run
+    last_prompt: env
+      .current_time()
+      .sub(chrono::Duration::hours(UPGRADE_CHECK_INTERVAL + 1)),
+    last_checked: env.current_time(),
+    current_version: env.current_version().to_string(),
+    latest_version,
+  };
+  env.write_check_file(&file.update_text(&env.read_check_file()));
 }
 
 pub async fn upgrade(
@@ -262,6 +361,14 @@ pub async fn upgrade(
   upgrade_flags: UpgradeFlags,
 ) -> Result<(), AnyError> {
   let ps = ProcState::build(flags).await?;
+  let client = &ps.http_client;
+  let channel = ReleaseChannel::from_flags(&upgrade_flags);
+
+  if upgrade_flags.list {
+    let page = upgrade_flags.page.unwrap_or(NonZeroUsize::new(1).unwrap());
+    return list_versions(client, channel, page).await;
+  }
+
   let current_exe_path = std::env::current_exe()?;
   let metadata = fs::metadata(&current_exe_path)?;
   let permissions = metadata.permissions();
@@ -283,21 +390,19 @@ pub async fn upgrade(
     ), current_exe_path.display());
   }
 
-  let client = &ps.http_client;
-
   let install_version = match upgrade_flags.version {
     Some(passed_version) => {
-      if upgrade_flags.canary
+      if channel == ReleaseChannel::Canary
         && !regex::Regex::new("^[0-9a-f]{40}$")?.is_match(&passed_version)
       {
         bail!("Invalid commit hash passed");
-      } else if !upgrade_flags.canary
-        && semver::Version::parse(&passed_version).is_err()
+      } else if channel != ReleaseChannel::Canary
+        && Version::parse_from_npm(&passed_version).is_err()
       {
         bail!("Invalid semver passed");
       }
 
-      let current_is_passed = if upgrade_flags.canary {
+      let current_is_passed = if channel == ReleaseChannel::Canary {
         crate::version::GIT_COMMIT_HASH == passed_version
       } else if !crate::version::is_canary() {
         crate::version::deno() == passed_version
@@ -316,20 +421,29 @@ pub async fn upgrade(
       }
     }
     None => {
-      let latest_version = if upgrade_flags.canary {
-        log::info!("Looking up latest canary version");
-        get_latest_canary_version(client).await?
-      } else {
-        log::info!("Looking up latest version");
-        get_latest_release_version(client).await?
+      let latest_version = match channel {
+        ReleaseChannel::Canary => {
+          log::info!("Looking up latest canary version");
+          get_latest_canary_version(client).await?
+        }
+        ReleaseChannel::Rc => {
+          log::info!("Looking up latest release candidate version");
+          get_latest_rc_version(client).await?
+        }
+        ReleaseChannel::Stable => {
+          log::info!("Looking up latest version");
+          get_latest_release_version(client).await?
+        }
       };
 
-      let current_is_most_recent = if upgrade_flags.canary {
+      let current_is_most_recent = if channel == ReleaseChannel::Canary {
         let latest_hash = latest_version.clone();
         crate::version::GIT_COMMIT_HASH == latest_hash
       } else if !crate::version::is_canary() {
-        let current = semver::Version::parse(&crate::version::deno()).unwrap();
-        let latest = semver::Version::parse(&latest_version).unwrap();
+        // release candidates sort before the release they precede, so an
+        // older release candidate is never installed over a newer release
+        let current = Version::parse_from_npm(&crate::version::deno())?;
+        let latest = Version::parse_from_npm(&latest_version)?;
         current >= latest
       } else {
         false
@@ -341,7 +455,7 @@ pub async fn upgrade(
       {
         log::info!(
           "Local deno version {} is the most recent release",
-          if upgrade_flags.canary {
+          if channel == ReleaseChannel::Canary {
             crate::version::GIT_COMMIT_HASH.to_string()
           } else {
             crate::version::deno()
@@ -355,7 +469,7 @@ pub async fn upgrade(
     }
   };
 
-  let download_url = if upgrade_flags.canary {
+  let download_url = if channel == ReleaseChannel::Canary {
     if env!("TARGET") == "aarch64-apple-darwin" {
       bail!("Canary builds are not available for M1");
     }
@@ -371,7 +485,11 @@ pub async fn upgrade(
     )
   };
 
-  let archive_data = download_package(client, &download_url)
+  let partial_path = ps
+    .dir
+    .upgrade_downloads_folder_path()
+    .join(format!("{}_{}.partial", install_version, *ARCHIVE_NAME));
+  let archive_data = download_package(client, &download_url, &partial_path)
     .await
     .with_context(|| format!("Failed downloading {download_url}"))?;
 
@@ -385,7 +503,7 @@ pub async fn upgrade(
   if upgrade_flags.dry_run {
     fs::remove_file(&new_exe_path)?;
     log::info!("Upgraded successfully (dry run)");
-    if !upgrade_flags.canary {
+    if channel == ReleaseChannel::Stable {
       print_release_notes(&version::deno(), &install_version);
     }
   } else {
@@ -419,7 +537,7 @@ pub async fn upgrade(
       }
     }
     log::info!("Upgraded successfully");
-    if !upgrade_flags.canary {
+    if channel == ReleaseChannel::Stable {
       print_release_notes(&version::deno(), &install_version);
     }
   }
@@ -448,9 +566,117 @@ async fn get_latest_canary_version(
   Ok(version)
 }
 
+async fn get_latest_rc_version(
+  client: &HttpClient,
+) -> Result<String, AnyError> {
+  let versions = get_channel_versions(client, ReleaseChannel::Rc, 1).await?;
+  versions
+    .first()
+    .map(|version| version.to_string())
+    .ok_or_else(|| anyhow!("No release candidates were found"))
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+  tag_name: String,
+  #[serde(default)]
+  draft: bool,
+}
+
+/// Fetches the versions released to the stable or release candidate channel,
+/// sorted newest first. Pages of releases are fetched until at least
+/// `min_count` versions were found or there are no more releases.
+async fn get_channel_versions(
+  client: &HttpClient,
+  channel: ReleaseChannel,
+  min_count: usize,
+) -> Result<Vec<Version>, AnyError> {
+  let mut versions = Vec::new();
+  for page in 1.. {
+    let url = format!(
+      "{RELEASES_API_URL}?per_page={RELEASES_API_PAGE_SIZE}&page={page}"
+    );
+    let text = client.download_text(&url).await?;
+    let releases: Vec<GithubRelease> = serde_json::from_str(&text)
+      .with_context(|| format!("Failed parsing releases from {url}"))?;
+    let is_last_page = releases.len() < RELEASES_API_PAGE_SIZE;
+    versions.extend(
+      releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| {
+          Version::parse_from_npm(release.tag_name.trim_start_matches('v')).ok()
+        })
+        .filter(|version| ReleaseChannel::of_version(version) == Some(channel)),
+    );
+    if is_last_page || versions.len() >= min_count {
+      break;
+    }
+  }
+  versions.sort_by(|a, b| b.cmp(a));
+  versions.dedup();
+  Ok(versions)
+}
+
+async fn list_versions(
+  client: &HttpClient,
+  channel: ReleaseChannel,
+  page: NonZeroUsize,
+) -> Result<(), AnyError> {
+  if channel == ReleaseChannel::Canary {
+    log::info!("Looking up latest canary version");
+    let latest_hash = get_latest_canary_version(client).await?;
+    print_listed_version(&latest_hash, version::GIT_COMMIT_HASH);
+    log::info!(
+      "{}",
+      colors::italic_gray("Only the latest canary build is available.")
+    );
+    return Ok(());
+  }
+
+  log::info!("Looking up {} versions", channel.name());
+  let page = page.get();
+  // fetch one more version than needed to know if there is a next page
+  let versions =
+    get_channel_versions(client, channel, page * LIST_PAGE_SIZE + 1).await?;
+  let page_versions = versions
+    .iter()
+    .skip((page - 1) * LIST_PAGE_SIZE)
+    .take(LIST_PAGE_SIZE)
+    .collect::<Vec<_>>();
+  if page_versions.is_empty() {
+    log::info!("No {} versions found on page {}", channel.name(), page);
+    return Ok(());
+  }
+  let current_version = version::deno();
+  for version in page_versions {
+    print_listed_version(&version.to_string(), &current_version);
+  }
+  if versions.len() > page * LIST_PAGE_SIZE {
+    log::info!(
+      "{}",
+      colors::italic_gray(format!(
+        "Run `deno upgrade --list --channel={} --page={}` to see older versions.",
+        channel.name(),
+        page + 1
+      ))
+    );
+  }
+  Ok(())
+}
+
+fn print_listed_version(version: &str, current_version: &str) {
+  if version == current_version {
+    println!("{} {}", version, colors::green("(installed)"));
+  } else {
+    println!("{version}");
+  }
+}
+
 async fn download_package(
   client: &HttpClient,
   download_url: &str,
+  partial_path: &Path,
 ) -> Result<Vec<u8>, AnyError> {
   log::info!("Downloading {}", &download_url);
   let maybe_bytes = {
@@ -459,7 +685,7 @@ async fn download_package(
     // text above which will stay alive after the progress bars are complete
     let progress = progress_bar.update("");
     client
-      .download_with_progress(download_url, &progress)
+      .download_with_progress_resumable(download_url, partial_path, &progress)
       .await?
   };
   match maybe_bytes {
@@ -562,8 +788,12 @@ fn check_exe(exe_path: &Path) -> Result<(), AnyError> {
   Ok(())
 }
 
+/// An entry of the version check file. The file holds one line per release
+/// channel, so executables of different channels sharing a cache directory
+/// don't overwrite each other's results.
 #[derive(Debug)]
 struct CheckVersionFile {
+  pub channel: ReleaseChannel,
   pub last_prompt: chrono::DateTime<chrono::Utc>,
   pub last_checked: chrono::DateTime<chrono::Utc>,
   pub current_version: String,
@@ -571,10 +801,18 @@ struct CheckVersionFile {
 }
 
 impl CheckVersionFile {
-  pub fn parse(content: String) -> Option<Self> {
-    let split_content = content.split('!').collect::<Vec<_>>();
+  /// Parses the entry of the given channel from the check file.
+  pub fn parse(content: &str, channel: ReleaseChannel) -> Option<Self> {
+    content
+      .lines()
+      .filter_map(Self::parse_line)
+      .find(|file| file.channel == channel)
+  }
 
-    if split_content.len() != 4 {
+  fn parse_line(line: &str) -> Option<Self> {
+    let split_content = line.trim().split('!').collect::<Vec<_>>();
+
+    if split_content.len() != 4 && split_content.len() != 5 {
       return None;
     }
 
@@ -593,8 +831,18 @@ impl CheckVersionFile {
     let last_checked = chrono::DateTime::parse_from_rfc3339(split_content[1])
       .map(|dt| dt.with_timezone(&chrono::Utc))
       .ok()?;
+    let channel = match split_content.get(4) {
+      Some(channel) => ReleaseChannel::parse(channel)?,
+      // files written before channels were stored only hold one entry, for
+      // either a release or a canary version
+      None => match Version::parse_from_npm(&latest_version) {
+        Ok(version) => ReleaseChannel::of_version(&version)?,
+        Err(_) => ReleaseChannel::Canary,
+      },
+    };
 
     Some(CheckVersionFile {
+      channel,
       last_prompt,
       last_checked,
       current_version,
@@ -604,14 +852,31 @@ impl CheckVersionFile {
 
   fn serialize(&self) -> String {
     format!(
-      "{}!{}!{}!{}",
+      "{}!{}!{}!{}!{}",
       self.last_prompt.to_rfc3339(),
       self.last_checked.to_rfc3339(),
       self.latest_version,
       self.current_version,
+      self.channel.name(),
     )
   }
 
+  /// Returns the text of the check file with the entry of this file's channel
+  /// replaced, keeping the entries of the other channels.
+  fn update_text(&self, content: &str) -> String {
+    let mut lines = content
+      .lines()
+      .filter(|line| {
+        Self::parse_line(line)
+          .map(|file| file.channel != self.channel)
+          .unwrap_or(false)
+      })
+      .map(|line| line.trim().to_string())
+      .collect::<Vec<_>>();
+    lines.push(self.serialize());
+    lines.join("\n")
+  }
+
   fn with_last_prompt(self, dt: chrono::DateTime<chrono::Utc>) -> Self {
     Self {
       last_prompt: dt,
@@ -631,8 +896,8 @@ mod test {
   #[test]
   fn test_parse_upgrade_check_file() {
     let file = CheckVersionFile::parse(
-      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2"
-        .to_string(),
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2!stable",
+      ReleaseChannel::Stable,
     )
     .unwrap();
     assert_eq!(
@@ -646,20 +911,70 @@ mod test {
     assert_eq!(file.latest_version, "1.2.3".to_string());
     assert_eq!(file.current_version, "1.2.2".to_string());
 
+    let result = CheckVersionFile::parse(
+      "2020-01-01T00:00:00+00:00!",
+      ReleaseChannel::Stable,
+    );
+    assert!(result.is_none());
+
     let result =
-      CheckVersionFile::parse("2020-01-01T00:00:00+00:00!".to_string());
+      CheckVersionFile::parse("garbage!test", ReleaseChannel::Stable);
     assert!(result.is_none());
 
-    let result = CheckVersionFile::parse("garbage!test".to_string());
+    let result = CheckVersionFile::parse("test", ReleaseChannel::Stable);
     assert!(result.is_none());
 
-    let result = CheckVersionFile::parse("test".to_string());
+    let result = CheckVersionFile::parse(
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2!other",
+      ReleaseChannel::Stable,
+    );
     assert!(result.is_none());
   }
 
+  #[test]
+  fn test_parse_upgrade_check_file_channels() {
+    let content = concat!(
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2!stable\n",
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.3.0-rc.1!1.3.0-rc.0!rc",
+    );
+    let file = CheckVersionFile::parse(content, ReleaseChannel::Rc).unwrap();
+    assert_eq!(file.channel, ReleaseChannel::Rc);
+    assert_eq!(file.latest_version, "1.3.0-rc.1".to_string());
+    assert_eq!(file.current_version, "1.3.0-rc.0".to_string());
+    let file =
+      CheckVersionFile::parse(content, ReleaseChannel::Stable).unwrap();
+    assert_eq!(file.latest_version, "1.2.3".to_string());
+    assert!(CheckVersionFile::parse(content, ReleaseChannel::Canary).is_none());
+  }
+
+  #[test]
+  fn test_parse_upgrade_check_file_without_channel() {
+    // files written by older versions don't store the channel
+    let file = CheckVersionFile::parse(
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2",
+      ReleaseChannel::Stable,
+    )
+    .unwrap();
+    assert_eq!(file.latest_version, "1.2.3".to_string());
+
+    let content = concat!(
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!",
+      "61fbfabe440f1cfffa7b8d17426ffdece4d430d0!",
+      "5c76e9d3efee2fb5dc8a3ff0a1b6a1b33ab7e8d9",
+    );
+    assert!(CheckVersionFile::parse(content, ReleaseChannel::Stable).is_none());
+    let file =
+      CheckVersionFile::parse(content, ReleaseChannel::Canary).unwrap();
+    assert_eq!(
+      file.latest_version,
+      "61fbfabe440f1cfffa7b8d17426ffdece4d430d0".to_string()
+    );
+  }
+
   #[test]
   fn test_serialize_upgrade_check_file() {
     let file = CheckVersionFile {
+      channel: ReleaseChannel::Stable,
       last_prompt: chrono::DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z")
         .unwrap()
         .with_timezone(&chrono::Utc),
@@ -673,12 +988,42 @@ mod test {
     };
     assert_eq!(
       file.serialize(),
-      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2"
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2!stable"
+    );
+  }
+
+  #[test]
+  fn test_update_upgrade_check_file_text() {
+    let time = chrono::DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z")
+      .unwrap()
+      .with_timezone(&chrono::Utc);
+    let file = CheckVersionFile {
+      channel: ReleaseChannel::Rc,
+      last_prompt: time,
+      last_checked: time,
+      latest_version: "1.3.0-rc.2".to_string(),
+      current_version: "1.3.0-rc.1".to_string(),
+    };
+    let content = concat!(
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2!stable\n",
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.3.0-rc.1!1.3.0-rc.0!rc",
+    );
+    assert_eq!(
+      file.update_text(content),
+      concat!(
+        "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.2.3!1.2.2!stable\n",
+        "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.3.0-rc.2!1.3.0-rc.1!rc",
+      )
+    );
+    assert_eq!(
+      file.update_text(""),
+      "2020-01-01T00:00:00+00:00!2020-01-01T00:00:00+00:00!1.3.0-rc.2!1.3.0-rc.1!rc"
     );
   }
 
   #[derive(Clone)]
   struct TestUpdateCheckerEnvironment {
+    channel: Arc<Mutex<ReleaseChannel>>,
     file_text: Arc<Mutex<String>>,
     current_version: Arc<Mutex<String>>,
     latest_version: Arc<Mutex<Result<String, String>>>,
@@ -688,6 +1033,7 @@ mod test {
   impl TestUpdateCheckerEnvironment {
     pub fn new() -> Self {
       Self {
+        channel: Arc::new(Mutex::new(ReleaseChannel::Stable)),
         file_text: Default::default(),
         current_version: Default::default(),
         latest_version: Arc::new(Mutex::new(Ok("".to_string()))),
@@ -702,6 +1048,10 @@ mod test {
         .unwrap();
     }
 
+    pub fn set_channel(&self, channel: ReleaseChannel) {
+      *self.channel.lock() = channel;
+    }
+
     pub fn set_file_text(&self, text: &str) {
       *self.file_text.lock() = text.to_string();
     }
@@ -720,6 +1070,10 @@ mod test {
   }
 
   impl UpdateCheckerEnvironment for TestUpdateCheckerEnvironment {
+    fn channel(&self) -> ReleaseChannel {
+      *self.channel.lock()
+    }
+
     fn latest_version(&self) -> BoxFuture<'static, Result<String, AnyError>> {
       let env = self.clone();
       async move {
@@ -815,10 +1169,65 @@ mod test {
     assert_eq!(checker.should_prompt(), None);
   }
 
+  #[tokio::test]
+  async fn test_update_checker_channels() {
+    let env = TestUpdateCheckerEnvironment::new();
+    env.set_current_version("1.0.0");
+    env.set_latest_version("1.1.0");
+    fetch_and_store_latest_version(&env).await;
+    let checker = UpdateChecker::new(env.clone());
+    assert!(!checker.should_check_for_new_version());
+    assert_eq!(checker.should_prompt(), Some("1.1.0".to_string()));
+
+    // a release candidate executable has its own entry
+    env.set_channel(ReleaseChannel::Rc);
+    env.set_current_version("1.1.0-rc.1");
+    env.set_latest_version("1.2.0-rc.1");
+    let checker = UpdateChecker::new(env.clone());
+    assert!(checker.should_check_for_new_version());
+    assert_eq!(checker.should_prompt(), None);
+    fetch_and_store_latest_version(&env).await;
+    let checker = UpdateChecker::new(env.clone());
+    assert!(!checker.should_check_for_new_version());
+    assert_eq!(checker.should_prompt(), Some("1.2.0-rc.1".to_string()));
+    checker.store_prompted();
+
+    // which doesn't clobber the entry of the stable executable
+    env.set_channel(ReleaseChannel::Stable);
+    env.set_current_version("1.0.0");
+    let checker = UpdateChecker::new(env.clone());
+    assert!(!checker.should_check_for_new_version());
+    assert_eq!(checker.should_prompt(), Some("1.1.0".to_string()));
+  }
+
+  #[tokio::test]
+  async fn test_update_checker_rc_older_than_current() {
+    let env = TestUpdateCheckerEnvironment::new();
+    env.set_channel(ReleaseChannel::Rc);
+    env.set_current_version("1.2.0");
+    env.set_latest_version("1.2.0-rc.3");
+    fetch_and_store_latest_version(&env).await;
+    let checker = UpdateChecker::new(env);
+    // a release candidate sorts before its release
+    assert_eq!(checker.should_prompt(), None);
+  }
+
+  #[test]
+  fn test_release_channel_of_version() {
+    let channel_of = |text: &str| {
+      ReleaseChannel::of_version(&Version::parse_from_npm(text).unwrap())
+    };
+    assert_eq!(channel_of("1.2.3"), Some(ReleaseChannel::Stable));
+    assert_eq!(channel_of("1.2.3-rc.1"), Some(ReleaseChannel::Rc));
+    assert_eq!(channel_of("1.0.0-rc1"), Some(ReleaseChannel::Rc));
+    assert_eq!(channel_of("1.2.3-alpha.1"), None);
+  }
+
   #[tokio::test]
   async fn test_update_checker_current_newer_than_latest() {
     let env = TestUpdateCheckerEnvironment::new();
     let file_content = CheckVersionFile {
+      channel: ReleaseChannel::Stable,
       last_prompt: env
         .current_time()
         .sub(chrono::Duration::hours(UPGRADE_CHECK_INTERVAL + 1)),
@@ -841,6 +1250,7 @@ mod test {
   async fn test_should_not_prompt_if_current_cli_version_has_changed() {
     let env = TestUpdateCheckerEnvironment::new();
     let file_content = CheckVersionFile {
+      channel: ReleaseChannel::Stable,
       last_prompt: env
         .current_time()
         .sub(chrono::Duration::hours(UPGRADE_CHECK_INTERVAL + 1)),
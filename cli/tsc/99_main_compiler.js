@@ -153,6 +153,11 @@ delete Object.prototype.__proto__;
   /** @type {string[]=} */
   let scriptFileNamesCache;
 
+  /** The memoized script file names of the language services of the library
+   * overrides, by the index of the override.
+   * @type {Map<number, string[]>} */
+  const libOverrideScriptFileNamesCache = new Map();
+
   /** @type {Map<string, string>} */
   const scriptVersionCache = new Map();
 
@@ -475,6 +480,18 @@ delete Object.prototype.__proto__;
   /** @type {ts.LanguageService} */
   let languageService;
 
+  /** The compiler options of the library overrides of the configuration file,
+   * which only differ from `compilationSettings` by their `lib`.
+   * @type {ts.CompilerOptions[]} */
+  let libOverrideSettings = [];
+
+  /** The language services of the library overrides, created when a document
+   * an override applies to is first requested. The documents of each service
+   * are only the ones its override applies to, so that the globals of the
+   * different libraries don't conflict.
+   * @type {Map<number, ts.LanguageService>} */
+  const libOverrideServices = new Map();
+
   /** An object literal of the incremental compiler host, which provides the
    * specific "bindings" to the Deno environment that tsc needs to work.
    *
@@ -673,7 +690,7 @@ delete Object.prototype.__proto__;
       if (scriptFileNamesCache) {
         return scriptFileNamesCache;
       }
-      return scriptFileNamesCache = ops.op_script_names();
+      return scriptFileNamesCache = ops.op_script_names({ libScope: null });
     },
     getScriptVersion(specifier) {
       if (logDebug) {
@@ -814,10 +831,148 @@ delete Object.prototype.__proto__;
     }
   }
 
+  /** Matches a comment which ignores the usages of deprecated APIs on the
+   * next line. */
+  const DEPRECATED_IGNORE_RE = /^\s*\/\/\s*deno-deprecated-ignore\b/;
+
+  /**
+   * Gets the call or `new` expression an identifier is the callee of, if any.
+   * @param {ts.Identifier} node
+   * @returns {ts.CallLikeExpression | undefined}
+   */
+  function getCallee(node) {
+    const parent = node.parent;
+    /** @type {ts.Node} */
+    let callee = node;
+    if (ts.isPropertyAccessExpression(parent) && parent.name === node) {
+      callee = parent;
+    }
+    const call = callee.parent;
+    if (
+      (ts.isCallExpression(call) || ts.isNewExpression(call)) &&
+      call.expression === callee
+    ) {
+      return call;
+    }
+    return undefined;
+  }
+
+  /**
+   * Gets the `@deprecated` tag of the declaration an identifier refers to,
+   * which for a call is the one of the called overload.
+   * @param {ts.TypeChecker} checker
+   * @param {ts.Identifier} node
+   * @returns {{ tag: ts.JSDocDeprecatedTag; declaration: ts.Declaration } | undefined}
+   */
+  function getDeprecation(checker, node) {
+    const parent = /** @type {any} */ (node.parent);
+    const isSpecifier = ts.isImportSpecifier(parent) ||
+      ts.isExportSpecifier(parent);
+    if (isSpecifier && parent.propertyName && parent.name === node) {
+      // `{ a as b }` is reported once, at `a`
+      return undefined;
+    }
+    // the declarations themselves aren't usages
+    if (
+      parent.name === node && !isSpecifier && !ts.isImportClause(parent) &&
+      !ts.isPropertyAccessExpression(parent) &&
+      !ts.isShorthandPropertyAssignment(parent)
+    ) {
+      return undefined;
+    }
+    let symbol = ts.isShorthandPropertyAssignment(parent)
+      ? checker.getShorthandAssignmentValueSymbol(parent)
+      : checker.getSymbolAtLocation(node);
+    if (symbol && symbol.flags & ts.SymbolFlags.Alias) {
+      symbol = checker.getAliasedSymbol(symbol);
+    }
+    const declarations = symbol?.declarations ?? [];
+    const call = getCallee(node);
+    if (call && declarations.length > 1) {
+      // an overloaded function is only deprecated when the called overload is
+      const declaration = checker.getResolvedSignature(call)?.declaration;
+      const tag = declaration && !ts.isJSDocSignature(declaration)
+        ? ts.getJSDocDeprecatedTag(declaration)
+        : undefined;
+      return tag ? { tag, declaration } : undefined;
+    }
+    const tags = declarations.map((d) => ts.getJSDocDeprecatedTag(d));
+    if (tags.length === 0 || !tags.every((tag) => tag)) {
+      return undefined;
+    }
+    return { tag: tags[0], declaration: declarations[0] };
+  }
+
+  /**
+   * Gets a warning for each usage of an API marked `@deprecated` in the
+   * modules of the program, apart from the usages on the line after a
+   * `// deno-deprecated-ignore` comment.
+   * @param {ts.Program} program
+   * @returns {ts.Diagnostic[]}
+   */
+  function getDeprecationDiagnostics(program) {
+    const checker = program.getTypeChecker();
+    /** @type {ts.Diagnostic[]} */
+    const diagnostics = [];
+    for (const sourceFile of program.getSourceFiles()) {
+      if (
+        sourceFile.isDeclarationFile ||
+        sourceFile.fileName.startsWith(ASSETS_URL_PREFIX)
+      ) {
+        continue;
+      }
+      const lineStarts = sourceFile.getLineStarts();
+      /** @param {number} line */
+      const isIgnored = (line) =>
+        line > 0 &&
+        DEPRECATED_IGNORE_RE.test(
+          sourceFile.text.slice(lineStarts[line - 1], lineStarts[line]),
+        );
+      /** @param {ts.Node} node */
+      const visit = (node) => {
+        if (ts.isIdentifier(node)) {
+          const deprecation = getDeprecation(checker, node);
+          const start = node.getStart(sourceFile);
+          if (
+            deprecation &&
+            !isIgnored(sourceFile.getLineAndCharacterOfPosition(start).line)
+          ) {
+            const { tag, declaration } = deprecation;
+            const comment = ts.getTextOfJSDocComment(tag.comment)?.trim();
+            const declarationName = ts.getNameOfDeclaration(declaration) ??
+              declaration;
+            const declarationFile = declaration.getSourceFile();
+            diagnostics.push({
+              category: ts.DiagnosticCategory.Warning,
+              code: 6385,
+              file: sourceFile,
+              start,
+              length: node.getWidth(sourceFile),
+              messageText: comment
+                ? `'${node.text}' is deprecated. ${comment}`
+                : `'${node.text}' is deprecated.`,
+              relatedInformation: [{
+                category: ts.DiagnosticCategory.Message,
+                code: 2798,
+                file: declarationFile,
+                start: declarationName.getStart(declarationFile),
+                length: declarationName.getWidth(declarationFile),
+                messageText: "The declaration was marked as deprecated here.",
+              }],
+            });
+          }
+        }
+        ts.forEachChild(node, visit);
+      };
+      visit(sourceFile);
+    }
+    return diagnostics;
+  }
+
   /** The API that is called by Rust when executing a request.
    * @param {Request} request
    */
-  function exec({ config, debug: debugFlag, rootNames }) {
+  function exec({ config, debug: debugFlag, reportDeprecations, rootNames }) {
     // https://github.com/microsoft/TypeScript/issues/49150
     ts.base64encode = function (host, input) {
       if (host && host.base64encode) {
@@ -856,6 +1011,9 @@ delete Object.prototype.__proto__;
       ...program.getGlobalDiagnostics(),
       ...program.getSemanticDiagnostics(),
     ].filter((diagnostic) => !IGNORED_DIAGNOSTICS.includes(diagnostic.code));
+    if (reportDeprecations) {
+      diagnostics.push(...getDeprecationDiagnostics(program.getProgram()));
+    }
 
     // emit the tsbuildinfo file
     // @ts-ignore: emitBuildInfo is not exposed (https://github.com/microsoft/TypeScript/issues/49871)
@@ -892,6 +1050,43 @@ delete Object.prototype.__proto__;
     ops.op_respond({ id, data });
   }
 
+  /**
+   * Gets the language service for a specifier, which has the libraries of the
+   * library override that applies to the specifier, if any.
+   * @param {string | undefined} specifier
+   * @returns {ts.LanguageService}
+   */
+  function getLanguageService(specifier) {
+    if (!specifier || libOverrideSettings.length === 0) {
+      return languageService;
+    }
+    const libScope = ops.op_lib_scope({ specifier });
+    if (libScope == null || !libOverrideSettings[libScope]) {
+      return languageService;
+    }
+    let service = libOverrideServices.get(libScope);
+    if (!service) {
+      /** @type {ts.CompilerHost & ts.LanguageServiceHost} */
+      const libOverrideHost = {
+        ...host,
+        getCompilationSettings() {
+          return libOverrideSettings[libScope];
+        },
+        getScriptFileNames() {
+          let scriptFileNames = libOverrideScriptFileNamesCache.get(libScope);
+          if (!scriptFileNames) {
+            scriptFileNames = ops.op_script_names({ libScope });
+            libOverrideScriptFileNamesCache.set(libScope, scriptFileNames);
+          }
+          return scriptFileNames;
+        },
+      };
+      service = ts.createLanguageService(libOverrideHost, documentRegistry);
+      libOverrideServices.set(libScope, service);
+    }
+    return service;
+  }
+
   /**
    * @param {LanguageServerRequest} request
    */
@@ -902,8 +1097,13 @@ delete Object.prototype.__proto__;
 
     // reset all memoized source files names
     scriptFileNamesCache = undefined;
+    libOverrideScriptFileNamesCache.clear();
     // evict all memoized source file versions
     scriptVersionCache.clear();
+    // the language service with the libraries of the requested document
+    const service = getLanguageService(
+      request.specifier ?? request.args?.specifier,
+    );
     switch (request.method) {
       case "restart": {
         serverRestart();
@@ -917,12 +1117,20 @@ delete Object.prototype.__proto__;
           debug(ts.formatDiagnostics(errors, host));
         }
         compilationSettings = options;
+        libOverrideSettings = request.libOverrides.map((lib) => {
+          const { options: libOptions } = ts.convertCompilerOptionsFromJson(
+            { ...request.compilerOptions, lib },
+            "",
+          );
+          return Object.assign(libOptions, { allowNonTsExtensions: true });
+        });
+        libOverrideServices.clear();
         return respond(id, true);
       }
       case "findRenameLocations": {
         return respond(
           id,
-          languageService.findRenameLocations(
+          service.findRenameLocations(
             request.specifier,
             request.position,
             request.findInStrings,
@@ -937,7 +1145,7 @@ delete Object.prototype.__proto__;
       case "getApplicableRefactors": {
         return respond(
           id,
-          languageService.getApplicableRefactors(
+          service.getApplicableRefactors(
             request.specifier,
             request.range,
             {
@@ -953,7 +1161,7 @@ delete Object.prototype.__proto__;
       case "getEditsForRefactor": {
         return respond(
           id,
-          languageService.getEditsForRefactor(
+          service.getEditsForRefactor(
             request.specifier,
             {
               indentSize: 2,
@@ -975,7 +1183,7 @@ delete Object.prototype.__proto__;
       case "getCodeFixes": {
         return respond(
           id,
-          languageService.getCodeFixesAtPosition(
+          service.getCodeFixesAtPosition(
             request.specifier,
             request.startPosition,
             request.endPosition,
@@ -994,7 +1202,7 @@ delete Object.prototype.__proto__;
       case "getCombinedCodeFix": {
         return respond(
           id,
-          languageService.getCombinedCodeFix(
+          service.getCombinedCodeFix(
             {
               type: "file",
               fileName: request.specifier,
@@ -1017,7 +1225,7 @@ delete Object.prototype.__proto__;
         }
         return respond(
           id,
-          languageService.getCompletionEntryDetails(
+          service.getCompletionEntryDetails(
             request.args.specifier,
             request.args.position,
             request.args.name,
@@ -1031,7 +1239,7 @@ delete Object.prototype.__proto__;
       case "getCompletions": {
         return respond(
           id,
-          languageService.getCompletionsAtPosition(
+          service.getCompletionsAtPosition(
             request.specifier,
             request.position,
             request.preferences,
@@ -1041,7 +1249,7 @@ delete Object.prototype.__proto__;
       case "getDefinition": {
         return respond(
           id,
-          languageService.getDefinitionAndBoundSpan(
+          service.getDefinitionAndBoundSpan(
             request.specifier,
             request.position,
           ),
@@ -1052,10 +1260,11 @@ delete Object.prototype.__proto__;
           /** @type {Record<string, any[]>} */
           const diagnosticMap = {};
           for (const specifier of request.specifiers) {
+            const service = getLanguageService(specifier);
             diagnosticMap[specifier] = fromTypeScriptDiagnostic([
-              ...languageService.getSemanticDiagnostics(specifier),
-              ...languageService.getSuggestionDiagnostics(specifier),
-              ...languageService.getSyntacticDiagnostics(specifier),
+              ...service.getSemanticDiagnostics(specifier),
+              ...service.getSuggestionDiagnostics(specifier),
+              ...service.getSyntacticDiagnostics(specifier),
             ].filter(({ code }) => !IGNORED_DIAGNOSTICS.includes(code)));
           }
           return respond(id, diagnosticMap);
@@ -1076,7 +1285,7 @@ delete Object.prototype.__proto__;
       case "getDocumentHighlights": {
         return respond(
           id,
-          languageService.getDocumentHighlights(
+          service.getDocumentHighlights(
             request.specifier,
             request.position,
             request.filesToSearch,
@@ -1086,7 +1295,7 @@ delete Object.prototype.__proto__;
       case "getEncodedSemanticClassifications": {
         return respond(
           id,
-          languageService.getEncodedSemanticClassifications(
+          service.getEncodedSemanticClassifications(
             request.specifier,
             request.span,
             ts.SemanticClassificationFormat.TwentyTwenty,
@@ -1096,7 +1305,7 @@ delete Object.prototype.__proto__;
       case "getImplementation": {
         return respond(
           id,
-          languageService.getImplementationAtPosition(
+          service.getImplementationAtPosition(
             request.specifier,
             request.position,
           ),
@@ -1105,7 +1314,7 @@ delete Object.prototype.__proto__;
       case "getNavigateToItems": {
         return respond(
           id,
-          languageService.getNavigateToItems(
+          service.getNavigateToItems(
             request.search,
             request.maxResultCount,
             request.fileName,
@@ -1115,13 +1324,13 @@ delete Object.prototype.__proto__;
       case "getNavigationTree": {
         return respond(
           id,
-          languageService.getNavigationTree(request.specifier),
+          service.getNavigationTree(request.specifier),
         );
       }
       case "getOutliningSpans": {
         return respond(
           id,
-          languageService.getOutliningSpans(
+          service.getOutliningSpans(
             request.specifier,
           ),
         );
@@ -1129,7 +1338,7 @@ delete Object.prototype.__proto__;
       case "getQuickInfo": {
         return respond(
           id,
-          languageService.getQuickInfoAtPosition(
+          service.getQuickInfoAtPosition(
             request.specifier,
             request.position,
           ),
@@ -1138,7 +1347,7 @@ delete Object.prototype.__proto__;
       case "getReferences": {
         return respond(
           id,
-          languageService.getReferencesAtPosition(
+          service.getReferencesAtPosition(
             request.specifier,
             request.position,
           ),
@@ -1147,7 +1356,7 @@ delete Object.prototype.__proto__;
       case "getSignatureHelpItems": {
         return respond(
           id,
-          languageService.getSignatureHelpItems(
+          service.getSignatureHelpItems(
             request.specifier,
             request.position,
             request.options,
@@ -1157,7 +1366,7 @@ delete Object.prototype.__proto__;
       case "getSmartSelectionRange": {
         return respond(
           id,
-          languageService.getSmartSelectionRange(
+          service.getSmartSelectionRange(
             request.specifier,
             request.position,
           ),
@@ -1172,7 +1381,7 @@ delete Object.prototype.__proto__;
       case "getTypeDefinition": {
         return respond(
           id,
-          languageService.getTypeDefinitionAtPosition(
+          service.getTypeDefinitionAtPosition(
             request.specifier,
             request.position,
           ),
@@ -1181,7 +1390,7 @@ delete Object.prototype.__proto__;
       case "prepareCallHierarchy": {
         return respond(
           id,
-          languageService.prepareCallHierarchy(
+          service.prepareCallHierarchy(
             request.specifier,
             request.position,
           ),
@@ -1190,7 +1399,7 @@ delete Object.prototype.__proto__;
       case "provideCallHierarchyIncomingCalls": {
         return respond(
           id,
-          languageService.provideCallHierarchyIncomingCalls(
+          service.provideCallHierarchyIncomingCalls(
             request.specifier,
             request.position,
           ),
@@ -1199,7 +1408,7 @@ delete Object.prototype.__proto__;
       case "provideCallHierarchyOutgoingCalls": {
         return respond(
           id,
-          languageService.provideCallHierarchyOutgoingCalls(
+          service.provideCallHierarchyOutgoingCalls(
             request.specifier,
             request.position,
           ),
@@ -1208,7 +1417,7 @@ delete Object.prototype.__proto__;
       case "provideInlayHints":
         return respond(
           id,
-          languageService.provideInlayHints(
+          service.provideInlayHints(
             request.specifier,
             request.span,
             request.preferences,
@@ -1236,6 +1445,7 @@ delete Object.prototype.__proto__;
 
   function serverRestart() {
     languageService = ts.createLanguageService(host, documentRegistry);
+    libOverrideServices.clear();
     isNodeSourceFileCache.clear();
     debug("serverRestart()");
   }
@@ -95,6 +95,8 @@ declare global {
     method: "configure";
     // deno-lint-ignore no-explicit-any
     compilerOptions: Record<string, any>;
+    /** The `lib` of each of the library overrides of the configuration. */
+    libOverrides: string[][];
   }
 
   interface FindRenameLocationsRequest extends BaseLanguageServerRequest {
@@ -13,6 +13,10 @@ use std::fmt;
 
 const MAX_SOURCE_LINE_LENGTH: usize = 150;
 
+/// The code of the warnings for the usages of APIs marked `@deprecated`, which
+/// are only reported on request.
+const DEPRECATION_CODE: u64 = 6385;
+
 const UNSTABLE_DENO_PROPS: &[&str] = &[
   "CreateHttpClientOptions",
   "DatagramConn",
@@ -53,17 +57,24 @@ static MSG_MISSING_PROPERTY_DENO: Lazy<Regex> = Lazy::new(|| {
 static MSG_SUGGESTION: Lazy<Regex> =
   Lazy::new(|| Regex::new(r#" Did you mean '([^']+)'\?"#).unwrap());
 
+/// Returns the name of the unstable `Deno` API a "raw" diagnostic message
+/// from TSC reports as missing, if any.
+pub fn get_unstable_deno_property(msg: &str) -> Option<&str> {
+  let property = MSG_MISSING_PROPERTY_DENO.captures(msg)?.get(1)?.as_str();
+  if UNSTABLE_DENO_PROPS.contains(&property) {
+    Some(property)
+  } else {
+    None
+  }
+}
+
 /// Potentially convert a "raw" diagnostic message from TSC to something that
 /// provides a more sensible error message given a Deno runtime context.
 fn format_message(msg: &str, code: &u64) -> String {
   match code {
     2339 => {
-      if let Some(captures) = MSG_MISSING_PROPERTY_DENO.captures(msg) {
-        if let Some(property) = captures.get(1) {
-          if UNSTABLE_DENO_PROPS.contains(&property.as_str()) {
-            return format!("{} 'Deno.{}' is an unstable API. Did you forget to run with the '--unstable' flag?", msg, property.as_str());
-          }
-        }
+      if let Some(property) = get_unstable_deno_property(msg) {
+        return format!("{msg} 'Deno.{property}' is an unstable API. Did you forget to run with the '--unstable' flag?");
       }
 
       msg.to_string()
@@ -316,6 +327,12 @@ impl Diagnostic {
   fn is_error(&self) -> bool {
     self.category == DiagnosticCategory::Error
   }
+
+  /// Whether this is the warning for a usage of a deprecated API.
+  pub fn is_deprecation(&self) -> bool {
+    self.code == DEPRECATION_CODE
+      && self.category == DiagnosticCategory::Warning
+  }
 }
 
 impl fmt::Display for Diagnostic {
@@ -344,9 +361,23 @@ impl Diagnostics {
     Self(diagnostics)
   }
 
+  /// Adds the diagnostics which aren't included already, like the ones of a
+  /// module which is type checked more than once.
+  pub fn extend_unique(&mut self, diagnostics: Diagnostics) {
+    for diagnostic in diagnostics.0 {
+      if !self.0.contains(&diagnostic) {
+        self.0.push(diagnostic);
+      }
+    }
+  }
+
   pub fn is_empty(&self) -> bool {
     self.0.is_empty()
   }
+
+  pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+    self.0.iter()
+  }
 }
 
 impl<'de> Deserialize<'de> for Diagnostics {
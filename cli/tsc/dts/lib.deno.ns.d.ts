@@ -484,6 +484,15 @@ declare namespace Deno {
      */
     sys?: "inherit" | boolean | string[];
 
+    /** Specifies if the `scheme` permission should be requested or revoked.
+     * If set to `"inherit"`, the current `scheme` permission will be inherited.
+     * If set to `true`, the global `scheme` permission will be requested.
+     * If set to `false`, the global `scheme` permission will be revoked.
+     *
+     * @default {false}
+     */
+    scheme?: "inherit" | boolean | string[];
+
     /** Specifies if the `hrtime` permission should be requested or revoked.
      * If set to `"inherit"`, the current `hrtime` permission will be inherited.
      * If set to `true`, the global `hrtime` permission will be requested.
@@ -693,6 +702,25 @@ declare namespace Deno {
      * ```
      */
     step(fn: (t: TestContext) => void | Promise<void>): Promise<boolean>;
+
+    /** Assert that the actual contents match a golden file, which is resolved
+     * relative to the test module. A string is compared as text, showing a
+     * unified diff when it doesn't match, while a `Uint8Array` is compared
+     * byte for byte, showing a hex dump of the first difference.
+     *
+     * When running `deno test --update-golden` (or with the `UPDATE_GOLDEN`
+     * environment variable set to `1`), the golden file is written with the
+     * actual contents instead, creating any missing directories. Reading and
+     * writing the golden file requires the corresponding permission.
+     *
+     * ```ts
+     * Deno.test("generates the migration", (t) => {
+     *   const sql = generateMigration();
+     *   t.assertGoldenFile(sql, "./testdata/migration.sql");
+     * });
+     * ```
+     */
+    assertGoldenFile(actual: string | Uint8Array, goldenPath: string): void;
   }
 
   /** @category Testing */
@@ -709,6 +737,13 @@ declare namespace Deno {
      * conditional logic, like determining if an environment feature is present.
      */
     ignore?: boolean;
+    /** Mark the step as a known failure. The step failing is reported as an
+     * expected failure and doesn't fail its parent, while the step passing
+     * is reported as unexpectedly passed and fails its parent.
+     *
+     * Steps of a test or step that is expected to fail are expected to fail
+     * as well, but them passing is not an error. */
+    expectedFailure?: boolean;
     /** Check that the number of async completed operations after the test step
      * is the same as number of dispatched operations. This ensures that the
      * code tested does not start async operations which it then does
@@ -744,6 +779,13 @@ declare namespace Deno {
     /** If at least one test has `only` set to `true`, only run tests that have
      * `only` set to `true` and fail the test suite. */
     only?: boolean;
+    /** Mark the test as a known failure, for example to track a bug that
+     * isn't fixed yet. The test failing is reported as an expected failure
+     * and doesn't fail the test suite, while the test passing is reported as
+     * unexpectedly passed and fails the test suite.
+     *
+     * @default {false} */
+    expectedFailure?: boolean;
     /** Check that the number of async completed operations after the test step
      * is the same as number of dispatched operations. This ensures that the
      * code tested does not start async operations which it then does
@@ -771,6 +813,17 @@ declare namespace Deno {
      *
      * @default {"inherit"} */
     permissions?: PermissionOptions;
+    /** Fail the test if it runs for longer than this many milliseconds,
+     * overriding the `--timeout` flag of `deno test`. A test that times out
+     * is stopped even while running synchronous code, and the remaining tests
+     * of the module run after evaluating it again. */
+    timeout?: number;
+    /** Tags to select the test with the `--tags` and `--skip-tags` flags of
+     * `deno test`. When `--tags` is specified, the tests without one of the
+     * tags are filtered out.
+     *
+     * @default {[]} */
+    tags?: string[];
   }
 
   /** Register a test which will be run when `deno test` is used on the command
@@ -4054,6 +4107,7 @@ declare namespace Deno {
     | "env"
     | "sys"
     | "ffi"
+    | "scheme"
     | "hrtime";
 
   /** The current status of the permission:
@@ -4176,6 +4230,18 @@ declare namespace Deno {
     path?: string | URL;
   }
 
+  /** The permission descriptor for the `allow-scheme` permissions, which
+   * controls importing modules from the custom schemes handled by the
+   * embedder. The option `scheme` allows scoping the permission to a specific
+   * scheme.
+   *
+   * @category Permissions */
+  export interface SchemePermissionDescriptor {
+    name: "scheme";
+    /** The scheme to scope the permission to, without the colon. */
+    scheme?: string;
+  }
+
   /** The permission descriptor for the `allow-hrtime` permission, which
    * controls if the runtime code has access to high resolution time. High
    * resolution time is consider sensitive information, because it can be used
@@ -4203,6 +4269,7 @@ declare namespace Deno {
     | EnvPermissionDescriptor
     | SysPermissionDescriptor
     | FfiPermissionDescriptor
+    | SchemePermissionDescriptor
     | HrtimePermissionDescriptor;
 
   /** The interface which defines what event types are supported by
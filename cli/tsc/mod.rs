@@ -34,6 +34,7 @@ use deno_graph::ModuleKind;
 use deno_graph::ResolutionResolved;
 use deno_runtime::deno_node::NodeResolutionMode;
 use deno_runtime::permissions::PermissionsContainer;
+#[cfg(feature = "tsc")]
 use once_cell::sync::Lazy;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -43,12 +44,14 @@ use std::sync::Arc;
 
 mod diagnostics;
 
+pub use self::diagnostics::get_unstable_deno_property;
 pub use self::diagnostics::Diagnostic;
 pub use self::diagnostics::DiagnosticCategory;
 pub use self::diagnostics::DiagnosticMessageChain;
 pub use self::diagnostics::Diagnostics;
 pub use self::diagnostics::Position;
 
+#[cfg(feature = "tsc")]
 pub static COMPILER_SNAPSHOT: Lazy<Box<[u8]>> = Lazy::new(
   #[cold]
   #[inline(never)]
@@ -66,9 +69,10 @@ pub static COMPILER_SNAPSHOT: Lazy<Box<[u8]>> = Lazy::new(
   },
 );
 
-pub fn get_types_declaration_file_text(unstable: bool) -> String {
-  let mut assets = get_asset_texts_from_new_runtime()
-    .unwrap()
+pub fn get_types_declaration_file_text(
+  unstable: bool,
+) -> Result<String, AnyError> {
+  let mut assets = get_asset_texts_from_new_runtime()?
     .into_iter()
     .map(|a| (a.specifier, a.text))
     .collect::<HashMap<_, _>>();
@@ -94,20 +98,22 @@ pub fn get_types_declaration_file_text(unstable: bool) -> String {
     lib_names.push("deno.unstable");
   }
 
-  lib_names
-    .into_iter()
-    .map(|name| {
-      let asset_url = format!("asset:///lib.{name}.d.ts");
-      assets.remove(&asset_url).unwrap()
-    })
-    .collect::<Vec<_>>()
-    .join("\n")
+  Ok(
+    lib_names
+      .into_iter()
+      .map(|name| {
+        let asset_url = format!("asset:///lib.{name}.d.ts");
+        assets.remove(&asset_url).unwrap()
+      })
+      .collect::<Vec<_>>()
+      .join("\n"),
+  )
 }
 
 fn get_asset_texts_from_new_runtime() -> Result<Vec<AssetText>, AnyError> {
   // the assets are stored within the typescript isolate, so take them out of there
   let mut runtime = JsRuntime::new(RuntimeOptions {
-    startup_snapshot: Some(compiler_snapshot()),
+    startup_snapshot: Some(compiler_snapshot()?),
     extensions: vec![Extension::builder("deno_cli_tsc")
       .ops(get_tsc_ops())
       .build()],
@@ -120,10 +126,21 @@ fn get_asset_texts_from_new_runtime() -> Result<Vec<AssetText>, AnyError> {
   Ok(serde_v8::from_v8::<Vec<AssetText>>(scope, local)?)
 }
 
-pub fn compiler_snapshot() -> Snapshot {
-  Snapshot::Static(&COMPILER_SNAPSHOT)
+#[cfg(feature = "tsc")]
+pub fn compiler_snapshot() -> Result<Snapshot, AnyError> {
+  Ok(Snapshot::Static(&COMPILER_SNAPSHOT))
 }
 
+/// Builds without the `tsc` feature, like the runtime embedded by
+/// `deno compile --strip`, leave out the compiler and its type definitions.
+#[cfg(not(feature = "tsc"))]
+pub fn compiler_snapshot() -> Result<Snapshot, AnyError> {
+  Err(anyhow!(
+    "This build of Deno doesn't include the TypeScript compiler, so it can't type check or provide the type definitions."
+  ))
+}
+
+#[cfg(feature = "tsc")]
 macro_rules! inc {
   ($e:expr) => {
     include_str!(concat!("./dts/", $e))
@@ -134,6 +151,7 @@ macro_rules! inc {
 ///
 /// We lazily load these because putting them in the compiler snapshot will
 /// increase memory usage when not used (last time checked by about 0.5MB).
+#[cfg(feature = "tsc")]
 pub static LAZILY_LOADED_STATIC_ASSETS: Lazy<
   HashMap<&'static str, &'static str>,
 > = Lazy::new(|| {
@@ -218,10 +236,16 @@ pub struct AssetText {
 }
 
 /// Retrieve a static asset that are included in the binary.
+#[cfg(feature = "tsc")]
 fn get_lazily_loaded_asset(asset: &str) -> Option<&'static str> {
   LAZILY_LOADED_STATIC_ASSETS.get(asset).map(|s| s.to_owned())
 }
 
+#[cfg(not(feature = "tsc"))]
+fn get_lazily_loaded_asset(_asset: &str) -> Option<&'static str> {
+  None
+}
+
 fn get_maybe_hash(
   maybe_source: Option<&str>,
   hash_data: &[Vec<u8>],
@@ -346,6 +370,9 @@ pub struct Request {
   pub maybe_config_specifier: Option<ModuleSpecifier>,
   pub maybe_npm_resolver: Option<NpmPackageResolver>,
   pub maybe_tsbuildinfo: Option<String>,
+  /// Whether to add a warning for each usage of an API marked `@deprecated`
+  /// to the diagnostics.
+  pub report_deprecations: bool,
   /// A vector of strings that represent the root/entry point modules for the
   /// program.
   pub root_names: Vec<(ModuleSpecifier, MediaType)>,
@@ -806,7 +833,7 @@ pub fn exec(request: Request) -> Result<Response, AnyError> {
     })
     .collect();
   let mut runtime = JsRuntime::new(RuntimeOptions {
-    startup_snapshot: Some(compiler_snapshot()),
+    startup_snapshot: Some(compiler_snapshot()?),
     extensions: vec![Extension::builder("deno_cli_tsc")
       .ops(get_tsc_ops())
       .state(move |state| {
@@ -829,6 +856,7 @@ pub fn exec(request: Request) -> Result<Response, AnyError> {
   let request_value = json!({
     "config": request.config,
     "debug": request.debug,
+    "reportDeprecations": request.report_deprecations,
     "rootNames": root_names,
   });
   let request_str = request_value.to_string();
@@ -871,7 +899,7 @@ fn get_tsc_ops() -> Vec<deno_core::OpDecl> {
   ]
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "tsc"))]
 mod tests {
   use super::Diagnostic;
   use super::DiagnosticCategory;
@@ -975,6 +1003,7 @@ mod tests {
       maybe_config_specifier: None,
       maybe_npm_resolver: None,
       maybe_tsbuildinfo: None,
+      report_deprecations: false,
       root_names: vec![(specifier.clone(), MediaType::TypeScript)],
     };
     exec(request)
@@ -983,7 +1012,7 @@ mod tests {
   #[test]
   fn test_compiler_snapshot() {
     let mut js_runtime = JsRuntime::new(RuntimeOptions {
-      startup_snapshot: Some(compiler_snapshot()),
+      startup_snapshot: Some(compiler_snapshot().unwrap()),
       ..Default::default()
     });
     js_runtime
@@ -0,0 +1,100 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Pinning threads to CPU cores, which is only supported on Linux. Elsewhere
+//! threads are never pinned.
+
+use std::sync::Arc;
+
+use deno_core::parking_lot::Mutex;
+
+/// A pool of the CPU cores the process may run on, from which threads take a
+/// core to be pinned to so that no two pinned threads share a core.
+#[derive(Clone)]
+pub struct CorePool(Arc<Mutex<Vec<usize>>>);
+
+impl CorePool {
+  pub fn from_allowed_cores() -> Self {
+    // the cores are taken from the end, so that the first core, which
+    // usually handles most interrupts, is taken last
+    Self(Arc::new(Mutex::new(allowed_cores())))
+  }
+
+  /// Pins the current thread to a free core until the returned guard is
+  /// dropped, or returns `None` when no core is free or pinning isn't
+  /// supported.
+  pub fn pin_current_thread(&self) -> Option<PinnedCore> {
+    let previous_cores = allowed_cores();
+    let core = self.0.lock().pop()?;
+    if !set_allowed_cores(&[core]) {
+      self.0.lock().push(core);
+      return None;
+    }
+    Some(PinnedCore {
+      pool: self.clone(),
+      core,
+      previous_cores,
+    })
+  }
+}
+
+/// A thread pinned to a core, which can run on its previous cores again and
+/// returns the core to the pool when dropped.
+pub struct PinnedCore {
+  pool: CorePool,
+  core: usize,
+  previous_cores: Vec<usize>,
+}
+
+impl Drop for PinnedCore {
+  fn drop(&mut self) {
+    set_allowed_cores(&self.previous_cores);
+    self.pool.0.lock().push(self.core);
+  }
+}
+
+/// Gets the cores the current thread may run on.
+#[cfg(target_os = "linux")]
+fn allowed_cores() -> Vec<usize> {
+  // SAFETY: the set is initialized by `sched_getaffinity` before it's read
+  unsafe {
+    let mut set: libc::cpu_set_t = std::mem::zeroed();
+    if libc::sched_getaffinity(
+      0,
+      std::mem::size_of::<libc::cpu_set_t>(),
+      &mut set,
+    ) != 0
+    {
+      return Vec::new();
+    }
+    (0..libc::CPU_SETSIZE as usize)
+      .filter(|core| libc::CPU_ISSET(*core, &set))
+      .collect()
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn allowed_cores() -> Vec<usize> {
+  Vec::new()
+}
+
+/// Restricts the current thread to run on the cores.
+#[cfg(target_os = "linux")]
+fn set_allowed_cores(cores: &[usize]) -> bool {
+  if cores.is_empty() {
+    return false;
+  }
+  // SAFETY: the set is zeroed and only holds cores below `CPU_SETSIZE`
+  unsafe {
+    let mut set: libc::cpu_set_t = std::mem::zeroed();
+    for core in cores {
+      libc::CPU_SET(*core, &mut set);
+    }
+    libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+      == 0
+  }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_allowed_cores(_cores: &[usize]) -> bool {
+  false
+}
@@ -1,6 +1,9 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use std::io::Read;
+
 use ring::digest::Context;
+use ring::digest::Digest;
 use ring::digest::SHA256;
 
 pub fn gen(v: &[impl AsRef<[u8]>]) -> String {
@@ -8,7 +11,25 @@ pub fn gen(v: &[impl AsRef<[u8]>]) -> String {
   for src in v {
     ctx.update(src.as_ref());
   }
-  let digest = ctx.finish();
+  to_hex(ctx.finish())
+}
+
+/// Like `gen`, for the bytes read from a reader, without holding them all in
+/// memory.
+pub fn gen_from_reader(mut reader: impl Read) -> std::io::Result<String> {
+  let mut ctx = Context::new(&SHA256);
+  let mut buf = vec![0; 64 * 1024];
+  loop {
+    let read = reader.read(&mut buf)?;
+    if read == 0 {
+      break;
+    }
+    ctx.update(&buf[..read]);
+  }
+  Ok(to_hex(ctx.finish()))
+}
+
+fn to_hex(digest: Digest) -> String {
   let out: Vec<String> = digest
     .as_ref()
     .iter()
@@ -29,4 +50,13 @@ mod tests {
       "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
     );
   }
+
+  #[test]
+  fn test_gen_from_reader() {
+    let bytes = "hello world".repeat(10_000);
+    assert_eq!(
+      gen_from_reader(bytes.as_bytes()).unwrap(),
+      gen(&[bytes.as_bytes()])
+    );
+  }
 }
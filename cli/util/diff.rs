@@ -1,8 +1,10 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 use crate::colors;
+use crate::util::display::pluralize;
 use dissimilar::diff as difference;
 use dissimilar::Chunk;
+use std::collections::HashMap;
 use std::fmt::Write as _;
 
 /// Print diff of the same file_path, before and after formatting.
@@ -145,6 +147,377 @@ impl DiffBuilder {
   }
 }
 
+/// The number of unchanged lines shown around each change of a unified diff.
+const UNIFIED_DIFF_CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineChange {
+  Equal,
+  Delete,
+  Insert,
+}
+
+/// A hunk of a unified diff: a run of changed lines with their context.
+struct UnifiedDiffHunk<'a> {
+  /// The number of lines of the original text before the hunk.
+  orig_line: usize,
+  orig_count: usize,
+  /// The number of lines of the edited text before the hunk.
+  edit_line: usize,
+  edit_count: usize,
+  lines: &'a [(LineChange, &'a str)],
+}
+
+/// Creates a unified diff of the lines of two texts, without colors, so that
+/// it can be included in an error message.
+pub fn unified_diff(
+  orig_name: &str,
+  edit_name: &str,
+  orig_text: &str,
+  edit_text: &str,
+) -> String {
+  build_unified_diff(orig_name, edit_name, orig_text, edit_text, None, false)
+}
+
+/// Creates a colored unified diff of the lines of two texts to print. When
+/// there are more than `max_hunks` hunks, the rest are left out and counted
+/// at the end instead.
+pub fn colored_unified_diff(
+  orig_name: &str,
+  edit_name: &str,
+  orig_text: &str,
+  edit_text: &str,
+  max_hunks: usize,
+) -> String {
+  build_unified_diff(
+    orig_name,
+    edit_name,
+    orig_text,
+    edit_text,
+    Some(max_hunks),
+    true,
+  )
+}
+
+fn build_unified_diff(
+  orig_name: &str,
+  edit_name: &str,
+  orig_text: &str,
+  edit_text: &str,
+  maybe_max_hunks: Option<usize>,
+  colored: bool,
+) -> String {
+  if orig_text == edit_text {
+    return String::new();
+  }
+
+  // normalize newlines as it adds too much noise if they differ
+  let orig_text = orig_text.replace("\r\n", "\n");
+  let edit_text = edit_text.replace("\r\n", "\n");
+
+  if orig_text == edit_text {
+    return " | Text differed by line endings.\n".to_string();
+  }
+
+  let orig_lines = orig_text.split('\n').collect::<Vec<_>>();
+  let edit_lines = edit_text.split('\n').collect::<Vec<_>>();
+  let lines = diff_lines(&orig_lines, &edit_lines);
+  let hunks = unified_diff_hunks(&lines);
+  let shown_count = maybe_max_hunks.unwrap_or(hunks.len()).min(hunks.len());
+
+  let paint = |text: String, color: fn(String) -> String| {
+    if colored {
+      color(text)
+    } else {
+      text
+    }
+  };
+  let mut output = String::new();
+  writeln!(
+    output,
+    "{}",
+    paint(format!("--- {orig_name}\n+++ {edit_name}"), |text| {
+      colors::bold(text).to_string()
+    })
+  )
+  .unwrap();
+  for hunk in &hunks[..shown_count] {
+    let header = format!(
+      "@@ -{} +{} @@",
+      hunk_range(hunk.orig_line, hunk.orig_count),
+      hunk_range(hunk.edit_line, hunk.edit_count),
+    );
+    writeln!(
+      output,
+      "{}",
+      paint(header, |text| colors::cyan(text).to_string())
+    )
+    .unwrap();
+    for (change, line) in hunk.lines {
+      let line = match change {
+        LineChange::Equal => format!(" {line}"),
+        LineChange::Delete => {
+          paint(format!("-{line}"), |text| colors::red(text).to_string())
+        }
+        LineChange::Insert => {
+          paint(format!("+{line}"), |text| colors::green(text).to_string())
+        }
+      };
+      writeln!(output, "{line}").unwrap();
+    }
+  }
+  let truncated_count = hunks.len() - shown_count;
+  if truncated_count > 0 {
+    let message = format!(
+      "... {} not shown",
+      pluralize(truncated_count, "more hunk", "more hunks")
+    );
+    writeln!(
+      output,
+      "{}",
+      paint(message, |text| colors::gray(text).to_string())
+    )
+    .unwrap();
+  }
+  output
+}
+
+fn unified_diff_hunks<'a>(
+  lines: &'a [(LineChange, &'a str)],
+) -> Vec<UnifiedDiffHunk<'a>> {
+  let mut hunks = Vec::new();
+  let mut index = 0;
+  let mut orig_line = 0;
+  let mut edit_line = 0;
+  while let Some(first_change) = lines[index..]
+    .iter()
+    .position(|(change, _)| *change != LineChange::Equal)
+    .map(|offset| index + offset)
+  {
+    // join the changes separated by less than twice the context
+    let mut changes_end = first_change;
+    loop {
+      while changes_end < lines.len()
+        && lines[changes_end].0 != LineChange::Equal
+      {
+        changes_end += 1;
+      }
+      match lines[changes_end..]
+        .iter()
+        .position(|(change, _)| *change != LineChange::Equal)
+      {
+        Some(equal_count) if equal_count <= UNIFIED_DIFF_CONTEXT * 2 => {
+          changes_end += equal_count;
+        }
+        _ => break,
+      }
+    }
+    let start = first_change.saturating_sub(UNIFIED_DIFF_CONTEXT).max(index);
+    let end = (changes_end + UNIFIED_DIFF_CONTEXT).min(lines.len());
+
+    for (change, _) in &lines[index..start] {
+      match change {
+        LineChange::Equal => {
+          orig_line += 1;
+          edit_line += 1;
+        }
+        LineChange::Delete => orig_line += 1,
+        LineChange::Insert => edit_line += 1,
+      }
+    }
+    let hunk_lines = &lines[start..end];
+    let orig_count = hunk_lines
+      .iter()
+      .filter(|(change, _)| *change != LineChange::Insert)
+      .count();
+    let edit_count = hunk_lines
+      .iter()
+      .filter(|(change, _)| *change != LineChange::Delete)
+      .count();
+    hunks.push(UnifiedDiffHunk {
+      orig_line,
+      orig_count,
+      edit_line,
+      edit_count,
+      lines: hunk_lines,
+    });
+    orig_line += orig_count;
+    edit_line += edit_count;
+    index = end;
+  }
+  hunks
+}
+
+fn hunk_range(lines_before: usize, count: usize) -> String {
+  if count == 0 {
+    format!("{lines_before},0")
+  } else if count == 1 {
+    format!("{}", lines_before + 1)
+  } else {
+    format!("{},{}", lines_before + 1, count)
+  }
+}
+
+/// Diffs two lists of lines by encoding every distinct line as a character,
+/// so that a diff of the encoded texts is a diff of the lines.
+fn diff_lines<'a>(
+  orig_lines: &[&'a str],
+  edit_lines: &[&'a str],
+) -> Vec<(LineChange, &'a str)> {
+  let mut line_chars = HashMap::new();
+  let mut encode = |lines: &[&'a str]| {
+    lines
+      .iter()
+      .map(|line| {
+        let next_index = line_chars.len() as u32;
+        *line_chars.entry(*line).or_insert_with(|| {
+          // skip the surrogate code points, which aren't valid chars
+          let code_point = if next_index < 0xD800 {
+            next_index
+          } else {
+            next_index + 0x800
+          };
+          char::from_u32(code_point).unwrap()
+        })
+      })
+      .collect::<String>()
+  };
+  let orig_encoded = encode(orig_lines);
+  let edit_encoded = encode(edit_lines);
+
+  let mut lines = Vec::with_capacity(orig_lines.len().max(edit_lines.len()));
+  let mut orig_index = 0;
+  let mut edit_index = 0;
+  for chunk in difference(&orig_encoded, &edit_encoded) {
+    match chunk {
+      Chunk::Equal(text) => {
+        for _ in text.chars() {
+          lines.push((LineChange::Equal, orig_lines[orig_index]));
+          orig_index += 1;
+          edit_index += 1;
+        }
+      }
+      Chunk::Delete(text) => {
+        for _ in text.chars() {
+          lines.push((LineChange::Delete, orig_lines[orig_index]));
+          orig_index += 1;
+        }
+      }
+      Chunk::Insert(text) => {
+        for _ in text.chars() {
+          lines.push((LineChange::Insert, edit_lines[edit_index]));
+          edit_index += 1;
+        }
+      }
+    }
+  }
+  lines
+}
+
+/// Returns the 1-based line and column in the original text where each line
+/// with changes starts to differ from the edited text.
+pub fn changed_positions(
+  orig_text: &str,
+  edit_text: &str,
+) -> Vec<(usize, usize)> {
+  if orig_text == edit_text {
+    return Vec::new();
+  }
+
+  let orig_text = orig_text.replace("\r\n", "\n");
+  let edit_text = edit_text.replace("\r\n", "\n");
+  if orig_text == edit_text {
+    return vec![(1, 1)];
+  }
+
+  let mut positions: Vec<(usize, usize)> = Vec::new();
+  let mut line = 1;
+  let mut column = 1;
+  for chunk in difference(&orig_text, &edit_text) {
+    let orig_chunk = match chunk {
+      Chunk::Equal(text) => text,
+      Chunk::Delete(text) => {
+        if positions.last().map(|(l, _)| *l) != Some(line) {
+          positions.push((line, column));
+        }
+        text
+      }
+      Chunk::Insert(_) => {
+        if positions.last().map(|(l, _)| *l) != Some(line) {
+          positions.push((line, column));
+        }
+        continue;
+      }
+    };
+    for c in orig_chunk.chars() {
+      if c == '\n' {
+        line += 1;
+        column = 1;
+      } else {
+        column += 1;
+      }
+    }
+  }
+  positions
+}
+
+/// The number of bytes shown per row of a hex diff.
+const HEX_DIFF_ROW_LEN: usize = 16;
+
+/// Creates a preview of the first difference between two byte sequences, as
+/// hex dumps of the rows around it.
+pub fn hex_diff(orig: &[u8], edit: &[u8]) -> String {
+  if orig == edit {
+    return String::new();
+  }
+
+  let offset = orig
+    .iter()
+    .zip(edit)
+    .position(|(a, b)| a != b)
+    .unwrap_or_else(|| orig.len().min(edit.len()));
+  let row = offset / HEX_DIFF_ROW_LEN;
+  let rows = row.saturating_sub(1)..=row + 1;
+  let mut output = format!(
+    "Bytes differ at offset {:#x} (expected {} bytes, actual {} bytes)\n",
+    offset,
+    orig.len(),
+    edit.len(),
+  );
+  for (prefix, bytes) in [('-', orig), ('+', edit)] {
+    for row in rows.clone() {
+      let start = row * HEX_DIFF_ROW_LEN;
+      if start >= bytes.len() && start > 0 {
+        break;
+      }
+      let end = (start + HEX_DIFF_ROW_LEN).min(bytes.len());
+      let row_bytes = &bytes[start.min(end)..end];
+      let hex = row_bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+      let text = row_bytes
+        .iter()
+        .map(|byte| {
+          if byte.is_ascii_graphic() || *byte == b' ' {
+            *byte as char
+          } else {
+            '.'
+          }
+        })
+        .collect::<String>();
+      writeln!(
+        output,
+        "{prefix} {start:08x}  {hex:<width$}  |{text}|",
+        width = HEX_DIFF_ROW_LEN * 3 - 1,
+      )
+      .unwrap();
+    }
+  }
+  output
+}
+
 fn fmt_add() -> String {
   colors::green_bold("+").to_string()
 }
@@ -219,6 +592,143 @@ mod tests {
     run_test("test\n", "test\r\n", " | Text differed by line endings.\n");
   }
 
+  #[test]
+  fn test_changed_positions() {
+    assert_eq!(changed_positions("a\nb\n", "a\nb\n"), vec![]);
+    assert_eq!(
+      changed_positions(
+        "const a = 1\nlet  b=2;\n",
+        "const a = 1;\nlet b = 2;\n"
+      ),
+      vec![(1, 12), (2, 5)]
+    );
+    assert_eq!(changed_positions("a\r\n", "a\n"), vec![(1, 1)]);
+  }
+
+  #[test]
+  fn test_unified_diff() {
+    assert_eq!(unified_diff("a", "b", "same\n", "same\n"), "");
+    assert_eq!(
+      unified_diff(
+        "a",
+        "b",
+        "1\n2\n3\n4\n5\n6\n7\n8\n",
+        "1\n2\n3\n4\nfive\n6\n7\n8\n"
+      ),
+      concat!(
+        "--- a\n",
+        "+++ b\n",
+        "@@ -2,7 +2,7 @@\n",
+        " 2\n",
+        " 3\n",
+        " 4\n",
+        "-5\n",
+        "+five\n",
+        " 6\n",
+        " 7\n",
+        " 8\n",
+      ),
+    );
+    // changes further apart than twice the context are separate hunks
+    let orig = (1..=20).map(|i| i.to_string()).collect::<Vec<_>>();
+    let mut edit = orig.clone();
+    edit[1] = "two".to_string();
+    edit.remove(17);
+    assert_eq!(
+      unified_diff("a", "b", &orig.join("\n"), &edit.join("\n")),
+      concat!(
+        "--- a\n",
+        "+++ b\n",
+        "@@ -1,5 +1,5 @@\n",
+        " 1\n",
+        "-2\n",
+        "+two\n",
+        " 3\n",
+        " 4\n",
+        " 5\n",
+        "@@ -15,6 +15,5 @@\n",
+        " 15\n",
+        " 16\n",
+        " 17\n",
+        "-18\n",
+        " 19\n",
+        " 20\n",
+      ),
+    );
+    assert_eq!(
+      unified_diff("a", "b", "", "added"),
+      concat!("--- a\n", "+++ b\n", "@@ -1 +1 @@\n", "-\n", "+added\n"),
+    );
+    assert_eq!(
+      unified_diff("a", "b", "test\n", "test\r\n"),
+      " | Text differed by line endings.\n",
+    );
+  }
+
+  #[test]
+  fn test_colored_unified_diff() {
+    let orig = (1..=40).map(|i| i.to_string()).collect::<Vec<_>>();
+    let mut edit = orig.clone();
+    edit[1] = "two".to_string();
+    edit[19] = "twenty".to_string();
+    edit[37] = "thirty-eight".to_string();
+    let diff = colored_unified_diff(
+      "a.ts",
+      "a.ts (formatted)",
+      &orig.join("\n"),
+      &edit.join("\n"),
+      2,
+    );
+    assert_eq!(
+      test_util::strip_ansi_codes(&diff),
+      concat!(
+        "--- a.ts\n",
+        "+++ a.ts (formatted)\n",
+        "@@ -1,5 +1,5 @@\n",
+        " 1\n",
+        "-2\n",
+        "+two\n",
+        " 3\n",
+        " 4\n",
+        " 5\n",
+        "@@ -17,7 +17,7 @@\n",
+        " 17\n",
+        " 18\n",
+        " 19\n",
+        "-20\n",
+        "+twenty\n",
+        " 21\n",
+        " 22\n",
+        " 23\n",
+        "... 1 more hunk not shown\n",
+      ),
+    );
+  }
+
+  #[test]
+  fn test_hex_diff() {
+    assert_eq!(hex_diff(b"same", b"same"), "");
+    assert_eq!(
+      hex_diff(b"0123456789abcdef0123456789abcdef\x00", b"0123456789abcdef0123456789abcdeF"),
+      concat!(
+        "Bytes differ at offset 0x1f (expected 33 bytes, actual 32 bytes)\n",
+        "- 00000000  30 31 32 33 34 35 36 37 38 39 61 62 63 64 65 66  |0123456789abcdef|\n",
+        "- 00000010  30 31 32 33 34 35 36 37 38 39 61 62 63 64 65 66  |0123456789abcdef|\n",
+        "- 00000020  00                                               |.|\n",
+        "+ 00000000  30 31 32 33 34 35 36 37 38 39 61 62 63 64 65 66  |0123456789abcdef|\n",
+        "+ 00000010  30 31 32 33 34 35 36 37 38 39 61 62 63 64 65 46  |0123456789abcdeF|\n",
+      ),
+    );
+    assert_eq!(
+      hex_diff(b"", b"\x01"),
+      concat!(
+        "Bytes differ at offset 0x0 (expected 0 bytes, actual 1 bytes)\n",
+        "- 00000000                                                   ||\n",
+        "+ 00000000  01                                               |.|\n",
+      ),
+    );
+  }
+
   fn run_test(diff_text1: &str, diff_text2: &str, expected_output: &str) {
     assert_eq!(
       test_util::strip_ansi_codes(&diff(diff_text1, diff_text2,)),
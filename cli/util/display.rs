@@ -1,7 +1,10 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::UnusedPermissionsFormat;
+use crate::colors;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
+use deno_runtime::permissions::Permissions;
 use std::io::Write;
 
 /// A function that converts a float to a string the represents a human
@@ -61,6 +64,16 @@ pub fn human_elapsed(elapsed: u128) -> String {
   format!("{minutes}m{seconds_remainder}s")
 }
 
+/// Formats a count along with the singular or plural form of what it counts,
+/// like "1 file" or "2 files".
+pub fn pluralize(count: usize, singular: &str, plural: &str) -> String {
+  if count == 1 {
+    format!("{count} {singular}")
+  } else {
+    format!("{count} {plural}")
+  }
+}
+
 pub fn write_to_stdout_ignore_sigpipe(
   bytes: &[u8],
 ) -> Result<(), std::io::Error> {
@@ -85,6 +98,58 @@ where
   Ok(())
 }
 
+/// Writes an error to stdout as a JSON object, for subcommands whose output
+/// is expected to be machine readable even when they fail.
+pub fn write_json_error_to_stdout(err: &AnyError) -> Result<(), AnyError> {
+  write_json_to_stdout(&serde_json::json!({
+    "error": {
+      "class": crate::errors::get_error_class_name(err),
+      "message": format!("{err:#}"),
+    }
+  }))
+}
+
+/// Quotes a value for a POSIX shell when it isn't made of safe characters.
+pub fn shell_quote(value: &str) -> String {
+  if !value.is_empty()
+    && value.chars().all(|c| {
+      c.is_ascii_alphanumeric()
+        || matches!(c, '-' | '_' | '.' | '/' | '@' | '=' | ':' | ',' | '+')
+    })
+  {
+    value.to_string()
+  } else {
+    format!("'{}'", value.replace('\'', "'\\''"))
+  }
+}
+
+/// Prints the permissions that were granted but never used, for
+/// `--report-unused-permissions`.
+pub fn print_unused_permissions(
+  permissions: &Permissions,
+  format: UnusedPermissionsFormat,
+) {
+  let unused_permissions = permissions.unused_permissions();
+  match format {
+    UnusedPermissionsFormat::Json => {
+      let report = serde_json::json!({
+        "unusedPermissions": unused_permissions,
+      });
+      eprintln!("{}", serde_json::to_string_pretty(&report).unwrap());
+    }
+    UnusedPermissionsFormat::Text => {
+      for permission in unused_permissions {
+        log::warn!(
+          "{} {} was granted but no {} access occurred",
+          colors::yellow("Warning"),
+          permission.flag,
+          permission.name
+        );
+      }
+    }
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -128,6 +193,15 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_shell_quote() {
+    assert_eq!(shell_quote("@scope/app-1.0"), "@scope/app-1.0");
+    assert_eq!(shell_quote("--allow-read=a,b"), "--allow-read=a,b");
+    assert_eq!(shell_quote("my app"), "'my app'");
+    assert_eq!(shell_quote("it's"), "'it'\\''s'");
+    assert_eq!(shell_quote(""), "''");
+  }
+
   #[test]
   fn test_human_elapsed() {
     assert_eq!(human_elapsed(1), "1ms");
@@ -138,4 +212,11 @@ mod tests {
     assert_eq!(human_elapsed(70 * 1000), "1m10s");
     assert_eq!(human_elapsed(86 * 1000 + 100), "1m26s");
   }
+
+  #[test]
+  fn test_pluralize() {
+    assert_eq!(pluralize(0, "file", "files"), "0 files");
+    assert_eq!(pluralize(1, "file", "files"), "1 file");
+    assert_eq!(pluralize(2, "type error", "type errors"), "2 type errors");
+  }
 }
@@ -0,0 +1,403 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Reading the properties of files from `.editorconfig` files, following
+//! https://spec.editorconfig.org.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use deno_core::parking_lot::Mutex;
+use regex::Regex;
+
+const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+/// The longest range of numbers in a glob which is matched exactly, while
+/// longer ranges match any number.
+const MAX_RANGE_LEN: i64 = 1000;
+
+/// The properties of a section, as key value pairs.
+type Properties = Vec<(String, String)>;
+
+/// A parsed `.editorconfig` file.
+#[derive(Debug)]
+struct EditorConfigFile {
+  path: PathBuf,
+  text: String,
+  is_root: bool,
+  /// The properties of each section, with the regex its glob translates to.
+  sections: Vec<(Regex, Properties)>,
+}
+
+impl EditorConfigFile {
+  fn parse(path: PathBuf, text: String) -> Self {
+    let mut is_root = false;
+    let mut sections = Vec::new();
+    // the properties of a section with an invalid glob are skipped
+    let mut maybe_section: Option<(Option<Regex>, Properties)> = None;
+    for line in text.lines() {
+      let line = line.trim();
+      if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+        continue;
+      }
+      if let Some(header) = line.strip_prefix('[') {
+        if let Some(end) = header.rfind(']') {
+          if let Some((Some(regex), properties)) = maybe_section.take() {
+            sections.push((regex, properties));
+          }
+          maybe_section = Some((glob_to_regex(&header[..end]), Vec::new()));
+          continue;
+        }
+      }
+      if let Some((key, value)) = line.split_once('=') {
+        let key = key.trim().to_lowercase();
+        let value = strip_inline_comment(value).trim().to_lowercase();
+        match &mut maybe_section {
+          Some((_, properties)) => properties.push((key, value)),
+          None if key == "root" => is_root = value == "true",
+          None => {}
+        }
+      }
+    }
+    if let Some((Some(regex), properties)) = maybe_section {
+      sections.push((regex, properties));
+    }
+    Self {
+      path,
+      text,
+      is_root,
+      sections,
+    }
+  }
+
+  /// Adds the properties of the sections matching the file, which is
+  /// relative to the directory of the `.editorconfig` file.
+  fn add_properties(
+    &self,
+    relative_path: &str,
+    properties: &mut HashMap<String, String>,
+  ) {
+    for (regex, section_properties) in &self.sections {
+      if regex.is_match(relative_path) {
+        for (key, value) in section_properties {
+          properties.insert(key.clone(), value.clone());
+        }
+      }
+    }
+  }
+}
+
+/// Resolves the properties of files from the `.editorconfig` files in their
+/// directories and the directories above, caching the parsed files.
+#[derive(Debug, Default)]
+pub struct EditorConfigResolver {
+  files_by_dir: Mutex<HashMap<PathBuf, Option<Arc<EditorConfigFile>>>>,
+}
+
+impl EditorConfigResolver {
+  /// Gets the properties of a file, with lowercased keys and values. The
+  /// `.editorconfig` files closer to the file override the ones further up,
+  /// up to the first one marked as root.
+  pub fn properties(&self, file_path: &Path) -> HashMap<String, String> {
+    let file_path = if file_path.is_absolute() {
+      file_path.to_path_buf()
+    } else {
+      match std::env::current_dir() {
+        Ok(cwd) => cwd.join(file_path),
+        Err(_) => return HashMap::new(),
+      }
+    };
+    let mut files = Vec::new();
+    for dir in file_path.ancestors().skip(1) {
+      if let Some(file) = self.get_or_load(dir) {
+        let is_root = file.is_root;
+        files.push(file);
+        if is_root {
+          break;
+        }
+      }
+    }
+
+    let mut properties = HashMap::new();
+    for file in files.iter().rev() {
+      let dir = file.path.parent().unwrap();
+      if let Ok(relative_path) = file_path.strip_prefix(dir) {
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+        file.add_properties(&relative_path, &mut properties);
+      }
+    }
+    // "unset" removes a property set by a previous section or file
+    properties.retain(|_, value| value != "unset");
+    properties
+  }
+
+  /// Gets the paths and the contents of the `.editorconfig` files read so
+  /// far, sorted by path.
+  pub fn loaded_files(&self) -> Vec<(PathBuf, String)> {
+    let mut files = self
+      .files_by_dir
+      .lock()
+      .values()
+      .flatten()
+      .map(|file| (file.path.clone(), file.text.clone()))
+      .collect::<Vec<_>>();
+    files.sort();
+    files
+  }
+
+  fn get_or_load(&self, dir: &Path) -> Option<Arc<EditorConfigFile>> {
+    if let Some(maybe_file) = self.files_by_dir.lock().get(dir) {
+      return maybe_file.clone();
+    }
+    let path = dir.join(EDITORCONFIG_FILE_NAME);
+    let maybe_file = std::fs::read_to_string(&path)
+      .ok()
+      .map(|text| Arc::new(EditorConfigFile::parse(path, text)));
+    self
+      .files_by_dir
+      .lock()
+      .insert(dir.to_path_buf(), maybe_file.clone());
+    maybe_file
+  }
+}
+
+/// Strips a comment following a value, which must be preceded by whitespace
+/// so that values like `#fff` are kept.
+fn strip_inline_comment(value: &str) -> &str {
+  let maybe_index = [" #", " ;", "\t#", "\t;"]
+    .iter()
+    .filter_map(|marker| value.find(marker))
+    .min();
+  match maybe_index {
+    Some(index) => &value[..index],
+    None => value,
+  }
+}
+
+/// Translates the glob of a section to a regex matching the paths relative
+/// to the directory of the `.editorconfig` file. A glob without a `/` matches
+/// files in any subdirectory, and `None` is returned for an invalid glob.
+fn glob_to_regex(glob: &str) -> Option<Regex> {
+  let (glob, is_anchored) = match glob.strip_prefix('/') {
+    Some(glob) => (glob, true),
+    None => (glob, glob.contains('/')),
+  };
+  let mut regex = String::from("^");
+  if !is_anchored {
+    regex.push_str("(?:.*/)?");
+  }
+  translate_glob(&glob.chars().collect::<Vec<_>>(), &mut regex);
+  regex.push('$');
+  Regex::new(&regex).ok()
+}
+
+fn translate_glob(chars: &[char], out: &mut String) {
+  let mut i = 0;
+  while i < chars.len() {
+    match chars[i] {
+      '\\' if i + 1 < chars.len() => {
+        i += 1;
+        push_literal(chars[i], out);
+      }
+      '*' if chars.get(i + 1) == Some(&'*') => {
+        i += 1;
+        if chars.get(i + 1) == Some(&'/') {
+          // "**/" also matches no directory
+          i += 1;
+          out.push_str("(?:.*/)?");
+        } else {
+          out.push_str(".*");
+        }
+      }
+      '*' => out.push_str("[^/]*"),
+      '?' => out.push_str("[^/]"),
+      '[' => match chars[i + 1..].iter().position(|c| *c == ']') {
+        Some(len) => {
+          let class = &chars[i + 1..i + 1 + len];
+          let (is_negated, class) = match class.first() {
+            Some('!') | Some('^') => (true, &class[1..]),
+            _ => (false, class),
+          };
+          out.push_str(if is_negated { "[^" } else { "[" });
+          for c in class {
+            match c {
+              '-' => out.push('-'),
+              c => out.push_str(&regex::escape(&c.to_string())),
+            }
+          }
+          out.push(']');
+          i += len + 1;
+        }
+        None => push_literal('[', out),
+      },
+      '{' => match find_closing_brace(chars, i) {
+        Some(end) => {
+          translate_braces(&chars[i + 1..end], out);
+          i = end;
+        }
+        None => push_literal('{', out),
+      },
+      c => push_literal(c, out),
+    }
+    i += 1;
+  }
+}
+
+/// Translates the contents of braces, which are either a range of numbers
+/// like `{1..3}` or alternatives like `{ts,tsx}`.
+fn translate_braces(chars: &[char], out: &mut String) {
+  let text = chars.iter().collect::<String>();
+  if let Some((start, end)) = text.split_once("..") {
+    if let (Ok(start), Ok(end)) = (start.parse::<i64>(), end.parse::<i64>()) {
+      let (start, end) = (start.min(end), start.max(end));
+      if end - start > MAX_RANGE_LEN {
+        out.push_str("[+-]?[0-9]+");
+      } else {
+        let numbers = (start..=end).map(|n| n.to_string()).collect::<Vec<_>>();
+        out.push_str(&format!("(?:{})", numbers.join("|")));
+      }
+      return;
+    }
+  }
+
+  let mut alternatives = Vec::new();
+  let mut depth = 0;
+  let mut start = 0;
+  for (i, c) in chars.iter().enumerate() {
+    match c {
+      '{' => depth += 1,
+      '}' => depth -= 1,
+      ',' if depth == 0 => {
+        alternatives.push(&chars[start..i]);
+        start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  alternatives.push(&chars[start..]);
+  if alternatives.len() == 1 {
+    // braces without alternatives are matched literally
+    out.push_str(&regex::escape(&format!("{{{text}}}")));
+    return;
+  }
+  out.push_str("(?:");
+  for (i, alternative) in alternatives.iter().enumerate() {
+    if i > 0 {
+      out.push('|');
+    }
+    translate_glob(alternative, out);
+  }
+  out.push(')');
+}
+
+fn find_closing_brace(chars: &[char], open_index: usize) -> Option<usize> {
+  let mut depth = 0;
+  for (i, c) in chars.iter().enumerate().skip(open_index) {
+    match c {
+      '{' => depth += 1,
+      '}' => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(i);
+        }
+      }
+      _ => {}
+    }
+  }
+  None
+}
+
+fn push_literal(c: char, out: &mut String) {
+  out.push_str(&regex::escape(&c.to_string()));
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  fn matches(glob: &str, path: &str) -> bool {
+    glob_to_regex(glob).unwrap().is_match(path)
+  }
+
+  #[test]
+  fn translates_globs() {
+    assert!(matches("*", "a.ts"));
+    assert!(matches("*", "dir/a.ts"));
+    assert!(matches("*.ts", "dir/sub/a.ts"));
+    assert!(!matches("*.ts", "a.tsx"));
+    assert!(matches("*.{ts,tsx}", "a.tsx"));
+    assert!(!matches("*.{ts,tsx}", "a.js"));
+    assert!(matches("{a,b{c,d}}.ts", "bd.ts"));
+    assert!(matches("{single}.ts", "{single}.ts"));
+    assert!(matches("file_{1..3}.ts", "file_2.ts"));
+    assert!(!matches("file_{1..3}.ts", "file_4.ts"));
+    assert!(matches("?.md", "a.md"));
+    assert!(!matches("?.md", "ab.md"));
+    assert!(matches("[abc].json", "b.json"));
+    assert!(!matches("[!abc].json", "b.json"));
+    assert!(matches("lib/*.js", "lib/a.js"));
+    assert!(!matches("lib/*.js", "src/lib/a.js"));
+    assert!(!matches("lib/*.js", "lib/sub/a.js"));
+    assert!(matches("/lib/**.js", "lib/sub/a.js"));
+    assert!(matches("lib/**/a.js", "lib/a.js"));
+    assert!(matches("a+b.(ts)", "a+b.(ts)"));
+  }
+
+  #[test]
+  fn parses_files() {
+    let file = EditorConfigFile::parse(
+      PathBuf::from("/.editorconfig"),
+      concat!(
+        "; comment\n",
+        "ROOT = True\n",
+        "[*]\n",
+        "Indent_Style = Tab # comment\n",
+        "[*.md] # comment\n",
+        "indent_size = 4\n",
+        "[{unclosed]\n",
+        "indent_size = 8\n",
+      )
+      .to_string(),
+    );
+    assert!(file.is_root);
+    let mut properties = HashMap::new();
+    file.add_properties("dir/readme.md", &mut properties);
+    assert_eq!(
+      properties,
+      HashMap::from([
+        ("indent_style".to_string(), "tab".to_string()),
+        ("indent_size".to_string(), "4".to_string()),
+      ])
+    );
+  }
+
+  #[test]
+  fn resolves_nested_files() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("project/sub");
+    temp_dir.write(
+      "project/.editorconfig",
+      "root = true\n[*]\nindent_style = space\nindent_size = 2\nmax_line_length = 100\n",
+    );
+    temp_dir.write(
+      "project/sub/.editorconfig",
+      "[*.ts]\nindent_size = 4\nmax_line_length = unset\n",
+    );
+    let resolver = EditorConfigResolver::default();
+    let properties =
+      resolver.properties(&temp_dir.path().join("project/sub/mod.ts"));
+    assert_eq!(
+      properties,
+      HashMap::from([
+        ("indent_style".to_string(), "space".to_string()),
+        ("indent_size".to_string(), "4".to_string()),
+      ])
+    );
+    let properties =
+      resolver.properties(&temp_dir.path().join("project/sub/data.json"));
+    assert_eq!(properties.get("indent_size").unwrap(), "2");
+    assert_eq!(properties.get("max_line_length").unwrap(), "100");
+    assert_eq!(resolver.loaded_files().len(), 2);
+  }
+}
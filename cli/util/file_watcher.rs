@@ -7,6 +7,7 @@ use deno_core::error::AnyError;
 use deno_core::error::JsError;
 use deno_core::futures::Future;
 use deno_runtime::fmt_errors::format_js_error;
+use deno_runtime::fmt_errors::StackTraceOptions;
 use log::info;
 use notify::event::Event as NotifyEvent;
 use notify::event::EventKind;
@@ -14,8 +15,12 @@ use notify::Error as NotifyError;
 use notify::RecommendedWatcher;
 use notify::RecursiveMode;
 use notify::Watcher;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::fmt;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::select;
@@ -66,14 +71,16 @@ impl DebouncedReceiver {
   }
 }
 
-async fn error_handler<F>(watch_future: F)
-where
+async fn error_handler<F>(
+  watch_future: F,
+  stack_trace_options: &StackTraceOptions,
+) where
   F: Future<Output = Result<(), AnyError>>,
 {
   let result = watch_future.await;
   if let Err(err) = result {
     let error_string = match err.downcast_ref::<JsError>() {
-      Some(e) => format_js_error(e),
+      Some(e) => format_js_error(e, stack_trace_options),
       None => format!("{err:?}"),
     };
     eprintln!(
@@ -125,6 +132,8 @@ pub struct PrintConfig {
   pub job_name: String,
   /// determine whether to clear the terminal screen; applicable to TTY environments only.
   pub clear_screen: bool,
+  /// how the stack traces of the errors of the operation are printed.
+  pub stack_trace_options: StackTraceOptions,
 }
 
 fn create_print_after_restart_fn(clear_screen: bool) -> impl Fn() {
@@ -139,6 +148,112 @@ fn create_print_after_restart_fn(clear_screen: bool) -> impl Fn() {
   }
 }
 
+/// Outputs the time a run started, which tells runs apart when the
+/// terminal screen is not cleared between them.
+pub fn print_run_started(job_name: &str) {
+  info!(
+    "{} {} run started at {}",
+    colors::intense_blue("Watcher"),
+    job_name,
+    chrono::Local::now().format("%H:%M:%S"),
+  );
+}
+
+/// The change in diagnostics between two runs of a watcher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiagnosticsDelta {
+  pub new: usize,
+  pub fixed: usize,
+}
+
+impl fmt::Display for DiagnosticsDelta {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} new {}, {} fixed",
+      self.new,
+      if self.new == 1 { "problem" } else { "problems" },
+      self.fixed,
+    )
+  }
+}
+
+/// Retains the diagnostics of the previous run of a watcher in memory, so
+/// that subsequent runs only need to output what changed.
+///
+/// Diagnostics are identified by a key that should not include their
+/// position, so that a diagnostic that moved is not reported again.
+#[derive(Debug, Default)]
+pub struct WatchDiagnostics {
+  maybe_previous: Option<HashMap<String, usize>>,
+}
+
+impl WatchDiagnostics {
+  /// Retains the diagnostic keys of the current run, returning whether each
+  /// diagnostic is new along with the change since the previous run. The
+  /// change is `None` for the first run.
+  pub fn update(
+    &mut self,
+    keys: &[String],
+  ) -> (Vec<bool>, Option<DiagnosticsDelta>) {
+    let mut current: HashMap<String, usize> = HashMap::new();
+    for key in keys {
+      *current.entry(key.clone()).or_default() += 1;
+    }
+
+    let maybe_previous = self.maybe_previous.replace(current);
+    // the diagnostics of the previous run which weren't reported again
+    let mut remaining = match maybe_previous {
+      Some(previous) => previous,
+      None => return (vec![true; keys.len()], None),
+    };
+    let is_new = keys
+      .iter()
+      .map(|key| match remaining.get_mut(key) {
+        Some(count) if *count > 0 => {
+          *count -= 1;
+          false
+        }
+        _ => true,
+      })
+      .collect::<Vec<_>>();
+    let delta = DiagnosticsDelta {
+      new: is_new.iter().filter(|is_new| **is_new).count(),
+      fixed: remaining.values().sum(),
+    };
+    (is_new, Some(delta))
+  }
+}
+
+/// Identifies a diagnostic between runs of the watcher without using its
+/// position, which changes when unrelated lines are edited.
+pub fn diagnostic_key(
+  file_name: &str,
+  code: impl fmt::Display,
+  message: &str,
+  line_text: &str,
+) -> String {
+  format!("{file_name}:{code}:{message}:{}", line_text.trim())
+}
+
+/// Runs the provided watcher until it's interrupted with Ctrl-C, at which
+/// point the process exits with the exit code of the watcher's last run.
+pub async fn watch_until_interrupted<F>(
+  watch_future: F,
+  last_exit_code: Arc<AtomicI32>,
+) -> Result<(), AnyError>
+where
+  F: Future<Output = Result<(), AnyError>>,
+{
+  select! {
+    result = watch_future => result,
+    result = tokio::signal::ctrl_c() => {
+      result?;
+      std::process::exit(last_exit_code.load(Ordering::Relaxed));
+    }
+  }
+}
+
 /// Creates a file watcher, which will call `resolver` with every file change.
 ///
 /// - `resolver` is used for resolving file paths to be watched at every restarting
@@ -165,6 +280,7 @@ where
   let PrintConfig {
     job_name,
     clear_screen,
+    stack_trace_options,
   } = print_config;
 
   // Store previous data. If module resolution fails at some point, the watcher will try to
@@ -214,9 +330,15 @@ where
 
     match resolution_result {
       Ok(operation_arg) => {
-        let fut = error_handler(operation(operation_arg));
+        let mut fut = Box::pin(error_handler(
+          operation(operation_arg),
+          &stack_trace_options,
+        ));
         select! {
           (paths, result) = next_restart(&mut resolver, &mut receiver) => {
+            // drop the previous run, like its worker, before the next one
+            // resets the state
+            drop(fut);
             if result.is_ok() {
               paths_to_watch = paths;
             }
@@ -225,7 +347,7 @@ where
             print_after_restart();
             continue;
           },
-          _ = fut => {},
+          _ = &mut fut => {},
         };
 
         info!(
@@ -260,7 +382,8 @@ where
 ///
 /// - `operation` is the actual operation we want to run every time the watcher detects file
 /// changes. For example, in the case where we would like to bundle, then `operation` would
-/// have the logic for it like bundling the code.
+/// have the logic for it like bundling the code. It receives the paths that
+/// changed since the previous run, or `None` on the first run.
 pub async fn watch_func2<T: Clone, O, F>(
   mut paths_to_watch_receiver: UnboundedReceiver<Vec<PathBuf>>,
   mut operation: O,
@@ -268,7 +391,7 @@ pub async fn watch_func2<T: Clone, O, F>(
   print_config: PrintConfig,
 ) -> Result<(), AnyError>
 where
-  O: FnMut(T) -> Result<F, AnyError>,
+  O: FnMut(T, Option<Vec<PathBuf>>) -> Result<F, AnyError>,
   F: Future<Output = Result<(), AnyError>>,
 {
   let (watcher_sender, mut watcher_receiver) =
@@ -277,6 +400,7 @@ where
   let PrintConfig {
     job_name,
     clear_screen,
+    stack_trace_options,
   } = print_config;
 
   let print_after_restart = create_print_after_restart_fn(clear_screen);
@@ -303,6 +427,7 @@ where
     }
   }
 
+  let mut changed_paths = None;
   loop {
     let mut watcher = new_watcher(watcher_sender.clone())?;
     consume_paths_to_watch(&mut watcher, &mut paths_to_watch_receiver);
@@ -313,15 +438,22 @@ where
         add_paths_to_watcher(&mut watcher, &maybe_paths.unwrap());
       }
     };
-    let operation_future = error_handler(operation(operation_args.clone())?);
+    let mut operation_future = Box::pin(error_handler(
+      operation(operation_args.clone(), changed_paths.take())?,
+      &stack_trace_options,
+    ));
 
     select! {
       _ = receiver_future => {},
-      _ = watcher_receiver.recv() => {
+      paths = watcher_receiver.recv() => {
+        // drop the previous run, like its worker, before the next one resets
+        // the state
+        drop(operation_future);
+        changed_paths = paths;
         print_after_restart();
         continue;
       },
-      _ = operation_future => {
+      _ = &mut operation_future => {
         consume_paths_to_watch(&mut watcher, &mut paths_to_watch_receiver);
         // TODO(bartlomieju): print exit code here?
         info!(
@@ -340,7 +472,8 @@ where
     };
     select! {
       _ = receiver_future => {},
-      _ = watcher_receiver.recv() => {
+      paths = watcher_receiver.recv() => {
+        changed_paths = paths;
         print_after_restart();
         continue;
       },
@@ -380,3 +513,41 @@ fn add_paths_to_watcher(watcher: &mut RecommendedWatcher, paths: &[PathBuf]) {
   }
   log::debug!("Watching paths: {:?}", paths);
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn watch_diagnostics_delta() {
+    let mut watch_diagnostics = WatchDiagnostics::default();
+    let keys =
+      |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+
+    let (is_new, delta) = watch_diagnostics.update(&keys(&["a", "b", "b"]));
+    assert_eq!(is_new, vec![true, true, true]);
+    assert_eq!(delta, None);
+
+    let (is_new, delta) = watch_diagnostics.update(&keys(&["b", "c", "c"]));
+    assert_eq!(is_new, vec![false, true, true]);
+    let delta = delta.unwrap();
+    assert_eq!(delta, DiagnosticsDelta { new: 2, fixed: 2 });
+    assert_eq!(delta.to_string(), "2 new problems, 2 fixed");
+
+    let (is_new, delta) = watch_diagnostics.update(&keys(&[]));
+    assert!(is_new.is_empty());
+    assert_eq!(delta, Some(DiagnosticsDelta { new: 0, fixed: 3 }));
+  }
+
+  #[test]
+  fn diagnostic_key_ignores_indentation() {
+    assert_eq!(
+      diagnostic_key("file:///a.ts", 2322, "Type error.", "  let a = 1;"),
+      diagnostic_key("file:///a.ts", 2322, "Type error.", "let a = 1;"),
+    );
+    assert_ne!(
+      diagnostic_key("file:///a.ts", "no-var", "No var.", "var a = 1;"),
+      diagnostic_key("file:///b.ts", "no-var", "No var.", "var a = 1;"),
+    );
+  }
+}
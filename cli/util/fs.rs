@@ -19,6 +19,10 @@ use walkdir::WalkDir;
 
 use crate::args::FilesConfig;
 
+use super::glob::is_glob_pattern;
+use super::glob::FilePatterns;
+use super::glob::PathOrPattern;
+use super::path::path_to_specifier;
 use super::path::specifier_to_file_path;
 
 pub fn atomic_write_file<T: AsRef<[u8]>>(
@@ -168,8 +172,9 @@ pub fn resolve_from_cwd(path: &Path) -> Result<PathBuf, AnyError> {
 
 /// Collects file paths that satisfy the given predicate, by recursively walking `files`.
 /// If the walker visits a path that is listed in `ignore`, it skips descending into the directory.
+/// Both `files` and the ignored paths may contain glob patterns (see [`super::glob`]).
 pub struct FileCollector<TFilter: Fn(&Path) -> bool> {
-  canonicalized_ignore: Vec<PathBuf>,
+  ignore: FilePatterns,
   file_filter: TFilter,
   ignore_git_folder: bool,
   ignore_node_modules: bool,
@@ -178,7 +183,7 @@ pub struct FileCollector<TFilter: Fn(&Path) -> bool> {
 impl<TFilter: Fn(&Path) -> bool> FileCollector<TFilter> {
   pub fn new(file_filter: TFilter) -> Self {
     Self {
-      canonicalized_ignore: Default::default(),
+      ignore: Default::default(),
       file_filter,
       ignore_git_folder: false,
       ignore_node_modules: false,
@@ -186,10 +191,16 @@ impl<TFilter: Fn(&Path) -> bool> FileCollector<TFilter> {
   }
 
   pub fn add_ignore_paths(mut self, paths: &[PathBuf]) -> Self {
+    let cwd = current_dir().unwrap_or_default();
     // retain only the paths which exist and ignore the rest
-    self
-      .canonicalized_ignore
-      .extend(paths.iter().filter_map(|i| canonicalize_path(i).ok()));
+    self.ignore.exclude.extend(paths.iter().filter_map(|i| {
+      match PathOrPattern::new(i, &cwd).ok()? {
+        PathOrPattern::Path(path) => {
+          canonicalize_path(&path).ok().map(PathOrPattern::Path)
+        }
+        pattern => Some(pattern),
+      }
+    }));
     self
   }
 
@@ -208,14 +219,30 @@ impl<TFilter: Fn(&Path) -> bool> FileCollector<TFilter> {
     files: &[PathBuf],
   ) -> Result<Vec<PathBuf>, AnyError> {
     let mut target_files = Vec::new();
-    let files = if files.is_empty() {
+    let cwd = current_dir()?;
+    let mut include = FilePatterns::default();
+    for file in files {
+      match PathOrPattern::new(file, &cwd)? {
+        PathOrPattern::Path(path) => {
+          if let Ok(path) = canonicalize_path(&path) {
+            include.include.push(PathOrPattern::Path(path));
+          }
+        }
+        pattern => include.include.push(pattern),
+      }
+    }
+    let mut roots = include
+      .include
+      .iter()
+      .filter(|entry| !entry.is_negated())
+      .map(|entry| entry.base_path())
+      .collect::<Vec<_>>();
+    if roots.is_empty() {
       // collect files in the current directory when empty
-      Cow::Owned(vec![PathBuf::from(".")])
-    } else {
-      Cow::Borrowed(files)
-    };
-    for file in files.iter() {
-      if let Ok(file) = canonicalize_path(file) {
+      roots.push(cwd);
+    }
+    for root in roots {
+      if let Ok(file) = canonicalize_path(&root) {
         // use an iterator like this in order to minimize the number of file system operations
         let mut iterator = WalkDir::new(&file).into_iter();
         loop {
@@ -227,8 +254,8 @@ impl<TFilter: Fn(&Path) -> bool> FileCollector<TFilter> {
           let file_type = e.file_type();
           let is_dir = file_type.is_dir();
           if let Ok(c) = canonicalize_path(e.path()) {
-            if self.canonicalized_ignore.iter().any(|i| c.starts_with(i)) {
-              if is_dir {
+            if self.ignore.is_excluded(&c) {
+              if is_dir && self.ignore.is_excluded_dir(&c) {
                 iterator.skip_current_dir();
               }
             } else if is_dir {
@@ -246,7 +273,7 @@ impl<TFilter: Fn(&Path) -> bool> FileCollector<TFilter> {
               if should_ignore_dir {
                 iterator.skip_current_dir();
               }
-            } else if (self.file_filter)(e.path()) {
+            } else if (self.file_filter)(e.path()) && include.matches_path(&c) {
               target_files.push(c);
             }
           } else if is_dir {
@@ -280,35 +307,48 @@ pub fn collect_specifiers(
   } else {
     Cow::Borrowed(&files.include)
   };
+  // negated entries apply to the files collected for every other entry
+  let (negated, include_files): (Vec<_>, Vec<_>) = include_files
+    .iter()
+    .cloned()
+    .partition(|path| path.to_string_lossy().starts_with('!'));
+  let include_files = if include_files.is_empty() {
+    vec![root_path.clone()]
+  } else {
+    include_files
+  };
   for path in include_files.iter() {
-    let path = path.to_string_lossy();
-    let lowercase_path = path.to_lowercase();
-    if lowercase_path.starts_with("http://")
-      || lowercase_path.starts_with("https://")
+    // the text is only used to detect URLs and globs, so that paths which
+    // aren't valid Unicode are still joined as they are
+    let text = path.to_string_lossy();
+    let lowercase_text = text.to_lowercase();
+    if lowercase_text.starts_with("http://")
+      || lowercase_text.starts_with("https://")
     {
-      let url = ModuleSpecifier::parse(&path)?;
+      let url = ModuleSpecifier::parse(&text)?;
       prepared.push(url);
       continue;
     }
 
-    let p = if lowercase_path.starts_with("file://") {
-      specifier_to_file_path(&ModuleSpecifier::parse(&path)?)?
+    let p = if lowercase_text.starts_with("file://") {
+      specifier_to_file_path(&ModuleSpecifier::parse(&text)?)?
     } else {
-      root_path.join(path.as_ref())
+      root_path.join(path)
     };
     let p = normalize_path(p);
-    if p.is_dir() {
-      let test_files = file_collector.collect_files(&[p])?;
+    if p.is_dir() || !p.exists() && is_glob_pattern(&text) {
+      let mut entries = vec![p];
+      entries.extend(negated.iter().cloned());
+      let test_files = file_collector.collect_files(&entries)?;
       let mut test_files_as_urls = test_files
         .iter()
-        .map(|f| ModuleSpecifier::from_file_path(f).unwrap())
-        .collect::<Vec<ModuleSpecifier>>();
+        .map(|f| path_to_specifier(f))
+        .collect::<Result<Vec<ModuleSpecifier>, _>>()?;
 
       test_files_as_urls.sort();
       prepared.extend(test_files_as_urls);
     } else {
-      let url = ModuleSpecifier::from_file_path(p).unwrap();
-      prepared.push(url);
+      prepared.push(path_to_specifier(&p)?);
     }
   }
 
@@ -317,6 +357,7 @@ pub fn collect_specifiers(
 
 /// Asynchronously removes a directory and all its descendants, but does not error
 /// when the directory does not exist.
+#[cfg_attr(not(feature = "lsp"), allow(dead_code))]
 pub async fn remove_dir_all_if_exists(path: &Path) -> std::io::Result<()> {
   let result = tokio::fs::remove_dir_all(path).await;
   match result {
@@ -644,6 +685,61 @@ mod tests {
     assert_eq!(file_names, expected);
   }
 
+  #[test]
+  fn test_collect_files_with_patterns() {
+    let t = TempDir::new();
+    t.create_dir_all("src/nested");
+    t.create_dir_all("src/testdata");
+    for file in [
+      "src/a.ts",
+      "src/b.js",
+      "src/nested/c.ts",
+      "src/nested/d.md",
+      "src/testdata/e.ts",
+    ] {
+      t.write(file, "");
+    }
+    let root_dir_path = t.path().to_path_buf();
+    let collect = |files: &[PathBuf], ignore: &[PathBuf]| {
+      let result = FileCollector::new(|_| true)
+        .add_ignore_paths(ignore)
+        .collect_files(files)
+        .unwrap();
+      let mut file_names = result
+        .into_iter()
+        .map(|r| r.file_name().unwrap().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+      file_names.sort();
+      file_names
+    };
+    let negated = |path: &str| {
+      PathBuf::from(format!("!{}", root_dir_path.join(path).display()))
+    };
+
+    assert_eq!(
+      collect(&[root_dir_path.join("src/**/*.ts")], &[]),
+      ["a.ts", "c.ts", "e.ts"]
+    );
+    assert_eq!(
+      collect(
+        &[root_dir_path.join("src/**/*.{ts,md}")],
+        &[root_dir_path.join("**/testdata")]
+      ),
+      ["a.ts", "c.ts", "d.md"]
+    );
+    assert_eq!(
+      collect(&[root_dir_path.join("src"), negated("src/nested")], &[]),
+      ["a.ts", "b.js", "e.ts"]
+    );
+    assert_eq!(
+      collect(
+        &[root_dir_path.join("src")],
+        &[root_dir_path.join("src/*"), negated("src/nested/c.ts")]
+      ),
+      ["c.ts"]
+    );
+  }
+
   #[test]
   fn test_collect_specifiers() {
     fn create_files(dir_path: &Path, files: &[&str]) {
@@ -691,14 +787,16 @@ mod tests {
     };
 
     let result = collect_specifiers(
-      &FilesConfig {
-        include: vec![
+      &FilesConfig::new(
+        vec![
           PathBuf::from("http://localhost:8080"),
           root_dir_path.clone(),
           PathBuf::from("https://localhost:8080".to_string()),
         ],
-        exclude: vec![ignore_dir_path],
-      },
+        vec![ignore_dir_path],
+        &root_dir_path,
+      )
+      .unwrap(),
       predicate,
     )
     .unwrap();
@@ -731,8 +829,8 @@ mod tests {
       "file://"
     };
     let result = collect_specifiers(
-      &FilesConfig {
-        include: vec![PathBuf::from(format!(
+      &FilesConfig::new(
+        vec![PathBuf::from(format!(
           "{}{}",
           scheme,
           root_dir_path
@@ -741,8 +839,10 @@ mod tests {
             .unwrap()
             .replace('\\', "/")
         ))],
-        exclude: vec![],
-      },
+        vec![],
+        &root_dir_path,
+      )
+      .unwrap(),
       predicate,
     )
     .unwrap();
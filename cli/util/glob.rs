@@ -0,0 +1,558 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Matching of the `include` and `exclude` paths used by `deno fmt`,
+//! `deno lint`, `deno test` and `deno bench`. Each entry is either a path,
+//! which matches itself and everything inside of it, or a glob pattern.
+//!
+//! Patterns support `*`, `**`, `?`, character classes (`[abc]`, `[a-z]`,
+//! `[!abc]`) and braces (`{a,b}`). A leading `!` negates an entry, so that
+//! it removes matches of the preceding entries of the same list. Both `/`
+//! and `\` are treated as path separators.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::error::generic_error;
+use deno_core::error::AnyError;
+use deno_core::normalize_path;
+use regex::Regex;
+
+use super::fs::canonicalize_path;
+
+/// Gets if the text contains any characters that have a special meaning in
+/// a glob pattern.
+pub fn is_glob_pattern(text: &str) -> bool {
+  text.starts_with('!') || has_glob_syntax(text)
+}
+
+fn has_glob_syntax(text: &str) -> bool {
+  text.contains(GLOB_SYNTAX_CHARS)
+}
+
+const GLOB_SYNTAX_CHARS: [char; 4] = ['*', '?', '[', '{'];
+
+/// Creates the error for when no files were found. Paths containing glob
+/// syntax were matched literally in the past, so the error explains how they
+/// are interpreted now.
+pub fn no_files_found_error(
+  message: &'static str,
+  include: &[PathBuf],
+) -> AnyError {
+  let has_pattern = include
+    .iter()
+    .any(|path| is_glob_pattern(&path.to_string_lossy()));
+  if has_pattern {
+    generic_error(format!(
+      "{message}\n\n{}",
+      concat!(
+        "Paths containing '*', '?', '[' or '{' or starting with '!' are ",
+        "matched as glob patterns unless they exist on disk. Wrap special ",
+        "characters in brackets (ex. '[[]') to match them literally."
+      )
+    ))
+  } else {
+    generic_error(message)
+  }
+}
+
+#[derive(Debug)]
+pub struct GlobPattern {
+  text: String,
+  base: String,
+  regex: Regex,
+  negated: bool,
+}
+
+impl GlobPattern {
+  /// Creates a pattern from an absolute pattern that uses `/` separators.
+  fn new(text: String, negated: bool) -> Result<Self, AnyError> {
+    let regex = Regex::new(&glob_to_regex(&text)?)?;
+    Ok(Self {
+      base: get_base(&text).to_string(),
+      text,
+      regex,
+      negated,
+    })
+  }
+
+  /// The path of the directory holding all the matches of this pattern.
+  pub fn base_path(&self) -> PathBuf {
+    PathBuf::from(&self.base)
+  }
+
+  fn matches_text(&self, path: &str) -> bool {
+    self.regex.is_match(path)
+  }
+}
+
+#[derive(Debug)]
+pub enum PathOrPattern {
+  Path(PathBuf),
+  Pattern(GlobPattern),
+}
+
+impl PathOrPattern {
+  /// Parses an `include` or `exclude` entry, resolving relative entries
+  /// against `base`. Entries containing pattern syntax that exist on disk
+  /// are kept as paths.
+  pub fn new(path: &Path, base: &Path) -> Result<Self, AnyError> {
+    let text = path.to_string_lossy();
+    let is_pattern = is_glob_pattern(&text) && !base.join(path).exists();
+    if !is_pattern {
+      return Ok(PathOrPattern::Path(normalize_path(base.join(path))));
+    }
+
+    let (negated, text) = match text.strip_prefix('!') {
+      Some(text) => (true, text),
+      None => (false, text.as_ref()),
+    };
+    let text = text.replace('\\', "/");
+    let text = if is_absolute(&text) {
+      text
+    } else {
+      let base = to_slash_text(base);
+      format!("{}/{}", base.trim_end_matches('/'), text)
+    };
+    let mut pattern = GlobPattern::new(normalize_text(&text), negated)?;
+    // match the canonicalized paths of the file collectors
+    if let Ok(canonical_base) = canonicalize_path(&pattern.base_path()) {
+      let canonical_base = to_slash_text(&canonical_base);
+      if canonical_base != pattern.base {
+        let text =
+          format!("{}{}", canonical_base, &pattern.text[pattern.base.len()..]);
+        pattern = GlobPattern::new(text, negated)?;
+      }
+    }
+    Ok(PathOrPattern::Pattern(pattern))
+  }
+
+  pub fn is_negated(&self) -> bool {
+    match self {
+      PathOrPattern::Path(_) => false,
+      PathOrPattern::Pattern(pattern) => pattern.negated,
+    }
+  }
+
+  /// The path of the directory or file holding all the matches.
+  pub fn base_path(&self) -> PathBuf {
+    match self {
+      PathOrPattern::Path(path) => path.clone(),
+      PathOrPattern::Pattern(pattern) => pattern.base_path(),
+    }
+  }
+
+  /// Gets if the path is matched by the entry, ignoring negation.
+  pub fn matches_path(&self, path: &Path) -> bool {
+    let path = to_slash_text(path);
+    match self {
+      PathOrPattern::Path(entry) => {
+        let entry = to_slash_text(entry);
+        let entry = entry.trim_end_matches('/');
+        path == entry
+          || path.starts_with(entry) && path[entry.len()..].starts_with('/')
+      }
+      PathOrPattern::Pattern(pattern) => pattern.matches_text(&path),
+    }
+  }
+}
+
+/// The compiled `include` and `exclude` entries of a tool.
+#[derive(Debug, Default)]
+pub struct FilePatterns {
+  pub include: Vec<PathOrPattern>,
+  pub exclude: Vec<PathOrPattern>,
+}
+
+impl FilePatterns {
+  pub fn new(
+    include: &[PathBuf],
+    exclude: &[PathBuf],
+    base: &Path,
+  ) -> Result<Self, AnyError> {
+    let parse = |paths: &[PathBuf]| {
+      paths
+        .iter()
+        .map(|path| PathOrPattern::new(path, base))
+        .collect::<Result<Vec<_>, _>>()
+    };
+    Ok(Self {
+      include: parse(include)?,
+      exclude: parse(exclude)?,
+    })
+  }
+
+  /// Gets if the path is matched by the includes and not by the excludes.
+  /// An empty list of includes matches every path.
+  pub fn matches_path(&self, path: &Path) -> bool {
+    let included = self.include.iter().all(|entry| entry.is_negated());
+    let included = last_match(&self.include, path).unwrap_or(included);
+    included && !self.is_excluded(path)
+  }
+
+  /// Gets if the path is matched by the excludes.
+  pub fn is_excluded(&self, path: &Path) -> bool {
+    last_match(&self.exclude, path).unwrap_or(false)
+  }
+
+  /// Gets if a directory can be skipped entirely because of the excludes.
+  pub fn is_excluded_dir(&self, path: &Path) -> bool {
+    self.is_excluded(path) && !self.exclude.iter().any(|e| e.is_negated())
+  }
+}
+
+/// Evaluates the entries in order so that the last matching entry wins.
+fn last_match(entries: &[PathOrPattern], path: &Path) -> Option<bool> {
+  entries
+    .iter()
+    .rev()
+    .find(|entry| entry.matches_path(path))
+    .map(|entry| !entry.is_negated())
+}
+
+fn to_slash_text(path: &Path) -> String {
+  path.to_string_lossy().replace('\\', "/")
+}
+
+fn is_absolute(text: &str) -> bool {
+  let bytes = text.as_bytes();
+  text.starts_with('/')
+    || bytes.len() > 2
+      && bytes[0].is_ascii_alphabetic()
+      && bytes[1] == b':'
+      && bytes[2] == b'/'
+}
+
+/// Removes `.` and `..` segments that come before any pattern syntax.
+fn normalize_text(text: &str) -> String {
+  let mut segments: Vec<&str> = Vec::new();
+  let mut parts = text.split('/');
+  while let Some(part) = parts.next() {
+    if has_glob_syntax(part) {
+      segments.push(part);
+      segments.extend(parts.by_ref());
+      // a trailing separator doesn't change what a pattern matches
+      while segments.len() > 1 && segments.last() == Some(&"") {
+        segments.pop();
+      }
+      break;
+    }
+    match part {
+      "." => {}
+      ".." if segments.len() > 1 => {
+        segments.pop();
+      }
+      "" if !segments.is_empty() => {}
+      _ => segments.push(part),
+    }
+  }
+  segments.join("/")
+}
+
+/// Gets the leading segments of the pattern that contain no pattern syntax.
+fn get_base(text: &str) -> &str {
+  let end = text
+    .find(GLOB_SYNTAX_CHARS)
+    .and_then(|index| text[..index].rfind('/'))
+    .unwrap_or(text.len());
+  if end == 0 {
+    "/"
+  } else {
+    &text[..end]
+  }
+}
+
+fn glob_to_regex(text: &str) -> Result<String, AnyError> {
+  let chars = text.chars().collect::<Vec<_>>();
+  let mut regex = String::from("^");
+  let mut brace_depth = 0;
+  let mut i = 0;
+  while i < chars.len() {
+    let c = chars[i];
+    match c {
+      '*' => {
+        let start = i;
+        while i < chars.len() && chars[i] == '*' {
+          i += 1;
+        }
+        let is_segment_start = start == 0 || chars[start - 1] == '/';
+        let is_segment_end = i == chars.len() || chars[i] == '/';
+        if i - start > 1 && is_segment_start && is_segment_end {
+          if i == chars.len() {
+            regex.push_str(".*");
+          } else {
+            // `**/` matches zero or more directories
+            regex.push_str("(?:[^/]*/)*");
+            i += 1;
+          }
+        } else {
+          regex.push_str("[^/]*");
+        }
+        continue;
+      }
+      '?' => regex.push_str("[^/]"),
+      '[' => match parse_class(&chars[i + 1..]) {
+        Some((class, len)) => {
+          regex.push_str(&class);
+          i += len;
+        }
+        None => regex.push_str(r"\["),
+      },
+      '{' => {
+        brace_depth += 1;
+        regex.push_str("(?:");
+      }
+      '}' if brace_depth > 0 => {
+        brace_depth -= 1;
+        regex.push(')');
+      }
+      ',' if brace_depth > 0 => regex.push('|'),
+      _ => regex.push_str(&regex::escape(&c.to_string())),
+    }
+    i += 1;
+  }
+  if brace_depth > 0 {
+    bail!("Unclosed brace in pattern: {}", text);
+  }
+  // a pattern matching a directory matches everything inside of it
+  regex.push_str("(?:/.*)?$");
+  Ok(regex)
+}
+
+/// Parses the character class following a `[`, returning the regex and the
+/// number of characters consumed, including the closing `]`.
+fn parse_class(chars: &[char]) -> Option<(String, usize)> {
+  let mut class = String::from("[");
+  let mut i = 0;
+  if matches!(chars.first(), Some('!' | '^')) {
+    class.push('^');
+    i += 1;
+  }
+  let content_start = i;
+  while i < chars.len() {
+    let c = chars[i];
+    if c == ']' && i > content_start {
+      class.push(']');
+      return Some((class, i + 1));
+    }
+    if c == '/' {
+      return None;
+    }
+    if matches!(c, '\\' | '[' | ']' | '&' | '~') {
+      class.push('\\');
+    }
+    class.push(c);
+    i += 1;
+  }
+  None
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  /// The files the patterns are matched against, relative to the base.
+  const FIXTURE: &[&str] = &[
+    "mod.ts",
+    "mod_test.ts",
+    "README.md",
+    "src/a.ts",
+    "src/b.js",
+    "src/c.tsx",
+    "src/nested/d.ts",
+    "src/nested/e.md",
+    "src/testdata/f.ts",
+    "src/[id].ts",
+    "tests/a_test.ts",
+    "tests/b_test.js",
+  ];
+
+  fn run_test(
+    base: &str,
+    separator: char,
+    include: &[&str],
+    exclude: &[&str],
+  ) -> Vec<&'static str> {
+    let to_path =
+      |text: &str| PathBuf::from(text.replace('/', &separator.to_string()));
+    let base = to_path(base);
+    let patterns = FilePatterns::new(
+      &include.iter().map(|p| to_path(p)).collect::<Vec<_>>(),
+      &exclude.iter().map(|p| to_path(p)).collect::<Vec<_>>(),
+      &base,
+    )
+    .unwrap();
+    FIXTURE
+      .iter()
+      .copied()
+      .filter(|file| {
+        let path = to_path(&format!("{}/{}", to_slash_text(&base), file));
+        patterns.matches_path(&path)
+      })
+      .collect()
+  }
+
+  /// Runs the patterns against the fixture using unix and windows style
+  /// paths, which must produce the same matches.
+  fn assert_matches(include: &[&str], exclude: &[&str], expected: &[&str]) {
+    assert_eq!(run_test("/project", '/', include, exclude), expected);
+    assert_eq!(run_test("C:/project", '\\', include, exclude), expected);
+  }
+
+  #[test]
+  fn matches_paths() {
+    assert_matches(&[], &[], FIXTURE);
+    assert_matches(
+      &["src"],
+      &[],
+      &[
+        "src/a.ts",
+        "src/b.js",
+        "src/c.tsx",
+        "src/nested/d.ts",
+        "src/nested/e.md",
+        "src/testdata/f.ts",
+        "src/[id].ts",
+      ],
+    );
+    assert_matches(
+      &["src/nested/d.ts", "tests"],
+      &[],
+      &["src/nested/d.ts", "tests/a_test.ts", "tests/b_test.js"],
+    );
+    assert_matches(
+      &[],
+      &["src", "mod_test.ts"],
+      &["mod.ts", "README.md", "tests/a_test.ts", "tests/b_test.js"],
+    );
+  }
+
+  #[test]
+  fn matches_wildcards() {
+    assert_matches(&["*.ts"], &[], &["mod.ts", "mod_test.ts"]);
+    assert_matches(
+      &["**/*.ts"],
+      &[],
+      &[
+        "mod.ts",
+        "mod_test.ts",
+        "src/a.ts",
+        "src/nested/d.ts",
+        "src/testdata/f.ts",
+        "src/[id].ts",
+        "tests/a_test.ts",
+      ],
+    );
+    assert_matches(
+      &["src/**"],
+      &["**/*.ts"],
+      &["src/b.js", "src/c.tsx", "src/nested/e.md"],
+    );
+    assert_matches(&["src/?.ts"], &[], &["src/a.ts"]);
+    assert_matches(
+      &["**/*_test.*"],
+      &[],
+      &["mod_test.ts", "tests/a_test.ts", "tests/b_test.js"],
+    );
+    assert_matches(
+      &["src/*"],
+      &["src/*/*"],
+      &["src/a.ts", "src/b.js", "src/c.tsx", "src/[id].ts"],
+    );
+    // a pattern matching a directory matches the files inside of it
+    assert_matches(&["src/n*"], &[], &["src/nested/d.ts", "src/nested/e.md"]);
+    assert_matches(
+      &[],
+      &["**/testdata", "**/nested"],
+      &[
+        "mod.ts",
+        "mod_test.ts",
+        "README.md",
+        "src/a.ts",
+        "src/b.js",
+        "src/c.tsx",
+        "src/[id].ts",
+        "tests/a_test.ts",
+        "tests/b_test.js",
+      ],
+    );
+  }
+
+  #[test]
+  fn matches_braces_and_classes() {
+    assert_matches(
+      &["src/*.{ts,tsx}"],
+      &[],
+      &["src/a.ts", "src/c.tsx", "src/[id].ts"],
+    );
+    assert_matches(
+      &["{src,tests}/{a,b}*.{js,ts}"],
+      &[],
+      &["src/a.ts", "src/b.js", "tests/a_test.ts", "tests/b_test.js"],
+    );
+    assert_matches(&["src/[a-b].*"], &[], &["src/a.ts", "src/b.js"]);
+    assert_matches(&["src/[!a-b].*"], &[], &["src/c.tsx"]);
+    assert_matches(&["src/[[]id].ts"], &[], &["src/[id].ts"]);
+  }
+
+  #[test]
+  fn matches_negations() {
+    assert_matches(
+      &["src", "!src/nested", "!**/*.js"],
+      &[],
+      &["src/a.ts", "src/c.tsx", "src/testdata/f.ts", "src/[id].ts"],
+    );
+    assert_matches(
+      &["!**/*.md"],
+      &[],
+      &[
+        "mod.ts",
+        "mod_test.ts",
+        "src/a.ts",
+        "src/b.js",
+        "src/c.tsx",
+        "src/nested/d.ts",
+        "src/testdata/f.ts",
+        "src/[id].ts",
+        "tests/a_test.ts",
+        "tests/b_test.js",
+      ],
+    );
+    // later entries take precedence
+    assert_matches(
+      &["tests"],
+      &["tests", "!tests/a_test.ts"],
+      &["tests/a_test.ts"],
+    );
+    assert_matches(&["tests"], &["!tests/a_test.ts", "tests"], &[]);
+  }
+
+  #[test]
+  fn resolves_relative_segments() {
+    assert_matches(&["./src/../tests/*.js"], &[], &["tests/b_test.js"]);
+  }
+
+  #[test]
+  fn errors_on_unclosed_brace() {
+    let err = FilePatterns::new(
+      &[PathBuf::from("src/{a,b")],
+      &[],
+      Path::new("/project"),
+    )
+    .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Unclosed brace in pattern: /project/src/{a,b"
+    );
+  }
+
+  #[test]
+  fn gets_base_path() {
+    assert_eq!(get_base("/project/src/**/*.ts"), "/project/src");
+    assert_eq!(get_base("/project/{src,tests}"), "/project");
+    assert_eq!(get_base("C:/project/*.ts"), "C:/project");
+    assert_eq!(get_base("/*.ts"), "/");
+    assert_eq!(get_base("/project/src"), "/project/src");
+  }
+}
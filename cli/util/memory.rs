@@ -0,0 +1,112 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use deno_runtime::ops::os::rss;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::display;
+
+/// The peak memory usage observed while the sampler was running.
+#[derive(
+  Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryReport {
+  /// Peak resident set size of the process in bytes.
+  pub peak_rss: u64,
+  /// Peak V8 heap size in bytes used across all isolates that reported it.
+  pub peak_heap_used: u64,
+  /// Peak V8 heap size in bytes reserved across all isolates that
+  /// reported it.
+  pub peak_heap_total: u64,
+}
+
+impl std::fmt::Display for MemoryReport {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "peak memory: {} rss, {} heap used, {} heap total",
+      display::human_size(self.peak_rss as f64),
+      display::human_size(self.peak_heap_used as f64),
+      display::human_size(self.peak_heap_total as f64),
+    )
+  }
+}
+
+#[derive(Debug, Default)]
+struct MemorySamplerInner {
+  peak_rss: AtomicU64,
+  peak_heap_used: AtomicU64,
+  peak_heap_total: AtomicU64,
+  stopped: AtomicBool,
+}
+
+/// Polls the resident set size of the process on a background thread and
+/// records the V8 heap statistics reported to it. Clones share the same
+/// peaks.
+///
+/// The polling thread is a plain OS thread so it never keeps an event loop
+/// alive and it stops itself once the sampler is stopped.
+#[derive(Debug, Clone)]
+pub struct MemorySampler(Arc<MemorySamplerInner>);
+
+impl MemorySampler {
+  pub fn start(interval: Duration) -> Self {
+    let sampler = Self(Default::default());
+    sampler.record_rss();
+    let inner = sampler.0.clone();
+    std::thread::spawn(move || {
+      while !inner.stopped.load(Ordering::Relaxed) {
+        inner.peak_rss.fetch_max(rss() as u64, Ordering::Relaxed);
+        std::thread::sleep(interval);
+      }
+    });
+    sampler
+  }
+
+  fn record_rss(&self) {
+    self.0.peak_rss.fetch_max(rss() as u64, Ordering::Relaxed);
+  }
+
+  /// Records the heap statistics of an isolate.
+  pub fn record_heap(&self, used: u64, total: u64) {
+    self.0.peak_heap_used.fetch_max(used, Ordering::Relaxed);
+    self.0.peak_heap_total.fetch_max(total, Ordering::Relaxed);
+  }
+
+  /// Stops the polling thread and returns the peaks observed.
+  pub fn stop(&self) -> MemoryReport {
+    self.record_rss();
+    self.0.stopped.store(true, Ordering::Relaxed);
+    MemoryReport {
+      peak_rss: self.0.peak_rss.load(Ordering::Relaxed),
+      peak_heap_used: self.0.peak_heap_used.load(Ordering::Relaxed),
+      peak_heap_total: self.0.peak_heap_total.load(Ordering::Relaxed),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn records_peaks() {
+    let sampler = MemorySampler::start(Duration::from_millis(10));
+    sampler.record_heap(10, 20);
+    let worker_sampler = sampler.clone();
+    std::thread::spawn(move || worker_sampler.record_heap(5, 40))
+      .join()
+      .unwrap();
+    let report = sampler.stop();
+    assert!(report.peak_rss > 0);
+    assert_eq!(report.peak_heap_used, 10);
+    assert_eq!(report.peak_heap_total, 40);
+  }
+}
@@ -1,16 +1,21 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
 // Note: Only add code in this folder that has no application specific logic
+pub mod affinity;
 pub mod checksum;
 pub mod console;
 pub mod diff;
 pub mod display;
 pub mod draw_thread;
+pub mod editorconfig;
 pub mod file_watcher;
 pub mod fs;
+pub mod glob;
 pub mod logger;
+pub mod memory;
 pub mod path;
 pub mod progress_bar;
+pub mod prompt;
 pub mod text_encoding;
 pub mod unix;
 pub mod v8;
@@ -8,6 +8,8 @@ use deno_ast::ModuleSpecifier;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
 
+use super::fs::normalize_path;
+
 /// Checks if the path has extension Deno supports.
 pub fn is_supported_ext(path: &Path) -> bool {
   if let Some(ext) = get_extension(path) {
@@ -69,7 +71,42 @@ pub fn specifier_to_file_path(
   }
 }
 
+/// Converts a path to a file specifier, percent-encoding the characters which
+/// aren't allowed in URLs, like spaces, `#` and `%`. Relative paths are
+/// resolved against the current directory. Paths which can't be represented,
+/// like the ones which aren't valid Unicode on Windows, fail with an error
+/// naming them.
+pub fn path_to_specifier(path: &Path) -> Result<ModuleSpecifier, AnyError> {
+  let path = if path.is_absolute() {
+    normalize_path(path)
+  } else {
+    normalize_path(std::env::current_dir()?.join(path))
+  };
+  ModuleSpecifier::from_file_path(&path).map_err(|()| {
+    uri_error(format!("Invalid file path.\n  Path: {}", path.display()))
+  })
+}
+
+/// Encodes a file specifier like `path_to_specifier` does, so that the
+/// specifiers created by other tools for the same file, which may encode
+/// different characters, are equal. Other specifiers are returned as they
+/// are.
+pub fn normalize_file_specifier(
+  specifier: &ModuleSpecifier,
+) -> ModuleSpecifier {
+  if specifier.scheme() != "file"
+    || specifier.query().is_some()
+    || specifier.fragment().is_some()
+  {
+    return specifier.clone();
+  }
+  specifier_to_file_path(specifier)
+    .and_then(|path| path_to_specifier(&path))
+    .unwrap_or_else(|_| specifier.clone())
+}
+
 /// Ensures a specifier that will definitely be a directory has a trailing slash.
+#[cfg_attr(not(feature = "lsp"), allow(dead_code))]
 pub fn ensure_directory_specifier(
   mut specifier: ModuleSpecifier,
 ) -> ModuleSpecifier {
@@ -264,6 +301,67 @@ mod test {
     }
   }
 
+  /// Names with the characters that need encoding in URLs, or that have a
+  /// meaning in them.
+  const ADVERSARIAL_NAME_PARTS: &[&str] = &[
+    "a", " ", "#", "%", "%20", "?", "[", "]", "{", "}", "&", "+", ";", "=",
+    "@", "'", "\"", "`", "^", "|", "~", ":", "é", "日本", "🦕", "\u{200b}",
+  ];
+
+  #[test]
+  fn test_path_to_specifier_round_trip() {
+    let base = std::env::temp_dir().join("deno path test");
+    let mut names = Vec::new();
+    for first in ADVERSARIAL_NAME_PARTS {
+      for second in ADVERSARIAL_NAME_PARTS {
+        names.push(format!("{first}{second}.ts"));
+        names.push(format!("{first}dir{second}"));
+      }
+    }
+    for name in names {
+      // characters which aren't allowed in the file names of Windows
+      if cfg!(windows) && name.contains(['"', '|', '?', ':']) {
+        continue;
+      }
+      let path = base.join(&name);
+      let specifier = path_to_specifier(&path).unwrap();
+      assert_eq!(specifier.scheme(), "file");
+      assert_eq!(specifier.query(), None, "{name}");
+      assert_eq!(specifier.fragment(), None, "{name}");
+      assert_eq!(specifier_to_file_path(&specifier).unwrap(), path, "{name}");
+      assert_eq!(normalize_file_specifier(&specifier), specifier, "{name}");
+    }
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_path_to_specifier_non_utf8() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = Path::new("/tmp").join(OsStr::from_bytes(b"a\xff b.ts"));
+    let specifier = path_to_specifier(&path).unwrap();
+    assert_eq!(specifier.as_str(), "file:///tmp/a%FF%20b.ts");
+    assert_eq!(specifier_to_file_path(&specifier).unwrap(), path);
+  }
+
+  #[test]
+  fn test_normalize_file_specifier() {
+    let expected =
+      path_to_specifier(&std::env::temp_dir().join("a[b] c#.ts")).unwrap();
+    assert!(expected.as_str().ends_with("/a[b]%20c%23.ts"));
+    // encoded differently, like the URLs of other tools
+    let specifier = ModuleSpecifier::parse(
+      &expected.as_str().replace('[', "%5B").replace(']', "%5D"),
+    )
+    .unwrap();
+    assert_ne!(specifier, expected);
+    assert_eq!(normalize_file_specifier(&specifier), expected);
+    let specifier =
+      ModuleSpecifier::parse("https://deno.land/a%5B.ts").unwrap();
+    assert_eq!(normalize_file_specifier(&specifier), specifier);
+  }
+
   #[test]
   fn test_ensure_directory_specifier() {
     run_test("file:///", "file:///");
@@ -0,0 +1,267 @@
+// Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
+
+//! Interactive prompts shown on stderr and answered on stdin.
+
+use std::fmt::Display;
+use std::io::BufRead;
+use std::io::Read;
+use std::io::Write;
+use std::str::FromStr;
+
+use console_static_text::ConsoleStaticText;
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_runtime::ops::tty;
+
+use crate::colors;
+use crate::util::console::console_size;
+
+/// Whether prompts can be shown, which requires stdin and stderr to be
+/// terminals.
+pub fn is_interactive() -> bool {
+  atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stderr)
+}
+
+/// Asks a yes or no question, where an empty answer is the default.
+pub fn confirm(message: &str, default: bool) -> Result<bool, AnyError> {
+  let hint = if default { "(Y/n)" } else { "(y/N)" };
+  loop {
+    eprint!("{} {} {} ", colors::green("?"), message, colors::gray(hint));
+    let answer = read_line()?;
+    match answer.to_lowercase().as_str() {
+      "" => return Ok(default),
+      "y" | "yes" => return Ok(true),
+      "n" | "no" => return Ok(false),
+      _ => eprintln!("{}", colors::red("Please answer yes or no.")),
+    }
+  }
+}
+
+/// Asks for a value, where an empty answer is the default.
+pub fn input<T>(message: &str, default: T) -> Result<T, AnyError>
+where
+  T: FromStr + Display,
+  T::Err: Display,
+{
+  loop {
+    eprint!(
+      "{} {} {} ",
+      colors::green("?"),
+      message,
+      colors::gray(format!("({default})"))
+    );
+    let answer = read_line()?;
+    if answer.is_empty() {
+      return Ok(default);
+    }
+    match answer.parse() {
+      Ok(value) => return Ok(value),
+      Err(err) => eprintln!("{}", colors::red(format!("Invalid value: {err}"))),
+    }
+  }
+}
+
+fn read_line() -> Result<String, AnyError> {
+  let mut line = String::new();
+  if std::io::stdin().lock().read_line(&mut line)? == 0 {
+    bail!("Prompt cancelled because stdin was closed.");
+  }
+  Ok(line.trim().to_string())
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Key {
+  Up,
+  Down,
+  Enter,
+  Interrupt,
+  Other,
+}
+
+/// Reads the next key pressed, where the arrows are escape sequences.
+fn read_key(reader: &mut impl Read) -> Result<Key, AnyError> {
+  let mut read_byte = || -> Result<u8, AnyError> {
+    let mut buf = [0; 1];
+    if reader.read(&mut buf)? == 0 {
+      bail!("Prompt cancelled because stdin was closed.");
+    }
+    Ok(buf[0])
+  };
+  Ok(match read_byte()? {
+    b'\r' | b'\n' => Key::Enter,
+    // ctrl+c and ctrl+d
+    3 | 4 => Key::Interrupt,
+    b'k' => Key::Up,
+    b'j' => Key::Down,
+    0x1b => match read_byte()? {
+      b'[' | b'O' => match read_byte()? {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        _ => Key::Other,
+      },
+      _ => Key::Other,
+    },
+    _ => Key::Other,
+  })
+}
+
+/// Asks to pick one of the options with the arrow keys and returns its index.
+pub fn select(
+  message: &str,
+  options: &[&str],
+  default: usize,
+) -> Result<usize, AnyError> {
+  let mut selected = default;
+  let mut static_text = ConsoleStaticText::new(|| {
+    let size = console_size().unwrap();
+    console_static_text::ConsoleSize {
+      cols: Some(size.cols as u16),
+      rows: Some(size.rows as u16),
+    }
+  });
+  let result = {
+    let _raw_mode = RawModeGuard::new()?;
+    let mut stdin = std::io::stdin().lock();
+    loop {
+      let mut text = format!(
+        "{} {} {}",
+        colors::green("?"),
+        message,
+        colors::gray("(use the arrow keys)")
+      );
+      for (index, option) in options.iter().enumerate() {
+        if index == selected {
+          text.push_str(&format!(
+            "\n{} {}",
+            colors::cyan("❯"),
+            colors::cyan(option)
+          ));
+        } else {
+          text.push_str(&format!("\n  {option}"));
+        }
+      }
+      if let Some(size) = console_size() {
+        static_text.eprint_with_size(
+          &text,
+          console_static_text::ConsoleSize {
+            cols: Some(size.cols as u16),
+            rows: Some(size.rows as u16),
+          },
+        );
+      }
+      match read_key(&mut stdin) {
+        Ok(Key::Up) => {
+          selected = selected.checked_sub(1).unwrap_or(options.len() - 1)
+        }
+        Ok(Key::Down) => selected = (selected + 1) % options.len(),
+        Ok(Key::Enter) => break Ok(selected),
+        Ok(Key::Interrupt) => break Err(anyhow!("Prompt cancelled.")),
+        Ok(Key::Other) => {}
+        Err(err) => break Err(err),
+      }
+    }
+  };
+  static_text.eprint_clear();
+  let selected = result?;
+  eprintln!(
+    "{} {} {}",
+    colors::green("?"),
+    message,
+    colors::cyan(options[selected])
+  );
+  Ok(selected)
+}
+
+/// Puts stdin in raw mode until dropped, so that keys are read as they're
+/// pressed without being echoed.
+struct RawModeGuard {
+  #[cfg(unix)]
+  original_mode: nix::sys::termios::Termios,
+  #[cfg(windows)]
+  original_mode: winapi::shared::minwindef::DWORD,
+}
+
+impl RawModeGuard {
+  #[cfg(unix)]
+  fn new() -> Result<Self, AnyError> {
+    use nix::sys::termios;
+
+    let original_mode = termios::tcgetattr(libc::STDIN_FILENO)?;
+    let raw = tty::termios_raw_mode(&original_mode, false);
+    termios::tcsetattr(libc::STDIN_FILENO, termios::SetArg::TCSADRAIN, &raw)?;
+    Ok(Self { original_mode })
+  }
+
+  #[cfg(windows)]
+  fn new() -> Result<Self, AnyError> {
+    let handle = stdin_handle();
+    let original_mode = tty::get_console_mode(handle)?;
+    tty::set_console_mode(handle, tty::console_raw_mode(original_mode, true))?;
+    Ok(Self { original_mode })
+  }
+}
+
+#[cfg(windows)]
+fn stdin_handle() -> std::os::windows::io::RawHandle {
+  use winapi::um::processenv::GetStdHandle;
+  use winapi::um::winbase::STD_INPUT_HANDLE;
+
+  // SAFETY: winapi call
+  unsafe { GetStdHandle(STD_INPUT_HANDLE) }
+}
+
+impl Drop for RawModeGuard {
+  fn drop(&mut self) {
+    #[cfg(unix)]
+    {
+      use nix::sys::termios;
+      let _ = termios::tcsetattr(
+        libc::STDIN_FILENO,
+        termios::SetArg::TCSADRAIN,
+        &self.original_mode,
+      );
+    }
+    #[cfg(windows)]
+    {
+      let _ = tty::set_console_mode(stdin_handle(), self.original_mode);
+    }
+    let _ = std::io::stderr().flush();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn read_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut reader = bytes;
+    let mut keys = Vec::new();
+    while !reader.is_empty() {
+      keys.push(read_key(&mut reader).unwrap());
+    }
+    keys
+  }
+
+  #[test]
+  fn test_read_key() {
+    assert_eq!(
+      read_keys(b"\x1b[A\x1b[B\x1bOA\x1bOBkj\r\n\x03\x04x\x1b[C"),
+      vec![
+        Key::Up,
+        Key::Down,
+        Key::Up,
+        Key::Down,
+        Key::Up,
+        Key::Down,
+        Key::Enter,
+        Key::Enter,
+        Key::Interrupt,
+        Key::Interrupt,
+        Key::Other,
+        Key::Other,
+      ]
+    );
+    assert!(read_key(&mut &b""[..]).is_err());
+  }
+}
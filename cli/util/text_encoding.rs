@@ -53,21 +53,34 @@ pub fn strip_bom(text: &str) -> &str {
   }
 }
 
+static SOURCE_MAP_URL_PREFIX: &str = "//# sourceMappingURL=";
+
 static SOURCE_MAP_PREFIX: &str =
   "//# sourceMappingURL=data:application/json;base64,";
 
 pub fn source_map_from_code(code: &str) -> Option<Vec<u8>> {
-  let last_line = code.rsplit(|u| u == '\n').next()?;
+  let last_line = code.trim_end().rsplit(|u| u == '\n').next()?;
   if last_line.starts_with(SOURCE_MAP_PREFIX) {
     let input = last_line.split_at(SOURCE_MAP_PREFIX.len()).1;
-    let decoded_map = base64::decode(input)
-      .expect("Unable to decode source map from emitted file.");
-    Some(decoded_map)
+    // an invalid source map only means that locations aren't mapped
+    base64::decode(input).ok()
   } else {
     None
   }
 }
 
+/// Gets the URL of the source map of the code when it isn't inlined, like the
+/// one of a bundle emitted with `--sourcemap=external`.
+pub fn external_source_map_url(code: &str) -> Option<&str> {
+  let last_line = code.trim_end().rsplit(|u| u == '\n').next()?;
+  let url = last_line.trim_end().strip_prefix(SOURCE_MAP_URL_PREFIX)?;
+  if url.is_empty() || url.starts_with("data:") {
+    None
+  } else {
+    Some(url)
+  }
+}
+
 pub fn code_without_source_map(mut code: String) -> String {
   if let Some(last_line_index) = code.rfind('\n') {
     if code[last_line_index + 1..].starts_with(SOURCE_MAP_PREFIX) {
@@ -159,4 +172,39 @@ mod tests {
       assert_eq!(code_without_source_map(input.to_string()), output);
     }
   }
+
+  #[test]
+  fn test_source_map_from_code() {
+    assert_eq!(
+      source_map_from_code(
+        "test\n//# sourceMappingURL=data:application/json;base64,e30=\n"
+      ),
+      Some(b"{}".to_vec())
+    );
+    assert_eq!(
+      source_map_from_code(
+        "test\n//# sourceMappingURL=data:application/json;base64,%%%"
+      ),
+      None
+    );
+    assert_eq!(
+      source_map_from_code("test\n//# sourceMappingURL=a.js.map"),
+      None
+    );
+  }
+
+  #[test]
+  fn test_external_source_map_url() {
+    assert_eq!(
+      external_source_map_url("test\n//# sourceMappingURL=bundle.js.map\n"),
+      Some("bundle.js.map")
+    );
+    assert_eq!(
+      external_source_map_url(
+        "test\n//# sourceMappingURL=data:application/json;base64,e30="
+      ),
+      None
+    );
+    assert_eq!(external_source_map_url("test\n"), None);
+  }
 }
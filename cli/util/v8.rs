@@ -1,5 +1,9 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_runtime::colors;
+
 #[inline(always)]
 pub fn get_v8_flags_from_env() -> Vec<String> {
   std::env::var("DENO_V8_FLAGS")
@@ -45,3 +49,46 @@ pub fn init_v8_flags(v8_flags: &[String], env_v8_flags: Vec<String>) {
     std::process::exit(0);
   }
 }
+
+/// Sets the V8 flags of a subcommand's configuration, followed by the flags
+/// passed with `--v8-flags` so that those take precedence. This must be
+/// called before the first isolate is created.
+///
+/// The flags passed on the command line were already validated by
+/// `init_v8_flags`, so any flag V8 doesn't recognize comes from the
+/// configuration. Those are ignored with a warning, or are an error when
+/// `strict` is set.
+pub fn init_config_v8_flags(
+  config_v8_flags: &[String],
+  v8_flags: &[String],
+  env_v8_flags: Vec<String>,
+  strict: bool,
+) -> Result<(), AnyError> {
+  let v8_flags = construct_v8_flags(v8_flags, env_v8_flags);
+  log::debug!("V8 flags: {}", v8_flags[1..].join(" "));
+  if config_v8_flags.is_empty() {
+    return Ok(());
+  }
+
+  let unrecognized_v8_flags = deno_core::v8_set_flags(v8_flags)
+    .into_iter()
+    .skip(1)
+    .collect::<Vec<_>>();
+  if unrecognized_v8_flags.is_empty() {
+    return Ok(());
+  }
+  if strict {
+    bail!(
+      "V8 did not recognize flags from the configuration file: {}",
+      unrecognized_v8_flags.join(", ")
+    );
+  }
+  for f in unrecognized_v8_flags {
+    log::warn!(
+      "{} V8 did not recognize flag '{}' from the configuration file.",
+      colors::yellow("Warning"),
+      f
+    );
+  }
+  Ok(())
+}
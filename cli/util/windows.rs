@@ -88,3 +88,113 @@ pub fn ensure_stdio_open() {
     }
   }
 }
+
+/// Quotes an argument for a Windows command line the way programs split it
+/// with `CommandLineToArgvW`, which is what Rust programs like deno do.
+/// Backslashes are only special before a double quote, so the ones before an
+/// embedded quote or the closing quote are doubled, and the quote is escaped.
+#[cfg(any(windows, test))]
+pub fn quote_arg(arg: &str) -> String {
+  let mut quoted = String::with_capacity(arg.len() + 2);
+  quoted.push('"');
+  let mut backslashes = 0;
+  for c in arg.chars() {
+    match c {
+      '\\' => {
+        backslashes += 1;
+        continue;
+      }
+      '"' => push_backslashes(&mut quoted, backslashes * 2 + 1),
+      _ => push_backslashes(&mut quoted, backslashes),
+    }
+    backslashes = 0;
+    quoted.push(c);
+  }
+  push_backslashes(&mut quoted, backslashes * 2);
+  quoted.push('"');
+  quoted
+}
+
+#[cfg(any(windows, test))]
+fn push_backslashes(text: &mut String, count: usize) {
+  text.extend(std::iter::repeat('\\').take(count));
+}
+
+/// Quotes an argument for a command line of a batch script, which cmd parses
+/// before the program does. cmd expands `%` even in quotes, so it's doubled.
+/// cmd doesn't know about escaped quotes either, so an embedded quote ends its
+/// quoting: from there on, the quotes and the characters cmd handles specially
+/// are escaped with `^`.
+#[cfg(any(windows, test))]
+pub fn quote_batch_arg(arg: &str) -> String {
+  let mut escaped = String::new();
+  let mut is_quoted = false;
+  for (index, c) in quote_arg(arg).char_indices() {
+    match c {
+      '%' => escaped.push_str("%%"),
+      '"' if index == 0 || is_quoted => {
+        escaped.push('"');
+        is_quoted = !is_quoted;
+      }
+      '"' | '^' | '&' | '|' | '<' | '>' | '(' | ')' if !is_quoted => {
+        escaped.push('^');
+        escaped.push(c);
+      }
+      _ => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+/// Quotes an argument for PowerShell as a verbatim string, in which nothing is
+/// expanded. PowerShell also treats the typographic single quotes as quotes,
+/// so those are doubled as well.
+#[cfg(any(windows, test))]
+pub fn quote_powershell_arg(arg: &str) -> String {
+  let mut quoted = String::with_capacity(arg.len() + 2);
+  quoted.push('\'');
+  for c in arg.chars() {
+    if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+      quoted.push(c);
+    }
+    quoted.push(c);
+  }
+  quoted.push('\'');
+  quoted
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn quotes_arg() {
+    assert_eq!(quote_arg(""), r#""""#);
+    assert_eq!(quote_arg("run"), r#""run""#);
+    assert_eq!(quote_arg("a b"), r#""a b""#);
+    assert_eq!(quote_arg(r#"say "hi""#), r#""say \"hi\"""#);
+    assert_eq!(quote_arg(r"C:\dir\"), r#""C:\dir\\""#);
+    assert_eq!(quote_arg(r#"a\"b"#), r#""a\\\"b""#);
+    assert_eq!(quote_arg(r"a\\b"), r#""a\\b""#);
+  }
+
+  #[test]
+  fn quotes_batch_arg() {
+    assert_eq!(quote_batch_arg("run"), r#""run""#);
+    assert_eq!(quote_batch_arg("%PATH%"), r#""%%PATH%%""#);
+    assert_eq!(quote_batch_arg("a & b | (c)"), r#""a & b | (c)""#);
+    assert_eq!(quote_batch_arg(r#"""#), r#""\"^""#);
+    assert_eq!(quote_batch_arg(r#"a"&calc"#), r#""a\"^&calc^""#);
+    assert_eq!(quote_batch_arg(r#"a"b"^c%"#), r#""a\"b\^"^^c%%^""#);
+    assert_eq!(quote_batch_arg(r"C:\dir\"), r#""C:\dir\\""#);
+  }
+
+  #[test]
+  fn quotes_powershell_arg() {
+    assert_eq!(quote_powershell_arg(""), "''");
+    assert_eq!(quote_powershell_arg("$env:PATH"), "'$env:PATH'");
+    assert_eq!(quote_powershell_arg("it's"), "'it''s'");
+    assert_eq!(quote_powershell_arg("it\u{2019}s"), "'it\u{2019}\u{2019}s'");
+    assert_eq!(quote_powershell_arg(r#"say "hi" `n"#), r#"'say "hi" `n'"#);
+  }
+}
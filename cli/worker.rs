@@ -3,12 +3,16 @@
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
 use deno_ast::ModuleSpecifier;
+use deno_core::error::generic_error;
 use deno_core::error::AnyError;
+use deno_core::error::JsError;
 use deno_core::futures::task::LocalFutureObj;
 use deno_core::futures::FutureExt;
 use deno_core::located_script_name;
+use deno_core::serde_json;
 use deno_core::serde_json::json;
 use deno_core::serde_v8;
 use deno_core::v8;
@@ -16,6 +20,7 @@ use deno_core::Extension;
 use deno_core::ModuleId;
 use deno_runtime::colors;
 use deno_runtime::deno_node;
+use deno_runtime::deno_webstorage::WebStorageOptions;
 use deno_runtime::fmt_errors::format_js_error;
 use deno_runtime::ops::worker_host::CreateWebWorkerCb;
 use deno_runtime::ops::worker_host::WorkerEventCb;
@@ -37,11 +42,14 @@ use crate::tools;
 use crate::tools::coverage::CoverageCollector;
 use crate::tools::test::TestMode;
 use crate::util::checksum;
+use crate::util::display::print_unused_permissions;
 use crate::version;
 
 pub struct CliMainWorker {
   main_module: ModuleSpecifier,
   is_main_cjs: bool,
+  /// Modules passed with `--preload`, evaluated before the main module.
+  preload_modules: Vec<ModuleSpecifier>,
   worker: MainWorker,
   ps: ProcState,
 
@@ -62,6 +70,27 @@ impl CliMainWorker {
   }
 
   pub async fn run(&mut self) -> Result<i32, AnyError> {
+    let result = self.run_main_module().await;
+    if let Some(format) = self.ps.options.print_import_order() {
+      if let Some(import_order) = &self.ps.maybe_import_order {
+        import_order.print(format);
+      }
+    }
+    if let Some(format) = self.ps.options.report_unused_permissions() {
+      if result.is_ok() {
+        let op_state = self.worker.js_runtime.op_state();
+        let op_state = op_state.borrow();
+        let permissions = op_state.borrow::<PermissionsContainer>();
+        print_unused_permissions(&permissions.0.lock(), format);
+      }
+    }
+    ops::event_loop_diagnostics::print_report(
+      &self.worker.js_runtime.op_state().borrow(),
+    );
+    result
+  }
+
+  async fn run_main_module(&mut self) -> Result<i32, AnyError> {
     let mut maybe_coverage_collector =
       self.maybe_setup_coverage_collector().await?;
     log::debug!("main_module {}", self.main_module);
@@ -69,6 +98,7 @@ impl CliMainWorker {
     if self.is_main_cjs {
       self.ps.prepare_node_std_graph().await?;
       self.initialize_main_module_for_node().await?;
+      self.execute_preload_modules().await?;
       deno_node::load_cjs_module(
         &mut self.worker.js_runtime,
         &self.main_module.to_file_path().unwrap().to_string_lossy(),
@@ -176,10 +206,14 @@ impl CliMainWorker {
     executor.execute().await
   }
 
+  /// Runs the tests of the main module, starting at `start_index` in the order
+  /// they run when the tests before it already ran in another worker. When
+  /// collecting coverage, returns the URLs of the scripts the tests executed.
   pub async fn run_test_specifier(
     &mut self,
     mode: TestMode,
-  ) -> Result<(), AnyError> {
+    start_index: usize,
+  ) -> Result<Vec<String>, AnyError> {
     self.enable_test();
 
     // Enable op call tracing in core to enable better debugging of op sanitizer
@@ -202,7 +236,14 @@ impl CliMainWorker {
     }
 
     self.worker.dispatch_load_event(&located_script_name!())?;
-    self.run_tests(&self.ps.options.shuffle_tests()).await?;
+    self
+      .run_tests(
+        &self.ps.options.shuffle_tests(),
+        start_index,
+        self.ps.options.test_timeout(),
+        self.ps.options.test_retries(),
+      )
+      .await?;
     loop {
       if !self
         .worker
@@ -215,15 +256,17 @@ impl CliMainWorker {
 
     self.worker.dispatch_unload_event(&located_script_name!())?;
 
+    let mut covered_script_urls = Vec::new();
     if let Some(coverage_collector) = maybe_coverage_collector.as_mut() {
-      self
+      covered_script_urls = self
         .worker
         .with_event_loop(coverage_collector.stop_collecting().boxed_local())
         .await?;
     }
-    Ok(())
+    Ok(covered_script_urls)
   }
 
+  #[cfg_attr(not(feature = "lsp"), allow(dead_code))]
   pub async fn run_lsp_test_specifier(
     &mut self,
     mode: TestMode,
@@ -241,7 +284,7 @@ impl CliMainWorker {
     }
 
     self.worker.dispatch_load_event(&located_script_name!())?;
-    self.run_tests(&None).await?;
+    self.run_tests(&None, 0, None, 0).await?;
     loop {
       if !self
         .worker
@@ -255,14 +298,20 @@ impl CliMainWorker {
     Ok(())
   }
 
-  pub async fn run_bench_specifier(&mut self) -> Result<(), AnyError> {
+  pub async fn run_bench_specifier(
+    &mut self,
+    profile_loop: Option<Option<Duration>>,
+    warmup_only_parallel: bool,
+  ) -> Result<(), AnyError> {
     self.enable_bench();
 
     // We execute the module module as a side module so that import.meta.main is not set.
     self.execute_side_module_possibly_with_npm().await?;
 
     self.worker.dispatch_load_event(&located_script_name!())?;
-    self.run_benchmarks().await?;
+    self
+      .run_benchmarks(profile_loop, warmup_only_parallel)
+      .await?;
     loop {
       if !self
         .worker
@@ -300,9 +349,22 @@ impl CliMainWorker {
     if self.ps.npm_resolver.has_packages() {
       self.initialize_main_module_for_node().await?;
     }
+    self.execute_preload_modules().await?;
     self.worker.evaluate_module(id).await
   }
 
+  /// Loads and evaluates the modules passed with `--preload` in order. Each
+  /// module, including its top-level await, is evaluated before the next one
+  /// is loaded.
+  async fn execute_preload_modules(&mut self) -> Result<(), AnyError> {
+    for specifier in &self.preload_modules {
+      log::debug!("preload_module {}", specifier);
+      let id = self.worker.preload_side_module(specifier).await?;
+      self.worker.evaluate_module(id).await?;
+    }
+    Ok(())
+  }
+
   async fn initialize_main_module_for_node(&mut self) -> Result<(), AnyError> {
     self.ps.prepare_node_std_graph().await?;
     deno_node::initialize_runtime(
@@ -353,13 +415,24 @@ impl CliMainWorker {
   pub async fn run_tests(
     &mut self,
     shuffle: &Option<u64>,
+    start_index: usize,
+    timeout: Option<Duration>,
+    retries: usize,
   ) -> Result<(), AnyError> {
     let promise = {
       let scope = &mut self.worker.js_runtime.handle_scope();
       let cb = self.js_run_tests_callback.as_ref().unwrap().open(scope);
       let this = v8::undefined(scope).into();
-      let options =
-        serde_v8::to_v8(scope, json!({ "shuffle": shuffle })).unwrap();
+      let options = serde_v8::to_v8(
+        scope,
+        json!({
+          "shuffle": shuffle,
+          "startIndex": start_index,
+          "timeout": timeout.map(|timeout| timeout.as_millis() as u64),
+          "retries": retries,
+        }),
+      )
+      .unwrap();
       let promise = cb.call(scope, this, &[options]).unwrap();
       v8::Global::new(scope, promise)
     };
@@ -369,7 +442,13 @@ impl CliMainWorker {
 
   /// Run benches declared with `Deno.bench()`. Bench events will be dispatched
   /// by calling ops which are currently only implemented in the CLI crate.
-  pub async fn run_benchmarks(&mut self) -> Result<(), AnyError> {
+  /// When `profile_loop` is set, the single matched bench is run in a loop
+  /// instead, for the duration if there's one.
+  pub async fn run_benchmarks(
+    &mut self,
+    profile_loop: Option<Option<Duration>>,
+    warmup_only_parallel: bool,
+  ) -> Result<(), AnyError> {
     let promise = {
       let scope = &mut self.worker.js_runtime.handle_scope();
       let cb = self
@@ -378,15 +457,88 @@ impl CliMainWorker {
         .unwrap()
         .open(scope);
       let this = v8::undefined(scope).into();
-      let promise = cb.call(scope, this, &[]).unwrap();
+      let profile_loop = profile_loop.map(|duration| {
+        let duration_ms = duration.map(|duration| duration.as_millis() as u64);
+        json!({ "durationMs": duration_ms })
+      });
+      let options = serde_v8::to_v8(
+        scope,
+        json!({
+          "profileLoop": profile_loop,
+          "warmupOnlyParallel": warmup_only_parallel,
+        }),
+      )
+      .unwrap();
+      let promise = cb.call(scope, this, &[options]).unwrap();
       v8::Global::new(scope, promise)
     };
     self.worker.js_runtime.resolve_value(promise).await?;
     Ok(())
   }
 
+  /// Evaluates the main module as a custom reporter of `deno test` and gets
+  /// the object it exports by default.
+  pub async fn load_test_reporter(
+    &mut self,
+  ) -> Result<v8::Global<v8::Object>, AnyError> {
+    let id = self.worker.preload_main_module(&self.main_module).await?;
+    self.evaluate_module_possibly_with_npm(id).await?;
+    let namespace = self.worker.js_runtime.get_module_namespace(id)?;
+    let scope = &mut self.worker.js_runtime.handle_scope();
+    let namespace = v8::Local::new(scope, namespace);
+    let key = v8::String::new(scope, "default").unwrap();
+    let maybe_reporter = namespace
+      .get(scope, key.into())
+      .and_then(|value| v8::Local::<v8::Object>::try_from(value).ok());
+    match maybe_reporter {
+      Some(reporter) => Ok(v8::Global::new(scope, reporter)),
+      None => Err(generic_error(format!(
+        "The test reporter {} doesn't export an object by default",
+        self.main_module
+      ))),
+    }
+  }
+
+  /// Calls the callback of a custom test reporter with the event, waiting for
+  /// the promise it returns. Reporters may leave out callbacks.
+  pub async fn call_test_reporter(
+    &mut self,
+    reporter: &v8::Global<v8::Object>,
+    callback: &str,
+    event: serde_json::Value,
+  ) -> Result<(), AnyError> {
+    let value = {
+      let scope = &mut self.worker.js_runtime.handle_scope();
+      let reporter = v8::Local::new(scope, reporter);
+      let key = v8::String::new(scope, callback).unwrap();
+      let cb = match reporter
+        .get(scope, key.into())
+        .and_then(|value| v8::Local::<v8::Function>::try_from(value).ok())
+      {
+        Some(cb) => cb,
+        None => return Ok(()),
+      };
+      let event = serde_v8::to_v8(scope, event)?;
+      let tc_scope = &mut v8::TryCatch::new(scope);
+      match cb.call(tc_scope, reporter.into(), &[event]) {
+        Some(value) => v8::Global::new(tc_scope, value),
+        None => {
+          let exception = tc_scope.exception().unwrap();
+          return Err(JsError::from_v8_exception(tc_scope, exception).into());
+        }
+      }
+    };
+    self.worker.js_runtime.resolve_value(value).await?;
+    Ok(())
+  }
+
   /// Enable `Deno.test()`. If this isn't called before executing user code,
   /// `Deno.test()` calls will noop.
+  /// A handle to terminate the execution of the worker from another thread.
+  pub fn isolate_handle(&mut self) -> v8::IsolateHandle {
+    self.worker.js_runtime.v8_isolate().thread_safe_handle()
+  }
+
   pub fn enable_test(&mut self) {
     let scope = &mut self.worker.js_runtime.handle_scope();
     let cb = self.js_enable_test_callback.as_ref().unwrap().open(scope);
@@ -470,6 +622,7 @@ async fn create_main_worker_internal(
   } else {
     (main_module, false)
   };
+  let preload_modules = ps.resolve_preload_modules()?;
 
   let module_loader = CliModuleLoader::new(
     ps.clone(),
@@ -492,6 +645,10 @@ async fn create_main_worker_internal(
       .origin_data_folder_path()
       .join(checksum::gen(&[key.as_bytes()]))
   });
+  let webstorage_options = WebStorageOptions {
+    origin: maybe_storage_key.clone(),
+    quota: ps.options.storage_quota(),
+  };
   let cache_storage_dir = maybe_storage_key.map(|key| {
     // TODO(@satyarohith): storage quota management
     // Note: we currently use temp_dir() to avoid managing storage size.
@@ -501,6 +658,12 @@ async fn create_main_worker_internal(
   });
 
   let mut extensions = ops::cli_exts(ps.clone());
+  if let Some(timeout) = ps.options.exit_on_idle_timeout() {
+    extensions.push(ops::idle_timeout::init(timeout));
+  }
+  if ps.options.diagnose_event_loop() {
+    extensions.push(ops::event_loop_diagnostics::init());
+  }
   extensions.append(&mut custom_extensions);
 
   let options = WorkerOptions {
@@ -521,7 +684,7 @@ async fn create_main_worker_internal(
       runtime_version: version::deno(),
       ts_version: version::TYPESCRIPT.to_string(),
       unstable: ps.options.unstable(),
-      user_agent: version::get_user_agent(),
+      user_agent: ps.user_agent.clone(),
       inspect: ps.options.is_inspecting(),
     },
     extensions,
@@ -546,6 +709,7 @@ async fn create_main_worker_internal(
     get_error_class_fn: Some(&errors::get_error_class_name),
     cache_storage_dir,
     origin_storage_dir,
+    webstorage_options,
     blob_store: ps.blob_store.clone(),
     broadcast_channel: ps.broadcast_channel.clone(),
     shared_array_buffer_store: Some(ps.shared_array_buffer_store.clone()),
@@ -558,6 +722,16 @@ async fn create_main_worker_internal(
     permissions,
     options,
   );
+  let op_names = worker.js_runtime.op_names();
+  ops::idle_timeout::set_op_names(
+    &mut worker.js_runtime.op_state().borrow_mut(),
+    op_names,
+  );
+  let isolate_handle = worker.js_runtime.v8_isolate().thread_safe_handle();
+  ops::event_loop_diagnostics::start_sampling(
+    &mut worker.js_runtime.op_state().borrow_mut(),
+    isolate_handle,
+  );
 
   let (
     js_run_tests_callback,
@@ -600,6 +774,7 @@ async fn create_main_worker_internal(
   Ok(CliMainWorker {
     main_module,
     is_main_cjs,
+    preload_modules,
     worker,
     ps: ps.clone(),
     js_run_tests_callback,
@@ -690,7 +865,7 @@ fn create_web_worker_callback(
         runtime_version: version::deno(),
         ts_version: version::TYPESCRIPT.to_string(),
         unstable: ps.options.unstable(),
-        user_agent: version::get_user_agent(),
+        user_agent: ps.user_agent.clone(),
         inspect: ps.options.is_inspecting(),
       },
       extensions,
@@ -705,6 +880,7 @@ fn create_web_worker_callback(
       preload_module_cb,
       pre_execute_module_cb,
       format_js_error_fn: Some(Arc::new(format_js_error)),
+      stack_trace_options: ps.options.stack_trace_options(),
       source_map_getter: Some(Box::new(module_loader.clone())),
       module_loader,
       npm_resolver: Some(Rc::new(ps.npm_resolver.clone())),
@@ -777,6 +953,7 @@ mod tests {
       get_error_class_fn: None,
       cache_storage_dir: None,
       origin_storage_dir: None,
+      webstorage_options: Default::default(),
       blob_store: BlobStore::default(),
       broadcast_channel: InMemoryBroadcastChannel::default(),
       shared_array_buffer_store: None,
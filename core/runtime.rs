@@ -724,6 +724,18 @@ impl JsRuntime {
     self.state.borrow().inspector()
   }
 
+  /// Returns the names of the registered ops, indexed by their `OpId`.
+  pub fn op_names(&mut self) -> Vec<&'static str> {
+    self
+      .global_realm()
+      .state(self.v8_isolate())
+      .borrow()
+      .op_ctxs
+      .iter()
+      .map(|ctx| ctx.decl.name)
+      .collect()
+  }
+
   #[inline]
   pub fn global_realm(&mut self) -> JsRealm {
     let state = self.state.borrow();
@@ -3245,6 +3257,24 @@ pub mod tests {
     });
   }
 
+  #[test]
+  fn test_op_names() {
+    #[op]
+    fn op_named() {}
+
+    let ext = Extension::builder("test_ext")
+      .ops(vec![op_named::decl()])
+      .build();
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+      extensions: vec![ext],
+      ..Default::default()
+    });
+    let op_names = runtime.op_names();
+    assert!(op_names.contains(&"op_named"));
+    let op_state = runtime.op_state();
+    assert_eq!(op_state.borrow().tracker.per_op().len(), op_names.len());
+  }
+
   #[test]
   fn test_error_builder() {
     #[op]
@@ -43,6 +43,7 @@ use reqwest::header::USER_AGENT;
 use reqwest::redirect::Policy;
 use reqwest::Body;
 use reqwest::Client;
+use reqwest::ClientBuilder;
 use reqwest::Method;
 use reqwest::RequestBuilder;
 use reqwest::Response;
@@ -655,6 +656,28 @@ pub fn create_http_client(
   unsafely_ignore_certificate_errors: Option<Vec<String>>,
   client_cert_chain_and_key: Option<(String, String)>,
 ) -> Result<Client, AnyError> {
+  let builder = create_http_client_builder(
+    user_agent,
+    root_cert_store,
+    ca_certs,
+    proxy,
+    unsafely_ignore_certificate_errors,
+    client_cert_chain_and_key,
+  )?;
+  // unwrap here because it can only fail when native TLS is used.
+  Ok(builder.build().unwrap())
+}
+
+/// Create the builder of the client of `create_http_client`, for embedders
+/// which configure the client further.
+pub fn create_http_client_builder(
+  user_agent: String,
+  root_cert_store: Option<RootCertStore>,
+  ca_certs: Vec<Vec<u8>>,
+  proxy: Option<Proxy>,
+  unsafely_ignore_certificate_errors: Option<Vec<String>>,
+  client_cert_chain_and_key: Option<(String, String)>,
+) -> Result<ClientBuilder, AnyError> {
   let mut tls_config = deno_tls::create_client_config(
     root_cert_store,
     ca_certs,
@@ -680,6 +703,5 @@ pub fn create_http_client(
     builder = builder.proxy(reqwest_proxy);
   }
 
-  // unwrap here because it can only fail when native TLS is used.
-  Ok(builder.build().unwrap())
+  Ok(builder)
 }
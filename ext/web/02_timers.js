@@ -4,6 +4,7 @@ const core = globalThis.Deno.core;
 const ops = core.ops;
 const primordials = globalThis.__bootstrap.primordials;
 const {
+  ArrayFrom,
   ArrayPrototypePush,
   ArrayPrototypeShift,
   FunctionPrototypeCall,
@@ -11,6 +12,7 @@ const {
   MapPrototypeDelete,
   MapPrototypeGet,
   MapPrototypeHas,
+  MapPrototypeKeys,
   MapPrototypeSet,
   Uint8Array,
   Uint32Array,
@@ -342,6 +344,28 @@ function clearInterval(id = 0) {
   clearTimeout(id);
 }
 
+/**
+ * The ID the next timer will get, so that the timers created after this point
+ * can be cleared with `clearTimersSince`.
+ * @returns {number}
+ */
+function nextTimerId() {
+  return nextId;
+}
+
+/**
+ * Clears the active timers created since `nextTimerId()` returned `id`.
+ * @param {number} id
+ */
+function clearTimersSince(id) {
+  const ids = ArrayFrom(MapPrototypeKeys(activeTimers));
+  for (const timerId of new SafeArrayIterator(ids)) {
+    if (timerId >= id) {
+      clearTimeout(timerId);
+    }
+  }
+}
+
 function refTimer(id) {
   const timerInfo = MapPrototypeGet(activeTimers, id);
   if (timerInfo === undefined || timerInfo.isRef) {
@@ -363,7 +387,9 @@ function unrefTimer(id) {
 export {
   clearInterval,
   clearTimeout,
+  clearTimersSince,
   handleTimerMacrotask,
+  nextTimerId,
   opNow,
   refTimer,
   setInterval,
@@ -17,11 +17,33 @@ use rusqlite::OptionalExtension;
 pub use rusqlite;
 
 #[derive(Clone)]
-struct OriginStorageDir(PathBuf);
-
-const MAX_STORAGE_BYTES: u32 = 10 * 1024 * 1024;
+struct OriginStorageDir(PathBuf, Option<String>);
+
+#[derive(Clone, Copy)]
+struct StorageQuota(u64);
+
+const MAX_STORAGE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// The name of the SQLite database persisting `localStorage` in an origin
+/// storage directory.
+pub const LOCAL_STORAGE_FILE_NAME: &str = "local_storage";
+/// The name of the file in an origin storage directory which holds the
+/// origin the data belongs to.
+pub const ORIGIN_FILE_NAME: &str = "origin";
+
+#[derive(Clone, Debug, Default)]
+pub struct WebStorageOptions {
+  /// The origin the storage directory belongs to. It is recorded next to the
+  /// database so the data can be attributed to the origin later on.
+  pub origin: Option<String>,
+  /// The maximum size in bytes of a storage area. Defaults to 10MiB.
+  pub quota: Option<u64>,
+}
 
-pub fn init(origin_storage_dir: Option<PathBuf>) -> Extension {
+pub fn init(
+  origin_storage_dir: Option<PathBuf>,
+  options: WebStorageOptions,
+) -> Extension {
   Extension::builder(env!("CARGO_PKG_NAME"))
     .dependencies(vec!["deno_webidl"])
     .esm(include_js_files!("01_webstorage.js",))
@@ -36,8 +58,12 @@ pub fn init(origin_storage_dir: Option<PathBuf>) -> Extension {
     ])
     .state(move |state| {
       if let Some(origin_storage_dir) = &origin_storage_dir {
-        state.put(OriginStorageDir(origin_storage_dir.clone()));
+        state.put(OriginStorageDir(
+          origin_storage_dir.clone(),
+          options.origin.clone(),
+        ));
       }
+      state.put(StorageQuota(options.quota.unwrap_or(MAX_STORAGE_BYTES)));
       Ok(())
     })
     .build()
@@ -62,7 +88,10 @@ fn get_webstorage(
         )
       })?;
       std::fs::create_dir_all(&path.0)?;
-      let conn = Connection::open(path.0.join("local_storage"))?;
+      if let Some(origin) = &path.1 {
+        std::fs::write(path.0.join(ORIGIN_FILE_NAME), origin)?;
+      }
+      let conn = Connection::open(path.0.join(LOCAL_STORAGE_FILE_NAME))?;
       // Enable write-ahead-logging and tweak some other stuff.
       let initial_pragmas = "
         -- enable write-ahead-logging mode
@@ -142,13 +171,14 @@ pub fn op_webstorage_set(
   value: String,
   persistent: bool,
 ) -> Result<(), AnyError> {
+  let quota = state.borrow::<StorageQuota>().0;
   let conn = get_webstorage(state, persistent)?;
 
   let mut stmt = conn
     .prepare_cached("SELECT SUM(pgsize) FROM dbstat WHERE name = 'data'")?;
-  let size: u32 = stmt.query_row(params![], |row| row.get(0))?;
+  let size: u64 = stmt.query_row(params![], |row| row.get(0))?;
 
-  if size >= MAX_STORAGE_BYTES {
+  if size >= quota {
     return Err(
       deno_web::DomExceptionQuotaExceededError::new(
         "Exceeded maximum storage size",
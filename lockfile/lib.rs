@@ -37,12 +37,53 @@ fn gen_checksum(v: &[impl AsRef<[u8]>]) -> String {
   out.join("")
 }
 
+/// Why a locked module or package failed the integrity check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum IntegrityFailureKind {
+  /// The source of a remote module differs from the one that was locked.
+  ContentChanged,
+  /// The integrity of an npm package differs from the one that was locked.
+  NpmIntegrityChanged,
+}
+
+#[derive(Debug, Clone)]
+pub struct IntegrityFailure {
+  pub kind: IntegrityFailureKind,
+  pub specifier: String,
+  /// The checksum or integrity stored in the lockfile.
+  pub expected: String,
+  /// The checksum or integrity of what was actually loaded.
+  pub actual: String,
+}
+
+/// All the integrity check failures of a run against a lockfile.
 #[derive(Debug)]
-pub struct LockfileError(String);
+pub struct LockfileError {
+  pub filename: PathBuf,
+  pub failures: Vec<IntegrityFailure>,
+}
 
 impl std::fmt::Display for LockfileError {
   fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-    f.write_str(&self.0)
+    write!(
+      f,
+      "Integrity check failed for {} {} in the lockfile at \"{}\".",
+      self.failures.len(),
+      if self.failures.len() == 1 {
+        "entry"
+      } else {
+        "entries"
+      },
+      self.filename.display(),
+    )?;
+    for failure in &self.failures {
+      write!(
+        f,
+        "\n  {} (expected {}, actual {})",
+        failure.specifier, failure.expected, failure.actual
+      )?;
+    }
+    Ok(())
   }
 }
 
@@ -99,6 +140,11 @@ impl LockfileContent {
       npm: NpmContent::default(),
     }
   }
+
+  /// Mapping between the locked remote URLs and their checksums.
+  pub fn remote(&self) -> &BTreeMap<String, String> {
+    &self.remote
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -205,13 +251,24 @@ impl Lockfile {
     specifier: &str,
     code: &str,
   ) -> bool {
+    self
+      .check_or_insert_remote_integrity(specifier, code)
+      .is_ok()
+  }
+
+  /// Same as `check_or_insert_remote`, but describes why the check failed.
+  pub fn check_or_insert_remote_integrity(
+    &mut self,
+    specifier: &str,
+    code: &str,
+  ) -> Result<(), IntegrityFailure> {
     if !(specifier.starts_with("http:") || specifier.starts_with("https:")) {
-      return true;
+      return Ok(());
     }
     if self.overwrite {
       // In case --lock-write is specified check always passes
       self.insert(specifier, code);
-      true
+      Ok(())
     } else {
       self.check_or_insert(specifier, code)
     }
@@ -220,7 +277,7 @@ impl Lockfile {
   pub fn check_or_insert_npm_package(
     &mut self,
     package_info: NpmPackageLockfileInfo,
-  ) -> Result<(), LockfileError> {
+  ) -> Result<(), IntegrityFailure> {
     if self.overwrite {
       // In case --lock-write is specified check always passes
       self.insert_npm(package_info);
@@ -232,14 +289,25 @@ impl Lockfile {
 
   /// Checks the given module is included, if so verify the checksum. If module
   /// is not included, insert it.
-  fn check_or_insert(&mut self, specifier: &str, code: &str) -> bool {
+  fn check_or_insert(
+    &mut self,
+    specifier: &str,
+    code: &str,
+  ) -> Result<(), IntegrityFailure> {
     if let Some(lockfile_checksum) = self.content.remote.get(specifier) {
       let compiled_checksum = gen_checksum(&[code.as_bytes()]);
-      lockfile_checksum == &compiled_checksum
+      if lockfile_checksum != &compiled_checksum {
+        return Err(IntegrityFailure {
+          kind: IntegrityFailureKind::ContentChanged,
+          specifier: specifier.to_string(),
+          expected: lockfile_checksum.to_string(),
+          actual: compiled_checksum,
+        });
+      }
     } else {
       self.insert(specifier, code);
-      true
     }
+    Ok(())
   }
 
   fn insert(&mut self, specifier: &str, code: &str) {
@@ -251,22 +319,17 @@ impl Lockfile {
   fn check_or_insert_npm(
     &mut self,
     package: NpmPackageLockfileInfo,
-  ) -> Result<(), LockfileError> {
+  ) -> Result<(), IntegrityFailure> {
     if let Some(package_info) =
       self.content.npm.packages.get(&package.serialized_id)
     {
       if package_info.integrity.as_str() != package.integrity {
-        return Err(LockfileError(format!(
-            "Integrity check failed for npm package: \"{}\". Unable to verify that the package
-is the same as when the lockfile was generated.
-
-This could be caused by:
-  * the lock file may be corrupt
-  * the source itself may be corrupt
-
-Use \"--lock-write\" flag to regenerate the lockfile at \"{}\".",
-            package.display_id, self.filename.display()
-          )));
+        return Err(IntegrityFailure {
+          kind: IntegrityFailureKind::NpmIntegrityChanged,
+          specifier: format!("npm:{}", package.display_id),
+          expected: package_info.integrity.to_string(),
+          actual: package.integrity,
+        });
       }
     } else {
       self.insert_npm(package);
@@ -482,6 +545,42 @@ mod tests {
     assert!(check_true);
   }
 
+  #[test]
+  fn check_or_insert_remote_integrity_failure() {
+    let temp_dir = TempDir::new();
+    let file_path = setup(&temp_dir);
+
+    let mut lockfile = Lockfile::new(file_path, false).unwrap();
+    let specifier = "https://deno.land/std@0.71.0/textproto/mod.ts";
+    lockfile.insert(specifier, "Here is some source code");
+
+    let failure = lockfile
+      .check_or_insert_remote_integrity(
+        specifier,
+        "Here is some NEW source code",
+      )
+      .unwrap_err();
+    assert_eq!(failure.kind, IntegrityFailureKind::ContentChanged);
+    assert_eq!(failure.specifier, specifier);
+    assert_eq!(
+      failure.expected,
+      gen_checksum(&["Here is some source code"])
+    );
+    assert_eq!(
+      failure.actual,
+      gen_checksum(&["Here is some NEW source code"])
+    );
+
+    // --lock-write always passes
+    lockfile.overwrite = true;
+    assert!(lockfile
+      .check_or_insert_remote_integrity(
+        specifier,
+        "Here is some NEW source code"
+      )
+      .is_ok());
+  }
+
   #[test]
   fn check_or_insert_lockfile_npm() {
     let temp_dir = TempDir::new();
@@ -525,7 +624,11 @@ mod tests {
       dependencies: vec![],
     };
     // Now present in lockfile, should file due to borked integrity
-    let check_err = lockfile.check_or_insert_npm_package(npm_package);
-    assert!(check_err.is_err());
+    let failure = lockfile
+      .check_or_insert_npm_package(npm_package)
+      .unwrap_err();
+    assert_eq!(failure.kind, IntegrityFailureKind::NpmIntegrityChanged);
+    assert_eq!(failure.specifier, "npm:source-map-js@1.0.2");
+    assert_eq!(failure.actual, "sha512-foobar");
   }
 }
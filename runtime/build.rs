@@ -169,13 +169,23 @@ mod not_docs {
       deno_fetch::init::<Permissions>(Default::default()),
       deno_cache::init::<SqliteBackedCache>(None),
       deno_websocket::init::<Permissions>("".to_owned(), None, None),
-      deno_webstorage::init(None),
+      deno_webstorage::init(None, Default::default()),
       deno_crypto::init(None),
+      #[cfg(feature = "webgpu")]
       deno_webgpu::init(false),
+      // the global scope imports the WebGPU API, which is left undefined
+      #[cfg(not(feature = "webgpu"))]
+      Extension::builder("deno_webgpu")
+        .esm(vec![ExtensionFileSource {
+          specifier: "01_webgpu.js".to_string(),
+          code: "",
+        }])
+        .build(),
       deno_broadcast_channel::init(
         deno_broadcast_channel::InMemoryBroadcastChannel::default(),
         false, // No --unstable.
       ),
+      #[cfg(feature = "node")]
       deno_node::init::<Permissions>(None),
       deno_ffi::init::<Permissions>(false),
       deno_net::init::<Permissions>(
@@ -2,8 +2,11 @@
 
 use atty;
 use once_cell::sync::Lazy;
+use std::ffi::OsStr;
 use std::fmt;
 use std::io::Write;
+use std::sync::atomic::AtomicU8;
+use std::sync::atomic::Ordering;
 use termcolor::Ansi;
 use termcolor::Color::Ansi256;
 use termcolor::Color::Black;
@@ -21,8 +24,24 @@ use termcolor::BufferWriter;
 #[cfg(windows)]
 use termcolor::ColorChoice;
 
-static NO_COLOR: Lazy<bool> =
-  Lazy::new(|| std::env::var_os("NO_COLOR").is_some());
+/// Whether colored output is used. `Auto` decides based on the `FORCE_COLOR`
+/// and `NO_COLOR` environment variables.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ColorMode {
+  #[default]
+  Auto,
+  Always,
+  Never,
+}
+
+static COLOR_MODE: AtomicU8 = AtomicU8::new(ColorMode::Auto as u8);
+
+static USE_COLOR_FROM_ENV: Lazy<bool> = Lazy::new(|| {
+  use_color_from_env(
+    std::env::var_os("FORCE_COLOR").as_deref(),
+    std::env::var_os("NO_COLOR").as_deref(),
+  )
+});
 
 static IS_TTY: Lazy<bool> = Lazy::new(|| atty::is(atty::Stream::Stdout));
 
@@ -30,8 +49,38 @@ pub fn is_tty() -> bool {
   *IS_TTY
 }
 
+/// Sets the color mode for the rest of the process. This should be called
+/// once, before any output is written.
+pub fn set_color_mode(mode: ColorMode) {
+  COLOR_MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn color_mode() -> ColorMode {
+  match COLOR_MODE.load(Ordering::Relaxed) {
+    1 => ColorMode::Always,
+    2 => ColorMode::Never,
+    _ => ColorMode::Auto,
+  }
+}
+
 pub fn use_color() -> bool {
-  !(*NO_COLOR)
+  match color_mode() {
+    ColorMode::Always => true,
+    ColorMode::Never => false,
+    ColorMode::Auto => *USE_COLOR_FROM_ENV,
+  }
+}
+
+/// `FORCE_COLOR` takes precedence over `NO_COLOR`, and disables color when
+/// set to `0` or `false`. Color is used when neither is set.
+fn use_color_from_env(
+  force_color: Option<&OsStr>,
+  no_color: Option<&OsStr>,
+) -> bool {
+  if let Some(force_color) = force_color {
+    return !matches!(force_color.to_str(), Some("0") | Some("false"));
+  }
+  no_color.is_none()
 }
 
 #[cfg(windows)]
@@ -143,3 +192,20 @@ pub fn white_bold_on_red<S: AsRef<str>>(s: S) -> impl fmt::Display {
     .set_fg(Some(White));
   style(s, style_spec)
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_use_color_from_env() {
+    let os = |s: &'static str| Some(OsStr::new(s));
+    assert!(use_color_from_env(None, None));
+    assert!(!use_color_from_env(None, os("1")));
+    assert!(!use_color_from_env(None, os("")));
+    assert!(use_color_from_env(os("1"), None));
+    assert!(use_color_from_env(os(""), os("1")));
+    assert!(!use_color_from_env(os("0"), None));
+    assert!(!use_color_from_env(os("false"), None));
+  }
+}
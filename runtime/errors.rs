@@ -153,8 +153,11 @@ pub fn get_nix_error_class(error: &nix::Error) -> &'static str {
 }
 
 pub fn get_error_class_name(e: &AnyError) -> Option<&'static str> {
-  deno_core::error::get_custom_error_class(e)
-    .or_else(|| deno_webgpu::error::get_error_class_name(e))
+  let maybe_class = deno_core::error::get_custom_error_class(e);
+  #[cfg(feature = "webgpu")]
+  let maybe_class =
+    maybe_class.or_else(|| deno_webgpu::error::get_error_class_name(e));
+  maybe_class
     .or_else(|| deno_web::get_error_class_name(e))
     .or_else(|| deno_webstorage::get_not_supported_error_class_name(e))
     .or_else(|| deno_websocket::get_network_error_class_name(e))
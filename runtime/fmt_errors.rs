@@ -9,6 +9,20 @@ use deno_core::error::JsError;
 use deno_core::error::JsStackFrame;
 use std::fmt::Write as _;
 
+/// The longest sequence of frames which is collapsed when it's repeated, as
+/// by mutually recursive functions.
+const MAX_REPEATED_FRAMES_LEN: usize = 8;
+
+/// How the stack frames of errors are processed before they're formatted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StackTraceOptions {
+  /// Whether to keep the frames of internal code, which are otherwise
+  /// dropped when there's a frame of user code.
+  pub full: bool,
+  /// The maximum number of frames shown for each error.
+  pub limit: Option<usize>,
+}
+
 /// Compares all properties of JsError, except for JsError::cause.
 /// This function is used to detect that 2 JsError objects in a JsError::cause
 /// chain are identical, ie. there is a recursive cause.
@@ -130,6 +144,76 @@ fn format_frame(frame: &JsStackFrame) -> String {
   result
 }
 
+fn is_internal_frame(frame: &JsStackFrame) -> bool {
+  frame.file_name.as_ref().map_or(false, |file_name| {
+    file_name.trim_start_matches('[').starts_with("internal:")
+  })
+}
+
+/// Gets the frames of an error to show. Unless the stack traces are full,
+/// the frames of internal code are dropped, except when every frame is
+/// internal.
+fn visible_frames<'a>(
+  frames: &'a [JsStackFrame],
+  options: &StackTraceOptions,
+) -> Vec<&'a JsStackFrame> {
+  let has_user_frame = frames.iter().any(|frame| !is_internal_frame(frame));
+  let mut frames = frames
+    .iter()
+    .filter(|frame| {
+      options.full || !has_user_frame || !is_internal_frame(frame)
+    })
+    .collect::<Vec<_>>();
+  if let Some(limit) = options.limit {
+    frames.truncate(limit);
+  }
+  frames
+}
+
+/// Finds the shortest sequence of frames at the start which is repeated
+/// right after at least twice, returning its length and how many more times
+/// it's repeated.
+fn find_repeated_frames(frames: &[&JsStackFrame]) -> Option<(usize, usize)> {
+  let max_len = MAX_REPEATED_FRAMES_LEN.min(frames.len() / 3);
+  (1..=max_len).find_map(|len| {
+    let sequence = &frames[..len];
+    let count = frames[len..]
+      .chunks_exact(len)
+      .take_while(|chunk| *chunk == sequence)
+      .count();
+    (count >= 2).then_some((len, count))
+  })
+}
+
+/// Writes the frames, collapsing the repeated sequences of frames so that
+/// they're only written once.
+fn write_frames(s: &mut String, frames: &[&JsStackFrame]) {
+  let mut i = 0;
+  while i < frames.len() {
+    match find_repeated_frames(&frames[i..]) {
+      Some((len, count)) => {
+        for frame in &frames[i..i + len] {
+          write!(s, "\n    at {}", format_frame(frame)).unwrap();
+        }
+        if len == 1 {
+          write!(s, "\n    ... (repeated {count} times)").unwrap();
+        } else {
+          write!(
+            s,
+            "\n    ... previous {len} frames (repeated {count} times)"
+          )
+          .unwrap();
+        }
+        i += len * (count + 1);
+      }
+      None => {
+        write!(s, "\n    at {}", format_frame(frames[i])).unwrap();
+        i += 1;
+      }
+    }
+  }
+}
+
 /// Take an optional source line and associated information to format it into
 /// a pretty printed version of that line.
 fn format_maybe_source_line(
@@ -208,6 +292,7 @@ fn find_recursive_cause(js_error: &JsError) -> Option<ErrorReference> {
 fn format_aggregated_error(
   aggregated_errors: &Vec<JsError>,
   circular_reference_index: usize,
+  options: &StackTraceOptions,
 ) -> String {
   let mut s = String::new();
   let mut nested_circular_reference_index = circular_reference_index;
@@ -224,6 +309,7 @@ fn format_aggregated_error(
         index: nested_circular_reference_index,
       }),
       false,
+      options,
     );
 
     for line in error_string.trim_start_matches("Uncaught ").lines() {
@@ -238,6 +324,7 @@ fn format_js_error_inner(
   js_error: &JsError,
   circular: Option<IndexedErrorReference>,
   include_source_code: bool,
+  options: &StackTraceOptions,
 ) -> String {
   let mut s = String::new();
 
@@ -253,6 +340,7 @@ fn format_js_error_inner(
     let aggregated_message = format_aggregated_error(
       aggregated,
       circular.as_ref().map_or(0, |circular| circular.index),
+      options,
     );
     s.push_str(&aggregated_message);
   }
@@ -270,9 +358,8 @@ fn format_js_error_inner(
     true,
     0,
   ));
-  for frame in &js_error.frames {
-    write!(s, "\n    at {}", format_frame(frame)).unwrap();
-  }
+  let frames = visible_frames(&js_error.frames, options);
+  write_frames(&mut s, &frames);
   if let Some(cause) = &js_error.cause {
     let is_caused_by_circular = circular.as_ref().map_or(false, |circular| {
       errors_are_equal_without_cause(circular.reference.from, js_error)
@@ -281,7 +368,7 @@ fn format_js_error_inner(
     let error_string = if is_caused_by_circular {
       cyan(format!("[Circular *{}]", circular.unwrap().index)).to_string()
     } else {
-      format_js_error_inner(cause, circular, false)
+      format_js_error_inner(cause, circular, false, options)
     };
 
     write!(
@@ -295,14 +382,17 @@ fn format_js_error_inner(
 }
 
 /// Format a [`JsError`] for terminal output.
-pub fn format_js_error(js_error: &JsError) -> String {
+pub fn format_js_error(
+  js_error: &JsError,
+  options: &StackTraceOptions,
+) -> String {
   let circular =
     find_recursive_cause(js_error).map(|reference| IndexedErrorReference {
       reference,
       index: 1,
     });
 
-  format_js_error_inner(js_error, circular, true)
+  format_js_error_inner(js_error, circular, true, options)
 }
 
 #[cfg(test)]
@@ -325,4 +415,70 @@ mod tests {
       "\nconsole.log(\'foo\');\n        ^"
     );
   }
+
+  fn frame(file_name: &str, line_number: i64) -> JsStackFrame {
+    JsStackFrame {
+      is_top_level: Some(true),
+      ..JsStackFrame::from_location(
+        Some(file_name.to_string()),
+        Some(line_number),
+        Some(1),
+      )
+    }
+  }
+
+  #[test]
+  fn test_visible_frames() {
+    let frames = vec![
+      frame("file:///mod.ts", 1),
+      frame("internal:core/01_core.js", 2),
+      frame("file:///mod.ts", 3),
+    ];
+    let visible = visible_frames(&frames, &StackTraceOptions::default());
+    assert_eq!(visible, vec![&frames[0], &frames[2]]);
+    let full = StackTraceOptions {
+      full: true,
+      limit: Some(2),
+    };
+    assert_eq!(visible_frames(&frames, &full), vec![&frames[0], &frames[1]]);
+
+    // only internal frames are kept
+    let frames = vec![frame("internal:core/01_core.js", 1)];
+    let visible = visible_frames(&frames, &StackTraceOptions::default());
+    assert_eq!(visible, vec![&frames[0]]);
+  }
+
+  #[test]
+  fn test_write_frames_collapses_repetitions() {
+    let a = frame("file:///a.ts", 1);
+    let b = frame("file:///b.ts", 2);
+    let c = frame("file:///c.ts", 3);
+    let mut s = String::new();
+    write_frames(&mut s, &[&c, &a, &a, &a, &a, &b, &a, &b, &a, &b, &c]);
+    assert_eq!(
+      strip_ansi_codes(&s),
+      concat!(
+        "\n    at file:///c.ts:3:1",
+        "\n    at file:///a.ts:1:1",
+        "\n    ... (repeated 3 times)",
+        "\n    at file:///b.ts:2:1",
+        "\n    at file:///a.ts:1:1",
+        "\n    at file:///b.ts:2:1",
+        "\n    at file:///a.ts:1:1",
+        "\n    at file:///b.ts:2:1",
+        "\n    at file:///c.ts:3:1",
+      )
+    );
+
+    let mut s = String::new();
+    write_frames(&mut s, &[&a, &b, &a, &b, &a, &b]);
+    assert_eq!(
+      strip_ansi_codes(&s),
+      concat!(
+        "\n    at file:///a.ts:1:1",
+        "\n    at file:///b.ts:2:1",
+        "\n    ... previous 2 frames (repeated 2 times)",
+      )
+    );
+  }
 }
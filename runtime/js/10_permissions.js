@@ -32,7 +32,7 @@ const illegalConstructorKey = Symbol("illegalConstructorKey");
  * @property {PermissionStatus} status
  */
 
-/** @type {ReadonlyArray<"read" | "write" | "net" | "env" | "sys" | "run" | "ffi" | "hrtime">} */
+/** @type {ReadonlyArray<"read" | "write" | "net" | "env" | "sys" | "run" | "ffi" | "scheme" | "hrtime">} */
 const permissionNames = [
   "read",
   "write",
@@ -41,6 +41,7 @@ const permissionNames = [
   "sys",
   "run",
   "ffi",
+  "scheme",
   "hrtime",
 ];
 
@@ -135,6 +136,8 @@ function cache(desc, state) {
     key += `-${desc.variable}&`;
   } else if (desc.name === "sys" && desc.kind) {
     key += `-${desc.kind}&`;
+  } else if (desc.name === "scheme" && desc.scheme) {
+    key += `-${desc.scheme}&`;
   } else {
     key += "$";
   }
@@ -266,7 +269,7 @@ function serializePermissions(permissions) {
       }
     }
     for (
-      const key of new SafeArrayIterator(["env", "hrtime", "net", "sys"])
+      const key of new SafeArrayIterator(["env", "hrtime", "net", "scheme", "sys"])
     ) {
       if (ArrayIsArray(permissions[key])) {
         serializedPermissions[key] = ArrayPrototypeSlice(permissions[key]);
@@ -62,6 +62,13 @@ function exit(code) {
     code = 0;
   }
 
+  // The handler takes over exiting, including dispatching `unload` when it
+  // does exit, like in workers which close themselves.
+  if (exitHandler) {
+    exitHandler(code);
+    return;
+  }
+
   // Dispatches `unload` only when it's not dispatched yet.
   if (!globalThis[SymbolFor("isUnloadDispatched")]) {
     // Invokes the `unload` hooks before exiting
@@ -69,11 +76,6 @@ function exit(code) {
     windowDispatchEvent(new Event("unload"));
   }
 
-  if (exitHandler) {
-    exitHandler(code);
-    return;
-  }
-
   ops.op_exit();
   throw new Error("Code not reachable");
 }
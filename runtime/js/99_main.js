@@ -586,9 +586,16 @@ function bootstrapWorkerRuntime(
 
   core.setPromiseRejectCallback(promiseRejectCallback);
 
-  // `Deno.exit()` is an alias to `self.close()`. Setting and exit
-  // code using an op in worker context is a no-op.
+  // `Deno.exit()` is an alias to `self.close()`, after dispatching `unload`
+  // like on the main thread. Setting and exit code using an op in worker
+  // context is a no-op.
+  let isUnloadDispatched = false;
   os.setExitHandler((_exitCode) => {
+    // an `unload` listener may call `Deno.exit()` again
+    if (!isUnloadDispatched) {
+      isUnloadDispatched = true;
+      globalThis.dispatchEvent(new event.Event("unload"));
+    }
     workerClose();
   });
 
@@ -15,6 +15,7 @@ pub use deno_node;
 pub use deno_tls;
 pub use deno_url;
 pub use deno_web;
+#[cfg(feature = "webgpu")]
 pub use deno_webgpu;
 pub use deno_webidl;
 pub use deno_websocket;
@@ -310,8 +310,9 @@ fn op_runtime_memory_usage(scope: &mut v8::HandleScope) -> MemoryUsage {
   }
 }
 
+/// Gets the resident set size of the current process in bytes.
 #[cfg(target_os = "linux")]
-fn rss() -> usize {
+pub fn rss() -> usize {
   // Inspired by https://github.com/Arc-blroth/memory-stats/blob/5364d0d09143de2a470d33161b2330914228fde9/src/linux.rs
 
   // Extracts a positive integer from a string that
@@ -361,7 +362,7 @@ fn rss() -> usize {
 }
 
 #[cfg(target_os = "macos")]
-fn rss() -> usize {
+pub fn rss() -> usize {
   // Inspired by https://github.com/Arc-blroth/memory-stats/blob/5364d0d09143de2a470d33161b2330914228fde9/src/darwin.rs
 
   let mut task_info =
@@ -384,7 +385,7 @@ fn rss() -> usize {
 }
 
 #[cfg(windows)]
-fn rss() -> usize {
+pub fn rss() -> usize {
   use winapi::shared::minwindef::DWORD;
   use winapi::shared::minwindef::FALSE;
   use winapi::um::processthreadsapi::GetCurrentProcess;
@@ -30,6 +30,7 @@ pub struct PermissionArgs {
   variable: Option<String>,
   kind: Option<String>,
   command: Option<String>,
+  scheme: Option<String>,
 }
 
 #[op]
@@ -55,6 +56,7 @@ pub fn op_query_permission(
       .query(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.query(args.command.as_deref()),
     "ffi" => permissions.ffi.query(args.path.as_deref().map(Path::new)),
+    "scheme" => permissions.scheme.query(args.scheme.as_deref()),
     "hrtime" => permissions.hrtime.query(),
     n => {
       return Err(custom_error(
@@ -89,6 +91,7 @@ pub fn op_revoke_permission(
       .revoke(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.revoke(args.command.as_deref()),
     "ffi" => permissions.ffi.revoke(args.path.as_deref().map(Path::new)),
+    "scheme" => permissions.scheme.revoke(args.scheme.as_deref()),
     "hrtime" => permissions.hrtime.revoke(),
     n => {
       return Err(custom_error(
@@ -123,6 +126,7 @@ pub fn op_request_permission(
       .request(args.kind.as_deref().map(parse_sys_kind).transpose()?),
     "run" => permissions.run.request(args.command.as_deref()),
     "ffi" => permissions.ffi.request(args.path.as_deref().map(Path::new)),
+    "scheme" => permissions.scheme.request(args.scheme.as_deref()),
     "hrtime" => permissions.hrtime.request(),
     n => {
       return Err(custom_error(
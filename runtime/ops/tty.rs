@@ -33,6 +33,80 @@ fn get_windows_handle(
   Ok(handle)
 }
 
+/// Gets the mode of a console handle.
+#[cfg(windows)]
+pub fn get_console_mode(
+  handle: std::os::windows::io::RawHandle,
+) -> Result<DWORD, AnyError> {
+  use winapi::shared::minwindef::FALSE;
+  use winapi::um::consoleapi;
+
+  let mut mode: DWORD = 0;
+  // SAFETY: winapi call
+  if unsafe { consoleapi::GetConsoleMode(handle, &mut mode) } == FALSE {
+    return Err(Error::last_os_error().into());
+  }
+  Ok(mode)
+}
+
+/// Sets the mode of a console handle.
+#[cfg(windows)]
+pub fn set_console_mode(
+  handle: std::os::windows::io::RawHandle,
+  mode: DWORD,
+) -> Result<(), AnyError> {
+  use winapi::shared::minwindef::FALSE;
+  use winapi::um::consoleapi;
+
+  // SAFETY: winapi call
+  if unsafe { consoleapi::SetConsoleMode(handle, mode) } == FALSE {
+    return Err(Error::last_os_error().into());
+  }
+  Ok(())
+}
+
+/// The mode of a console input handle in raw mode, where the keys are read as
+/// they're pressed without being echoed, or back from it.
+#[cfg(windows)]
+pub fn console_raw_mode(original_mode: DWORD, is_raw: bool) -> DWORD {
+  const RAW_MODE_MASK: DWORD = wincon::ENABLE_LINE_INPUT
+    | wincon::ENABLE_ECHO_INPUT
+    | wincon::ENABLE_PROCESSED_INPUT;
+  if is_raw {
+    original_mode & !RAW_MODE_MASK | wincon::ENABLE_VIRTUAL_TERMINAL_INPUT
+  } else {
+    original_mode | RAW_MODE_MASK & !wincon::ENABLE_VIRTUAL_TERMINAL_INPUT
+  }
+}
+
+/// The mode of a terminal in raw mode, where the keys are read as they're
+/// pressed without being echoed. With `cbreak`, keys like Ctrl+C still send
+/// their signals.
+#[cfg(unix)]
+pub fn termios_raw_mode(
+  original_mode: &termios::Termios,
+  cbreak: bool,
+) -> termios::Termios {
+  let mut raw = original_mode.clone();
+  raw.input_flags &= !(termios::InputFlags::BRKINT
+    | termios::InputFlags::ICRNL
+    | termios::InputFlags::INPCK
+    | termios::InputFlags::ISTRIP
+    | termios::InputFlags::IXON);
+
+  raw.control_flags |= termios::ControlFlags::CS8;
+
+  raw.local_flags &= !(termios::LocalFlags::ECHO
+    | termios::LocalFlags::ICANON
+    | termios::LocalFlags::IEXTEN);
+  if !cbreak {
+    raw.local_flags &= !(termios::LocalFlags::ISIG);
+  }
+  raw.control_chars[termios::SpecialCharacterIndices::VMIN as usize] = 1;
+  raw.control_chars[termios::SpecialCharacterIndices::VTIME as usize] = 0;
+  raw
+}
+
 pub fn init() -> Extension {
   Extension::builder("deno_tty")
     .ops(vec![
@@ -59,8 +133,6 @@ fn op_stdin_set_raw(
   #[cfg(windows)]
   {
     use std::os::windows::io::AsRawHandle;
-    use winapi::shared::minwindef::FALSE;
-    use winapi::um::consoleapi;
     use winapi::um::handleapi;
 
     if cbreak {
@@ -75,29 +147,8 @@ fn op_stdin_set_raw(
       } else if handle.is_null() {
         return Err(custom_error("ReferenceError", "null handle"));
       }
-      let mut original_mode: DWORD = 0;
-      // SAFETY: winapi call
-      if unsafe { consoleapi::GetConsoleMode(handle, &mut original_mode) }
-        == FALSE
-      {
-        return Err(Error::last_os_error().into());
-      }
-
-      const RAW_MODE_MASK: DWORD = wincon::ENABLE_LINE_INPUT
-        | wincon::ENABLE_ECHO_INPUT
-        | wincon::ENABLE_PROCESSED_INPUT;
-      let new_mode = if is_raw {
-        original_mode & !RAW_MODE_MASK | wincon::ENABLE_VIRTUAL_TERMINAL_INPUT
-      } else {
-        original_mode | RAW_MODE_MASK & !wincon::ENABLE_VIRTUAL_TERMINAL_INPUT
-      };
-
-      // SAFETY: winapi call
-      if unsafe { consoleapi::SetConsoleMode(handle, new_mode) } == FALSE {
-        return Err(Error::last_os_error().into());
-      }
-
-      Ok(())
+      let original_mode = get_console_mode(handle)?;
+      set_console_mode(handle, console_raw_mode(original_mode, is_raw))
     })
   }
   #[cfg(unix)]
@@ -111,7 +162,7 @@ fn op_stdin_set_raw(
         let raw_fd = std_file.as_raw_fd();
 
         if is_raw {
-          let mut raw = {
+          let raw = {
             let mut meta_data = meta_data.lock();
             let maybe_tty_mode = &mut meta_data.tty.mode;
             if maybe_tty_mode.is_none() {
@@ -119,27 +170,8 @@ fn op_stdin_set_raw(
               let original_mode = termios::tcgetattr(raw_fd)?;
               maybe_tty_mode.replace(original_mode);
             }
-            maybe_tty_mode.clone().unwrap()
+            termios_raw_mode(maybe_tty_mode.as_ref().unwrap(), cbreak)
           };
-
-          raw.input_flags &= !(termios::InputFlags::BRKINT
-            | termios::InputFlags::ICRNL
-            | termios::InputFlags::INPCK
-            | termios::InputFlags::ISTRIP
-            | termios::InputFlags::IXON);
-
-          raw.control_flags |= termios::ControlFlags::CS8;
-
-          raw.local_flags &= !(termios::LocalFlags::ECHO
-            | termios::LocalFlags::ICANON
-            | termios::LocalFlags::IEXTEN);
-          if !cbreak {
-            raw.local_flags &= !(termios::LocalFlags::ISIG);
-          }
-          raw.control_chars[termios::SpecialCharacterIndices::VMIN as usize] =
-            1;
-          raw.control_chars[termios::SpecialCharacterIndices::VTIME as usize] =
-            0;
           termios::tcsetattr(raw_fd, termios::SetArg::TCSADRAIN, &raw)?;
         } else {
           // Try restore saved mode.
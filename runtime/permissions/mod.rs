@@ -24,6 +24,8 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::string::ToString;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 mod prompter;
@@ -141,14 +143,51 @@ impl Default for PermissionState {
   }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+/// Counts the checks of a permission, for `--report-unused-permissions`.
+/// Clones share the count, so that the checks of workers inheriting the
+/// permission are counted as well.
+#[derive(Clone, Debug, Default)]
+pub struct PermissionUsage(Arc<AtomicUsize>);
+
+impl PermissionUsage {
+  pub fn record(&self) {
+    self.0.fetch_add(1, Ordering::Relaxed);
+  }
+
+  pub fn count(&self) -> usize {
+    self.0.load(Ordering::Relaxed)
+  }
+}
+
+#[derive(Clone, Debug)]
 pub struct UnitPermission {
   pub name: &'static str,
   pub description: &'static str,
   pub state: PermissionState,
   pub prompt: bool,
+  pub usage: PermissionUsage,
+}
+
+// The usage isn't part of the state of a permission, so it's ignored when
+// comparing permissions.
+impl PartialEq for UnitPermission {
+  fn eq(&self, other: &Self) -> bool {
+    let UnitPermission {
+      name,
+      description,
+      state,
+      prompt,
+      usage: _,
+    } = self;
+    *name == other.name
+      && *description == other.description
+      && *state == other.state
+      && *prompt == other.prompt
+  }
 }
 
+impl Eq for UnitPermission {}
+
 impl UnitPermission {
   pub fn query(&self) -> PermissionState {
     self.state
@@ -179,6 +218,7 @@ impl UnitPermission {
   }
 
   pub fn check(&mut self) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) =
       self.state.check(self.name, None, None, self.prompt);
     if prompted {
@@ -217,7 +257,7 @@ impl AsRef<str> for EnvVarName {
   }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct UnaryPermission<T: Eq + Hash> {
   pub name: &'static str,
   pub description: &'static str,
@@ -225,6 +265,40 @@ pub struct UnaryPermission<T: Eq + Hash> {
   pub granted_list: HashSet<T>,
   pub denied_list: HashSet<T>,
   pub prompt: bool,
+  pub usage: PermissionUsage,
+}
+
+// The usage is ignored when comparing permissions, like for `UnitPermission`.
+impl<T: Eq + Hash> PartialEq for UnaryPermission<T> {
+  fn eq(&self, other: &Self) -> bool {
+    let UnaryPermission {
+      name,
+      description,
+      global_state,
+      granted_list,
+      denied_list,
+      prompt,
+      usage: _,
+    } = self;
+    *name == other.name
+      && *description == other.description
+      && *global_state == other.global_state
+      && *granted_list == other.granted_list
+      && *denied_list == other.denied_list
+      && *prompt == other.prompt
+  }
+}
+
+impl<T: Eq + Hash> Eq for UnaryPermission<T> {}
+
+impl<T: Eq + Hash> UnaryPermission<T> {
+  /// Whether the permission was granted, entirely or for some descriptors,
+  /// without being checked since.
+  fn is_unused_grant(&self) -> bool {
+    (self.global_state == PermissionState::Granted
+      || !self.granted_list.is_empty())
+      && self.usage.count() == 0
+  }
 }
 
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
@@ -321,6 +395,10 @@ pub fn parse_sys_kind(kind: &str) -> Result<&str, AnyError> {
 #[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct FfiDescriptor(pub PathBuf);
 
+/// A module scheme handled by the embedder, like `test-fixture`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SchemeDescriptor(pub String);
+
 impl UnaryPermission<ReadDescriptor> {
   pub fn query(&self, path: Option<&Path>) -> PermissionState {
     if self.global_state == PermissionState::Granted {
@@ -421,6 +499,7 @@ impl UnaryPermission<ReadDescriptor> {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) = self.query(Some(path)).check2(
       self.name,
       api_name,
@@ -447,6 +526,7 @@ impl UnaryPermission<ReadDescriptor> {
     display: &str,
     api_name: &str,
   ) -> Result<(), AnyError> {
+    self.usage.record();
     let resolved_path = resolve_from_cwd(path)?;
     let (result, prompted) = self.query(Some(&resolved_path)).check(
       self.name,
@@ -466,6 +546,7 @@ impl UnaryPermission<ReadDescriptor> {
   }
 
   pub fn check_all(&mut self, api_name: Option<&str>) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) =
       self
         .query(None)
@@ -490,6 +571,7 @@ impl Default for UnaryPermission<ReadDescriptor> {
       granted_list: Default::default(),
       denied_list: Default::default(),
       prompt: false,
+      usage: Default::default(),
     }
   }
 }
@@ -594,6 +676,7 @@ impl UnaryPermission<WriteDescriptor> {
     path: &Path,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) = self.query(Some(path)).check2(
       self.name,
       api_name,
@@ -613,6 +696,7 @@ impl UnaryPermission<WriteDescriptor> {
   }
 
   pub fn check_all(&mut self, api_name: Option<&str>) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) =
       self
         .query(None)
@@ -637,6 +721,7 @@ impl Default for UnaryPermission<WriteDescriptor> {
       granted_list: Default::default(),
       denied_list: Default::default(),
       prompt: false,
+      usage: Default::default(),
     }
   }
 }
@@ -755,6 +840,7 @@ impl UnaryPermission<NetDescriptor> {
     host: &(T, Option<u16>),
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    self.usage.record();
     let new_host = NetDescriptor::new(&host);
     let (result, prompted) = self.query(Some(host)).check(
       self.name,
@@ -778,6 +864,7 @@ impl UnaryPermission<NetDescriptor> {
     url: &url::Url,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    self.usage.record();
     let hostname = url
       .host_str()
       .ok_or_else(|| uri_error("Missing host"))?
@@ -805,6 +892,7 @@ impl UnaryPermission<NetDescriptor> {
   }
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) =
       self
         .query::<&str>(None)
@@ -829,6 +917,7 @@ impl Default for UnaryPermission<NetDescriptor> {
       granted_list: Default::default(),
       denied_list: Default::default(),
       prompt: false,
+      usage: Default::default(),
     }
   }
 }
@@ -915,6 +1004,7 @@ impl UnaryPermission<EnvDescriptor> {
   }
 
   pub fn check(&mut self, env: &str) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) = self.query(Some(env)).check(
       self.name,
       None,
@@ -933,6 +1023,7 @@ impl UnaryPermission<EnvDescriptor> {
   }
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) =
       self
         .query(None)
@@ -957,6 +1048,7 @@ impl Default for UnaryPermission<EnvDescriptor> {
       granted_list: Default::default(),
       denied_list: Default::default(),
       prompt: false,
+      usage: Default::default(),
     }
   }
 }
@@ -1041,6 +1133,7 @@ impl UnaryPermission<SysDescriptor> {
     kind: &str,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) = self.query(Some(kind)).check(
       self.name,
       api_name,
@@ -1059,6 +1152,7 @@ impl UnaryPermission<SysDescriptor> {
   }
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) =
       self
         .query(None)
@@ -1083,6 +1177,142 @@ impl Default for UnaryPermission<SysDescriptor> {
       granted_list: Default::default(),
       denied_list: Default::default(),
       prompt: false,
+      usage: Default::default(),
+    }
+  }
+}
+
+impl UnaryPermission<SchemeDescriptor> {
+  pub fn query(&self, scheme: Option<&str>) -> PermissionState {
+    if self.global_state == PermissionState::Denied
+      && match scheme {
+        None => true,
+        Some(scheme) => self
+          .denied_list
+          .contains(&SchemeDescriptor(scheme.to_string())),
+      }
+    {
+      PermissionState::Denied
+    } else if self.global_state == PermissionState::Granted
+      || match scheme {
+        None => false,
+        Some(scheme) => self
+          .granted_list
+          .contains(&SchemeDescriptor(scheme.to_string())),
+      }
+    {
+      PermissionState::Granted
+    } else {
+      PermissionState::Prompt
+    }
+  }
+
+  pub fn request(&mut self, scheme: Option<&str>) -> PermissionState {
+    let state = self.query(scheme);
+    if state != PermissionState::Prompt {
+      return state;
+    }
+    if let Some(scheme) = scheme {
+      let desc = SchemeDescriptor(scheme.to_string());
+      if PromptResponse::Allow
+        == permission_prompt(
+          &format!("import from the \"{scheme}\" scheme"),
+          self.name,
+          Some("Deno.permissions.query()"),
+        )
+      {
+        self.granted_list.insert(desc);
+        PermissionState::Granted
+      } else {
+        self.denied_list.insert(desc);
+        self.global_state = PermissionState::Denied;
+        PermissionState::Denied
+      }
+    } else {
+      if PromptResponse::Allow
+        == permission_prompt(
+          "import from custom schemes",
+          self.name,
+          Some("Deno.permissions.query()"),
+        )
+      {
+        self.global_state = PermissionState::Granted;
+      } else {
+        self.granted_list.clear();
+        self.global_state = PermissionState::Denied;
+      }
+      self.global_state
+    }
+  }
+
+  pub fn revoke(&mut self, scheme: Option<&str>) -> PermissionState {
+    if let Some(scheme) = scheme {
+      self
+        .granted_list
+        .remove(&SchemeDescriptor(scheme.to_string()));
+    } else {
+      self.granted_list.clear();
+    }
+    if self.global_state == PermissionState::Granted {
+      self.global_state = PermissionState::Prompt;
+    }
+    self.query(scheme)
+  }
+
+  pub fn check(
+    &mut self,
+    scheme: &str,
+    api_name: Option<&str>,
+  ) -> Result<(), AnyError> {
+    self.usage.record();
+    let (result, prompted) = self.query(Some(scheme)).check(
+      self.name,
+      api_name,
+      Some(&format!("\"{scheme}\"")),
+      self.prompt,
+    );
+    if prompted {
+      if result.is_ok() {
+        self
+          .granted_list
+          .insert(SchemeDescriptor(scheme.to_string()));
+      } else {
+        self
+          .denied_list
+          .insert(SchemeDescriptor(scheme.to_string()));
+        self.global_state = PermissionState::Denied;
+      }
+    }
+    result
+  }
+
+  pub fn check_all(&mut self) -> Result<(), AnyError> {
+    self.usage.record();
+    let (result, prompted) =
+      self
+        .query(None)
+        .check(self.name, None, Some("all"), self.prompt);
+    if prompted {
+      if result.is_ok() {
+        self.global_state = PermissionState::Granted;
+      } else {
+        self.global_state = PermissionState::Denied;
+      }
+    }
+    result
+  }
+}
+
+impl Default for UnaryPermission<SchemeDescriptor> {
+  fn default() -> Self {
+    UnaryPermission::<SchemeDescriptor> {
+      name: "scheme",
+      description: "custom module schemes",
+      global_state: Default::default(),
+      granted_list: Default::default(),
+      denied_list: Default::default(),
+      prompt: false,
+      usage: Default::default(),
     }
   }
 }
@@ -1184,6 +1414,7 @@ impl UnaryPermission<RunDescriptor> {
     cmd: &str,
     api_name: Option<&str>,
   ) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) = self.query(Some(cmd)).check(
       self.name,
       api_name,
@@ -1206,6 +1437,7 @@ impl UnaryPermission<RunDescriptor> {
   }
 
   pub fn check_all(&mut self, api_name: Option<&str>) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) =
       self
         .query(None)
@@ -1230,6 +1462,7 @@ impl Default for UnaryPermission<RunDescriptor> {
       granted_list: Default::default(),
       denied_list: Default::default(),
       prompt: false,
+      usage: Default::default(),
     }
   }
 }
@@ -1326,6 +1559,7 @@ impl UnaryPermission<FfiDescriptor> {
   }
 
   pub fn check(&mut self, path: Option<&Path>) -> Result<(), AnyError> {
+    self.usage.record();
     if let Some(path) = path {
       let (resolved_path, display_path) = resolved_and_display_path(path);
       let (result, prompted) = self.query(Some(&resolved_path)).check(
@@ -1362,6 +1596,7 @@ impl UnaryPermission<FfiDescriptor> {
   }
 
   pub fn check_all(&mut self) -> Result<(), AnyError> {
+    self.usage.record();
     let (result, prompted) =
       self
         .query(None)
@@ -1386,6 +1621,7 @@ impl Default for UnaryPermission<FfiDescriptor> {
       granted_list: Default::default(),
       denied_list: Default::default(),
       prompt: false,
+      usage: Default::default(),
     }
   }
 }
@@ -1399,6 +1635,7 @@ pub struct Permissions {
   pub sys: UnaryPermission<SysDescriptor>,
   pub run: UnaryPermission<RunDescriptor>,
   pub ffi: UnaryPermission<FfiDescriptor>,
+  pub scheme: UnaryPermission<SchemeDescriptor>,
   pub hrtime: UnitPermission,
 }
 
@@ -1412,6 +1649,7 @@ impl Default for Permissions {
       sys: Permissions::new_sys(&None, false).unwrap(),
       run: Permissions::new_run(&None, false).unwrap(),
       ffi: Permissions::new_ffi(&None, false).unwrap(),
+      scheme: Permissions::new_scheme(&None, false).unwrap(),
       hrtime: Permissions::new_hrtime(false),
     }
   }
@@ -1425,11 +1663,19 @@ pub struct PermissionsOptions {
   pub allow_ffi: Option<Vec<PathBuf>>,
   pub allow_read: Option<Vec<PathBuf>>,
   pub allow_run: Option<Vec<String>>,
+  pub allow_scheme: Option<Vec<String>>,
   pub allow_sys: Option<Vec<String>>,
   pub allow_write: Option<Vec<PathBuf>>,
   pub prompt: bool,
 }
 
+/// A permission that was granted but never checked.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct UnusedPermission {
+  pub name: &'static str,
+  pub flag: String,
+}
+
 impl Permissions {
   pub fn new_read(
     state: &Option<Vec<PathBuf>>,
@@ -1561,6 +1807,31 @@ impl Permissions {
     })
   }
 
+  pub fn new_scheme(
+    state: &Option<Vec<String>>,
+    prompt: bool,
+  ) -> Result<UnaryPermission<SchemeDescriptor>, AnyError> {
+    Ok(UnaryPermission::<SchemeDescriptor> {
+      global_state: global_state_from_option(state),
+      granted_list: state.as_ref().map_or_else(
+        || Ok(HashSet::new()),
+        |v| {
+          v.iter()
+            .map(|x| {
+              if x.is_empty() {
+                Err(AnyError::msg("Empty scheme is not allowed"))
+              } else {
+                Ok(SchemeDescriptor(x.to_string()))
+              }
+            })
+            .collect()
+        },
+      )?,
+      prompt,
+      ..Default::default()
+    })
+  }
+
   pub fn new_hrtime(state: bool) -> UnitPermission {
     unit_permission_from_flag_bool(
       state,
@@ -1579,6 +1850,7 @@ impl Permissions {
       sys: Permissions::new_sys(&opts.allow_sys, opts.prompt)?,
       run: Permissions::new_run(&opts.allow_run, opts.prompt)?,
       ffi: Permissions::new_ffi(&opts.allow_ffi, opts.prompt)?,
+      scheme: Permissions::new_scheme(&opts.allow_scheme, opts.prompt)?,
       hrtime: Permissions::new_hrtime(opts.allow_hrtime),
     })
   }
@@ -1592,12 +1864,41 @@ impl Permissions {
       sys: Permissions::new_sys(&Some(vec![]), false).unwrap(),
       run: Permissions::new_run(&Some(vec![]), false).unwrap(),
       ffi: Permissions::new_ffi(&Some(vec![]), false).unwrap(),
+      scheme: Permissions::new_scheme(&Some(vec![]), false).unwrap(),
       hrtime: Permissions::new_hrtime(true),
     }
   }
 
+  /// The permissions that were granted but never checked, for
+  /// `--report-unused-permissions`.
+  pub fn unused_permissions(&self) -> Vec<UnusedPermission> {
+    [
+      (self.read.name, self.read.is_unused_grant()),
+      (self.write.name, self.write.is_unused_grant()),
+      (self.net.name, self.net.is_unused_grant()),
+      (self.env.name, self.env.is_unused_grant()),
+      (self.sys.name, self.sys.is_unused_grant()),
+      (self.run.name, self.run.is_unused_grant()),
+      (self.ffi.name, self.ffi.is_unused_grant()),
+      (self.scheme.name, self.scheme.is_unused_grant()),
+      (
+        self.hrtime.name,
+        self.hrtime.state == PermissionState::Granted
+          && self.hrtime.usage.count() == 0,
+      ),
+    ]
+    .into_iter()
+    .filter(|(_, is_unused)| *is_unused)
+    .map(|(name, _)| UnusedPermission {
+      name,
+      flag: format!("--allow-{name}"),
+    })
+    .collect()
+  }
+
   /// A helper function that determines if the module specifier is a local or
-  /// remote, and performs a read or net check for the specifier.
+  /// remote, and performs a read or net check for the specifier. Any other
+  /// scheme is handled by the embedder and needs the scheme permission.
   pub fn check_specifier(
     &mut self,
     specifier: &ModuleSpecifier,
@@ -1611,7 +1912,8 @@ impl Permissions {
       },
       "data" => Ok(()),
       "blob" => Ok(()),
-      _ => self.net.check_url(specifier, Some("import()")),
+      "http" | "https" => self.net.check_url(specifier, Some("import()")),
+      scheme => self.scheme.check(scheme, Some("import()")),
     }
   }
 }
@@ -1835,6 +2137,7 @@ fn unit_permission_from_flag_bool(
       PermissionState::Prompt
     },
     prompt,
+    usage: Default::default(),
   }
 }
 
@@ -2044,6 +2347,7 @@ pub struct ChildPermissionsArg {
   ffi: ChildUnaryPermissionArg,
   read: ChildUnaryPermissionArg,
   run: ChildUnaryPermissionArg,
+  scheme: ChildUnaryPermissionArg,
   sys: ChildUnaryPermissionArg,
   write: ChildUnaryPermissionArg,
 }
@@ -2057,6 +2361,7 @@ impl ChildPermissionsArg {
       ffi: ChildUnaryPermissionArg::Inherit,
       read: ChildUnaryPermissionArg::Inherit,
       run: ChildUnaryPermissionArg::Inherit,
+      scheme: ChildUnaryPermissionArg::Inherit,
       sys: ChildUnaryPermissionArg::Inherit,
       write: ChildUnaryPermissionArg::Inherit,
     }
@@ -2070,6 +2375,7 @@ impl ChildPermissionsArg {
       ffi: ChildUnaryPermissionArg::NotGranted,
       read: ChildUnaryPermissionArg::NotGranted,
       run: ChildUnaryPermissionArg::NotGranted,
+      scheme: ChildUnaryPermissionArg::NotGranted,
       sys: ChildUnaryPermissionArg::NotGranted,
       write: ChildUnaryPermissionArg::NotGranted,
     }
@@ -2147,6 +2453,11 @@ impl<'de> Deserialize<'de> for ChildPermissionsArg {
             child_permissions_arg.run = arg.map_err(|e| {
               de::Error::custom(format!("(deno.permissions.run) {e}"))
             })?;
+          } else if key == "scheme" {
+            let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
+            child_permissions_arg.scheme = arg.map_err(|e| {
+              de::Error::custom(format!("(deno.permissions.scheme) {e}"))
+            })?;
           } else if key == "sys" {
             let arg = serde_json::from_value::<ChildUnaryPermissionArg>(value);
             child_permissions_arg.sys = arg.map_err(|e| {
@@ -2231,6 +2542,35 @@ pub fn create_child_permissions(
     worker_perms.sys.global_state = PermissionState::Denied;
   }
   worker_perms.sys.prompt = main_perms.sys.prompt;
+  match child_permissions_arg.scheme {
+    ChildUnaryPermissionArg::Inherit => {
+      worker_perms.scheme = main_perms.scheme.clone();
+    }
+    ChildUnaryPermissionArg::Granted => {
+      if main_perms.scheme.check_all().is_err() {
+        return Err(escalation_error());
+      }
+      worker_perms.scheme.global_state = PermissionState::Granted;
+    }
+    ChildUnaryPermissionArg::NotGranted => {}
+    ChildUnaryPermissionArg::GrantedList(granted_list) => {
+      worker_perms.scheme.granted_list =
+        Permissions::new_scheme(&Some(granted_list), false)?.granted_list;
+      if !worker_perms
+        .scheme
+        .granted_list
+        .iter()
+        .all(|desc| main_perms.scheme.check(&desc.0, None).is_ok())
+      {
+        return Err(escalation_error());
+      }
+    }
+  }
+  worker_perms.scheme.denied_list = main_perms.scheme.denied_list.clone();
+  if main_perms.scheme.global_state == PermissionState::Denied {
+    worker_perms.scheme.global_state = PermissionState::Denied;
+  }
+  worker_perms.scheme.prompt = main_perms.scheme.prompt;
   match child_permissions_arg.hrtime {
     ChildUnitPermissionArg::Inherit => {
       worker_perms.hrtime = main_perms.hrtime.clone();
@@ -2705,6 +3045,7 @@ mod tests {
     let mut perms = Permissions::from_options(&PermissionsOptions {
       allow_read: Some(read_allowlist),
       allow_net: Some(svec!["localhost"]),
+      allow_scheme: Some(svec!["test-fixture"]),
       ..Default::default()
     })
     .unwrap();
@@ -2722,6 +3063,14 @@ mod tests {
         resolve_url_or_path("data:text/plain,Hello%2C%20Deno!").unwrap(),
         true,
       ),
+      (
+        resolve_url_or_path("test-fixture://fixtures/mod.ts").unwrap(),
+        true,
+      ),
+      (
+        resolve_url_or_path("other://fixtures/mod.ts").unwrap(),
+        false,
+      ),
     ];
 
     if cfg!(target_os = "windows") {
@@ -2797,6 +3146,10 @@ mod tests {
         global_state: PermissionState::Prompt,
         ..Permissions::new_run(&Some(svec!["deno"]), false).unwrap()
       },
+      scheme: UnaryPermission {
+        global_state: PermissionState::Prompt,
+        ..Permissions::new_scheme(&Some(svec!["test-fixture"]), false).unwrap()
+      },
       hrtime: UnitPermission {
         state: PermissionState::Prompt,
         ..Permissions::new_hrtime(false)
@@ -2835,6 +3188,10 @@ mod tests {
       assert_eq!(perms1.run.query(Some("deno")), PermissionState::Granted);
       assert_eq!(perms2.run.query(None), PermissionState::Prompt);
       assert_eq!(perms2.run.query(Some("deno")), PermissionState::Granted);
+      assert_eq!(perms1.scheme.query(None), PermissionState::Granted);
+      assert_eq!(perms1.scheme.query(Some("test-fixture")), PermissionState::Granted);
+      assert_eq!(perms2.scheme.query(None), PermissionState::Prompt);
+      assert_eq!(perms2.scheme.query(Some("test-fixture")), PermissionState::Granted);
       assert_eq!(perms1.hrtime.query(), PermissionState::Granted);
       assert_eq!(perms2.hrtime.query(), PermissionState::Prompt);
     };
@@ -2881,6 +3238,11 @@ mod tests {
       assert_eq!(perms.run.query(None), PermissionState::Prompt);
       prompt_value.set(false);
       assert_eq!(perms.run.request(Some("deno")), PermissionState::Granted);
+      prompt_value.set(true);
+      assert_eq!(perms.scheme.request(Some("test-fixture")), PermissionState::Granted);
+      assert_eq!(perms.scheme.query(None), PermissionState::Prompt);
+      prompt_value.set(false);
+      assert_eq!(perms.scheme.request(Some("test-fixture")), PermissionState::Granted);
       prompt_value.set(false);
       assert_eq!(perms.hrtime.request(), PermissionState::Denied);
       prompt_value.set(true);
@@ -2936,6 +3298,10 @@ mod tests {
         global_state: PermissionState::Prompt,
         ..Permissions::new_run(&Some(svec!["deno"]), false).unwrap()
       },
+      scheme: UnaryPermission {
+        global_state: PermissionState::Prompt,
+        ..Permissions::new_scheme(&Some(svec!["test-fixture"]), false).unwrap()
+      },
       hrtime: UnitPermission {
         state: PermissionState::Denied,
         ..Permissions::new_hrtime(false)
@@ -2958,6 +3324,7 @@ mod tests {
       assert_eq!(perms.env.revoke(Some("HOME")), PermissionState::Prompt);
       assert_eq!(perms.env.revoke(Some("hostname")), PermissionState::Prompt);
       assert_eq!(perms.run.revoke(Some("deno")), PermissionState::Prompt);
+      assert_eq!(perms.scheme.revoke(Some("test-fixture")), PermissionState::Prompt);
       assert_eq!(perms.hrtime.revoke(), PermissionState::Denied);
     };
   }
@@ -2973,6 +3340,7 @@ mod tests {
       sys: Permissions::new_sys(&None, true).unwrap(),
       run: Permissions::new_run(&None, true).unwrap(),
       ffi: Permissions::new_ffi(&None, true).unwrap(),
+      scheme: Permissions::new_scheme(&None, true).unwrap(),
       hrtime: Permissions::new_hrtime(false),
     };
 
@@ -3037,6 +3405,7 @@ mod tests {
       sys: Permissions::new_sys(&None, true).unwrap(),
       run: Permissions::new_run(&None, true).unwrap(),
       ffi: Permissions::new_ffi(&None, true).unwrap(),
+      scheme: Permissions::new_scheme(&None, true).unwrap(),
       hrtime: Permissions::new_hrtime(false),
     };
 
@@ -3138,6 +3507,7 @@ mod tests {
         ffi: ChildUnaryPermissionArg::Inherit,
         read: ChildUnaryPermissionArg::Inherit,
         run: ChildUnaryPermissionArg::Inherit,
+        scheme: ChildUnaryPermissionArg::Inherit,
         sys: ChildUnaryPermissionArg::Inherit,
         write: ChildUnaryPermissionArg::Inherit,
       }
@@ -3151,6 +3521,7 @@ mod tests {
         ffi: ChildUnaryPermissionArg::NotGranted,
         read: ChildUnaryPermissionArg::NotGranted,
         run: ChildUnaryPermissionArg::NotGranted,
+        scheme: ChildUnaryPermissionArg::NotGranted,
         sys: ChildUnaryPermissionArg::NotGranted,
         write: ChildUnaryPermissionArg::NotGranted,
       }
@@ -3204,6 +3575,7 @@ mod tests {
         "ffi": true,
         "read": true,
         "run": true,
+        "scheme": true,
         "sys": true,
         "write": true,
       }))
@@ -3214,6 +3586,7 @@ mod tests {
         ffi: ChildUnaryPermissionArg::Granted,
         read: ChildUnaryPermissionArg::Granted,
         run: ChildUnaryPermissionArg::Granted,
+        scheme: ChildUnaryPermissionArg::Granted,
         sys: ChildUnaryPermissionArg::Granted,
         write: ChildUnaryPermissionArg::Granted,
         ..ChildPermissionsArg::none()
@@ -3226,6 +3599,7 @@ mod tests {
         "ffi": false,
         "read": false,
         "run": false,
+        "scheme": false,
         "sys": false,
         "write": false,
       }))
@@ -3236,6 +3610,7 @@ mod tests {
         ffi: ChildUnaryPermissionArg::NotGranted,
         read: ChildUnaryPermissionArg::NotGranted,
         run: ChildUnaryPermissionArg::NotGranted,
+        scheme: ChildUnaryPermissionArg::NotGranted,
         sys: ChildUnaryPermissionArg::NotGranted,
         write: ChildUnaryPermissionArg::NotGranted,
         ..ChildPermissionsArg::none()
@@ -3248,6 +3623,7 @@ mod tests {
         "ffi": ["foo", "file:///bar/baz"],
         "read": ["foo", "file:///bar/baz"],
         "run": ["foo", "file:///bar/baz", "./qux"],
+        "scheme": ["test-fixture"],
         "sys": ["hostname", "osRelease"],
         "write": ["foo", "file:///bar/baz"],
       }))
@@ -3268,6 +3644,7 @@ mod tests {
           "file:///bar/baz",
           "./qux"
         ]),
+        scheme: ChildUnaryPermissionArg::GrantedList(svec!["test-fixture"]),
         sys: ChildUnaryPermissionArg::GrantedList(svec![
           "hostname",
           "osRelease"
@@ -3386,4 +3763,44 @@ mod tests {
     assert!(Permissions::new_net(&Some(svec![String::new()]), false).is_err());
     assert!(Permissions::new_write(&Some(vec![PathBuf::new()]), false).is_err());
   }
+
+  #[test]
+  fn test_unused_permissions() {
+    set_prompter(Box::new(TestPrompter));
+    let mut perms = Permissions::from_options(&PermissionsOptions {
+      allow_env: Some(vec![]),
+      allow_hrtime: true,
+      allow_net: Some(svec!["deno.land"]),
+      allow_read: Some(vec![PathBuf::from("/foo")]),
+      ..Default::default()
+    })
+    .unwrap();
+    let unused_names = |perms: &Permissions| {
+      perms
+        .unused_permissions()
+        .into_iter()
+        .map(|unused| unused.name)
+        .collect::<Vec<_>>()
+    };
+    assert_eq!(unused_names(&perms), vec!["read", "net", "env", "hrtime"]);
+    assert_eq!(perms.unused_permissions()[0].flag, "--allow-read");
+
+    // a denied check counts as a use of the permission
+    assert!(perms.read.check(Path::new("/bar"), None).is_err());
+    assert!(perms.env.check("HOME").is_ok());
+    assert_eq!(unused_names(&perms), vec!["net", "hrtime"]);
+
+    // the checks of a worker inheriting the permission are counted as well
+    let mut worker_perms =
+      create_child_permissions(&mut perms, ChildPermissionsArg::inherit())
+        .unwrap();
+    assert!(worker_perms.net.check(&("deno.land", None), None).is_ok());
+    assert_eq!(unused_names(&perms), vec!["hrtime"]);
+
+    // the usage isn't compared along with the state of the permissions
+    assert_eq!(
+      perms.net,
+      Permissions::new_net(&Some(svec!["deno.land"]), false).unwrap()
+    );
+  }
 }
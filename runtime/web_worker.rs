@@ -1,5 +1,6 @@
 // Copyright 2018-2023 the Deno authors. All rights reserved. MIT license.
 use crate::colors;
+use crate::fmt_errors::StackTraceOptions;
 use crate::inspector_server::InspectorServer;
 use crate::js;
 use crate::ops;
@@ -319,6 +320,7 @@ pub struct WebWorker {
   pub worker_type: WebWorkerType,
   pub main_module: ModuleSpecifier,
   poll_for_messages_fn: Option<v8::Global<v8::Value>>,
+  stack_trace_options: StackTraceOptions,
 }
 
 pub struct WebWorkerOptions {
@@ -334,6 +336,8 @@ pub struct WebWorkerOptions {
   pub preload_module_cb: Arc<ops::worker_host::WorkerEventCb>,
   pub pre_execute_module_cb: Arc<ops::worker_host::WorkerEventCb>,
   pub format_js_error_fn: Option<Arc<FormatJsErrorFn>>,
+  /// How the stack traces of the errors the worker fails with are printed.
+  pub stack_trace_options: StackTraceOptions,
   pub source_map_getter: Option<Box<dyn SourceMapGetter>>,
   pub worker_type: WebWorkerType,
   pub maybe_inspector_server: Option<Arc<InspectorServer>>,
@@ -408,9 +412,10 @@ impl WebWorker {
         options.root_cert_store.clone(),
         options.unsafely_ignore_certificate_errors.clone(),
       ),
-      deno_webstorage::init(None).disable(),
+      deno_webstorage::init(None, Default::default()).disable(),
       deno_broadcast_channel::init(options.broadcast_channel.clone(), unstable),
       deno_crypto::init(options.seed),
+      #[cfg(feature = "webgpu")]
       deno_webgpu::init(unstable),
       // ffi
       deno_ffi::init::<PermissionsContainer>(unstable),
@@ -435,6 +440,7 @@ impl WebWorker {
         options.unsafely_ignore_certificate_errors.clone(),
       ),
       deno_napi::init::<PermissionsContainer>(unstable),
+      #[cfg(feature = "node")]
       deno_node::init::<PermissionsContainer>(options.npm_resolver),
       ops::os::init_for_worker(),
       ops::permissions::init(),
@@ -501,6 +507,7 @@ impl WebWorker {
         worker_type: options.worker_type,
         main_module,
         poll_for_messages_fn: None,
+        stack_trace_options: options.stack_trace_options,
       },
       external_handle,
     )
@@ -675,10 +682,11 @@ fn print_worker_error(
   error: &AnyError,
   name: &str,
   format_js_error_fn: Option<&FormatJsErrorFn>,
+  stack_trace_options: &StackTraceOptions,
 ) {
   let error_str = match format_js_error_fn {
     Some(format_js_error_fn) => match error.downcast_ref::<JsError>() {
-      Some(js_error) => format_js_error_fn(js_error),
+      Some(js_error) => format_js_error_fn(js_error, stack_trace_options),
       None => error.to_string(),
     },
     None => error.to_string(),
@@ -702,6 +710,7 @@ pub fn run_web_worker(
   format_js_error_fn: Option<Arc<FormatJsErrorFn>>,
 ) -> Result<(), AnyError> {
   let name = worker.name.to_string();
+  let stack_trace_options = worker.stack_trace_options;
 
   // TODO(bartlomieju): run following block using "select!"
   // with terminate
@@ -713,7 +722,12 @@ pub fn run_web_worker(
     let mut worker = match result {
       Ok(worker) => worker,
       Err(e) => {
-        print_worker_error(&e, &name, format_js_error_fn.as_deref());
+        print_worker_error(
+          &e,
+          &name,
+          format_js_error_fn.as_deref(),
+          &stack_trace_options,
+        );
         internal_handle
           .post_event(WorkerControlEvent::TerminalError(e))
           .expect("Failed to post message to host");
@@ -736,7 +750,12 @@ pub fn run_web_worker(
           worker = match (pre_execute_module_cb)(worker).await {
             Ok(worker) => worker,
             Err(e) => {
-              print_worker_error(&e, &name, format_js_error_fn.as_deref());
+              print_worker_error(
+                &e,
+                &name,
+                format_js_error_fn.as_deref(),
+                &stack_trace_options,
+              );
               internal_handle
                 .post_event(WorkerControlEvent::TerminalError(e))
                 .expect("Failed to post message to host");
@@ -765,7 +784,12 @@ pub fn run_web_worker(
     };
 
     if let Err(e) = result {
-      print_worker_error(&e, &name, format_js_error_fn.as_deref());
+      print_worker_error(
+        &e,
+        &name,
+        format_js_error_fn.as_deref(),
+        &stack_trace_options,
+      );
       internal_handle
         .post_event(WorkerControlEvent::TerminalError(e))
         .expect("Failed to post message to host");
@@ -34,6 +34,7 @@ use deno_tls::rustls::RootCertStore;
 use deno_web::BlobStore;
 use log::debug;
 
+use crate::fmt_errors::StackTraceOptions;
 use crate::inspector_server::InspectorServer;
 use crate::js;
 use crate::ops;
@@ -41,7 +42,8 @@ use crate::ops::io::Stdio;
 use crate::permissions::PermissionsContainer;
 use crate::BootstrapOptions;
 
-pub type FormatJsErrorFn = dyn Fn(&JsError) -> String + Sync + Send;
+pub type FormatJsErrorFn =
+  dyn Fn(&JsError, &StackTraceOptions) -> String + Sync + Send;
 
 #[derive(Clone, Default)]
 pub struct ExitCode(Arc<AtomicI32>);
@@ -124,6 +126,7 @@ pub struct WorkerOptions {
   pub get_error_class_fn: Option<GetErrorClassFn>,
   pub cache_storage_dir: Option<std::path::PathBuf>,
   pub origin_storage_dir: Option<std::path::PathBuf>,
+  pub webstorage_options: deno_webstorage::WebStorageOptions,
   pub blob_store: BlobStore,
   pub broadcast_channel: InMemoryBroadcastChannel,
 
@@ -167,6 +170,7 @@ impl Default for WorkerOptions {
       format_js_error_fn: Default::default(),
       get_error_class_fn: Default::default(),
       origin_storage_dir: Default::default(),
+      webstorage_options: Default::default(),
       cache_storage_dir: Default::default(),
       broadcast_channel: Default::default(),
       source_map_getter: Default::default(),
@@ -241,9 +245,13 @@ impl MainWorker {
         options.root_cert_store.clone(),
         options.unsafely_ignore_certificate_errors.clone(),
       ),
-      deno_webstorage::init(options.origin_storage_dir.clone()),
+      deno_webstorage::init(
+        options.origin_storage_dir.clone(),
+        options.webstorage_options.clone(),
+      ),
       deno_broadcast_channel::init(options.broadcast_channel.clone(), unstable),
       deno_crypto::init(options.seed),
+      #[cfg(feature = "webgpu")]
       deno_webgpu::init(unstable),
       // ffi
       deno_ffi::init::<PermissionsContainer>(unstable),
@@ -267,6 +275,7 @@ impl MainWorker {
         options.unsafely_ignore_certificate_errors.clone(),
       ),
       deno_napi::init::<PermissionsContainer>(unstable),
+      #[cfg(feature = "node")]
       deno_node::init::<PermissionsContainer>(options.npm_resolver),
       ops::os::init(exit_code.clone()),
       ops::permissions::init(),
@@ -22,6 +22,7 @@ use rustls::Certificate;
 use rustls::PrivateKey;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::Infallible;
 use std::env;
 use std::io;
@@ -38,11 +39,14 @@ use std::process::Command;
 use std::process::Output;
 use std::process::Stdio;
 use std::result::Result;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
 use std::task::Context;
 use std::task::Poll;
+use std::time::Duration;
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpListener;
 use tokio::net::TcpStream;
@@ -84,7 +88,25 @@ pub const PERMISSION_VARIANTS: [&str; 5] =
   ["read", "write", "env", "net", "run"];
 pub const PERMISSION_DENIED_PATTERN: &str = "PermissionDenied";
 
+/// How long the HTTP/2 only server takes to serve each file of the fetch
+/// concurrency test.
+pub const FETCH_CONCURRENCY_DELAY: Duration = Duration::from_millis(200);
+
+/// The id of the next connection accepted by the HTTP/2 only server.
+static NEXT_H2_ONLY_CONNECTION_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The number of files of the fetch concurrency test being served, and the
+/// most served at the same time.
+static FETCH_CONCURRENCY_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static FETCH_CONCURRENCY_MAX_IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+
 lazy_static! {
+  /// The ids of the connections of the HTTP/2 only server which served the
+  /// requests of each path, so that a test can count the connections used for
+  /// its own files while other tests use the server.
+  static ref H2_ONLY_CONNECTIONS_BY_PATH: Mutex<HashMap<String, HashSet<usize>>> =
+    Mutex::new(HashMap::new());
+
   // STRIP_ANSI_RE and strip_ansi_codes are lifted from the "console" crate.
   // Copyright 2017 Armin Ronacher <armin.ronacher@active-4.com>. MIT License.
   static ref STRIP_ANSI_RE: Regex = Regex::new(
@@ -167,6 +189,18 @@ pub fn deno_exe_path() -> PathBuf {
   p
 }
 
+/// The runtime without the TypeScript compiler which `deno compile --strip`
+/// embeds. It's built separately with
+/// `cargo build -p deno --no-default-features --features napi,npm` and copied
+/// next to the deno executable.
+pub fn denort_exe_path() -> PathBuf {
+  let mut p = target_dir().join("denort");
+  if cfg!(windows) {
+    p.set_extension("exe");
+  }
+  p
+}
+
 pub fn prebuilt_tool_path(tool: &str) -> PathBuf {
   let mut exe = tool.to_string();
   exe.push_str(if cfg!(windows) { ".exe" } else { "" });
@@ -757,6 +791,47 @@ async fn main_server(
       );
       Ok(res)
     }
+    (_, "/range_resume.txt") => {
+      // serves the requested range when the If-Range validator matches
+      const CONTENT: &str = "0123456789abcdefghij";
+      const ETAG: &str = "\"range-resume\"";
+      let maybe_start = req
+        .headers()
+        .get("range")
+        .and_then(|range| range.to_str().ok())
+        .and_then(|range| range.strip_prefix("bytes="))
+        .and_then(|range| range.trim_end_matches('-').parse::<usize>().ok())
+        .filter(|_| {
+          req.headers().get("if-range") == Some(&HeaderValue::from_static(ETAG))
+        });
+      let mut resp = match maybe_start {
+        Some(start) if start < CONTENT.len() => {
+          let mut resp = Response::new(Body::from(&CONTENT[start..]));
+          *resp.status_mut() = StatusCode::PARTIAL_CONTENT;
+          resp.headers_mut().insert(
+            "Content-Range",
+            HeaderValue::from_str(&format!(
+              "bytes {}-{}/{}",
+              start,
+              CONTENT.len() - 1,
+              CONTENT.len()
+            ))
+            .unwrap(),
+          );
+          resp
+        }
+        Some(_) => {
+          let mut resp = Response::new(Body::empty());
+          *resp.status_mut() = StatusCode::RANGE_NOT_SATISFIABLE;
+          resp
+        }
+        None => Response::new(Body::from(CONTENT)),
+      };
+      resp
+        .headers_mut()
+        .insert("ETag", HeaderValue::from_static(ETAG));
+      Ok(resp)
+    }
     (_, "/etag_script.ts") => {
       let if_none_match = req.headers().get("if-none-match");
       if if_none_match == Some(&HeaderValue::from_static("33a64df551425fcc55e"))
@@ -949,6 +1024,23 @@ async fn main_server(
       let version = format!("{:?}", req.version());
       Ok(Response::new(version.into()))
     }
+    (_, "/h2_connection_count") => {
+      // the query is the prefix of the paths to count the connections of
+      let prefix = req.uri().query().unwrap_or_default();
+      let connections_by_path = H2_ONLY_CONNECTIONS_BY_PATH.lock().unwrap();
+      let count = connections_by_path
+        .iter()
+        .filter(|(path, _)| path.starts_with(prefix))
+        .flat_map(|(_, ids)| ids)
+        .collect::<HashSet<_>>()
+        .len();
+      Ok(Response::new(count.to_string().into()))
+    }
+    (_, "/fetch_concurrency_max_in_flight") => {
+      // reset so that the next run of the test starts over
+      let max = FETCH_CONCURRENCY_MAX_IN_FLIGHT.swap(0, Ordering::SeqCst);
+      Ok(Response::new(max.to_string().into()))
+    }
     (_, "/content_length") => {
       let content_length = format!("{:?}", req.headers().get("content-length"));
       Ok(Response::new(content_length.into()))
@@ -1378,8 +1470,14 @@ async fn wrap_https_h2_only_server() {
     }
     .boxed();
 
-    let main_server_https_svc = make_service_fn(|_| async {
-      Ok::<_, Infallible>(service_fn(main_server))
+    let main_server_https_svc = make_service_fn(|_| {
+      let connection_id =
+        NEXT_H2_ONLY_CONNECTION_ID.fetch_add(1, Ordering::SeqCst);
+      async move {
+        Ok::<_, Infallible>(service_fn(move |req| {
+          h2_only_server(connection_id, req)
+        }))
+      }
     });
     let main_server_https = Server::builder(HyperAcceptor {
       acceptor: incoming_tls_stream,
@@ -1394,6 +1492,33 @@ async fn wrap_https_h2_only_server() {
   }
 }
 
+/// Records the connection of each request, and delays the files of the
+/// fetch concurrency test and counts how many are served at the same time, so
+/// that the test can check that they're downloaded concurrently up to the
+/// limit.
+async fn h2_only_server(
+  connection_id: usize,
+  req: Request<Body>,
+) -> Result<Response<Body>, hyper::http::Error> {
+  let path = req.uri().path().to_string();
+  H2_ONLY_CONNECTIONS_BY_PATH
+    .lock()
+    .unwrap()
+    .entry(path.clone())
+    .or_default()
+    .insert(connection_id);
+  if !path.starts_with("/cache/fetch_concurrency/") {
+    return main_server(req).await;
+  }
+  let in_flight =
+    FETCH_CONCURRENCY_IN_FLIGHT.fetch_add(1, Ordering::SeqCst) + 1;
+  FETCH_CONCURRENCY_MAX_IN_FLIGHT.fetch_max(in_flight, Ordering::SeqCst);
+  tokio::time::sleep(FETCH_CONCURRENCY_DELAY).await;
+  let response = main_server(req).await;
+  FETCH_CONCURRENCY_IN_FLIGHT.fetch_sub(1, Ordering::SeqCst);
+  response
+}
+
 async fn wrap_client_auth_https_server() {
   let main_server_https_addr =
     SocketAddr::from(([127, 0, 0, 1], HTTPS_CLIENT_AUTH_PORT));
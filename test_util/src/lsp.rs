@@ -41,6 +41,12 @@ pub struct LspResponseError {
   data: Option<Value>,
 }
 
+impl LspResponseError {
+  pub fn code(&self) -> i32 {
+    self.code
+  }
+}
+
 #[derive(Clone, Debug)]
 pub enum LspMessage {
   Notification(String, Option<Value>),
@@ -333,6 +339,36 @@ impl LspClient {
     S: AsRef<str>,
     V: Serialize,
     R: de::DeserializeOwned,
+  {
+    self.write_request_message(method, params)?;
+    self.read_response()
+  }
+
+  /// Writes a request immediately followed by a `$/cancelRequest`
+  /// notification for it. The response is either the result, when the server
+  /// finished before it noticed the cancellation, or a cancellation error.
+  pub fn write_request_and_cancel<S, V, R>(
+    &mut self,
+    method: S,
+    params: V,
+  ) -> Result<(Option<R>, Option<LspResponseError>)>
+  where
+    S: AsRef<str>,
+    V: Serialize,
+    R: de::DeserializeOwned,
+  {
+    self.write_request_message(method, params)?;
+    self.write_notification(
+      "$/cancelRequest",
+      json!({ "id": self.request_id }),
+    )?;
+    self.read_response()
+  }
+
+  fn write_request_message<S, V>(&mut self, method: S, params: V) -> Result<()>
+  where
+    S: AsRef<str>,
+    V: Serialize,
   {
     let value = if to_value(&params).unwrap().is_null() {
       json!({
@@ -348,8 +384,15 @@ impl LspClient {
         "params": params,
       })
     };
-    self.write(value)?;
+    self.write(value)
+  }
 
+  fn read_response<R>(
+    &mut self,
+  ) -> Result<(Option<R>, Option<LspResponseError>)>
+  where
+    R: de::DeserializeOwned,
+  {
     self.reader.read_message(|msg| match msg {
       LspMessage::Response(id, maybe_result, maybe_error) => {
         assert_eq!(*id, self.request_id);